@@ -0,0 +1,55 @@
+//! Trace generation and proving of `FinalExponentiateStark`, on a random element of the cyclotomic subgroup from `test_utils`.
+//!
+//! `RUST_MIN_STACK=16777216 cargo bench --bench final_exp --features test-utils`
+
+use criterion::{black_box, BatchSize, Criterion};
+use plonky2::util::timing::TimingTree;
+use rand::{rngs::StdRng, SeedableRng};
+use starky::{config::StarkConfig, prover::prove};
+use starky_bls12_381::{
+    config::{DefaultConfig, DefaultField, EXTENSION_DEGREE},
+    final_exponentiate::FinalExponentiateStark,
+    test_utils::random_fp12_in_cyclotomic_subgroup,
+    utils::trace_rows_to_poly_values_streaming,
+};
+
+const D: usize = EXTENSION_DEGREE;
+type C = DefaultConfig;
+type F = DefaultField;
+type S = FinalExponentiateStark<F, D>;
+
+fn final_exp(c: &mut Criterion) {
+    let x = random_fp12_in_cyclotomic_subgroup(&mut StdRng::seed_from_u64(0));
+    let mut config = StarkConfig::standard_fast_config();
+    config.fri_config.rate_bits = 2;
+    let stark = S::with_min_rows();
+    let public_inputs = S::public_inputs(&x, &x.final_exponentiate());
+
+    // A trace takes about 5GB and a proof tens of seconds, so each sample is a single run.
+    let mut group = c.benchmark_group("final_exp");
+    group.sample_size(10);
+    group.bench_function("trace generation", |b| b.iter(|| stark.generate_trace(black_box(x)).unwrap()));
+    let template = stark.trace_template();
+    group.bench_function("trace generation from template", |b| b.iter(|| stark.generate_trace_from_template(&template, black_box(x)).unwrap()));
+    group.bench_function("proving", |b| b.iter_batched(
+        || trace_rows_to_poly_values_streaming(stark.generate_trace(x).unwrap()),
+        |trace_poly_values| prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap(),
+        BatchSize::PerIteration,
+    ));
+    group.finish();
+}
+
+fn main() {
+    // The trace rows are too large for the default stack of the main thread, so the benches run in a thread whose stack size is set by RUST_MIN_STACK.
+    std::thread::Builder::new().spawn(|| {
+        let mut c = Criterion::default().configure_from_args();
+        final_exp(&mut c);
+        c.final_summary();
+    }).unwrap().join().unwrap();
+}
@@ -0,0 +1,27 @@
+//! Native fp12 arithmetic, on random inputs from `test_utils`.
+//!
+//! `cargo bench --bench native --features test-utils`
+
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use rand::{rngs::StdRng, SeedableRng};
+use starky_bls12_381::test_utils::{random_fp12, random_fp12_in_cyclotomic_subgroup};
+
+fn fp12(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(0);
+    let (x, y) = (random_fp12(&mut rng), random_fp12(&mut rng));
+    // The cyclotomic square and exponent are only correct in the cyclotomic subgroup, where the final exponentiation uses them.
+    let z = random_fp12_in_cyclotomic_subgroup(&mut rng);
+
+    c.bench_function("fp12 mul", |b| b.iter(|| black_box(x) * black_box(y)));
+    c.bench_function("fp12 cyclotomic_square", |b| b.iter(|| black_box(z).cyclotomic_square()));
+
+    // Each run takes from a hundred milliseconds to a second.
+    let mut group = c.benchmark_group("fp12 exponentiation");
+    group.sample_size(10);
+    group.bench_function("cyclotocmic_exponent", |b| b.iter(|| black_box(z).cyclotocmic_exponent()));
+    group.bench_function("final_exponentiate", |b| b.iter(|| black_box(x).final_exponentiate()));
+    group.finish();
+}
+
+criterion_group!(benches, fp12);
+criterion_main!(benches);
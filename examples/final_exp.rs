@@ -0,0 +1,56 @@
+//! Proves one final exponentiation of a random element of the cyclotomic subgroup, and prints the time taken by each step and the proof size.
+//!
+//! `RUST_MIN_STACK=16777216 cargo run --release --example final_exp --features test-utils`
+//!
+//! With `--features test-utils,quartic-extension` the proof is over the quartic extension of Goldilocks (see `config.rs`).
+
+use std::time::Instant;
+
+use plonky2::util::timing::TimingTree;
+use starky::{config::StarkConfig, prover::prove, verifier::verify_stark_proof};
+use starky_bls12_381::{
+    config::{DefaultConfig, DefaultField, EXTENSION_DEGREE},
+    final_exponentiate::{FinalExponentiateStark, PUBLIC_INPUTS},
+    serialization::serialize_proof,
+    test_utils::random_fp12_in_cyclotomic_subgroup,
+    utils::trace_rows_to_poly_values_streaming,
+};
+
+const D: usize = EXTENSION_DEGREE;
+type C = DefaultConfig;
+type F = DefaultField;
+type S = FinalExponentiateStark<F, D>;
+
+fn final_exp() {
+    let x = random_fp12_in_cyclotomic_subgroup(&mut rand::thread_rng());
+    let mut config = StarkConfig::standard_fast_config();
+    config.fri_config.rate_bits = 2;
+    let stark = S::with_min_rows();
+    let public_inputs = S::public_inputs(&x, &x.final_exponentiate());
+    assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+
+    let s = Instant::now();
+    let trace = stark.generate_trace(x).unwrap();
+    let trace_poly_values = trace_rows_to_poly_values_streaming(trace);
+    println!("Trace generation: {:?}", s.elapsed());
+
+    let s = Instant::now();
+    let proof = prove::<F, C, S, D>(
+        stark,
+        &config,
+        trace_poly_values,
+        &public_inputs,
+        &mut TimingTree::default(),
+    ).unwrap();
+    println!("Proving: {:?}", s.elapsed());
+    println!("Serialized proof size: {} bytes", serialize_proof(&proof).unwrap().len());
+
+    let s = Instant::now();
+    verify_stark_proof(stark, proof, &config).unwrap();
+    println!("Verification: {:?}", s.elapsed());
+}
+
+fn main() {
+    env_logger::init();
+    std::thread::Builder::new().spawn(final_exp).unwrap().join().unwrap();
+}
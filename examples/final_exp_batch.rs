@@ -0,0 +1,58 @@
+//! Compares the proving time per instance of `FinalExponentiateStark::prove_batch` with proving a single final exponentiation.
+//!
+//! `RUST_MIN_STACK=16777216 cargo run --release --example final_exp_batch --features test-utils`
+
+use std::time::Instant;
+
+use plonky2::util::timing::TimingTree;
+use starky::{config::StarkConfig, prover::prove, verifier::verify_stark_proof};
+use starky_bls12_381::{
+    config::{DefaultConfig, DefaultField, EXTENSION_DEGREE},
+    final_exponentiate::FinalExponentiateStark,
+    native::Fp12,
+    test_utils::random_fp12_in_cyclotomic_subgroup,
+    utils::trace_rows_to_poly_values_streaming,
+};
+
+const D: usize = EXTENSION_DEGREE;
+type C = DefaultConfig;
+type F = DefaultField;
+type S = FinalExponentiateStark<F, D>;
+
+const BATCH_SIZE: usize = 4;
+
+fn final_exp_batch() {
+    let mut rng = rand::thread_rng();
+    let inputs = (0..BATCH_SIZE).map(|_| random_fp12_in_cyclotomic_subgroup(&mut rng)).collect::<Vec<Fp12>>();
+    let mut config = StarkConfig::standard_fast_config();
+    config.fri_config.rate_bits = 2;
+    let stark = S::with_min_rows();
+
+    let s = Instant::now();
+    let public_inputs = S::public_inputs(&inputs[0], &inputs[0].final_exponentiate());
+    let trace_poly_values = trace_rows_to_poly_values_streaming(stark.generate_trace(inputs[0]).unwrap());
+    let proof = prove::<F, C, S, D>(
+        stark,
+        &config,
+        trace_poly_values,
+        &public_inputs,
+        &mut TimingTree::default(),
+    ).unwrap();
+    let single = s.elapsed();
+    verify_stark_proof(stark, proof, &config).unwrap();
+
+    let s = Instant::now();
+    let proofs = stark.prove_batch::<C>(&config, &inputs).unwrap();
+    let batch = s.elapsed();
+    for proof in proofs {
+        verify_stark_proof(stark, proof, &config).unwrap();
+    }
+
+    println!("Single instance: {:?}", single);
+    println!("Batch of {}: {:?}, {:?} per instance", BATCH_SIZE, batch, batch / BATCH_SIZE as u32);
+}
+
+fn main() {
+    env_logger::init();
+    std::thread::Builder::new().spawn(final_exp_batch).unwrap().join().unwrap();
+}
@@ -0,0 +1,62 @@
+//! Prints the columns of the final exponentiation stark with the 13 bit row counter, against the one-hot row selectors it replaces (a column per row), then proves one final exponentiation and prints the field elements the trace commitment takes in the proof for both layouts. The one-hot layout is no longer in the crate, so its figures are estimates computed from the column counts, not measurements.
+//!
+//! `RUST_MIN_STACK=16777216 cargo run --release --example final_exp_row_counter --features test-utils`
+
+use std::time::Instant;
+
+use plonky2::{hash::hash_types::NUM_HASH_OUT_ELTS, util::{log2_strict, timing::TimingTree}};
+use starky::{config::StarkConfig, prover::prove, verifier::verify_stark_proof};
+use starky_bls12_381::{
+    config::{DefaultConfig, DefaultField, EXTENSION_DEGREE},
+    final_exponentiate::{FinalExponentiateStark, FINAL_EXP_FORBENIUS_MAP_SELECTOR, FINAL_EXP_MAX_ROWS, TOTAL_COLUMNS},
+    serialization::serialize_proof,
+    test_utils::random_fp12_in_cyclotomic_subgroup,
+    utils::trace_rows_to_poly_values_streaming,
+};
+
+const D: usize = EXTENSION_DEGREE;
+type C = DefaultConfig;
+type F = DefaultField;
+type S = FinalExponentiateStark<F, D>;
+
+fn final_exp_row_counter() {
+    // The row counter bits and the low and high row selectors are the columns before the op selectors.
+    let row_counter_columns = FINAL_EXP_FORBENIUS_MAP_SELECTOR;
+    let saved_columns = FINAL_EXP_MAX_ROWS - row_counter_columns;
+    println!("Row counter columns: {}, one-hot row selector columns: {}", row_counter_columns, FINAL_EXP_MAX_ROWS);
+    println!("Total columns: {}, with one-hot row selectors (estimate): {}", TOTAL_COLUMNS, TOTAL_COLUMNS + saved_columns);
+
+    let x = random_fp12_in_cyclotomic_subgroup(&mut rand::thread_rng());
+    let mut config = StarkConfig::standard_fast_config();
+    config.fri_config.rate_bits = 2;
+    let stark = S::with_min_rows();
+    let public_inputs = S::public_inputs(&x, &x.final_exponentiate());
+
+    let s = Instant::now();
+    let trace = stark.generate_trace(x).unwrap();
+    let rows = trace.len();
+    println!("Trace: {} bytes, with one-hot row selectors (estimate): {} bytes", rows * TOTAL_COLUMNS * 8, rows * (TOTAL_COLUMNS + saved_columns) * 8);
+    let trace_poly_values = trace_rows_to_poly_values_streaming(trace);
+    let proof = prove::<F, C, S, D>(
+        stark,
+        &config,
+        trace_poly_values,
+        &public_inputs,
+        &mut TimingTree::default(),
+    ).unwrap();
+    println!("Trace generation and proving: {:?}", s.elapsed());
+
+    // Estimate of the field elements of the trace commitment in the proof: every trace column is opened at two points in the extension field and once in the Merkle leaves of each FRI query, and each query carries the Merkle path of its leaf up to the cap, which is the same for both layouts since they have the same rows. The other FRI data does not depend on the trace columns.
+    let merkle_path_elements = (log2_strict(rows) + config.fri_config.rate_bits - config.fri_config.cap_height) * NUM_HASH_OUT_ELTS;
+    let cap_elements = (1 << config.fri_config.cap_height) * NUM_HASH_OUT_ELTS;
+    let trace_elements = |columns: usize| cap_elements + columns * 2 * D + config.fri_config.num_query_rounds * (columns + merkle_path_elements);
+    println!("Proof field elements for the trace commitment: {}, with one-hot row selectors (estimate): {}", trace_elements(TOTAL_COLUMNS), trace_elements(TOTAL_COLUMNS + saved_columns));
+    println!("Serialized proof size: {} bytes", serialize_proof(&proof).unwrap().len());
+
+    verify_stark_proof(stark, proof, &config).unwrap();
+}
+
+fn main() {
+    env_logger::init();
+    std::thread::Builder::new().spawn(final_exp_row_counter).unwrap().join().unwrap();
+}
@@ -0,0 +1,50 @@
+//! Compares generating the traces of a batch of final exponentiations from scratch with generating them from one `TraceTemplate`, which has the columns that do not depend on the input precomputed.
+//!
+//! `RUST_MIN_STACK=16777216 cargo run --release --example final_exp_trace_template --features test-utils`
+
+use std::time::Instant;
+
+use starky_bls12_381::{
+    config::{DefaultField, EXTENSION_DEGREE},
+    final_exponentiate::FinalExponentiateStark,
+    native::Fp12,
+    test_utils::random_fp12_in_cyclotomic_subgroup,
+};
+
+const D: usize = EXTENSION_DEGREE;
+type F = DefaultField;
+type S = FinalExponentiateStark<F, D>;
+
+const BATCH_SIZE: usize = 16;
+
+fn final_exp_trace_template() {
+    let mut rng = rand::thread_rng();
+    let inputs = (0..BATCH_SIZE).map(|_| random_fp12_in_cyclotomic_subgroup(&mut rng)).collect::<Vec<Fp12>>();
+    let stark = S::with_min_rows();
+
+    // A trace takes about 5GB, so each one is dropped before the next one is generated.
+    let s = Instant::now();
+    for x in inputs.iter() {
+        stark.generate_trace(*x).unwrap();
+    }
+    let from_scratch = s.elapsed();
+
+    let s = Instant::now();
+    let template = stark.trace_template();
+    let template_time = s.elapsed();
+    assert!(stark.generate_trace_from_template(&template, inputs[0]).unwrap() == stark.generate_trace(inputs[0]).unwrap());
+    let s = Instant::now();
+    for x in inputs.iter() {
+        stark.generate_trace_from_template(&template, *x).unwrap();
+    }
+    let from_template = s.elapsed();
+
+    println!("From scratch, {} traces: {:?}, {:?} per trace", BATCH_SIZE, from_scratch, from_scratch / BATCH_SIZE as u32);
+    println!("Template: {:?}", template_time);
+    println!("From the template, {} traces: {:?}, {:?} per trace", BATCH_SIZE, from_template, from_template / BATCH_SIZE as u32);
+}
+
+fn main() {
+    env_logger::init();
+    std::thread::Builder::new().spawn(final_exp_trace_template).unwrap().join().unwrap();
+}
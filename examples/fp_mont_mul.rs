@@ -0,0 +1,36 @@
+//! Compares the native fp multiplication with schoolbook reduction (`mul_fp`) against the Montgomery form multiplication (`FpMont`).
+//!
+//! `cargo run --release --example fp_mont_mul`
+
+use std::{str::FromStr, time::Instant};
+
+use num_bigint::BigUint;
+use starky_bls12_381::native::{mul_fp, Fp};
+
+const ITERATIONS: usize = 100_000;
+
+fn main() {
+    let x = Fp::from(BigUint::from_str("1216495682195235861952885506871698490232894470117269383940381148575524314493849307811227440691167647909822763414941").unwrap());
+    let y = Fp::from(BigUint::from_str("2153848155426317245700560287567131132765685008362732985860101000686875894603366983854567186180519945327668975076337").unwrap());
+
+    let s = Instant::now();
+    let mut acc = x;
+    for _ in 0..ITERATIONS {
+        acc = mul_fp(acc, y);
+    }
+    let schoolbook = s.elapsed();
+
+    // The conversions are done once, outside of the loop, as when a whole computation is done in Montgomery form.
+    let s = Instant::now();
+    let (mut acc_mont, y_mont) = (x.to_mont(), y.to_mont());
+    for _ in 0..ITERATIONS {
+        acc_mont = acc_mont * y_mont;
+    }
+    let acc_mont = Fp::from_mont(acc_mont);
+    let montgomery = s.elapsed();
+
+    assert_eq!(acc, acc_mont);
+    println!("{} fp multiplications", ITERATIONS);
+    println!("Schoolbook reduction: {:?}", schoolbook);
+    println!("Montgomery reduction: {:?}", montgomery);
+}
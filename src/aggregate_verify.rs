@@ -0,0 +1,686 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::{circuit_builder::CircuitBuilder, config::GenericConfig},
+};
+use starky::{
+    config::StarkConfig,
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    proof::StarkProofWithPublicInputs,
+    stark::Stark,
+    verifier::verify_stark_proof,
+};
+
+use anyhow::{bail, ensure, Result};
+
+use crate::config::check_field;
+use crate::native::{Fp2, Fp12, G1Affine, G2Affine};
+
+use crate::fp::*;
+use crate::fp6::*;
+use crate::fp12::*;
+use crate::g1::*;
+use crate::final_exponentiate::*;
+use crate::miller_loop::*;
+use crate::pairing::pairing_precomp;
+
+/// Maximum number of public keys which can be aggregated by the stark. Unused key slots are set to the point at infinity.
+pub const AGGREGATE_VERIFY_MAX_KEYS: usize = 16;
+
+/*
+    These offsets are for verifying an aggregate BLS signature over a single message, i.e. e(aggPk, H(m)) == e(G1, sig), where aggPk is the sum of the public keys. This stark needs 8192 rows.
+    The check is done as e(aggPk, H(m)) * e(-G1, sig) == 1, hence a single final exponentiate is needed for the product of the two miller loops.
+    AGG_FINAL_EXP_OFFSET -> offset where the final exponentiate trace is filled.
+    AGG_MILLER_LOOP_PK_OFFSET -> offset where the miller loop trace of (aggPk, H(m)) is filled.
+    AGG_MILLER_LOOP_SIG_OFFSET -> offset where the miller loop trace of (-G1, sig) is filled.
+    AGG_FP12_MUL_OFFSET -> offset where the product of the two miller loop results is calculated, in the first 12 rows. The product is the input of final exponentiate.
+    AGG_G1_ADD_OFFSET -> offset where the public keys are summed. Each addition takes 12 rows, the k-th addition is filled in rows 12*k..12*k+11 and adds the k+1-th key to the sum of the previous keys.
+    AGG_G1_DOUBLE_OFFSET -> offset where the first input of each addition is doubled, in the same rows. The addition does not support adding a point to itself, so the sum is taken from the doubling when the next key is equal to the sum of the previous keys, e.g. for a key signing twice.
+    AGG_KEY_EQUAL_OFFSET -> Flag set 1 in the rows of an addition whose inputs are the same finite point. The addition is then turned off and only its inputs are filled.
+    AGG_KEY_INDEX_OFFSET -> offset which stores which addition the trace is currently on. Total AGGREGATE_VERIFY_MAX_KEYS-1 selectors, one for each addition.
+*/
+pub const AGG_FINAL_EXP_OFFSET: usize = 0;
+pub const AGG_MILLER_LOOP_PK_OFFSET: usize = AGG_FINAL_EXP_OFFSET + FINAL_EXP_TOTAL_COLUMNS;
+pub const AGG_MILLER_LOOP_SIG_OFFSET: usize = AGG_MILLER_LOOP_PK_OFFSET + MILLER_LOOP_TOTAL;
+pub const AGG_FP12_MUL_OFFSET: usize = AGG_MILLER_LOOP_SIG_OFFSET + MILLER_LOOP_TOTAL;
+pub const AGG_G1_ADD_OFFSET: usize = AGG_FP12_MUL_OFFSET + FP12_MUL_TOTAL_COLUMNS;
+pub const AGG_G1_DOUBLE_OFFSET: usize = AGG_G1_ADD_OFFSET + G1_ADD_TOTAL;
+pub const AGG_KEY_EQUAL_OFFSET: usize = AGG_G1_DOUBLE_OFFSET + G1_DOUBLE_TOTAL;
+pub const AGG_KEY_INDEX_OFFSET: usize = AGG_KEY_EQUAL_OFFSET + 1;
+pub const AGG_TOTAL_COLUMNS: usize = AGG_KEY_INDEX_OFFSET + AGGREGATE_VERIFY_MAX_KEYS - 1;
+
+pub const TOTAL_COLUMNS: usize = AGG_TOTAL_COLUMNS;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the public keys, each as (x, y) and its infinity flag, then the `ell_coeffs` of the message hash point H(m) and the `ell_coeffs` of the aggregated signature, both resulted from calc_pairing_precomp.
+    As in PairingStark, H(m) and the signature are not in the trace and the constraints only see their `ell_coeffs`. These are computed from the two G2 points by `AggregateVerifyStark::public_inputs`, so the proof verifies (pks, H(m), sig) for a verifier who lays out the public inputs from the points itself. Proofs must be checked with `AggregateVerifyStark::verify`, which rebuilds the public inputs from the points and rejects a proof carrying any other public inputs.
+    The public keys are only summed by the constraints, which do not check them to be on the curve nor in the G1 subgroup. `AggregateVerifyStark::verify` checks them natively, as the verifier has the keys, and callers laying out the public inputs themselves must do the same. Aggregating keys over a single message also assumes each key comes with a proof of possession of its secret key, against rogue key attacks, which is left to the caller.
+*/
+pub const PIS_KEYS_OFFSET: usize = 0;
+pub const PIS_KEY_SIZE: usize = 12*2 + 1;
+pub const PIS_HM_ELL_COEFFS_OFFSET: usize = PIS_KEYS_OFFSET + PIS_KEY_SIZE*AGGREGATE_VERIFY_MAX_KEYS;
+pub const PIS_SIG_ELL_COEFFS_OFFSET: usize = PIS_HM_ELL_COEFFS_OFFSET + 68*24*3;
+pub const PUBLIC_INPUTS: usize = PIS_SIG_ELL_COEFFS_OFFSET + 68*24*3;
+
+/// Returns the column of the i-th limb of the fp12 multiplication result.
+fn fp12_mul_res(start_col: usize, i: usize) -> usize {
+    let (k, j, l) = (i / (24*3), (i % (24*3)) / 12, i % 12);
+    let x_y = if k == 0 {
+        FP12_MUL_X_CALC_OFFSET + FP6_ADDITION_TOTAL
+    } else {
+        FP12_MUL_Y_CALC_OFFSET + FP6_ADDITION_TOTAL + FP6_SUBTRACTION_TOTAL
+    };
+    start_col + x_y + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*j + FP_SINGLE_REDUCED_OFFSET + l
+}
+
+/// Pairs of (trace column, public input) which are constrained to be equal wherever the selector of the k-th addition is set, along with the number of limbs. The first addition also takes the first key as its first input.
+fn key_links(k: usize) -> Vec<(usize, usize, usize)> {
+    let key = |n: usize| PIS_KEYS_OFFSET + n*PIS_KEY_SIZE;
+    let mut links = vec![
+        (AGG_G1_ADD_OFFSET + G1_ADD_X2_OFFSET, key(k+1), 12),
+        (AGG_G1_ADD_OFFSET + G1_ADD_Y2_OFFSET, key(k+1) + 12, 12),
+        (AGG_G1_ADD_OFFSET + G1_ADD_INFINITY2_OFFSET, key(k+1) + 24, 1),
+    ];
+    if k == 0 {
+        links.extend([
+            (AGG_G1_ADD_OFFSET + G1_ADD_X1_OFFSET, key(0), 12),
+            (AGG_G1_ADD_OFFSET + G1_ADD_Y1_OFFSET, key(0) + 12, 12),
+            (AGG_G1_ADD_OFFSET + G1_ADD_INFINITY1_OFFSET, key(0) + 24, 1),
+        ]);
+    }
+    links
+}
+
+/// Triples of (addition result column, doubling result column, input column) which carry the sum from one addition to the first input of the next one, along with the number of limbs.
+const CHAIN_LINKS: [(usize, usize, usize, usize); 3] = [
+    (G1_ADD_RES_X_OFFSET, G1_DOUBLE_RES_X_OFFSET, G1_ADD_X1_OFFSET, 12),
+    (G1_ADD_RES_Y_OFFSET, G1_DOUBLE_RES_Y_OFFSET, G1_ADD_Y1_OFFSET, 12),
+    (G1_ADD_RES_INFINITY_OFFSET, G1_DOUBLE_RES_INFINITY_OFFSET, G1_ADD_INFINITY1_OFFSET, 1),
+];
+
+/// Pairs of (doubling input column, addition input column), along with the number of limbs. The doubling input is the first input of the addition.
+const DOUBLE_INPUT_LINKS: [(usize, usize, usize); 3] = [
+    (G1_DOUBLE_X_OFFSET, G1_ADD_X1_OFFSET, 12),
+    (G1_DOUBLE_Y_OFFSET, G1_ADD_Y1_OFFSET, 12),
+    (G1_DOUBLE_INFINITY_OFFSET, G1_ADD_INFINITY1_OFFSET, 1),
+];
+
+/// The sum of the keys in a row of the key aggregation, from the doubling if the key equal flag is set and from the addition otherwise.
+fn key_sum<P: PackedField>(values: &[P], add_res_col: usize, double_res_col: usize) -> P {
+    let equal = values[AGG_KEY_EQUAL_OFFSET];
+    equal * values[AGG_G1_DOUBLE_OFFSET + double_res_col] + (P::ONES - equal) * values[AGG_G1_ADD_OFFSET + add_res_col]
+}
+
+fn key_sum_ext_circuit<F: RichField + Extendable<D>,
+    const D: usize,
+>(builder: &mut CircuitBuilder<F, D>, values: &[ExtensionTarget<D>], add_res_col: usize, double_res_col: usize) -> ExtensionTarget<D> {
+    let diff = builder.sub_extension(values[AGG_G1_DOUBLE_OFFSET + double_res_col], values[AGG_G1_ADD_OFFSET + add_res_col]);
+    builder.mul_add_extension(values[AGG_KEY_EQUAL_OFFSET], diff, values[AGG_G1_ADD_OFFSET + add_res_col])
+}
+
+#[derive(Clone, Copy)]
+pub struct AggregateVerifyStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> AggregateVerifyStark<F, D> {
+    /// Rows used by the verification: the public key sum, the miller loops and final exponentiate, side by side from the first row.
+    pub fn required_rows() -> usize {
+        MILLER_LOOP_ROWS.max(12 * (AGGREGATE_VERIFY_MAX_KEYS - 1)).max(crate::final_exponentiate::TOTAL_ROW)
+    }
+
+    /// Returns an error if `num_rows` is less than [AggregateVerifyStark::required_rows], or more than `FINAL_EXP_MAX_ROWS`, the number of rows the row counter of final exponentiate can number, or if the field does not fit the trace (see [check_field](crate::config::check_field)).
+    pub fn new(num_rows: usize) -> Result<Self> {
+        if num_rows < Self::required_rows() {
+            bail!("AggregateVerifyStark::new: num_rows {} is less than the {} rows required by the trace", num_rows, Self::required_rows());
+        }
+        if num_rows > FINAL_EXP_MAX_ROWS {
+            bail!("AggregateVerifyStark::new: num_rows {} is more than the {} rows of the row counter", num_rows, FINAL_EXP_MAX_ROWS);
+        }
+        check_field::<F>(num_rows)?;
+        Ok(Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        })
+    }
+
+    /// Lays out the public inputs for this stark. The public keys are padded with the point at infinity up to `AGGREGATE_VERIFY_MAX_KEYS`, followed by the `ell_coeffs` of `hm` and of `sig` computed by `calc_pairing_precomp`. Returns an error if there are no keys or more than `AGGREGATE_VERIFY_MAX_KEYS`, or if `hm` or `sig` is the point at infinity or is not on the G2 curve.
+    pub fn public_inputs(pks: &[G1Affine], hm: &G2Affine, sig: &G2Affine) -> Result<Vec<F>> {
+        ensure!(!pks.is_empty() && pks.len() <= AGGREGATE_VERIFY_MAX_KEYS, "expected 1 to {} public keys, got {}", AGGREGATE_VERIFY_MAX_KEYS, pks.len());
+        let hm_ell_coeffs = pairing_precomp(hm)?;
+        let sig_ell_coeffs = pairing_precomp(sig)?;
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for n in 0..AGGREGATE_VERIFY_MAX_KEYS {
+            let pk = pks.get(n).copied().unwrap_or(G1Affine::identity());
+            for i in 0..12 {
+                public_inputs[PIS_KEYS_OFFSET + n*PIS_KEY_SIZE + i] = F::from_canonical_u32(pk.x.0[i]);
+                public_inputs[PIS_KEYS_OFFSET + n*PIS_KEY_SIZE + 12 + i] = F::from_canonical_u32(pk.y.0[i]);
+            }
+            public_inputs[PIS_KEYS_OFFSET + n*PIS_KEY_SIZE + 24] = F::from_bool(pk.infinity);
+        }
+        for (offset, ell_coeffs) in [(PIS_HM_ELL_COEFFS_OFFSET, hm_ell_coeffs), (PIS_SIG_ELL_COEFFS_OFFSET, sig_ell_coeffs)] {
+            for (j, coeff) in ell_coeffs.iter().enumerate() {
+                for (k, e) in coeff.iter().flat_map(|f2| f2.get_u32_slice().concat()).enumerate() {
+                    public_inputs[offset + j*24*3 + k] = F::from_canonical_u32(e);
+                }
+            }
+        }
+        Ok(public_inputs)
+    }
+
+    /// Fills the trace for the aggregate verification. First sums the public keys, padded with the point at infinity, with one G1 addition per 12 rows, alongside the doubling of the sum of the previous keys which is used instead when the next key is equal to it. Then fills the miller loop traces of (aggPk, H(m)) and (-G1, sig), their product, and the final exponentiate trace with the product as input. Returns an error for the same inputs as [AggregateVerifyStark::public_inputs].
+    pub fn generate_trace(&self, pks: &[G1Affine], hm: &G2Affine, sig: &G2Affine) -> Result<Vec<[F; TOTAL_COLUMNS]>> {
+        ensure!(!pks.is_empty() && pks.len() <= AGGREGATE_VERIFY_MAX_KEYS, "expected 1 to {} public keys, got {}", AGGREGATE_VERIFY_MAX_KEYS, pks.len());
+        let hm_ell_coeffs = pairing_precomp(hm)?;
+        let sig_ell_coeffs = pairing_precomp(sig)?;
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        let mut agg_pk = pks[0];
+        for k in 0..AGGREGATE_VERIFY_MAX_KEYS-1 {
+            let pk = pks.get(k+1).copied().unwrap_or(G1Affine::identity());
+            let doubled = fill_trace_g1_double(&mut trace, &agg_pk, k*12, k*12 + 11, AGG_G1_DOUBLE_OFFSET);
+            let equal = !pk.infinity && pk == agg_pk;
+            agg_pk = if equal {
+                fill_trace_g1_add_inputs(&mut trace, &pk, k*12, k*12 + 11, AGG_G1_ADD_OFFSET);
+                doubled
+            } else {
+                fill_trace_g1_add(&mut trace, &agg_pk, &pk, k*12, k*12 + 11, AGG_G1_ADD_OFFSET)?
+            };
+            for row in k*12..k*12 + 12 {
+                trace[row][AGG_KEY_INDEX_OFFSET + k] = F::ONE;
+                trace[row][AGG_KEY_EQUAL_OFFSET] = F::from_bool(equal);
+            }
+        }
+        fill_trace_pairing_product(&mut trace, &agg_pk, &hm_ell_coeffs, &-G1Affine::generator(), &sig_ell_coeffs);
+        Ok(trace)
+    }
+
+    /// Verifies a proof of the aggregate signature `sig` of the message hash `hm` under the keys `pks`. Each key is checked natively to be a finite point of the G1 subgroup, since the constraints do not check them, then the public inputs are rebuilt with [AggregateVerifyStark::public_inputs] and the proof is rejected if its public inputs differ from them before the stark proof is checked.
+    /// Returns an error if a key is invalid, for the same inputs as [AggregateVerifyStark::public_inputs], if the public inputs of the proof do not match, or if the proof does not verify.
+    pub fn verify<C: GenericConfig<D, F = F>>(&self, config: &StarkConfig, proof: StarkProofWithPublicInputs<F, C, D>, pks: &[G1Affine], hm: &G2Affine, sig: &G2Affine) -> Result<()> {
+        for (n, pk) in pks.iter().enumerate() {
+            ensure!(!pk.infinity && pk.is_on_curve() && pk.is_in_subgroup(), "AggregateVerifyStark::verify: public key {} is not a finite point of the G1 subgroup", n);
+        }
+        let public_inputs = Self::public_inputs(pks, hm, sig)?;
+        ensure!(proof.public_inputs == public_inputs, "AggregateVerifyStark::verify: the public inputs of the proof are not the ones of the given keys, H(m) and signature");
+        verify_stark_proof(*self, proof, config)
+    }
+}
+
+/// Fills the miller loop traces of (p1, Q1) at `AGG_MILLER_LOOP_PK_OFFSET` and (p2, Q2) at `AGG_MILLER_LOOP_SIG_OFFSET` over all the rows, where the G2 points are given by their `ell_coeffs`. Then fills their product in the first 12 rows and the final exponentiate trace with the product as input.
+pub(crate) fn fill_trace_pairing_product<F: RichField + Extendable<D>,
+    const D: usize,
+>(trace: &mut Vec<[F; TOTAL_COLUMNS]>, p1: &G1Affine, ell_coeffs1: &[[Fp2; 3]], p2: &G1Affine, ell_coeffs2: &[[Fp2; 3]]) {
+    let last_row = trace.len() - 1;
+    let f12_pk = fill_trace_miller_loop(trace, &p1.x, &p1.y, ell_coeffs1, 0, last_row, AGG_MILLER_LOOP_PK_OFFSET);
+    let f12_sig = fill_trace_miller_loop(trace, &p2.x, &p2.y, ell_coeffs2, 0, last_row, AGG_MILLER_LOOP_SIG_OFFSET);
+    fill_trace_fp12_multiplication(trace, &f12_pk, &f12_sig, 0, 11, AGG_FP12_MUL_OFFSET);
+    fill_trace_final_exponentiate(trace, &(f12_pk * f12_sig), AGG_FINAL_EXP_OFFSET).unwrap();
+}
+
+/// Constraints the product of the two miller loops filled by [fill_trace_pairing_product] to be one after the final exponentiation. The `ell_coeffs` of both miller loops are constrained with the public inputs at `PIS_HM_ELL_COEFFS_OFFSET` and `PIS_SIG_ELL_COEFFS_OFFSET`, while their G1 points are left for the caller to constrain.
+/// Constraints the miller loop results to be same across all rows, the inputs of the fp12 multiplication with them and its result with the final exponentiate input in the first row, and the final exponentiate output to be one in the last row. Then constraints the miller loop, fp12 multiplication and final exponentiate computations.
+pub(crate) fn add_pairing_product_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    public_inputs: &[FE],
+    yield_constr: &mut ConstraintConsumer<P>,
+    num_rows: usize,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    for (offset, pis_offset) in [(AGG_MILLER_LOOP_PK_OFFSET, PIS_HM_ELL_COEFFS_OFFSET), (AGG_MILLER_LOOP_SIG_OFFSET, PIS_SIG_ELL_COEFFS_OFFSET)] {
+        for i in 0..68 {
+            for j in 0..24*3 {
+                yield_constr.constraint(
+                    local_values[offset + ELL_COEFFS_INDEX_OFFEST + i] *
+                    (local_values[offset + ELL_COEFFS_OFFSET + j] - public_inputs[pis_offset + i*24*3 + j])
+                );
+            }
+        }
+        for i in 0..24*3*2 {
+            yield_constr.constraint_transition(
+                local_values[offset + MILLER_LOOP_RES_OFFSET + i] - next_values[offset + MILLER_LOOP_RES_OFFSET + i]
+            );
+        }
+    }
+
+    yield_constr.constraint_first_row(local_values[AGG_FP12_MUL_OFFSET + FP12_MUL_SELECTOR_OFFSET] - P::ONES);
+    for i in 0..24*3*2 {
+        yield_constr.constraint_first_row(
+            local_values[AGG_FP12_MUL_OFFSET + FP12_MUL_X_INPUT_OFFSET + i] - local_values[AGG_MILLER_LOOP_PK_OFFSET + MILLER_LOOP_RES_OFFSET + i]
+        );
+        yield_constr.constraint_first_row(
+            local_values[AGG_FP12_MUL_OFFSET + FP12_MUL_Y_INPUT_OFFSET + i] - local_values[AGG_MILLER_LOOP_SIG_OFFSET + MILLER_LOOP_RES_OFFSET + i]
+        );
+        yield_constr.constraint_first_row(
+            local_values[fp12_mul_res(AGG_FP12_MUL_OFFSET, i)] - local_values[AGG_FINAL_EXP_OFFSET + FINAL_EXP_INPUT_OFFSET + i]
+        );
+        let one = if i == 0 { P::ONES } else { P::ZEROS };
+        yield_constr.constraint_last_row(local_values[AGG_FINAL_EXP_OFFSET + FINAL_EXP_T31_OFFSET + i] - one);
+    }
+
+    add_miller_loop_constraints(local_values, next_values, yield_constr, AGG_MILLER_LOOP_PK_OFFSET, None);
+    add_miller_loop_constraints(local_values, next_values, yield_constr, AGG_MILLER_LOOP_SIG_OFFSET, None);
+    add_fp12_multiplication_constraints(local_values, next_values, yield_constr, AGG_FP12_MUL_OFFSET, None);
+    add_final_exponentiate_constraints(local_values, next_values, yield_constr, num_rows, AGG_FINAL_EXP_OFFSET);
+}
+
+pub(crate) fn add_pairing_product_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    public_inputs: &[ExtensionTarget<D>],
+    num_rows: usize,
+) {
+    let one = builder.constant_extension(F::Extension::ONE);
+    let zero = builder.constant_extension(F::Extension::ZERO);
+
+    for (offset, pis_offset) in [(AGG_MILLER_LOOP_PK_OFFSET, PIS_HM_ELL_COEFFS_OFFSET), (AGG_MILLER_LOOP_SIG_OFFSET, PIS_SIG_ELL_COEFFS_OFFSET)] {
+        for i in 0..68 {
+            for j in 0..24*3 {
+                let c = builder.sub_extension(local_values[offset + ELL_COEFFS_OFFSET + j], public_inputs[pis_offset + i*24*3 + j]);
+                let c = builder.mul_extension(local_values[offset + ELL_COEFFS_INDEX_OFFEST + i], c);
+                yield_constr.constraint(builder, c);
+            }
+        }
+        for i in 0..24*3*2 {
+            let c = builder.sub_extension(local_values[offset + MILLER_LOOP_RES_OFFSET + i], next_values[offset + MILLER_LOOP_RES_OFFSET + i]);
+            yield_constr.constraint_transition(builder, c);
+        }
+    }
+
+    let c = builder.sub_extension(local_values[AGG_FP12_MUL_OFFSET + FP12_MUL_SELECTOR_OFFSET], one);
+    yield_constr.constraint_first_row(builder, c);
+    for i in 0..24*3*2 {
+        let c = builder.sub_extension(local_values[AGG_FP12_MUL_OFFSET + FP12_MUL_X_INPUT_OFFSET + i], local_values[AGG_MILLER_LOOP_PK_OFFSET + MILLER_LOOP_RES_OFFSET + i]);
+        yield_constr.constraint_first_row(builder, c);
+        let c = builder.sub_extension(local_values[AGG_FP12_MUL_OFFSET + FP12_MUL_Y_INPUT_OFFSET + i], local_values[AGG_MILLER_LOOP_SIG_OFFSET + MILLER_LOOP_RES_OFFSET + i]);
+        yield_constr.constraint_first_row(builder, c);
+        let c = builder.sub_extension(local_values[fp12_mul_res(AGG_FP12_MUL_OFFSET, i)], local_values[AGG_FINAL_EXP_OFFSET + FINAL_EXP_INPUT_OFFSET + i]);
+        yield_constr.constraint_first_row(builder, c);
+        let expected = if i == 0 { one } else { zero };
+        let c = builder.sub_extension(local_values[AGG_FINAL_EXP_OFFSET + FINAL_EXP_T31_OFFSET + i], expected);
+        yield_constr.constraint_last_row(builder, c);
+    }
+
+    add_miller_loop_constraints_ext_circuit(builder, yield_constr, local_values, next_values, AGG_MILLER_LOOP_PK_OFFSET, None);
+    add_miller_loop_constraints_ext_circuit(builder, yield_constr, local_values, next_values, AGG_MILLER_LOOP_SIG_OFFSET, None);
+    add_fp12_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, next_values, AGG_FP12_MUL_OFFSET, None);
+    add_final_exponentiate_constraints_ext_circuit(builder, yield_constr, local_values, next_values, num_rows, AGG_FINAL_EXP_OFFSET);
+}
+
+/*
+    The constraints of this stark are as follows:
+    * Constraints the key index selectors to be boolean, the first one to be set in the first row, each one to stay the same within its addition and to be followed by the next one. The G1 addition check is constrained to be the sum of the key index selectors.
+    * Constraints the inputs of each G1 addition with the public keys, and the first input of each addition with the sum of the previous one.
+    * Constraints the doubling to follow the selectors of the addition and to double its first input. Constraints the key equal flag to be boolean, same across the rows of an addition, and set only if both inputs are the same finite point. The addition is gated by the negated flag, and the sum of each addition is the result of the doubling if the flag is set, of the addition otherwise.
+    * Constraints the sum of the last addition to be finite and equal to Px, Py of the (aggPk, H(m)) miller loop. Px, Py of the (-G1, sig) miller loop are constrained to the constant -G1.
+    * Constraints Px, Py and the miller loop results to be same across all rows, and the `ell_coeffs` of both miller loops with the public inputs.
+    * Constraints the miller loop results to be the inputs of the fp12 multiplication, and its result to be the final exponentiate input, in the first row.
+    * Constraints the final exponentiate output (T31) to be one in the last row.
+    * Constraints the G1 addition, miller loop, fp12 multiplication and final exponentiate computations.
+*/
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for AggregateVerifyStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        // Key aggregation
+        let last = AGGREGATE_VERIFY_MAX_KEYS - 2;
+        let mut index_sum = P::ZEROS;
+        for k in 0..AGGREGATE_VERIFY_MAX_KEYS-1 {
+            let index = local_values[AGG_KEY_INDEX_OFFSET + k];
+            index_sum = index_sum + index;
+            yield_constr.constraint(index * (P::ONES - index));
+            yield_constr.constraint_transition(
+                local_values[AGG_G1_ADD_OFFSET + G1_ADD_SELECTOR_OFFSET] *
+                (next_values[AGG_KEY_INDEX_OFFSET + k] - index)
+            );
+            for (col, pis, limbs) in key_links(k) {
+                for i in 0..limbs {
+                    yield_constr.constraint(index * (local_values[col + i] - public_inputs[pis + i]));
+                }
+            }
+            if k < last {
+                yield_constr.constraint_transition(
+                    index * (P::ONES - local_values[AGG_G1_ADD_OFFSET + G1_ADD_SELECTOR_OFFSET]) *
+                    (next_values[AGG_KEY_INDEX_OFFSET + k + 1] - P::ONES)
+                );
+                for (res_col, double_res_col, input_col, limbs) in CHAIN_LINKS {
+                    for i in 0..limbs {
+                        yield_constr.constraint_transition(
+                            index * next_values[AGG_KEY_INDEX_OFFSET + k + 1] *
+                            (next_values[AGG_G1_ADD_OFFSET + input_col + i] - key_sum(local_values, res_col + i, double_res_col + i))
+                        );
+                    }
+                }
+            }
+        }
+        yield_constr.constraint_first_row(local_values[AGG_KEY_INDEX_OFFSET] - P::ONES);
+        yield_constr.constraint(local_values[AGG_G1_ADD_OFFSET + G1_ADD_CHECK_OFFSET] - index_sum);
+        for (double_col, add_col) in [(G1_DOUBLE_SELECTOR_OFFSET, G1_ADD_SELECTOR_OFFSET), (G1_DOUBLE_CHECK_OFFSET, G1_ADD_CHECK_OFFSET)] {
+            yield_constr.constraint(local_values[AGG_G1_DOUBLE_OFFSET + double_col] - local_values[AGG_G1_ADD_OFFSET + add_col]);
+        }
+        for (double_col, add_col, limbs) in DOUBLE_INPUT_LINKS {
+            for i in 0..limbs {
+                yield_constr.constraint(
+                    index_sum *
+                    (local_values[AGG_G1_DOUBLE_OFFSET + double_col + i] - local_values[AGG_G1_ADD_OFFSET + add_col + i])
+                );
+            }
+        }
+        let equal = local_values[AGG_KEY_EQUAL_OFFSET];
+        yield_constr.constraint(equal * (P::ONES - equal));
+        yield_constr.constraint_transition(
+            local_values[AGG_G1_ADD_OFFSET + G1_ADD_SELECTOR_OFFSET] *
+            (next_values[AGG_KEY_EQUAL_OFFSET] - equal)
+        );
+        for col in [G1_ADD_INFINITY1_OFFSET, G1_ADD_INFINITY2_OFFSET] {
+            yield_constr.constraint(equal * local_values[AGG_G1_ADD_OFFSET + col]);
+        }
+        for i in 0..12 {
+            for (col1, col2) in [(G1_ADD_X1_OFFSET, G1_ADD_X2_OFFSET), (G1_ADD_Y1_OFFSET, G1_ADD_Y2_OFFSET)] {
+                yield_constr.constraint(
+                    equal *
+                    (local_values[AGG_G1_ADD_OFFSET + col1 + i] - local_values[AGG_G1_ADD_OFFSET + col2 + i])
+                );
+            }
+        }
+        let last_index = local_values[AGG_KEY_INDEX_OFFSET + last];
+        yield_constr.constraint(last_index * key_sum(local_values, G1_ADD_RES_INFINITY_OFFSET, G1_DOUBLE_RES_INFINITY_OFFSET));
+        for i in 0..12 {
+            yield_constr.constraint(
+                last_index *
+                (local_values[AGG_MILLER_LOOP_PK_OFFSET + PX_OFFSET + i] - key_sum(local_values, G1_ADD_RES_X_OFFSET + i, G1_DOUBLE_RES_X_OFFSET + i))
+            );
+            yield_constr.constraint(
+                last_index *
+                (local_values[AGG_MILLER_LOOP_PK_OFFSET + PY_OFFSET + i] - key_sum(local_values, G1_ADD_RES_Y_OFFSET + i, G1_DOUBLE_RES_Y_OFFSET + i))
+            );
+        }
+
+        // Miller loops
+        let neg_g1 = -G1Affine::generator();
+        for i in 0..12 {
+            yield_constr.constraint(local_values[AGG_MILLER_LOOP_SIG_OFFSET + PX_OFFSET + i] - FE::from_canonical_u32(neg_g1.x.0[i]));
+            yield_constr.constraint(local_values[AGG_MILLER_LOOP_SIG_OFFSET + PY_OFFSET + i] - FE::from_canonical_u32(neg_g1.y.0[i]));
+            for col in [PX_OFFSET, PY_OFFSET] {
+                yield_constr.constraint_transition(
+                    local_values[AGG_MILLER_LOOP_PK_OFFSET + col + i] - next_values[AGG_MILLER_LOOP_PK_OFFSET + col + i]
+                );
+            }
+        }
+
+        add_g1_add_constraints(local_values, next_values, yield_constr, AGG_G1_ADD_OFFSET, Some(P::ONES - equal));
+        add_g1_double_constraints(local_values, next_values, yield_constr, AGG_G1_DOUBLE_OFFSET, None);
+        add_pairing_product_constraints(local_values, next_values, public_inputs, yield_constr, self.num_rows);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let zero = builder.constant_extension(F::Extension::ZERO);
+
+        // Key aggregation
+        let last = AGGREGATE_VERIFY_MAX_KEYS - 2;
+        let mut index_sum = zero;
+        for k in 0..AGGREGATE_VERIFY_MAX_KEYS-1 {
+            let index = local_values[AGG_KEY_INDEX_OFFSET + k];
+            index_sum = builder.add_extension(index_sum, index);
+            let c = builder.sub_extension(one, index);
+            let c = builder.mul_extension(index, c);
+            yield_constr.constraint(builder, c);
+            let c = builder.sub_extension(next_values[AGG_KEY_INDEX_OFFSET + k], index);
+            let c = builder.mul_extension(local_values[AGG_G1_ADD_OFFSET + G1_ADD_SELECTOR_OFFSET], c);
+            yield_constr.constraint_transition(builder, c);
+            for (col, pis, limbs) in key_links(k) {
+                for i in 0..limbs {
+                    let c = builder.sub_extension(local_values[col + i], public_inputs[pis + i]);
+                    let c = builder.mul_extension(index, c);
+                    yield_constr.constraint(builder, c);
+                }
+            }
+            if k < last {
+                let block_end = builder.sub_extension(one, local_values[AGG_G1_ADD_OFFSET + G1_ADD_SELECTOR_OFFSET]);
+                let c = builder.sub_extension(next_values[AGG_KEY_INDEX_OFFSET + k + 1], one);
+                let c = builder.mul_many_extension([index, block_end, c]);
+                yield_constr.constraint_transition(builder, c);
+                for (res_col, double_res_col, input_col, limbs) in CHAIN_LINKS {
+                    for i in 0..limbs {
+                        let sum = key_sum_ext_circuit(builder, local_values, res_col + i, double_res_col + i);
+                        let c = builder.sub_extension(next_values[AGG_G1_ADD_OFFSET + input_col + i], sum);
+                        let c = builder.mul_many_extension([index, next_values[AGG_KEY_INDEX_OFFSET + k + 1], c]);
+                        yield_constr.constraint_transition(builder, c);
+                    }
+                }
+            }
+        }
+        let c = builder.sub_extension(local_values[AGG_KEY_INDEX_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        let c = builder.sub_extension(local_values[AGG_G1_ADD_OFFSET + G1_ADD_CHECK_OFFSET], index_sum);
+        yield_constr.constraint(builder, c);
+        for (double_col, add_col) in [(G1_DOUBLE_SELECTOR_OFFSET, G1_ADD_SELECTOR_OFFSET), (G1_DOUBLE_CHECK_OFFSET, G1_ADD_CHECK_OFFSET)] {
+            let c = builder.sub_extension(local_values[AGG_G1_DOUBLE_OFFSET + double_col], local_values[AGG_G1_ADD_OFFSET + add_col]);
+            yield_constr.constraint(builder, c);
+        }
+        for (double_col, add_col, limbs) in DOUBLE_INPUT_LINKS {
+            for i in 0..limbs {
+                let c = builder.sub_extension(local_values[AGG_G1_DOUBLE_OFFSET + double_col + i], local_values[AGG_G1_ADD_OFFSET + add_col + i]);
+                let c = builder.mul_extension(index_sum, c);
+                yield_constr.constraint(builder, c);
+            }
+        }
+        let equal = local_values[AGG_KEY_EQUAL_OFFSET];
+        let not_equal = builder.sub_extension(one, equal);
+        let c = builder.mul_extension(equal, not_equal);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(next_values[AGG_KEY_EQUAL_OFFSET], equal);
+        let c = builder.mul_extension(local_values[AGG_G1_ADD_OFFSET + G1_ADD_SELECTOR_OFFSET], c);
+        yield_constr.constraint_transition(builder, c);
+        for col in [G1_ADD_INFINITY1_OFFSET, G1_ADD_INFINITY2_OFFSET] {
+            let c = builder.mul_extension(equal, local_values[AGG_G1_ADD_OFFSET + col]);
+            yield_constr.constraint(builder, c);
+        }
+        for i in 0..12 {
+            for (col1, col2) in [(G1_ADD_X1_OFFSET, G1_ADD_X2_OFFSET), (G1_ADD_Y1_OFFSET, G1_ADD_Y2_OFFSET)] {
+                let c = builder.sub_extension(local_values[AGG_G1_ADD_OFFSET + col1 + i], local_values[AGG_G1_ADD_OFFSET + col2 + i]);
+                let c = builder.mul_extension(equal, c);
+                yield_constr.constraint(builder, c);
+            }
+        }
+        let last_index = local_values[AGG_KEY_INDEX_OFFSET + last];
+        let sum_infinity = key_sum_ext_circuit(builder, local_values, G1_ADD_RES_INFINITY_OFFSET, G1_DOUBLE_RES_INFINITY_OFFSET);
+        let c = builder.mul_extension(last_index, sum_infinity);
+        yield_constr.constraint(builder, c);
+        for i in 0..12 {
+            let sum_x = key_sum_ext_circuit(builder, local_values, G1_ADD_RES_X_OFFSET + i, G1_DOUBLE_RES_X_OFFSET + i);
+            let c = builder.sub_extension(local_values[AGG_MILLER_LOOP_PK_OFFSET + PX_OFFSET + i], sum_x);
+            let c = builder.mul_extension(last_index, c);
+            yield_constr.constraint(builder, c);
+            let sum_y = key_sum_ext_circuit(builder, local_values, G1_ADD_RES_Y_OFFSET + i, G1_DOUBLE_RES_Y_OFFSET + i);
+            let c = builder.sub_extension(local_values[AGG_MILLER_LOOP_PK_OFFSET + PY_OFFSET + i], sum_y);
+            let c = builder.mul_extension(last_index, c);
+            yield_constr.constraint(builder, c);
+        }
+
+        // Miller loops
+        let neg_g1 = -G1Affine::generator();
+        for i in 0..12 {
+            let x = builder.constant_extension(F::Extension::from_canonical_u32(neg_g1.x.0[i]));
+            let c = builder.sub_extension(local_values[AGG_MILLER_LOOP_SIG_OFFSET + PX_OFFSET + i], x);
+            yield_constr.constraint(builder, c);
+            let y = builder.constant_extension(F::Extension::from_canonical_u32(neg_g1.y.0[i]));
+            let c = builder.sub_extension(local_values[AGG_MILLER_LOOP_SIG_OFFSET + PY_OFFSET + i], y);
+            yield_constr.constraint(builder, c);
+            for col in [PX_OFFSET, PY_OFFSET] {
+                let c = builder.sub_extension(local_values[AGG_MILLER_LOOP_PK_OFFSET + col + i], next_values[AGG_MILLER_LOOP_PK_OFFSET + col + i]);
+                yield_constr.constraint_transition(builder, c);
+            }
+        }
+
+        add_g1_add_constraints_ext_circuit(builder, yield_constr, local_values, next_values, AGG_G1_ADD_OFFSET, Some(not_equal));
+        add_g1_double_constraints_ext_circuit(builder, yield_constr, local_values, next_values, AGG_G1_DOUBLE_OFFSET, None);
+        add_pairing_product_constraints_ext_circuit(builder, yield_constr, local_values, next_values, public_inputs, self.num_rows);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        FINAL_EXP_CONSTRAINT_DEGREE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::final_exponentiate::FINAL_EXP_MAX_ROWS;
+    use crate::native::{self, group_order, Fp, Fp2, Fp12, G1Affine, G2Affine, G2Projective};
+
+    use super::{AggregateVerifyStark, AGGREGATE_VERIFY_MAX_KEYS, PUBLIC_INPUTS};
+
+    fn fp(s: &str) -> Fp {
+        Fp::get_fp_from_biguint(BigUint::from_str(s).unwrap())
+    }
+
+    /// The key, message hash and signature of native::verify_bls_signatures.
+    fn bls_signature() -> (G1Affine, G2Affine, G2Affine) {
+        let pk = G1Affine {
+            x: fp("1216495682195235861952885506871698490232894470117269383940381148575524314493849307811227440691167647909822763414941"),
+            y: fp("2153848155426317245700560287567131132765685008362732985860101000686875894603366983854567186180519945327668975076337"),
+            infinity: false,
+        };
+        let hm = G2Projective {
+            x: Fp2([fp("2640504383352253166624742184946918613522392710628037055952404127879364455194422343335555527925815834654853618706317"), fp("3512267754584411844719003222712149130451230828216813699108449950001725181635151866954918805409098715392393669496763")]),
+            y: Fp2([fp("1819141142055458317635768413798746444112487913647217792452244858223746035103974374419118545961357374373926748974853"), fp("2023172707753915325613231249141956147838197708174300845595677034762003254300804275953249871078804883738174492552197")]),
+            z: Fp2([fp("2090317837686632453881173016321367129380434356038329533464948735487686003804511165163385664859654015333500347340874"), fp("3589273988676721566549754197317344469206294207551897598521700599244392528027952567094835689880190836504376087662460")]),
+        }.to_affine();
+        let sig = G2Affine {
+            x: Fp2([fp("2623971017592927791661443929103810896934774536775525535423614243457684905034147949323467412106133456094022067726851"), fp("2791552278788393998835490815906332650385266234676766868498515429583366873304026057923442494886948609285829286788356")]),
+            y: Fp2([fp("1392880899106984160179818268515214962705329372907929072981217458923190202387659009520579695608141992620405977748755"), fp("2607207514294746608778464853061537277878553458184247374568293197687045701239874275081091959210122811260239467513958")]),
+            infinity: false,
+        };
+        (pk, hm, sig)
+    }
+
+    /// Proves and verifies the aggregate verification of the signature of `bls_signature` under the keys `pks`, which must sum to its key.
+    fn prove_and_verify_aggregate(pks: &[G1Affine]) {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = AggregateVerifyStark<F, D>;
+
+        let (pk, hm, sig) = bls_signature();
+        assert_eq!(pks.iter().fold(G1Affine::identity(), |sum, pk| sum + *pk), pk);
+        let g1 = G1Affine::generator();
+        let mu = native::miller_loop(pk.x, pk.y, hm.x, hm.y, Fp2::one()) * native::miller_loop((-g1).x, (-g1).y, sig.x, sig.y, Fp2::one());
+        assert_eq!(mu.final_exponentiate(), Fp12::one());
+
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let stark = S::new(8192).unwrap();
+        let public_inputs = S::public_inputs(pks, &hm, &sig).unwrap();
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(pks, &hm, &sig).unwrap();
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof.clone(), &config).unwrap();
+        stark.verify(&config, proof.clone(), pks, &hm, &sig).unwrap();
+        // The proof does not verify against another signature, nor against keys which are not finite points of the G1 subgroup.
+        assert!(stark.verify(&config, proof.clone(), pks, &hm, &hm).is_err());
+        let off_curve = G1Affine { x: pks[0].x, y: pks[0].y + Fp::one(), infinity: false };
+        let mut invalid_pks = pks.to_vec();
+        invalid_pks[0] = off_curve;
+        assert!(stark.verify(&config, proof.clone(), &invalid_pks, &hm, &sig).is_err());
+        invalid_pks[0] = G1Affine::identity();
+        assert!(stark.verify(&config, proof, &invalid_pks, &hm, &sig).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_verify_two_signers() {
+        // Splits the secret key sk into (sk - 2) + 2. The signatures (sk - 2)*H(m) and 2*H(m) of the two signers aggregate to sk*H(m).
+        let (pk, _, _) = bls_signature();
+        let pk2 = G1Affine::generator().double();
+        let pk1 = pk + -pk2;
+        prove_and_verify_aggregate(&[pk1, pk2]);
+    }
+
+    #[test]
+    fn test_aggregate_verify_duplicate_keys() {
+        // Splits the secret key sk into sk/4 + sk/4 + sk/2. The second key is the first one, and the third key is the sum of the first two, so both additions are doublings.
+        let (pk, _, _) = bls_signature();
+        let half = (group_order() + 1u32) / 2u32;
+        let quarter = pk.mul_scalar(&half).mul_scalar(&half);
+        prove_and_verify_aggregate(&[quarter, quarter, quarter.double()]);
+    }
+
+    #[test]
+    fn test_aggregate_verify_invalid_inputs() {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+        type S = AggregateVerifyStark<F, 2>;
+
+        let (pk, hm, sig) = bls_signature();
+        assert!(S::public_inputs(&[], &hm, &sig).is_err());
+        assert!(S::public_inputs(&[pk; AGGREGATE_VERIFY_MAX_KEYS + 1], &hm, &sig).is_err());
+        assert!(S::public_inputs(&[pk], &G2Affine::identity(), &sig).is_err());
+        let off_curve = G2Affine { x: hm.x, y: sig.y, infinity: false };
+        assert!(S::public_inputs(&[pk], &hm, &off_curve).is_err());
+        assert!(S::new(8192).unwrap().generate_trace(&[pk], &off_curve, &sig).is_err());
+        assert!(S::new(S::required_rows() - 1).is_err());
+        assert!(S::new(2 * FINAL_EXP_MAX_ROWS).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+//! Proves the final exponentiation of an Fp12 given as its 12 fp components in hex, verifies the proof, and writes the proof bytes (see `serialization::serialize_proof`) to a file.
+//!
+//! `prove_final_exp [--out <proof path>] <12 hex words>`
+//! `prove_final_exp [--out <proof path>] --file <input path>`
+//!
+//! The words are in the order of `Fp12::to_hex`, `0x` prefixed or not. An input file holds the 12 words separated by whitespace. The proof is written to `final_exp_proof.json` unless `--out` is given.
+
+use std::time::Instant;
+
+use anyhow::{bail, Context, Result};
+use plonky2::util::timing::TimingTree;
+use starky::{config::StarkConfig, prover::prove};
+use starky_bls12_381::{
+    config::{DefaultConfig, DefaultField, EXTENSION_DEGREE},
+    final_exponentiate::{verify_final_exp, FinalExponentiateStark},
+    native::Fp12,
+    serialization::serialize_proof,
+    utils::trace_rows_to_poly_values_streaming,
+};
+
+const D: usize = EXTENSION_DEGREE;
+type C = DefaultConfig;
+type F = DefaultField;
+type S = FinalExponentiateStark<F, D>;
+
+const USAGE: &str = "usage: prove_final_exp [--out <proof path>] (<12 hex words> | --file <input path>)";
+
+struct Args {
+    input: Fp12,
+    out: String,
+}
+
+fn parse_args(args: &[String]) -> Result<Args> {
+    let mut out = "final_exp_proof.json".to_string();
+    let mut file = None;
+    let mut words = vec![];
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--out" => out = args.next().context(USAGE)?.clone(),
+            "--file" => file = Some(args.next().context(USAGE)?.clone()),
+            _ => words.push(arg.clone()),
+        }
+    }
+    if let Some(file) = file {
+        if !words.is_empty() {
+            bail!("{}\nthe input is given both as words and as a file", USAGE);
+        }
+        let contents = std::fs::read_to_string(&file).with_context(|| format!("reading {}", file))?;
+        words = contents.split_whitespace().map(str::to_string).collect();
+    }
+    let input = Fp12::from_hex(&words).context(USAGE)?;
+    Ok(Args { input, out })
+}
+
+fn prove_final_exp(args: Args) -> Result<()> {
+    let mut config = StarkConfig::standard_fast_config();
+    config.fri_config.rate_bits = 2;
+    let stark = S::with_min_rows();
+    let x = args.input;
+    let public_inputs = S::public_inputs(&x, &x.final_exponentiate());
+
+    let s = Instant::now();
+    let trace = stark.generate_trace(x)?;
+    println!("Trace generation: {:?}", s.elapsed());
+    let s = Instant::now();
+    let proof = prove::<F, C, S, D>(stark, &config, trace_rows_to_poly_values_streaming(trace), &public_inputs, &mut TimingTree::default())?;
+    println!("Proving: {:?}", s.elapsed());
+    let s = Instant::now();
+    verify_final_exp::<F, C, D>(proof.proof.clone(), &proof.public_inputs, &config)?;
+    println!("Verification: {:?}", s.elapsed());
+
+    let bytes = serialize_proof(&proof)?;
+    std::fs::write(&args.out, &bytes).with_context(|| format!("writing {}", args.out))?;
+    println!("Wrote the {} byte proof to {}", bytes.len(), args.out);
+    Ok(())
+}
+
+fn main() -> Result<()> {
+    env_logger::init();
+    let args = parse_args(&std::env::args().skip(1).collect::<Vec<String>>())?;
+    std::thread::Builder::new().spawn(move || prove_final_exp(args)).unwrap().join().unwrap()
+}
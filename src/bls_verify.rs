@@ -0,0 +1,431 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use anyhow::{bail, Result};
+
+use crate::native::{decompress_g1, decompress_g2, G1Affine, G2Affine};
+
+use crate::aggregate_verify::{add_pairing_product_constraints, add_pairing_product_constraints_ext_circuit, fill_trace_pairing_product, AggregateVerifyStark, AGG_MILLER_LOOP_PK_OFFSET, AGG_MILLER_LOOP_SIG_OFFSET, COLUMNS, PIS_KEYS_OFFSET, PIS_KEY_SIZE, PUBLIC_INPUTS};
+use crate::miller_loop::{PX_OFFSET, PY_OFFSET};
+use crate::pairing::pairing_precomp;
+
+pub const TOTAL_COLUMNS: usize = COLUMNS;
+
+/// The two BLS ciphersuites, which differ in the groups holding the public key and the signature. The message is hashed to the group of the signature.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlsVariant {
+    /// Public keys in G1 and signatures in G2, e.g. Ethereum. Verifies `e(pk, H(m)) == e(G1, sig)`.
+    MinPk,
+    /// Public keys in G2 and signatures in G1. Verifies `e(H(m), pk) == e(sig, G2)`.
+    MinSig,
+}
+
+/*
+    This stark verifies a single BLS signature in either variant (see `BlsVariant`). This stark needs 8192 rows.
+    In the minimal public key size variant, it is the aggregate verification stark with a single public key, see `AggregateVerifyStark`. The public inputs are the same, with the remaining key slots set to the point at infinity.
+    The public key and the signature are decompressed natively from their 48 and 96 byte encodings, both by the prover and the verifier when laying out the public inputs. H(m) is taken as an affine point, since hashing to G2 is not available in this crate, and is checked to be in the G2 subgroup like the signature.
+    In the minimal signature size variant, the check is done as e(H(m), pk) * e(sig, -G2) == 1 on the columns of the two miller loops, the product and the final exponentiate of `AggregateVerifyStark`, whose G1 addition is left unused.
+    The public inputs keep the layout of `AggregateVerifyStark`: H(m) and the signature take the first two key slots, then come the `ell_coeffs` of the public key and the `ell_coeffs` of -G2. The public key is decompressed natively from its 96 byte encoding. H(m) is an affine G1 point, e.g. from `map_to_curve_g1`, `iso_map_g1` and `clear_cofactor_g1`, the signature can be decompressed with `decompress_g1`.
+*/
+pub const PIS_MIN_SIG_HM_OFFSET: usize = PIS_KEYS_OFFSET;
+pub const PIS_MIN_SIG_SIG_OFFSET: usize = PIS_KEYS_OFFSET + PIS_KEY_SIZE;
+
+#[derive(Clone, Copy)]
+pub struct BlsVerifyStark<F: RichField + Extendable<D>, const D: usize> {
+    aggregate: AggregateVerifyStark<F, D>,
+    variant: BlsVariant,
+    num_rows: usize,
+}
+
+/// Decompresses the public key, returns an error if the encoding is invalid, the key is the point at infinity or it is not in the prime order subgroup.
+fn decompress_public_key(pk: &[u8; 48]) -> Result<G1Affine> {
+    let pk = match decompress_g1(pk) {
+        Some(pk) => pk,
+        None => bail!("invalid public key encoding"),
+    };
+    if pk.infinity {
+        bail!("public key is the point at infinity");
+    }
+    if !pk.is_in_subgroup() {
+        bail!("public key is not in the G1 subgroup");
+    }
+    Ok(pk)
+}
+
+/// Returns an error if the G2 point is the point at infinity, is not on the curve or is not in the prime order subgroup.
+fn check_g2_point(p: &G2Affine, name: &str) -> Result<()> {
+    if p.infinity || !p.is_on_curve() || !p.is_in_subgroup() {
+        bail!("{} is not a finite point of the G2 subgroup", name);
+    }
+    Ok(())
+}
+
+/// Decompresses the signature of the minimal public key size variant, returns an error if the encoding is invalid, the signature is the point at infinity or it is not in the prime order subgroup.
+fn decompress_signature(sig: &[u8; 96]) -> Result<G2Affine> {
+    let sig = match decompress_g2(sig) {
+        Some(sig) => sig,
+        None => bail!("invalid signature encoding"),
+    };
+    check_g2_point(&sig, "signature")?;
+    Ok(sig)
+}
+
+/// Decompresses the public key of the minimal signature size variant, returns an error if the encoding is invalid, the key is the point at infinity or it is not in the prime order subgroup.
+fn decompress_g2_public_key(pk: &[u8; 96]) -> Result<G2Affine> {
+    let pk = match decompress_g2(pk) {
+        Some(pk) => pk,
+        None => bail!("invalid public key encoding"),
+    };
+    if pk.infinity {
+        bail!("public key is the point at infinity");
+    }
+    if !pk.is_in_subgroup() {
+        bail!("public key is not in the G2 subgroup");
+    }
+    Ok(pk)
+}
+
+/// Returns an error if the G1 point is the point at infinity, is not on the curve or is not in the prime order subgroup.
+fn check_g1_point(p: &G1Affine, name: &str) -> Result<()> {
+    if p.infinity || !p.is_on_curve() || !p.is_in_subgroup() {
+        bail!("{} is not a finite point of the G1 subgroup", name);
+    }
+    Ok(())
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> BlsVerifyStark<F, D> {
+    /// Rows used by the verification of either variant, the same as [AggregateVerifyStark::required_rows].
+    pub fn required_rows() -> usize {
+        AggregateVerifyStark::<F, D>::required_rows()
+    }
+
+    /// Stark for the minimal public key size variant.
+    pub fn new(num_rows: usize) -> Result<Self> {
+        Self::with_variant(num_rows, BlsVariant::MinPk)
+    }
+
+    /// Returns an error for the same `num_rows` as [AggregateVerifyStark::new].
+    pub fn with_variant(num_rows: usize, variant: BlsVariant) -> Result<Self> {
+        Ok(Self {
+            aggregate: AggregateVerifyStark::new(num_rows)?,
+            variant,
+            num_rows,
+        })
+    }
+
+    pub fn variant(&self) -> BlsVariant {
+        self.variant
+    }
+
+    /// Lays out the public inputs of the minimal public key size variant for the compressed public key `pk`, the message hash `hm` and the compressed signature `sig`. See [AggregateVerifyStark::public_inputs].
+    pub fn public_inputs(pk: &[u8; 48], hm: &G2Affine, sig: &[u8; 96]) -> Result<Vec<F>> {
+        let pk = decompress_public_key(pk)?;
+        check_g2_point(hm, "H(m)")?;
+        let sig = decompress_signature(sig)?;
+        AggregateVerifyStark::<F, D>::public_inputs(&[pk], hm, &sig)
+    }
+
+    /// Fills the trace for the verification of the compressed signature `sig` of the message hash `hm` under the compressed public key `pk`, in the minimal public key size variant. See [AggregateVerifyStark::generate_trace].
+    pub fn generate_trace(&self, pk: &[u8; 48], hm: &G2Affine, sig: &[u8; 96]) -> Result<Vec<[F; TOTAL_COLUMNS]>> {
+        if self.variant != BlsVariant::MinPk {
+            bail!("generate_trace: the stark is for the {:?} variant, use generate_trace_min_sig", self.variant);
+        }
+        let pk = decompress_public_key(pk)?;
+        check_g2_point(hm, "H(m)")?;
+        let sig = decompress_signature(sig)?;
+        self.aggregate.generate_trace(&[pk], hm, &sig)
+    }
+
+    /// Lays out the public inputs of the minimal signature size variant for the compressed public key `pk`, the message hash `hm` and the signature `sig`, as [AggregateVerifyStark::public_inputs] with the keys H(m) and sig, the `ell_coeffs` of pk in place of those of H(m) and the `ell_coeffs` of -G2 in place of those of the signature.
+    pub fn min_sig_public_inputs(pk: &[u8; 96], hm: &G1Affine, sig: &G1Affine) -> Result<Vec<F>> {
+        let pk = decompress_g2_public_key(pk)?;
+        check_g1_point(hm, "H(m)")?;
+        check_g1_point(sig, "signature")?;
+        AggregateVerifyStark::<F, D>::public_inputs(&[*hm, *sig], &pk, &-G2Affine::generator())
+    }
+
+    /// Fills the trace for the verification of the signature `sig` of the message hash `hm` under the compressed public key `pk`, in the minimal signature size variant. Fills the miller loops of (H(m), pk) and (sig, -G2), their product and the final exponentiate trace.
+    pub fn generate_trace_min_sig(&self, pk: &[u8; 96], hm: &G1Affine, sig: &G1Affine) -> Result<Vec<[F; TOTAL_COLUMNS]>> {
+        if self.variant != BlsVariant::MinSig {
+            bail!("generate_trace_min_sig: the stark is for the {:?} variant, use generate_trace", self.variant);
+        }
+        let pk = decompress_g2_public_key(pk)?;
+        check_g1_point(hm, "H(m)")?;
+        check_g1_point(sig, "signature")?;
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_pairing_product(&mut trace, hm, &pairing_precomp(&pk)?, sig, &pairing_precomp(&-G2Affine::generator())?);
+        Ok(trace)
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for BlsVerifyStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        if self.variant == BlsVariant::MinPk {
+            return self.aggregate.eval_packed_generic(vars, yield_constr);
+        }
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        // The G1 points of the miller loops are H(m) and the signature
+        for (offset, pis_offset) in [(AGG_MILLER_LOOP_PK_OFFSET, PIS_MIN_SIG_HM_OFFSET), (AGG_MILLER_LOOP_SIG_OFFSET, PIS_MIN_SIG_SIG_OFFSET)] {
+            for i in 0..12 {
+                yield_constr.constraint(local_values[offset + PX_OFFSET + i] - public_inputs[pis_offset + i]);
+                yield_constr.constraint(local_values[offset + PY_OFFSET + i] - public_inputs[pis_offset + 12 + i]);
+            }
+        }
+        add_pairing_product_constraints(local_values, next_values, public_inputs, yield_constr, self.num_rows);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        if self.variant == BlsVariant::MinPk {
+            return self.aggregate.eval_ext_circuit(builder, vars, yield_constr);
+        }
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        for (offset, pis_offset) in [(AGG_MILLER_LOOP_PK_OFFSET, PIS_MIN_SIG_HM_OFFSET), (AGG_MILLER_LOOP_SIG_OFFSET, PIS_MIN_SIG_SIG_OFFSET)] {
+            for i in 0..12 {
+                let c = builder.sub_extension(local_values[offset + PX_OFFSET + i], public_inputs[pis_offset + i]);
+                yield_constr.constraint(builder, c);
+                let c = builder.sub_extension(local_values[offset + PY_OFFSET + i], public_inputs[pis_offset + 12 + i]);
+                yield_constr.constraint(builder, c);
+            }
+        }
+        add_pairing_product_constraints_ext_circuit(builder, yield_constr, local_values, next_values, public_inputs, self.num_rows);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        self.aggregate.constraint_degree()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{clear_cofactor_g1, decompress_g1, decompress_g2, iso_map_g1, map_to_curve_g1, sqrt_fp2, Fp, Fp2, G1Affine, G2Affine, G2Projective};
+
+    use super::{BlsVariant, BlsVerifyStark, PUBLIC_INPUTS};
+
+    fn fp(s: &str) -> Fp {
+        Fp::get_fp_from_biguint(BigUint::from_str(s).unwrap())
+    }
+
+    /// A key pair and signature of the message "starky_bls12_381" generated with blst in the Ethereum ciphersuite, BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_, with the compressed public key, H(m) decompressed from its compressed encoding and the compressed signature.
+    fn blst_vector() -> ([u8; 48], G2Affine, [u8; 96]) {
+        let sk = BigUint::from_bytes_be(&hex::decode("23c205e368093188a73311a45658e3d30e00741019b0eff05277ba2fd42bc422").unwrap());
+        let pk: [u8; 48] = hex::decode("a6ceb0760781082c1954d2a4ec868c82e81d0b2bfb6d95b28bfcae30842fc58387da58dcfed367f74d878739285cae92").unwrap().try_into().unwrap();
+        let hm: [u8; 96] = hex::decode("977ce8dfe4ced60539d11f48f6239ceb34ac7d86517e4e35d234375d94b14888b6ff2c6c71c266e73411e77030025ead0ef2e95444e2b71337bd68130db8a229df1f86005a6c1760638e5d15c0f66ab82d8161117e3781a67965d5b19f6ed2d5").unwrap().try_into().unwrap();
+        let sig: [u8; 96] = hex::decode("b60011f3463e7f3e550d51d9d201182cb065252ffa8de20a1294a9d917ecbf340eecfef94e553ff6f8902e2bbee8346c110d00f57ee130b2e4aa20089ee6d6f13a484ab70da94d0ee2e91682bd285e837d3dbe033a4faa8f282db16c3597237c").unwrap().try_into().unwrap();
+        assert_eq!(decompress_g1(&pk).unwrap(), G1Affine::generator().mul_scalar(&sk));
+        (pk, decompress_g2(&hm).unwrap(), sig)
+    }
+
+    /// H(m) as a G1 point from two field elements, the key pair from a fixed secret key and the signature sk * H(m).
+    fn min_sig_inputs() -> ([u8; 96], G1Affine, G1Affine) {
+        let u = |i: u32| Fp::get_fp_from_biguint(BigUint::from(i) * 0x9e3779b9u32);
+        let hm = clear_cofactor_g1(iso_map_g1(map_to_curve_g1(u(1))) + iso_map_g1(map_to_curve_g1(u(2))));
+        let sk = BigUint::from_str("40535487838306347262513418034127355155416283154289396519919342425880917706245").unwrap();
+        let pk = G2Affine::generator().mul_scalar(&sk);
+        (pk.to_compressed(), hm, hm.mul_scalar(&sk))
+    }
+
+    #[test]
+    fn test_bls_verify_invalid_public_key() {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+        type S = BlsVerifyStark<F, 2>;
+
+        let g2 = G2Affine::generator();
+        let mut infinity = [0u8; 48];
+        infinity[0] = 0xc0;
+        assert!(S::public_inputs(&infinity, &g2, &g2.to_compressed()).is_err());
+        let mut x_one = [0u8; 48];
+        x_one[0] = 0x80;
+        x_one[47] = 1;
+        assert!(S::public_inputs(&x_one, &g2, &g2.to_compressed()).is_err());
+    }
+
+    #[test]
+    fn test_bls_verify_invalid_g2_points() {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+        type S = BlsVerifyStark<F, 2>;
+
+        let (pk, hm, sig) = blst_vector();
+        assert!(S::public_inputs(&pk, &hm, &sig).is_ok());
+        let mut infinity = [0u8; 96];
+        infinity[0] = 0xc0;
+        assert!(S::public_inputs(&pk, &hm, &infinity).is_err());
+        assert!(S::public_inputs(&pk, &G2Affine::identity(), &sig).is_err());
+        // The first point of the curve with x in Fp, which is on the curve but not in the G2 subgroup.
+        let four = Fp::get_fp_from_biguint(BigUint::from(4u32));
+        let not_in_subgroup = (1u32..).find_map(|i| {
+            let x = Fp2([Fp::get_fp_from_biguint(BigUint::from(i)), Fp::zero()]);
+            sqrt_fp2(x * x * x + Fp2([four, four])).map(|y| G2Affine { x, y, infinity: false })
+        }).unwrap();
+        assert!(not_in_subgroup.is_on_curve() && !not_in_subgroup.is_in_subgroup());
+        assert!(S::public_inputs(&pk, &not_in_subgroup, &sig).is_err());
+        assert!(S::public_inputs(&pk, &hm, &not_in_subgroup.to_compressed()).is_err());
+        assert!(S::new(8192).unwrap().generate_trace(&pk, &not_in_subgroup, &sig).is_err());
+        assert!(S::new(8192).unwrap().generate_trace(&pk, &hm, &not_in_subgroup.to_compressed()).is_err());
+    }
+
+    #[test]
+    fn test_bls_verify_min_sig_invalid_inputs() {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+        type S = BlsVerifyStark<F, 2>;
+
+        let (pk, hm, sig) = min_sig_inputs();
+        assert!(S::min_sig_public_inputs(&pk, &hm, &sig).is_ok());
+        let mut infinity = [0u8; 96];
+        infinity[0] = 0xc0;
+        assert!(S::min_sig_public_inputs(&infinity, &hm, &sig).is_err());
+        assert!(S::min_sig_public_inputs(&pk, &G1Affine::identity(), &sig).is_err());
+        let off_curve = G1Affine { x: sig.x, y: sig.y + Fp::one(), infinity: false };
+        assert!(S::min_sig_public_inputs(&pk, &hm, &off_curve).is_err());
+        // The traces of the two variants are not interchangeable.
+        assert!(S::new(8192).unwrap().generate_trace_min_sig(&pk, &hm, &sig).is_err());
+        let g2 = G2Affine::generator();
+        assert!(S::with_variant(8192, BlsVariant::MinSig).unwrap().generate_trace(&G1Affine::generator().to_compressed(), &g2, &g2.to_compressed()).is_err());
+    }
+
+    #[test]
+    fn test_bls_verify() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = BlsVerifyStark<F, D>;
+
+        // Same key, message and signature as native::verify_bls_signatures.
+        let pk = G1Affine {
+            x: fp("1216495682195235861952885506871698490232894470117269383940381148575524314493849307811227440691167647909822763414941"),
+            y: fp("2153848155426317245700560287567131132765685008362732985860101000686875894603366983854567186180519945327668975076337"),
+            infinity: false,
+        };
+        let hm = G2Projective {
+            x: Fp2([fp("2640504383352253166624742184946918613522392710628037055952404127879364455194422343335555527925815834654853618706317"), fp("3512267754584411844719003222712149130451230828216813699108449950001725181635151866954918805409098715392393669496763")]),
+            y: Fp2([fp("1819141142055458317635768413798746444112487913647217792452244858223746035103974374419118545961357374373926748974853"), fp("2023172707753915325613231249141956147838197708174300845595677034762003254300804275953249871078804883738174492552197")]),
+            z: Fp2([fp("2090317837686632453881173016321367129380434356038329533464948735487686003804511165163385664859654015333500347340874"), fp("3589273988676721566549754197317344469206294207551897598521700599244392528027952567094835689880190836504376087662460")]),
+        }.to_affine();
+        let sig = G2Projective {
+            x: Fp2([fp("2623971017592927791661443929103810896934774536775525535423614243457684905034147949323467412106133456094022067726851"), fp("2791552278788393998835490815906332650385266234676766868498515429583366873304026057923442494886948609285829286788356")]),
+            y: Fp2([fp("1392880899106984160179818268515214962705329372907929072981217458923190202387659009520579695608141992620405977748755"), fp("2607207514294746608778464853061537277878553458184247374568293197687045701239874275081091959210122811260239467513958")]),
+            z: Fp2::one(),
+        }.to_affine();
+        let pk_compressed = pk.to_compressed();
+        let sig = sig.to_compressed();
+
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let stark = S::new(8192).unwrap();
+        let public_inputs = S::public_inputs(&pk_compressed, &hm, &sig).unwrap();
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(&pk_compressed, &hm, &sig).unwrap();
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+    }
+
+    #[test]
+    fn test_bls_verify_blst_vector() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = BlsVerifyStark<F, D>;
+
+        let (pk, hm, sig) = blst_vector();
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let stark = S::new(8192).unwrap();
+        let public_inputs = S::public_inputs(&pk, &hm, &sig).unwrap();
+        let trace = stark.generate_trace(&pk, &hm, &sig).unwrap();
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_rows_to_poly_values(trace),
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+    }
+
+    #[test]
+    fn test_bls_verify_min_sig() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = BlsVerifyStark<F, D>;
+
+        let (pk, hm, sig) = min_sig_inputs();
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let stark = S::with_variant(8192, BlsVariant::MinSig).unwrap();
+        let public_inputs = S::min_sig_public_inputs(&pk, &hm, &sig).unwrap();
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace_min_sig(&pk, &hm, &sig).unwrap();
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_rows_to_poly_values(trace),
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+
+        // H(m) is a valid G1 point but not the signature of the message.
+        let public_inputs = S::min_sig_public_inputs(&pk, &hm, &hm).unwrap();
+        let trace = stark.generate_trace_min_sig(&pk, &hm, &hm).unwrap();
+        let res = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            prove::<F, C, S, D>(stark, &config, trace_rows_to_poly_values(trace), &public_inputs, &mut TimingTree::default())
+        }));
+        if let Ok(Ok(proof)) = res {
+            assert!(verify_stark_proof(stark, proof, &config).is_err());
+        }
+    }
+}
@@ -24,6 +24,9 @@ use crate::fp::*;
 use crate::fp2::*;
 use crate::utils::*;
 
+/// Rows of the pairing precompute trace, 12 for each of the 68 `ell_coeffs`.
+pub const PAIRING_PRECOMP_ROWS: usize = 12 * 68;
+
 /*
     These trace offsets are for the calc_pairing_precomp function (super::native::calc_pairing_precomp). It takes 12*68 rows. The offsets are defined such that each 0 bit of the bls12-381 parameter takes 12 rows (one operation) and each 1 bit takes 12*2 rows (two operations). The MSB of bls12-381 parameter is not used.
     Z_MULT_Z_INV_OFFSET -> offset for multiplication z(input) and z_inv. Required to verify that z*z_inv = 1.
@@ -139,6 +142,11 @@ pub struct PairingPrecompStark<F: RichField + Extendable<D>, const D: usize> {
 
 // Implement trace generator
 impl<F: RichField + Extendable<D>, const D: usize> PairingPrecompStark<F, D> {
+    /// Rows used by the pairing precompute, `PAIRING_PRECOMP_ROWS`. The trace is filled up to `num_rows`, which must be a power of two of at least this many.
+    pub fn required_rows() -> usize {
+        PAIRING_PRECOMP_ROWS
+    }
+
     pub fn new(num_rows: usize) -> Self {
         Self {
             num_rows: num_rows,
@@ -0,0 +1,45 @@
+/*
+    Supported configs.
+    The starks are generic over `F: RichField + Extendable<D>`, but the fp arithmetic sets u32 limbs and their products with carries as single field elements, which needs a 64 bit prime field. Goldilocks is the only such RichField in plonky2, so the supported configs are Goldilocks with the quadratic extension (PoseidonGoldilocksConfig, the default and the one used by the recursive proofs in main.rs) or the quartic extension (PoseidonGoldilocksQuarticConfig), which gives more soundness bits per FRI query at a higher proving cost.
+    Another field would need an order of at least 2^63 (so that the limb products with their carries, and the row indices the row selectors are weighted with, are not reduced) and a 2-adic subgroup fitting the rows of the trace. `check_field` checks both, and the final exponentiation stark, the largest trace, checks its field on creation.
+    The examples prove with DefaultConfig, which is the quartic config when the `quartic-extension` feature is enabled and the quadratic one otherwise.
+*/
+
+use anyhow::{ensure, Result};
+use num_bigint::BigUint;
+use plonky2::{
+    field::{extension::quartic::QuarticExtension, goldilocks_field::GoldilocksField},
+    hash::{hash_types::RichField, poseidon::PoseidonHash},
+    plonk::config::GenericConfig,
+    util::log2_ceil,
+};
+
+/// Poseidon over Goldilocks with the quartic extension, for starks proved with `D = 4`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct PoseidonGoldilocksQuarticConfig;
+
+impl GenericConfig<4> for PoseidonGoldilocksQuarticConfig {
+    type F = GoldilocksField;
+    type FE = QuarticExtension<Self::F>;
+    type Hasher = PoseidonHash;
+    type InnerHasher = PoseidonHash;
+}
+
+#[cfg(not(feature = "quartic-extension"))]
+pub const EXTENSION_DEGREE: usize = 2;
+#[cfg(not(feature = "quartic-extension"))]
+pub type DefaultConfig = plonky2::plonk::config::PoseidonGoldilocksConfig;
+
+#[cfg(feature = "quartic-extension")]
+pub const EXTENSION_DEGREE: usize = 4;
+#[cfg(feature = "quartic-extension")]
+pub type DefaultConfig = PoseidonGoldilocksQuarticConfig;
+
+pub type DefaultField = <DefaultConfig as GenericConfig<EXTENSION_DEGREE>>::F;
+
+/// Checks that the field `F` fits the starks for a trace of `num_rows` rows, see the comment at the top of this file. The rows extended by the FRI rate must also fit the 2-adic subgroup, which the prover checks.
+pub fn check_field<F: RichField>(num_rows: usize) -> Result<()> {
+    ensure!(F::order() >= BigUint::from(1u64 << 63), "check_field: the field order {} is less than 2^63", F::order());
+    ensure!(log2_ceil(num_rows) <= F::TWO_ADICITY, "check_field: {} rows do not fit the 2-adic subgroup of order 2^{}", num_rows, F::TWO_ADICITY);
+    Ok(())
+}
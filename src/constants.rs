@@ -0,0 +1,114 @@
+//! BLS12-381 constants as canonical crate types, so that the generators and the group order are not rebuilt from literals at each use.
+
+use std::sync::LazyLock;
+
+use num_bigint::BigUint;
+
+use crate::native::{Fp, Fp12, Fp2, Fp6, G1Affine, G2Affine};
+
+pub use crate::native::get_bls_12_381_parameter;
+
+/// Generator of the G1 subgroup.
+pub const G1_GENERATOR: G1Affine = G1Affine {
+    x: Fp([3676489403, 4214943754, 4185529071, 1817569343, 387689560, 2706258495, 2541009157, 3278408783, 1336519695, 647324556, 832034708, 401724327]),
+    y: Fp([1187375073, 212476713, 2726857444, 3493644100, 738505709, 14358731, 3587181302, 4243972245, 1948093156, 2694721773, 3819610353, 146011265]),
+    infinity: false,
+};
+
+/// Generator of the G2 subgroup.
+pub const G2_GENERATOR: G2Affine = G2Affine {
+    x: Fp2([
+        Fp([3240213944, 3565180616, 2818948079, 195822374, 2061750647, 3025210212, 4198513410, 3336862420, 767889489, 638059815, 4035906193, 38445746]),
+        Fp([1560554366, 3853286661, 328490327, 860680466, 3699331145, 3050987963, 2569057818, 1500238032, 2284277605, 2108478368, 1383178080, 333458272]),
+    ]),
+    y: Fp2([
+        Fp([146286593, 3784529030, 1001169545, 2453326284, 1365299500, 1833081449, 2361250727, 2919078826, 3660461338, 2362035654, 1920822801, 216388903]),
+        Fp([4032788926, 2863204191, 1558977953, 1060572455, 1462671787, 645173931, 2242339759, 3409848446, 734170009, 850186928, 782709964, 101106848]),
+    ]),
+    infinity: false,
+};
+
+/// Order `r` of the G1 and G2 subgroups as 8 u32 limbs, least significant limb first.
+pub const GROUP_ORDER_R_LIMBS: [u32; 8] = [1, 4294967295, 4294859774, 1404937218, 161601541, 859428872, 698187080, 1944954707];
+
+/// Order `r` of the G1 and G2 subgroups.
+pub static GROUP_ORDER_R: LazyLock<BigUint> = LazyLock::new(|| BigUint::from_slice(&GROUP_ORDER_R_LIMBS));
+
+/// Frobenius coefficients of fp2 indexed by the power modulo 2, `(-1)^((p^i - 1) / 2)`. Built once from [Fp2::forbenius_coefficients] and read by `forbenius_map` and the forbenius map gadgets.
+pub static FP2_FORBENIUS_COEFFICIENTS: LazyLock<[Fp; 2]> = LazyLock::new(Fp2::forbenius_coefficients);
+
+/// Frobenius coefficients of the `c1` component of fp6 indexed by the power modulo 6, `(1 + u)^((p^i - 1) / 3)`.
+pub static FP6_FORBENIUS_COEFFICIENTS_1: LazyLock<[Fp2; 6]> = LazyLock::new(Fp6::forbenius_coefficients_1);
+
+/// Frobenius coefficients of the `c2` component of fp6 indexed by the power modulo 6, `(1 + u)^(2(p^i - 1) / 3)`.
+pub static FP6_FORBENIUS_COEFFICIENTS_2: LazyLock<[Fp2; 6]> = LazyLock::new(Fp6::forbenius_coefficients_2);
+
+/// Frobenius coefficients of the `c1` component of fp12 indexed by the power modulo 12, `(1 + u)^((p^i - 1) / 6)`.
+pub static FP12_FORBENIUS_COEFFICIENTS: LazyLock<[Fp2; 12]> = LazyLock::new(Fp12::forbenius_coefficients);
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::g1_scalar_mul::G1ScalarMulStark;
+    use crate::native::{modulus, Fp, Fp12, Fp2, Fp6, G1Affine};
+
+    use super::{FP12_FORBENIUS_COEFFICIENTS, FP2_FORBENIUS_COEFFICIENTS, FP6_FORBENIUS_COEFFICIENTS_1, FP6_FORBENIUS_COEFFICIENTS_2, G1_GENERATOR, G2_GENERATOR, GROUP_ORDER_R};
+
+    #[test]
+    fn test_generators() {
+        assert!(G1_GENERATOR.is_on_curve() && G1_GENERATOR.is_in_subgroup());
+        assert!(G2_GENERATOR.is_on_curve());
+        assert_eq!(G1_GENERATOR.x.to_biguint().to_string(), "3685416753713387016781088315183077757961620795782546409894578378688607592378376318836054947676345821548104185464507");
+        assert_eq!(G2_GENERATOR.y.0[1].to_biguint().to_string(), "927553665492332455747201965776037880757740193453592970025027978793976877002675564980949289727957565575433344219582");
+        assert_eq!(GROUP_ORDER_R.to_string(), "52435875175126190479447740508185965837690552500527637822603658699938581184513");
+    }
+
+    #[test]
+    fn test_forbenius_coefficients_cache() {
+        assert_eq!(*FP2_FORBENIUS_COEFFICIENTS, Fp2::forbenius_coefficients());
+        assert_eq!(*FP6_FORBENIUS_COEFFICIENTS_1, Fp6::forbenius_coefficients_1());
+        assert_eq!(*FP6_FORBENIUS_COEFFICIENTS_2, Fp6::forbenius_coefficients_2());
+        assert_eq!(*FP12_FORBENIUS_COEFFICIENTS, Fp12::forbenius_coefficients());
+        // Against the definitions, as powers of the non residue 1 + u.
+        let xi = Fp2([Fp::one(), Fp::one()]);
+        for i in 0..12 {
+            let e = modulus().pow(i as u32) - 1u32;
+            assert_eq!(FP12_FORBENIUS_COEFFICIENTS[i].to_biguint(), xi.pow(&(&e / 6u32)).to_biguint());
+            if i < 6 {
+                assert_eq!(FP6_FORBENIUS_COEFFICIENTS_1[i].to_biguint(), xi.pow(&(&e / 3u32)).to_biguint());
+                assert_eq!(FP6_FORBENIUS_COEFFICIENTS_2[i].to_biguint(), xi.pow(&(&e * 2u32 / 3u32)).to_biguint());
+            }
+            if i < 2 {
+                let minus_one = Fp::get_fp_from_biguint(modulus() - 1u32);
+                assert_eq!(FP2_FORBENIUS_COEFFICIENTS[i], if i == 0 { Fp::one() } else { minus_one });
+            }
+        }
+    }
+
+    #[test]
+    fn test_group_order_times_g1_generator() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = G1ScalarMulStark<F, D>;
+
+        let res = G1_GENERATOR.mul_scalar(&GROUP_ORDER_R);
+        assert_eq!(res, G1Affine::identity());
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(4096);
+        let public_inputs = S::public_inputs(&G1_GENERATOR, &GROUP_ORDER_R, &res);
+        let trace = stark.generate_trace(&G1_GENERATOR, &GROUP_ORDER_R);
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+    }
+}
@@ -0,0 +1,137 @@
+use plonky2::{
+    field::{
+        extension::Extendable,
+        polynomial::PolynomialValues,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    util::log2_ceil,
+};
+use starky::{
+    constraint_consumer::ConstraintConsumer,
+    evaluation_frame::StarkEvaluationFrame,
+    stark::Stark,
+};
+
+/*
+    Row by row check of the constraints of a stark over its trace, in the base field, before proving. Only compiled with the `debug-constraints` feature.
+    The constraint consumer only exposes the constraints combined with powers of alpha, i.e. for a row with constraints c_0, ..., c_{n-1} the accumulator is A(alpha) = c_0 * alpha^(n-1) + ... + c_{n-1}.
+    A failing row is found by evaluating A at a single alpha. For that row:
+    * The number of constraints n is found by emitting a constraint 1 before the stark's, which adds alpha^n to the accumulator, and taking the discrete log of alpha^n. Alpha is the primitive root of unity of order 2^TWO_ADICITY, so the discrete log is found bit by bit.
+    * A is evaluated at the 2^log2_ceil(n) roots of unity, and its coefficients (the constraints) are recovered with an inverse fft.
+*/
+
+/// Evaluates the constraints of `stark` at `row` of `trace` with the given `alphas`, and returns the accumulators. The next row of the last row is the first row. If `sentinel` is set, a constraint 1 is emitted before the stark's constraints.
+fn eval_row<F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+    const D: usize,
+    const C: usize,
+>(stark: &S, trace: &[[F; C]], public_inputs: &[F], row: usize, alphas: Vec<F>, sentinel: bool) -> Vec<F> {
+    let num_rows = trace.len();
+    let frame = <S::EvaluationFrame<F, F, 1> as StarkEvaluationFrame<F, F>>::from_values(
+        &trace[row],
+        &trace[(row + 1) % num_rows],
+        public_inputs,
+    );
+    let indicator = |b: bool| if b { F::ONE } else { F::ZERO };
+    let mut yield_constr = ConstraintConsumer::new(
+        alphas,
+        indicator(row != num_rows - 1),
+        indicator(row == 0),
+        indicator(row == num_rows - 1),
+    );
+    if sentinel {
+        yield_constr.constraint(F::ONE);
+    }
+    stark.eval_packed_generic::<F, F, 1>(&frame, &mut yield_constr);
+    yield_constr.accumulators()
+}
+
+/// Returns `e` such that `omega^e = h`, where `omega` is the primitive root of unity of order 2^TWO_ADICITY.
+fn discrete_log_two_adic<F: Field>(h: F) -> u64 {
+    let omega = F::primitive_root_of_unity(F::TWO_ADICITY);
+    let mut e = 0u64;
+    for bit in 0..F::TWO_ADICITY {
+        // h * omega^(-e) = omega^(m) with 2^bit dividing m, the bit is set iff it is not a 2^(TWO_ADICITY - bit - 1)-th root of unity
+        let t = (h * omega.exp_u64(e).inverse()).exp_power_of_2(F::TWO_ADICITY - bit - 1);
+        if t != F::ONE {
+            e |= 1 << bit;
+        }
+    }
+    e
+}
+
+/// Evaluates the constraints of `stark` row by row over `trace` in the base field, and returns the first `(row, constraint_index)` where a constraint is nonzero, or `None` if the trace satisfies all the constraints. `constraint_index` is the position of the constraint in the order it is emitted by `eval_packed_generic`, counting the constraints of all types (transition, first row, last row) and including those which are zero on the row.
+pub fn debug_check_trace<F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+    const D: usize,
+    const C: usize,
+>(stark: &S, trace: &[[F; C]], public_inputs: &[F]) -> Option<(usize, usize)> {
+    let omega = F::primitive_root_of_unity(F::TWO_ADICITY);
+    for row in 0..trace.len() {
+        let acc = eval_row(stark, trace, public_inputs, row, vec![omega], false)[0];
+        if acc == F::ZERO {
+            continue;
+        }
+        let acc_with_sentinel = eval_row(stark, trace, public_inputs, row, vec![omega], true)[0];
+        let num_constraints = discrete_log_two_adic(acc_with_sentinel - acc) as usize;
+        let alphas = F::two_adic_subgroup(log2_ceil(num_constraints));
+        let values = eval_row(stark, trace, public_inputs, row, alphas, false);
+        let coeffs = PolynomialValues::new(values).ifft().coeffs;
+        let degree = coeffs.iter().rposition(|c| *c != F::ZERO).unwrap();
+        return Some((row, num_constraints - 1 - degree));
+    }
+    None
+}
+
+/// Panics with the first `(row, constraint_index)` found by [debug_check_trace] if `trace` does not satisfy the constraints of `stark`.
+pub fn assert_trace_satisfies<F: RichField + Extendable<D>,
+    S: Stark<F, D>,
+    const D: usize,
+    const C: usize,
+>(stark: &S, trace: &[[F; C]], public_inputs: &[F]) {
+    if let Some((row, constraint_index)) = debug_check_trace(stark, trace, public_inputs) {
+        panic!("{}: constraint {} is not satisfied at row {}", std::any::type_name::<S>(), constraint_index, row);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{field::types::Field, plonk::config::{GenericConfig, PoseidonGoldilocksConfig}};
+
+    use crate::fp2::FP2_FORBENIUS_MAP_INPUT_OFFSET;
+    use crate::fp2_frobenius::{Fp2FrobeniusStark, FP2_FROBENIUS_MAP_OFFSET, TOTAL_COLUMNS};
+    use crate::native::{Fp, Fp2};
+
+    use super::{debug_check_trace, discrete_log_two_adic};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Fp2FrobeniusStark<F, D>;
+
+    #[test]
+    fn test_discrete_log_two_adic() {
+        let omega = F::primitive_root_of_unity(F::TWO_ADICITY);
+        for e in [0u64, 1, 2, 5, 1000, 123456, (1 << 32) - 1] {
+            assert_eq!(discrete_log_two_adic(omega.exp_u64(e)), e);
+        }
+    }
+
+    #[test]
+    fn test_debug_check_trace_pinpoints_corrupted_limb() {
+        let x = Fp2([
+            Fp::get_fp_from_biguint(BigUint::parse_bytes(b"17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb", 16).unwrap()),
+            Fp::get_fp_from_biguint(BigUint::parse_bytes(b"08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1", 16).unwrap()),
+        ]);
+        let stark = S::new(16);
+        let public_inputs = S::public_inputs(&x, 1, &x.forbenius_map(1));
+        let mut trace = stark.generate_trace(&x, 1);
+        assert_eq!(debug_check_trace::<F, S, D, TOTAL_COLUMNS>(&stark, &trace, &public_inputs), None);
+
+        // The first row constraints are emitted first: the two selectors, then the input limbs against the public inputs
+        trace[0][FP2_FROBENIUS_MAP_OFFSET + FP2_FORBENIUS_MAP_INPUT_OFFSET + 3] += F::ONE;
+        assert_eq!(debug_check_trace::<F, S, D, TOTAL_COLUMNS>(&stark, &trace, &public_inputs), Some((0, 2 + 3)));
+    }
+}
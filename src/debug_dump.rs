@@ -0,0 +1,92 @@
+use std::{fs::File, io::{BufWriter, Write}, ops::Range, path::Path};
+
+use anyhow::{ensure, Result};
+use plonky2::field::types::PrimeField64;
+
+/*
+    CSV dump of trace columns, to inspect a trace in a spreadsheet, e.g. around the constraint reported by `debug_check_trace` of the `debug-constraints` feature. Only compiled with the `debug-dump` feature.
+    The header names each column after the named offset at or before it, e.g. `FP2_FROBENIUS_MAP_OFFSET[3]` for the fourth column after `FP2_FROBENIUS_MAP_OFFSET`. The named offsets are listed with the `trace_offsets!` macro, which takes the names from the offset expressions.
+*/
+
+/// Lists the given column offsets along with their names, as taken by [dump_trace_csv]. The offsets can be any expressions, e.g. `trace_offsets![FP2_FROBENIUS_MAP_OFFSET, FP2_FROBENIUS_MAP_OFFSET + FP2_FORBENIUS_MAP_INPUT_OFFSET]`.
+#[macro_export]
+macro_rules! trace_offsets {
+    ($($offset:expr),* $(,)?) => {
+        [$((stringify!($offset), $offset)),*]
+    };
+}
+
+/// Name of column `col`, after the greatest of the named `offsets` which is not more than `col`. Columns before all the offsets are named `col[i]`.
+fn column_name(col: usize, offsets: &[(&str, usize)]) -> String {
+    match offsets.iter().filter(|(_, offset)| *offset <= col).max_by_key(|(_, offset)| *offset) {
+        Some((name, offset)) => format!("{}[{}]", name.replace(' ', ""), col - offset),
+        None => format!("col[{}]", col),
+    }
+}
+
+/// Writes the columns in `column_range` of every row of `trace` to a CSV file at `path`. The first column is the row number, and the header names the trace columns after `offsets` (see [trace_offsets]). The values are written as canonical u64s.
+/// Returns an error if `column_range` is not within the trace columns, or if the file can not be written.
+pub fn dump_trace_csv<F: PrimeField64, const C: usize>(
+    trace: &[[F; C]],
+    path: impl AsRef<Path>,
+    column_range: Range<usize>,
+    offsets: &[(&str, usize)],
+) -> Result<()> {
+    ensure!(column_range.start <= column_range.end && column_range.end <= C, "dump_trace_csv: columns {:?} are not within the {} columns of the trace", column_range, C);
+    let mut w = BufWriter::new(File::create(path)?);
+    let header = column_range.clone().map(|col| column_name(col, offsets));
+    writeln!(w, "{}", std::iter::once("row".to_string()).chain(header).collect::<Vec<String>>().join(","))?;
+    for (i, row) in trace.iter().enumerate() {
+        let values = row[column_range.clone()].iter().map(|v| v.to_canonical_u64().to_string());
+        writeln!(w, "{}", std::iter::once(i.to_string()).chain(values).collect::<Vec<String>>().join(","))?;
+    }
+    w.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{field::types::PrimeField64, plonk::config::{GenericConfig, PoseidonGoldilocksConfig}};
+
+    use crate::fp2::{FP2_FORBENIUS_MAP_INPUT_OFFSET, FP2_FORBENIUS_MAP_POW_OFFSET};
+    use crate::fp2_frobenius::{Fp2FrobeniusStark, FP2_FROBENIUS_MAP_OFFSET, TOTAL_COLUMNS};
+    use crate::native::{Fp, Fp2};
+
+    use super::{column_name, dump_trace_csv};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Fp2FrobeniusStark<F, D>;
+
+    #[test]
+    fn test_column_name() {
+        let offsets = trace_offsets![FP2_FROBENIUS_MAP_OFFSET + FP2_FORBENIUS_MAP_INPUT_OFFSET, FP2_FROBENIUS_MAP_OFFSET + FP2_FORBENIUS_MAP_POW_OFFSET];
+        assert_eq!(column_name(0, &offsets), "col[0]");
+        assert_eq!(column_name(FP2_FORBENIUS_MAP_INPUT_OFFSET + 3, &offsets), "FP2_FROBENIUS_MAP_OFFSET+FP2_FORBENIUS_MAP_INPUT_OFFSET[3]");
+        assert_eq!(column_name(FP2_FORBENIUS_MAP_POW_OFFSET, &offsets), "FP2_FROBENIUS_MAP_OFFSET+FP2_FORBENIUS_MAP_POW_OFFSET[0]");
+    }
+
+    #[test]
+    fn test_dump_trace_csv() {
+        let x = Fp2([Fp::get_fp_from_biguint(BigUint::from(3u32)), Fp::get_fp_from_biguint(BigUint::from(5u32))]);
+        let stark = S::new(16);
+        let trace = stark.generate_trace(&x, 1);
+        let path = std::env::temp_dir().join(format!("dump_trace_csv_{}.csv", std::process::id()));
+        let offsets = trace_offsets![FP2_FROBENIUS_MAP_OFFSET, FP2_FROBENIUS_MAP_OFFSET + FP2_FORBENIUS_MAP_INPUT_OFFSET];
+        let cols = FP2_FORBENIUS_MAP_INPUT_OFFSET - 1..FP2_FORBENIUS_MAP_INPUT_OFFSET + 2;
+        dump_trace_csv(&trace, &path, cols.clone(), &offsets).unwrap();
+        let csv = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        let lines = csv.lines().collect::<Vec<&str>>();
+        assert_eq!(lines.len(), 1 + 16);
+        assert_eq!(lines[0], "row,FP2_FROBENIUS_MAP_OFFSET[0],FP2_FROBENIUS_MAP_OFFSET+FP2_FORBENIUS_MAP_INPUT_OFFSET[0],FP2_FROBENIUS_MAP_OFFSET+FP2_FORBENIUS_MAP_INPUT_OFFSET[1]");
+        let first = trace[0][cols].iter().map(|v| v.to_canonical_u64().to_string()).collect::<Vec<String>>().join(",");
+        assert_eq!(lines[1], format!("0,{}", first));
+        assert!(lines[1..].iter().enumerate().all(|(i, line)| line.starts_with(&format!("{},", i)) && line.split(',').count() == 4));
+
+        assert!(dump_trace_csv(&trace, &path, TOTAL_COLUMNS - 1..TOTAL_COLUMNS + 1, &offsets).is_err());
+    }
+}
@@ -2,18 +2,31 @@ use plonky2::{
     field::{
         extension::{Extendable, FieldExtension},
         packed::PackedField,
-        types::Field,
+        types::{Field, PrimeField64},
     },
     hash::hash_types::RichField,
     iop::ext_target::ExtensionTarget,
+    plonk::config::GenericConfig,
+    util::timing::TimingTree,
 };
 use starky::{
+    config::StarkConfig,
     constraint_consumer::ConstraintConsumer,
     evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    proof::{StarkProof, StarkProofWithPublicInputs},
+    prover::prove,
     stark::Stark,
+    verifier::verify_stark_proof,
 };
 
-use crate::native::Fp12;
+use std::ops::Range;
+
+use anyhow::{bail, Result};
+
+use crate::config::check_field;
+#[cfg(feature = "debug-constraints")]
+use crate::debug_constraints::assert_trace_satisfies;
+use crate::native::{get_bits_as_array, Fp12, BLS_12_381_PARAMETER};
 
 use crate::fp::*;
 use crate::fp2::*;
@@ -23,57 +36,92 @@ use crate::utils::*;
 
 /*
     These offsets are for final_exponentiation function (super::native::Fp12::final_exponentiate). This stark needs 8192 rows. The Ti's are defined in the function definition.
-    FINAL_EXP_ROW_SELECTORS -> 8192 selectors set 1 for the corresponding row number.
+    FINAL_EXP_ROW_BITS_OFFSET -> 13 bits of the row number, least significant first.
+    FINAL_EXP_ROW_LOW_SELECTORS -> 128 selectors, the one for the 7 low bits of the row number set 1.
+    FINAL_EXP_ROW_HIGH_SELECTORS -> 64 selectors, the one for the 6 high bits of the row number set 1.
+    The product of a high and a low selector is 1 only in the corresponding row, see [final_exp_row_selector].
     FINAL_EXP_FORBENIUS_MAP_SELECTOR -> selector set 1 when the operation is Fp12 forbenius map.
     FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR -> selector set 1 when the operation is cyclotomicExponent.
     FINAL_EXP_MUL_SELECTOR -> selector set 1 when the operation is fp12 multiplication.
     FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR -> selector set 1 when the operation is Fp12 forbenius map.
     FINAL_EXP_CONJUGATE_SELECTOR -> selector set 1 when the operation is fp12 conjugate.
     FINAL_EXP_INPUT_OFFSET -> offset where input of the function is set.
-    FINAL_EXP_T${i}_OFFSET -> offset where the Ti's are set.
+    FINAL_EXP_SLOT${i}_OFFSET -> 12*12 columns shared by the Ti's that are not live at the same time (see the liveness analysis below).
+    FINAL_EXP_T${i}_OFFSET -> offset where the Ti's are set, which is the slot assigned to Ti.
+    FINAL_EXP_INPUT_INV_OFFSET -> offset where the inverse of the input is set. Used to prove that the input is invertible, since T1 divides by the input. It is also assigned a slot.
     FINAL_EXP_OP_OFFSET -> offset where the stark trace of an operation is filled.
+    The offsets are relative to the `start_col` passed to the trace and constraint functions, so the gadget can be placed at any column of a larger trace. It needs `FINAL_EXP_TOTAL_COLUMNS` columns from `start_col`.
 */
 
-pub const FINAL_EXP_ROW_SELECTORS: usize = 0;
-pub const FINAL_EXP_FORBENIUS_MAP_SELECTOR: usize = FINAL_EXP_ROW_SELECTORS + 8192;
+// Number of bits of the row counter, which bounds the number of rows of the stark. The low bits select one of the low selectors and the others one of the high selectors.
+pub const FINAL_EXP_ROW_BITS: usize = 13;
+pub const FINAL_EXP_ROW_LOW_BITS: usize = 7;
+pub const FINAL_EXP_MAX_ROWS: usize = 1 << FINAL_EXP_ROW_BITS;
+pub const FINAL_EXP_ROW_LOW_SELECTORS_TOTAL: usize = 1 << FINAL_EXP_ROW_LOW_BITS;
+pub const FINAL_EXP_ROW_HIGH_SELECTORS_TOTAL: usize = 1 << (FINAL_EXP_ROW_BITS - FINAL_EXP_ROW_LOW_BITS);
+
+pub const FINAL_EXP_ROW_BITS_OFFSET: usize = 0;
+pub const FINAL_EXP_ROW_LOW_SELECTORS: usize = FINAL_EXP_ROW_BITS_OFFSET + FINAL_EXP_ROW_BITS;
+pub const FINAL_EXP_ROW_HIGH_SELECTORS: usize = FINAL_EXP_ROW_LOW_SELECTORS + FINAL_EXP_ROW_LOW_SELECTORS_TOTAL;
+pub const FINAL_EXP_FORBENIUS_MAP_SELECTOR: usize = FINAL_EXP_ROW_HIGH_SELECTORS + FINAL_EXP_ROW_HIGH_SELECTORS_TOTAL;
 pub const FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR: usize = FINAL_EXP_FORBENIUS_MAP_SELECTOR + 1;
 pub const FINAL_EXP_MUL_SELECTOR: usize = FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR + 1;
 pub const FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR: usize = FINAL_EXP_MUL_SELECTOR + 1;
 pub const FINAL_EXP_CONJUGATE_SELECTOR: usize = FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR + 1;
 pub const FINAL_EXP_INPUT_OFFSET: usize = FINAL_EXP_CONJUGATE_SELECTOR + 1;
-pub const FINAL_EXP_T0_OFFSET: usize = FINAL_EXP_INPUT_OFFSET + 12*12;
-pub const FINAL_EXP_T1_OFFSET: usize = FINAL_EXP_T0_OFFSET + 12*12;
-pub const FINAL_EXP_T2_OFFSET: usize = FINAL_EXP_T1_OFFSET + 12*12;
-pub const FINAL_EXP_T3_OFFSET: usize = FINAL_EXP_T2_OFFSET + 12*12;
-pub const FINAL_EXP_T4_OFFSET: usize = FINAL_EXP_T3_OFFSET + 12*12;
-pub const FINAL_EXP_T5_OFFSET: usize = FINAL_EXP_T4_OFFSET + 12*12;
-pub const FINAL_EXP_T6_OFFSET: usize = FINAL_EXP_T5_OFFSET + 12*12;
-pub const FINAL_EXP_T7_OFFSET: usize = FINAL_EXP_T6_OFFSET + 12*12;
-pub const FINAL_EXP_T8_OFFSET: usize = FINAL_EXP_T7_OFFSET + 12*12;
-pub const FINAL_EXP_T9_OFFSET: usize = FINAL_EXP_T8_OFFSET + 12*12;
-pub const FINAL_EXP_T10_OFFSET: usize = FINAL_EXP_T9_OFFSET + 12*12;
-pub const FINAL_EXP_T11_OFFSET: usize = FINAL_EXP_T10_OFFSET + 12*12;
-pub const FINAL_EXP_T12_OFFSET: usize = FINAL_EXP_T11_OFFSET + 12*12;
-pub const FINAL_EXP_T13_OFFSET: usize = FINAL_EXP_T12_OFFSET + 12*12;
-pub const FINAL_EXP_T14_OFFSET: usize = FINAL_EXP_T13_OFFSET + 12*12;
-pub const FINAL_EXP_T15_OFFSET: usize = FINAL_EXP_T14_OFFSET + 12*12;
-pub const FINAL_EXP_T16_OFFSET: usize = FINAL_EXP_T15_OFFSET + 12*12;
-pub const FINAL_EXP_T17_OFFSET: usize = FINAL_EXP_T16_OFFSET + 12*12;
-pub const FINAL_EXP_T18_OFFSET: usize = FINAL_EXP_T17_OFFSET + 12*12;
-pub const FINAL_EXP_T19_OFFSET: usize = FINAL_EXP_T18_OFFSET + 12*12;
-pub const FINAL_EXP_T20_OFFSET: usize = FINAL_EXP_T19_OFFSET + 12*12;
-pub const FINAL_EXP_T21_OFFSET: usize = FINAL_EXP_T20_OFFSET + 12*12;
-pub const FINAL_EXP_T22_OFFSET: usize = FINAL_EXP_T21_OFFSET + 12*12;
-pub const FINAL_EXP_T23_OFFSET: usize = FINAL_EXP_T22_OFFSET + 12*12;
-pub const FINAL_EXP_T24_OFFSET: usize = FINAL_EXP_T23_OFFSET + 12*12;
-pub const FINAL_EXP_T25_OFFSET: usize = FINAL_EXP_T24_OFFSET + 12*12;
-pub const FINAL_EXP_T26_OFFSET: usize = FINAL_EXP_T25_OFFSET + 12*12;
-pub const FINAL_EXP_T27_OFFSET: usize = FINAL_EXP_T26_OFFSET + 12*12;
-pub const FINAL_EXP_T28_OFFSET: usize = FINAL_EXP_T27_OFFSET + 12*12;
-pub const FINAL_EXP_T29_OFFSET: usize = FINAL_EXP_T28_OFFSET + 12*12;
-pub const FINAL_EXP_T30_OFFSET: usize = FINAL_EXP_T29_OFFSET + 12*12;
-pub const FINAL_EXP_T31_OFFSET: usize = FINAL_EXP_T30_OFFSET + 12*12;
-pub const FINAL_EXP_OP_OFFSET: usize = FINAL_EXP_T31_OFFSET + 12*12;
+/*
+    Liveness of the Ti's. Each Ti is set from the row where the operation computing it sets its output, to the row of the last operation taking it as input (see [final_exp_live_rows]). Ti's whose live rows are disjoint share the same 12*12 slot, which is assigned greedily in the order of the first live row:
+    slot 0 -> T0 (0..=12), T4 (888..=889), T6 (890..=902), T8 (903..=4356), T27 (4357..=4369), T21 (4381..=4393), T29 (4405..=4417), T31 (4429..)
+    slot 1 -> T1 (12..=36), T5 (889..=4307), T20 (4319..=4405), T30 (4417..=4429), input inverse (4441)
+    slot 2 -> T3 (36..=4381)
+    slot 3 -> T7 (902..=903), T9 (1755..=1756), T11 (2597..=2598), T13 (3439..=3440), T15 (3441..=3453), T17 (4305..=4306), T19 (4307..=4319), T23 (4331..=4332), T25 (4344..=4417)
+    slot 4 -> T10 (1756..=4381)
+    slot 5 -> T12 (2598..=4307), T24 (4332..=4344), T26 (4356..=4357), T28 (4369..=4429)
+    slot 6 -> T14 (3440..=3453), T18 (4306..=4357)
+    slot 7 -> T16 (3453..=4332)
+    T2 and T22 have no slot, as the multiplications computing T3 and T29 take them from the columns of the forbenius maps computing them (see add_constraints_forbenius_mul).
+    At most 8 Ti's are live in the same row (e.g. T3, T5, T8, T10, T12, T14, T15 and T16 at row 3453), so 8 slots are needed.
+*/
+pub const FINAL_EXP_SLOT0_OFFSET: usize = FINAL_EXP_INPUT_OFFSET + 12*12;
+pub const FINAL_EXP_SLOT1_OFFSET: usize = FINAL_EXP_SLOT0_OFFSET + 12*12;
+pub const FINAL_EXP_SLOT2_OFFSET: usize = FINAL_EXP_SLOT1_OFFSET + 12*12;
+pub const FINAL_EXP_SLOT3_OFFSET: usize = FINAL_EXP_SLOT2_OFFSET + 12*12;
+pub const FINAL_EXP_SLOT4_OFFSET: usize = FINAL_EXP_SLOT3_OFFSET + 12*12;
+pub const FINAL_EXP_SLOT5_OFFSET: usize = FINAL_EXP_SLOT4_OFFSET + 12*12;
+pub const FINAL_EXP_SLOT6_OFFSET: usize = FINAL_EXP_SLOT5_OFFSET + 12*12;
+pub const FINAL_EXP_SLOT7_OFFSET: usize = FINAL_EXP_SLOT6_OFFSET + 12*12;
+pub const FINAL_EXP_T0_OFFSET: usize = FINAL_EXP_SLOT0_OFFSET;
+pub const FINAL_EXP_T1_OFFSET: usize = FINAL_EXP_SLOT1_OFFSET;
+pub const FINAL_EXP_T3_OFFSET: usize = FINAL_EXP_SLOT2_OFFSET;
+pub const FINAL_EXP_T4_OFFSET: usize = FINAL_EXP_SLOT0_OFFSET;
+pub const FINAL_EXP_T5_OFFSET: usize = FINAL_EXP_SLOT1_OFFSET;
+pub const FINAL_EXP_T6_OFFSET: usize = FINAL_EXP_SLOT0_OFFSET;
+pub const FINAL_EXP_T7_OFFSET: usize = FINAL_EXP_SLOT3_OFFSET;
+pub const FINAL_EXP_T8_OFFSET: usize = FINAL_EXP_SLOT0_OFFSET;
+pub const FINAL_EXP_T9_OFFSET: usize = FINAL_EXP_SLOT3_OFFSET;
+pub const FINAL_EXP_T10_OFFSET: usize = FINAL_EXP_SLOT4_OFFSET;
+pub const FINAL_EXP_T11_OFFSET: usize = FINAL_EXP_SLOT3_OFFSET;
+pub const FINAL_EXP_T12_OFFSET: usize = FINAL_EXP_SLOT5_OFFSET;
+pub const FINAL_EXP_T13_OFFSET: usize = FINAL_EXP_SLOT3_OFFSET;
+pub const FINAL_EXP_T14_OFFSET: usize = FINAL_EXP_SLOT6_OFFSET;
+pub const FINAL_EXP_T15_OFFSET: usize = FINAL_EXP_SLOT3_OFFSET;
+pub const FINAL_EXP_T16_OFFSET: usize = FINAL_EXP_SLOT7_OFFSET;
+pub const FINAL_EXP_T17_OFFSET: usize = FINAL_EXP_SLOT3_OFFSET;
+pub const FINAL_EXP_T18_OFFSET: usize = FINAL_EXP_SLOT6_OFFSET;
+pub const FINAL_EXP_T19_OFFSET: usize = FINAL_EXP_SLOT3_OFFSET;
+pub const FINAL_EXP_T20_OFFSET: usize = FINAL_EXP_SLOT1_OFFSET;
+pub const FINAL_EXP_T21_OFFSET: usize = FINAL_EXP_SLOT0_OFFSET;
+pub const FINAL_EXP_T23_OFFSET: usize = FINAL_EXP_SLOT3_OFFSET;
+pub const FINAL_EXP_T24_OFFSET: usize = FINAL_EXP_SLOT5_OFFSET;
+pub const FINAL_EXP_T25_OFFSET: usize = FINAL_EXP_SLOT3_OFFSET;
+pub const FINAL_EXP_T26_OFFSET: usize = FINAL_EXP_SLOT5_OFFSET;
+pub const FINAL_EXP_T27_OFFSET: usize = FINAL_EXP_SLOT0_OFFSET;
+pub const FINAL_EXP_T28_OFFSET: usize = FINAL_EXP_SLOT5_OFFSET;
+pub const FINAL_EXP_T29_OFFSET: usize = FINAL_EXP_SLOT0_OFFSET;
+pub const FINAL_EXP_T30_OFFSET: usize = FINAL_EXP_SLOT1_OFFSET;
+pub const FINAL_EXP_T31_OFFSET: usize = FINAL_EXP_SLOT0_OFFSET;
+pub const FINAL_EXP_INPUT_INV_OFFSET: usize = FINAL_EXP_SLOT1_OFFSET;
+pub const FINAL_EXP_OP_OFFSET: usize = FINAL_EXP_SLOT7_OFFSET + 12*12;
 pub const FINAL_EXP_TOTAL_COLUMNS: usize = FINAL_EXP_OP_OFFSET + CYCLOTOMIC_EXP_TOTAL_COLUMNS;
 
 // Number of rows required for each operation
@@ -81,7 +129,7 @@ pub const FP12_MUL_ROWS: usize = 12;
 pub const FP12_FORBENIUS_MAP_ROWS: usize = 12;
 pub const CYCLOTOMIC_SQ_ROWS: usize = 12;
 pub const CONJUGATE_ROWS: usize = 1;
-pub const CYCLOTOMIC_EXP_ROWS: usize = 70*12 + 1;
+pub const CYCLOTOMIC_EXP_ROWS: usize = cyclotomic_exp_rows(BLS_12_381_PARAMETER);
 
 // Row number where the operation for computing Ti starts.
 pub const T0_ROW: usize = 0;
@@ -105,20 +153,79 @@ pub const T17_ROW: usize = T16_ROW + FP12_MUL_ROWS;
 pub const T18_ROW: usize = T17_ROW + CYCLOTOMIC_EXP_ROWS;
 pub const T19_ROW: usize = T18_ROW + CONJUGATE_ROWS;
 pub const T20_ROW: usize = T19_ROW + FP12_MUL_ROWS;
-pub const T21_ROW: usize = T20_ROW + FP12_FORBENIUS_MAP_ROWS;
-pub const T22_ROW: usize = T21_ROW + FP12_MUL_ROWS;
-pub const T23_ROW: usize = T22_ROW + FP12_FORBENIUS_MAP_ROWS;
+pub const T23_ROW: usize = T20_ROW + FP12_FORBENIUS_MAP_ROWS;
 pub const T24_ROW: usize = T23_ROW + CONJUGATE_ROWS;
 pub const T25_ROW: usize = T24_ROW + FP12_MUL_ROWS;
 pub const T26_ROW: usize = T25_ROW + FP12_FORBENIUS_MAP_ROWS;
 pub const T27_ROW: usize = T26_ROW + CONJUGATE_ROWS;
 pub const T28_ROW: usize = T27_ROW + FP12_MUL_ROWS;
-pub const T29_ROW: usize = T28_ROW + FP12_MUL_ROWS;
+// T21 and T22 are computed right before T29, which multiplies T22 by T20 in the rows following its forbenius map (see add_constraints_forbenius_mul).
+pub const T21_ROW: usize = T28_ROW + FP12_MUL_ROWS;
+pub const T22_ROW: usize = T21_ROW + FP12_MUL_ROWS;
+pub const T29_ROW: usize = T22_ROW + FP12_FORBENIUS_MAP_ROWS;
 pub const T30_ROW: usize = T29_ROW + FP12_MUL_ROWS;
 pub const T31_ROW: usize = T30_ROW + FP12_MUL_ROWS;
-pub const TOTAL_ROW: usize = T31_ROW + FP12_MUL_ROWS;
+pub const INPUT_INV_ROW: usize = T31_ROW + FP12_MUL_ROWS;
+pub const TOTAL_ROW: usize = INPUT_INV_ROW + FP12_MUL_ROWS;
+// Every row of the trace needs its own row number.
+const _: () = assert!(TOTAL_ROW <= FINAL_EXP_MAX_ROWS, "TOTAL_ROW does not fit in the FINAL_EXP_ROW_BITS bits of the row counter");
+
+/// Rows of the final exponentiation trace, as (operation, first row, number of rows) for every Ti in order, followed by the multiplication `x * x^-1` which checks the inverse of the input. The operations take the rows `0..TOTAL_ROW` one after the other. The five cyclotomic exponents take `5 * CYCLOTOMIC_EXP_ROWS` of them.
+pub fn final_exp_row_budget() -> Vec<(String, usize, usize)> {
+    let ops: [(&str, usize, usize); 33] = [
+        ("T0 forbenius_map", T0_ROW, FP12_FORBENIUS_MAP_ROWS),
+        ("T1 div", T1_ROW, FP12_MUL_ROWS),
+        ("T2 forbenius_map", T2_ROW, FP12_FORBENIUS_MAP_ROWS),
+        ("T3 mul", T3_ROW, FP12_MUL_ROWS),
+        ("T4 cyclotomic_exp", T4_ROW, CYCLOTOMIC_EXP_ROWS),
+        ("T5 conjugate", T5_ROW, CONJUGATE_ROWS),
+        ("T6 cyclotomic_square", T6_ROW, CYCLOTOMIC_SQ_ROWS),
+        ("T7 conjugate", T7_ROW, CONJUGATE_ROWS),
+        ("T8 mul", T8_ROW, FP12_MUL_ROWS),
+        ("T9 cyclotomic_exp", T9_ROW, CYCLOTOMIC_EXP_ROWS),
+        ("T10 conjugate", T10_ROW, CONJUGATE_ROWS),
+        ("T11 cyclotomic_exp", T11_ROW, CYCLOTOMIC_EXP_ROWS),
+        ("T12 conjugate", T12_ROW, CONJUGATE_ROWS),
+        ("T13 cyclotomic_exp", T13_ROW, CYCLOTOMIC_EXP_ROWS),
+        ("T14 conjugate", T14_ROW, CONJUGATE_ROWS),
+        ("T15 cyclotomic_square", T15_ROW, CYCLOTOMIC_SQ_ROWS),
+        ("T16 mul", T16_ROW, FP12_MUL_ROWS),
+        ("T17 cyclotomic_exp", T17_ROW, CYCLOTOMIC_EXP_ROWS),
+        ("T18 conjugate", T18_ROW, CONJUGATE_ROWS),
+        ("T19 mul", T19_ROW, FP12_MUL_ROWS),
+        ("T20 forbenius_map", T20_ROW, FP12_FORBENIUS_MAP_ROWS),
+        ("T23 conjugate", T23_ROW, CONJUGATE_ROWS),
+        ("T24 mul", T24_ROW, FP12_MUL_ROWS),
+        ("T25 forbenius_map", T25_ROW, FP12_FORBENIUS_MAP_ROWS),
+        ("T26 conjugate", T26_ROW, CONJUGATE_ROWS),
+        ("T27 mul", T27_ROW, FP12_MUL_ROWS),
+        ("T28 mul", T28_ROW, FP12_MUL_ROWS),
+        ("T21 mul", T21_ROW, FP12_MUL_ROWS),
+        ("T22 forbenius_map", T22_ROW, FP12_FORBENIUS_MAP_ROWS),
+        ("T29 mul", T29_ROW, FP12_MUL_ROWS),
+        ("T30 mul", T30_ROW, FP12_MUL_ROWS),
+        ("T31 mul", T31_ROW, FP12_MUL_ROWS),
+        ("input_inv mul", INPUT_INV_ROW, FP12_MUL_ROWS),
+    ];
+    ops.iter().map(|(op, row, rows)| (op.to_string(), *row, *rows)).collect()
+}
 
-pub const TOTAL_COLUMNS: usize = FINAL_EXP_TOTAL_COLUMNS;
+// Range checks of the public inputs
+/*
+    These trace offsets are for checking that the public inputs are canonical fp elements. Row i checks the i-th fp element of the public inputs, so it needs `PUBLIC_INPUTS / 12` rows.
+    PIS_RANGE_CHECK_BITS_OFFSET -> bit decomposition of the 12 limbs of the checked element, which proves that each limb is a u32.
+    PIS_RANGE_CHECK_INPUT_OFFSET -> the 12 limbs of the checked element, constrained with the public inputs.
+    PIS_RANGE_CHECK_OFFSET -> range check of the element wrt the field prime. The range check takes its input from the 12 columns before it.
+    PIS_RANGE_CHECK_SUM_BITS_OFFSET -> bit decomposition of the lower 11 limbs of the sum of the range check, the most significant limb being decomposed by the range check itself.
+*/
+pub const PIS_RANGE_CHECK_BITS_OFFSET: usize = 0;
+pub const PIS_RANGE_CHECK_INPUT_OFFSET: usize = PIS_RANGE_CHECK_BITS_OFFSET + 12*32;
+pub const PIS_RANGE_CHECK_OFFSET: usize = PIS_RANGE_CHECK_INPUT_OFFSET + 12;
+pub const PIS_RANGE_CHECK_SUM_BITS_OFFSET: usize = PIS_RANGE_CHECK_OFFSET + RANGE_CHECK_TOTAL;
+pub const PIS_RANGE_CHECK_TOTAL: usize = PIS_RANGE_CHECK_SUM_BITS_OFFSET + 11*32;
+
+pub const FINAL_EXP_PIS_RANGE_CHECK_OFFSET: usize = FINAL_EXP_TOTAL_COLUMNS;
+pub const TOTAL_COLUMNS: usize = FINAL_EXP_PIS_RANGE_CHECK_OFFSET + PIS_RANGE_CHECK_TOTAL;
 pub const COLUMNS: usize = TOTAL_COLUMNS;
 
 // Public inputs to this stark are the input and output of the function final_exponentiate.
@@ -126,160 +233,516 @@ pub const PIS_INPUT_OFFSET: usize = 0;
 pub const PIS_OUTPUT_OFFSET: usize = PIS_INPUT_OFFSET + 24*3*2;
 pub const PUBLIC_INPUTS: usize = PIS_OUTPUT_OFFSET + 24*3*2;
 
+/// Rows in which each Ti (except T2 and T22, which are not set outside the forbenius_mul operations computing T3 and T29) and the inverse of the input are set, as (column, first row, last row), for a trace of `num_rows` rows.
+/// The first row is where the value is constrained with the output of the operation computing it, and the last row is the last one where it is constrained with the input of another operation. T31 is kept till the last row, for the caller to tie it with public inputs.
+/// The values are constrained to be same in consecutive rows, except when entering the first row and leaving the last row. Ti's sharing a slot have disjoint live rows, and outside the live rows of all of them the columns of the slot are left zero.
+pub fn final_exp_live_rows(num_rows: usize) -> [(usize, usize, usize); 31] {
+    [
+        (FINAL_EXP_T0_OFFSET, T0_ROW, T1_ROW),
+        (FINAL_EXP_T1_OFFSET, T1_ROW, T3_ROW),
+        (FINAL_EXP_T3_OFFSET, T3_ROW, T21_ROW),
+        (FINAL_EXP_T4_OFFSET, T5_ROW - 1, T5_ROW),
+        (FINAL_EXP_T5_OFFSET, T5_ROW, T19_ROW),
+        (FINAL_EXP_T6_OFFSET, T6_ROW, T7_ROW),
+        (FINAL_EXP_T7_OFFSET, T7_ROW, T8_ROW),
+        (FINAL_EXP_T8_OFFSET, T8_ROW, T26_ROW),
+        (FINAL_EXP_T9_OFFSET, T10_ROW - 1, T10_ROW),
+        (FINAL_EXP_T10_OFFSET, T10_ROW, T21_ROW),
+        (FINAL_EXP_T11_OFFSET, T12_ROW - 1, T12_ROW),
+        (FINAL_EXP_T12_OFFSET, T12_ROW, T19_ROW),
+        (FINAL_EXP_T13_OFFSET, T14_ROW - 1, T14_ROW),
+        (FINAL_EXP_T14_OFFSET, T14_ROW, T16_ROW),
+        (FINAL_EXP_T15_OFFSET, T15_ROW, T16_ROW),
+        (FINAL_EXP_T16_OFFSET, T16_ROW, T24_ROW),
+        (FINAL_EXP_T17_OFFSET, T18_ROW - 1, T18_ROW),
+        (FINAL_EXP_T18_OFFSET, T18_ROW, T27_ROW),
+        (FINAL_EXP_T19_OFFSET, T19_ROW, T20_ROW),
+        (FINAL_EXP_T20_OFFSET, T20_ROW, T29_ROW),
+        (FINAL_EXP_T21_OFFSET, T21_ROW, T22_ROW),
+        (FINAL_EXP_T23_OFFSET, T23_ROW, T24_ROW),
+        (FINAL_EXP_T24_OFFSET, T24_ROW, T25_ROW),
+        (FINAL_EXP_T25_OFFSET, T25_ROW, T30_ROW),
+        (FINAL_EXP_T26_OFFSET, T26_ROW, T27_ROW),
+        (FINAL_EXP_T27_OFFSET, T27_ROW, T28_ROW),
+        (FINAL_EXP_T28_OFFSET, T28_ROW, T31_ROW),
+        (FINAL_EXP_T29_OFFSET, T29_ROW, T30_ROW),
+        (FINAL_EXP_T30_OFFSET, T30_ROW, T31_ROW),
+        (FINAL_EXP_T31_OFFSET, T31_ROW, num_rows - 1),
+        (FINAL_EXP_INPUT_INV_OFFSET, INPUT_INV_ROW, INPUT_INV_ROW),
+    ]
+}
+
+/// Rows of each slot (given by its column) after which the slot is not constrained to be same in the next row, i.e. the rows before the first live row and the last live rows of the Ti's set in the slot, for a trace of `num_rows` rows.
+fn final_exp_slot_boundaries(num_rows: usize) -> Vec<(usize, Vec<usize>)> {
+    let mut slots: Vec<(usize, Vec<usize>)> = vec![];
+    for (col, first_row, last_row) in final_exp_live_rows(num_rows) {
+        let i = match slots.iter().position(|(c, _)| *c == col) {
+            Some(i) => i,
+            None => {
+                slots.push((col, vec![]));
+                slots.len() - 1
+            }
+        };
+        if first_row > 0 {
+            slots[i].1.push(first_row - 1);
+        }
+        if last_row < num_rows - 1 {
+            slots[i].1.push(last_row);
+        }
+    }
+    for (_, rows) in slots.iter_mut() {
+        rows.sort();
+        rows.dedup();
+    }
+    slots
+}
+
+/// Sets `x` at `start_col + col` in the live rows of the value set in `col` by the operation in rows `start_row` to `end_row`, as given by [final_exp_live_rows].
+fn assign_live_rows<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, col: usize, start_row: usize, end_row: usize, x: &Fp12, start_col: usize) {
+    let (_, first_row, last_row) = final_exp_live_rows(trace.len()).into_iter()
+        .find(|(c, first_row, _)| *c == col && (start_row..end_row + 1).contains(first_row))
+        .expect("assign_live_rows: no Ti or input inverse is set in the column by the operation");
+    for row in first_row..last_row + 1 {
+        assign_limbs(trace, row, start_col + col, x);
+    }
+}
+
+/// Trace of [FinalExponentiateStark] with only the columns which do not depend on the input filled: the row counter, the row selectors and the operation selectors (see [fill_trace_final_exp_selectors]). Made once by [FinalExponentiateStark::trace_template] and copied for every input by [FinalExponentiateStark::generate_trace_from_template], which then fills the rest of the trace.
+#[derive(Clone)]
+pub struct TraceTemplate<F: RichField> {
+    rows: Vec<[F; TOTAL_COLUMNS]>,
+}
+
+impl<F: RichField> TraceTemplate<F> {
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+}
+
 // A (Fp) * B (Fp) => C (Fp)
 #[derive(Clone, Copy)]
 pub struct FinalExponentiateStark<F: RichField + Extendable<D>, const D: usize> {
     num_rows: usize,
+    output_is_one: bool,
     _f: std::marker::PhantomData<F>,
 }
 
-/// Fills trace for forbenius map operation. First sets `FINAL_EXP_FORBENIUS_MAP_SELECTOR` to 1 in the rows of the operation. Sets the result of the operaion in all rows of the trace. Then fills the trace for the forbenius map operation.
+/// Fills trace for forbenius map operation. First sets `FINAL_EXP_FORBENIUS_MAP_SELECTOR` to 1 in the rows of the operation. Sets the result of the operaion in its live rows (see [final_exp_live_rows]). Then fills the trace for the forbenius map operation.
 pub fn fill_trace_forbenius<F: RichField + Extendable<D>,
     const D: usize,
     const C: usize,
->(trace: &mut Vec<[F; C]>, x: &Fp12, pow: usize, start_row: usize, end_row: usize, output_col: usize) -> Fp12 {
+>(trace: &mut Vec<[F; C]>, x: &Fp12, pow: usize, start_row: usize, end_row: usize, output_col: usize, start_col: usize) -> Fp12 {
     let res = x.forbenius_map(pow);
     for row in start_row..end_row+1 {
-        trace[row][FINAL_EXP_FORBENIUS_MAP_SELECTOR] = F::ONE;
-    }
-    for row in 0..trace.len() {
-        assign_u32_in_series(trace, row, output_col, &res.get_u32_slice().concat());
+        trace[row][start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR] = F::ONE;
     }
-    fill_trace_fp12_forbenius_map(trace, x, pow, start_row, end_row, FINAL_EXP_OP_OFFSET);
+    assign_live_rows(trace, output_col, start_row, end_row, &res, start_col);
+    fill_trace_fp12_forbenius_map(trace, x, pow, start_row, end_row, start_col + FINAL_EXP_OP_OFFSET);
     res
 }
 
-/// Fills trace for fp12 multiplication operation. First sets `FINAL_EXP_MUL_SELECTOR` to 1 in the rows of the operation. Sets the result of the operaion in all rows of the trace. Then fills the trace for the fp12 multiplication operation.
+/// Fills trace for fp12 multiplication operation. First sets `FINAL_EXP_MUL_SELECTOR` to 1 in the rows of the operation. Sets the result of the operaion in its live rows (see [final_exp_live_rows]). Then fills the trace for the fp12 multiplication operation.
 pub fn fill_trace_mul<F: RichField + Extendable<D>,
     const D: usize,
     const C: usize,
->(trace: &mut Vec<[F; C]>, x: &Fp12, y: &Fp12, start_row: usize, end_row: usize, output_col: usize) -> Fp12 {
+>(trace: &mut Vec<[F; C]>, x: &Fp12, y: &Fp12, start_row: usize, end_row: usize, output_col: usize, start_col: usize) -> Fp12 {
     let res = (*x)*(*y);
     for row in start_row..end_row+1 {
-        trace[row][FINAL_EXP_MUL_SELECTOR] = F::ONE;
+        trace[row][start_col + FINAL_EXP_MUL_SELECTOR] = F::ONE;
     }
-    for row in 0..trace.len() {
-        assign_u32_in_series(trace, row, output_col, &res.get_u32_slice().concat());
-    }
-    fill_trace_fp12_multiplication(trace, &x, &y, start_row, end_row, FINAL_EXP_OP_OFFSET);
+    assign_live_rows(trace, output_col, start_row, end_row, &res, start_col);
+    fill_trace_fp12_multiplication(trace, &x, &y, start_row, end_row, start_col + FINAL_EXP_OP_OFFSET);
     res
 }
 
-/// Fills trace for fp12 division (which is basically fp12 multiplication) operation. First sets `FINAL_EXP_MUL_SELECTOR` to 1 in the rows of the operation. Sets the result of the operaion in all rows of the trace. Then fills the trace for the fp12 multiplication operation.
+/// Fills trace for forbenius map operation followed by fp12 multiplication operation, which computes `x.forbenius_map(pow) * y` without setting the forbenius map in columns of its own. First sets `FINAL_EXP_FORBENIUS_MAP_SELECTOR` to 1 in the first `FP12_FORBENIUS_MAP_ROWS` rows of the operation and `FINAL_EXP_MUL_SELECTOR` to 1 in the rest. Sets the result of the operation in its live rows (see [final_exp_live_rows]). Then fills the trace for the forbenius_mul operation. Returns the forbenius map and the result.
+pub fn fill_trace_forbenius_mul<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, x: &Fp12, pow: usize, y: &Fp12, start_row: usize, end_row: usize, output_col: usize, start_col: usize) -> (Fp12, Fp12) {
+    let x_pow = x.forbenius_map(pow);
+    let res = x.forbenius_mul(pow, y);
+    for row in start_row..start_row + FP12_FORBENIUS_MAP_ROWS {
+        trace[row][start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR] = F::ONE;
+    }
+    for row in start_row + FP12_FORBENIUS_MAP_ROWS..end_row+1 {
+        trace[row][start_col + FINAL_EXP_MUL_SELECTOR] = F::ONE;
+    }
+    assign_live_rows(trace, output_col, start_row, end_row, &res, start_col);
+    fill_trace_fp12_forbenius_mul(trace, x, pow, y, start_row, end_row, start_col + FINAL_EXP_OP_OFFSET);
+    (x_pow, res)
+}
+
+/// Fills trace for fp12 division (which is basically fp12 multiplication) operation. Returns [NativeError::NonInvertible](crate::native::NativeError::NonInvertible) if `y` is zero. First sets `FINAL_EXP_MUL_SELECTOR` to 1 in the rows of the operation. Sets the result of the operaion in its live rows (see [final_exp_live_rows]). Then fills the trace for the fp12 multiplication operation. Also sets the inverse of `y` in `inv_col` in its live rows and fills the trace for `y*y_inv` fp12 multiplication in the rows from `inv_start_row` to `inv_end_row`, which is constrained to be 1.
 pub fn fill_trace_div<F: RichField + Extendable<D>,
     const D: usize,
     const C: usize,
->(trace: &mut Vec<[F; C]>, x: &Fp12, y: &Fp12, start_row: usize, end_row: usize, output_col: usize) -> Fp12 {
-    let res = *x / *y;
+>(trace: &mut Vec<[F; C]>, x: &Fp12, y: &Fp12, start_row: usize, end_row: usize, output_col: usize, inv_start_row: usize, inv_end_row: usize, inv_col: usize, start_col: usize) -> Result<Fp12> {
+    let y_inv = y.try_invert()?;
+    let res = (*x) * y_inv;
     for row in start_row..end_row+1 {
-        trace[row][FINAL_EXP_MUL_SELECTOR] = F::ONE;
+        trace[row][start_col + FINAL_EXP_MUL_SELECTOR] = F::ONE;
     }
-    for row in 0..trace.len() {
-        assign_u32_in_series(trace, row, output_col, &res.get_u32_slice().concat());
+    assign_live_rows(trace, output_col, start_row, end_row, &res, start_col);
+    fill_trace_fp12_multiplication(trace, &res, &y, start_row, end_row, start_col + FINAL_EXP_OP_OFFSET);
+    for row in inv_start_row..inv_end_row+1 {
+        trace[row][start_col + FINAL_EXP_MUL_SELECTOR] = F::ONE;
     }
-    fill_trace_fp12_multiplication(trace, &res, &y, start_row, end_row, FINAL_EXP_OP_OFFSET);
-    res
+    assign_live_rows(trace, inv_col, inv_start_row, inv_end_row, &y_inv, start_col);
+    fill_trace_fp12_multiplication(trace, y, &y_inv, inv_start_row, inv_end_row, start_col + FINAL_EXP_OP_OFFSET);
+    Ok(res)
 }
 
-/// Fills trace for cyclotomic exponent operation. First sets `FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR` to 1 in the rows of the operation. Sets the result of the operaion in all rows of the trace. Then fills the trace for the cyclotomic exponent operation.
+/// Fills trace for cyclotomic exponent operation. First sets `FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR` to 1 in the rows of the operation. Sets the result of the operaion in its live rows (see [final_exp_live_rows]). Then fills the trace for the cyclotomic exponent operation.
 pub fn fill_trace_cyc_exp<F: RichField + Extendable<D>,
     const D: usize,
     const C: usize,
->(trace: &mut Vec<[F; C]>, x: &Fp12, start_row: usize, end_row: usize, output_col: usize) -> Fp12 {
+>(trace: &mut Vec<[F; C]>, x: &Fp12, start_row: usize, end_row: usize, output_col: usize, start_col: usize) -> Fp12 {
     let res = x.cyclotocmic_exponent();
     for row in start_row..end_row+1 {
-        trace[row][FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR] = F::ONE;
-    }
-    for row in 0..trace.len() {
-        assign_u32_in_series(trace, row, output_col, &res.get_u32_slice().concat());
+        trace[row][start_col + FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR] = F::ONE;
     }
-    fill_trace_cyclotomic_exp(trace, x, start_row, end_row, FINAL_EXP_OP_OFFSET);
+    assign_live_rows(trace, output_col, start_row, end_row, &res, start_col);
+    fill_trace_cyclotomic_exp(trace, x, start_row, end_row, start_col + FINAL_EXP_OP_OFFSET);
     res
 }
 
-/// Fills trace for fp12 conjugate operation. First sets `FINAL_EXP_CONJUGATE_SELECTOR` to 1 in the rows of the operation. Sets the result of the operaion in all rows of the trace. Then fills the trace for the fp12 conjugate operation.
+/// Fills trace for fp12 conjugate operation. First sets `FINAL_EXP_CONJUGATE_SELECTOR` to 1 in the rows of the operation. Sets the result of the operaion in its live rows (see [final_exp_live_rows]). Then fills the trace for the fp12 conjugate operation.
 pub fn fill_trace_conjugate<F: RichField + Extendable<D>,
     const D: usize,
     const C: usize,
->(trace: &mut Vec<[F; C]>, x: &Fp12, row: usize, output_col: usize) -> Fp12 {
+>(trace: &mut Vec<[F; C]>, x: &Fp12, row: usize, output_col: usize, start_col: usize) -> Fp12 {
     let res = x.conjugate();
-    trace[row][FINAL_EXP_CONJUGATE_SELECTOR] = F::ONE;
-    for i in 0..trace.len() {
-        assign_u32_in_series(trace, i, output_col, &res.get_u32_slice().concat());
-    }
-    fill_trace_fp12_conjugate(trace, x, row, FINAL_EXP_OP_OFFSET);
+    trace[row][start_col + FINAL_EXP_CONJUGATE_SELECTOR] = F::ONE;
+    assign_live_rows(trace, output_col, row, row, &res, start_col);
+    fill_trace_fp12_conjugate(trace, x, row, start_col + FINAL_EXP_OP_OFFSET);
     res
 }
 
-/// Fills trace for cyclotomic square operation. First sets `FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR` to 1 in the rows of the operation. Sets the result of the operaion in all rows of the trace. Then fills the trace for the cyclotomic square operation.
+/// Fills trace for cyclotomic square operation. First sets `FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR` to 1 in the rows of the operation. Sets the result of the operaion in its live rows (see [final_exp_live_rows]). Then fills the trace for the cyclotomic square operation.
 pub fn fill_trace_cyc_sq<F: RichField + Extendable<D>,
     const D: usize,
     const C: usize,
->(trace: &mut Vec<[F; C]>, x: &Fp12, start_row: usize, end_row: usize, output_col: usize) -> Fp12 {
+>(trace: &mut Vec<[F; C]>, x: &Fp12, start_row: usize, end_row: usize, output_col: usize, start_col: usize) -> Fp12 {
     let res = x.cyclotomic_square();
     for row in start_row..end_row+1 {
-        trace[row][FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR] = F::ONE;
+        trace[row][start_col + FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR] = F::ONE;
+    }
+    assign_live_rows(trace, output_col, start_row, end_row, &res, start_col);
+    fill_trace_cyclotomic_sq(trace, x, start_row, end_row, start_col + FINAL_EXP_OP_OFFSET);
+    res
+}
+
+/// Operation selector of each operation of [final_exp_row_budget].
+fn final_exp_op_selector(op: &str) -> usize {
+    match op.split(' ').last() {
+        Some("forbenius_map") => FINAL_EXP_FORBENIUS_MAP_SELECTOR,
+        Some("div") | Some("mul") => FINAL_EXP_MUL_SELECTOR,
+        Some("cyclotomic_exp") => FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR,
+        Some("conjugate") => FINAL_EXP_CONJUGATE_SELECTOR,
+        Some("cyclotomic_square") => FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR,
+        _ => unreachable!("final_exp_op_selector: unknown operation {}", op),
     }
+}
+
+/// Fills the columns of the final exponentiate trace which do not depend on the input: the bits of the row number, the low and high row selectors they pick, and the operation selectors in the rows of each operation (see [final_exp_row_budget]). All the columns are offset by `start_col`.
+pub fn fill_trace_final_exp_selectors<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, start_col: usize) {
     for row in 0..trace.len() {
-        assign_u32_in_series(trace, row, output_col, &res.get_u32_slice().concat());
+        for k in 0..FINAL_EXP_ROW_BITS {
+            trace[row][start_col + FINAL_EXP_ROW_BITS_OFFSET + k] = F::from_canonical_usize((row >> k) & 1);
+        }
+        trace[row][start_col + FINAL_EXP_ROW_LOW_SELECTORS + (row & (FINAL_EXP_ROW_LOW_SELECTORS_TOTAL - 1))] = F::ONE;
+        trace[row][start_col + FINAL_EXP_ROW_HIGH_SELECTORS + (row >> FINAL_EXP_ROW_LOW_BITS)] = F::ONE;
+    }
+    for (op, start_row, rows) in final_exp_row_budget() {
+        let selector = final_exp_op_selector(&op);
+        for row in start_row..start_row + rows {
+            trace[row][start_col + selector] = F::ONE;
+        }
+    }
+}
+
+/// Fills the trace for [final_exponentiate](super::native::Fp12::final_exponentiate) function. First fills the columns which do not depend on the input, see [fill_trace_final_exp_selectors], then the columns which do, see [fill_trace_final_exp_values]. Returns an error if the input is zero. All the columns are offset by `start_col`.
+pub fn fill_trace_final_exponentiate<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, x: &Fp12, start_col: usize) -> Result<Fp12> {
+    fill_trace_final_exp_selectors(trace, start_col);
+    fill_trace_final_exp_values(trace, x, start_col)
+}
+
+/// The values T0..T31 which [fill_trace_final_exp_values] sets in the trace for the input `x`, in order, so that T31 is the final exponentiation of `x`. Computed natively, without filling a trace, for checking each step against a reference implementation. `x` must be non-zero, which [FinalExponentiateStark::generate_trace] checks before computing them.
+pub fn compute_intermediates(x: Fp12) -> [Fp12; 32] {
+    x.final_exponentiate_steps()
+}
+
+/// Fills the columns of the final exponentiate trace which depend on the input `x`, in a trace whose selectors are filled by [fill_trace_final_exp_selectors]. Assigns the input to all rows in `FINAL_EXP_INPUT_OFFSET`, then fills trace for each Ti term, with the inputs of every operation taken from [compute_intermediates]. Returns an error if the input is zero. All the columns are offset by `start_col`.
+pub fn fill_trace_final_exp_values<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, x: &Fp12, start_col: usize) -> Result<Fp12> {
+    for row in 0..trace.len() {
+        assign_limbs(trace, row, start_col + FINAL_EXP_INPUT_OFFSET, x);
+    }
+    // The zero check of the division, done before computing the native values, which divide by the input.
+    x.try_invert()?;
+    let t = compute_intermediates(*x);
+    let (t_2, t_3) = fill_trace_forbenius_mul(trace, &t[1], 2, &t[1], T2_ROW, T4_ROW-1, FINAL_EXP_T3_OFFSET, start_col);
+    // T29 = T20 * T22 is filled as the forbenius map of T21 multiplied by T20.
+    let (t_22, t_29) = fill_trace_forbenius_mul(trace, &t[21], 3, &t[20], T22_ROW, T30_ROW-1, FINAL_EXP_T29_OFFSET, start_col);
+    let filled = [
+        fill_trace_forbenius(trace, x, 6, T0_ROW, T1_ROW-1, FINAL_EXP_T0_OFFSET, start_col),
+        fill_trace_div(trace, &t[0], x, T1_ROW, T2_ROW-1, FINAL_EXP_T1_OFFSET, INPUT_INV_ROW, TOTAL_ROW-1, FINAL_EXP_INPUT_INV_OFFSET, start_col)?,
+        t_2,
+        t_3,
+        fill_trace_cyc_exp(trace, &t[3], T4_ROW, T5_ROW-1, FINAL_EXP_T4_OFFSET, start_col),
+        fill_trace_conjugate(trace, &t[4], T5_ROW, FINAL_EXP_T5_OFFSET, start_col),
+        fill_trace_cyc_sq(trace, &t[3], T6_ROW, T7_ROW-1, FINAL_EXP_T6_OFFSET, start_col),
+        fill_trace_conjugate(trace, &t[6], T7_ROW, FINAL_EXP_T7_OFFSET, start_col),
+        fill_trace_mul(trace, &t[7], &t[5], T8_ROW, T9_ROW-1, FINAL_EXP_T8_OFFSET, start_col),
+        fill_trace_cyc_exp(trace, &t[8], T9_ROW, T10_ROW-1, FINAL_EXP_T9_OFFSET, start_col),
+        fill_trace_conjugate(trace, &t[9], T10_ROW, FINAL_EXP_T10_OFFSET, start_col),
+        fill_trace_cyc_exp(trace, &t[10], T11_ROW, T12_ROW-1, FINAL_EXP_T11_OFFSET, start_col),
+        fill_trace_conjugate(trace, &t[11], T12_ROW, FINAL_EXP_T12_OFFSET, start_col),
+        fill_trace_cyc_exp(trace, &t[12], T13_ROW, T14_ROW-1, FINAL_EXP_T13_OFFSET, start_col),
+        fill_trace_conjugate(trace, &t[13], T14_ROW, FINAL_EXP_T14_OFFSET, start_col),
+        fill_trace_cyc_sq(trace, &t[5], T15_ROW, T16_ROW-1, FINAL_EXP_T15_OFFSET, start_col),
+        fill_trace_mul(trace, &t[14], &t[15], T16_ROW, T17_ROW-1, FINAL_EXP_T16_OFFSET, start_col),
+        fill_trace_cyc_exp(trace, &t[16], T17_ROW, T18_ROW-1, FINAL_EXP_T17_OFFSET, start_col),
+        fill_trace_conjugate(trace, &t[17], T18_ROW, FINAL_EXP_T18_OFFSET, start_col),
+        fill_trace_mul(trace, &t[5], &t[12], T19_ROW, T20_ROW-1, FINAL_EXP_T19_OFFSET, start_col),
+        fill_trace_forbenius(trace, &t[19], 2, T20_ROW, T23_ROW-1, FINAL_EXP_T20_OFFSET, start_col),
+        fill_trace_mul(trace, &t[10], &t[3], T21_ROW, T22_ROW-1, FINAL_EXP_T21_OFFSET, start_col),
+        t_22,
+        fill_trace_conjugate(trace, &t[3], T23_ROW, FINAL_EXP_T23_OFFSET, start_col),
+        fill_trace_mul(trace, &t[16], &t[23], T24_ROW, T25_ROW-1, FINAL_EXP_T24_OFFSET, start_col),
+        fill_trace_forbenius(trace, &t[24], 1, T25_ROW, T26_ROW-1, FINAL_EXP_T25_OFFSET, start_col),
+        fill_trace_conjugate(trace, &t[8], T26_ROW, FINAL_EXP_T26_OFFSET, start_col),
+        fill_trace_mul(trace, &t[18], &t[26], T27_ROW, T28_ROW-1, FINAL_EXP_T27_OFFSET, start_col),
+        fill_trace_mul(trace, &t[27], &t[3], T28_ROW, T21_ROW-1, FINAL_EXP_T28_OFFSET, start_col),
+        t_29,
+        fill_trace_mul(trace, &t[29], &t[25], T30_ROW, T31_ROW-1, FINAL_EXP_T30_OFFSET, start_col),
+        fill_trace_mul(trace, &t[30], &t[28], T31_ROW, INPUT_INV_ROW-1, FINAL_EXP_T31_OFFSET, start_col),
+    ];
+    debug_assert!(filled == t, "fill_trace_final_exponentiate: trace values differ from the native final exponentiation");
+    Ok(t[31])
+}
+
+/// Public inputs of [FinalExponentiateStark]. Input Fp12 limbs are placed at `PIS_INPUT_OFFSET` and the expected output limbs at `PIS_OUTPUT_OFFSET`, in the same order as `get_u32_slice().concat()`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FinalExponentiatePublicInputs {
+    pub input: Fp12,
+    pub output: Fp12,
+}
+
+impl<F: RichField> StarkPublicInputs<F> for FinalExponentiatePublicInputs {
+    fn encode(&self) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for (i, e) in self.input.get_u32_slice().concat().iter().enumerate() {
+            public_inputs[PIS_INPUT_OFFSET + i] = F::from_canonical_u32(*e);
+        }
+        for (i, e) in self.output.get_u32_slice().concat().iter().enumerate() {
+            public_inputs[PIS_OUTPUT_OFFSET + i] = F::from_canonical_u32(*e);
+        }
+        public_inputs
+    }
+
+    /// Panics if `pis` is not `PUBLIC_INPUTS` long or if a limb does not fit in a u32.
+    fn decode(pis: &[F]) -> Self {
+        assert_eq!(pis.len(), PUBLIC_INPUTS);
+        FinalExponentiatePublicInputs {
+            input: fp12_from_public_inputs(pis, PIS_INPUT_OFFSET),
+            output: fp12_from_public_inputs(pis, PIS_OUTPUT_OFFSET),
+        }
+    }
+}
+
+/// Fills the range checks of the public inputs, which are given as `PUBLIC_INPUTS` u32 limbs. Row i checks the i-th fp element of the public inputs: sets its limbs and their bit decompositions, fills the range check of the element and sets the bit decompositions of the limbs of the range check sum. All the columns are offset by `start_col`.
+pub fn fill_trace_public_input_range_checks<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, public_inputs: &[u32], start_col: usize) {
+    assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+    for (row, limbs) in public_inputs.chunks(12).enumerate() {
+        let limbs: [u32; 12] = limbs.try_into().unwrap();
+        for (i, limb) in limbs.iter().enumerate() {
+            assign_u32_in_series(trace, row, start_col + PIS_RANGE_CHECK_BITS_OFFSET + i*32, &get_bits_as_array(*limb));
+        }
+        assign_u32_in_series(trace, row, start_col + PIS_RANGE_CHECK_INPUT_OFFSET, &limbs);
+        fill_range_check_trace(trace, &limbs, row, start_col + PIS_RANGE_CHECK_OFFSET);
+        for i in 0..11 {
+            let sum = trace[row][start_col + PIS_RANGE_CHECK_OFFSET + RANGE_CHECK_SUM_OFFSET + i].to_canonical_u64() as u32;
+            assign_u32_in_series(trace, row, start_col + PIS_RANGE_CHECK_SUM_BITS_OFFSET + i*32, &get_bits_as_array(sum));
+        }
     }
-    fill_trace_cyclotomic_sq(trace, x, start_row, end_row, FINAL_EXP_OP_OFFSET);
-    res
 }
 
 // Implement trace generator
 impl<F: RichField + Extendable<D>, const D: usize> FinalExponentiateStark<F, D> {
-    pub fn new(num_rows: usize) -> Self {
-        Self {
+    /// Rows used by one final exponentiation, `TOTAL_ROW`. A stark composing final exponentiate with other gadgets side by side needs the most of their required rows, rounded up to a power of two.
+    pub fn required_rows() -> usize {
+        TOTAL_ROW
+    }
+
+    /// Returns an error if `num_rows` is less than `TOTAL_ROW`, which the trace needs, or more than `FINAL_EXP_MAX_ROWS`, the number of rows the row counter can number, or if the field does not fit the trace (see [check_field](crate::config::check_field)).
+    pub fn new(num_rows: usize) -> Result<Self> {
+        if num_rows < TOTAL_ROW {
+            bail!("FinalExponentiateStark::new: num_rows {} is less than TOTAL_ROW {} required by the trace", num_rows, TOTAL_ROW);
+        }
+        if num_rows > FINAL_EXP_MAX_ROWS {
+            bail!("FinalExponentiateStark::new: num_rows {} is more than the {} rows of the row counter", num_rows, FINAL_EXP_MAX_ROWS);
+        }
+        check_field::<F>(num_rows)?;
+        Ok(Self {
             num_rows,
+            output_is_one: false,
             _f: std::marker::PhantomData,
+        })
+    }
+
+    /// Creates the stark with the smallest power of two number of rows that fits `TOTAL_ROW`.
+    pub fn with_min_rows() -> Self {
+        Self::new(Self::required_rows().next_power_of_two()).unwrap()
+    }
+
+    /// Returns the stark which also constrains the output at `FINAL_EXP_T31_OFFSET` to be the Fp12 one, as needed for pairing equality checks. See [FinalExponentiateStark::prove_pairing_is_one].
+    pub fn with_output_one(self) -> Self {
+        Self {
+            output_is_one: true,
+            ..self
         }
     }
 
-    /// Fills the trace for [final_exponentiate](super::native::Fp12::final_exponentiate) function. First fill the `FINAL_EXP_ROW_SELECTORS` according to the row number. Assigns the input to all rows in `FINAL_EXP_INPUT_OFFSET`, then fills trace for each Ti term as defined in the native function definition.
-    pub fn generate_trace(&self, x: Fp12) -> Vec<[F; TOTAL_COLUMNS]> {
+    /// Lays out the public inputs for this stark, see [FinalExponentiatePublicInputs].
+    pub fn public_inputs(input: &Fp12, output: &Fp12) -> Vec<F> {
+        FinalExponentiatePublicInputs { input: *input, output: *output }.encode()
+    }
+
+    /// Fills the trace for [final_exponentiate](super::native::Fp12::final_exponentiate) function. See [fill_trace_final_exponentiate].
+    /// Returns an error if the input is zero, the only Fp12 element without an inverse, since T1 divides by the input. The constraints enforce the same precondition, by checking the inverse of the input set at `FINAL_EXP_INPUT_INV_OFFSET`.
+    pub fn generate_trace(&self, x: Fp12) -> Result<Vec<[F; TOTAL_COLUMNS]>> {
         let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
-        for row in 0..trace.len() {
-            trace[row][FINAL_EXP_ROW_SELECTORS + row] = F::ONE;
-            assign_u32_in_series(&mut trace, row, FINAL_EXP_INPUT_OFFSET, &x.get_u32_slice().concat());
-        }
-        let t0 = fill_trace_forbenius(&mut trace, &x, 6, T0_ROW, T1_ROW-1, FINAL_EXP_T0_OFFSET);
-        let t1 = fill_trace_div(&mut trace, &t0, &x, T1_ROW, T2_ROW-1, FINAL_EXP_T1_OFFSET);
-        let t2 = fill_trace_forbenius(&mut trace, &t1, 2, T2_ROW, T3_ROW-1, FINAL_EXP_T2_OFFSET);
-        let t3 = fill_trace_mul(&mut trace, &t2, &t1, T3_ROW, T4_ROW-1, FINAL_EXP_T3_OFFSET);
-        let t4 = fill_trace_cyc_exp(&mut trace, &t3, T4_ROW, T5_ROW-1, FINAL_EXP_T4_OFFSET);
-        let t5 = fill_trace_conjugate(&mut trace, &t4, T5_ROW, FINAL_EXP_T5_OFFSET);
-        let t6 = fill_trace_cyc_sq(&mut trace, &t3, T6_ROW, T7_ROW-1, FINAL_EXP_T6_OFFSET);
-        let t7 = fill_trace_conjugate(&mut trace, &t6, T7_ROW, FINAL_EXP_T7_OFFSET);
-        let t8 = fill_trace_mul(&mut trace, &t7, &t5, T8_ROW, T9_ROW-1, FINAL_EXP_T8_OFFSET);
-        let t9 = fill_trace_cyc_exp(&mut trace, &t8, T9_ROW, T10_ROW-1, FINAL_EXP_T9_OFFSET);
-        let t10 = fill_trace_conjugate(&mut trace, &t9, T10_ROW, FINAL_EXP_T10_OFFSET);
-        let t11 = fill_trace_cyc_exp(&mut trace, &t10, T11_ROW, T12_ROW-1, FINAL_EXP_T11_OFFSET);
-        let t12 = fill_trace_conjugate(&mut trace, &t11, T12_ROW, FINAL_EXP_T12_OFFSET);
-        let t13 = fill_trace_cyc_exp(&mut trace, &t12, T13_ROW, T14_ROW-1, FINAL_EXP_T13_OFFSET);
-        let t14 = fill_trace_conjugate(&mut trace, &t13, T14_ROW, FINAL_EXP_T14_OFFSET);
-        let t15 = fill_trace_cyc_sq(&mut trace, &t5, T15_ROW, T16_ROW-1, FINAL_EXP_T15_OFFSET);
-        let t16 = fill_trace_mul(&mut trace, &t14, &t15, T16_ROW, T17_ROW-1, FINAL_EXP_T16_OFFSET);
-        let t17 = fill_trace_cyc_exp(&mut trace, &t16, T17_ROW, T18_ROW-1, FINAL_EXP_T17_OFFSET);
-        let t18 = fill_trace_conjugate(&mut trace, &t17, T18_ROW, FINAL_EXP_T18_OFFSET);
-        let t19 = fill_trace_mul(&mut trace, &t5, &t12, T19_ROW, T20_ROW-1, FINAL_EXP_T19_OFFSET);
-        let t20 = fill_trace_forbenius(&mut trace, &t19, 2, T20_ROW, T21_ROW-1, FINAL_EXP_T20_OFFSET);
-        let t21 = fill_trace_mul(&mut trace, &t10, &t3, T21_ROW, T22_ROW-1, FINAL_EXP_T21_OFFSET);
-        let t22 = fill_trace_forbenius(&mut trace, &t21, 3, T22_ROW, T23_ROW-1, FINAL_EXP_T22_OFFSET);
-        let t23 = fill_trace_conjugate(&mut trace, &t3, T23_ROW, FINAL_EXP_T23_OFFSET);
-        let t24 = fill_trace_mul(&mut trace, &t16, &t23, T24_ROW, T25_ROW-1, FINAL_EXP_T24_OFFSET);
-        let t25 = fill_trace_forbenius(&mut trace, &t24, 1, T25_ROW, T26_ROW-1, FINAL_EXP_T25_OFFSET);
-        let t26 = fill_trace_conjugate(&mut trace, &t8, T26_ROW, FINAL_EXP_T26_OFFSET);
-        let t27 = fill_trace_mul(&mut trace, &t18, &t26, T27_ROW, T28_ROW-1, FINAL_EXP_T27_OFFSET);
-        let t28 = fill_trace_mul(&mut trace, &t27, &t3, T28_ROW, T29_ROW-1, FINAL_EXP_T28_OFFSET);
-        let t29 = fill_trace_mul(&mut trace, &t20, &t22, T29_ROW, T30_ROW-1, FINAL_EXP_T29_OFFSET);
-        let t30 = fill_trace_mul(&mut trace, &t29, &t25, T30_ROW, T31_ROW-1, FINAL_EXP_T30_OFFSET);
-        let _t31 = fill_trace_mul(&mut trace, &t30, &t28, T31_ROW, TOTAL_ROW-1, FINAL_EXP_T31_OFFSET);
-        trace
+        let res = fill_trace_final_exponentiate(&mut trace, &x, 0)?;
+        let public_inputs = [x.get_u32_slice().concat(), res.get_u32_slice().concat()].concat();
+        fill_trace_public_input_range_checks(&mut trace, &public_inputs, FINAL_EXP_PIS_RANGE_CHECK_OFFSET);
+        Ok(trace)
+    }
+
+    /// Precomputes the columns of the trace which are the same for every input, see [TraceTemplate].
+    pub fn trace_template(&self) -> TraceTemplate<F> {
+        let mut rows = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_final_exp_selectors(&mut rows, 0);
+        TraceTemplate { rows }
+    }
+
+    /// Same trace as [FinalExponentiateStark::generate_trace], starting from a copy of `template` and filling only the columns which depend on the input `x`.
+    /// Returns an error if the template was made for a different number of rows, or if the input is zero.
+    pub fn generate_trace_from_template(&self, template: &TraceTemplate<F>, x: Fp12) -> Result<Vec<[F; TOTAL_COLUMNS]>> {
+        if template.num_rows() != self.num_rows {
+            bail!("FinalExponentiateStark::generate_trace_from_template: template has {} rows, the stark {}", template.num_rows(), self.num_rows);
+        }
+        let mut trace = template.rows.clone();
+        let res = fill_trace_final_exp_values(&mut trace, &x, 0)?;
+        let public_inputs = [x.get_u32_slice().concat(), res.get_u32_slice().concat()].concat();
+        fill_trace_public_input_range_checks(&mut trace, &public_inputs, FINAL_EXP_PIS_RANGE_CHECK_OFFSET);
+        Ok(trace)
+    }
+
+    /// Proves the final exponentiation of each of the inputs with this stark and `config`, returning one proof per input in the same order. Each proof carries its input and output as public inputs, see [FinalExponentiateStark::public_inputs].
+    /// The instances are not stacked into one trace, since a single instance already needs `TOTAL_ROW` of the `FINAL_EXP_MAX_ROWS` rows. The stark and config are shared, so the proofs verify against the same verifier data, and a recursive circuit can verify all of them with one circuit built for the first one.
+    /// The traces are generated from one [TraceTemplate]. Returns an error if any input is zero, before proving the following inputs. With the `debug-constraints` feature, each trace is checked row by row before proving and a failing constraint panics with its row.
+    pub fn prove_batch<C: GenericConfig<D, F = F>>(&self, config: &StarkConfig, inputs: &[Fp12]) -> Result<Vec<StarkProofWithPublicInputs<F, C, D>>> {
+        let template = self.trace_template();
+        inputs.iter().map(|x| {
+            let public_inputs = Self::public_inputs(x, &x.final_exponentiate());
+            let trace = self.generate_trace_from_template(&template, *x)?;
+            #[cfg(feature = "debug-constraints")]
+            assert_trace_satisfies(self, &trace, &public_inputs);
+            let trace_poly_values = trace_rows_to_poly_values_streaming(trace);
+            prove::<F, C, Self, D>(*self, config, trace_poly_values, &public_inputs, &mut TimingTree::default())
+        }).collect()
+    }
+
+    /// Proves that the final exponentiation of `input` is the Fp12 one, which is the check done by pairing based signature verification. The output public inputs are set to one, and the proof is made with [FinalExponentiateStark::with_output_one], so the proof must be verified with `self.with_output_one()`.
+    /// Returns an error if the input is zero or if its final exponentiation is not one. The trace is checked before proving with the `debug-constraints` feature, as in [FinalExponentiateStark::prove_batch].
+    pub fn prove_pairing_is_one<C: GenericConfig<D, F = F>>(&self, config: &StarkConfig, input: Fp12) -> Result<StarkProofWithPublicInputs<F, C, D>> {
+        if input.final_exponentiate() != Fp12::one() {
+            bail!("FinalExponentiateStark::prove_pairing_is_one: final exponentiation of the input is not one");
+        }
+        let stark = self.with_output_one();
+        let public_inputs = Self::public_inputs(&input, &Fp12::one());
+        let trace = stark.generate_trace(input)?;
+        #[cfg(feature = "debug-constraints")]
+        assert_trace_satisfies(&stark, &trace, &public_inputs);
+        let trace_poly_values = trace_rows_to_poly_values_streaming(trace);
+        prove::<F, C, Self, D>(stark, config, trace_poly_values, &public_inputs, &mut TimingTree::default())
+    }
+}
+
+/// Verifies a [FinalExponentiateStark] proof of the final exponentiation given by `public_inputs` (see [FinalExponentiateStark::public_inputs]), without the verifier knowing the number of rows the prover used: the stark is reconstructed with the number of rows given by the degree bits of the proof.
+/// Returns an error if the number of rows is not one [FinalExponentiateStark::new] accepts, or if the proof does not verify. Proofs of [FinalExponentiateStark::prove_pairing_is_one] are verified by this function too, as proofs of a final exponentiation whose output is one.
+pub fn verify_final_exp<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    proof: StarkProof<F, C, D>,
+    public_inputs: &[F],
+    config: &StarkConfig,
+) -> Result<()> {
+    let degree_bits = proof.recover_degree_bits(config);
+    if degree_bits > FINAL_EXP_ROW_BITS {
+        bail!("verify_final_exp: proof of 2^{} rows, more than the {} rows of the row counter", degree_bits, FINAL_EXP_MAX_ROWS);
+    }
+    let stark = FinalExponentiateStark::<F, D>::new(1 << degree_bits)?;
+    let proof = StarkProofWithPublicInputs { proof, public_inputs: public_inputs.to_vec() };
+    verify_stark_proof(stark, proof, config)
+}
+
+/// Returns 1 in the row numbered `row` and 0 in the other rows: the product of the high selector and the low selector of `row`, which are set by the row counter filled in [fill_trace_final_exponentiate]. The product has degree 2, so the constraints gated by it have one degree more than with a column per row.
+pub fn final_exp_row_selector<P: PackedField>(local_values: &[P], row: usize, start_col: usize) -> P {
+    local_values[start_col + FINAL_EXP_ROW_HIGH_SELECTORS + (row >> FINAL_EXP_ROW_LOW_BITS)] *
+    local_values[start_col + FINAL_EXP_ROW_LOW_SELECTORS + (row & (FINAL_EXP_ROW_LOW_SELECTORS_TOTAL - 1))]
+}
+
+pub fn final_exp_row_selector_ext_circuit<F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    row: usize,
+    start_col: usize,
+) -> ExtensionTarget<D> {
+    builder.mul_extension(
+        local_values[start_col + FINAL_EXP_ROW_HIGH_SELECTORS + (row >> FINAL_EXP_ROW_LOW_BITS)],
+        local_values[start_col + FINAL_EXP_ROW_LOW_SELECTORS + (row & (FINAL_EXP_ROW_LOW_SELECTORS_TOTAL - 1))],
+    )
+}
+
+/// Number given by the bits `bits` of the row counter, least significant first.
+fn final_exp_row_number<P: PackedField>(values: &[P], bits: Range<usize>, start_col: usize) -> P {
+    bits.clone().fold(P::ZEROS, |acc, k| acc + values[start_col + FINAL_EXP_ROW_BITS_OFFSET + k] * P::Scalar::from_canonical_usize(1 << (k - bits.start)))
+}
+
+fn final_exp_row_number_ext_circuit<F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<F, D>,
+    values: &[ExtensionTarget<D>],
+    bits: Range<usize>,
+    start_col: usize,
+) -> ExtensionTarget<D> {
+    let mut acc = builder.constant_extension(F::Extension::ZERO);
+    for k in bits.clone() {
+        let coeff = builder.constant_extension(F::Extension::from_canonical_usize(1 << (k - bits.start)));
+        acc = builder.mul_add_extension(values[start_col + FINAL_EXP_ROW_BITS_OFFSET + k], coeff, acc);
     }
+    acc
 }
 
-/// Constraints `FINAL_EXP_FORBENIUS_MAP_SELECTOR` to be 1 and other op selectors to be 0 in the `FP12_FORBENIUS_MAP_ROWS` starting from `row`. Constraints the values in input columns to input of forbenius_map operation trace. Constraints the output of forbenius_map trace to the values set in output columns.
+/// Constraints `FINAL_EXP_FORBENIUS_MAP_SELECTOR` to be 1 and other op selectors to be 0 in the `FP12_FORBENIUS_MAP_ROWS` starting from `row`. Constraints the values in input columns to input of forbenius_map operation trace. Constraints the output of forbenius_map trace to the values set in output columns, if any (the output of the forbenius map computing the input of [add_constraints_forbenius_mul]'s multiplication has none).
 fn add_constraints_forbenius<F: RichField + Extendable<D>,
     const D: usize,
     FE,
@@ -290,77 +753,51 @@ fn add_constraints_forbenius<F: RichField + Extendable<D>,
     yield_constr: &mut ConstraintConsumer<P>,
     row: usize,
     input_col: usize,
-    output_col: usize,
+    output_col: Option<usize>,
     pow: usize,
+    start_col: usize,
 ) where
     FE: FieldExtension<D2, BaseField = F>,
     P: PackedField<Scalar = FE>,
 {
+    let row_selector = final_exp_row_selector(local_values, row, start_col);
     for i in row..row + FP12_FORBENIUS_MAP_ROWS {
+        let selector = final_exp_row_selector(local_values, i, start_col);
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            (local_values[FINAL_EXP_FORBENIUS_MAP_SELECTOR] - P::ONES)
-        );
-        yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]
+            selector *
+            (local_values[start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR] - P::ONES)
         );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_MUL_SELECTOR]
+            selector *
+            local_values[start_col + FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]
         );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]
+            selector *
+            local_values[start_col + FINAL_EXP_MUL_SELECTOR]
         );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_CONJUGATE_SELECTOR]
+            selector *
+            local_values[start_col + FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]
         );
-    }
-    for i in 0..24*3*2 {
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + row] *
-            (local_values[input_col + i] -
-            local_values[FINAL_EXP_OP_OFFSET + FP12_FORBENIUS_MAP_INPUT_OFFSET + i])
+            selector *
+            local_values[start_col + FINAL_EXP_CONJUGATE_SELECTOR]
         );
     }
+    add_fp12_equal_constraints(local_values, yield_constr, start_col + input_col, start_col + FINAL_EXP_OP_OFFSET + FP12_FORBENIUS_MAP_INPUT_OFFSET, Some(row_selector));
     yield_constr.constraint(
-        local_values[FINAL_EXP_ROW_SELECTORS + row] *
-        (local_values[FINAL_EXP_OP_OFFSET + FP12_FORBENIUS_MAP_POW_OFFSET] - FE::from_canonical_usize(pow))
+        row_selector *
+        (local_values[start_col + FINAL_EXP_OP_OFFSET + FP12_FORBENIUS_MAP_POW_OFFSET] - FE::from_canonical_usize(pow))
     );
-    for i in 0..12 {
-        for j in 0..12 {
-            let offset = if j == 0 {
-                FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_X_CALC_OFFSET + FP2_FORBENIUS_MAP_INPUT_OFFSET
-            } else if j == 1 {
-                FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_X_CALC_OFFSET + FP2_FORBENIUS_MAP_T0_CALC_OFFSET + FP_MULTIPLICATION_TOTAL_COLUMNS + REDUCED_OFFSET
-            } else if j == 2 {
-                FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_Y_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 3 {
-                FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_Y_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 4 {
-                FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_Z_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 5 {
-                FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_Z_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 6 {
-                FP12_FORBENIUS_MAP_C0_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 7 {
-                FP12_FORBENIUS_MAP_C0_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 8 {
-                FP12_FORBENIUS_MAP_C1_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 9 {
-                FP12_FORBENIUS_MAP_C1_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 10 {
-                FP12_FORBENIUS_MAP_C2_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET
-            } else {
-                FP12_FORBENIUS_MAP_C2_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET
-            };
-            yield_constr.constraint(
-                local_values[FINAL_EXP_ROW_SELECTORS + row] *
-                (local_values[FINAL_EXP_OP_OFFSET + offset + i] -
-                local_values[output_col + j*12 + i])
-            );
+    if let Some(output_col) = output_col {
+        for (j, col) in fp12_forbenius_map_result_cols(start_col + FINAL_EXP_OP_OFFSET).into_iter().enumerate() {
+            for i in 0..12 {
+                yield_constr.constraint(
+                    row_selector *
+                    (local_values[col + i] -
+                    local_values[start_col + output_col + j*12 + i])
+                );
+            }
         }
     }
 }
@@ -373,73 +810,49 @@ pub fn add_constraints_forbenius_ext_circuit<F: RichField + Extendable<D>,
     local_values: &[ExtensionTarget<D>],
     row: usize,
     input_col: usize,
-    output_col: usize,
+    output_col: Option<usize>,
     pow: usize,
+    start_col: usize,
 ) {
+    let row_selector = final_exp_row_selector_ext_circuit(builder, local_values, row, start_col);
     for i in row..row + FP12_FORBENIUS_MAP_ROWS {
+        let selector = final_exp_row_selector_ext_circuit(builder, local_values, i, start_col);
         let one = builder.constant_extension(F::Extension::ONE);
 
-        let c = builder.sub_extension(local_values[FINAL_EXP_FORBENIUS_MAP_SELECTOR], one);
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], c);
+        let c = builder.sub_extension(local_values[start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR], one);
+        let c = builder.mul_extension(selector, c);
         yield_constr.constraint(builder, c);
 
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]);
         yield_constr.constraint(builder, c);
 
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_MUL_SELECTOR]);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_MUL_SELECTOR]);
         yield_constr.constraint(builder, c);
 
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]);
         yield_constr.constraint(builder, c);
 
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_CONJUGATE_SELECTOR]);
-        yield_constr.constraint(builder, c);
-    }
-    for i in 0..24*3*2 {
-        let c = builder.sub_extension(local_values[input_col + i], local_values[FINAL_EXP_OP_OFFSET + FP12_FORBENIUS_MAP_INPUT_OFFSET + i]);
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], c);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_CONJUGATE_SELECTOR]);
         yield_constr.constraint(builder, c);
     }
+    add_fp12_equal_constraints_ext_circuit(builder, yield_constr, local_values, start_col + input_col, start_col + FINAL_EXP_OP_OFFSET + FP12_FORBENIUS_MAP_INPUT_OFFSET, Some(row_selector));
     let pow = builder.constant_extension(F::Extension::from_canonical_usize(pow));
-    let c = builder.sub_extension(local_values[FINAL_EXP_OP_OFFSET + FP12_FORBENIUS_MAP_POW_OFFSET], pow);
-    let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], c);
+    let c = builder.sub_extension(local_values[start_col + FINAL_EXP_OP_OFFSET + FP12_FORBENIUS_MAP_POW_OFFSET], pow);
+    let c = builder.mul_extension(row_selector, c);
     yield_constr.constraint(builder, c);
 
-    for i in 0..12 {
-        for j in 0..12 {
-            let offset = if j == 0 {
-                FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_X_CALC_OFFSET + FP2_FORBENIUS_MAP_INPUT_OFFSET
-            } else if j == 1 {
-                FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_X_CALC_OFFSET + FP2_FORBENIUS_MAP_T0_CALC_OFFSET + FP_MULTIPLICATION_TOTAL_COLUMNS + REDUCED_OFFSET
-            } else if j == 2 {
-                FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_Y_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 3 {
-                FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_Y_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 4 {
-                FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_Z_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 5 {
-                FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_Z_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 6 {
-                FP12_FORBENIUS_MAP_C0_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 7 {
-                FP12_FORBENIUS_MAP_C0_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 8 {
-                FP12_FORBENIUS_MAP_C1_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 9 {
-                FP12_FORBENIUS_MAP_C1_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET
-            } else if j == 10 {
-                FP12_FORBENIUS_MAP_C2_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET
-            } else {
-                FP12_FORBENIUS_MAP_C2_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET
-            };
-            let c = builder.sub_extension(local_values[FINAL_EXP_OP_OFFSET + offset + i], local_values[output_col + j*12 + i]);
-            let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], c);
-            yield_constr.constraint(builder, c);
+    if let Some(output_col) = output_col {
+        for (j, col) in fp12_forbenius_map_result_cols(start_col + FINAL_EXP_OP_OFFSET).into_iter().enumerate() {
+            for i in 0..12 {
+                let c = builder.sub_extension(local_values[col + i], local_values[start_col + output_col + j*12 + i]);
+                let c = builder.mul_extension(row_selector, c);
+                yield_constr.constraint(builder, c);
+            }
         }
     }
 }
 
-/// Constraints `FINAL_EXP_MUL_SELECTOR` to be 1, and other op selectors to be 0 in the `FP12_MUL_ROWS` starting from `row`. Constraints the values in input columns to input of fp12_multiplication operation trace. Constraints the output of fp12_multiplication trace to the values set in output columns.
+/// Constraints `FINAL_EXP_MUL_SELECTOR` to be 1, and other op selectors to be 0 in the `FP12_MUL_ROWS` starting from `row`. Constraints the values in input columns to input of fp12_multiplication operation trace, without `x_col` for the X input set by the forbenius map of [add_constraints_forbenius_mul]. Constraints the output of fp12_multiplication trace to the values set in output columns.
 fn add_constraints_mul<F: RichField + Extendable<D>,
     const D: usize,
     FE,
@@ -449,45 +862,50 @@ fn add_constraints_mul<F: RichField + Extendable<D>,
     local_values: &[P],
     yield_constr: &mut ConstraintConsumer<P>,
     row: usize,
-    x_col: usize,
+    x_col: Option<usize>,
     y_col: usize,
     res_col: usize,
+    start_col: usize,
 ) where
     FE: FieldExtension<D2, BaseField = F>,
     P: PackedField<Scalar = FE>,
 {
+    let row_selector = final_exp_row_selector(local_values, row, start_col);
     for i in row..row + FP12_MUL_ROWS {
+        let selector = final_exp_row_selector(local_values, i, start_col);
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_FORBENIUS_MAP_SELECTOR]
+            selector *
+            local_values[start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR]
         );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]
+            selector *
+            local_values[start_col + FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]
         );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            (local_values[FINAL_EXP_MUL_SELECTOR] - P::ONES)
+            selector *
+            (local_values[start_col + FINAL_EXP_MUL_SELECTOR] - P::ONES)
         );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]
+            selector *
+            local_values[start_col + FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]
         );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_CONJUGATE_SELECTOR]
+            selector *
+            local_values[start_col + FINAL_EXP_CONJUGATE_SELECTOR]
         );
     }
     for i in 0..24*3*2 {
+        if let Some(x_col) = x_col {
+            yield_constr.constraint(
+                row_selector *
+                (local_values[start_col + x_col + i] -
+                local_values[start_col + FINAL_EXP_OP_OFFSET + FP12_MUL_X_INPUT_OFFSET + i])
+            );
+        }
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + row] *
-            (local_values[x_col + i] -
-            local_values[FINAL_EXP_OP_OFFSET + FP12_MUL_X_INPUT_OFFSET + i])
-        );
-        yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + row] *
-            (local_values[y_col + i] -
-            local_values[FINAL_EXP_OP_OFFSET + FP12_MUL_Y_INPUT_OFFSET + i])
+            row_selector *
+            (local_values[start_col + y_col + i] -
+            local_values[start_col + FINAL_EXP_OP_OFFSET + FP12_MUL_Y_INPUT_OFFSET + i])
         );
     }
     for i in 0..12 {
@@ -500,9 +918,9 @@ fn add_constraints_mul<F: RichField + Extendable<D>,
                 };
                 let offset = x_y + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*j + FP_SINGLE_REDUCED_OFFSET + i;
                 yield_constr.constraint(
-                    local_values[FINAL_EXP_ROW_SELECTORS + row] *
-                    (local_values[res_col + k*24*3 + j*12 + i] -
-                    local_values[FINAL_EXP_OP_OFFSET + offset])
+                    row_selector *
+                    (local_values[start_col + res_col + k*24*3 + j*12 + i] -
+                    local_values[start_col + FINAL_EXP_OP_OFFSET + offset])
                 );
             }
         }
@@ -516,36 +934,41 @@ pub fn add_constraints_mul_ext_circuit<F: RichField + Extendable<D>,
     yield_constr: &mut starky::constraint_consumer::RecursiveConstraintConsumer<F, D>,
     local_values: &[ExtensionTarget<D>],
     row: usize,
-    x_col: usize,
+    x_col: Option<usize>,
     y_col: usize,
     res_col: usize,
+    start_col: usize,
 ) {
+    let row_selector = final_exp_row_selector_ext_circuit(builder, local_values, row, start_col);
     for i in row..row + FP12_MUL_ROWS {
+        let selector = final_exp_row_selector_ext_circuit(builder, local_values, i, start_col);
         let one = builder.constant_extension(F::Extension::ONE);
 
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_FORBENIUS_MAP_SELECTOR]);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR]);
         yield_constr.constraint(builder, c);
 
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]);
         yield_constr.constraint(builder, c);
 
-        let c = builder.sub_extension(local_values[FINAL_EXP_MUL_SELECTOR], one);
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], c);
+        let c = builder.sub_extension(local_values[start_col + FINAL_EXP_MUL_SELECTOR], one);
+        let c = builder.mul_extension(selector, c);
         yield_constr.constraint(builder, c);
 
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]);
         yield_constr.constraint(builder, c);
 
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_CONJUGATE_SELECTOR]);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_CONJUGATE_SELECTOR]);
         yield_constr.constraint(builder, c);
     }
     for i in 0..24*3*2 {
-        let c = builder.sub_extension(local_values[x_col + i], local_values[FINAL_EXP_OP_OFFSET + FP12_MUL_X_INPUT_OFFSET + i]);
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], c);
-        yield_constr.constraint(builder, c);
+        if let Some(x_col) = x_col {
+            let c = builder.sub_extension(local_values[start_col + x_col + i], local_values[start_col + FINAL_EXP_OP_OFFSET + FP12_MUL_X_INPUT_OFFSET + i]);
+            let c = builder.mul_extension(row_selector, c);
+            yield_constr.constraint(builder, c);
+        }
 
-        let c = builder.sub_extension(local_values[y_col + i], local_values[FINAL_EXP_OP_OFFSET + FP12_MUL_Y_INPUT_OFFSET + i]);
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], c);
+        let c = builder.sub_extension(local_values[start_col + y_col + i], local_values[start_col + FINAL_EXP_OP_OFFSET + FP12_MUL_Y_INPUT_OFFSET + i]);
+        let c = builder.mul_extension(row_selector, c);
         yield_constr.constraint(builder, c);
     }
     for i in 0..12 {
@@ -557,70 +980,137 @@ pub fn add_constraints_mul_ext_circuit<F: RichField + Extendable<D>,
                     FP12_MUL_Y_CALC_OFFSET + FP6_ADDITION_TOTAL + FP6_SUBTRACTION_TOTAL
                 };
                 let offset = x_y + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*j + FP_SINGLE_REDUCED_OFFSET + i;
-                let c = builder.sub_extension(local_values[res_col + k*24*3 + j*12 + i], local_values[FINAL_EXP_OP_OFFSET + offset]);
-                let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], c);
+                let c = builder.sub_extension(local_values[start_col + res_col + k*24*3 + j*12 + i], local_values[start_col + FINAL_EXP_OP_OFFSET + offset]);
+                let c = builder.mul_extension(row_selector, c);
                 yield_constr.constraint(builder, c);
             }
         }
     }
 }
 
-/// Constraints `FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR` to be 1 and other op selectors to be 0 in the `CYCLOTOMIC_EXP_ROWS` starting from `row`. Constraints the values in input columns to input of cyclotomic_exponent operation trace. Constraints the output of cyclotomic_exponent trace to the values set in output columns.
-fn add_constraints_cyc_exp<F: RichField + Extendable<D>,
+/// Constraints the forbenius map of the values in input columns in the `FP12_FORBENIUS_MAP_ROWS` starting from `row` (see [add_constraints_forbenius]), followed by its multiplication with the values in `y_col` in the next `FP12_MUL_ROWS` (see [add_constraints_mul]). The forbenius map has no output columns, its result in the last row of the forbenius map is constrained to the X input of the multiplication in the row after it.
+fn add_constraints_forbenius_mul<F: RichField + Extendable<D>,
     const D: usize,
     FE,
     P,
     const D2: usize
 >(
     local_values: &[P],
+    next_values: &[P],
     yield_constr: &mut ConstraintConsumer<P>,
     row: usize,
     input_col: usize,
-    output_col: usize,
+    y_col: usize,
+    res_col: usize,
+    pow: usize,
+    start_col: usize,
 ) where
     FE: FieldExtension<D2, BaseField = F>,
     P: PackedField<Scalar = FE>,
 {
-    for i in row..row + CYCLOTOMIC_EXP_ROWS {
+    add_constraints_forbenius(local_values, yield_constr, row, input_col, None, pow, start_col);
+    let last_row_selector = final_exp_row_selector(local_values, row + FP12_FORBENIUS_MAP_ROWS - 1, start_col);
+    add_fp12_forbenius_mul_constraints(local_values, next_values, yield_constr, start_col + FINAL_EXP_OP_OFFSET, last_row_selector);
+    add_constraints_mul(local_values, yield_constr, row + FP12_FORBENIUS_MAP_ROWS, None, y_col, res_col, start_col);
+}
+
+pub fn add_constraints_forbenius_mul_ext_circuit<F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<F, D>,
+    yield_constr: &mut starky::constraint_consumer::RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    row: usize,
+    input_col: usize,
+    y_col: usize,
+    res_col: usize,
+    pow: usize,
+    start_col: usize,
+) {
+    add_constraints_forbenius_ext_circuit(builder, yield_constr, local_values, row, input_col, None, pow, start_col);
+    let last_row_selector = final_exp_row_selector_ext_circuit(builder, local_values, row + FP12_FORBENIUS_MAP_ROWS - 1, start_col);
+    add_fp12_forbenius_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FINAL_EXP_OP_OFFSET, last_row_selector);
+    add_constraints_mul_ext_circuit(builder, yield_constr, local_values, row + FP12_FORBENIUS_MAP_ROWS, None, y_col, res_col, start_col);
+}
+
+/// Constraints `FINAL_EXP_MUL_SELECTOR` to be 1, and other op selectors to be 0 in the `FP12_MUL_ROWS` starting from `row`. Constraints the values in input and inverse columns to inputs of fp12_multiplication operation trace. Constraints the output of fp12_multiplication trace to be 1.
+fn add_constraints_inverse<F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize
+>(
+    local_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    row: usize,
+    input_col: usize,
+    inv_col: usize,
+    start_col: usize,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let row_selector = final_exp_row_selector(local_values, row, start_col);
+    for i in row..row + FP12_MUL_ROWS {
+        let selector = final_exp_row_selector(local_values, i, start_col);
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_FORBENIUS_MAP_SELECTOR]
+            selector *
+            local_values[start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR]
         );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            (local_values[FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR] - P::ONES)
+            selector *
+            local_values[start_col + FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]
         );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_MUL_SELECTOR]
+            selector *
+            (local_values[start_col + FINAL_EXP_MUL_SELECTOR] - P::ONES)
         );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]
+            selector *
+            local_values[start_col + FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]
         );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_CONJUGATE_SELECTOR]
+            selector *
+            local_values[start_col + FINAL_EXP_CONJUGATE_SELECTOR]
         );
     }
     for i in 0..24*3*2 {
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + row] *
-            (local_values[input_col + i] -
-            local_values[FINAL_EXP_OP_OFFSET + INPUT_OFFSET + i])
+            row_selector *
+            (local_values[start_col + input_col + i] -
+            local_values[start_col + FINAL_EXP_OP_OFFSET + FP12_MUL_X_INPUT_OFFSET + i])
         );
-    }
-    for i in 0..24*3*2 {
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + row + CYCLOTOMIC_EXP_ROWS - 1] *
-            local_values[FINAL_EXP_OP_OFFSET + RES_ROW_SELECTOR_OFFSET] *
-            (local_values[output_col + i] -
-            local_values[FINAL_EXP_OP_OFFSET + Z_OFFSET + i])
+            row_selector *
+            (local_values[start_col + inv_col + i] -
+            local_values[start_col + FINAL_EXP_OP_OFFSET + FP12_MUL_Y_INPUT_OFFSET + i])
         );
     }
+    for i in 0..12 {
+        for j in 0..6 {
+            for k in 0..2 {
+                let x_y = if k == 0 {
+                    FP12_MUL_X_CALC_OFFSET + FP6_ADDITION_TOTAL
+                } else {
+                    FP12_MUL_Y_CALC_OFFSET + FP6_ADDITION_TOTAL + FP6_SUBTRACTION_TOTAL
+                };
+                let offset = x_y + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*j + FP_SINGLE_REDUCED_OFFSET + i;
+                let val = if i == 0 && j == 0 && k == 0 {
+                    P::ONES
+                } else {
+                    P::ZEROS
+                };
+                yield_constr.constraint(
+                    row_selector *
+                    (local_values[start_col + FINAL_EXP_OP_OFFSET + offset] - val)
+                );
+            }
+        }
+    }
 }
 
-pub fn add_constraints_cyc_exp_ext_circuit<F: RichField + Extendable<D>,
+pub fn add_constraints_inverse_ext_circuit<F: RichField + Extendable<D>,
     const D: usize,
 >(
     builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<F, D>,
@@ -628,42 +1118,63 @@ pub fn add_constraints_cyc_exp_ext_circuit<F: RichField + Extendable<D>,
     local_values: &[ExtensionTarget<D>],
     row: usize,
     input_col: usize,
-    output_col: usize,
+    inv_col: usize,
+    start_col: usize,
 ) {
-    for i in row..row + CYCLOTOMIC_EXP_ROWS {
-        let one = builder.constant_extension(F::Extension::ONE);
-
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_FORBENIUS_MAP_SELECTOR]);
+    let row_selector = final_exp_row_selector_ext_circuit(builder, local_values, row, start_col);
+    let one = builder.constant_extension(F::Extension::ONE);
+    let zero = builder.constant_extension(F::Extension::ZERO);
+    for i in row..row + FP12_MUL_ROWS {
+        let selector = final_exp_row_selector_ext_circuit(builder, local_values, i, start_col);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR]);
         yield_constr.constraint(builder, c);
 
-        let c = builder.sub_extension(local_values[FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR], one);
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], c);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]);
         yield_constr.constraint(builder, c);
 
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_MUL_SELECTOR]);
+        let c = builder.sub_extension(local_values[start_col + FINAL_EXP_MUL_SELECTOR], one);
+        let c = builder.mul_extension(selector, c);
         yield_constr.constraint(builder, c);
 
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]);
         yield_constr.constraint(builder, c);
 
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_CONJUGATE_SELECTOR]);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_CONJUGATE_SELECTOR]);
         yield_constr.constraint(builder, c);
     }
     for i in 0..24*3*2 {
-        let c = builder.sub_extension(local_values[input_col + i], local_values[FINAL_EXP_OP_OFFSET + INPUT_OFFSET + i]);
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], c);
+        let c = builder.sub_extension(local_values[start_col + input_col + i], local_values[start_col + FINAL_EXP_OP_OFFSET + FP12_MUL_X_INPUT_OFFSET + i]);
+        let c = builder.mul_extension(row_selector, c);
         yield_constr.constraint(builder, c);
-    }
-    for i in 0..24*3*2 {
-        let c = builder.sub_extension(local_values[output_col + i], local_values[FINAL_EXP_OP_OFFSET + Z_OFFSET + i]);
-        let c = builder.mul_extension(local_values[FINAL_EXP_OP_OFFSET + RES_ROW_SELECTOR_OFFSET], c);
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row + CYCLOTOMIC_EXP_ROWS - 1], c);
+
+        let c = builder.sub_extension(local_values[start_col + inv_col + i], local_values[start_col + FINAL_EXP_OP_OFFSET + FP12_MUL_Y_INPUT_OFFSET + i]);
+        let c = builder.mul_extension(row_selector, c);
         yield_constr.constraint(builder, c);
     }
+    for i in 0..12 {
+        for j in 0..6 {
+            for k in 0..2 {
+                let x_y = if k == 0 {
+                    FP12_MUL_X_CALC_OFFSET + FP6_ADDITION_TOTAL
+                } else {
+                    FP12_MUL_Y_CALC_OFFSET + FP6_ADDITION_TOTAL + FP6_SUBTRACTION_TOTAL
+                };
+                let offset = x_y + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*j + FP_SINGLE_REDUCED_OFFSET + i;
+                let val = if i == 0 && j == 0 && k == 0 {
+                    one
+                } else {
+                    zero
+                };
+                let c = builder.sub_extension(local_values[start_col + FINAL_EXP_OP_OFFSET + offset], val);
+                let c = builder.mul_extension(row_selector, c);
+                yield_constr.constraint(builder, c);
+            }
+        }
+    }
 }
 
-/// Constraints `FINAL_EXP_CONJUGATE_SELECTOR` to be 1 and other op selectors to be 0 in the `CONJUGATE_ROWS` starting from `row`. Constraints the values in input columns to input of fp12_conjugate operation trace. Constraints the output of fp12_conjugate trace to the values set in output columns.
-pub fn add_constraints_conjugate<F: RichField + Extendable<D>,
+/// Constraints `FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR` to be 1 and other op selectors to be 0 in the `CYCLOTOMIC_EXP_ROWS` starting from `row`. Constraints the values in input columns to input of cyclotomic_exponent operation trace. Constraints the output of cyclotomic_exponent trace to the values set in output columns.
+fn add_constraints_cyc_exp<F: RichField + Extendable<D>,
     const D: usize,
     FE,
     P,
@@ -674,46 +1185,131 @@ pub fn add_constraints_conjugate<F: RichField + Extendable<D>,
     row: usize,
     input_col: usize,
     output_col: usize,
+    start_col: usize,
 ) where
     FE: FieldExtension<D2, BaseField = F>,
     P: PackedField<Scalar = FE>,
 {
-    yield_constr.constraint(
-        local_values[FINAL_EXP_ROW_SELECTORS + row] *
-        local_values[FINAL_EXP_FORBENIUS_MAP_SELECTOR]
-    );
-    yield_constr.constraint(
-        local_values[FINAL_EXP_ROW_SELECTORS + row] *
-        local_values[FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]
-    );
-    yield_constr.constraint(
-        local_values[FINAL_EXP_ROW_SELECTORS + row] *
-        local_values[FINAL_EXP_MUL_SELECTOR]
-    );
-    yield_constr.constraint(
-        local_values[FINAL_EXP_ROW_SELECTORS + row] *
-        local_values[FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]
-    );
-    yield_constr.constraint(
-        local_values[FINAL_EXP_ROW_SELECTORS + row] *
-        (local_values[FINAL_EXP_CONJUGATE_SELECTOR] - P::ONES)
-    );
-    for i in 0..24*3*2 {
+    let row_selector = final_exp_row_selector(local_values, row, start_col);
+    let last_row_selector = final_exp_row_selector(local_values, row + CYCLOTOMIC_EXP_ROWS - 1, start_col);
+    for i in row..row + CYCLOTOMIC_EXP_ROWS {
+        let selector = final_exp_row_selector(local_values, i, start_col);
+        yield_constr.constraint(
+            selector *
+            local_values[start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR]
+        );
+        yield_constr.constraint(
+            selector *
+            (local_values[start_col + FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR] - P::ONES)
+        );
+        yield_constr.constraint(
+            selector *
+            local_values[start_col + FINAL_EXP_MUL_SELECTOR]
+        );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + row] *
-            (local_values[input_col + i] -
-            local_values[FINAL_EXP_OP_OFFSET + FP12_CONJUGATE_INPUT_OFFSET + i])
+            selector *
+            local_values[start_col + FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]
+        );
+        yield_constr.constraint(
+            selector *
+            local_values[start_col + FINAL_EXP_CONJUGATE_SELECTOR]
         );
     }
+    add_fp12_equal_constraints(local_values, yield_constr, start_col + input_col, start_col + FINAL_EXP_OP_OFFSET + INPUT_OFFSET, Some(row_selector));
     for i in 0..24*3*2 {
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + row] *
-            (local_values[output_col + i] -
-            local_values[FINAL_EXP_OP_OFFSET + FP12_CONJUGATE_OUTPUT_OFFSET + i])
+            last_row_selector *
+            local_values[start_col + FINAL_EXP_OP_OFFSET + RES_ROW_SELECTOR_OFFSET] *
+            (local_values[start_col + output_col + i] -
+            local_values[start_col + FINAL_EXP_OP_OFFSET + Z_OFFSET + i])
         );
     }
 }
 
+pub fn add_constraints_cyc_exp_ext_circuit<F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<F, D>,
+    yield_constr: &mut starky::constraint_consumer::RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    row: usize,
+    input_col: usize,
+    output_col: usize,
+    start_col: usize,
+) {
+    let row_selector = final_exp_row_selector_ext_circuit(builder, local_values, row, start_col);
+    let last_row_selector = final_exp_row_selector_ext_circuit(builder, local_values, row + CYCLOTOMIC_EXP_ROWS - 1, start_col);
+    for i in row..row + CYCLOTOMIC_EXP_ROWS {
+        let selector = final_exp_row_selector_ext_circuit(builder, local_values, i, start_col);
+        let one = builder.constant_extension(F::Extension::ONE);
+
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR]);
+        yield_constr.constraint(builder, c);
+
+        let c = builder.sub_extension(local_values[start_col + FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR], one);
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint(builder, c);
+
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_MUL_SELECTOR]);
+        yield_constr.constraint(builder, c);
+
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]);
+        yield_constr.constraint(builder, c);
+
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_CONJUGATE_SELECTOR]);
+        yield_constr.constraint(builder, c);
+    }
+    add_fp12_equal_constraints_ext_circuit(builder, yield_constr, local_values, start_col + input_col, start_col + FINAL_EXP_OP_OFFSET + INPUT_OFFSET, Some(row_selector));
+    for i in 0..24*3*2 {
+        let c = builder.sub_extension(local_values[start_col + output_col + i], local_values[start_col + FINAL_EXP_OP_OFFSET + Z_OFFSET + i]);
+        let c = builder.mul_extension(local_values[start_col + FINAL_EXP_OP_OFFSET + RES_ROW_SELECTOR_OFFSET], c);
+        let c = builder.mul_extension(last_row_selector, c);
+        yield_constr.constraint(builder, c);
+    }
+}
+
+/// Constraints `FINAL_EXP_CONJUGATE_SELECTOR` to be 1 and other op selectors to be 0 in the `CONJUGATE_ROWS` starting from `row`. Constraints the values in input columns to input of fp12_conjugate operation trace. Constraints the output of fp12_conjugate trace to the values set in output columns.
+pub fn add_constraints_conjugate<F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize
+>(
+    local_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    row: usize,
+    input_col: usize,
+    output_col: usize,
+    start_col: usize,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let row_selector = final_exp_row_selector(local_values, row, start_col);
+    yield_constr.constraint(
+        row_selector *
+        local_values[start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR]
+    );
+    yield_constr.constraint(
+        row_selector *
+        local_values[start_col + FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]
+    );
+    yield_constr.constraint(
+        row_selector *
+        local_values[start_col + FINAL_EXP_MUL_SELECTOR]
+    );
+    yield_constr.constraint(
+        row_selector *
+        local_values[start_col + FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]
+    );
+    yield_constr.constraint(
+        row_selector *
+        (local_values[start_col + FINAL_EXP_CONJUGATE_SELECTOR] - P::ONES)
+    );
+    add_fp12_equal_constraints(local_values, yield_constr, start_col + input_col, start_col + FINAL_EXP_OP_OFFSET + FP12_CONJUGATE_INPUT_OFFSET, Some(row_selector));
+    add_fp12_equal_constraints(local_values, yield_constr, start_col + output_col, start_col + FINAL_EXP_OP_OFFSET + FP12_CONJUGATE_OUTPUT_OFFSET, Some(row_selector));
+}
+
 pub fn add_constraints_conjugate_ext_circuit<F: RichField + Extendable<D>,
     const D: usize,
 >(
@@ -723,35 +1319,29 @@ pub fn add_constraints_conjugate_ext_circuit<F: RichField + Extendable<D>,
     row: usize,
     input_col: usize,
     output_col: usize,
+    start_col: usize,
 ) {
+    let row_selector = final_exp_row_selector_ext_circuit(builder, local_values, row, start_col);
     let one = builder.constant_extension(F::Extension::ONE);
 
-    let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], local_values[FINAL_EXP_FORBENIUS_MAP_SELECTOR]);
+    let c = builder.mul_extension(row_selector, local_values[start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR]);
     yield_constr.constraint(builder, c);
 
-    let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], local_values[FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]);
+    let c = builder.mul_extension(row_selector, local_values[start_col + FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]);
     yield_constr.constraint(builder, c);
 
-    let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], local_values[FINAL_EXP_MUL_SELECTOR]);
+    let c = builder.mul_extension(row_selector, local_values[start_col + FINAL_EXP_MUL_SELECTOR]);
     yield_constr.constraint(builder, c);
 
-    let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], local_values[FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]);
+    let c = builder.mul_extension(row_selector, local_values[start_col + FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]);
     yield_constr.constraint(builder, c);
 
-    let c = builder.sub_extension(local_values[FINAL_EXP_CONJUGATE_SELECTOR], one);
-    let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], c);
+    let c = builder.sub_extension(local_values[start_col + FINAL_EXP_CONJUGATE_SELECTOR], one);
+    let c = builder.mul_extension(row_selector, c);
     yield_constr.constraint(builder, c);
 
-    for i in 0..24*3*2 {
-        let c = builder.sub_extension(local_values[input_col + i], local_values[FINAL_EXP_OP_OFFSET + FP12_CONJUGATE_INPUT_OFFSET + i]);
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], c);
-        yield_constr.constraint(builder, c);
-    }
-    for i in 0..24*3*2 {
-        let c = builder.sub_extension(local_values[output_col + i], local_values[FINAL_EXP_OP_OFFSET + FP12_CONJUGATE_OUTPUT_OFFSET + i]);
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], c);
-        yield_constr.constraint(builder, c);
-    }
+    add_fp12_equal_constraints_ext_circuit(builder, yield_constr, local_values, start_col + input_col, start_col + FINAL_EXP_OP_OFFSET + FP12_CONJUGATE_INPUT_OFFSET, Some(row_selector));
+    add_fp12_equal_constraints_ext_circuit(builder, yield_constr, local_values, start_col + output_col, start_col + FINAL_EXP_OP_OFFSET + FP12_CONJUGATE_OUTPUT_OFFSET, Some(row_selector));
 }
 
 /// Constraints `FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR` to be 1 and other op selectors to be 0 in the `CYCLOTOMIC_SQ_ROWS` starting from `row`. Constraints the values in input columns to input of cyclotomic_square operation trace. Constraints the output of cyclotomic_square trace to the values set in output columns.
@@ -766,39 +1356,36 @@ pub fn add_constraints_cyc_sq<F: RichField + Extendable<D>,
     row: usize,
     input_col: usize,
     output_col: usize,
+    start_col: usize,
 ) where
     FE: FieldExtension<D2, BaseField = F>,
     P: PackedField<Scalar = FE>,
 {
+    let row_selector = final_exp_row_selector(local_values, row, start_col);
     for i in row..row + CYCLOTOMIC_SQ_ROWS {
+        let selector = final_exp_row_selector(local_values, i, start_col);
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_FORBENIUS_MAP_SELECTOR]
+            selector *
+            local_values[start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR]
         );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]
+            selector *
+            local_values[start_col + FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]
         );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_MUL_SELECTOR]
+            selector *
+            local_values[start_col + FINAL_EXP_MUL_SELECTOR]
         );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            (local_values[FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR] - P::ONES)
+            selector *
+            (local_values[start_col + FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR] - P::ONES)
         );
         yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + i] *
-            local_values[FINAL_EXP_CONJUGATE_SELECTOR]
-        );
-    }
-    for i in 0..24*3*2 {
-        yield_constr.constraint(
-            local_values[FINAL_EXP_ROW_SELECTORS + row] *
-            (local_values[input_col + i] -
-            local_values[FINAL_EXP_OP_OFFSET + CYCLOTOMIC_SQ_INPUT_OFFSET + i])
+            selector *
+            local_values[start_col + FINAL_EXP_CONJUGATE_SELECTOR]
         );
     }
+    add_fp12_equal_constraints(local_values, yield_constr, start_col + input_col, start_col + FINAL_EXP_OP_OFFSET + CYCLOTOMIC_SQ_INPUT_OFFSET, Some(row_selector));
     for i in 0..12 {
         for j in 0..6 {
             let c_offset = if j == 0 {
@@ -817,9 +1404,9 @@ pub fn add_constraints_cyc_sq<F: RichField + Extendable<D>,
             for k in 0..2 {
                 let offset = c_offset + FP2_ADDITION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*k + FP_SINGLE_REDUCED_OFFSET;
                 yield_constr.constraint(
-                    local_values[FINAL_EXP_ROW_SELECTORS + row] *
-                    (local_values[FINAL_EXP_OP_OFFSET + offset + i] -
-                    local_values[output_col + j*24 + k*12 + i])
+                    row_selector *
+                    (local_values[start_col + FINAL_EXP_OP_OFFSET + offset + i] -
+                    local_values[start_col + output_col + j*24 + k*12 + i])
                 );
             }
         }
@@ -835,31 +1422,30 @@ pub fn add_constraints_cyc_sq_ext_circuit<F: RichField + Extendable<D>,
     row: usize,
     input_col: usize,
     output_col: usize,
+    start_col: usize,
 ) {
+    let row_selector = final_exp_row_selector_ext_circuit(builder, local_values, row, start_col);
     for i in row..row + CYCLOTOMIC_SQ_ROWS {
+        let selector = final_exp_row_selector_ext_circuit(builder, local_values, i, start_col);
         let one = builder.constant_extension(F::Extension::ONE);
 
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_FORBENIUS_MAP_SELECTOR]);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR]);
         yield_constr.constraint(builder, c);
 
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]);
         yield_constr.constraint(builder, c);
 
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_MUL_SELECTOR]);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_MUL_SELECTOR]);
         yield_constr.constraint(builder, c);
 
-        let c = builder.sub_extension(local_values[FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR], one);
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], c);
+        let c = builder.sub_extension(local_values[start_col + FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR], one);
+        let c = builder.mul_extension(selector, c);
         yield_constr.constraint(builder, c);
 
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], local_values[FINAL_EXP_CONJUGATE_SELECTOR]);
-        yield_constr.constraint(builder, c);
-    }
-    for i in 0..24*3*2 {
-        let c = builder.sub_extension(local_values[input_col + i], local_values[FINAL_EXP_OP_OFFSET + CYCLOTOMIC_SQ_INPUT_OFFSET + i]);
-        let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], c);
+        let c = builder.mul_extension(selector, local_values[start_col + FINAL_EXP_CONJUGATE_SELECTOR]);
         yield_constr.constraint(builder, c);
     }
+    add_fp12_equal_constraints_ext_circuit(builder, yield_constr, local_values, start_col + input_col, start_col + FINAL_EXP_OP_OFFSET + CYCLOTOMIC_SQ_INPUT_OFFSET, Some(row_selector));
     for i in 0..12 {
         for j in 0..6 {
             let c_offset = if j == 0 {
@@ -877,8 +1463,8 @@ pub fn add_constraints_cyc_sq_ext_circuit<F: RichField + Extendable<D>,
             };
             for k in 0..2 {
                 let offset = c_offset + FP2_ADDITION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*k + FP_SINGLE_REDUCED_OFFSET;
-                let c = builder.sub_extension(local_values[FINAL_EXP_OP_OFFSET + offset + i], local_values[output_col + j*24 + k*12 + i]);
-                let c = builder.mul_extension(local_values[FINAL_EXP_ROW_SELECTORS + row], c);
+                let c = builder.sub_extension(local_values[start_col + FINAL_EXP_OP_OFFSET + offset + i], local_values[start_col + output_col + j*24 + k*12 + i]);
+                let c = builder.mul_extension(row_selector, c);
                 yield_constr.constraint(builder, c);
             }
         }
@@ -889,250 +1475,552 @@ pub fn add_constraints_cyc_sq_ext_circuit<F: RichField + Extendable<D>,
     Constraints for final_exponentiate trace (super::native::Fp12::final_exponentiate)
     * Constraints input of trace to public inputs
     * Constraints T31 of trace (result of final exponentiate) to public inputs
-    * Constraints `FINAL_EXP_ROW_SELECTORS` for row=0, to 1 in the first row.
-    * Constraints the `FINAL_EXP_ROW_SELECTORS` to rotate right by 1 in each next row
-    * Constraints `FINAL_EXP_ROW_SELECTORS` for row=8192, to 1 in the last row.
+    * Constraints the bits of the row counter to be 0 in the first row, and the number they make to increase by 1 in each next row.
+    * Constraints exactly one low and one high row selector to be 1, the ones numbered by the low and high bits of the row counter.
+    * Constraints the operation selectors to be boolean, with at most one of them set in each row.
     * Constraints the inputs of the trace and the Ti's to be same across all rows. Ti's defined in the native function.
     * Constraints the operation selectors, inputs and outputs for the operation for each Ti.
     * Constraints for all the operations with operation selector, i.e. those constraints will only be active if the operation selector is set 1. 
 */
 
-// Implement constraint generator
-impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FinalExponentiateStark<F, D> {
-    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
-    where
-        FE: FieldExtension<D2, BaseField = F>,
-        P: PackedField<Scalar = FE>;
-
-    fn eval_packed_generic<FE, P, const D2: usize>(
-        &self,
-        vars: &Self::EvaluationFrame<FE, P, D2>,
-        yield_constr: &mut ConstraintConsumer<P>,
-    ) where
-        FE: FieldExtension<D2, BaseField = F>,
-        P: PackedField<Scalar = FE>,
-    {
-        let local_values = vars.get_local_values();
-        let next_values = vars.get_next_values();
-        let public_inputs = vars.get_public_inputs();
+const fn max_degree(degrees: &[usize]) -> usize {
+    let mut max = 0;
+    let mut i = 0;
+    while i < degrees.len() {
+        if degrees[i] > max {
+            max = degrees[i];
+        }
+        i += 1;
+    }
+    max
+}
 
-        // ----
-        for i in 0..24*3*2 {
-            yield_constr.constraint(
-                local_values[FINAL_EXP_INPUT_OFFSET + i] -
-                public_inputs[PIS_INPUT_OFFSET + i]
-            );
-            yield_constr.constraint(
-                local_values[FINAL_EXP_T31_OFFSET + i] -
-                public_inputs[PIS_OUTPUT_OFFSET + i]
-            );
+/// Degree of the constraints of [add_final_exponentiate_constraints] and of the starks built on it. The operation gadgets are gated by their operation selectors, which adds one to the degree they declare, and the constraints gated by a row selector (see [final_exp_row_selector]) have degree 3. The fp12 forbenius map and the cyclotomic exponent reach 5.
+pub const FINAL_EXP_CONSTRAINT_DEGREE: usize = max_degree(&[
+    FP12_FORBENIUS_MAP_CONSTRAINT_DEGREE + 1,
+    FP12_MUL_CONSTRAINT_DEGREE + 1,
+    CYCLOTOMIC_EXP_CONSTRAINT_DEGREE + 1,
+    FP12_CONJUGATE_CONSTRAINT_DEGREE + 1,
+    CYCLOTOMIC_SQ_CONSTRAINT_DEGREE + 1,
+    3,
+]);
+
+/// Constraints the [final_exponentiate](super::native::Fp12::final_exponentiate) computation filled by [fill_trace_final_exponentiate]. Constraints the row counter and the row selectors, constraints the operation selectors to be boolean with at most one of them set in each row, constraints the input to be same across all rows and the Ti's to be same across their live rows (see [final_exp_live_rows]), then constraints each Ti computation and the operation traces gated by their selectors. The input and output (T31) are left for the caller to tie with public inputs or other columns, T31 being set only from `T31_ROW` to the last row. All the columns are offset by `start_col`.
+/// The constraints have degree at most [FINAL_EXP_CONSTRAINT_DEGREE].
+pub fn add_final_exponentiate_constraints<F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    num_rows: usize,
+    start_col: usize,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    for k in 0..FINAL_EXP_ROW_BITS {
+        let bit = local_values[start_col + FINAL_EXP_ROW_BITS_OFFSET + k];
+        yield_constr.constraint(bit * (P::ONES - bit));
+        yield_constr.constraint_first_row(bit);
+    }
+    yield_constr.constraint_transition(
+        final_exp_row_number(next_values, 0..FINAL_EXP_ROW_BITS, start_col) -
+        final_exp_row_number(local_values, 0..FINAL_EXP_ROW_BITS, start_col) -
+        P::ONES
+    );
+    for (selectors_col, selectors_total, bits) in [
+        (FINAL_EXP_ROW_LOW_SELECTORS, FINAL_EXP_ROW_LOW_SELECTORS_TOTAL, 0..FINAL_EXP_ROW_LOW_BITS),
+        (FINAL_EXP_ROW_HIGH_SELECTORS, FINAL_EXP_ROW_HIGH_SELECTORS_TOTAL, FINAL_EXP_ROW_LOW_BITS..FINAL_EXP_ROW_BITS),
+    ] {
+        let mut selectors_sum = P::ZEROS;
+        let mut selected = P::ZEROS;
+        for j in 0..selectors_total {
+            let selector = local_values[start_col + selectors_col + j];
+            yield_constr.constraint(selector * (P::ONES - selector));
+            selectors_sum = selectors_sum + selector;
+            selected = selected + selector * FE::from_canonical_usize(j);
         }
+        yield_constr.constraint(selectors_sum - P::ONES);
+        yield_constr.constraint(selected - final_exp_row_number(local_values, bits, start_col));
+    }
+    // The operation constraints only tie the op selectors in the rows of each operation, so they are also constrained in every row to be boolean and at most one of them to be set.
+    let mut op_selectors_sum = P::ZEROS;
+    for col in FINAL_EXP_FORBENIUS_MAP_SELECTOR..FINAL_EXP_INPUT_OFFSET {
+        let selector = local_values[start_col + col];
+        yield_constr.constraint(selector * (P::ONES - selector));
+        op_selectors_sum = op_selectors_sum + selector;
+    }
+    yield_constr.constraint(op_selectors_sum * (P::ONES - op_selectors_sum));
 
-        for i in 0..self.num_rows {
-            let val = if i == 0 {
-                P::ONES
-            } else {
-                P::ZEROS
-            };
-            yield_constr.constraint_first_row(
-                local_values[FINAL_EXP_ROW_SELECTORS + i] - val
-            );
+    for i in 0..24*3*2 {
+        yield_constr.constraint_transition(
+            local_values[start_col + FINAL_EXP_INPUT_OFFSET + i] -
+            next_values[start_col + FINAL_EXP_INPUT_OFFSET + i]
+        );
+    }
+    for (col, boundary_rows) in final_exp_slot_boundaries(num_rows) {
+        let mut boundary = P::ZEROS;
+        for row in boundary_rows {
+            boundary = boundary + final_exp_row_selector(local_values, row, start_col);
         }
-        for i in 0..self.num_rows-1 {
+        for i in 0..24*3*2 {
             yield_constr.constraint_transition(
-                local_values[FINAL_EXP_ROW_SELECTORS + i] -
-                next_values[FINAL_EXP_ROW_SELECTORS + i + 1]
-            );
-        }
-        for i in 0..self.num_rows {
-            let val = if i == self.num_rows-1 {
-                P::ONES
-            } else {
-                P::ZEROS
-            };
-            yield_constr.constraint_last_row(
-                local_values[FINAL_EXP_ROW_SELECTORS + i] - val
+                (P::ONES - boundary) *
+                (local_values[start_col + col + i] - next_values[start_col + col + i])
             );
         }
+    }
+
+    // T0
+    add_constraints_forbenius(local_values, yield_constr, T0_ROW, FINAL_EXP_INPUT_OFFSET, Some(FINAL_EXP_T0_OFFSET), 6, start_col);
+
+    // T1
+    add_constraints_mul(local_values, yield_constr, T1_ROW, Some(FINAL_EXP_T1_OFFSET), FINAL_EXP_INPUT_OFFSET, FINAL_EXP_T0_OFFSET, start_col);
+
+    // T2, T3
+    add_constraints_forbenius_mul(local_values, next_values, yield_constr, T2_ROW, FINAL_EXP_T1_OFFSET, FINAL_EXP_T1_OFFSET, FINAL_EXP_T3_OFFSET, 2, start_col);
+
+    // T4
+    add_constraints_cyc_exp(local_values, yield_constr, T4_ROW, FINAL_EXP_T3_OFFSET, FINAL_EXP_T4_OFFSET, start_col);
+
+    // T5
+    add_constraints_conjugate(local_values, yield_constr, T5_ROW, FINAL_EXP_T4_OFFSET, FINAL_EXP_T5_OFFSET, start_col);
+
+    // T6
+    add_constraints_cyc_sq(local_values, yield_constr, T6_ROW, FINAL_EXP_T3_OFFSET, FINAL_EXP_T6_OFFSET, start_col);
+
+    // T7
+    add_constraints_conjugate(local_values, yield_constr, T7_ROW, FINAL_EXP_T6_OFFSET, FINAL_EXP_T7_OFFSET, start_col);
+
+    // T8
+    add_constraints_mul(local_values, yield_constr, T8_ROW, Some(FINAL_EXP_T7_OFFSET), FINAL_EXP_T5_OFFSET, FINAL_EXP_T8_OFFSET, start_col);
+
+    // T9
+    add_constraints_cyc_exp(local_values, yield_constr, T9_ROW, FINAL_EXP_T8_OFFSET, FINAL_EXP_T9_OFFSET, start_col);
+
+    // T10
+    add_constraints_conjugate(local_values, yield_constr, T10_ROW, FINAL_EXP_T9_OFFSET, FINAL_EXP_T10_OFFSET, start_col);
+
+    // T11
+    add_constraints_cyc_exp(local_values, yield_constr, T11_ROW, FINAL_EXP_T10_OFFSET, FINAL_EXP_T11_OFFSET, start_col);
+
+    // T12
+    add_constraints_conjugate(local_values, yield_constr, T12_ROW, FINAL_EXP_T11_OFFSET, FINAL_EXP_T12_OFFSET, start_col);
+
+    // T13
+    add_constraints_cyc_exp(local_values, yield_constr, T13_ROW, FINAL_EXP_T12_OFFSET, FINAL_EXP_T13_OFFSET, start_col);
+
+    // T14
+    add_constraints_conjugate(local_values, yield_constr, T14_ROW, FINAL_EXP_T13_OFFSET, FINAL_EXP_T14_OFFSET, start_col);
+
+    // T15
+    add_constraints_cyc_sq(local_values, yield_constr, T15_ROW, FINAL_EXP_T5_OFFSET, FINAL_EXP_T15_OFFSET, start_col);
+
+    // T16
+    add_constraints_mul(local_values, yield_constr, T16_ROW, Some(FINAL_EXP_T14_OFFSET), FINAL_EXP_T15_OFFSET, FINAL_EXP_T16_OFFSET, start_col);
+
+    // T17
+    add_constraints_cyc_exp(local_values, yield_constr, T17_ROW, FINAL_EXP_T16_OFFSET, FINAL_EXP_T17_OFFSET, start_col);
+
+    // T18
+    add_constraints_conjugate(local_values, yield_constr, T18_ROW, FINAL_EXP_T17_OFFSET, FINAL_EXP_T18_OFFSET, start_col);
+
+    // T19
+    add_constraints_mul(local_values, yield_constr, T19_ROW, Some(FINAL_EXP_T5_OFFSET), FINAL_EXP_T12_OFFSET, FINAL_EXP_T19_OFFSET, start_col);
+
+    // T20
+    add_constraints_forbenius(local_values, yield_constr, T20_ROW, FINAL_EXP_T19_OFFSET, Some(FINAL_EXP_T20_OFFSET), 2, start_col);
+
+    // T21
+    add_constraints_mul(local_values, yield_constr, T21_ROW, Some(FINAL_EXP_T10_OFFSET), FINAL_EXP_T3_OFFSET, FINAL_EXP_T21_OFFSET, start_col);
+
+    // T23
+    add_constraints_conjugate(local_values, yield_constr, T23_ROW, FINAL_EXP_T3_OFFSET, FINAL_EXP_T23_OFFSET, start_col);
+
+    // T24
+    add_constraints_mul(local_values, yield_constr, T24_ROW, Some(FINAL_EXP_T16_OFFSET), FINAL_EXP_T23_OFFSET, FINAL_EXP_T24_OFFSET, start_col);
+
+    // T25
+    add_constraints_forbenius(local_values, yield_constr, T25_ROW, FINAL_EXP_T24_OFFSET, Some(FINAL_EXP_T25_OFFSET), 1, start_col);
+
+    // T26
+    add_constraints_conjugate(local_values, yield_constr, T26_ROW, FINAL_EXP_T8_OFFSET, FINAL_EXP_T26_OFFSET, start_col);
 
+    // T27
+    add_constraints_mul(local_values, yield_constr, T27_ROW, Some(FINAL_EXP_T18_OFFSET), FINAL_EXP_T26_OFFSET, FINAL_EXP_T27_OFFSET, start_col);
+
+    // T28
+    add_constraints_mul(local_values, yield_constr, T28_ROW, Some(FINAL_EXP_T27_OFFSET), FINAL_EXP_T3_OFFSET, FINAL_EXP_T28_OFFSET, start_col);
+
+    // T22, T29
+    add_constraints_forbenius_mul(local_values, next_values, yield_constr, T22_ROW, FINAL_EXP_T21_OFFSET, FINAL_EXP_T20_OFFSET, FINAL_EXP_T29_OFFSET, 3, start_col);
+
+    // T30
+    add_constraints_mul(local_values, yield_constr, T30_ROW, Some(FINAL_EXP_T29_OFFSET), FINAL_EXP_T25_OFFSET, FINAL_EXP_T30_OFFSET, start_col);
+
+    // T31
+    add_constraints_mul(local_values, yield_constr, T31_ROW, Some(FINAL_EXP_T30_OFFSET), FINAL_EXP_T28_OFFSET, FINAL_EXP_T31_OFFSET, start_col);
+
+    // Input * Input_inv == 1
+    add_constraints_inverse(local_values, yield_constr, INPUT_INV_ROW, FINAL_EXP_INPUT_OFFSET, FINAL_EXP_INPUT_INV_OFFSET, start_col);
+
+    add_fp12_forbenius_map_constraints(local_values, next_values, yield_constr, start_col + FINAL_EXP_OP_OFFSET, Some(local_values[start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR]));
+    add_fp12_multiplication_constraints(local_values, next_values, yield_constr, start_col + FINAL_EXP_OP_OFFSET, Some(local_values[start_col + FINAL_EXP_MUL_SELECTOR]));
+    add_cyclotomic_exp_constraints(local_values, next_values, yield_constr, start_col + FINAL_EXP_OP_OFFSET, Some(local_values[start_col + FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]));
+    add_fp12_conjugate_constraints(local_values, yield_constr, start_col + FINAL_EXP_OP_OFFSET, Some(local_values[start_col + FINAL_EXP_CONJUGATE_SELECTOR]));
+    add_cyclotomic_sq_constraints(local_values, next_values, yield_constr, start_col + FINAL_EXP_OP_OFFSET, Some(local_values[start_col + FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]));
+}
+
+pub fn add_final_exponentiate_constraints_ext_circuit<F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<F, D>,
+    yield_constr: &mut starky::constraint_consumer::RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    num_rows: usize,
+    start_col: usize,
+) {
+    let one = builder.constant_extension(F::Extension::ONE);
+    let zero = builder.constant_extension(F::Extension::ZERO);
+
+    for k in 0..FINAL_EXP_ROW_BITS {
+        let bit = local_values[start_col + FINAL_EXP_ROW_BITS_OFFSET + k];
+        let not_bit = builder.sub_extension(one, bit);
+        let c = builder.mul_extension(bit, not_bit);
+        yield_constr.constraint(builder, c);
+        yield_constr.constraint_first_row(builder, bit);
+    }
+    let next_counter = final_exp_row_number_ext_circuit(builder, next_values, 0..FINAL_EXP_ROW_BITS, start_col);
+    let counter = final_exp_row_number_ext_circuit(builder, local_values, 0..FINAL_EXP_ROW_BITS, start_col);
+    let c = builder.sub_extension(next_counter, counter);
+    let c = builder.sub_extension(c, one);
+    yield_constr.constraint_transition(builder, c);
+    for (selectors_col, selectors_total, bits) in [
+        (FINAL_EXP_ROW_LOW_SELECTORS, FINAL_EXP_ROW_LOW_SELECTORS_TOTAL, 0..FINAL_EXP_ROW_LOW_BITS),
+        (FINAL_EXP_ROW_HIGH_SELECTORS, FINAL_EXP_ROW_HIGH_SELECTORS_TOTAL, FINAL_EXP_ROW_LOW_BITS..FINAL_EXP_ROW_BITS),
+    ] {
+        let mut selectors_sum = zero;
+        let mut selected = zero;
+        for j in 0..selectors_total {
+            let selector = local_values[start_col + selectors_col + j];
+            let not_selector = builder.sub_extension(one, selector);
+            let c = builder.mul_extension(selector, not_selector);
+            yield_constr.constraint(builder, c);
+            selectors_sum = builder.add_extension(selectors_sum, selector);
+            let index = builder.constant_extension(F::Extension::from_canonical_usize(j));
+            selected = builder.mul_add_extension(selector, index, selected);
+        }
+        let c = builder.sub_extension(selectors_sum, one);
+        yield_constr.constraint(builder, c);
+        let row_number = final_exp_row_number_ext_circuit(builder, local_values, bits, start_col);
+        let c = builder.sub_extension(selected, row_number);
+        yield_constr.constraint(builder, c);
+    }
+    let mut op_selectors_sum = zero;
+    for col in FINAL_EXP_FORBENIUS_MAP_SELECTOR..FINAL_EXP_INPUT_OFFSET {
+        let selector = local_values[start_col + col];
+        let not_selector = builder.sub_extension(one, selector);
+        let c = builder.mul_extension(selector, not_selector);
+        yield_constr.constraint(builder, c);
+        op_selectors_sum = builder.add_extension(op_selectors_sum, selector);
+    }
+    let not_op_selectors_sum = builder.sub_extension(one, op_selectors_sum);
+    let c = builder.mul_extension(op_selectors_sum, not_op_selectors_sum);
+    yield_constr.constraint(builder, c);
+    for i in 0..24*3*2 {
+        let c = builder.sub_extension(local_values[start_col + FINAL_EXP_INPUT_OFFSET + i], next_values[start_col + FINAL_EXP_INPUT_OFFSET + i]);
+        yield_constr.constraint_transition(builder, c);
+    }
+    for (col, boundary_rows) in final_exp_slot_boundaries(num_rows) {
+        let mut boundary = zero;
+        for row in boundary_rows {
+            let row_selector = final_exp_row_selector_ext_circuit(builder, local_values, row, start_col);
+            boundary = builder.add_extension(boundary, row_selector);
+        }
+        let not_boundary = builder.sub_extension(one, boundary);
         for i in 0..24*3*2 {
-            yield_constr.constraint_transition(
-                local_values[FINAL_EXP_INPUT_OFFSET + i] -
-                next_values[FINAL_EXP_INPUT_OFFSET + i]
-            );
-            for j in 0..32 {
-                let t = if j == 0 {
-                    FINAL_EXP_T0_OFFSET
-                } else if j == 1 {
-                    FINAL_EXP_T1_OFFSET
-                } else if j == 2 {
-                    FINAL_EXP_T2_OFFSET
-                } else if j == 3 {
-                    FINAL_EXP_T3_OFFSET
-                } else if j == 4 {
-                    FINAL_EXP_T4_OFFSET
-                } else if j == 5 {
-                    FINAL_EXP_T5_OFFSET
-                } else if j == 6 {
-                    FINAL_EXP_T6_OFFSET
-                } else if j == 7 {
-                    FINAL_EXP_T7_OFFSET
-                } else if j == 8 {
-                    FINAL_EXP_T8_OFFSET
-                } else if j == 9 {
-                    FINAL_EXP_T9_OFFSET
-                } else if j == 10 {
-                    FINAL_EXP_T10_OFFSET
-                } else if j == 11 {
-                    FINAL_EXP_T11_OFFSET
-                } else if j == 12 {
-                    FINAL_EXP_T12_OFFSET
-                } else if j == 13 {
-                    FINAL_EXP_T13_OFFSET
-                } else if j == 14 {
-                    FINAL_EXP_T14_OFFSET
-                } else if j == 15 {
-                    FINAL_EXP_T15_OFFSET
-                } else if j == 16 {
-                    FINAL_EXP_T16_OFFSET
-                } else if j == 17 {
-                    FINAL_EXP_T17_OFFSET
-                } else if j == 18 {
-                    FINAL_EXP_T18_OFFSET
-                } else if j == 19 {
-                    FINAL_EXP_T19_OFFSET
-                } else if j == 20 {
-                    FINAL_EXP_T20_OFFSET
-                } else if j == 21 {
-                    FINAL_EXP_T21_OFFSET
-                } else if j == 22 {
-                    FINAL_EXP_T22_OFFSET
-                } else if j == 23 {
-                    FINAL_EXP_T23_OFFSET
-                } else if j == 24 {
-                    FINAL_EXP_T24_OFFSET
-                } else if j == 25 {
-                    FINAL_EXP_T25_OFFSET
-                } else if j == 26 {
-                    FINAL_EXP_T26_OFFSET
-                } else if j == 27 {
-                    FINAL_EXP_T27_OFFSET
-                } else if j == 28 {
-                    FINAL_EXP_T28_OFFSET
-                } else if j == 29 {
-                    FINAL_EXP_T29_OFFSET
-                } else if j == 30 {
-                    FINAL_EXP_T30_OFFSET
-                } else {
-                    FINAL_EXP_T31_OFFSET
-                };
-                yield_constr.constraint_transition(
-                    local_values[t+i] - next_values[t+i]
-                );
-            }
+            let c = builder.sub_extension(local_values[start_col + col + i], next_values[start_col + col + i]);
+            let c = builder.mul_extension(not_boundary, c);
+            yield_constr.constraint_transition(builder, c);
         }
+    }
 
-        // T0
-        add_constraints_forbenius(local_values, yield_constr, T0_ROW, FINAL_EXP_INPUT_OFFSET, FINAL_EXP_T0_OFFSET, 6);
+    // T0
+    add_constraints_forbenius_ext_circuit(builder, yield_constr, local_values, T0_ROW, FINAL_EXP_INPUT_OFFSET, Some(FINAL_EXP_T0_OFFSET), 6, start_col);
 
-        // T1
-        add_constraints_mul(local_values, yield_constr, T1_ROW, FINAL_EXP_T1_OFFSET, FINAL_EXP_INPUT_OFFSET, FINAL_EXP_T0_OFFSET);
+    // T1
+    add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T1_ROW, Some(FINAL_EXP_T1_OFFSET), FINAL_EXP_INPUT_OFFSET, FINAL_EXP_T0_OFFSET, start_col);
 
-        // T2
-        add_constraints_forbenius(local_values, yield_constr, T2_ROW, FINAL_EXP_T1_OFFSET, FINAL_EXP_T2_OFFSET, 2);
+    // T2, T3
+    add_constraints_forbenius_mul_ext_circuit(builder, yield_constr, local_values, next_values, T2_ROW, FINAL_EXP_T1_OFFSET, FINAL_EXP_T1_OFFSET, FINAL_EXP_T3_OFFSET, 2, start_col);
 
-        // T3
-        add_constraints_mul(local_values, yield_constr, T3_ROW, FINAL_EXP_T2_OFFSET, FINAL_EXP_T1_OFFSET, FINAL_EXP_T3_OFFSET);
+    // T4
+    add_constraints_cyc_exp_ext_circuit(builder, yield_constr, local_values, T4_ROW, FINAL_EXP_T3_OFFSET, FINAL_EXP_T4_OFFSET, start_col);
 
-        // T4
-        add_constraints_cyc_exp(local_values, yield_constr, T4_ROW, FINAL_EXP_T3_OFFSET, FINAL_EXP_T4_OFFSET);
+    // T5
+    add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T5_ROW, FINAL_EXP_T4_OFFSET, FINAL_EXP_T5_OFFSET, start_col);
 
-        // T5
-        add_constraints_conjugate(local_values, yield_constr, T5_ROW, FINAL_EXP_T4_OFFSET, FINAL_EXP_T5_OFFSET);
+    // T6
+    add_constraints_cyc_sq_ext_circuit(builder, yield_constr, local_values, T6_ROW, FINAL_EXP_T3_OFFSET, FINAL_EXP_T6_OFFSET, start_col);
 
-        // T6
-        add_constraints_cyc_sq(local_values, yield_constr, T6_ROW, FINAL_EXP_T3_OFFSET, FINAL_EXP_T6_OFFSET);
+    // T7
+    add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T7_ROW, FINAL_EXP_T6_OFFSET, FINAL_EXP_T7_OFFSET, start_col);
 
-        // T7
-        add_constraints_conjugate(local_values, yield_constr, T7_ROW, FINAL_EXP_T6_OFFSET, FINAL_EXP_T7_OFFSET);
+    // T8
+    add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T8_ROW, Some(FINAL_EXP_T7_OFFSET), FINAL_EXP_T5_OFFSET, FINAL_EXP_T8_OFFSET, start_col);
 
-        // T8
-        add_constraints_mul(local_values, yield_constr, T8_ROW, FINAL_EXP_T7_OFFSET, FINAL_EXP_T5_OFFSET, FINAL_EXP_T8_OFFSET);
+    // T9
+    add_constraints_cyc_exp_ext_circuit(builder, yield_constr, local_values, T9_ROW, FINAL_EXP_T8_OFFSET, FINAL_EXP_T9_OFFSET, start_col);
 
-        // T9
-        add_constraints_cyc_exp(local_values, yield_constr, T9_ROW, FINAL_EXP_T8_OFFSET, FINAL_EXP_T9_OFFSET);
+    // T10
+    add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T10_ROW, FINAL_EXP_T9_OFFSET, FINAL_EXP_T10_OFFSET, start_col);
 
-        // T10
-        add_constraints_conjugate(local_values, yield_constr, T10_ROW, FINAL_EXP_T9_OFFSET, FINAL_EXP_T10_OFFSET);
+    // T11
+    add_constraints_cyc_exp_ext_circuit(builder, yield_constr, local_values, T11_ROW, FINAL_EXP_T10_OFFSET, FINAL_EXP_T11_OFFSET, start_col);
 
-        // T11
-        add_constraints_cyc_exp(local_values, yield_constr, T11_ROW, FINAL_EXP_T10_OFFSET, FINAL_EXP_T11_OFFSET);
+    // T12
+    add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T12_ROW, FINAL_EXP_T11_OFFSET, FINAL_EXP_T12_OFFSET, start_col);
 
-        // T12
-        add_constraints_conjugate(local_values, yield_constr, T12_ROW, FINAL_EXP_T11_OFFSET, FINAL_EXP_T12_OFFSET);
+    // T13
+    add_constraints_cyc_exp_ext_circuit(builder, yield_constr, local_values, T13_ROW, FINAL_EXP_T12_OFFSET, FINAL_EXP_T13_OFFSET, start_col);
 
-        // T13
-        add_constraints_cyc_exp(local_values, yield_constr, T13_ROW, FINAL_EXP_T12_OFFSET, FINAL_EXP_T13_OFFSET);
+    // T14
+    add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T14_ROW, FINAL_EXP_T13_OFFSET, FINAL_EXP_T14_OFFSET, start_col);
 
-        // T14
-        add_constraints_conjugate(local_values, yield_constr, T14_ROW, FINAL_EXP_T13_OFFSET, FINAL_EXP_T14_OFFSET);
+    // T15
+    add_constraints_cyc_sq_ext_circuit(builder, yield_constr, local_values, T15_ROW, FINAL_EXP_T5_OFFSET, FINAL_EXP_T15_OFFSET, start_col);
 
-        // T15
-        add_constraints_cyc_sq(local_values, yield_constr, T15_ROW, FINAL_EXP_T5_OFFSET, FINAL_EXP_T15_OFFSET);
+    // T16
+    add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T16_ROW, Some(FINAL_EXP_T14_OFFSET), FINAL_EXP_T15_OFFSET, FINAL_EXP_T16_OFFSET, start_col);
 
-        // T16
-        add_constraints_mul(local_values, yield_constr, T16_ROW, FINAL_EXP_T14_OFFSET, FINAL_EXP_T15_OFFSET, FINAL_EXP_T16_OFFSET);
+    // T17
+    add_constraints_cyc_exp_ext_circuit(builder, yield_constr, local_values, T17_ROW, FINAL_EXP_T16_OFFSET, FINAL_EXP_T17_OFFSET, start_col);
 
-        // T17
-        add_constraints_cyc_exp(local_values, yield_constr, T17_ROW, FINAL_EXP_T16_OFFSET, FINAL_EXP_T17_OFFSET);
+    // T18
+    add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T18_ROW, FINAL_EXP_T17_OFFSET, FINAL_EXP_T18_OFFSET, start_col);
 
-        // T18
-        add_constraints_conjugate(local_values, yield_constr, T18_ROW, FINAL_EXP_T17_OFFSET, FINAL_EXP_T18_OFFSET);
+    // T19
+    add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T19_ROW, Some(FINAL_EXP_T5_OFFSET), FINAL_EXP_T12_OFFSET, FINAL_EXP_T19_OFFSET, start_col);
 
-        // T19
-        add_constraints_mul(local_values, yield_constr, T19_ROW, FINAL_EXP_T5_OFFSET, FINAL_EXP_T12_OFFSET, FINAL_EXP_T19_OFFSET);
+    // T20
+    add_constraints_forbenius_ext_circuit(builder, yield_constr, local_values, T20_ROW, FINAL_EXP_T19_OFFSET, Some(FINAL_EXP_T20_OFFSET), 2, start_col);
 
-        // T20
-        add_constraints_forbenius(local_values, yield_constr, T20_ROW, FINAL_EXP_T19_OFFSET, FINAL_EXP_T20_OFFSET, 2);
+    // T21
+    add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T21_ROW, Some(FINAL_EXP_T10_OFFSET), FINAL_EXP_T3_OFFSET, FINAL_EXP_T21_OFFSET, start_col);
 
-        // T21
-        add_constraints_mul(local_values, yield_constr, T21_ROW, FINAL_EXP_T10_OFFSET, FINAL_EXP_T3_OFFSET, FINAL_EXP_T21_OFFSET);
+    // T23
+    add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T23_ROW, FINAL_EXP_T3_OFFSET, FINAL_EXP_T23_OFFSET, start_col);
 
-        // T22
-        add_constraints_forbenius(local_values, yield_constr, T22_ROW, FINAL_EXP_T21_OFFSET, FINAL_EXP_T22_OFFSET, 3);
+    // T24
+    add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T24_ROW, Some(FINAL_EXP_T16_OFFSET), FINAL_EXP_T23_OFFSET, FINAL_EXP_T24_OFFSET, start_col);
 
-        // T23
-        add_constraints_conjugate(local_values, yield_constr, T23_ROW, FINAL_EXP_T3_OFFSET, FINAL_EXP_T23_OFFSET);
+    // T25
+    add_constraints_forbenius_ext_circuit(builder, yield_constr, local_values, T25_ROW, FINAL_EXP_T24_OFFSET, Some(FINAL_EXP_T25_OFFSET), 1, start_col);
 
-        // T24
-        add_constraints_mul(local_values, yield_constr, T24_ROW, FINAL_EXP_T16_OFFSET, FINAL_EXP_T23_OFFSET, FINAL_EXP_T24_OFFSET);
+    // T26
+    add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T26_ROW, FINAL_EXP_T8_OFFSET, FINAL_EXP_T26_OFFSET, start_col);
 
-        // T25
-        add_constraints_forbenius(local_values, yield_constr, T25_ROW, FINAL_EXP_T24_OFFSET, FINAL_EXP_T25_OFFSET, 1);
+    // T27
+    add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T27_ROW, Some(FINAL_EXP_T18_OFFSET), FINAL_EXP_T26_OFFSET, FINAL_EXP_T27_OFFSET, start_col);
 
-        // T26
-        add_constraints_conjugate(local_values, yield_constr, T26_ROW, FINAL_EXP_T8_OFFSET, FINAL_EXP_T26_OFFSET);
+    // T28
+    add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T28_ROW, Some(FINAL_EXP_T27_OFFSET), FINAL_EXP_T3_OFFSET, FINAL_EXP_T28_OFFSET, start_col);
 
-        // T27
-        add_constraints_mul(local_values, yield_constr, T27_ROW, FINAL_EXP_T18_OFFSET, FINAL_EXP_T26_OFFSET, FINAL_EXP_T27_OFFSET);
+    // T22, T29
+    add_constraints_forbenius_mul_ext_circuit(builder, yield_constr, local_values, next_values, T22_ROW, FINAL_EXP_T21_OFFSET, FINAL_EXP_T20_OFFSET, FINAL_EXP_T29_OFFSET, 3, start_col);
 
-        // T28
-        add_constraints_mul(local_values, yield_constr, T28_ROW, FINAL_EXP_T27_OFFSET, FINAL_EXP_T3_OFFSET, FINAL_EXP_T28_OFFSET);
+    // T30
+    add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T30_ROW, Some(FINAL_EXP_T29_OFFSET), FINAL_EXP_T25_OFFSET, FINAL_EXP_T30_OFFSET, start_col);
 
-        // T29
-        add_constraints_mul(local_values, yield_constr, T29_ROW, FINAL_EXP_T20_OFFSET, FINAL_EXP_T22_OFFSET, FINAL_EXP_T29_OFFSET);
+    // T31
+    add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T31_ROW, Some(FINAL_EXP_T30_OFFSET), FINAL_EXP_T28_OFFSET, FINAL_EXP_T31_OFFSET, start_col);
 
-        // T30
-        add_constraints_mul(local_values, yield_constr, T30_ROW, FINAL_EXP_T29_OFFSET, FINAL_EXP_T25_OFFSET, FINAL_EXP_T30_OFFSET);
+    // Input * Input_inv == 1
+    add_constraints_inverse_ext_circuit(builder, yield_constr, local_values, INPUT_INV_ROW, FINAL_EXP_INPUT_OFFSET, FINAL_EXP_INPUT_INV_OFFSET, start_col);
 
-        // T31
-        add_constraints_mul(local_values, yield_constr, T31_ROW, FINAL_EXP_T30_OFFSET, FINAL_EXP_T28_OFFSET, FINAL_EXP_T31_OFFSET);
+    add_fp12_forbenius_map_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FINAL_EXP_OP_OFFSET, Some(local_values[start_col + FINAL_EXP_FORBENIUS_MAP_SELECTOR]));
+    add_fp12_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FINAL_EXP_OP_OFFSET, Some(local_values[start_col + FINAL_EXP_MUL_SELECTOR]));
+    add_cyclotomic_exp_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FINAL_EXP_OP_OFFSET, Some(local_values[start_col + FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]));
+    add_fp12_conjugate_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FINAL_EXP_OP_OFFSET, Some(local_values[start_col + FINAL_EXP_CONJUGATE_SELECTOR]));
+    add_cyclotomic_sq_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FINAL_EXP_OP_OFFSET, Some(local_values[start_col + FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]));
+}
 
-        add_fp12_forbenius_map_constraints(local_values, next_values, yield_constr, FINAL_EXP_OP_OFFSET, Some(local_values[FINAL_EXP_FORBENIUS_MAP_SELECTOR]));
-        add_fp12_multiplication_constraints(local_values, next_values, yield_constr, FINAL_EXP_OP_OFFSET, Some(local_values[FINAL_EXP_MUL_SELECTOR]));
-        add_cyclotomic_exp_constraints(local_values, next_values, yield_constr, FINAL_EXP_OP_OFFSET, Some(local_values[FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]));
-        add_fp12_conjugate_constraints(local_values, yield_constr, FINAL_EXP_OP_OFFSET, Some(local_values[FINAL_EXP_CONJUGATE_SELECTOR]));
-        add_cyclotomic_sq_constraints(local_values, next_values, yield_constr, FINAL_EXP_OP_OFFSET, Some(local_values[FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]));
+/// Constraints the range checks of the public inputs filled by [fill_trace_public_input_range_checks]. In the row whose selector (see [final_exp_row_selector], for the final exponentiate gadget at `final_exp_col`) is set for i < `PUBLIC_INPUTS / 12`, constraints the checked limbs to be the i-th fp element of the public inputs. The selector of the range check is constrained to be on exactly in these rows.
+///
+/// The limbs, and the limbs of the range check sum, are constrained to be u32 by their bit decompositions, and the carries of the range check to be bits, so that the range check proves that the element is less than the field prime. Also constraints the most significant bit of the sum and the last carry to be 0, which the range check leaves unconstrained. All the columns are offset by `start_col`.
+pub fn add_public_input_range_check_constraints<F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    public_inputs: &[FE],
+    yield_constr: &mut ConstraintConsumer<P>,
+    final_exp_col: usize,
+    start_col: usize,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let mut row_selectors_sum = P::ZEROS;
+    for i in 0..PUBLIC_INPUTS/12 {
+        let row_selector = final_exp_row_selector(local_values, i, final_exp_col);
+        row_selectors_sum = row_selectors_sum + row_selector;
+        for j in 0..12 {
+            yield_constr.constraint(
+                row_selector *
+                (local_values[start_col + PIS_RANGE_CHECK_INPUT_OFFSET + j] - public_inputs[i*12 + j])
+            );
+        }
+    }
+    let selector = local_values[start_col + PIS_RANGE_CHECK_OFFSET + RANGE_CHECK_SELECTOR_OFFSET];
+    yield_constr.constraint(selector - row_selectors_sum);
+
+    for i in 0..12 {
+        let bit_col = start_col + PIS_RANGE_CHECK_BITS_OFFSET + i*32;
+        for j in 0..32 {
+            yield_constr.constraint(local_values[bit_col + j] * (P::ONES - local_values[bit_col + j]));
+        }
+        let val_reconstructed = (0..32).fold(P::ZEROS, |acc, j| acc + local_values[bit_col + j] * FE::from_canonical_u64(1 << j));
+        yield_constr.constraint(val_reconstructed - local_values[start_col + PIS_RANGE_CHECK_INPUT_OFFSET + i]);
+    }
+    for i in 0..11 {
+        let bit_col = start_col + PIS_RANGE_CHECK_SUM_BITS_OFFSET + i*32;
+        for j in 0..32 {
+            yield_constr.constraint(selector * local_values[bit_col + j] * (P::ONES - local_values[bit_col + j]));
+        }
+        let val_reconstructed = (0..32).fold(P::ZEROS, |acc, j| acc + local_values[bit_col + j] * FE::from_canonical_u64(1 << j));
+        yield_constr.constraint(selector * (val_reconstructed - local_values[start_col + PIS_RANGE_CHECK_OFFSET + RANGE_CHECK_SUM_OFFSET + i]));
+    }
+    for i in 0..12 {
+        let carry = local_values[start_col + PIS_RANGE_CHECK_OFFSET + RANGE_CHECK_SUM_CARRY_OFFSET + i];
+        yield_constr.constraint(selector * carry * (P::ONES - carry));
+    }
+    let bit_col = start_col + PIS_RANGE_CHECK_OFFSET + RANGE_CHECK_BIT_DECOMP_OFFSET;
+    for j in 0..32 {
+        yield_constr.constraint(selector * local_values[bit_col + j] * (P::ONES - local_values[bit_col + j]));
+    }
+    yield_constr.constraint(selector * local_values[bit_col + 31]);
+    yield_constr.constraint(selector * local_values[start_col + PIS_RANGE_CHECK_OFFSET + RANGE_CHECK_SUM_CARRY_OFFSET + 11]);
+    add_range_check_constraints(local_values, yield_constr, start_col + PIS_RANGE_CHECK_OFFSET, None);
+}
+
+pub fn add_public_input_range_check_constraints_ext_circuit<F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<F, D>,
+    yield_constr: &mut starky::constraint_consumer::RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    public_inputs: &[ExtensionTarget<D>],
+    final_exp_col: usize,
+    start_col: usize,
+) {
+    let one = builder.constant_extension(F::Extension::ONE);
+    let mut row_selectors_sum = builder.constant_extension(F::Extension::ZERO);
+    for i in 0..PUBLIC_INPUTS/12 {
+        let row_selector = final_exp_row_selector_ext_circuit(builder, local_values, i, final_exp_col);
+        row_selectors_sum = builder.add_extension(row_selectors_sum, row_selector);
+        for j in 0..12 {
+            let c = builder.sub_extension(local_values[start_col + PIS_RANGE_CHECK_INPUT_OFFSET + j], public_inputs[i*12 + j]);
+            let c = builder.mul_extension(row_selector, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+    let selector = local_values[start_col + PIS_RANGE_CHECK_OFFSET + RANGE_CHECK_SELECTOR_OFFSET];
+    let c = builder.sub_extension(selector, row_selectors_sum);
+    yield_constr.constraint(builder, c);
+
+    for i in 0..12 {
+        let bit_col = start_col + PIS_RANGE_CHECK_BITS_OFFSET + i*32;
+        let mut val_reconstructed = builder.constant_extension(F::Extension::ZERO);
+        for j in 0..32 {
+            let not_bit = builder.sub_extension(one, local_values[bit_col + j]);
+            let c = builder.mul_extension(local_values[bit_col + j], not_bit);
+            yield_constr.constraint(builder, c);
+            let coeff = builder.constant_extension(F::Extension::from_canonical_u64(1 << j));
+            val_reconstructed = builder.mul_add_extension(local_values[bit_col + j], coeff, val_reconstructed);
+        }
+        let c = builder.sub_extension(val_reconstructed, local_values[start_col + PIS_RANGE_CHECK_INPUT_OFFSET + i]);
+        yield_constr.constraint(builder, c);
+    }
+    for i in 0..11 {
+        let bit_col = start_col + PIS_RANGE_CHECK_SUM_BITS_OFFSET + i*32;
+        let mut val_reconstructed = builder.constant_extension(F::Extension::ZERO);
+        for j in 0..32 {
+            let not_bit = builder.sub_extension(one, local_values[bit_col + j]);
+            let c = builder.mul_many_extension([selector, local_values[bit_col + j], not_bit]);
+            yield_constr.constraint(builder, c);
+            let coeff = builder.constant_extension(F::Extension::from_canonical_u64(1 << j));
+            val_reconstructed = builder.mul_add_extension(local_values[bit_col + j], coeff, val_reconstructed);
+        }
+        let c = builder.sub_extension(val_reconstructed, local_values[start_col + PIS_RANGE_CHECK_OFFSET + RANGE_CHECK_SUM_OFFSET + i]);
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint(builder, c);
+    }
+    for i in 0..12 {
+        let carry = local_values[start_col + PIS_RANGE_CHECK_OFFSET + RANGE_CHECK_SUM_CARRY_OFFSET + i];
+        let not_carry = builder.sub_extension(one, carry);
+        let c = builder.mul_many_extension([selector, carry, not_carry]);
+        yield_constr.constraint(builder, c);
+    }
+    let bit_col = start_col + PIS_RANGE_CHECK_OFFSET + RANGE_CHECK_BIT_DECOMP_OFFSET;
+    for j in 0..32 {
+        let not_bit = builder.sub_extension(one, local_values[bit_col + j]);
+        let c = builder.mul_many_extension([selector, local_values[bit_col + j], not_bit]);
+        yield_constr.constraint(builder, c);
+    }
+    let c = builder.mul_extension(selector, local_values[bit_col + 31]);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(selector, local_values[start_col + PIS_RANGE_CHECK_OFFSET + RANGE_CHECK_SUM_CARRY_OFFSET + 11]);
+    yield_constr.constraint(builder, c);
+    add_range_check_constraints_ext_circuit(builder, yield_constr, local_values, start_col + PIS_RANGE_CHECK_OFFSET, None);
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FinalExponentiateStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        // ----
+        for i in 0..24*3*2 {
+            yield_constr.constraint(
+                local_values[FINAL_EXP_INPUT_OFFSET + i] -
+                public_inputs[PIS_INPUT_OFFSET + i]
+            );
+            yield_constr.constraint_last_row(
+                local_values[FINAL_EXP_T31_OFFSET + i] -
+                public_inputs[PIS_OUTPUT_OFFSET + i]
+            );
+        }
+        if self.output_is_one {
+            for i in 0..24*3*2 {
+                let one = if i == 0 { P::ONES } else { P::ZEROS };
+                yield_constr.constraint_last_row(local_values[FINAL_EXP_T31_OFFSET + i] - one);
+            }
+        }
+
+        add_final_exponentiate_constraints(local_values, next_values, yield_constr, self.num_rows, 0);
+        add_public_input_range_check_constraints(local_values, public_inputs, yield_constr, 0, FINAL_EXP_PIS_RANGE_CHECK_OFFSET);
     }
 
     type EvaluationFrameTarget =
@@ -1154,212 +2042,1065 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FinalExponent
             yield_constr.constraint(builder, c);
 
             let c = builder.sub_extension(local_values[FINAL_EXP_T31_OFFSET + i], public_inputs[PIS_OUTPUT_OFFSET + i]);
-            yield_constr.constraint(builder, c);
+            yield_constr.constraint_last_row(builder, c);
+        }
+        if self.output_is_one {
+            let one = builder.one_extension();
+            for i in 0..24*3*2 {
+                let c = if i == 0 {
+                    builder.sub_extension(local_values[FINAL_EXP_T31_OFFSET + i], one)
+                } else {
+                    local_values[FINAL_EXP_T31_OFFSET + i]
+                };
+                yield_constr.constraint_last_row(builder, c);
+            }
         }
 
-        let one = builder.constant_extension(F::Extension::ONE);
-        let zero = builder.constant_extension(F::Extension::ZERO);
+        add_final_exponentiate_constraints_ext_circuit(builder, yield_constr, local_values, next_values, self.num_rows, 0);
+        add_public_input_range_check_constraints_ext_circuit(builder, yield_constr, local_values, public_inputs, 0, FINAL_EXP_PIS_RANGE_CHECK_OFFSET);
+    }
 
-        for i in 0..self.num_rows {
-            let val = if i == 0 {
-                one
-            } else {
-                zero
-            };
-            let c = builder.sub_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], val);
-            yield_constr.constraint_first_row(builder, c);
+    fn constraint_degree(&self) -> usize {
+        FINAL_EXP_CONSTRAINT_DEGREE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::{field::types::{Field, PrimeField64}, plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::config::{check_field, PoseidonGoldilocksQuarticConfig};
+    use crate::constants::{G1_GENERATOR, G2_GENERATOR};
+    use crate::native::{miller_loop, negate_fp, Fp, Fp12, Fp2, NativeError};
+    use crate::serialization::{deserialize_proof, serialize_proof};
+    use crate::utils::{num_constraints, StarkPublicInputs};
+    use crate::miller_loop::MILLER_LOOP_ROWS;
+    use crate::pairing::PairingStark;
+
+    use plonky2::{field::{extension::{Extendable, FieldExtension}, packed::PackedField}, hash::hash_types::RichField, iop::ext_target::ExtensionTarget};
+    use starky::{constraint_consumer::ConstraintConsumer, evaluation_frame::{StarkEvaluationFrame, StarkFrame}, stark::Stark, stark_testing::test_stark_low_degree};
+    use crate::fp12::{add_cyclotomic_exp_constraints, add_cyclotomic_exp_constraints_ext_circuit, add_cyclotomic_sq_constraints, add_cyclotomic_sq_constraints_ext_circuit, add_fp12_conjugate_constraints, add_fp12_conjugate_constraints_ext_circuit, add_fp12_forbenius_map_constraints, add_fp12_forbenius_map_constraints_ext_circuit, add_fp12_multiplication_constraints, add_fp12_multiplication_constraints_ext_circuit, CYCLOTOMIC_EXP_CONSTRAINT_DEGREE, CYCLOTOMIC_EXP_TOTAL_COLUMNS, CYCLOTOMIC_SQ_CONSTRAINT_DEGREE, FP12_CONJUGATE_CONSTRAINT_DEGREE, FP12_FORBENIUS_MAP_CONSTRAINT_DEGREE, FP12_MUL_CONSTRAINT_DEGREE};
+
+    #[cfg(feature = "debug-constraints")]
+    use crate::debug_constraints::debug_check_trace;
+    #[cfg(feature = "debug-constraints")]
+    use crate::native::{get_u32_vec_from_literal, modulus};
+    #[cfg(feature = "debug-constraints")]
+    use num_bigint::BigUint;
+    #[cfg(feature = "debug-constraints")]
+    use crate::fp12::Z_OFFSET;
+    #[cfg(feature = "debug-constraints")]
+    use super::{add_constraints_conjugate, add_constraints_conjugate_ext_circuit, add_constraints_cyc_exp, add_constraints_cyc_exp_ext_circuit, add_constraints_cyc_sq, add_constraints_cyc_sq_ext_circuit, add_constraints_forbenius, add_constraints_forbenius_ext_circuit, add_constraints_mul, add_constraints_mul_ext_circuit, final_exp_slot_boundaries, FINAL_EXP_T13_OFFSET, FINAL_EXP_T14_OFFSET, FINAL_EXP_T15_OFFSET, FINAL_EXP_T16_OFFSET, FINAL_EXP_T17_OFFSET, FINAL_EXP_T19_OFFSET, FINAL_EXP_T20_OFFSET, FINAL_EXP_T5_OFFSET, FINAL_EXP_CONJUGATE_SELECTOR, FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR, FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR, FINAL_EXP_MUL_SELECTOR, FINAL_EXP_ROW_HIGH_SELECTORS_TOTAL, FINAL_EXP_T10_OFFSET, FP12_MUL_ROWS, T12_ROW, T14_ROW, T15_ROW, T16_ROW, T17_ROW, T18_ROW, T20_ROW, T21_ROW};
+    use super::{add_final_exponentiate_constraints, FINAL_EXP_CONSTRAINT_DEGREE, add_final_exponentiate_constraints_ext_circuit, add_public_input_range_check_constraints, add_public_input_range_check_constraints_ext_circuit, fill_trace_div, fill_trace_final_exponentiate, fill_trace_public_input_range_checks, final_exp_live_rows, final_exp_row_budget, final_exp_row_selector, FinalExponentiatePublicInputs, FinalExponentiateStark, verify_final_exp, FINAL_EXP_FORBENIUS_MAP_SELECTOR, FINAL_EXP_INPUT_OFFSET, FINAL_EXP_MAX_ROWS, FINAL_EXP_ROW_BITS, FINAL_EXP_ROW_BITS_OFFSET, FINAL_EXP_ROW_HIGH_SELECTORS, FINAL_EXP_ROW_LOW_BITS, FINAL_EXP_ROW_LOW_SELECTORS, FINAL_EXP_ROW_LOW_SELECTORS_TOTAL, FINAL_EXP_OP_OFFSET, FINAL_EXP_SLOT0_OFFSET, FINAL_EXP_T31_OFFSET, FINAL_EXP_TOTAL_COLUMNS, INPUT_INV_ROW, PIS_INPUT_OFFSET, PIS_OUTPUT_OFFSET, PIS_RANGE_CHECK_TOTAL, PUBLIC_INPUTS, T4_ROW, CYCLOTOMIC_EXP_ROWS, TOTAL_COLUMNS, TOTAL_ROW};
+
+    // Columns before the final exponentiate gadget, which are left unconstrained.
+    const OFFSET_BASE: usize = 7;
+    const OFFSET_COLUMNS: usize = OFFSET_BASE + FINAL_EXP_TOTAL_COLUMNS;
+
+    /// Final exponentiate gadget placed at `OFFSET_BASE`, with the same public inputs as [FinalExponentiateStark].
+    #[derive(Clone, Copy)]
+    struct OffsetFinalExponentiateStark<F: RichField + Extendable<D>, const D: usize> {
+        num_rows: usize,
+        _f: std::marker::PhantomData<F>,
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> OffsetFinalExponentiateStark<F, D> {
+        fn generate_trace(&self, x: Fp12) -> Vec<[F; OFFSET_COLUMNS]> {
+            let mut trace = vec![[F::ZERO; OFFSET_COLUMNS]; self.num_rows];
+            for (row, values) in trace.iter_mut().enumerate() {
+                for col in 0..OFFSET_BASE {
+                    values[col] = F::from_canonical_usize(row + col);
+                }
+            }
+            fill_trace_final_exponentiate(&mut trace, &x, OFFSET_BASE).unwrap();
+            trace
         }
-        for i in 0..self.num_rows-1 {
-            let c = builder.sub_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], next_values[FINAL_EXP_ROW_SELECTORS + i + 1]);
-            yield_constr.constraint_transition(builder, c);
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for OffsetFinalExponentiateStark<F, D> {
+        type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, OFFSET_COLUMNS, PUBLIC_INPUTS>
+        where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>;
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: &Self::EvaluationFrame<FE, P, D2>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            let local_values = vars.get_local_values();
+            let next_values = vars.get_next_values();
+            let public_inputs = vars.get_public_inputs();
+
+            for i in 0..24*3*2 {
+                yield_constr.constraint(
+                    local_values[OFFSET_BASE + FINAL_EXP_INPUT_OFFSET + i] -
+                    public_inputs[PIS_INPUT_OFFSET + i]
+                );
+                yield_constr.constraint_last_row(
+                    local_values[OFFSET_BASE + FINAL_EXP_T31_OFFSET + i] -
+                    public_inputs[PIS_OUTPUT_OFFSET + i]
+                );
+            }
+            add_final_exponentiate_constraints(local_values, next_values, yield_constr, self.num_rows, OFFSET_BASE);
         }
-        for i in 0..self.num_rows {
-            let val = if i == self.num_rows-1 {
-                one
-            } else {
-                zero
-            };
-            let c = builder.sub_extension(local_values[FINAL_EXP_ROW_SELECTORS + i], val);
-            yield_constr.constraint_last_row(builder, c);
+
+        type EvaluationFrameTarget =
+            StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, OFFSET_COLUMNS, PUBLIC_INPUTS>;
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<F, D>,
+            vars: &Self::EvaluationFrameTarget,
+            yield_constr: &mut starky::constraint_consumer::RecursiveConstraintConsumer<F, D>,
+        ) {
+            let local_values = vars.get_local_values();
+            let next_values = vars.get_next_values();
+            let public_inputs = vars.get_public_inputs();
+
+            for i in 0..24*3*2 {
+                let c = builder.sub_extension(local_values[OFFSET_BASE + FINAL_EXP_INPUT_OFFSET + i], public_inputs[PIS_INPUT_OFFSET + i]);
+                yield_constr.constraint(builder, c);
+
+                let c = builder.sub_extension(local_values[OFFSET_BASE + FINAL_EXP_T31_OFFSET + i], public_inputs[PIS_OUTPUT_OFFSET + i]);
+                yield_constr.constraint_last_row(builder, c);
+            }
+            add_final_exponentiate_constraints_ext_circuit(builder, yield_constr, local_values, next_values, self.num_rows, OFFSET_BASE);
         }
-        for i in 0..24*3*2 {
-            let c = builder.sub_extension(local_values[FINAL_EXP_INPUT_OFFSET + i], next_values[FINAL_EXP_INPUT_OFFSET + i]);
-            yield_constr.constraint_transition(builder, c);
-            for j in 0..32 {
-                let t = if j == 0 {
-                    FINAL_EXP_T0_OFFSET
-                } else if j == 1 {
-                    FINAL_EXP_T1_OFFSET
-                } else if j == 2 {
-                    FINAL_EXP_T2_OFFSET
-                } else if j == 3 {
-                    FINAL_EXP_T3_OFFSET
-                } else if j == 4 {
-                    FINAL_EXP_T4_OFFSET
-                } else if j == 5 {
-                    FINAL_EXP_T5_OFFSET
-                } else if j == 6 {
-                    FINAL_EXP_T6_OFFSET
-                } else if j == 7 {
-                    FINAL_EXP_T7_OFFSET
-                } else if j == 8 {
-                    FINAL_EXP_T8_OFFSET
-                } else if j == 9 {
-                    FINAL_EXP_T9_OFFSET
-                } else if j == 10 {
-                    FINAL_EXP_T10_OFFSET
-                } else if j == 11 {
-                    FINAL_EXP_T11_OFFSET
-                } else if j == 12 {
-                    FINAL_EXP_T12_OFFSET
-                } else if j == 13 {
-                    FINAL_EXP_T13_OFFSET
-                } else if j == 14 {
-                    FINAL_EXP_T14_OFFSET
-                } else if j == 15 {
-                    FINAL_EXP_T15_OFFSET
-                } else if j == 16 {
-                    FINAL_EXP_T16_OFFSET
-                } else if j == 17 {
-                    FINAL_EXP_T17_OFFSET
-                } else if j == 18 {
-                    FINAL_EXP_T18_OFFSET
-                } else if j == 19 {
-                    FINAL_EXP_T19_OFFSET
-                } else if j == 20 {
-                    FINAL_EXP_T20_OFFSET
-                } else if j == 21 {
-                    FINAL_EXP_T21_OFFSET
-                } else if j == 22 {
-                    FINAL_EXP_T22_OFFSET
-                } else if j == 23 {
-                    FINAL_EXP_T23_OFFSET
-                } else if j == 24 {
-                    FINAL_EXP_T24_OFFSET
-                } else if j == 25 {
-                    FINAL_EXP_T25_OFFSET
-                } else if j == 26 {
-                    FINAL_EXP_T26_OFFSET
-                } else if j == 27 {
-                    FINAL_EXP_T27_OFFSET
-                } else if j == 28 {
-                    FINAL_EXP_T28_OFFSET
-                } else if j == 29 {
-                    FINAL_EXP_T29_OFFSET
-                } else if j == 30 {
-                    FINAL_EXP_T30_OFFSET
-                } else {
-                    FINAL_EXP_T31_OFFSET
-                };
-                let c = builder.sub_extension(local_values[t+i], next_values[t+i]);
-                yield_constr.constraint_transition(builder, c);
+
+        fn constraint_degree(&self) -> usize {
+            FINAL_EXP_CONSTRAINT_DEGREE
+        }
+    }
+
+    fn test_input() -> Fp12 {
+        Fp12::from_str(["2181142506194812233868097821779361009807326315828153071050324314717744521676711650071190927260282422014627435089208",
+            "3266212670671256779826008414922395966600400122723332695666308996296105595418386213353825620535446475769829785237189",
+            "3280330655787598118299804758957910379684134784964426565939861302675766948066521588562898980898245868682162153155911",
+            "333668007718210311816046938245689395232794221928183840372182128979685996722059498232053963662509478803385469716056",
+            "1650925102445293819378017648160637800280351377141029658990698964033732511884552459036333864590686008335846481856882",
+            "3925133212240632255860280854235945320282874550806663137653784505923891479863770370026712801361887427462376126696706",
+            "2444089052091192833501409081021321360112867893942837175254954622703299880931587618210267154453853513743076365662283",
+            "3142914221549818039420055870398197863502329018278548609868118001898418737390067291084903575823960349378631910285921",
+            "1952057563719092278028425573632201081234877258097927010867141683896274170520489868686437644804596724295624637397077",
+            "254131389529427774765960554324483250584297364987873642087841623909520980093766889928789173976296059957431962608694",
+            "1385128161651935856764061834929068245137081648283968377947672499160305921464670953157912428887005620142387465559867",
+            "101302147352745188522496764263445345397483945567997375025250825330209385517139484882425580831299520200841767383756"])
+    }
+
+    #[test]
+    fn test_final_exponentiate_public_inputs_prove_verify() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        let x = test_input();
+
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let stark = S::new(8192).unwrap();
+        let public_inputs = S::public_inputs(&x, &x.final_exponentiate());
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(x).unwrap();
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+    }
+
+    #[test]
+    fn test_fill_trace_div_by_zero() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let mut trace: Vec<[F; TOTAL_COLUMNS]> = vec![];
+        let zero = Fp12([Fp::zero(); 12]);
+        assert!(fill_trace_div::<F, D, TOTAL_COLUMNS>(&mut trace, &Fp12::one(), &zero, 0, 11, 0, 12, 23, 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_final_exponentiate_trace_template() {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+        type S = FinalExponentiateStark<F, 2>;
+
+        // Each trace takes a few GB, so the template is dropped before comparing.
+        let stark = S::with_min_rows();
+        let x = test_input();
+        let from_template = stark.generate_trace_from_template(&stark.trace_template(), x).unwrap();
+        assert!(from_template == stark.generate_trace(x).unwrap());
+        drop(from_template);
+
+        let template = stark.trace_template();
+        assert_eq!(template.num_rows(), TOTAL_ROW.next_power_of_two());
+        assert!(stark.generate_trace_from_template(&template, Fp12([Fp::zero(); 12])).is_err());
+        // TOTAL_ROW is not a power of two.
+        let smaller = S::new(TOTAL_ROW).unwrap();
+        assert!(smaller.generate_trace_from_template(&template, x).is_err());
+    }
+
+    #[test]
+    fn test_final_exponentiate_prove_batch() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        let x = test_input();
+        let inputs = [x, x * x];
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let stark = S::with_min_rows();
+        let proofs = stark.prove_batch::<C>(&config, &inputs).unwrap();
+        assert_eq!(proofs.len(), inputs.len());
+        for (input, proof) in inputs.iter().zip(proofs) {
+            assert_eq!(proof.public_inputs, S::public_inputs(input, &input.final_exponentiate()));
+            verify_stark_proof(stark, proof, &config).unwrap();
+        }
+        assert!(stark.prove_batch::<C>(&config, &[Fp12([Fp::zero(); 12]), x]).is_err());
+    }
+
+    #[test]
+    fn test_verify_final_exp_from_serialized_proof() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        let x = test_input();
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let proof = S::with_min_rows().prove_batch::<C>(&config, &[x]).unwrap().remove(0);
+        let bytes = serialize_proof(&proof).unwrap();
+
+        // The verifier only has the bytes and the public inputs.
+        let public_inputs = S::public_inputs(&x, &x.final_exponentiate());
+        let proof = deserialize_proof::<F, C, D>(&bytes).unwrap().proof;
+        verify_final_exp(proof.clone(), &public_inputs, &config).unwrap();
+
+        let wrong_output = S::public_inputs(&x, &(x * x).final_exponentiate());
+        assert!(verify_final_exp(proof, &wrong_output, &config).is_err());
+    }
+
+    #[test]
+    fn test_final_exponentiate_prove_pairing_is_one() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        // e(g1, g2) * e(-g1, g2) == 1
+        let (g1, g2) = (G1_GENERATOR, G2_GENERATOR);
+        let x = miller_loop(g1.x, g1.y, g2.x, g2.y, Fp2::one()) * miller_loop(g1.x, negate_fp(g1.y), g2.x, g2.y, Fp2::one());
+        assert_eq!(x.final_exponentiate(), Fp12::one());
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let stark = S::with_min_rows();
+        let proof = stark.prove_pairing_is_one::<C>(&config, x).unwrap();
+        assert_eq!(proof.public_inputs, S::public_inputs(&x, &Fp12::one()));
+        verify_stark_proof(stark.with_output_one(), proof, &config).unwrap();
+    }
+
+    #[test]
+    fn test_final_exponentiate_prove_pairing_is_one_fails() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        let x = test_input();
+        assert!(x.final_exponentiate() != Fp12::one());
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let stark = S::with_min_rows();
+        assert!(stark.prove_pairing_is_one::<C>(&config, x).is_err());
+
+        // The trace of the actual output does not satisfy the stark constraining the output to be one, first at the constraint on the first limb of the output which is not the limb of one. These constraints come after the public input constraints.
+        #[cfg(feature = "debug-constraints")]
+        {
+            let output = x.final_exponentiate();
+            let limb = output.get_u32_slice().concat().iter().enumerate().position(|(i, limb)| *limb != (i == 0) as u32).unwrap();
+            let public_inputs = S::public_inputs(&x, &output);
+            let trace = stark.generate_trace(x).unwrap();
+            assert_eq!(debug_check_trace(&stark.with_output_one(), &trace, &public_inputs), Some((stark.num_rows - 1, 2*24*3*2 + limb)));
+        }
+    }
+
+    #[test]
+    fn test_final_exponentiate_quartic_extension() {
+        const D: usize = 4;
+        type C = PoseidonGoldilocksQuarticConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        let x = test_input();
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let stark = S::with_min_rows();
+        let public_inputs = S::public_inputs(&x, &x.final_exponentiate());
+        let trace_poly_values = trace_rows_to_poly_values(stark.generate_trace(x).unwrap());
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+    }
+
+    #[test]
+    fn test_final_exponentiate_public_inputs_round_trip() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let x = test_input();
+        let pis = FinalExponentiatePublicInputs { input: x, output: x.final_exponentiate() };
+        let encoded: Vec<F> = pis.encode();
+        assert_eq!(encoded.len(), PUBLIC_INPUTS);
+        assert_eq!(encoded, FinalExponentiateStark::<F, D>::public_inputs(&x, &x.final_exponentiate()));
+        assert_eq!(encoded[PIS_INPUT_OFFSET..PIS_INPUT_OFFSET + 144], x.get_u32_slice().concat().iter().map(|e| F::from_canonical_u32(*e)).collect::<Vec<F>>()[..]);
+        assert_eq!(FinalExponentiatePublicInputs::decode(&encoded), pis);
+    }
+
+    #[test]
+    fn test_final_exponentiate_zero_input() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        let stark = S::with_min_rows();
+        let err = stark.generate_trace(Fp12([Fp::zero(); 12])).unwrap_err();
+        assert_eq!(err.downcast_ref::<NativeError>(), Some(&NativeError::NonInvertible));
+    }
+
+    #[test]
+    fn test_final_exponentiate_outputs_only_in_live_rows() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        let x = test_input();
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let num_rows = 8192;
+        let stark = S::new(num_rows).unwrap();
+        let trace = stark.generate_trace(x).unwrap();
+        let live_rows = final_exp_live_rows(num_rows);
+        for (col, first_row, last_row) in live_rows {
+            for row in first_row..last_row {
+                assert_eq!(trace[row][col..col + 24*3*2], trace[row + 1][col..col + 24*3*2]);
+            }
+        }
+        for (col, _, _) in live_rows {
+            for row in 0..num_rows {
+                if !live_rows.iter().any(|(c, first_row, last_row)| *c == col && (*first_row..*last_row + 1).contains(&row)) {
+                    assert!(trace[row][col..col + 24*3*2].iter().all(|v| *v == F::ZERO));
+                }
             }
         }
+        let public_inputs = S::public_inputs(&x, &x.final_exponentiate());
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+    }
+
+    #[test]
+    fn test_final_exp_slots_are_not_shared_while_live() {
+        let live_rows = final_exp_live_rows(FINAL_EXP_MAX_ROWS);
+        for (i, (col, first_row, last_row)) in live_rows.iter().enumerate() {
+            assert!((*col - FINAL_EXP_SLOT0_OFFSET) % (12*12) == 0 && *col < FINAL_EXP_OP_OFFSET);
+            for (other_col, other_first_row, other_last_row) in &live_rows[i + 1..] {
+                if col == other_col {
+                    assert!(last_row < other_first_row || other_last_row < first_row);
+                }
+            }
+        }
+        assert_eq!(FINAL_EXP_OP_OFFSET, FINAL_EXP_SLOT0_OFFSET + 8*12*12);
+    }
+
+    /// Pins the layout, since every offset is chained from the previous one and an edit to one of them silently shifts all the following columns. Update the values here only for a deliberate change of the layout.
+    #[test]
+    fn test_final_exponentiate_layout() {
+        assert_eq!(FINAL_EXP_INPUT_OFFSET, 210);
+        assert_eq!(FINAL_EXP_SLOT0_OFFSET, 354);
+        assert_eq!(FINAL_EXP_OP_OFFSET, 1506);
+        assert_eq!(FINAL_EXP_TOTAL_COLUMNS, 62084);
+        assert_eq!(TOTAL_COLUMNS, 62889);
+        assert_eq!(PUBLIC_INPUTS, 288);
+        assert_eq!(T4_ROW, 48);
+        assert_eq!(INPUT_INV_ROW, 4441);
+        assert_eq!(CYCLOTOMIC_EXP_ROWS, 70*12 + 1);
+        assert_eq!(TOTAL_ROW, 4453);
+        assert_eq!(TOTAL_ROW.next_power_of_two(), FINAL_EXP_MAX_ROWS);
+    }
+
+    #[test]
+    fn test_final_exp_row_counter() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        let trace = S::with_min_rows().generate_trace(test_input()).unwrap();
+        for (row, values) in trace.iter().enumerate() {
+            let counter = (0..FINAL_EXP_ROW_BITS).map(|k| values[FINAL_EXP_ROW_BITS_OFFSET + k].to_canonical_u64() << k).sum::<u64>();
+            assert_eq!(counter, row as u64);
+            assert_eq!(values[FINAL_EXP_ROW_LOW_SELECTORS..FINAL_EXP_FORBENIUS_MAP_SELECTOR].iter().filter(|v| **v == F::ONE).count(), 2);
+            for other_row in [0, T4_ROW, row, TOTAL_ROW - 1, FINAL_EXP_MAX_ROWS - 1] {
+                let expected = if other_row == row { F::ONE } else { F::ZERO };
+                assert_eq!(final_exp_row_selector(&values[..], other_row, 0), expected);
+            }
+        }
+    }
+
+    // Index of the constraint on the row counter transition, after the public input constraints and the constraints on the bits of the row counter.
+    #[cfg(feature = "debug-constraints")]
+    const ROW_COUNTER_CONSTRAINT: usize = 2*24*3*2 + 2*FINAL_EXP_ROW_BITS;
+    // Index of the one-hot constraint on the op selectors, after the row counter, the row selector constraints and the 5 op selector boolean constraints.
+    #[cfg(feature = "debug-constraints")]
+    const OP_SELECTORS_SUM_CONSTRAINT: usize = ROW_COUNTER_CONSTRAINT + 1 + FINAL_EXP_ROW_LOW_SELECTORS_TOTAL + 2 + FINAL_EXP_ROW_HIGH_SELECTORS_TOTAL + 2 + 5;
+
+    #[cfg(feature = "debug-constraints")]
+    #[test]
+    fn test_final_exp_row_counter_swapped_rows_fail() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        let x = test_input();
+        let stark = S::with_min_rows();
+        // Swaps the row numbers of the first two rows of T4, which moves the links of the input of the cyclotomic exponent to the second row.
+        let mut trace = stark.generate_trace(x).unwrap();
+        for col in FINAL_EXP_ROW_BITS_OFFSET..FINAL_EXP_FORBENIUS_MAP_SELECTOR {
+            let value = trace[T4_ROW][col];
+            trace[T4_ROW][col] = trace[T4_ROW + 1][col];
+            trace[T4_ROW + 1][col] = value;
+        }
+        let public_inputs = S::public_inputs(&x, &x.final_exponentiate());
+        assert_eq!(debug_check_trace(&stark, &trace, &public_inputs), Some((T4_ROW - 1, ROW_COUNTER_CONSTRAINT)));
+    }
 
-        // T0
-        add_constraints_forbenius_ext_circuit(builder, yield_constr, local_values, T0_ROW, FINAL_EXP_INPUT_OFFSET, FINAL_EXP_T0_OFFSET, 6);
+    #[cfg(feature = "debug-constraints")]
+    #[test]
+    fn test_final_exp_live_value_changed_mid_trace_fails() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        let x = test_input();
+        let stark = S::with_min_rows();
+        // T10 is live from T10_ROW to T21_ROW, and is not the input of any operation between the cyclotomic exponent computing T11 and T21.
+        // Changing it in one row breaks only its copy from the previous row and to the next row.
+        let row = (T12_ROW + T21_ROW) / 2;
+        let mut trace = stark.generate_trace(x).unwrap();
+        trace[row][FINAL_EXP_T10_OFFSET] += F::ONE;
+        let public_inputs = S::public_inputs(&x, &x.final_exponentiate());
+        // The copy constraints of the slot of T10 come after the input copy constraints and the copy constraints of the slots before it.
+        let slot = final_exp_slot_boundaries(stark.num_rows).iter().position(|(col, _)| *col == FINAL_EXP_T10_OFFSET).unwrap();
+        assert_eq!(
+            debug_check_trace(&stark, &trace, &public_inputs),
+            Some((row - 1, OP_SELECTORS_SUM_CONSTRAINT + 1 + 24*3*2 + 24*3*2*slot)),
+        );
+    }
+
+    #[cfg(feature = "debug-constraints")]
+    #[test]
+    fn test_final_exp_two_op_selectors_in_row_fails() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        let x = test_input();
+        let stark = S::with_min_rows();
+        // The first padding row, which is not in the rows of any operation, so only the one-hot constraint on the op selectors ties them.
+        let row = TOTAL_ROW;
+        let mut trace = stark.generate_trace(x).unwrap();
+        trace[row][FINAL_EXP_FORBENIUS_MAP_SELECTOR] = F::ONE;
+        trace[row][FINAL_EXP_MUL_SELECTOR] = F::ONE;
+        let public_inputs = S::public_inputs(&x, &x.final_exponentiate());
+        assert_eq!(debug_check_trace(&stark, &trace, &public_inputs), Some((row, OP_SELECTORS_SUM_CONSTRAINT)));
+    }
+
+    #[test]
+    fn test_final_exp_row_budget() {
+        let budget = final_exp_row_budget();
+        let mut next_row = 0;
+        for (_, row, rows) in &budget {
+            assert_eq!(*row, next_row);
+            next_row += rows;
+        }
+        assert_eq!(next_row, TOTAL_ROW);
+        let cyc_exp_rows: usize = budget.iter().filter(|(op, _, _)| op.ends_with("cyclotomic_exp")).map(|(_, _, rows)| rows).sum();
+        assert_eq!(cyc_exp_rows, 5 * CYCLOTOMIC_EXP_ROWS);
+        assert!(2 * cyc_exp_rows > TOTAL_ROW);
+    }
+
+    #[test]
+    fn test_final_exponentiate_with_min_rows() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        let x = test_input();
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let stark = S::with_min_rows();
+        let trace = stark.generate_trace(x).unwrap();
+        assert!(trace.len().is_power_of_two());
+        assert!(trace.len() >= TOTAL_ROW && trace.len() / 2 < TOTAL_ROW);
+        let public_inputs = S::public_inputs(&x, &x.final_exponentiate());
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+    }
+
+    #[test]
+    fn test_final_exponentiate_invalid_num_rows() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        assert!(S::new(TOTAL_ROW - 1).is_err());
+        assert!(S::new(FINAL_EXP_MAX_ROWS * 2).is_err());
+        assert!(S::new(TOTAL_ROW).is_ok());
+    }
 
-        // T1
-        add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T1_ROW, FINAL_EXP_T1_OFFSET, FINAL_EXP_INPUT_OFFSET, FINAL_EXP_T0_OFFSET);
+    #[test]
+    fn test_final_exponentiate_field_check() {
+        type F = <PoseidonGoldilocksConfig as GenericConfig<2>>::F;
+        type FQ = <PoseidonGoldilocksQuarticConfig as GenericConfig<4>>::F;
 
-        // T2
-        add_constraints_forbenius_ext_circuit(builder, yield_constr, local_values, T2_ROW, FINAL_EXP_T1_OFFSET, FINAL_EXP_T2_OFFSET, 2);
+        // The selector indices and the row counter are far below the order, and the largest trace fits the 2-adic subgroup with room for the FRI rate.
+        check_field::<F>(FINAL_EXP_MAX_ROWS).unwrap();
+        check_field::<FQ>(FINAL_EXP_MAX_ROWS).unwrap();
+        assert!(FINAL_EXP_MAX_ROWS.trailing_zeros() + 3 <= F::TWO_ADICITY as u32);
+        assert!(check_field::<F>(1 << (F::TWO_ADICITY + 1)).is_err());
+    }
 
-        // T3
-        add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T3_ROW, FINAL_EXP_T2_OFFSET, FINAL_EXP_T1_OFFSET, FINAL_EXP_T3_OFFSET);
+    #[test]
+    fn test_final_exponentiate_required_rows() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        assert_eq!(S::required_rows(), TOTAL_ROW);
+        assert!(S::new(S::required_rows()).is_ok());
+        // Final exponentiate is the longest gadget of the pairing, next to the miller loop.
+        assert!(MILLER_LOOP_ROWS < TOTAL_ROW);
+        assert_eq!(PairingStark::<F, D>::required_rows(), TOTAL_ROW);
+        assert_eq!(PairingStark::<F, D>::required_rows().next_power_of_two(), 8192);
+    }
 
-        // T4
-        add_constraints_cyc_exp_ext_circuit(builder, yield_constr, local_values, T4_ROW, FINAL_EXP_T3_OFFSET, FINAL_EXP_T4_OFFSET);
+    #[test]
+    fn test_final_exponentiate_at_base_offset() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = OffsetFinalExponentiateStark<F, D>;
+
+        let x = test_input();
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let num_rows = 8192;
+        let stark = S {
+            num_rows,
+            _f: std::marker::PhantomData,
+        };
+        let trace = stark.generate_trace(x);
+        let base_trace = FinalExponentiateStark::<F, D>::new(num_rows).unwrap().generate_trace(x).unwrap();
+        for row in 0..num_rows {
+            assert_eq!(trace[row][OFFSET_BASE..], base_trace[row][..FINAL_EXP_TOTAL_COLUMNS]);
+        }
+        let public_inputs = FinalExponentiateStark::<F, D>::public_inputs(&x, &x.final_exponentiate());
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+    }
 
-        // T5
-        add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T5_ROW, FINAL_EXP_T4_OFFSET, FINAL_EXP_T5_OFFSET);
+    // Row selectors of the final exponentiate gadget followed by the range checks of the public inputs.
+    const PIS_RANGE_CHECK_ROWS: usize = 32;
+    const PIS_RANGE_CHECK_START: usize = FINAL_EXP_FORBENIUS_MAP_SELECTOR;
+    const PIS_RANGE_CHECK_COLUMNS: usize = PIS_RANGE_CHECK_START + PIS_RANGE_CHECK_TOTAL;
 
-        // T6
-        add_constraints_cyc_sq_ext_circuit(builder, yield_constr, local_values, T6_ROW, FINAL_EXP_T3_OFFSET, FINAL_EXP_T6_OFFSET);
+    /// Stark with only the range checks of the public inputs of [FinalExponentiateStark].
+    #[derive(Clone, Copy)]
+    struct PisRangeCheckStark<F: RichField + Extendable<D>, const D: usize> {
+        _f: std::marker::PhantomData<F>,
+    }
 
-        // T7
-        add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T7_ROW, FINAL_EXP_T6_OFFSET, FINAL_EXP_T7_OFFSET);
+    impl<F: RichField + Extendable<D>, const D: usize> PisRangeCheckStark<F, D> {
+        fn generate_trace(&self, public_inputs: &[u32]) -> Vec<[F; PIS_RANGE_CHECK_COLUMNS]> {
+            let mut trace = vec![[F::ZERO; PIS_RANGE_CHECK_COLUMNS]; PIS_RANGE_CHECK_ROWS];
+            for row in 0..PIS_RANGE_CHECK_ROWS {
+                trace[row][FINAL_EXP_ROW_LOW_SELECTORS + (row & (FINAL_EXP_ROW_LOW_SELECTORS_TOTAL - 1))] = F::ONE;
+                trace[row][FINAL_EXP_ROW_HIGH_SELECTORS + (row >> FINAL_EXP_ROW_LOW_BITS)] = F::ONE;
+            }
+            fill_trace_public_input_range_checks(&mut trace, public_inputs, PIS_RANGE_CHECK_START);
+            trace
+        }
+    }
 
-        // T8
-        add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T8_ROW, FINAL_EXP_T7_OFFSET, FINAL_EXP_T5_OFFSET, FINAL_EXP_T8_OFFSET);
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for PisRangeCheckStark<F, D> {
+        type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, PIS_RANGE_CHECK_COLUMNS, PUBLIC_INPUTS>
+        where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>;
+
+        type EvaluationFrameTarget = StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, PIS_RANGE_CHECK_COLUMNS, PUBLIC_INPUTS>;
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: &Self::EvaluationFrame<FE, P, D2>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            add_public_input_range_check_constraints(vars.get_local_values(), vars.get_public_inputs(), yield_constr, 0, PIS_RANGE_CHECK_START);
+        }
 
-        // T9
-        add_constraints_cyc_exp_ext_circuit(builder, yield_constr, local_values, T9_ROW, FINAL_EXP_T8_OFFSET, FINAL_EXP_T9_OFFSET);
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<F, D>,
+            vars: &Self::EvaluationFrameTarget,
+            yield_constr: &mut starky::constraint_consumer::RecursiveConstraintConsumer<F, D>,
+        ) {
+            add_public_input_range_check_constraints_ext_circuit(builder, yield_constr, vars.get_local_values(), vars.get_public_inputs(), 0, PIS_RANGE_CHECK_START);
+        }
 
-        // T10
-        add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T10_ROW, FINAL_EXP_T9_OFFSET, FINAL_EXP_T10_OFFSET);
+        fn constraint_degree(&self) -> usize {
+            3
+        }
+    }
 
-        // T11
-        add_constraints_cyc_exp_ext_circuit(builder, yield_constr, local_values, T11_ROW, FINAL_EXP_T10_OFFSET, FINAL_EXP_T11_OFFSET);
+    #[test]
+    fn test_public_input_range_checks() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = PisRangeCheckStark<F, D>;
+
+        let x = test_input();
+        let public_inputs = [x.get_u32_slice().concat(), x.final_exponentiate().get_u32_slice().concat()].concat();
+        let config = StarkConfig::standard_fast_config();
+        let stark = S { _f: std::marker::PhantomData };
+        let trace_poly_values = trace_rows_to_poly_values(stark.generate_trace(&public_inputs));
+        let public_inputs: Vec<F> = public_inputs.iter().map(|x| F::from_canonical_u32(*x)).collect();
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+    }
 
-        // T12
-        add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T12_ROW, FINAL_EXP_T11_OFFSET, FINAL_EXP_T12_OFFSET);
+    #[cfg(feature = "debug-constraints")]
+    #[test]
+    fn test_out_of_range_public_input_fails() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = PisRangeCheckStark<F, D>;
+
+        // Index of the constraint on the bit 31 of the range check sum, after the public input constraints, the range check selector constraint and the bit decompositions of the limbs, of the limbs of the sum and of its most significant limb, and the carry constraints.
+        const BIT_31_CONSTRAINT: usize = PUBLIC_INPUTS + 1 + 12*33 + 11*33 + 12 + 32;
+        // The last carry is constrained next, then the range check itself, whose bit 30 constraint on its first limb comes after the sum and bit decomposition constraints.
+        const LAST_CARRY_CONSTRAINT: usize = BIT_31_CONSTRAINT + 1;
+        const BIT_30_CONSTRAINT: usize = BIT_31_CONSTRAINT + 4;
+
+        let x = test_input();
+        let public_inputs = [x.get_u32_slice().concat(), x.final_exponentiate().get_u32_slice().concat()].concat();
+        let x0 = BigUint::new(public_inputs[..12].to_vec());
+        let one = BigUint::from(1u32);
+        let stark = S { _f: std::marker::PhantomData };
+        // Non reduced representation of x0, caught by the range check, and values caught only by the bit 31 of the sum and the last carry of the range check.
+        for (element, constraint) in [
+            (x0 + modulus(), BIT_30_CONSTRAINT),
+            (modulus() + (one.clone() << 382), BIT_31_CONSTRAINT),
+            ((one << 384) - 1u32, LAST_CARRY_CONSTRAINT),
+        ] {
+            let mut public_inputs = public_inputs.clone();
+            public_inputs[PIS_OUTPUT_OFFSET..PIS_OUTPUT_OFFSET + 12].copy_from_slice(&get_u32_vec_from_literal(element));
+            let trace = stark.generate_trace(&public_inputs);
+            let public_inputs: Vec<F> = public_inputs.iter().map(|x| F::from_canonical_u32(*x)).collect();
+            assert_eq!(debug_check_trace(&stark, &trace, &public_inputs), Some((PIS_OUTPUT_OFFSET / 12, constraint)));
+        }
+    }
 
-        // T13
-        add_constraints_cyc_exp_ext_circuit(builder, yield_constr, local_values, T13_ROW, FINAL_EXP_T12_OFFSET, FINAL_EXP_T13_OFFSET);
+    /// Operation of the final exponentiation constrained by [FinalExpHelperStark], with its row, input and output columns as passed to the `add_constraints_*` helper.
+    #[cfg(feature = "debug-constraints")]
+    #[derive(Clone, Copy)]
+    enum Helper {
+        Forbenius(usize, usize, usize, usize),
+        Mul(usize, usize, usize, usize),
+        CycExp(usize, usize, usize),
+        Conjugate(usize, usize, usize),
+        CycSq(usize, usize, usize),
+    }
 
-        // T14
-        add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T14_ROW, FINAL_EXP_T13_OFFSET, FINAL_EXP_T14_OFFSET);
+    /// Stark with only the constraints of one `add_constraints_*` helper over the final exponentiate trace.
+    #[cfg(feature = "debug-constraints")]
+    #[derive(Clone, Copy)]
+    struct FinalExpHelperStark<F: RichField + Extendable<D>, const D: usize> {
+        helper: Helper,
+        _f: std::marker::PhantomData<F>,
+    }
 
-        // T15
-        add_constraints_cyc_sq_ext_circuit(builder, yield_constr, local_values, T15_ROW, FINAL_EXP_T5_OFFSET, FINAL_EXP_T15_OFFSET);
+    #[cfg(feature = "debug-constraints")]
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FinalExpHelperStark<F, D> {
+        type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, TOTAL_COLUMNS, PUBLIC_INPUTS>
+        where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>;
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: &Self::EvaluationFrame<FE, P, D2>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            let local_values = vars.get_local_values();
+            match self.helper {
+                Helper::Forbenius(row, input_col, output_col, pow) => add_constraints_forbenius(local_values, yield_constr, row, input_col, Some(output_col), pow, 0),
+                Helper::Mul(row, x_col, y_col, res_col) => add_constraints_mul(local_values, yield_constr, row, Some(x_col), y_col, res_col, 0),
+                Helper::CycExp(row, input_col, output_col) => add_constraints_cyc_exp(local_values, yield_constr, row, input_col, output_col, 0),
+                Helper::Conjugate(row, input_col, output_col) => add_constraints_conjugate(local_values, yield_constr, row, input_col, output_col, 0),
+                Helper::CycSq(row, input_col, output_col) => add_constraints_cyc_sq(local_values, yield_constr, row, input_col, output_col, 0),
+            }
+        }
 
-        // T16
-        add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T16_ROW, FINAL_EXP_T14_OFFSET, FINAL_EXP_T15_OFFSET, FINAL_EXP_T16_OFFSET);
+        type EvaluationFrameTarget =
+            StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, TOTAL_COLUMNS, PUBLIC_INPUTS>;
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<F, D>,
+            vars: &Self::EvaluationFrameTarget,
+            yield_constr: &mut starky::constraint_consumer::RecursiveConstraintConsumer<F, D>,
+        ) {
+            let local_values = vars.get_local_values();
+            match self.helper {
+                Helper::Forbenius(row, input_col, output_col, pow) => add_constraints_forbenius_ext_circuit(builder, yield_constr, local_values, row, input_col, Some(output_col), pow, 0),
+                Helper::Mul(row, x_col, y_col, res_col) => add_constraints_mul_ext_circuit(builder, yield_constr, local_values, row, Some(x_col), y_col, res_col, 0),
+                Helper::CycExp(row, input_col, output_col) => add_constraints_cyc_exp_ext_circuit(builder, yield_constr, local_values, row, input_col, output_col, 0),
+                Helper::Conjugate(row, input_col, output_col) => add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, row, input_col, output_col, 0),
+                Helper::CycSq(row, input_col, output_col) => add_constraints_cyc_sq_ext_circuit(builder, yield_constr, local_values, row, input_col, output_col, 0),
+            }
+        }
 
-        // T17
-        add_constraints_cyc_exp_ext_circuit(builder, yield_constr, local_values, T17_ROW, FINAL_EXP_T16_OFFSET, FINAL_EXP_T17_OFFSET);
+        fn constraint_degree(&self) -> usize {
+            3
+        }
+    }
 
-        // T18
-        add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T18_ROW, FINAL_EXP_T17_OFFSET, FINAL_EXP_T18_OFFSET);
+    #[cfg(feature = "debug-constraints")]
+    #[test]
+    fn test_final_exponentiate_helpers_reject_corrupted_output() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        let x = test_input();
+        let stark = S::with_min_rows();
+        let mut trace = stark.generate_trace(x).unwrap();
+        let public_inputs = S::public_inputs(&x, &x.final_exponentiate());
+        let live_rows = final_exp_live_rows(trace.len());
+
+        // (output column, first live row of the output, helper computing it), for each kind of helper
+        let helpers = [
+            (FINAL_EXP_T14_OFFSET, T14_ROW, Helper::Conjugate(T14_ROW, FINAL_EXP_T13_OFFSET, FINAL_EXP_T14_OFFSET)),
+            (FINAL_EXP_T15_OFFSET, T15_ROW, Helper::CycSq(T15_ROW, FINAL_EXP_T5_OFFSET, FINAL_EXP_T15_OFFSET)),
+            (FINAL_EXP_T16_OFFSET, T16_ROW, Helper::Mul(T16_ROW, FINAL_EXP_T14_OFFSET, FINAL_EXP_T15_OFFSET, FINAL_EXP_T16_OFFSET)),
+            (FINAL_EXP_T17_OFFSET, T18_ROW - 1, Helper::CycExp(T17_ROW, FINAL_EXP_T16_OFFSET, FINAL_EXP_T17_OFFSET)),
+            (FINAL_EXP_T20_OFFSET, T20_ROW, Helper::Forbenius(T20_ROW, FINAL_EXP_T19_OFFSET, FINAL_EXP_T20_OFFSET, 2)),
+        ];
+        for (col, first_row, helper) in helpers {
+            let helper_stark = FinalExpHelperStark::<F, D> { helper, _f: std::marker::PhantomData };
+            assert_eq!(debug_check_trace(&helper_stark, &trace, &public_inputs), None);
+
+            // Changes the first limb of the output in all its live rows, so that only the output constraint of the helper fails.
+            let (_, _, last_row) = live_rows.into_iter().find(|(c, r, _)| *c == col && *r == first_row).unwrap();
+            for row in first_row..last_row + 1 {
+                trace[row][col] += F::ONE;
+            }
+            assert_eq!(debug_check_trace(&helper_stark, &trace, &public_inputs).map(|(row, _)| row), Some(first_row));
+            if let Helper::Mul(..) = helper {
+                // The output constraints of add_constraints_mul follow the op selector and input constraints, limb 0 of the output is the first one.
+                assert_eq!(debug_check_trace(&helper_stark, &trace, &public_inputs), Some((T16_ROW, 5*FP12_MUL_ROWS + 2*24*3*2)));
+                assert_eq!(debug_check_trace(&stark, &trace, &public_inputs).map(|(row, _)| row), Some(T16_ROW));
+            }
+            for row in first_row..last_row + 1 {
+                trace[row][col] -= F::ONE;
+            }
+        }
+    }
 
-        // T19
-        add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T19_ROW, FINAL_EXP_T5_OFFSET, FINAL_EXP_T12_OFFSET, FINAL_EXP_T19_OFFSET);
+    /// Stark with only the constraints of one operation gadget over the final exponentiate trace, gated by its op selector as in [add_final_exponentiate_constraints].
+    #[cfg(feature = "debug-constraints")]
+    #[derive(Clone, Copy)]
+    struct FinalExpGatedOpStark<F: RichField + Extendable<D>, const D: usize> {
+        op: FinalExpOp,
+        _f: std::marker::PhantomData<F>,
+    }
 
-        // T20
-        add_constraints_forbenius_ext_circuit(builder, yield_constr, local_values, T20_ROW, FINAL_EXP_T19_OFFSET, FINAL_EXP_T20_OFFSET, 2);
+    #[cfg(feature = "debug-constraints")]
+    impl<F: RichField + Extendable<D>, const D: usize> FinalExpGatedOpStark<F, D> {
+        fn op_selector(&self) -> usize {
+            match self.op {
+                FinalExpOp::ForbeniusMap => FINAL_EXP_FORBENIUS_MAP_SELECTOR,
+                FinalExpOp::Mul => FINAL_EXP_MUL_SELECTOR,
+                FinalExpOp::CyclotomicExp => FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR,
+                FinalExpOp::Conjugate => FINAL_EXP_CONJUGATE_SELECTOR,
+                FinalExpOp::CyclotomicSq => FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR,
+            }
+        }
+    }
 
-        // T21
-        add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T21_ROW, FINAL_EXP_T10_OFFSET, FINAL_EXP_T3_OFFSET, FINAL_EXP_T21_OFFSET);
+    #[cfg(feature = "debug-constraints")]
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FinalExpGatedOpStark<F, D> {
+        type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, TOTAL_COLUMNS, PUBLIC_INPUTS>
+        where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>;
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: &Self::EvaluationFrame<FE, P, D2>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            let local_values = vars.get_local_values();
+            let next_values = vars.get_next_values();
+            let selector = Some(local_values[self.op_selector()]);
+            match self.op {
+                FinalExpOp::ForbeniusMap => add_fp12_forbenius_map_constraints(local_values, next_values, yield_constr, FINAL_EXP_OP_OFFSET, selector),
+                FinalExpOp::Mul => add_fp12_multiplication_constraints(local_values, next_values, yield_constr, FINAL_EXP_OP_OFFSET, selector),
+                FinalExpOp::CyclotomicExp => add_cyclotomic_exp_constraints(local_values, next_values, yield_constr, FINAL_EXP_OP_OFFSET, selector),
+                FinalExpOp::Conjugate => add_fp12_conjugate_constraints(local_values, yield_constr, FINAL_EXP_OP_OFFSET, selector),
+                FinalExpOp::CyclotomicSq => add_cyclotomic_sq_constraints(local_values, next_values, yield_constr, FINAL_EXP_OP_OFFSET, selector),
+            }
+        }
 
-        // T22
-        add_constraints_forbenius_ext_circuit(builder, yield_constr, local_values, T22_ROW, FINAL_EXP_T21_OFFSET, FINAL_EXP_T22_OFFSET, 3);
+        type EvaluationFrameTarget =
+            StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, TOTAL_COLUMNS, PUBLIC_INPUTS>;
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<F, D>,
+            vars: &Self::EvaluationFrameTarget,
+            yield_constr: &mut starky::constraint_consumer::RecursiveConstraintConsumer<F, D>,
+        ) {
+            let local_values = vars.get_local_values();
+            let next_values = vars.get_next_values();
+            let selector = Some(local_values[self.op_selector()]);
+            match self.op {
+                FinalExpOp::ForbeniusMap => add_fp12_forbenius_map_constraints_ext_circuit(builder, yield_constr, local_values, next_values, FINAL_EXP_OP_OFFSET, selector),
+                FinalExpOp::Mul => add_fp12_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, next_values, FINAL_EXP_OP_OFFSET, selector),
+                FinalExpOp::CyclotomicExp => add_cyclotomic_exp_constraints_ext_circuit(builder, yield_constr, local_values, next_values, FINAL_EXP_OP_OFFSET, selector),
+                FinalExpOp::Conjugate => add_fp12_conjugate_constraints_ext_circuit(builder, yield_constr, local_values, FINAL_EXP_OP_OFFSET, selector),
+                FinalExpOp::CyclotomicSq => add_cyclotomic_sq_constraints_ext_circuit(builder, yield_constr, local_values, next_values, FINAL_EXP_OP_OFFSET, selector),
+            }
+        }
 
-        // T23
-        add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T23_ROW, FINAL_EXP_T3_OFFSET, FINAL_EXP_T23_OFFSET);
+        fn constraint_degree(&self) -> usize {
+            self.op.constraint_degree() + 1
+        }
+    }
 
-        // T24
-        add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T24_ROW, FINAL_EXP_T16_OFFSET, FINAL_EXP_T23_OFFSET, FINAL_EXP_T24_OFFSET);
+    #[cfg(feature = "debug-constraints")]
+    #[test]
+    fn test_final_exp_op_scratch_gated_by_op_selectors() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        let x = test_input();
+        let stark = S::with_min_rows();
+        let mut trace = stark.generate_trace(x).unwrap();
+        let public_inputs = S::public_inputs(&x, &x.final_exponentiate());
+        let gated = |op| FinalExpGatedOpStark::<F, D> { op, _f: std::marker::PhantomData };
+        let (cyc_exp, mul, conjugate) = (gated(FinalExpOp::CyclotomicExp), gated(FinalExpOp::Mul), gated(FinalExpOp::Conjugate));
+        for op_stark in [cyc_exp, mul, conjugate] {
+            assert_eq!(debug_check_trace(&op_stark, &trace, &public_inputs), None);
+        }
 
-        // T25
-        add_constraints_forbenius_ext_circuit(builder, yield_constr, local_values, T25_ROW, FINAL_EXP_T24_OFFSET, FINAL_EXP_T25_OFFSET, 1);
+        // T17 is the cyclotomic exponent in rows T17_ROW..T18_ROW, after the T16 multiplication and before the T18 conjugate, which share its scratch columns.
+        // The first limb of its z must be 1 in the first row, and the last one is its result, constrained from the row before.
+        let col = FINAL_EXP_OP_OFFSET + Z_OFFSET;
+        let corrupted = |trace: &mut Vec<[F; TOTAL_COLUMNS]>, row: usize, op_stark: &FinalExpGatedOpStark<F, D>| {
+            trace[row][col] += F::ONE;
+            let failing_row = debug_check_trace(op_stark, trace, &public_inputs).map(|(row, _)| row);
+            trace[row][col] -= F::ONE;
+            failing_row
+        };
+        // Just outside the rows of the cyclotomic exponent, its constraints do not apply.
+        assert_eq!(corrupted(&mut trace, T17_ROW - 1, &cyc_exp), None);
+        assert_eq!(corrupted(&mut trace, T18_ROW, &cyc_exp), None);
+        // Just inside, they do.
+        assert_eq!(corrupted(&mut trace, T17_ROW, &cyc_exp), Some(T17_ROW));
+        assert_eq!(corrupted(&mut trace, T18_ROW - 1, &cyc_exp), Some(T18_ROW - 2));
+        // The neighbouring operations do not constrain the rows of the cyclotomic exponent, which the whole stark still does.
+        assert_eq!(corrupted(&mut trace, T17_ROW, &mul), None);
+        assert_eq!(corrupted(&mut trace, T18_ROW - 1, &conjugate), None);
+        trace[T17_ROW][col] += F::ONE;
+        assert_eq!(debug_check_trace(&stark, &trace, &public_inputs).map(|(row, _)| row), Some(T17_ROW));
+    }
 
-        // T26
-        add_constraints_conjugate_ext_circuit(builder, yield_constr, local_values, T26_ROW, FINAL_EXP_T8_OFFSET, FINAL_EXP_T26_OFFSET);
+    /// Operation gadgets of the final exponentiate stark.
+    #[derive(Clone, Copy, Debug)]
+    enum FinalExpOp {
+        ForbeniusMap,
+        Mul,
+        CyclotomicExp,
+        Conjugate,
+        CyclotomicSq,
+    }
 
-        // T27
-        add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T27_ROW, FINAL_EXP_T18_OFFSET, FINAL_EXP_T26_OFFSET, FINAL_EXP_T27_OFFSET);
+    impl FinalExpOp {
+        fn constraint_degree(&self) -> usize {
+            match self {
+                FinalExpOp::ForbeniusMap => FP12_FORBENIUS_MAP_CONSTRAINT_DEGREE,
+                FinalExpOp::Mul => FP12_MUL_CONSTRAINT_DEGREE,
+                FinalExpOp::CyclotomicExp => CYCLOTOMIC_EXP_CONSTRAINT_DEGREE,
+                FinalExpOp::Conjugate => FP12_CONJUGATE_CONSTRAINT_DEGREE,
+                FinalExpOp::CyclotomicSq => CYCLOTOMIC_SQ_CONSTRAINT_DEGREE,
+            }
+        }
+    }
 
-        // T28
-        add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T28_ROW, FINAL_EXP_T27_OFFSET, FINAL_EXP_T3_OFFSET, FINAL_EXP_T28_OFFSET);
+    /// One operation gadget without `bit_selector`, in the columns of the operation traces of the final exponentiate stark, declaring the degree of the gadget.
+    #[derive(Clone, Copy)]
+    struct FinalExpOpStark<F: RichField + Extendable<D>, const D: usize> {
+        op: FinalExpOp,
+        _f: std::marker::PhantomData<F>,
+    }
 
-        // T29
-        add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T29_ROW, FINAL_EXP_T20_OFFSET, FINAL_EXP_T22_OFFSET, FINAL_EXP_T29_OFFSET);
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FinalExpOpStark<F, D> {
+        type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, CYCLOTOMIC_EXP_TOTAL_COLUMNS, 0>
+        where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>;
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: &Self::EvaluationFrame<FE, P, D2>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            let local_values = vars.get_local_values();
+            let next_values = vars.get_next_values();
+            match self.op {
+                FinalExpOp::ForbeniusMap => add_fp12_forbenius_map_constraints(local_values, next_values, yield_constr, 0, None),
+                FinalExpOp::Mul => add_fp12_multiplication_constraints(local_values, next_values, yield_constr, 0, None),
+                FinalExpOp::CyclotomicExp => add_cyclotomic_exp_constraints(local_values, next_values, yield_constr, 0, None),
+                FinalExpOp::Conjugate => add_fp12_conjugate_constraints(local_values, yield_constr, 0, None),
+                FinalExpOp::CyclotomicSq => add_cyclotomic_sq_constraints(local_values, next_values, yield_constr, 0, None),
+            }
+        }
 
-        // T30
-        add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T30_ROW, FINAL_EXP_T29_OFFSET, FINAL_EXP_T25_OFFSET, FINAL_EXP_T30_OFFSET);
+        type EvaluationFrameTarget =
+            StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, CYCLOTOMIC_EXP_TOTAL_COLUMNS, 0>;
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<F, D>,
+            vars: &Self::EvaluationFrameTarget,
+            yield_constr: &mut starky::constraint_consumer::RecursiveConstraintConsumer<F, D>,
+        ) {
+            let local_values = vars.get_local_values();
+            let next_values = vars.get_next_values();
+            match self.op {
+                FinalExpOp::ForbeniusMap => add_fp12_forbenius_map_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None),
+                FinalExpOp::Mul => add_fp12_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None),
+                FinalExpOp::CyclotomicExp => add_cyclotomic_exp_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None),
+                FinalExpOp::Conjugate => add_fp12_conjugate_constraints_ext_circuit(builder, yield_constr, local_values, 0, None),
+                FinalExpOp::CyclotomicSq => add_cyclotomic_sq_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None),
+            }
+        }
 
-        // T31
-        add_constraints_mul_ext_circuit(builder, yield_constr, local_values, T31_ROW, FINAL_EXP_T30_OFFSET, FINAL_EXP_T28_OFFSET, FINAL_EXP_T31_OFFSET);
+        fn constraint_degree(&self) -> usize {
+            self.op.constraint_degree()
+        }
+    }
 
-        add_fp12_forbenius_map_constraints_ext_circuit(builder, yield_constr, local_values, next_values, FINAL_EXP_OP_OFFSET, Some(local_values[FINAL_EXP_FORBENIUS_MAP_SELECTOR]));
-        add_fp12_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, next_values, FINAL_EXP_OP_OFFSET, Some(local_values[FINAL_EXP_MUL_SELECTOR]));
-        add_cyclotomic_exp_constraints_ext_circuit(builder, yield_constr, local_values, next_values, FINAL_EXP_OP_OFFSET, Some(local_values[FINAL_EXP_CYCLOTOMIC_EXP_SELECTOR]));
-        add_fp12_conjugate_constraints_ext_circuit(builder, yield_constr, local_values, FINAL_EXP_OP_OFFSET, Some(local_values[FINAL_EXP_CONJUGATE_SELECTOR]));
-        add_cyclotomic_sq_constraints_ext_circuit(builder, yield_constr, local_values, next_values, FINAL_EXP_OP_OFFSET, Some(local_values[FINAL_EXP_CYCLOTOMIC_SQ_SELECTOR]));
+    #[test]
+    fn test_final_exponentiate_constraint_degree() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        // Each gadget stays within the degree it declares, which FINAL_EXP_CONSTRAINT_DEGREE is built from.
+        for op in [FinalExpOp::ForbeniusMap, FinalExpOp::Mul, FinalExpOp::CyclotomicExp, FinalExpOp::Conjugate, FinalExpOp::CyclotomicSq] {
+            let stark = FinalExpOpStark::<F, D> { op, _f: std::marker::PhantomData };
+            if let Err(e) = test_stark_low_degree::<F, _, D>(stark) {
+                panic!("{:?} exceeds its declared constraint degree {}: {}", op, op.constraint_degree(), e);
+            }
+        }
+        let stark = FinalExponentiateStark::<F, D>::new(TOTAL_ROW).unwrap();
+        assert_eq!(stark.constraint_degree(), 5);
+        test_stark_low_degree::<F, _, D>(stark).unwrap();
+        test_stark_low_degree::<F, _, D>(stark.with_output_one()).unwrap();
     }
 
-    fn constraint_degree(&self) -> usize {
-        5
+    #[test]
+    fn test_final_exponentiate_num_constraints() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FinalExponentiateStark<F, D>;
+
+        // The count does not depend on the number of rows. Update it along with the constraints, since it sizes the recursive verifier.
+        assert_eq!(num_constraints(&S::new(TOTAL_ROW).unwrap()), 334476);
+        assert_eq!(num_constraints(&S::with_min_rows()), 334476);
+        // Plus the 144 last row constraints on the limbs of the output.
+        assert_eq!(num_constraints(&S::with_min_rows().with_output_one()), 334476 + 24*3*2);
     }
 }
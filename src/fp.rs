@@ -3,7 +3,7 @@ use num_bigint::{BigUint, ToBigUint};
 use plonky2::{field::{extension::{Extendable, FieldExtension}, packed::PackedField, types::Field}, hash::hash_types::RichField, iop::ext_target::ExtensionTarget, plonk::circuit_builder::CircuitBuilder};
 use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
 
-use crate::{native::{add_u32_slices, add_u32_slices_12, get_bits_as_array, get_div_rem_modulus_from_biguint_12, get_selector_bits_from_u32, get_u32_vec_from_literal, get_u32_vec_from_literal_24, modulus, mul_u32_slice_u32, multiply_by_slice, sub_u32_slices, sub_u32_slices_12, Fp}, utils::*};
+use crate::{native::{add_u32_slices, add_u32_slices_12, get_bits_as_array, get_div_rem_modulus_from_biguint_12, get_selector_bits_from_u32, get_u32_vec_from_literal, get_u32_vec_from_literal_24, modulus, mul_u32_slice_u32, multiply_by_slice, negate_fp, sub_u32_slices, sub_u32_slices_12, Fp}, utils::*};
 
 // Fp Multiplication layout offsets
 /*
@@ -162,6 +162,117 @@ pub const FP_SINGLE_REDUCTION_ADDITION_OFFSET: usize = FP_SINGLE_REDUCE_X_OFFSET
 pub const FP_SINGLE_REDUCED_OFFSET: usize = FP_SINGLE_REDUCTION_ADDITION_OFFSET + FP_ADDITION_TOTAL;
 pub const FP_SINGLE_REDUCE_TOTAL: usize = FP_SINGLE_REDUCED_OFFSET + 12;
 
+// Fp multiplication modulo p layout offsets
+/*
+    These trace offsets are for multiplying two Fp elements followed by reduction of the result with the field prime and range check of the reduced result.
+    It needs 12 rows. The reduced result is stored in the slice [FP_MUL_MOD_REDUCE_OFFSET + REDUCED_OFFSET..FP_MUL_MOD_REDUCE_OFFSET + REDUCED_OFFSET + 12] in all rows.
+    FP_MUL_MOD_SELECTOR_OFFSET -> Selector to ensure that the input is same across all rows. Set 1 in all rows except last one.
+    FP_MUL_MOD_MULTIPLICATION_OFFSET -> offset at which the multiplication operation (without reduction) is done. Inputs are set at X_INPUT_OFFSET and Y_INPUT_OFFSET of this operation.
+    FP_MUL_MOD_RES_ROW_OFFSET -> Selector indicating which row contains result of the multiplication. Set 1 on the 11th row.
+    FP_MUL_MOD_REDUCE_OFFSET -> offset at which the reduction of the multiplication result is done.
+    FP_MUL_MOD_RANGECHECK_OFFSET -> offset at which the range check of the reduced result is done.
+*/
+pub const FP_MUL_MOD_SELECTOR_OFFSET: usize = 0;
+pub const FP_MUL_MOD_MULTIPLICATION_OFFSET: usize = FP_MUL_MOD_SELECTOR_OFFSET + 1;
+pub const FP_MUL_MOD_RES_ROW_OFFSET: usize = FP_MUL_MOD_MULTIPLICATION_OFFSET + FP_MULTIPLICATION_TOTAL_COLUMNS;
+pub const FP_MUL_MOD_REDUCE_OFFSET: usize = FP_MUL_MOD_RES_ROW_OFFSET + 1;
+pub const FP_MUL_MOD_RANGECHECK_OFFSET: usize = FP_MUL_MOD_REDUCE_OFFSET + REDUCTION_TOTAL;
+pub const FP_MUL_MOD_TOTAL: usize = FP_MUL_MOD_RANGECHECK_OFFSET + RANGE_CHECK_TOTAL;
+
+// Fp addition modulo p layout offsets
+/*
+    These trace offsets are for adding two Fp elements followed by reduction of the result with the field prime and range check of the reduced result. Needs 1 row.
+    FP_ADD_MOD_ADDITION_OFFSET -> offset at which the addition operation is done. Inputs are set at FP_ADDITION_X_OFFSET and FP_ADDITION_Y_OFFSET of this operation.
+    FP_ADD_MOD_REDUCE_OFFSET -> offset at which the reduction of the sum is done. Reduced result is at FP_SINGLE_REDUCED_OFFSET of this operation.
+    FP_ADD_MOD_RANGECHECK_OFFSET -> offset at which the range check of the reduced result is done.
+*/
+pub const FP_ADD_MOD_ADDITION_OFFSET: usize = 0;
+pub const FP_ADD_MOD_REDUCE_OFFSET: usize = FP_ADD_MOD_ADDITION_OFFSET + FP_ADDITION_TOTAL;
+pub const FP_ADD_MOD_RANGECHECK_OFFSET: usize = FP_ADD_MOD_REDUCE_OFFSET + FP_SINGLE_REDUCE_TOTAL;
+pub const FP_ADD_MOD_TOTAL: usize = FP_ADD_MOD_RANGECHECK_OFFSET + RANGE_CHECK_TOTAL;
+
+// Fp subtraction modulo p layout offsets
+/*
+    These trace offsets are for subtracting two Fp elements followed by reduction of the result with the field prime and range check of the reduced result. Needs 1 row.
+    The field prime is added to x first, so that the subtraction never underflows.
+    FP_SUB_MOD_ADDITION_OFFSET -> offset at which x + p is done. x is set at FP_ADDITION_X_OFFSET of this operation.
+    FP_SUB_MOD_SUBTRACTION_OFFSET -> offset at which (x + p) - y is done. y is set at FP_SUBTRACTION_Y_OFFSET of this operation.
+    FP_SUB_MOD_REDUCE_OFFSET -> offset at which the reduction of the difference is done. Reduced result is at FP_SINGLE_REDUCED_OFFSET of this operation.
+    FP_SUB_MOD_RANGECHECK_OFFSET -> offset at which the range check of the reduced result is done.
+*/
+pub const FP_SUB_MOD_ADDITION_OFFSET: usize = 0;
+pub const FP_SUB_MOD_SUBTRACTION_OFFSET: usize = FP_SUB_MOD_ADDITION_OFFSET + FP_ADDITION_TOTAL;
+pub const FP_SUB_MOD_REDUCE_OFFSET: usize = FP_SUB_MOD_SUBTRACTION_OFFSET + FP_SUBTRACTION_TOTAL;
+pub const FP_SUB_MOD_RANGECHECK_OFFSET: usize = FP_SUB_MOD_REDUCE_OFFSET + FP_SINGLE_REDUCE_TOTAL;
+pub const FP_SUB_MOD_TOTAL: usize = FP_SUB_MOD_RANGECHECK_OFFSET + RANGE_CHECK_TOTAL;
+
+// Fp negation modulo p layout offsets
+/*
+    These trace offsets are for negating an Fp element with the result reduced, i.e. the negation of zero is zero, unlike the negation of `fill_trace_negate_fp`. Needs 1 row.
+    FP_NEG_RES_OFFSET -> offset at which the negated element is set.
+    FP_NEG_RANGECHECK_OFFSET -> offset at which the range check of the negated element is done.
+    FP_NEG_ADD_MOD_OFFSET -> offset at which x + (-x) modulo p is done, whose result is constrained to be zero. x is set at FP_ADDITION_X_OFFSET of the addition of this operation.
+*/
+pub const FP_NEG_RES_OFFSET: usize = 0;
+pub const FP_NEG_RANGECHECK_OFFSET: usize = FP_NEG_RES_OFFSET + 12;
+pub const FP_NEG_ADD_MOD_OFFSET: usize = FP_NEG_RANGECHECK_OFFSET + RANGE_CHECK_TOTAL;
+pub const FP_NEG_TOTAL: usize = FP_NEG_ADD_MOD_OFFSET + FP_ADD_MOD_TOTAL;
+
+/// Column of the reduced result of an fp multiplication modulo p operation starting at `col`.
+pub fn fp_mul_mod_res(col: usize) -> usize {
+    col + FP_MUL_MOD_REDUCE_OFFSET + REDUCED_OFFSET
+}
+
+/// Column of the reduced result of an fp addition modulo p operation starting at `col`.
+pub fn fp_add_mod_res(col: usize) -> usize {
+    col + FP_ADD_MOD_REDUCE_OFFSET + FP_SINGLE_REDUCED_OFFSET
+}
+
+/// Column of the reduced result of an fp subtraction modulo p operation starting at `col`.
+pub fn fp_sub_mod_res(col: usize) -> usize {
+    col + FP_SUB_MOD_REDUCE_OFFSET + FP_SINGLE_REDUCED_OFFSET
+}
+
+/// Column of the result of an fp negation modulo p operation starting at `col`.
+pub fn fp_neg_res(col: usize) -> usize {
+    col + FP_NEG_RES_OFFSET
+}
+
+/// Column of the x input of an fp negation modulo p operation starting at `col`.
+pub fn fp_neg_x(col: usize) -> usize {
+    fp_add_mod_x(col + FP_NEG_ADD_MOD_OFFSET)
+}
+
+/// Column of the x input of an fp multiplication modulo p operation starting at `col`.
+pub fn fp_mul_mod_x(col: usize) -> usize {
+    col + FP_MUL_MOD_MULTIPLICATION_OFFSET + X_INPUT_OFFSET
+}
+
+/// Column of the y input of an fp multiplication modulo p operation starting at `col`.
+pub fn fp_mul_mod_y(col: usize) -> usize {
+    col + FP_MUL_MOD_MULTIPLICATION_OFFSET + Y_INPUT_OFFSET
+}
+
+/// Column of the x input of an fp addition modulo p operation starting at `col`.
+pub fn fp_add_mod_x(col: usize) -> usize {
+    col + FP_ADD_MOD_ADDITION_OFFSET + FP_ADDITION_X_OFFSET
+}
+
+/// Column of the y input of an fp addition modulo p operation starting at `col`.
+pub fn fp_add_mod_y(col: usize) -> usize {
+    col + FP_ADD_MOD_ADDITION_OFFSET + FP_ADDITION_Y_OFFSET
+}
+
+/// Column of the x input of an fp subtraction modulo p operation starting at `col`.
+pub fn fp_sub_mod_x(col: usize) -> usize {
+    col + FP_SUB_MOD_ADDITION_OFFSET + FP_ADDITION_X_OFFSET
+}
+
+/// Column of the y input of an fp subtraction modulo p operation starting at `col`.
+pub fn fp_sub_mod_y(col: usize) -> usize {
+    col + FP_SUB_MOD_SUBTRACTION_OFFSET + FP_SUBTRACTION_Y_OFFSET
+}
+
 macro_rules! bit_decomp_32 {
     ($row:expr, $col:expr, $f:ty, $p:ty) => {
         ((0..32).fold(<$p>::ZEROS, |acc, i| {
@@ -229,7 +340,7 @@ pub fn fill_trace_negate_fp<F: RichField + Extendable<D>,
     row: usize,
     start_col: usize
 ) {
-    let minus_x = (-Fp(x.to_owned())).0;
+    let minus_x = negate_fp(Fp(x.to_owned())).0;
     fill_trace_addition_fp(trace, x, &minus_x, row, start_col);
 }
 
@@ -245,7 +356,8 @@ pub fn fill_subtraction_trace<F: RichField + Extendable<D>,
     start_col: usize,
 ) {
     trace[row][start_col + SUBTRACTION_CHECK_OFFSET] = F::ONE;
-    let (x_y_diff, x_y_diff_borrow) = sub_u32_slices(&x, &y);
+    let (x_y_diff, x_y_diff_borrow, borrow_out) = sub_u32_slices(&x, &y);
+    debug_assert!(!borrow_out, "subtraction underflow, x < y");
     assign_u32_in_series(trace, row, start_col + SUBTRACTION_X_OFFSET, x);
     assign_u32_in_series(trace, row, start_col + SUBTRACTION_Y_OFFSET, y);
     assign_u32_in_series(trace, row, start_col + SUBTRACTION_DIFF_OFFSET, &x_y_diff);
@@ -300,7 +412,7 @@ pub fn fill_trace_reduce_single<F: RichField + Extendable<D>,
     row: usize,
     start_col: usize,
 ) -> [u32; 12] {
-    let (div, rem) = get_div_rem_modulus_from_biguint_12(BigUint::new(x.to_vec()));
+    let (div, rem) = get_div_rem_modulus_from_biguint_12(BigUint::new(x.to_vec())).unwrap();
     let div = div[0];
     let modulus = get_u32_vec_from_literal(modulus());
     fill_trace_multiply_single_fp(trace, &modulus, div, row, start_col + FP_SINGLE_REDUCE_MULTIPLICATION_OFFSET);
@@ -393,7 +505,7 @@ pub fn fill_reduction_trace<F: RichField + Extendable<D>,
     end_row: usize,
     start_col: usize,
 ) -> [u32; 12] {
-    let (div, rem) = get_div_rem_modulus_from_biguint_12(BigUint::new(x.to_vec()));
+    let (div, rem) = get_div_rem_modulus_from_biguint_12(BigUint::new(x.to_vec())).unwrap();
     let modulus = get_u32_vec_from_literal(modulus());
     fill_multiplication_trace_no_mod_reduction(trace, 
         &div,
@@ -427,6 +539,87 @@ pub fn fill_reduction_trace<F: RichField + Extendable<D>,
 
 }
 
+/// Fills the stark trace of fp multiplication modulo p. Inputs are 12 limbs each. Needs 12 rows. Fills the multiplication trace, the reduction trace of the product and the range check of the reduced result. Returns the answer as \[u32; 12\].
+pub fn fill_trace_fp_mul_mod<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(
+    trace: &mut Vec<[F; C]>,
+    x: &[u32; 12],
+    y: &[u32; 12],
+    start_row: usize,
+    end_row: usize,
+    start_col: usize,
+) -> [u32; 12] {
+    for row in start_row..end_row {
+        trace[row][start_col + FP_MUL_MOD_SELECTOR_OFFSET] = F::ONE;
+    }
+    fill_multiplication_trace_no_mod_reduction(trace, x, y, start_row, end_row, start_col + FP_MUL_MOD_MULTIPLICATION_OFFSET);
+    trace[start_row + 11][start_col + FP_MUL_MOD_RES_ROW_OFFSET] = F::ONE;
+    let x_y = get_u32_vec_from_literal_24(BigUint::new(x.to_vec()) * BigUint::new(y.to_vec()));
+    let res = fill_reduction_trace(trace, &x_y, start_row, end_row, start_col + FP_MUL_MOD_REDUCE_OFFSET);
+    for row in start_row..end_row + 1 {
+        fill_range_check_trace(trace, &res, row, start_col + FP_MUL_MOD_RANGECHECK_OFFSET);
+    }
+    res
+}
+
+/// Fills the stark trace of fp addition modulo p. Inputs are 12 limbs each. Needs 1 row. Returns the answer as \[u32; 12\].
+pub fn fill_trace_addition_fp_mod<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(
+    trace: &mut Vec<[F; C]>,
+    x: &[u32; 12],
+    y: &[u32; 12],
+    row: usize,
+    start_col: usize,
+) -> [u32; 12] {
+    fill_trace_addition_fp(trace, x, y, row, start_col + FP_ADD_MOD_ADDITION_OFFSET);
+    let x_y = get_u32_vec_from_literal(BigUint::new(x.to_vec()) + BigUint::new(y.to_vec()));
+    let res = fill_trace_reduce_single(trace, &x_y, row, start_col + FP_ADD_MOD_REDUCE_OFFSET);
+    fill_range_check_trace(trace, &res, row, start_col + FP_ADD_MOD_RANGECHECK_OFFSET);
+    res
+}
+
+/// Fills the stark trace of fp subtraction modulo p. Inputs are 12 limbs each. Needs 1 row. Fills trace of adding field prime p to x first, and then the trace for subtraction with y. Returns the answer as \[u32; 12\].
+pub fn fill_trace_subtraction_fp_mod<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(
+    trace: &mut Vec<[F; C]>,
+    x: &[u32; 12],
+    y: &[u32; 12],
+    row: usize,
+    start_col: usize,
+) -> [u32; 12] {
+    let modulus = get_u32_vec_from_literal(modulus());
+    fill_trace_addition_fp(trace, x, &modulus, row, start_col + FP_SUB_MOD_ADDITION_OFFSET);
+    let x_modulus = get_u32_vec_from_literal(BigUint::new(x.to_vec()) + BigUint::new(modulus.to_vec()));
+    fill_trace_subtraction_fp(trace, &x_modulus, y, row, start_col + FP_SUB_MOD_SUBTRACTION_OFFSET);
+    let x_y = get_u32_vec_from_literal(BigUint::new(x_modulus.to_vec()) - BigUint::new(y.to_vec()));
+    let res = fill_trace_reduce_single(trace, &x_y, row, start_col + FP_SUB_MOD_REDUCE_OFFSET);
+    fill_range_check_trace(trace, &res, row, start_col + FP_SUB_MOD_RANGECHECK_OFFSET);
+    res
+}
+
+/// Fills the stark trace of fp negation modulo p. Input is 12 limbs. Needs 1 row. Sets the reduced negation of `x` with its range check, and fills the trace of `x + (-x)` modulo p. Returns the negation as \[u32; 12\].
+pub fn fill_trace_neg_fp<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(
+    trace: &mut Vec<[F; C]>,
+    x: &[u32; 12],
+    row: usize,
+    start_col: usize,
+) -> [u32; 12] {
+    let res = (-Fp(*x)).0;
+    assign_u32_in_series(trace, row, start_col + FP_NEG_RES_OFFSET, &res);
+    fill_range_check_trace(trace, &res, row, start_col + FP_NEG_RANGECHECK_OFFSET);
+    fill_trace_addition_fp_mod(trace, x, &res, row, start_col + FP_NEG_ADD_MOD_OFFSET);
+    res
+}
+
 /// Constraints the operation for multiplication of two \[u32; 12\].
 ///
 /// Constraint the input values across this row and next row wherever selector is on.
@@ -1631,3 +1824,270 @@ pub fn add_reduce_constraints_ext_circuit<
         yield_constr.constraint_transition(builder, c);
     }
 }
+
+/// Constraints fp multiplication modulo p.
+/// Constraints the multiplication operation, then constraints the multiplication result (set in the 11th row) with the input of the reduction operation. Then constraints the reduction operation and the range check of the reduced result.
+pub fn add_fp_mul_mod_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+
+    add_multiplication_constraints(local_values, next_values, yield_constr, start_col + FP_MUL_MOD_MULTIPLICATION_OFFSET, bit_selector);
+    for i in 0..24 {
+        yield_constr.constraint(
+            bit_selector_val *
+            local_values[start_col + FP_MUL_MOD_RES_ROW_OFFSET] *
+            (local_values[start_col + FP_MUL_MOD_MULTIPLICATION_OFFSET + SUM_OFFSET + i] -
+            local_values[start_col + FP_MUL_MOD_REDUCE_OFFSET + REDUCE_X_OFFSET + i])
+        );
+    }
+    add_reduce_constraints(local_values, next_values, yield_constr, start_col + FP_MUL_MOD_REDUCE_OFFSET, start_col + FP_MUL_MOD_SELECTOR_OFFSET, bit_selector);
+    add_range_check_constraints(local_values, yield_constr, start_col + FP_MUL_MOD_RANGECHECK_OFFSET, bit_selector);
+}
+
+pub fn add_fp_mul_mod_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let bit_selector_val = bit_selector.unwrap_or(builder.constant_extension(F::Extension::ONE));
+
+    add_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FP_MUL_MOD_MULTIPLICATION_OFFSET, bit_selector);
+    for i in 0..24 {
+        let tmp = builder.mul_extension(bit_selector_val, local_values[start_col + FP_MUL_MOD_RES_ROW_OFFSET]);
+
+        let c = builder.sub_extension(local_values[start_col + FP_MUL_MOD_MULTIPLICATION_OFFSET + SUM_OFFSET + i], local_values[start_col + FP_MUL_MOD_REDUCE_OFFSET + REDUCE_X_OFFSET + i]);
+        let c = builder.mul_extension(tmp, c);
+        yield_constr.constraint(builder, c);
+    }
+    add_reduce_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FP_MUL_MOD_REDUCE_OFFSET, start_col + FP_MUL_MOD_SELECTOR_OFFSET, bit_selector);
+    add_range_check_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP_MUL_MOD_RANGECHECK_OFFSET, bit_selector);
+}
+
+/// Constraints fp addition modulo p.
+/// Constraints the addition operation, then constraints the sum with the input of the reduction operation. Then constraints the reduction operation and the range check of the reduced result.
+pub fn add_addition_fp_mod_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+
+    add_addition_fp_constraints(local_values, yield_constr, start_col + FP_ADD_MOD_ADDITION_OFFSET, bit_selector);
+    for i in 0..12 {
+        yield_constr.constraint(
+            bit_selector_val *
+            local_values[start_col + FP_ADD_MOD_ADDITION_OFFSET + FP_ADDITION_CHECK_OFFSET] *
+            (local_values[start_col + FP_ADD_MOD_ADDITION_OFFSET + FP_ADDITION_SUM_OFFSET + i] -
+            local_values[start_col + FP_ADD_MOD_REDUCE_OFFSET + FP_SINGLE_REDUCE_X_OFFSET + i])
+        );
+    }
+    add_fp_reduce_single_constraints(local_values, yield_constr, start_col + FP_ADD_MOD_REDUCE_OFFSET, bit_selector);
+    add_range_check_constraints(local_values, yield_constr, start_col + FP_ADD_MOD_RANGECHECK_OFFSET, bit_selector);
+}
+
+pub fn add_addition_fp_mod_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let bit_selector_val = bit_selector.unwrap_or(builder.constant_extension(F::Extension::ONE));
+
+    add_addition_fp_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP_ADD_MOD_ADDITION_OFFSET, bit_selector);
+    for i in 0..12 {
+        let tmp = builder.mul_extension(bit_selector_val, local_values[start_col + FP_ADD_MOD_ADDITION_OFFSET + FP_ADDITION_CHECK_OFFSET]);
+
+        let c = builder.sub_extension(local_values[start_col + FP_ADD_MOD_ADDITION_OFFSET + FP_ADDITION_SUM_OFFSET + i], local_values[start_col + FP_ADD_MOD_REDUCE_OFFSET + FP_SINGLE_REDUCE_X_OFFSET + i]);
+        let c = builder.mul_extension(tmp, c);
+        yield_constr.constraint(builder, c);
+    }
+    add_fp_reduce_single_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP_ADD_MOD_REDUCE_OFFSET, bit_selector);
+    add_range_check_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP_ADD_MOD_RANGECHECK_OFFSET, bit_selector);
+}
+
+/// Constraints fp subtraction modulo p.
+/// Constraints the addition of x with the field prime, then constraints the sum with the x input of the subtraction operation. Then constraints the subtraction, the reduction of the difference and the range check of the reduced result.
+pub fn add_subtraction_fp_mod_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+    let modulus = get_u32_vec_from_literal(modulus());
+
+    add_addition_fp_constraints(local_values, yield_constr, start_col + FP_SUB_MOD_ADDITION_OFFSET, bit_selector);
+    for i in 0..12 {
+        yield_constr.constraint(
+            bit_selector_val *
+            local_values[start_col + FP_SUB_MOD_ADDITION_OFFSET + FP_ADDITION_CHECK_OFFSET] *
+            (local_values[start_col + FP_SUB_MOD_ADDITION_OFFSET + FP_ADDITION_Y_OFFSET + i] -
+            FE::from_canonical_u32(modulus[i]))
+        );
+    }
+    add_subtraction_fp_constraints(local_values, yield_constr, start_col + FP_SUB_MOD_SUBTRACTION_OFFSET, bit_selector);
+    for i in 0..12 {
+        yield_constr.constraint(
+            bit_selector_val *
+            local_values[start_col + FP_SUB_MOD_SUBTRACTION_OFFSET + FP_SUBTRACTION_CHECK_OFFSET] *
+            (local_values[start_col + FP_SUB_MOD_ADDITION_OFFSET + FP_ADDITION_SUM_OFFSET + i] -
+            local_values[start_col + FP_SUB_MOD_SUBTRACTION_OFFSET + FP_SUBTRACTION_X_OFFSET + i])
+        );
+        yield_constr.constraint(
+            bit_selector_val *
+            local_values[start_col + FP_SUB_MOD_SUBTRACTION_OFFSET + FP_SUBTRACTION_CHECK_OFFSET] *
+            (local_values[start_col + FP_SUB_MOD_SUBTRACTION_OFFSET + FP_SUBTRACTION_DIFF_OFFSET + i] -
+            local_values[start_col + FP_SUB_MOD_REDUCE_OFFSET + FP_SINGLE_REDUCE_X_OFFSET + i])
+        );
+    }
+    add_fp_reduce_single_constraints(local_values, yield_constr, start_col + FP_SUB_MOD_REDUCE_OFFSET, bit_selector);
+    add_range_check_constraints(local_values, yield_constr, start_col + FP_SUB_MOD_RANGECHECK_OFFSET, bit_selector);
+}
+
+pub fn add_subtraction_fp_mod_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let bit_selector_val = bit_selector.unwrap_or(builder.constant_extension(F::Extension::ONE));
+    let modulus = get_u32_vec_from_literal(modulus());
+
+    add_addition_fp_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP_SUB_MOD_ADDITION_OFFSET, bit_selector);
+    for i in 0..12 {
+        let tmp = builder.mul_extension(bit_selector_val, local_values[start_col + FP_SUB_MOD_ADDITION_OFFSET + FP_ADDITION_CHECK_OFFSET]);
+        let lc = builder.constant_extension(F::Extension::from_canonical_u32(modulus[i]));
+
+        let c = builder.sub_extension(local_values[start_col + FP_SUB_MOD_ADDITION_OFFSET + FP_ADDITION_Y_OFFSET + i], lc);
+        let c = builder.mul_extension(tmp, c);
+        yield_constr.constraint(builder, c);
+    }
+    add_subtraction_fp_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP_SUB_MOD_SUBTRACTION_OFFSET, bit_selector);
+    for i in 0..12 {
+        let tmp = builder.mul_extension(bit_selector_val, local_values[start_col + FP_SUB_MOD_SUBTRACTION_OFFSET + FP_SUBTRACTION_CHECK_OFFSET]);
+
+        let c = builder.sub_extension(local_values[start_col + FP_SUB_MOD_ADDITION_OFFSET + FP_ADDITION_SUM_OFFSET + i], local_values[start_col + FP_SUB_MOD_SUBTRACTION_OFFSET + FP_SUBTRACTION_X_OFFSET + i]);
+        let c = builder.mul_extension(tmp, c);
+        yield_constr.constraint(builder, c);
+
+        let c = builder.sub_extension(local_values[start_col + FP_SUB_MOD_SUBTRACTION_OFFSET + FP_SUBTRACTION_DIFF_OFFSET + i], local_values[start_col + FP_SUB_MOD_REDUCE_OFFSET + FP_SINGLE_REDUCE_X_OFFSET + i]);
+        let c = builder.mul_extension(tmp, c);
+        yield_constr.constraint(builder, c);
+    }
+    add_fp_reduce_single_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP_SUB_MOD_REDUCE_OFFSET, bit_selector);
+    add_range_check_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP_SUB_MOD_RANGECHECK_OFFSET, bit_selector);
+}
+
+/// Constraints fp negation modulo p.
+/// Constraints the fp addition modulo p of x with the negated element, whose y input is constrained with the negated element and whose result is constrained to be zero, i.e. `x + (-x) == 0 mod p`. Then constraints the range check of the negated element, with its selector set, so that the negation is reduced.
+pub fn add_neg_fp_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+    let selector = local_values[start_col + FP_NEG_ADD_MOD_OFFSET + FP_ADD_MOD_ADDITION_OFFSET + FP_ADDITION_CHECK_OFFSET];
+
+    add_addition_fp_mod_constraints(local_values, yield_constr, start_col + FP_NEG_ADD_MOD_OFFSET, bit_selector);
+    for i in 0..12 {
+        yield_constr.constraint(
+            bit_selector_val * selector *
+            (local_values[fp_add_mod_y(start_col + FP_NEG_ADD_MOD_OFFSET) + i] - local_values[start_col + FP_NEG_RES_OFFSET + i])
+        );
+        yield_constr.constraint(
+            bit_selector_val * selector * local_values[fp_add_mod_res(start_col + FP_NEG_ADD_MOD_OFFSET) + i]
+        );
+    }
+    yield_constr.constraint(
+        bit_selector_val * selector *
+        (P::ONES - local_values[start_col + FP_NEG_RANGECHECK_OFFSET + RANGE_CHECK_SELECTOR_OFFSET])
+    );
+    add_range_check_constraints(local_values, yield_constr, start_col + FP_NEG_RANGECHECK_OFFSET, bit_selector);
+}
+
+pub fn add_neg_fp_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let one = builder.constant_extension(F::Extension::ONE);
+    let bit_selector_val = bit_selector.unwrap_or(one);
+    let selector = local_values[start_col + FP_NEG_ADD_MOD_OFFSET + FP_ADD_MOD_ADDITION_OFFSET + FP_ADDITION_CHECK_OFFSET];
+    let tmp = builder.mul_extension(bit_selector_val, selector);
+
+    add_addition_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP_NEG_ADD_MOD_OFFSET, bit_selector);
+    for i in 0..12 {
+        let c = builder.sub_extension(local_values[fp_add_mod_y(start_col + FP_NEG_ADD_MOD_OFFSET) + i], local_values[start_col + FP_NEG_RES_OFFSET + i]);
+        let c = builder.mul_extension(tmp, c);
+        yield_constr.constraint(builder, c);
+
+        let c = builder.mul_extension(tmp, local_values[fp_add_mod_res(start_col + FP_NEG_ADD_MOD_OFFSET) + i]);
+        yield_constr.constraint(builder, c);
+    }
+    let c = builder.sub_extension(one, local_values[start_col + FP_NEG_RANGECHECK_OFFSET + RANGE_CHECK_SELECTOR_OFFSET]);
+    let c = builder.mul_extension(tmp, c);
+    yield_constr.constraint(builder, c);
+    add_range_check_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP_NEG_RANGECHECK_OFFSET, bit_selector);
+}
@@ -1,7 +1,7 @@
 use num_bigint::BigUint;
 use plonky2::{field::{extension::{Extendable, FieldExtension}, packed::PackedField, types::Field}, hash::hash_types::RichField, iop::ext_target::ExtensionTarget, plonk::circuit_builder::CircuitBuilder};
 use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
-use crate::{native::{fp4_square, get_bls_12_381_parameter, mul_by_nonresidue, Fp, Fp12, Fp2, Fp6}, utils::*, fp::*, fp2::*, fp6::*};
+use crate::{constants::FP12_FORBENIUS_COEFFICIENTS, native::{fp4_square, mul_by_nonresidue, BLS_12_381_PARAMETER, Fp, Fp12, Fp2, Fp6}, utils::*, fp::*, fp2::*, fp6::*};
 
 // MultiplyBy014
 /*
@@ -23,9 +23,31 @@ pub const MULTIPLY_BY_014_T6_CALC_OFFSET: usize = MULTIPLY_BY_014_T5_CALC_OFFSET
 pub const MULTIPLY_BY_014_Y_CALC_OFFSET: usize = MULTIPLY_BY_014_T6_CALC_OFFSET + FP6_ADDITION_TOTAL + FP6_SUBTRACTION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*6;
 pub const MULTIPLY_BY_014_TOTAL: usize = MULTIPLY_BY_014_Y_CALC_OFFSET + FP6_ADDITION_TOTAL + FP6_SUBTRACTION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*6;
 
+// MultiplyBy034
+/*
+    These trace offsets are for multiplyBy034 (super::native::Fp12::multiply_by_034) function. The Ti's are defined in the native function definition. T0 is computed as three fp2 multiplications by o0. It needs 12 rows.
+*/
+pub const MULTIPLY_BY_034_SELECTOR_OFFSET: usize = 0;
+pub const MULTIPLY_BY_034_INPUT_OFFSET: usize = MULTIPLY_BY_034_SELECTOR_OFFSET + 1;
+pub const MULTIPLY_BY_034_O0_OFFSET: usize = MULTIPLY_BY_034_INPUT_OFFSET + 24*3*2;
+pub const MULTIPLY_BY_034_O3_OFFSET: usize = MULTIPLY_BY_034_O0_OFFSET + 24;
+pub const MULTIPLY_BY_034_O4_OFFSET: usize = MULTIPLY_BY_034_O3_OFFSET + 24;
+pub const MULTIPLY_BY_034_T0_CALC_OFFSET: usize = MULTIPLY_BY_034_O4_OFFSET + 24;
+pub const MULTIPLY_BY_034_T1_CALC_OFFSET: usize = MULTIPLY_BY_034_T0_CALC_OFFSET + TOTAL_COLUMNS_FP2_MULTIPLICATION*3;
+pub const MULTIPLY_BY_034_T2_CALC_OFFSET: usize = MULTIPLY_BY_034_T1_CALC_OFFSET + MULTIPLY_BY_01_TOTAL;
+pub const MULTIPLY_BY_034_X_CALC_OFFSET: usize = MULTIPLY_BY_034_T2_CALC_OFFSET + FP6_NON_RESIDUE_MUL_TOTAL;
+pub const MULTIPLY_BY_034_T3_CALC_OFFSET: usize = MULTIPLY_BY_034_X_CALC_OFFSET + FP6_ADDITION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*6;
+pub const MULTIPLY_BY_034_T4_CALC_OFFSET: usize = MULTIPLY_BY_034_T3_CALC_OFFSET + FP6_ADDITION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*6;
+pub const MULTIPLY_BY_034_T5_CALC_OFFSET: usize = MULTIPLY_BY_034_T4_CALC_OFFSET + FP2_ADDITION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*2;
+pub const MULTIPLY_BY_034_T6_CALC_OFFSET: usize = MULTIPLY_BY_034_T5_CALC_OFFSET + MULTIPLY_BY_01_TOTAL;
+pub const MULTIPLY_BY_034_Y_CALC_OFFSET: usize = MULTIPLY_BY_034_T6_CALC_OFFSET + FP6_ADDITION_TOTAL + FP6_SUBTRACTION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*6;
+pub const MULTIPLY_BY_034_TOTAL: usize = MULTIPLY_BY_034_Y_CALC_OFFSET + FP6_ADDITION_TOTAL + FP6_SUBTRACTION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*6;
+
 // FP12 multiplication offsets
 /*
     These trace offsets are for fp12 multiplication. It needs 12 rows. The Ti's are defined in (super::native::mul_fp_12).
+    The multiplication is Karatsuba over fp6, with 3 fp6 multiplications (T0, T1, T5) instead of 4, and the fp6 multiplication is Karatsuba over fp2, with 6 fp2 multiplications instead of 9.
+    The fp2 multiplication stays schoolbook. Karatsuba would save one fp multiplication, 135 columns, but adds 195 columns: the 12 limb additions x0 + x1 and y0 + y1, and two 24 limb subtractions in place of the 24 limb addition x0*y1 + x1*y0. See test_fp12_mul_column_count.
 */
 pub const FP12_MUL_SELECTOR_OFFSET: usize = 0;
 pub const FP12_MUL_X_INPUT_OFFSET: usize = FP12_MUL_SELECTOR_OFFSET + 1;
@@ -119,6 +141,13 @@ pub const FP12_FORBENIUS_MAP_C1_CALC_OFFSET: usize = FP12_FORBENIUS_MAP_C0_CALC_
 pub const FP12_FORBENIUS_MAP_C2_CALC_OFFSET: usize = FP12_FORBENIUS_MAP_C1_CALC_OFFSET + TOTAL_COLUMNS_FP2_MULTIPLICATION;
 pub const FP12_FORBENIUS_MAP_TOTAL_COLUMNS: usize = FP12_FORBENIUS_MAP_C2_CALC_OFFSET + TOTAL_COLUMNS_FP2_MULTIPLICATION;
 
+// Forbenius map then multiplication Fp12
+/*
+    These trace offsets are for forbenius_mul (super::native::Fp12::forbenius_mul) function. It needs 24 rows: the forbenius map in the first 12 rows at the forbenius map offsets, then the fp12 multiplication of its result in the next 12 rows at the fp12 multiplication offsets, in the same columns.
+    The X input of the multiplication is constrained to the reduced result columns of the forbenius map, so the intermediate value is never set in columns of its own.
+*/
+pub const FP12_FORBENIUS_MUL_TOTAL_COLUMNS: usize = if FP12_FORBENIUS_MAP_TOTAL_COLUMNS > FP12_MUL_TOTAL_COLUMNS { FP12_FORBENIUS_MAP_TOTAL_COLUMNS } else { FP12_MUL_TOTAL_COLUMNS };
+
 // Fp12 conjugate
 /*
     These trace offsets are for fp12 conjugate (super::native::Fp12::conjugate). It needs 1 row.
@@ -182,6 +211,65 @@ pub fn fill_trace_multiply_by_014<F: RichField + Extendable<D>,
     }
 }
 
+/// Fills trace of [multiply_by_034](super::native::Fp12::multiply_by_034) function. Input is 12\*12 limbs and three 12\*2 limbs. Needs 12 rows.
+pub fn fill_trace_multiply_by_034<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, x: &Fp12, o0: &Fp2, o3: &Fp2, o4: &Fp2, start_row: usize, end_row: usize, start_col: usize) {
+    for row in start_row..end_row+1 {
+        for i in 0..12 {
+            assign_u32_in_series(trace, row, start_col + MULTIPLY_BY_034_INPUT_OFFSET + i*12, &x.0[i].0);
+        }
+        for i in 0..2 {
+            assign_u32_in_series(trace, row, start_col + MULTIPLY_BY_034_O0_OFFSET + i*12, &o0.0[i].0);
+            assign_u32_in_series(trace, row, start_col + MULTIPLY_BY_034_O3_OFFSET + i*12, &o3.0[i].0);
+            assign_u32_in_series(trace, row, start_col + MULTIPLY_BY_034_O4_OFFSET + i*12, &o4.0[i].0);
+        }
+        trace[row][start_col + MULTIPLY_BY_034_SELECTOR_OFFSET] = F::ONE;
+    }
+    trace[end_row][start_col + MULTIPLY_BY_034_SELECTOR_OFFSET] = F::ZERO;
+
+    let c0 = Fp6(x.0[..6].try_into().unwrap());
+    let c1 = Fp6(x.0[6..].try_into().unwrap());
+
+    let mut t0 = [Fp::zero(); 6];
+    for i in 0..3 {
+        let c = Fp2([c0.0[i*2], c0.0[i*2+1]]);
+        t0[i*2..i*2+2].copy_from_slice(&(c*(*o0)).0);
+        generate_trace_fp2_mul(trace, c.get_u32_slice(), o0.get_u32_slice(), start_row, end_row, start_col + MULTIPLY_BY_034_T0_CALC_OFFSET + i*TOTAL_COLUMNS_FP2_MULTIPLICATION);
+    }
+    let t0 = Fp6(t0);
+    let t1 = c1.multiply_by_01(*o3, *o4);
+    fill_trace_multiply_by_01(trace, &c1, o3, o4, start_row, end_row, start_col + MULTIPLY_BY_034_T1_CALC_OFFSET);
+    let t2 = mul_by_nonresidue(t1.0);
+    for row in start_row..end_row+1 {
+        fill_trace_non_residue_multiplication_fp6(trace, &t1, row, start_col + MULTIPLY_BY_034_T2_CALC_OFFSET);
+    }
+    let _x = t2+t0;
+    for row in start_row..end_row+1 {
+        fill_trace_addition_with_reduction_fp6(trace, &t2, &t0, row, start_col + MULTIPLY_BY_034_X_CALC_OFFSET);
+    }
+
+    let t3 = c0+c1;
+    for row in start_row..end_row+1 {
+        fill_trace_addition_with_reduction_fp6(trace, &c0, &c1, row, start_col + MULTIPLY_BY_034_T3_CALC_OFFSET);
+    }
+    let t4 = (*o0)+(*o3);
+    for row in start_row..end_row+1 {
+        fill_trace_addition_with_reduction(trace, &o0.get_u32_slice(), &o3.get_u32_slice(), row, start_col + MULTIPLY_BY_034_T4_CALC_OFFSET);
+    }
+    let t5 = t3.multiply_by_01(t4, *o4);
+    fill_trace_multiply_by_01(trace, &t3, &t4, o4, start_row, end_row, start_col + MULTIPLY_BY_034_T5_CALC_OFFSET);
+    let t6 = t5-t0;
+    for row in start_row..end_row+1 {
+        fill_trace_subtraction_with_reduction_fp6(trace, &t5, &t0, row, start_col + MULTIPLY_BY_034_T6_CALC_OFFSET);
+    }
+    let _y = t6-t1;
+    for row in start_row..end_row+1 {
+        fill_trace_subtraction_with_reduction_fp6(trace, &t6, &t1, row, start_col + MULTIPLY_BY_034_Y_CALC_OFFSET);
+    }
+}
+
 /// Fills stark trace for fp12 multiplication. Inputs are 12*12 limbs each. Needs 12 rows.
 pub fn fill_trace_fp12_multiplication<F: RichField + Extendable<D>,
     const D: usize,
@@ -329,11 +417,28 @@ pub fn fill_trace_cyclotomic_sq<F: RichField + Extendable<D>,
     }
 }
 
-/// Fills trace of [cyclotomicExponent](super::native::Fp12::cyclotocmicExponent) function. Input is 12\*12 limbs. Needs 12\*70 rows. For each bit 0 of bls12-381 parameter, fills the trace for cyclotomicSquare computation. For each bit 1 of the bls12-381 parameter, fills trace for cyclotomic square computation in 12 rows, then fills the trace for fp12 multiplication computation in the next 12 rows and also sets `trace[row][start_col + BIT1_SELECTOR_OFFSET]` to 1 for these rows. After going through all bits of the bls12-381 parameter, fills the result in the next row's Z_OFFSET, while also setting RES_ROW_SELECTOR to 1.
+/// Number of rows used by [fill_trace_cyclotomic_exp_by] for the exponent `exp`: 12 rows for each cyclotomic square (one per bit) and for each fp12 multiplication (one per 1 bit), plus the result row.
+pub const fn cyclotomic_exp_rows(exp: u64) -> usize {
+    ((64 - exp.leading_zeros()) + exp.count_ones()) as usize * 12 + 1
+}
+
+/// Fills trace of [cyclotomicExponent](super::native::Fp12::cyclotocmic_exponent) function, i.e. [fill_trace_cyclotomic_exp_by] with the bls12-381 parameter. Needs 12\*70 + 1 rows.
 pub fn fill_trace_cyclotomic_exp<F: RichField + Extendable<D>,
     const D: usize,
     const C: usize,
 >(trace: &mut Vec<[F; C]>, x: &Fp12, start_row: usize, end_row: usize, start_col: usize) {
+    fill_trace_cyclotomic_exp_by(trace, x, BLS_12_381_PARAMETER, start_row, end_row, start_col);
+}
+
+/// Fills trace of [cyclotomic_exp_by](super::native::Fp12::cyclotomic_exp_by) function. Input is 12\*12 limbs and a non zero exponent. Needs [cyclotomic_exp_rows]`(exp)` rows. For each bit 0 of the exponent, fills the trace for cyclotomicSquare computation. For each bit 1 of the exponent, fills trace for cyclotomic square computation in 12 rows, then fills the trace for fp12 multiplication computation in the next 12 rows and also sets `trace[row][start_col + BIT1_SELECTOR_OFFSET]` to 1 for these rows. After going through all bits of the exponent, fills the result in the next row's Z_OFFSET, while also setting RES_ROW_SELECTOR to 1.
+pub fn fill_trace_cyclotomic_exp_by<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, x: &Fp12, exp: u64, start_row: usize, end_row: usize, start_col: usize) {
+    assert!(exp != 0, "cyclotomic exponent must be non zero");
+    let rows = cyclotomic_exp_rows(exp);
+    let ops = (rows - 1) / 12;
+    assert_eq!(end_row + 1 - start_row, rows);
     for row in start_row..end_row+1 {
         assign_u32_in_series(trace, row, start_col + INPUT_OFFSET, &x.get_u32_slice().concat());
         trace[row][start_col + CYCLOTOMIC_EXP_SELECTOR_OFFSET] = F::ONE;
@@ -341,11 +446,10 @@ pub fn fill_trace_cyclotomic_exp<F: RichField + Extendable<D>,
     trace[end_row][start_col + CYCLOTOMIC_EXP_SELECTOR_OFFSET] = F::ZERO;
     trace[start_row][start_col + CYCLOTOMIC_EXP_START_ROW] = F::ONE;
     let mut z = Fp12::one();
-    let mut i  = get_bls_12_381_parameter().bits() - 1;
+    let mut i = 63 - exp.leading_zeros();
     let mut bitone = false;
-    assert_eq!(end_row + 1 - start_row, 70*12 + 1);
 
-    for j in 0..70 {
+    for j in 0..ops {
         let s_row = start_row + j*12;
         let e_row = s_row + 11;
         for row in s_row..e_row+1 {
@@ -362,15 +466,15 @@ pub fn fill_trace_cyclotomic_exp<F: RichField + Extendable<D>,
             fill_trace_cyclotomic_sq(trace, &z, s_row, e_row, start_col + Z_CYCLOTOMIC_SQ_OFFSET);
             z = z.cyclotomic_square();
         }
-        if get_bls_12_381_parameter().bit(i) && !bitone {
+        if (exp >> i) & 1 == 1 && !bitone {
             bitone = true;
-        } else if j < 69 {
+        } else if j < ops - 1 {
             i -= 1;
             bitone = false;
         }
     }
-    trace[start_row + 70*12][start_col + RES_ROW_SELECTOR_OFFSET] = F::ONE;
-    assign_u32_in_series(trace, start_row + 70*12, start_col + Z_OFFSET, &z.get_u32_slice().concat());
+    trace[end_row][start_col + RES_ROW_SELECTOR_OFFSET] = F::ONE;
+    assign_u32_in_series(trace, end_row, start_col + Z_OFFSET, &z.get_u32_slice().concat());
 }
 
 /// Fills trace of [forbenius_map](super::native::Fp12::forbenius_map) function. Input is 12*12 limbs and usize. Needs 12 rows.
@@ -401,13 +505,24 @@ pub fn fill_trace_fp12_forbenius_map<F: RichField + Extendable<D>,
     let c0 = Fp2(c0c1c2.0[0..2].to_vec().try_into().unwrap());
     let c1 = Fp2(c0c1c2.0[2..4].to_vec().try_into().unwrap());
     let c2 = Fp2(c0c1c2.0[4..6].to_vec().try_into().unwrap());
-    let forbenius_coefficients = Fp12::forbenius_coefficients();
+    let forbenius_coefficients = *FP12_FORBENIUS_COEFFICIENTS;
     let coeff = forbenius_coefficients[pow % 12];
     generate_trace_fp2_mul(trace, c0.get_u32_slice(), coeff.get_u32_slice(), start_row, end_row, start_col + FP12_FORBENIUS_MAP_C0_CALC_OFFSET);
     generate_trace_fp2_mul(trace, c1.get_u32_slice(), coeff.get_u32_slice(), start_row, end_row, start_col + FP12_FORBENIUS_MAP_C1_CALC_OFFSET);
     generate_trace_fp2_mul(trace, c2.get_u32_slice(), coeff.get_u32_slice(), start_row, end_row, start_col + FP12_FORBENIUS_MAP_C2_CALC_OFFSET);
 }
 
+/// Fills trace of [forbenius_mul](super::native::Fp12::forbenius_mul) function. Inputs are 12*12 limbs each and usize. Needs 24 rows: fills the trace for the forbenius map of `x` in the first 12 rows, then the trace for the multiplication of its result by `y` in the next 12 rows, both from `start_col`. Returns the product.
+pub fn fill_trace_fp12_forbenius_mul<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, x: &Fp12, pow: usize, y: &Fp12, start_row: usize, end_row: usize, start_col: usize) -> Fp12 {
+    let x_pow = x.forbenius_map(pow);
+    fill_trace_fp12_forbenius_map(trace, x, pow, start_row, start_row + 11, start_col);
+    fill_trace_fp12_multiplication(trace, &x_pow, y, start_row + 12, end_row, start_col);
+    x_pow * *y
+}
+
 /// Fill trace of [conjugate](super::native::Fp12::conjugate) function. Input is 12*12 limbs. Needs 1 row.
 pub fn fill_trace_fp12_conjugate<F: RichField + Extendable<D>,
     const D: usize,
@@ -521,34 +636,15 @@ pub fn add_multiply_by_014_constraints<F: RichField + Extendable<D>,
     add_multiply_by_1_constraints(local_values, next_values, yield_constr, start_col + MULTIPLY_BY_014_T1_CALC_OFFSET, bit_selector);
 
     // T2
-    for j in 0..2 {
-        let (x_offset, yz_offset) = if j==0 {
-            (FP2_NON_RESIDUE_MUL_Z0_REDUCE_OFFSET, Z1_REDUCE_OFFSET)
-        } else {
-            (FP2_NON_RESIDUE_MUL_Z1_REDUCE_OFFSET, Z2_REDUCE_OFFSET)
-        };
-        for i in 0..12 {
-            yield_constr.constraint(
-            bit_selector_val *
-                local_values[start_col + MULTIPLY_BY_014_T2_CALC_OFFSET + FP6_NON_RESIDUE_MUL_CHECK_OFFSET] *
-                (local_values[start_col + MULTIPLY_BY_014_T2_CALC_OFFSET + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + i + j*12] -
-                local_values[start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_X_CALC_OFFSET + x_offset + FP_SINGLE_REDUCED_OFFSET + i])
-            );
-            yield_constr.constraint(
-            bit_selector_val *
-                local_values[start_col + MULTIPLY_BY_014_T2_CALC_OFFSET + FP6_NON_RESIDUE_MUL_CHECK_OFFSET] *
-                (local_values[start_col + MULTIPLY_BY_014_T2_CALC_OFFSET + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + i + j*12 + 24] -
-                local_values[start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_Y_CALC_OFFSET + yz_offset + REDUCED_OFFSET + i])
-            );
-            yield_constr.constraint(
-            bit_selector_val *
-                local_values[start_col + MULTIPLY_BY_014_T2_CALC_OFFSET + FP6_NON_RESIDUE_MUL_CHECK_OFFSET] *
-                (local_values[start_col + MULTIPLY_BY_014_T2_CALC_OFFSET + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + i + j*12 + 48] -
-                local_values[start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_Z_CALC_OFFSET + yz_offset + REDUCED_OFFSET + i])
-            );
-        }
-    }
-    add_non_residue_multiplication_fp6_constraints(local_values, yield_constr, start_col + MULTIPLY_BY_014_T2_CALC_OFFSET, bit_selector);
+    let t1_cols = [
+        start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_X_CALC_OFFSET + FP2_NON_RESIDUE_MUL_Z0_REDUCE_OFFSET + FP_SINGLE_REDUCED_OFFSET,
+        start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_X_CALC_OFFSET + FP2_NON_RESIDUE_MUL_Z1_REDUCE_OFFSET + FP_SINGLE_REDUCED_OFFSET,
+        start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_Y_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET,
+        start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_Y_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET,
+        start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_Z_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET,
+        start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_Z_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET,
+    ];
+    add_mul_by_nonresidue_fp6_constraints(local_values, yield_constr, start_col + MULTIPLY_BY_014_T2_CALC_OFFSET, t1_cols, bit_selector);
 
     // X
     for j in 0..6 {
@@ -818,32 +914,15 @@ pub fn add_multiply_by_014_constraints_ext_circuit<
     add_multiply_by_1_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + MULTIPLY_BY_014_T1_CALC_OFFSET, bit_selector);
 
     // T2
-    for j in 0..2 {
-        let (x_offset, yz_offset) = if j==0 {
-            (FP2_NON_RESIDUE_MUL_Z0_REDUCE_OFFSET, Z1_REDUCE_OFFSET)
-        } else {
-            (FP2_NON_RESIDUE_MUL_Z1_REDUCE_OFFSET, Z2_REDUCE_OFFSET)
-        };
-        for i in 0..12 {
-            let mul_tmp = local_values[start_col + MULTIPLY_BY_014_T2_CALC_OFFSET + FP6_NON_RESIDUE_MUL_CHECK_OFFSET];
-
-            let sub_tmp1 = builder.sub_extension(local_values[start_col + MULTIPLY_BY_014_T2_CALC_OFFSET + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + i + j*12] , local_values[start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_X_CALC_OFFSET + x_offset + FP_SINGLE_REDUCED_OFFSET + i]);
-            let c1 = builder.mul_extension(sub_tmp1, mul_tmp);
-            let c = builder.mul_extension(bit_selector_val, c1);
-            yield_constr.constraint(builder,c);
-
-            let sub_tmp2 = builder.sub_extension(local_values[start_col + MULTIPLY_BY_014_T2_CALC_OFFSET + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + i + j*12 + 24] , local_values[start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_Y_CALC_OFFSET + yz_offset + REDUCED_OFFSET + i]);
-            let c2 = builder.mul_extension(sub_tmp2, mul_tmp);
-            let c = builder.mul_extension(bit_selector_val, c2);
-            yield_constr.constraint(builder,c);
-
-            let sub_tmp3 = builder.sub_extension(local_values[start_col + MULTIPLY_BY_014_T2_CALC_OFFSET + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + i + j*12 + 48] , local_values[start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_Z_CALC_OFFSET + yz_offset + REDUCED_OFFSET + i]);
-            let c3 = builder.mul_extension(sub_tmp3, mul_tmp);
-            let c = builder.mul_extension(bit_selector_val, c3);
-            yield_constr.constraint(builder,c);
-        }
-    }
-    add_non_residue_multiplication_fp6_constraints_ext_circuit(builder, yield_constr, local_values, start_col + MULTIPLY_BY_014_T2_CALC_OFFSET, bit_selector);
+    let t1_cols = [
+        start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_X_CALC_OFFSET + FP2_NON_RESIDUE_MUL_Z0_REDUCE_OFFSET + FP_SINGLE_REDUCED_OFFSET,
+        start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_X_CALC_OFFSET + FP2_NON_RESIDUE_MUL_Z1_REDUCE_OFFSET + FP_SINGLE_REDUCED_OFFSET,
+        start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_Y_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET,
+        start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_Y_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET,
+        start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_Z_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET,
+        start_col + MULTIPLY_BY_014_T1_CALC_OFFSET + MULTIPLY_BY_1_Z_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET,
+    ];
+    add_mul_by_nonresidue_fp6_constraints_ext_circuit(builder, yield_constr, local_values, start_col + MULTIPLY_BY_014_T2_CALC_OFFSET, t1_cols, bit_selector);
 
     // X
     for j in 0..6 {
@@ -1019,9 +1098,212 @@ pub fn add_multiply_by_014_constraints_ext_circuit<
     add_subtraction_with_reduction_constraints_fp6_ext_circuit(builder, yield_constr, local_values, start_col + MULTIPLY_BY_014_Y_CALC_OFFSET, bit_selector);
 }
 
+/// Columns of the sum of two fp6 elements reduced with [fill_trace_addition_with_reduction_fp6], or of their difference with [fill_trace_subtraction_with_reduction_fp6] when `subtraction` is set.
+fn fp6_reduced_cols(col: usize, subtraction: bool) -> [usize; 6] {
+    let offset = if subtraction { FP6_ADDITION_TOTAL + FP6_SUBTRACTION_TOTAL } else { FP6_ADDITION_TOTAL };
+    core::array::from_fn(|j| col + offset + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*j + FP_SINGLE_REDUCED_OFFSET)
+}
+
+/// Columns of the result of the [multiply_by_01](super::native::Fp6::multiply_by_01) gadget starting at `col`.
+fn multiply_by_01_result_cols(col: usize) -> [usize; 6] {
+    [
+        col + MULTIPLY_BY_01_X_CALC_OFFSET + FP2_ADDITION_TOTAL + FP_SINGLE_REDUCED_OFFSET,
+        col + MULTIPLY_BY_01_X_CALC_OFFSET + FP2_ADDITION_TOTAL + FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL + FP_SINGLE_REDUCED_OFFSET,
+        col + MULTIPLY_BY_01_Y_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + FP_SINGLE_REDUCED_OFFSET,
+        col + MULTIPLY_BY_01_Y_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL + FP_SINGLE_REDUCED_OFFSET,
+        col + MULTIPLY_BY_01_Z_CALC_OFFSET + FP2_ADDITION_TOTAL + FP_SINGLE_REDUCED_OFFSET,
+        col + MULTIPLY_BY_01_Z_CALC_OFFSET + FP2_ADDITION_TOTAL + FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL + FP_SINGLE_REDUCED_OFFSET,
+    ]
+}
+
+/// Triples of (selector column, column, column) of the [multiply_by_034](super::native::Fp12::multiply_by_034) gadget, whose 12 limbs are equal wherever the selector is on. Links every Ti to the inputs or to the results of the earlier Ti's.
+fn multiply_by_034_links(start_col: usize) -> Vec<(usize, usize, usize)> {
+    let fp6_addition_offsets = [
+        FP6_ADDITION_0_OFFSET + FP2_ADDITION_0_OFFSET, FP6_ADDITION_0_OFFSET + FP2_ADDITION_1_OFFSET,
+        FP6_ADDITION_1_OFFSET + FP2_ADDITION_0_OFFSET, FP6_ADDITION_1_OFFSET + FP2_ADDITION_1_OFFSET,
+        FP6_ADDITION_2_OFFSET + FP2_ADDITION_0_OFFSET, FP6_ADDITION_2_OFFSET + FP2_ADDITION_1_OFFSET,
+    ];
+    let fp6_subtraction_offsets = [
+        FP6_SUBTRACTION_0_OFFSET + FP2_SUBTRACTION_0_OFFSET, FP6_SUBTRACTION_0_OFFSET + FP2_SUBTRACTION_1_OFFSET,
+        FP6_SUBTRACTION_1_OFFSET + FP2_SUBTRACTION_0_OFFSET, FP6_SUBTRACTION_1_OFFSET + FP2_SUBTRACTION_1_OFFSET,
+        FP6_SUBTRACTION_2_OFFSET + FP2_SUBTRACTION_0_OFFSET, FP6_SUBTRACTION_2_OFFSET + FP2_SUBTRACTION_1_OFFSET,
+    ];
+    let input = start_col + MULTIPLY_BY_034_INPUT_OFFSET;
+    let t0_cols: [usize; 6] = core::array::from_fn(|j| {
+        start_col + MULTIPLY_BY_034_T0_CALC_OFFSET + (j/2)*TOTAL_COLUMNS_FP2_MULTIPLICATION + [Z1_REDUCE_OFFSET, Z2_REDUCE_OFFSET][j%2] + REDUCED_OFFSET
+    });
+    let t1_cols = multiply_by_01_result_cols(start_col + MULTIPLY_BY_034_T1_CALC_OFFSET);
+    let t2 = start_col + MULTIPLY_BY_034_T2_CALC_OFFSET;
+    let t2_cols = [
+        t2 + FP6_NON_RESIDUE_MUL_C2 + FP2_NON_RESIDUE_MUL_Z0_REDUCE_OFFSET + FP_SINGLE_REDUCED_OFFSET,
+        t2 + FP6_NON_RESIDUE_MUL_C2 + FP2_NON_RESIDUE_MUL_Z1_REDUCE_OFFSET + FP_SINGLE_REDUCED_OFFSET,
+        t2 + FP6_NON_RESIDUE_MUL_INPUT_OFFSET,
+        t2 + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + 12,
+        t2 + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + 24,
+        t2 + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + 36,
+    ];
+    let t3_cols = fp6_reduced_cols(start_col + MULTIPLY_BY_034_T3_CALC_OFFSET, false);
+    let t4_cols: [usize; 2] = core::array::from_fn(|k| {
+        start_col + MULTIPLY_BY_034_T4_CALC_OFFSET + FP2_ADDITION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*k + FP_SINGLE_REDUCED_OFFSET
+    });
+    let t5_cols = multiply_by_01_result_cols(start_col + MULTIPLY_BY_034_T5_CALC_OFFSET);
+    let t6_cols = fp6_reduced_cols(start_col + MULTIPLY_BY_034_T6_CALC_OFFSET, true);
+
+    let mut links = vec![];
+    for k in 0..2 {
+        let o0 = start_col + MULTIPLY_BY_034_O0_OFFSET + k*12;
+        let o3 = start_col + MULTIPLY_BY_034_O3_OFFSET + k*12;
+        let o4 = start_col + MULTIPLY_BY_034_O4_OFFSET + k*12;
+        // T0
+        for i in 0..3 {
+            let mul = start_col + MULTIPLY_BY_034_T0_CALC_OFFSET + i*TOTAL_COLUMNS_FP2_MULTIPLICATION;
+            links.push((mul + FP2_FP2_SELECTOR_OFFSET, mul + FP2_FP2_X_INPUT_OFFSET + k*12, input + (i*2 + k)*12));
+            links.push((mul + FP2_FP2_SELECTOR_OFFSET, mul + FP2_FP2_Y_INPUT_OFFSET + k*12, o0));
+        }
+        // T1
+        let t1 = start_col + MULTIPLY_BY_034_T1_CALC_OFFSET;
+        links.push((t1 + MULTIPLY_BY_01_SELECTOR_OFFSET, t1 + MULTIPLY_BY_01_B0_OFFSET + k*12, o3));
+        links.push((t1 + MULTIPLY_BY_01_SELECTOR_OFFSET, t1 + MULTIPLY_BY_01_B1_OFFSET + k*12, o4));
+        // T4
+        let addition = start_col + MULTIPLY_BY_034_T4_CALC_OFFSET + [FP2_ADDITION_0_OFFSET, FP2_ADDITION_1_OFFSET][k];
+        links.push((addition + FP_ADDITION_CHECK_OFFSET, addition + FP_ADDITION_X_OFFSET, o0));
+        links.push((addition + FP_ADDITION_CHECK_OFFSET, addition + FP_ADDITION_Y_OFFSET, o3));
+        // T5
+        let t5 = start_col + MULTIPLY_BY_034_T5_CALC_OFFSET;
+        links.push((t5 + MULTIPLY_BY_01_SELECTOR_OFFSET, t5 + MULTIPLY_BY_01_B0_OFFSET + k*12, t4_cols[k]));
+        links.push((t5 + MULTIPLY_BY_01_SELECTOR_OFFSET, t5 + MULTIPLY_BY_01_B1_OFFSET + k*12, o4));
+    }
+    for j in 0..6 {
+        // T1
+        let t1 = start_col + MULTIPLY_BY_034_T1_CALC_OFFSET;
+        links.push((t1 + MULTIPLY_BY_01_SELECTOR_OFFSET, t1 + MULTIPLY_BY_01_INPUT_OFFSET + j*12, input + 24*3 + j*12));
+        // X
+        let addition = start_col + MULTIPLY_BY_034_X_CALC_OFFSET + fp6_addition_offsets[j];
+        links.push((addition + FP_ADDITION_CHECK_OFFSET, addition + FP_ADDITION_X_OFFSET, t2_cols[j]));
+        links.push((addition + FP_ADDITION_CHECK_OFFSET, addition + FP_ADDITION_Y_OFFSET, t0_cols[j]));
+        // T3
+        let addition = start_col + MULTIPLY_BY_034_T3_CALC_OFFSET + fp6_addition_offsets[j];
+        links.push((addition + FP_ADDITION_CHECK_OFFSET, addition + FP_ADDITION_X_OFFSET, input + j*12));
+        links.push((addition + FP_ADDITION_CHECK_OFFSET, addition + FP_ADDITION_Y_OFFSET, input + 24*3 + j*12));
+        // T5
+        let t5 = start_col + MULTIPLY_BY_034_T5_CALC_OFFSET;
+        links.push((t5 + MULTIPLY_BY_01_SELECTOR_OFFSET, t5 + MULTIPLY_BY_01_INPUT_OFFSET + j*12, t3_cols[j]));
+        // T6
+        let addition = start_col + MULTIPLY_BY_034_T6_CALC_OFFSET + fp6_addition_offsets[j];
+        let subtraction = start_col + MULTIPLY_BY_034_T6_CALC_OFFSET + FP6_ADDITION_TOTAL + fp6_subtraction_offsets[j];
+        links.push((addition + FP_ADDITION_CHECK_OFFSET, addition + FP_ADDITION_X_OFFSET, t5_cols[j]));
+        links.push((subtraction + FP_SUBTRACTION_CHECK_OFFSET, subtraction + FP_SUBTRACTION_Y_OFFSET, t0_cols[j]));
+        // Y
+        let addition = start_col + MULTIPLY_BY_034_Y_CALC_OFFSET + fp6_addition_offsets[j];
+        let subtraction = start_col + MULTIPLY_BY_034_Y_CALC_OFFSET + FP6_ADDITION_TOTAL + fp6_subtraction_offsets[j];
+        links.push((addition + FP_ADDITION_CHECK_OFFSET, addition + FP_ADDITION_X_OFFSET, t6_cols[j]));
+        links.push((subtraction + FP_SUBTRACTION_CHECK_OFFSET, subtraction + FP_SUBTRACTION_Y_OFFSET, t1_cols[j]));
+    }
+    links
+}
+
+/// Constraints [multiply_by_034](super::native::Fp12::multiply_by_034) function.
+///
+/// Constraints inputs across this and next row, wherever selector is set to on. Constraints all the Ti's (defined in the native function) accordinng to their respective operations. T0 only needs three fp2 multiplications, so the gadget has far fewer columns than [add_fp12_multiplication_constraints].
+pub fn add_multiply_by_034_constraints<F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+    >(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+
+    for i in MULTIPLY_BY_034_INPUT_OFFSET..MULTIPLY_BY_034_T0_CALC_OFFSET {
+        yield_constr.constraint_transition(
+            bit_selector_val *
+            local_values[start_col + MULTIPLY_BY_034_SELECTOR_OFFSET] *
+            (local_values[start_col + i] - next_values[start_col + i])
+        );
+    }
+
+    for (selector, x, y) in multiply_by_034_links(start_col) {
+        for i in 0..12 {
+            yield_constr.constraint(
+                bit_selector_val *
+                local_values[selector] *
+                (local_values[x + i] - local_values[y + i])
+            );
+        }
+    }
+
+    for i in 0..3 {
+        add_fp2_mul_constraints(local_values, next_values, yield_constr, start_col + MULTIPLY_BY_034_T0_CALC_OFFSET + i*TOTAL_COLUMNS_FP2_MULTIPLICATION, bit_selector);
+    }
+    add_multiply_by_01_constraints(local_values, next_values, yield_constr, start_col + MULTIPLY_BY_034_T1_CALC_OFFSET, bit_selector);
+    let t1_cols = multiply_by_01_result_cols(start_col + MULTIPLY_BY_034_T1_CALC_OFFSET);
+    add_mul_by_nonresidue_fp6_constraints(local_values, yield_constr, start_col + MULTIPLY_BY_034_T2_CALC_OFFSET, t1_cols, bit_selector);
+    add_addition_with_reduction_constranints_fp6(local_values, yield_constr, start_col + MULTIPLY_BY_034_X_CALC_OFFSET, bit_selector);
+    add_addition_with_reduction_constranints_fp6(local_values, yield_constr, start_col + MULTIPLY_BY_034_T3_CALC_OFFSET, bit_selector);
+    add_addition_with_reduction_constranints(local_values, yield_constr, start_col + MULTIPLY_BY_034_T4_CALC_OFFSET, bit_selector);
+    add_multiply_by_01_constraints(local_values, next_values, yield_constr, start_col + MULTIPLY_BY_034_T5_CALC_OFFSET, bit_selector);
+    add_subtraction_with_reduction_constranints_fp6(local_values, yield_constr, start_col + MULTIPLY_BY_034_T6_CALC_OFFSET, bit_selector);
+    add_subtraction_with_reduction_constranints_fp6(local_values, yield_constr, start_col + MULTIPLY_BY_034_Y_CALC_OFFSET, bit_selector);
+}
+
+pub fn add_multiply_by_034_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+){
+    let bit_selector_val = bit_selector.unwrap_or(builder.constant_extension(F::Extension::ONE));
+
+    for i in MULTIPLY_BY_034_INPUT_OFFSET..MULTIPLY_BY_034_T0_CALC_OFFSET {
+        let sub_tmp = builder.sub_extension(local_values[start_col + i], next_values[start_col + i]);
+        let c = builder.mul_extension(sub_tmp, local_values[start_col + MULTIPLY_BY_034_SELECTOR_OFFSET]);
+        let c = builder.mul_extension(bit_selector_val, c);
+        yield_constr.constraint_transition(builder, c);
+    }
+
+    for (selector, x, y) in multiply_by_034_links(start_col) {
+        for i in 0..12 {
+            let sub_tmp = builder.sub_extension(local_values[x + i], local_values[y + i]);
+            let c = builder.mul_extension(sub_tmp, local_values[selector]);
+            let c = builder.mul_extension(bit_selector_val, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+
+    for i in 0..3 {
+        add_fp2_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + MULTIPLY_BY_034_T0_CALC_OFFSET + i*TOTAL_COLUMNS_FP2_MULTIPLICATION, bit_selector);
+    }
+    add_multiply_by_01_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + MULTIPLY_BY_034_T1_CALC_OFFSET, bit_selector);
+    let t1_cols = multiply_by_01_result_cols(start_col + MULTIPLY_BY_034_T1_CALC_OFFSET);
+    add_mul_by_nonresidue_fp6_constraints_ext_circuit(builder, yield_constr, local_values, start_col + MULTIPLY_BY_034_T2_CALC_OFFSET, t1_cols, bit_selector);
+    add_addition_with_reduction_constraints_fp6_ext_circuit(builder, yield_constr, local_values, start_col + MULTIPLY_BY_034_X_CALC_OFFSET, bit_selector);
+    add_addition_with_reduction_constraints_fp6_ext_circuit(builder, yield_constr, local_values, start_col + MULTIPLY_BY_034_T3_CALC_OFFSET, bit_selector);
+    add_addition_with_reduction_constraints_ext_circuit(builder, yield_constr, local_values, start_col + MULTIPLY_BY_034_T4_CALC_OFFSET, bit_selector);
+    add_multiply_by_01_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + MULTIPLY_BY_034_T5_CALC_OFFSET, bit_selector);
+    add_subtraction_with_reduction_constraints_fp6_ext_circuit(builder, yield_constr, local_values, start_col + MULTIPLY_BY_034_T6_CALC_OFFSET, bit_selector);
+    add_subtraction_with_reduction_constraints_fp6_ext_circuit(builder, yield_constr, local_values, start_col + MULTIPLY_BY_034_Y_CALC_OFFSET, bit_selector);
+}
+
+/// Maximum degree of the constraints of [add_fp12_multiplication_constraints] without `bit_selector`: the selector of each fp multiplication times the product of two limbs. A `bit_selector` adds one.
+pub const FP12_MUL_CONSTRAINT_DEGREE: usize = 3;
+
 /// Constraints fp12 multiplication.
 ///
 /// Constraints inputs across this and next row, wherever selector is set to on. Constraints all the Ti's (defined in the [function](super::native::mul_fp_12)) accordinng to their respective operations.
+/// The constraints have degree at most [FP12_MUL_CONSTRAINT_DEGREE], plus one with `bit_selector`.
 pub fn add_fp12_multiplication_constraints<F: RichField + Extendable<D>,
     const D: usize,
     FE,
@@ -1083,7 +1365,7 @@ pub fn add_fp12_multiplication_constraints<F: RichField + Extendable<D>,
     add_fp6_multiplication_constraints(local_values, next_values, yield_constr, start_col + FP12_MUL_T1_CALC_OFFSET, bit_selector);
     
     // T2
-    for i in 0..6 {
+    let t1_cols: [usize; 6] = std::array::from_fn(|i| {
         let fp2_offset = if i < 2 {
             FP6_MUL_X_CALC_OFFSET
         } else if i < 4 {
@@ -1091,17 +1373,9 @@ pub fn add_fp12_multiplication_constraints<F: RichField + Extendable<D>,
         } else {
             FP6_MUL_Z_CALC_OFFSET
         };
-        let fp_offset = i%2;
-        for j in 0..12 {
-            yield_constr.constraint(
-            bit_selector_val *
-                local_values[start_col + FP12_MUL_T2_CALC_OFFSET + FP6_NON_RESIDUE_MUL_CHECK_OFFSET] *
-                (local_values[start_col + FP12_MUL_T2_CALC_OFFSET + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + i*12 + j] - 
-                local_values[start_col + FP12_MUL_T1_CALC_OFFSET + fp2_offset + FP2_ADDITION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*fp_offset + FP_SINGLE_REDUCED_OFFSET + j])
-            );
-        }
-    }
-    add_non_residue_multiplication_fp6_constraints(local_values, yield_constr, start_col + FP12_MUL_T2_CALC_OFFSET, bit_selector);
+        start_col + FP12_MUL_T1_CALC_OFFSET + fp2_offset + FP2_ADDITION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*(i%2) + FP_SINGLE_REDUCED_OFFSET
+    });
+    add_mul_by_nonresidue_fp6_constraints(local_values, yield_constr, start_col + FP12_MUL_T2_CALC_OFFSET, t1_cols, bit_selector);
 
     // X
     for i in 0..6 {
@@ -1353,7 +1627,7 @@ pub fn add_fp12_multiplication_constraints_ext_circuit<
     add_fp6_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FP12_MUL_T1_CALC_OFFSET, bit_selector);
 
     // T2
-    for i in 0..6 {
+    let t1_cols: [usize; 6] = std::array::from_fn(|i| {
         let fp2_offset = if i < 2 {
             FP6_MUL_X_CALC_OFFSET
         } else if i < 4 {
@@ -1361,15 +1635,9 @@ pub fn add_fp12_multiplication_constraints_ext_circuit<
         } else {
             FP6_MUL_Z_CALC_OFFSET
         };
-        let fp_offset = i%2;
-        for j in 0..12 {
-            let sub_tmp = builder.sub_extension(local_values[start_col + FP12_MUL_T2_CALC_OFFSET + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + i*12 + j] , local_values[start_col + FP12_MUL_T1_CALC_OFFSET + fp2_offset + FP2_ADDITION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*fp_offset + FP_SINGLE_REDUCED_OFFSET + j]);
-            let c = builder.mul_extension(sub_tmp, local_values[start_col + FP12_MUL_T2_CALC_OFFSET + FP6_NON_RESIDUE_MUL_CHECK_OFFSET]);
-            let c = builder.mul_extension(bit_selector_val,c);
-            yield_constr.constraint(builder,c);
-        }
-    }
-    add_non_residue_multiplication_fp6_constraints_ext_circuit(builder, yield_constr, local_values,start_col + FP12_MUL_T2_CALC_OFFSET, bit_selector);
+        start_col + FP12_MUL_T1_CALC_OFFSET + fp2_offset + FP2_ADDITION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*(i%2) + FP_SINGLE_REDUCED_OFFSET
+    });
+    add_mul_by_nonresidue_fp6_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP12_MUL_T2_CALC_OFFSET, t1_cols, bit_selector);
 
     // X
     for i in 0..6 {
@@ -1550,9 +1818,13 @@ pub fn add_fp12_multiplication_constraints_ext_circuit<
     add_subtraction_with_reduction_constraints_fp6_ext_circuit(builder, yield_constr, local_values, start_col + FP12_MUL_Y_CALC_OFFSET, bit_selector);
 }
 
+/// Maximum degree of the constraints of [add_cyclotomic_sq_constraints] without `bit_selector`, reached by its fp2 multiplications. A `bit_selector` adds one.
+pub const CYCLOTOMIC_SQ_CONSTRAINT_DEGREE: usize = 3;
+
 /// Constraints for [cyclotomicSquare](super::native::Fp12::cyclotomicSquare) function.
 ///
 /// Constraints inputs across this and next row, wherever selector is set to on. Constraints all the Ti's (defined in the native function) accordinng to their respective operations.
+/// The constraints have degree at most [CYCLOTOMIC_SQ_CONSTRAINT_DEGREE], plus one with `bit_selector`.
 pub fn add_cyclotomic_sq_constraints<F: RichField + Extendable<D>,
     const D: usize,
     FE,
@@ -1624,21 +1896,7 @@ pub fn add_cyclotomic_sq_constraints<F: RichField + Extendable<D>,
     }
     add_fp4_sq_constraints(local_values, next_values, yield_constr, start_col + CYCLOTOMIC_SQ_T2_CALC_OFFSET, bit_selector);
 
-    for i in 0..12 {
-        yield_constr.constraint(
-            bit_selector.unwrap_or(P::ONES) *
-            local_values[start_col + CYCLOTOMIC_SQ_T3_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET] *
-            (local_values[start_col + CYCLOTOMIC_SQ_T3_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i] -
-            local_values[start_col + CYCLOTOMIC_SQ_T2_CALC_OFFSET + FP4_SQ_Y_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + FP_SINGLE_REDUCED_OFFSET + i])
-        );
-        yield_constr.constraint(
-            bit_selector.unwrap_or(P::ONES) *
-            local_values[start_col + CYCLOTOMIC_SQ_T3_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET] *
-            (local_values[start_col + CYCLOTOMIC_SQ_T3_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i + 12] -
-            local_values[start_col + CYCLOTOMIC_SQ_T2_CALC_OFFSET + FP4_SQ_Y_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL) + FP_SINGLE_REDUCED_OFFSET + i])
-        );
-    }
-    add_non_residue_multiplication_constraints(local_values, yield_constr, start_col + CYCLOTOMIC_SQ_T3_CALC_OFFSET, bit_selector);
+    add_mul_by_nonresidue_constraints(local_values, yield_constr, start_col + CYCLOTOMIC_SQ_T3_CALC_OFFSET, [start_col + CYCLOTOMIC_SQ_T2_CALC_OFFSET + FP4_SQ_Y_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + FP_SINGLE_REDUCED_OFFSET, start_col + CYCLOTOMIC_SQ_T2_CALC_OFFSET + FP4_SQ_Y_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL) + FP_SINGLE_REDUCED_OFFSET], bit_selector);
 
     for i in 0..12 {
         for j in 0..2 {
@@ -2107,18 +2365,7 @@ pub fn add_cyclotomic_sq_constraints_ext_circuit<F: RichField + Extendable<D>,
     }
     add_fp4_sq_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + CYCLOTOMIC_SQ_T2_CALC_OFFSET, bit_selector);
 
-    for i in 0..12 {
-        let tmp = builder.mul_extension(bit_selector_val, local_values[start_col + CYCLOTOMIC_SQ_T3_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET]);
-
-        let c = builder.sub_extension (local_values[start_col + CYCLOTOMIC_SQ_T3_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i], local_values[start_col + CYCLOTOMIC_SQ_T2_CALC_OFFSET + FP4_SQ_Y_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + FP_SINGLE_REDUCED_OFFSET + i]);
-        let c = builder.mul_extension(tmp, c);
-        yield_constr.constraint(builder, c);
-
-        let c = builder.sub_extension(local_values[start_col + CYCLOTOMIC_SQ_T3_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i + 12], local_values[start_col + CYCLOTOMIC_SQ_T2_CALC_OFFSET + FP4_SQ_Y_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL) + FP_SINGLE_REDUCED_OFFSET + i]);
-        let c = builder.mul_extension(tmp, c);
-        yield_constr.constraint(builder, c);
-    }
-    add_non_residue_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, start_col + CYCLOTOMIC_SQ_T3_CALC_OFFSET, bit_selector);
+    add_mul_by_nonresidue_constraints_ext_circuit(builder, yield_constr, local_values, start_col + CYCLOTOMIC_SQ_T3_CALC_OFFSET, [start_col + CYCLOTOMIC_SQ_T2_CALC_OFFSET + FP4_SQ_Y_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + FP_SINGLE_REDUCED_OFFSET, start_col + CYCLOTOMIC_SQ_T2_CALC_OFFSET + FP4_SQ_Y_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL) + FP_SINGLE_REDUCED_OFFSET], bit_selector);
 
     for i in 0..12 {
         for j in 0..2 {
@@ -2474,9 +2721,14 @@ pub fn add_cyclotomic_sq_constraints_ext_circuit<F: RichField + Extendable<D>,
     add_addition_with_reduction_constraints_ext_circuit(builder, yield_constr, local_values, start_col + CYCLOTOMIC_SQ_C5_CALC_OFFSET, bit_selector);
 }
 
+/// Maximum degree of the constraints of [add_cyclotomic_exp_constraints] without `bit_selector`: one more than the cyclotomicSquare and fp12 multiplication constraints, which are gated by the bits of the exponent. A `bit_selector` adds one.
+pub const CYCLOTOMIC_EXP_CONSTRAINT_DEGREE: usize = 4;
+
 /// Constraints for [cyclotomicExponent](super::native::Fp12::cyclotocmicExponent) function.
 ///
-/// Constraints inputs across this and next row, wherever selector is set to on. When `CYCLOTOMIC_EXP_START_ROW` is set, constraints z to be 1. Creates two `bit_selector` values from `BIT1_SELECTOR`. Constraints cyclotomicSquare function with `bit0` and constraints fp12 multiplication with `bit1`. What it does is switch on the constraints of cyclotomicSquare when `BIT1_SELECTOR` is off and switch on the constraints of fp12 multiplication when `BIT1_SELECTOR` is on. When `FIRST_ROW_SELECTOR` is on in the next row, constraints z value of the next row with result of cyclotmicSquare function and `bit0` of current row and constraints z value of the next row with result of fp12 multiplication and `bit1` of current row.
+/// Constraints inputs across this and next row, wherever selector is set to on. When `CYCLOTOMIC_EXP_START_ROW` is set, constraints z to be 1. Creates two `bit_selector` values from `BIT1_SELECTOR`. Constraints cyclotomicSquare function with `bit0` and constraints fp12 multiplication with `bit1`. What it does is switch on the constraints of cyclotomicSquare when `BIT1_SELECTOR` is off and switch on the constraints of fp12 multiplication when `BIT1_SELECTOR` is on. When `FIRST_ROW_SELECTOR` is on in the next row, constraints z value of the next row with result of cyclotmicSquare function and `bit0` of current row and constraints z value of the next row with result of fp12 multiplication and `bit1` of current row. The same is done when `RES_ROW_SELECTOR` is on in the next row, so that the result row is constrained whether the last bit of the exponent is 0 or 1.
+///
+/// The cyclotomicSquare and fp12 multiplication constraints are gated by `bit0` and `bit1`, so the constraints have degree at most [CYCLOTOMIC_EXP_CONSTRAINT_DEGREE], plus one with `bit_selector`.
 pub fn add_cyclotomic_exp_constraints<F: RichField + Extendable<D>,
     const D: usize,
     FE,
@@ -2605,7 +2857,7 @@ pub fn add_cyclotomic_exp_constraints<F: RichField + Extendable<D>,
             };
             for k in 0..2 {
                 yield_constr.constraint_transition(
-                    op_selector.unwrap_or(P::ONES) *
+                    bit0 *
                     local_values[start_col + CYCLOTOMIC_EXP_SELECTOR_OFFSET] *
                     next_values[start_col + RES_ROW_SELECTOR_OFFSET] *
                     (next_values[start_col + Z_OFFSET + j*24 + k*12 + i] -
@@ -2614,6 +2866,25 @@ pub fn add_cyclotomic_exp_constraints<F: RichField + Extendable<D>,
             }
         }
     }
+
+    for i in 0..12 {
+        for j in 0..6 {
+            yield_constr.constraint_transition(
+                bit1 *
+                local_values[start_col + CYCLOTOMIC_EXP_SELECTOR_OFFSET] *
+                next_values[start_col + RES_ROW_SELECTOR_OFFSET] *
+                (next_values[start_col + Z_OFFSET + j*12 + i] -
+                local_values[start_col + Z_MUL_INPUT_OFFSET + FP12_MUL_X_CALC_OFFSET + FP6_ADDITION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*j + FP_SINGLE_REDUCED_OFFSET + i])
+            );
+            yield_constr.constraint_transition(
+                bit1 *
+                local_values[start_col + CYCLOTOMIC_EXP_SELECTOR_OFFSET] *
+                next_values[start_col + RES_ROW_SELECTOR_OFFSET] *
+                (next_values[start_col + Z_OFFSET + j*12 + i + 24*3] -
+                local_values[start_col + Z_MUL_INPUT_OFFSET + FP12_MUL_Y_CALC_OFFSET + FP6_ADDITION_TOTAL + FP6_SUBTRACTION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*j + FP_SINGLE_REDUCED_OFFSET + i])
+            );
+        }
+    }
 }
 
 pub fn add_cyclotomic_exp_constraints_ext_circuit<F: RichField + Extendable<D>,
@@ -2716,7 +2987,7 @@ pub fn add_cyclotomic_exp_constraints_ext_circuit<F: RichField + Extendable<D>,
     add_fp12_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + Z_MUL_INPUT_OFFSET, Some(bit1));
 
     for i in 0..12 {
-        let mul = builder.mul_extension(op_selector_val, local_values[start_col + CYCLOTOMIC_EXP_SELECTOR_OFFSET]);
+        let mul = builder.mul_extension(bit0, local_values[start_col + CYCLOTOMIC_EXP_SELECTOR_OFFSET]);
         let mul = builder.mul_extension(mul, next_values[start_col + RES_ROW_SELECTOR_OFFSET]);
         for j in 0..6 {
             let c_offset = if j == 0 {
@@ -2739,11 +3010,29 @@ pub fn add_cyclotomic_exp_constraints_ext_circuit<F: RichField + Extendable<D>,
             }
         }
     }
+
+    for i in 0..12 {
+        let mul = builder.mul_extension(bit1, local_values[start_col + CYCLOTOMIC_EXP_SELECTOR_OFFSET]);
+        let mul = builder.mul_extension(mul, next_values[start_col + RES_ROW_SELECTOR_OFFSET]);
+        for j in 0..6 {
+            let c = builder.sub_extension(next_values[start_col + Z_OFFSET + j*12 + i], local_values[start_col + Z_MUL_INPUT_OFFSET + FP12_MUL_X_CALC_OFFSET + FP6_ADDITION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*j + FP_SINGLE_REDUCED_OFFSET + i]);
+            let c = builder.mul_extension(mul, c);
+            yield_constr.constraint_transition(builder, c);
+
+            let c = builder.sub_extension(next_values[start_col + Z_OFFSET + j*12 + i + 24*3], local_values[start_col + Z_MUL_INPUT_OFFSET + FP12_MUL_Y_CALC_OFFSET + FP6_ADDITION_TOTAL + FP6_SUBTRACTION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*j + FP_SINGLE_REDUCED_OFFSET + i]);
+            let c = builder.mul_extension(mul, c);
+            yield_constr.constraint_transition(builder, c);
+        }
+    }
 }
 
+/// Maximum degree of the constraints of [add_fp12_forbenius_map_constraints] without `bit_selector`: the forbenius constant is selected by a product of three bits of the power, and constrained as an input of a multiplication gated by its selector. A `bit_selector` adds one.
+pub const FP12_FORBENIUS_MAP_CONSTRAINT_DEGREE: usize = 4;
+
 /// Constraints for [forbenius_map](super::native::Fp12::forbenius_map) function.
 ///
 ///  Constraints both input and power across this and next row, wherever selector is set to on. Constraint the divisor and remainder with power for `power == divisor*12 + remainder`. Constraints the bit decomposition as `remainder == bit0 + bit1*2 + bit2*4 + bit3*8`. Selects the forbenius constant using mupliplexer logic. Then constraints fp6 forbenius map, multiplication, reduction and range check operations.
+/// The constraints have degree at most [FP12_FORBENIUS_MAP_CONSTRAINT_DEGREE], plus one with `bit_selector`.
 pub fn add_fp12_forbenius_map_constraints<F: RichField + Extendable<D>,
     const D: usize,
     FE,
@@ -2793,7 +3082,7 @@ pub fn add_fp12_forbenius_map_constraints<F: RichField + Extendable<D>,
         bit3 * FE::from_canonical_usize(8) -
         local_values[start_col + FP12_FORBENIUS_MAP_REM_OFFSET])
     );
-    let forbenius_coefficients = Fp12::forbenius_coefficients().iter().map(|fp2| fp2.get_u32_slice().concat().try_into().unwrap()).collect::<Vec<[u32; 24]>>();
+    let forbenius_coefficients = FP12_FORBENIUS_COEFFICIENTS.iter().map(|fp2| fp2.get_u32_slice().concat().try_into().unwrap()).collect::<Vec<[u32; 24]>>();
     let y = (0..24).map(|i|
         (P::ONES - bit0) * (P::ONES - bit1) * (P::ONES - bit2) * FE::from_canonical_u32(forbenius_coefficients[0][i]) +
         (bit0) * (P::ONES - bit1) * (P::ONES - bit2) * FE::from_canonical_u32(forbenius_coefficients[1][i]) +
@@ -2955,7 +3244,7 @@ pub fn add_fp12_forbenius_map_constraints_ext_circuit<F: RichField + Extendable<
     let c = builder.mul_extension(tmp, c);
     yield_constr.constraint(builder, c);
 
-    let forbenius_coefficients = Fp12::forbenius_coefficients().iter().map(|fp2| fp2.get_u32_slice().concat().try_into().unwrap()).collect::<Vec<[u32; 24]>>();
+    let forbenius_coefficients = FP12_FORBENIUS_COEFFICIENTS.iter().map(|fp2| fp2.get_u32_slice().concat().try_into().unwrap()).collect::<Vec<[u32; 24]>>();
     let y = (0..24).map(|i| {
         let fc0 = builder.constant_extension(F::Extension::from_canonical_u32(forbenius_coefficients[0][i]));
         let fc1 = builder.constant_extension(F::Extension::from_canonical_u32(forbenius_coefficients[1][i]));
@@ -3064,7 +3353,122 @@ pub fn add_fp12_forbenius_map_constraints_ext_circuit<F: RichField + Extendable<
     add_fp2_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FP12_FORBENIUS_MAP_C2_CALC_OFFSET, bit_selector);
 }
 
+/// Columns of the 12 fp elements of the result of the [forbenius_map](super::native::Fp12::forbenius_map) gadget starting at `col`, in the order of the fp12 limbs. Set in all the rows of the gadget.
+pub fn fp12_forbenius_map_result_cols(col: usize) -> [usize; 12] {
+    [
+        col + FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_X_CALC_OFFSET + FP2_FORBENIUS_MAP_INPUT_OFFSET,
+        col + FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_X_CALC_OFFSET + FP2_FORBENIUS_MAP_T0_CALC_OFFSET + FP_MULTIPLICATION_TOTAL_COLUMNS + REDUCED_OFFSET,
+        col + FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_Y_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET,
+        col + FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_Y_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET,
+        col + FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_Z_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET,
+        col + FP12_FORBENIUS_MAP_R0_CALC_OFFSET + FP6_FORBENIUS_MAP_Z_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET,
+        col + FP12_FORBENIUS_MAP_C0_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET,
+        col + FP12_FORBENIUS_MAP_C0_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET,
+        col + FP12_FORBENIUS_MAP_C1_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET,
+        col + FP12_FORBENIUS_MAP_C1_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET,
+        col + FP12_FORBENIUS_MAP_C2_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET,
+        col + FP12_FORBENIUS_MAP_C2_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET,
+    ]
+}
+
+/// Constraints for [forbenius_mul](super::native::Fp12::forbenius_mul) function, filled by [fill_trace_fp12_forbenius_mul].
+///
+/// Constraints the X input of the multiplication in the next row to the result of the forbenius map in this row, wherever `selector` is on, which has to be the last row of the forbenius map. The forbenius map and the multiplication share their columns, so they are constrained by [add_fp12_forbenius_map_constraints] and [add_fp12_multiplication_constraints] gated by selectors of their own rows.
+/// The constraints have degree one, plus the degree of `selector`.
+pub fn add_fp12_forbenius_mul_constraints<F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    selector: P,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    for (j, col) in fp12_forbenius_map_result_cols(start_col).into_iter().enumerate() {
+        for i in 0..12 {
+            yield_constr.constraint_transition(
+                selector *
+                (local_values[col + i] - next_values[start_col + FP12_MUL_X_INPUT_OFFSET + j*12 + i])
+            );
+        }
+    }
+}
+
+pub fn add_fp12_forbenius_mul_constraints_ext_circuit<F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    selector: ExtensionTarget<D>,
+) {
+    for (j, col) in fp12_forbenius_map_result_cols(start_col).into_iter().enumerate() {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[col + i], next_values[start_col + FP12_MUL_X_INPUT_OFFSET + j*12 + i]);
+            let c = builder.mul_extension(selector, c);
+            yield_constr.constraint_transition(builder, c);
+        }
+    }
+}
+
+/// Constraints the 24*3*2 limbs of the fp12 in the columns starting at `a_col` to be equal to the ones starting at `b_col`, in the rows where `selector` is 1 (in every row without `selector`). Used to link the output of one gadget to the input of another.
+/// The constraints have degree one, plus the degree of `selector`.
+pub fn add_fp12_equal_constraints<F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    a_col: usize,
+    b_col: usize,
+    selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    for i in 0..24*3*2 {
+        yield_constr.constraint(
+            selector.unwrap_or(P::ONES) *
+            (local_values[a_col + i] - local_values[b_col + i])
+        );
+    }
+}
+
+pub fn add_fp12_equal_constraints_ext_circuit<F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    a_col: usize,
+    b_col: usize,
+    selector: Option<ExtensionTarget<D>>,
+) {
+    for i in 0..24*3*2 {
+        let c = builder.sub_extension(local_values[a_col + i], local_values[b_col + i]);
+        let c = match selector {
+            Some(selector) => builder.mul_extension(selector, c),
+            None => c,
+        };
+        yield_constr.constraint(builder, c);
+    }
+}
+
+/// Maximum degree of the constraints of [add_fp12_conjugate_constraints] without `bit_selector`, which adds one.
+pub const FP12_CONJUGATE_CONSTRAINT_DEGREE: usize = 3;
+
 /// Constraints for [conjugate](super::native::Fp12::conjugate) function.
+/// The constraints have degree at most [FP12_CONJUGATE_CONSTRAINT_DEGREE], plus one with `bit_selector`.
 pub fn add_fp12_conjugate_constraints<F: RichField + Extendable<D>,
     const D: usize,
     FE,
@@ -3152,3 +3556,495 @@ pub fn add_fp12_conjugate_constraints_ext_circuit<F: RichField + Extendable<D>,
     }
     add_negate_fp6_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP12_CONJUGATE_ADDITIION_OFFSET, bit_selector);
 }
+
+#[cfg(test)]
+mod tests {
+    use plonky2::{
+        field::{
+            extension::{Extendable, FieldExtension},
+            packed::PackedField,
+            types::PrimeField64,
+        },
+        hash::hash_types::RichField,
+        iop::ext_target::ExtensionTarget,
+        plonk::{circuit_builder::CircuitBuilder, config::{GenericConfig, PoseidonGoldilocksConfig}},
+        util::timing::TimingTree,
+    };
+    use starky::{
+        config::StarkConfig,
+        constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+        evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+        prover::prove,
+        stark::Stark,
+        util::trace_rows_to_poly_values,
+        verifier::verify_stark_proof,
+    };
+
+    use crate::{native::{Fp, Fp12, Fp2}, recursive_proof};
+    #[cfg(feature = "debug-constraints")]
+    use crate::{debug_constraints::debug_check_trace, utils::num_constraints};
+
+    use super::*;
+
+    fn test_fp12() -> Fp12 {
+        Fp12::from_str(["2181142506194812233868097821779361009807326315828153071050324314717744521676711650071190927260282422014627435089208",
+        "3266212670671256779826008414922395966600400122723332695666308996296105595418386213353825620535446475769829785237189",
+        "3280330655787598118299804758957910379684134784964426565939861302675766948066521588562898980898245868682162153155911",
+        "333668007718210311816046938245689395232794221928183840372182128979685996722059498232053963662509478803385469716056",
+        "1650925102445293819378017648160637800280351377141029658990698964033732511884552459036333864590686008335846481856882",
+        "3925133212240632255860280854235945320282874550806663137653784505923891479863770370026712801361887427462376126696706",
+        "2444089052091192833501409081021321360112867893942837175254954622703299880931587618210267154453853513743076365662283",
+        "3142914221549818039420055870398197863502329018278548609868118001898418737390067291084903575823960349378631910285921",
+        "1952057563719092278028425573632201081234877258097927010867141683896274170520489868686437644804596724295624637397077",
+        "254131389529427774765960554324483250584297364987873642087841623909520980093766889928789173976296059957431962608694",
+        "1385128161651935856764061834929068245137081648283968377947672499160305921464670953157912428887005620142387465559867",
+        "101302147352745188522496764263445345397483945567997375025250825330209385517139484882425580831299520200841767383756"])
+    }
+
+    /// Stark containing only the cyclotomic exponent gadget, used to check its recursive constraints in isolation.
+    #[derive(Clone, Copy)]
+    struct CyclotomicExpStark<F: RichField + Extendable<D>, const D: usize> {
+        num_rows: usize,
+        exp: u64,
+        _f: std::marker::PhantomData<F>,
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> CyclotomicExpStark<F, D> {
+        fn generate_trace(&self, x: &Fp12) -> Vec<[F; CYCLOTOMIC_EXP_TOTAL_COLUMNS]> {
+            let mut trace = vec![[F::ZERO; CYCLOTOMIC_EXP_TOTAL_COLUMNS]; self.num_rows];
+            fill_trace_cyclotomic_exp_by(&mut trace, x, self.exp, 0, cyclotomic_exp_rows(self.exp) - 1, 0);
+            trace
+        }
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for CyclotomicExpStark<F, D> {
+        type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, CYCLOTOMIC_EXP_TOTAL_COLUMNS, 0>
+        where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>;
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: &Self::EvaluationFrame<FE, P, D2>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            add_cyclotomic_exp_constraints(vars.get_local_values(), vars.get_next_values(), yield_constr, 0, None);
+        }
+
+        type EvaluationFrameTarget =
+            StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, CYCLOTOMIC_EXP_TOTAL_COLUMNS, 0>;
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut CircuitBuilder<F, D>,
+            vars: &Self::EvaluationFrameTarget,
+            yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+        ) {
+            add_cyclotomic_exp_constraints_ext_circuit(builder, yield_constr, vars.get_local_values(), vars.get_next_values(), 0, None);
+        }
+
+        fn constraint_degree(&self) -> usize {
+            CYCLOTOMIC_EXP_CONSTRAINT_DEGREE
+        }
+    }
+
+    #[test]
+    fn test_cyclotomic_exp_recursive_verify() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = CyclotomicExpStark<F, D>;
+
+        let x = test_fp12();
+
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let stark = S { num_rows: 1024, exp: BLS_12_381_PARAMETER, _f: std::marker::PhantomData };
+        let trace = stark.generate_trace(&x);
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &[],
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof.clone(), &config).unwrap();
+        recursive_proof::<F, C, S, C, D>(stark, proof, &config, false);
+    }
+
+    #[test]
+    fn test_cyclotomic_exp_short_exponent() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = CyclotomicExpStark<F, D>;
+
+        assert_eq!(cyclotomic_exp_rows(BLS_12_381_PARAMETER), 70*12 + 1);
+        let x = test_fp12();
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        // 0b1011 ends with a 1 bit, so the result row follows an fp12 multiplication; 0b110 ends with a squaring.
+        for exp in [0b1011u64, 0b110] {
+            let rows = cyclotomic_exp_rows(exp);
+            assert_eq!(rows, ((64 - exp.leading_zeros()) + exp.count_ones()) as usize * 12 + 1);
+            let stark = S { num_rows: rows.next_power_of_two(), exp, _f: std::marker::PhantomData };
+            let trace = stark.generate_trace(&x);
+            let expected = x.cyclotomic_exp_by(exp).get_u32_slice().concat();
+            for i in 0..24*3*2 {
+                assert_eq!(trace[rows - 1][Z_OFFSET + i], F::from_canonical_u32(expected[i]));
+            }
+            let trace_poly_values = trace_rows_to_poly_values(trace);
+            let proof = prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace_poly_values,
+                &[],
+                &mut TimingTree::default(),
+            ).unwrap();
+            verify_stark_proof(stark, proof, &config).unwrap();
+        }
+    }
+
+    /// Stark containing only the multiplyBy034 gadget.
+    #[derive(Clone, Copy)]
+    struct MultiplyBy034Stark<F: RichField + Extendable<D>, const D: usize> {
+        num_rows: usize,
+        _f: std::marker::PhantomData<F>,
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> MultiplyBy034Stark<F, D> {
+        fn generate_trace(&self, x: &Fp12, o0: &Fp2, o3: &Fp2, o4: &Fp2) -> Vec<[F; MULTIPLY_BY_034_TOTAL]> {
+            let mut trace = vec![[F::ZERO; MULTIPLY_BY_034_TOTAL]; self.num_rows];
+            fill_trace_multiply_by_034(&mut trace, x, o0, o3, o4, 0, 11, 0);
+            trace
+        }
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for MultiplyBy034Stark<F, D> {
+        type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, MULTIPLY_BY_034_TOTAL, 0>
+        where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>;
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: &Self::EvaluationFrame<FE, P, D2>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            add_multiply_by_034_constraints(vars.get_local_values(), vars.get_next_values(), yield_constr, 0, None);
+        }
+
+        type EvaluationFrameTarget =
+            StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, MULTIPLY_BY_034_TOTAL, 0>;
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut CircuitBuilder<F, D>,
+            vars: &Self::EvaluationFrameTarget,
+            yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+        ) {
+            add_multiply_by_034_constraints_ext_circuit(builder, yield_constr, vars.get_local_values(), vars.get_next_values(), 0, None);
+        }
+
+        fn constraint_degree(&self) -> usize {
+            3
+        }
+    }
+
+    const FP12_EQUAL_A_OFFSET: usize = 0;
+    const FP12_EQUAL_B_OFFSET: usize = FP12_EQUAL_A_OFFSET + 24*3*2;
+    const FP12_EQUAL_SELECTOR_OFFSET: usize = FP12_EQUAL_B_OFFSET + 24*3*2;
+    const FP12_EQUAL_TOTAL: usize = FP12_EQUAL_SELECTOR_OFFSET + 1;
+
+    /// Stark containing two fp12s and a selector, linked with [add_fp12_equal_constraints] in the selected rows.
+    #[derive(Clone, Copy)]
+    struct Fp12EqualStark<F: RichField + Extendable<D>, const D: usize> {
+        num_rows: usize,
+        _f: std::marker::PhantomData<F>,
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Fp12EqualStark<F, D> {
+        /// Sets `a` and `b` in every row, and the selector in the even rows.
+        fn generate_trace(&self, a: &Fp12, b: &Fp12) -> Vec<[F; FP12_EQUAL_TOTAL]> {
+            let mut trace = vec![[F::ZERO; FP12_EQUAL_TOTAL]; self.num_rows];
+            for row in 0..self.num_rows {
+                for i in 0..12 {
+                    assign_u32_in_series(&mut trace, row, FP12_EQUAL_A_OFFSET + i*12, &a.0[i].0);
+                    assign_u32_in_series(&mut trace, row, FP12_EQUAL_B_OFFSET + i*12, &b.0[i].0);
+                }
+                trace[row][FP12_EQUAL_SELECTOR_OFFSET] = F::from_bool(row % 2 == 0);
+            }
+            trace
+        }
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Fp12EqualStark<F, D> {
+        type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, FP12_EQUAL_TOTAL, 0>
+        where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>;
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: &Self::EvaluationFrame<FE, P, D2>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            let local_values = vars.get_local_values();
+            add_fp12_equal_constraints(local_values, yield_constr, FP12_EQUAL_A_OFFSET, FP12_EQUAL_B_OFFSET, Some(local_values[FP12_EQUAL_SELECTOR_OFFSET]));
+        }
+
+        type EvaluationFrameTarget =
+            StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, FP12_EQUAL_TOTAL, 0>;
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut CircuitBuilder<F, D>,
+            vars: &Self::EvaluationFrameTarget,
+            yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+        ) {
+            let local_values = vars.get_local_values();
+            add_fp12_equal_constraints_ext_circuit(builder, yield_constr, local_values, FP12_EQUAL_A_OFFSET, FP12_EQUAL_B_OFFSET, Some(local_values[FP12_EQUAL_SELECTOR_OFFSET]));
+        }
+
+        fn constraint_degree(&self) -> usize {
+            2
+        }
+    }
+
+    const FP12_FORBENIUS_MUL_FORBENIUS_SELECTOR: usize = 0;
+    const FP12_FORBENIUS_MUL_MUL_SELECTOR: usize = FP12_FORBENIUS_MUL_FORBENIUS_SELECTOR + 1;
+    const FP12_FORBENIUS_MUL_OFFSET: usize = FP12_FORBENIUS_MUL_MUL_SELECTOR + 1;
+    const FP12_FORBENIUS_MUL_TOTAL: usize = FP12_FORBENIUS_MUL_OFFSET + FP12_FORBENIUS_MUL_TOTAL_COLUMNS;
+
+    /// Stark containing only the forbenius_mul gadget, with a selector for the rows of its forbenius map and one for the rows of its multiplication.
+    #[derive(Clone, Copy)]
+    struct Fp12ForbeniusMulStark<F: RichField + Extendable<D>, const D: usize> {
+        num_rows: usize,
+        _f: std::marker::PhantomData<F>,
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Fp12ForbeniusMulStark<F, D> {
+        fn generate_trace(&self, x: &Fp12, pow: usize, y: &Fp12) -> (Vec<[F; FP12_FORBENIUS_MUL_TOTAL]>, Fp12) {
+            let mut trace = vec![[F::ZERO; FP12_FORBENIUS_MUL_TOTAL]; self.num_rows];
+            for row in 0..12 {
+                trace[row][FP12_FORBENIUS_MUL_FORBENIUS_SELECTOR] = F::ONE;
+                trace[row + 12][FP12_FORBENIUS_MUL_MUL_SELECTOR] = F::ONE;
+            }
+            let res = fill_trace_fp12_forbenius_mul(&mut trace, x, pow, y, 0, 23, FP12_FORBENIUS_MUL_OFFSET);
+            (trace, res)
+        }
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Fp12ForbeniusMulStark<F, D> {
+        type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, FP12_FORBENIUS_MUL_TOTAL, 0>
+        where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>;
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: &Self::EvaluationFrame<FE, P, D2>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            let local_values = vars.get_local_values();
+            let next_values = vars.get_next_values();
+            add_fp12_forbenius_map_constraints(local_values, next_values, yield_constr, FP12_FORBENIUS_MUL_OFFSET, Some(local_values[FP12_FORBENIUS_MUL_FORBENIUS_SELECTOR]));
+            add_fp12_multiplication_constraints(local_values, next_values, yield_constr, FP12_FORBENIUS_MUL_OFFSET, Some(local_values[FP12_FORBENIUS_MUL_MUL_SELECTOR]));
+            // Only the last row of the forbenius map is followed by a row of the multiplication.
+            let selector = local_values[FP12_FORBENIUS_MUL_FORBENIUS_SELECTOR] * next_values[FP12_FORBENIUS_MUL_MUL_SELECTOR];
+            add_fp12_forbenius_mul_constraints(local_values, next_values, yield_constr, FP12_FORBENIUS_MUL_OFFSET, selector);
+        }
+
+        type EvaluationFrameTarget =
+            StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, FP12_FORBENIUS_MUL_TOTAL, 0>;
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut CircuitBuilder<F, D>,
+            vars: &Self::EvaluationFrameTarget,
+            yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+        ) {
+            let local_values = vars.get_local_values();
+            let next_values = vars.get_next_values();
+            add_fp12_forbenius_map_constraints_ext_circuit(builder, yield_constr, local_values, next_values, FP12_FORBENIUS_MUL_OFFSET, Some(local_values[FP12_FORBENIUS_MUL_FORBENIUS_SELECTOR]));
+            add_fp12_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, next_values, FP12_FORBENIUS_MUL_OFFSET, Some(local_values[FP12_FORBENIUS_MUL_MUL_SELECTOR]));
+            let selector = builder.mul_extension(local_values[FP12_FORBENIUS_MUL_FORBENIUS_SELECTOR], next_values[FP12_FORBENIUS_MUL_MUL_SELECTOR]);
+            add_fp12_forbenius_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, FP12_FORBENIUS_MUL_OFFSET, selector);
+        }
+
+        fn constraint_degree(&self) -> usize {
+            FP12_FORBENIUS_MAP_CONSTRAINT_DEGREE + 1
+        }
+    }
+
+    #[test]
+    fn test_fp12_mul_column_count() {
+        let fp2_add_reduce = FP2_ADDITION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*2;
+        let fp6_add_reduce = FP6_ADDITION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*6;
+
+        // Schoolbook fp12, c0 = a0*b0 + v*a1*b1 and c1 = a0*b1 + a1*b0, with 4 fp6 multiplications.
+        let schoolbook_fp12 = FP12_MUL_T0_CALC_OFFSET + FP6_MUL_TOTAL_COLUMNS*4 + FP6_NON_RESIDUE_MUL_TOTAL + fp6_add_reduce*2;
+        assert!(FP12_MUL_TOTAL_COLUMNS < schoolbook_fp12);
+
+        // Schoolbook fp6, with 9 fp2 multiplications, 6 fp2 additions and the 2 multiplications by the non residue of c0 and c1.
+        let schoolbook_fp6 = FP6_MUL_T0_CALC_OFFSET + TOTAL_COLUMNS_FP2_MULTIPLICATION*9 + fp2_add_reduce*6 + FP2_NON_RESIDUE_MUL_TOTAL*2;
+        assert!(FP6_MUL_TOTAL_COLUMNS < schoolbook_fp6);
+
+        // Karatsuba fp2, z0 = x0*y0 + p*p - x1*y1 and z1 = (x0 + x1)*(y0 + y1) - x0*y0 - x1*y1, with 3 fp multiplications.
+        let karatsuba_fp2 = X_0_Y_0_MULTIPLICATION_OFFSET + FP_MULTIPLICATION_TOTAL_COLUMNS*3 + FP_ADDITION_TOTAL*2 + ADDITION_TOTAL + SUBTRACTION_TOTAL*3 + (REDUCTION_TOTAL + RANGE_CHECK_TOTAL)*2;
+        assert_eq!(FP_MULTIPLICATION_TOTAL_COLUMNS, 135);
+        assert_eq!(FP_ADDITION_TOTAL*2 + SUBTRACTION_TOTAL*2 - ADDITION_TOTAL, 195);
+        assert_eq!(karatsuba_fp2 - TOTAL_COLUMNS_FP2_MULTIPLICATION, 195 - 135);
+    }
+
+    #[test]
+    fn test_multiply_by_034_matches_full_multiplication() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = MultiplyBy034Stark<F, D>;
+
+        assert!(MULTIPLY_BY_034_TOTAL < FP12_MUL_TOTAL_COLUMNS);
+
+        let x = test_fp12();
+        let (o0, o3, o4) = (Fp2([x.0[3], x.0[8]]), Fp2([x.0[5], x.0[0]]), Fp2([x.0[10], x.0[7]]));
+        let mut sparse = [Fp::zero(); 12];
+        for (i, o) in [(0, o0), (3, o3), (4, o4)] {
+            sparse[i*2..i*2+2].copy_from_slice(&o.0);
+        }
+        let expected = x * Fp12(sparse);
+        assert_eq!(x.multiply_by_034(o0, o3, o4), expected);
+
+        let stark = S { num_rows: 16, _f: std::marker::PhantomData };
+        let trace = stark.generate_trace(&x, &o0, &o3, &o4);
+        let output_cols = [
+            fp6_reduced_cols(MULTIPLY_BY_034_X_CALC_OFFSET, false),
+            fp6_reduced_cols(MULTIPLY_BY_034_Y_CALC_OFFSET, true),
+        ].concat();
+        let output = Fp12(core::array::from_fn(|i| {
+            Fp(core::array::from_fn(|j| trace[0][output_cols[i] + j].to_canonical_u64() as u32))
+        }));
+        assert_eq!(output, expected);
+
+        let config = StarkConfig::standard_fast_config();
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &[],
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof.clone(), &config).unwrap();
+        recursive_proof::<F, C, S, C, D>(stark, proof, &config, false);
+    }
+
+    #[test]
+    fn test_fp12_equal_constraints() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = Fp12EqualStark<F, D>;
+
+        let x = test_fp12();
+        let config = StarkConfig::standard_fast_config();
+        let stark = S { num_rows: 16, _f: std::marker::PhantomData };
+
+        // x is equal to x * 1.
+        let trace = stark.generate_trace(&x, &(x * Fp12::one()));
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_rows_to_poly_values(trace),
+            &[],
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof.clone(), &config).unwrap();
+        recursive_proof::<F, C, S, C, D>(stark, proof, &config, false);
+
+        // Unequal values are only allowed in the rows which are not selected.
+        let mut trace = stark.generate_trace(&x, &x);
+        let y = x.conjugate();
+        for i in 0..12 {
+            assign_u32_in_series(&mut trace, 1, FP12_EQUAL_B_OFFSET + i*12, &y.0[i].0);
+        }
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_rows_to_poly_values(trace.clone()),
+            &[],
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+
+        // In a selected row, the first unequal limb fails.
+        #[cfg(feature = "debug-constraints")]
+        {
+            assign_u32_in_series(&mut trace, 2, FP12_EQUAL_B_OFFSET + 12*11, &y.0[11].0);
+            let limb = (0..12).position(|i| y.0[11].0[i] != x.0[11].0[i]).unwrap();
+            assert_eq!(debug_check_trace(&stark, &trace, &[]), Some((2, 12*11 + limb)));
+        }
+    }
+
+    #[test]
+    fn test_fp12_forbenius_mul_matches_separate_operations() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = Fp12ForbeniusMulStark<F, D>;
+
+        let x = test_fp12();
+        let y = x.conjugate();
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let stark = S { num_rows: 32, _f: std::marker::PhantomData };
+        for pow in [2, 3] {
+            let expected = x.forbenius_map(pow) * y;
+            assert_eq!(x.forbenius_mul(pow, &y), expected);
+
+            let (trace, res) = stark.generate_trace(&x, pow, &y);
+            assert_eq!(res, expected);
+            let output_cols = [
+                fp6_reduced_cols(FP12_FORBENIUS_MUL_OFFSET + FP12_MUL_X_CALC_OFFSET, false),
+                fp6_reduced_cols(FP12_FORBENIUS_MUL_OFFSET + FP12_MUL_Y_CALC_OFFSET, true),
+            ].concat();
+            let output = Fp12(core::array::from_fn(|i| {
+                Fp(core::array::from_fn(|j| trace[12][output_cols[i] + j].to_canonical_u64() as u32))
+            }));
+            assert_eq!(output, expected);
+
+            let proof = prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace_rows_to_poly_values(trace),
+                &[],
+                &mut TimingTree::default(),
+            ).unwrap();
+            verify_stark_proof(stark, proof.clone(), &config).unwrap();
+            recursive_proof::<F, C, S, C, D>(stark, proof, &config, false);
+        }
+
+        // A multiplication of another value than the result of the forbenius map is only caught by the link between them, which is emitted last, in the last row of the forbenius map. The first limb where the result differs from the input fails.
+        #[cfg(feature = "debug-constraints")]
+        {
+            let (mut trace, _) = stark.generate_trace(&x, 2, &y);
+            fill_trace_fp12_multiplication(&mut trace, &x, &y, 12, 23, FP12_FORBENIUS_MUL_OFFSET);
+            let x_pow = x.forbenius_map(2).get_u32_slice().concat();
+            let limb = x.get_u32_slice().concat().iter().zip(x_pow).position(|(a, b)| *a != b).unwrap();
+            assert_eq!(debug_check_trace(&stark, &trace, &[]), Some((11, num_constraints(&stark) - 24*3*2 + limb)));
+        }
+    }
+}
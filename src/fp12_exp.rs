@@ -0,0 +1,595 @@
+use num_bigint::BigUint;
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use crate::native::{get_bits_as_array, Fp12};
+
+use crate::fp::*;
+use crate::fp6::*;
+use crate::fp12::*;
+use crate::utils::*;
+
+/// Number of bits of the exponent, one square-and-multiply step is done for each bit.
+pub const FP12_EXP_BITS: usize = 256;
+/// Number of rows needed by one square-and-multiply step, 12 rows for the squaring and 12 rows for the multiplication.
+pub const FP12_EXP_STEP_ROWS: usize = 24;
+/// Number of rows needed by the exponentiation, including the row where the result is set.
+pub const FP12_EXP_ROWS: usize = FP12_EXP_BITS * FP12_EXP_STEP_ROWS + 1;
+
+// Fp12 exponentiation layout offsets
+/*
+    These trace offsets are for exponentiation of an fp12 element by an arbitrary exponent of at most 256 bits (super::native::Fp12::pow). It needs FP12_EXP_ROWS rows.
+    The exponent is processed from the most significant bit. Each bit is processed in a step of 24 rows. In the first 12 rows the accumulator is squared, and in the next 12 rows it is multiplied by the input if the bit is 1, or by one otherwise. Both are done with the fp12 multiplication operation.
+    FP12_EXP_SELECTOR_OFFSET -> Selector to ensure that the input is same across all rows. Set 1 in all rows of the steps, 0 in the result row.
+    FP12_EXP_START_ROW -> Selector set 1 in the first row of the operation, where the accumulator is constrained to be one.
+    FP12_EXP_RES_ROW_SELECTOR -> Selector set 1 in the row after the last step, where the result is set in FP12_EXP_Z_OFFSET.
+    FP12_EXP_MUL_STEP_SELECTOR -> Selector set 1 in the 12 rows of each step where the multiplication is done, 0 in the rows where the squaring is done.
+    FP12_EXP_STEP_END_SELECTOR -> Selector set 1 in the last row of each step, except the last one.
+    FP12_EXP_BIT_OFFSET -> the bit processed in the current step.
+    FP12_EXP_EXPONENT_BITS_OFFSET -> offset where the bit decomposition of the exponent is set, least significant bit first.
+    FP12_EXP_STEP_SELECTORS -> FP12_EXP_BITS selectors, the i-th one set 1 in the rows of the i-th step. Step i processes bit (FP12_EXP_BITS - 1 - i) of the exponent.
+    FP12_EXP_INPUT_OFFSET -> offset where the input is set.
+    FP12_EXP_Z_OFFSET -> offset where the accumulator before the current operation is set.
+    FP12_EXP_MUL_OFFSET -> offset where the fp12 multiplication is done.
+*/
+pub const FP12_EXP_SELECTOR_OFFSET: usize = 0;
+pub const FP12_EXP_START_ROW: usize = FP12_EXP_SELECTOR_OFFSET + 1;
+pub const FP12_EXP_RES_ROW_SELECTOR: usize = FP12_EXP_START_ROW + 1;
+pub const FP12_EXP_MUL_STEP_SELECTOR: usize = FP12_EXP_RES_ROW_SELECTOR + 1;
+pub const FP12_EXP_STEP_END_SELECTOR: usize = FP12_EXP_MUL_STEP_SELECTOR + 1;
+pub const FP12_EXP_BIT_OFFSET: usize = FP12_EXP_STEP_END_SELECTOR + 1;
+pub const FP12_EXP_EXPONENT_BITS_OFFSET: usize = FP12_EXP_BIT_OFFSET + 1;
+pub const FP12_EXP_STEP_SELECTORS: usize = FP12_EXP_EXPONENT_BITS_OFFSET + FP12_EXP_BITS;
+pub const FP12_EXP_INPUT_OFFSET: usize = FP12_EXP_STEP_SELECTORS + FP12_EXP_BITS;
+pub const FP12_EXP_Z_OFFSET: usize = FP12_EXP_INPUT_OFFSET + 24*3*2;
+pub const FP12_EXP_MUL_OFFSET: usize = FP12_EXP_Z_OFFSET + 24*3*2;
+pub const FP12_EXP_TOTAL: usize = FP12_EXP_MUL_OFFSET + FP12_MUL_TOTAL_COLUMNS;
+
+pub const TOTAL_COLUMNS: usize = FP12_EXP_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the input, the exponent as 8 u32 limbs (least significant limb first) and the result.
+*/
+pub const PIS_INPUT_OFFSET: usize = 0;
+pub const PIS_EXPONENT_OFFSET: usize = PIS_INPUT_OFFSET + 24*3*2;
+pub const PIS_OUTPUT_OFFSET: usize = PIS_EXPONENT_OFFSET + 8;
+pub const PUBLIC_INPUTS: usize = PIS_OUTPUT_OFFSET + 24*3*2;
+
+/// Splits an exponent of at most 256 bits into 8 u32 limbs, least significant limb first.
+pub fn get_exponent_limbs(exp: &BigUint) -> [u32; 8] {
+    assert!(exp.bits() <= FP12_EXP_BITS as u64);
+    let mut limbs = [0u32; 8];
+    for (i, d) in exp.to_u32_digits().iter().enumerate() {
+        limbs[i] = *d;
+    }
+    limbs
+}
+
+/// Column of the i-th limb of the fp12 multiplication result, in the same order as the input.
+fn fp12_mul_res_col(i: usize) -> usize {
+    let (j, k) = ((i % 72) / 12, i % 12);
+    if i < 72 {
+        FP12_MUL_X_CALC_OFFSET + FP6_ADDITION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*j + FP_SINGLE_REDUCED_OFFSET + k
+    } else {
+        FP12_MUL_Y_CALC_OFFSET + FP6_ADDITION_TOTAL + FP6_SUBTRACTION_TOTAL + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*j + FP_SINGLE_REDUCED_OFFSET + k
+    }
+}
+
+/// Fills the stark trace of fp12 exponentiation. Inputs are an fp12 element and an exponent of at most 256 bits. Needs FP12_EXP_ROWS rows starting from `start_row`. For each bit of the exponent, fills the squaring of the accumulator, followed by the multiplication of the accumulator with the input if the bit is 1, or with one otherwise. Sets the result in the last row. Returns `x^exp`.
+pub fn fill_trace_fp12_exp<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, x: &Fp12, exp: &BigUint, start_row: usize, start_col: usize) -> Fp12 {
+    let res_row = start_row + FP12_EXP_ROWS - 1;
+    let bits = get_exponent_limbs(exp).iter().map(|limb| get_bits_as_array(*limb)).collect::<Vec<[u32; 32]>>().concat();
+    for row in start_row..res_row + 1 {
+        trace[row][start_col + FP12_EXP_SELECTOR_OFFSET] = F::ONE;
+        assign_u32_in_series(trace, row, start_col + FP12_EXP_INPUT_OFFSET, &x.get_u32_slice().concat());
+        assign_u32_in_series(trace, row, start_col + FP12_EXP_EXPONENT_BITS_OFFSET, &bits);
+    }
+    trace[res_row][start_col + FP12_EXP_SELECTOR_OFFSET] = F::ZERO;
+    trace[start_row][start_col + FP12_EXP_START_ROW] = F::ONE;
+    trace[res_row][start_col + FP12_EXP_RES_ROW_SELECTOR] = F::ONE;
+
+    let mut z = Fp12::one();
+    for step in 0..FP12_EXP_BITS {
+        let s_row = start_row + step * FP12_EXP_STEP_ROWS;
+        let bit = bits[FP12_EXP_BITS - 1 - step];
+        for row in s_row..s_row + FP12_EXP_STEP_ROWS {
+            trace[row][start_col + FP12_EXP_STEP_SELECTORS + step] = F::ONE;
+            trace[row][start_col + FP12_EXP_BIT_OFFSET] = F::from_canonical_u32(bit);
+        }
+        for row in s_row..s_row + 12 {
+            assign_u32_in_series(trace, row, start_col + FP12_EXP_Z_OFFSET, &z.get_u32_slice().concat());
+        }
+        fill_trace_fp12_multiplication(trace, &z, &z, s_row, s_row + 11, start_col + FP12_EXP_MUL_OFFSET);
+        z = z * z;
+        let y = if bit == 1 {
+            *x
+        } else {
+            Fp12::one()
+        };
+        for row in s_row + 12..s_row + FP12_EXP_STEP_ROWS {
+            trace[row][start_col + FP12_EXP_MUL_STEP_SELECTOR] = F::ONE;
+            assign_u32_in_series(trace, row, start_col + FP12_EXP_Z_OFFSET, &z.get_u32_slice().concat());
+        }
+        fill_trace_fp12_multiplication(trace, &z, &y, s_row + 12, s_row + 23, start_col + FP12_EXP_MUL_OFFSET);
+        z = z * y;
+        if step < FP12_EXP_BITS - 1 {
+            trace[s_row + 23][start_col + FP12_EXP_STEP_END_SELECTOR] = F::ONE;
+        }
+    }
+    assign_u32_in_series(trace, res_row, start_col + FP12_EXP_Z_OFFSET, &z.get_u32_slice().concat());
+    assert_eq!(z, x.pow(exp));
+    z
+}
+
+/// Constraints fp12 exponentiation.
+///
+/// Constraints the input and the exponent bits to be same across the rows wherever the selector is on, and the exponent bits to be boolean.
+/// When `FP12_EXP_START_ROW` is set, constraints the accumulator to be one, the first step selector to be on and the operation to be a squaring. At the end of each fp12 multiplication, the result is constrained to be the accumulator of the next row, and the operation alternates between squaring and multiplication. The step selectors shift by one at the end of each step. The selector is constrained to turn off only after the multiplication of the last step, in which case the next row is the result row.
+/// Constraints the bit of the current step with the step selectors and the exponent bits. Constraints the inputs of the fp12 multiplication to be the accumulator and itself when squaring, or the accumulator and the input (or one, if the bit is 0) otherwise.
+pub fn add_fp12_exp_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let selector = local_values[start_col + FP12_EXP_SELECTOR_OFFSET];
+    let start_row = local_values[start_col + FP12_EXP_START_ROW];
+    let mul_step = local_values[start_col + FP12_EXP_MUL_STEP_SELECTOR];
+    let step_end = local_values[start_col + FP12_EXP_STEP_END_SELECTOR];
+    let bit = local_values[start_col + FP12_EXP_BIT_OFFSET];
+    let mul_col = start_col + FP12_EXP_MUL_OFFSET;
+    // 1 on the last row of every fp12 multiplication
+    let op_end = selector - local_values[mul_col + FP12_MUL_SELECTOR_OFFSET];
+    let next_selector = next_values[start_col + FP12_EXP_SELECTOR_OFFSET];
+    let last_step = local_values[start_col + FP12_EXP_STEP_SELECTORS + FP12_EXP_BITS - 1];
+
+    for i in 0..24*3*2 {
+        yield_constr.constraint_transition(
+            selector *
+            (local_values[start_col + FP12_EXP_INPUT_OFFSET + i] - next_values[start_col + FP12_EXP_INPUT_OFFSET + i])
+        );
+    }
+    for i in 0..FP12_EXP_BITS {
+        let b = local_values[start_col + FP12_EXP_EXPONENT_BITS_OFFSET + i];
+        yield_constr.constraint_transition(
+            selector *
+            (b - next_values[start_col + FP12_EXP_EXPONENT_BITS_OFFSET + i])
+        );
+        yield_constr.constraint(selector * b * (P::ONES - b));
+    }
+
+    for i in 0..24*3*2 {
+        let val = if i == 0 {
+            P::ONES
+        } else {
+            P::ZEROS
+        };
+        yield_constr.constraint(start_row * (local_values[start_col + FP12_EXP_Z_OFFSET + i] - val));
+    }
+    for i in 0..FP12_EXP_BITS {
+        let val = if i == 0 {
+            P::ONES
+        } else {
+            P::ZEROS
+        };
+        yield_constr.constraint(start_row * (local_values[start_col + FP12_EXP_STEP_SELECTORS + i] - val));
+    }
+    yield_constr.constraint(start_row * mul_step);
+
+    for i in 0..24*3*2 {
+        yield_constr.constraint_transition(
+            op_end *
+            (next_values[start_col + FP12_EXP_Z_OFFSET + i] - local_values[mul_col + fp12_mul_res_col(i)])
+        );
+    }
+    yield_constr.constraint_transition(
+        selector *
+        (next_values[start_col + FP12_EXP_MUL_STEP_SELECTOR] - mul_step * (P::ONES - op_end) - (P::ONES - mul_step) * op_end)
+    );
+    yield_constr.constraint(step_end - op_end * mul_step * (P::ONES - last_step));
+    for i in 0..FP12_EXP_BITS {
+        let prev = if i == 0 {
+            P::ZEROS
+        } else {
+            local_values[start_col + FP12_EXP_STEP_SELECTORS + i - 1]
+        };
+        let curr = local_values[start_col + FP12_EXP_STEP_SELECTORS + i];
+        yield_constr.constraint_transition(
+            selector *
+            (next_values[start_col + FP12_EXP_STEP_SELECTORS + i] - curr * (P::ONES - step_end) - prev * step_end)
+        );
+    }
+    yield_constr.constraint_transition(selector * (P::ONES - next_selector) * (P::ONES - last_step));
+    yield_constr.constraint_transition(selector * (P::ONES - next_selector) * (P::ONES - mul_step));
+    yield_constr.constraint_transition(
+        next_values[start_col + FP12_EXP_RES_ROW_SELECTOR] - selector * (P::ONES - next_selector)
+    );
+
+    let step_bit = (0..FP12_EXP_BITS).fold(P::ZEROS, |acc, i|
+        acc + local_values[start_col + FP12_EXP_STEP_SELECTORS + i] * local_values[start_col + FP12_EXP_EXPONENT_BITS_OFFSET + FP12_EXP_BITS - 1 - i]
+    );
+    yield_constr.constraint(selector * (bit - step_bit));
+
+    for i in 0..24*3*2 {
+        let z = local_values[start_col + FP12_EXP_Z_OFFSET + i];
+        yield_constr.constraint(
+            selector *
+            (local_values[mul_col + FP12_MUL_X_INPUT_OFFSET + i] - z)
+        );
+        yield_constr.constraint(
+            selector *
+            (P::ONES - mul_step) *
+            (local_values[mul_col + FP12_MUL_Y_INPUT_OFFSET + i] - z)
+        );
+        let one = if i == 0 {
+            P::ONES
+        } else {
+            P::ZEROS
+        };
+        yield_constr.constraint(
+            mul_step *
+            (local_values[mul_col + FP12_MUL_Y_INPUT_OFFSET + i] - bit * local_values[start_col + FP12_EXP_INPUT_OFFSET + i] - (P::ONES - bit) * one)
+        );
+    }
+
+    add_fp12_multiplication_constraints(local_values, next_values, yield_constr, mul_col, None);
+}
+
+pub fn add_fp12_exp_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+) {
+    let one = builder.constant_extension(F::Extension::ONE);
+    let zero = builder.constant_extension(F::Extension::ZERO);
+    let selector = local_values[start_col + FP12_EXP_SELECTOR_OFFSET];
+    let start_row = local_values[start_col + FP12_EXP_START_ROW];
+    let mul_step = local_values[start_col + FP12_EXP_MUL_STEP_SELECTOR];
+    let not_mul_step = builder.sub_extension(one, mul_step);
+    let step_end = local_values[start_col + FP12_EXP_STEP_END_SELECTOR];
+    let not_step_end = builder.sub_extension(one, step_end);
+    let bit = local_values[start_col + FP12_EXP_BIT_OFFSET];
+    let not_bit = builder.sub_extension(one, bit);
+    let mul_col = start_col + FP12_EXP_MUL_OFFSET;
+    let op_end = builder.sub_extension(selector, local_values[mul_col + FP12_MUL_SELECTOR_OFFSET]);
+    let not_op_end = builder.sub_extension(one, op_end);
+    let next_selector = next_values[start_col + FP12_EXP_SELECTOR_OFFSET];
+    let not_next_selector = builder.sub_extension(one, next_selector);
+    let last_step = local_values[start_col + FP12_EXP_STEP_SELECTORS + FP12_EXP_BITS - 1];
+    let not_last_step = builder.sub_extension(one, last_step);
+
+    for i in 0..24*3*2 {
+        let c = builder.sub_extension(local_values[start_col + FP12_EXP_INPUT_OFFSET + i], next_values[start_col + FP12_EXP_INPUT_OFFSET + i]);
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint_transition(builder, c);
+    }
+    for i in 0..FP12_EXP_BITS {
+        let b = local_values[start_col + FP12_EXP_EXPONENT_BITS_OFFSET + i];
+        let c = builder.sub_extension(b, next_values[start_col + FP12_EXP_EXPONENT_BITS_OFFSET + i]);
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint_transition(builder, c);
+
+        let not_b = builder.sub_extension(one, b);
+        let c = builder.mul_extension(b, not_b);
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint(builder, c);
+    }
+
+    for i in 0..24*3*2 {
+        let c = if i == 0 {
+            builder.sub_extension(local_values[start_col + FP12_EXP_Z_OFFSET + i], one)
+        } else {
+            local_values[start_col + FP12_EXP_Z_OFFSET + i]
+        };
+        let c = builder.mul_extension(start_row, c);
+        yield_constr.constraint(builder, c);
+    }
+    for i in 0..FP12_EXP_BITS {
+        let c = if i == 0 {
+            builder.sub_extension(local_values[start_col + FP12_EXP_STEP_SELECTORS + i], one)
+        } else {
+            local_values[start_col + FP12_EXP_STEP_SELECTORS + i]
+        };
+        let c = builder.mul_extension(start_row, c);
+        yield_constr.constraint(builder, c);
+    }
+    let c = builder.mul_extension(start_row, mul_step);
+    yield_constr.constraint(builder, c);
+
+    for i in 0..24*3*2 {
+        let c = builder.sub_extension(next_values[start_col + FP12_EXP_Z_OFFSET + i], local_values[mul_col + fp12_mul_res_col(i)]);
+        let c = builder.mul_extension(op_end, c);
+        yield_constr.constraint_transition(builder, c);
+    }
+    let keep = builder.mul_extension(mul_step, not_op_end);
+    let flip = builder.mul_extension(not_mul_step, op_end);
+    let c = builder.sub_extension(next_values[start_col + FP12_EXP_MUL_STEP_SELECTOR], keep);
+    let c = builder.sub_extension(c, flip);
+    let c = builder.mul_extension(selector, c);
+    yield_constr.constraint_transition(builder, c);
+    let c = builder.mul_extension(op_end, mul_step);
+    let c = builder.mul_extension(c, not_last_step);
+    let c = builder.sub_extension(step_end, c);
+    yield_constr.constraint(builder, c);
+    for i in 0..FP12_EXP_BITS {
+        let curr = local_values[start_col + FP12_EXP_STEP_SELECTORS + i];
+        let c = builder.mul_extension(curr, not_step_end);
+        let c = builder.sub_extension(next_values[start_col + FP12_EXP_STEP_SELECTORS + i], c);
+        let c = if i == 0 {
+            c
+        } else {
+            let prev = builder.mul_extension(local_values[start_col + FP12_EXP_STEP_SELECTORS + i - 1], step_end);
+            builder.sub_extension(c, prev)
+        };
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint_transition(builder, c);
+    }
+    let sel_off = builder.mul_extension(selector, not_next_selector);
+    let c = builder.mul_extension(sel_off, not_last_step);
+    yield_constr.constraint_transition(builder, c);
+    let c = builder.mul_extension(sel_off, not_mul_step);
+    yield_constr.constraint_transition(builder, c);
+    let c = builder.sub_extension(next_values[start_col + FP12_EXP_RES_ROW_SELECTOR], sel_off);
+    yield_constr.constraint_transition(builder, c);
+
+    let step_bit = (0..FP12_EXP_BITS).fold(zero, |acc, i| {
+        let c = builder.mul_extension(local_values[start_col + FP12_EXP_STEP_SELECTORS + i], local_values[start_col + FP12_EXP_EXPONENT_BITS_OFFSET + FP12_EXP_BITS - 1 - i]);
+        builder.add_extension(acc, c)
+    });
+    let c = builder.sub_extension(bit, step_bit);
+    let c = builder.mul_extension(selector, c);
+    yield_constr.constraint(builder, c);
+
+    for i in 0..24*3*2 {
+        let z = local_values[start_col + FP12_EXP_Z_OFFSET + i];
+        let c = builder.sub_extension(local_values[mul_col + FP12_MUL_X_INPUT_OFFSET + i], z);
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint(builder, c);
+
+        let c = builder.sub_extension(local_values[mul_col + FP12_MUL_Y_INPUT_OFFSET + i], z);
+        let c = builder.mul_extension(not_mul_step, c);
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint(builder, c);
+
+        let operand = builder.mul_extension(bit, local_values[start_col + FP12_EXP_INPUT_OFFSET + i]);
+        let operand = if i == 0 {
+            builder.add_extension(operand, not_bit)
+        } else {
+            operand
+        };
+        let c = builder.sub_extension(local_values[mul_col + FP12_MUL_Y_INPUT_OFFSET + i], operand);
+        let c = builder.mul_extension(mul_step, c);
+        yield_constr.constraint(builder, c);
+    }
+
+    add_fp12_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, next_values, mul_col, None);
+}
+
+#[derive(Clone, Copy)]
+pub struct Fp12ExpStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> Fp12ExpStark<F, D> {
+    /// Rows used by one exponentiation, `FP12_EXP_ROWS`.
+    pub fn required_rows() -> usize {
+        FP12_EXP_ROWS
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(x: &Fp12, exp: &BigUint, res: &Fp12) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for (i, e) in x.get_u32_slice().concat().iter().enumerate() {
+            public_inputs[PIS_INPUT_OFFSET + i] = F::from_canonical_u32(*e);
+        }
+        for (i, limb) in get_exponent_limbs(exp).iter().enumerate() {
+            public_inputs[PIS_EXPONENT_OFFSET + i] = F::from_canonical_u32(*limb);
+        }
+        for (i, e) in res.get_u32_slice().concat().iter().enumerate() {
+            public_inputs[PIS_OUTPUT_OFFSET + i] = F::from_canonical_u32(*e);
+        }
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, x: &Fp12, exp: &BigUint) -> Vec<[F; TOTAL_COLUMNS]> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_fp12_exp(&mut trace, x, exp, 0, 0);
+        trace
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Fp12ExpStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[FP12_EXP_START_ROW] - P::ONES);
+        yield_constr.constraint_first_row(local_values[FP12_EXP_SELECTOR_OFFSET] - P::ONES);
+        // The result row must be reached before the end of the trace
+        yield_constr.constraint_last_row(local_values[FP12_EXP_SELECTOR_OFFSET]);
+        for i in 0..24*3*2 {
+            yield_constr.constraint_first_row(local_values[FP12_EXP_INPUT_OFFSET + i] - public_inputs[PIS_INPUT_OFFSET + i]);
+            yield_constr.constraint(
+                local_values[FP12_EXP_RES_ROW_SELECTOR] *
+                (local_values[FP12_EXP_Z_OFFSET + i] - public_inputs[PIS_OUTPUT_OFFSET + i])
+            );
+        }
+        for i in 0..8 {
+            let limb = (0..32).fold(P::ZEROS, |acc, j|
+                acc + local_values[FP12_EXP_EXPONENT_BITS_OFFSET + i*32 + j] * FE::from_canonical_u64(1 << j)
+            );
+            yield_constr.constraint_first_row(limb - public_inputs[PIS_EXPONENT_OFFSET + i]);
+        }
+
+        add_fp12_exp_constraints(local_values, next_values, yield_constr, 0);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[FP12_EXP_START_ROW], one);
+        yield_constr.constraint_first_row(builder, c);
+        let c = builder.sub_extension(local_values[FP12_EXP_SELECTOR_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        yield_constr.constraint_last_row(builder, local_values[FP12_EXP_SELECTOR_OFFSET]);
+        for i in 0..24*3*2 {
+            let c = builder.sub_extension(local_values[FP12_EXP_INPUT_OFFSET + i], public_inputs[PIS_INPUT_OFFSET + i]);
+            yield_constr.constraint_first_row(builder, c);
+            let c = builder.sub_extension(local_values[FP12_EXP_Z_OFFSET + i], public_inputs[PIS_OUTPUT_OFFSET + i]);
+            let c = builder.mul_extension(local_values[FP12_EXP_RES_ROW_SELECTOR], c);
+            yield_constr.constraint(builder, c);
+        }
+        for i in 0..8 {
+            let zero = builder.constant_extension(F::Extension::ZERO);
+            let limb = (0..32).fold(zero, |acc, j| {
+                let tmp_const = builder.constant_extension(F::Extension::from_canonical_u64(1 << j));
+                let c = builder.mul_extension(local_values[FP12_EXP_EXPONENT_BITS_OFFSET + i*32 + j], tmp_const);
+                builder.add_extension(acc, c)
+            });
+            let c = builder.sub_extension(limb, public_inputs[PIS_EXPONENT_OFFSET + i]);
+            yield_constr.constraint_first_row(builder, c);
+        }
+
+        add_fp12_exp_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{group_order, Fp12};
+
+    use super::{Fp12ExpStark, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Fp12ExpStark<F, D>;
+
+    fn test_input() -> Fp12 {
+        Fp12::from_str(["2181142506194812233868097821779361009807326315828153071050324314717744521676711650071190927260282422014627435089208",
+            "3266212670671256779826008414922395966600400122723332695666308996296105595418386213353825620535446475769829785237189",
+            "3280330655787598118299804758957910379684134784964426565939861302675766948066521588562898980898245868682162153155911",
+            "333668007718210311816046938245689395232794221928183840372182128979685996722059498232053963662509478803385469716056",
+            "1650925102445293819378017648160637800280351377141029658990698964033732511884552459036333864590686008335846481856882",
+            "3925133212240632255860280854235945320282874550806663137653784505923891479863770370026712801361887427462376126696706",
+            "2444089052091192833501409081021321360112867893942837175254954622703299880931587618210267154453853513743076365662283",
+            "3142914221549818039420055870398197863502329018278548609868118001898418737390067291084903575823960349378631910285921",
+            "1952057563719092278028425573632201081234877258097927010867141683896274170520489868686437644804596724295624637397077",
+            "254131389529427774765960554324483250584297364987873642087841623909520980093766889928789173976296059957431962608694",
+            "1385128161651935856764061834929068245137081648283968377947672499160305921464670953157912428887005620142387465559867",
+            "101302147352745188522496764263445345397483945567997375025250825330209385517139484882425580831299520200841767383756"])
+    }
+
+    fn prove_and_verify_exp(x: &Fp12, exp: &BigUint) -> Fp12 {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(8192);
+        let res = x.pow(exp);
+        let public_inputs = S::public_inputs(x, exp, &res);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(x, exp);
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        res
+    }
+
+    #[test]
+    fn test_fp12_exp_group_order() {
+        let x = test_input();
+        let res = prove_and_verify_exp(&x, &group_order());
+        assert_eq!(res, x.pow(&(group_order() - 1u32)) * x);
+    }
+
+    #[test]
+    fn test_fp12_exp_random_exponent() {
+        let x = test_input();
+        let exp = BigUint::parse_bytes(b"e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855", 16).unwrap();
+        let res = prove_and_verify_exp(&x, &exp);
+        let mut expected = Fp12::one();
+        for limb in exp.to_u32_digits().iter().rev() {
+            for _ in 0..32 {
+                expected = expected * expected;
+            }
+            expected = expected * x.pow(&BigUint::from(*limb));
+        }
+        assert_eq!(res, expected);
+    }
+}
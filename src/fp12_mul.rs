@@ -34,6 +34,11 @@ pub struct FP12MulStark<F: RichField + Extendable<D>, const D: usize> {
 }
 
 impl<F: RichField + Extendable<D>, const D: usize> FP12MulStark<F, D> {
+    /// Rows used by one fp12 multiplication, 12. The stark needs a power of two number of rows of at least this many.
+    pub fn required_rows() -> usize {
+        12
+    }
+
     pub fn new(num_rows: usize) -> Self {
         Self {
             num_rows,
@@ -140,6 +145,40 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FP12MulStark<
     }
 
     fn constraint_degree(&self) -> usize {
-        3
+        FP12_MUL_CONSTRAINT_DEGREE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+
+    use crate::{fp12_mul_main, native::Fp12, recursive_proof};
+
+    use super::FP12MulStark;
+
+    #[test]
+    fn test_fp12_mul_recursive_verify() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = FP12MulStark<F, D>;
+
+        let x = Fp12::from_str(["2181142506194812233868097821779361009807326315828153071050324314717744521676711650071190927260282422014627435089208",
+            "3266212670671256779826008414922395966600400122723332695666308996296105595418386213353825620535446475769829785237189",
+            "3280330655787598118299804758957910379684134784964426565939861302675766948066521588562898980898245868682162153155911",
+            "333668007718210311816046938245689395232794221928183840372182128979685996722059498232053963662509478803385469716056",
+            "1650925102445293819378017648160637800280351377141029658990698964033732511884552459036333864590686008335846481856882",
+            "3925133212240632255860280854235945320282874550806663137653784505923891479863770370026712801361887427462376126696706",
+            "2444089052091192833501409081021321360112867893942837175254954622703299880931587618210267154453853513743076365662283",
+            "3142914221549818039420055870398197863502329018278548609868118001898418737390067291084903575823960349378631910285921",
+            "1952057563719092278028425573632201081234877258097927010867141683896274170520489868686437644804596724295624637397077",
+            "254131389529427774765960554324483250584297364987873642087841623909520980093766889928789173976296059957431962608694",
+            "1385128161651935856764061834929068245137081648283968377947672499160305921464670953157912428887005620142387465559867",
+            "101302147352745188522496764263445345397483945567997375025250825330209385517139484882425580831299520200841767383756"]);
+        let y = x * x;
+        let (stark, proof, config) = fp12_mul_main::<F, C, D>(x, y);
+        let (recursive, _, _) = recursive_proof::<F, C, S, C, D>(stark, proof.clone(), &config, false);
+        assert_eq!(recursive.public_inputs, proof.public_inputs);
     }
 }
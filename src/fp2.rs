@@ -2,7 +2,7 @@
 use num_bigint::BigUint;
 use plonky2::{field::{extension::{Extendable, FieldExtension}, packed::PackedField, types::Field}, hash::hash_types::RichField, iop::ext_target::ExtensionTarget, plonk::circuit_builder::CircuitBuilder};
 use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
-use crate::{native::{get_u32_vec_from_literal, get_u32_vec_from_literal_24, modulus, Fp, Fp2}, utils::*, fp::*};
+use crate::{constants::FP2_FORBENIUS_COEFFICIENTS, native::{get_u32_vec_from_literal, get_u32_vec_from_literal_24, modulus, negate_fp, Fp, Fp2}, utils::*, fp::*};
 
 // Fp2 Multiplication layout offsets
 /*
@@ -107,6 +107,14 @@ pub const FP2_SUBTRACTION_0_OFFSET: usize = 0;
 pub const FP2_SUBTRACTION_1_OFFSET: usize = FP2_SUBTRACTION_0_OFFSET + FP_SUBTRACTION_TOTAL;
 pub const FP2_SUBTRACTION_TOTAL: usize = FP2_SUBTRACTION_1_OFFSET + FP_SUBTRACTION_TOTAL;
 
+// Fp2 negation modulo p layout offsets
+/*
+    These trace offsets are for negation of an Fp2 element with the result reduced. In essence it's two concatenated Fp negations modulo p. It needs 1 row.
+*/
+pub const FP2_NEG_0_OFFSET: usize = 0;
+pub const FP2_NEG_1_OFFSET: usize = FP2_NEG_0_OFFSET + FP_NEG_TOTAL;
+pub const FP2_NEG_TOTAL: usize = FP2_NEG_1_OFFSET + FP_NEG_TOTAL;
+
 // Fp2 multiply single
 /*
     These trace offsets are for multiply by single for two Fp2 elements. In essence it's two concatenated Fp multiply by single. It needs 1 row.
@@ -238,10 +246,26 @@ pub fn fill_trace_negate_fp2<F: RichField + Extendable<D>,
     row: usize,
     start_col: usize
 ) {
-    let minus_x: [[u32; 12]; 2] = (-Fp2([Fp(x[0].to_owned()), Fp(x[1].to_owned())])).0.iter().map(|x| x.0).collect::<Vec<[u32; 12]>>().try_into().unwrap();
+    let minus_x = [negate_fp(Fp(x[0])).0, negate_fp(Fp(x[1])).0];
     fill_trace_addition_fp2(trace, x, &minus_x, row, start_col);
 }
 
+/// Fills the stark trace of fp2 negation modulo p. Input is 12*2 limbs. Needs 1 row. Returns the reduced negation as \[\[u32; 12\]; 2\].
+pub fn fill_trace_neg_fp2<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(
+    trace: &mut Vec<[F; C]>,
+    x: &[[u32; 12]; 2],
+    row: usize,
+    start_col: usize
+) -> [[u32; 12]; 2] {
+    [
+        fill_trace_neg_fp(trace, &x[0], row, start_col + FP2_NEG_0_OFFSET),
+        fill_trace_neg_fp(trace, &x[1], row, start_col + FP2_NEG_1_OFFSET),
+    ]
+}
+
 /// Fills stark trace for fp2 multiplication. Inputs are 12*2 limbs each. Needs 12 rows. Sets addition and subtraction selectors to 1 only in 11th row, becuase that's where multiplication result is set.
 pub fn generate_trace_fp2_mul<F: RichField + Extendable<D>,
     const D: usize,
@@ -508,7 +532,7 @@ pub fn fill_trace_fp2_forbenius_map<F: RichField + Extendable<D>,
         trace[row][start_col + FP2_FORBENIUS_MAP_REM_OFFSET] = F::from_canonical_usize(rem);
     }
     trace[end_row][start_col + FP2_FORBENIUS_MAP_SELECTOR_OFFSET] = F::ZERO;
-    let forbenius_coefficients = Fp2::forbenius_coefficients();
+    let forbenius_coefficients = *FP2_FORBENIUS_COEFFICIENTS;
     fill_multiplication_trace_no_mod_reduction(trace, &x.0[1].0, &forbenius_coefficients[rem].0, start_row, end_row, start_col + FP2_FORBENIUS_MAP_T0_CALC_OFFSET);
     trace[start_row + 11][start_col + FP2_FORBENIUS_MAP_MUL_RES_ROW] = F::ONE;
     let x_y = get_u32_vec_from_literal_24(x.0[1].to_biguint() * forbenius_coefficients[rem].to_biguint());
@@ -691,6 +715,40 @@ pub fn add_negate_fp2_constraints_ext_circuit<
     }
 }   
 
+/// Constraints fp2 negation modulo p, i.e. `x + (-x) == 0 mod p` with `-x` reduced, for both fp elements. See [add_neg_fp_constraints].
+pub fn add_neg_fp2_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    add_neg_fp_constraints(local_values, yield_constr, start_col + FP2_NEG_0_OFFSET, bit_selector);
+    add_neg_fp_constraints(local_values, yield_constr, start_col + FP2_NEG_1_OFFSET, bit_selector);
+}
+
+pub fn add_neg_fp2_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    add_neg_fp_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP2_NEG_0_OFFSET, bit_selector);
+    add_neg_fp_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP2_NEG_1_OFFSET, bit_selector);
+}
+
 /// Constraints fp2 multiplication.
 ///
 /// Constraints inputs across this and next row, wherever selector is set to on. Constraints x0\*y0, x1\*y1, x0\*y1, x1\*y0 multiplication operations. Then constraints the x0\*y0 + p^2 operation, followed by x0\*y0 + p^2 - x1\*y1 operation. Constraints the reduction of result of the previous subtraction, followed by a range check operation. Constraints x0\*y1 + x1\*y0. Constraints the reduction of result of the previous addition, followed by a range check operation.
@@ -1867,6 +1925,60 @@ pub fn add_non_residue_multiplication_constraints_ext_circuit<
     add_range_check_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP2_NON_RESIDUE_MUL_Z1_RANGECHECK_OFFSET, bit_selector);
 }
 
+/// Constraints [mul_by_nonresidue](super::native::Fp2::mul_by_nonresidue) function along with its input. `input_cols` are the starting columns of the real and imaginary parts (12 limbs each) of the value multiplied by the non residue, which are constrained to be equal to the input of the gadget at `start_col`.
+pub fn add_mul_by_nonresidue_constraints<F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+    >(
+    local_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    input_cols: [usize; 2],
+    bit_selector: Option<P>,
+    ) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+
+    for i in 0..12 {
+        for (j, input_col) in input_cols.iter().enumerate() {
+            yield_constr.constraint(
+                bit_selector_val *
+                local_values[start_col + FP2_NON_RESIDUE_MUL_CHECK_OFFSET] *
+                (local_values[start_col + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + j*12 + i] - local_values[input_col + i])
+            );
+        }
+    }
+    add_non_residue_multiplication_constraints(local_values, yield_constr, start_col, bit_selector);
+}
+
+pub fn add_mul_by_nonresidue_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    input_cols: [usize; 2],
+    bit_selector: Option<ExtensionTarget<D>>,
+){
+    let bit_selector_val = bit_selector.unwrap_or(builder.constant_extension(F::Extension::ONE));
+
+    for i in 0..12 {
+        let tmp = builder.mul_extension(bit_selector_val, local_values[start_col + FP2_NON_RESIDUE_MUL_CHECK_OFFSET]);
+        for (j, input_col) in input_cols.iter().enumerate() {
+            let c = builder.sub_extension(local_values[start_col + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + j*12 + i], local_values[input_col + i]);
+            let c = builder.mul_extension(tmp, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+    add_non_residue_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, start_col, bit_selector);
+}
+
 /// Constraints for [fp4_square](super::native::fp4_square) function.
 ///
 ///  Constraints inputs across this and next row, wherever selector is set to on. Constraints the respective multiplication, addition and subtraction operations.
@@ -1930,21 +2042,7 @@ pub fn add_fp4_sq_constraints<F: RichField + Extendable<D>,
     }
     add_fp2_mul_constraints(local_values, next_values, yield_constr, start_col + FP4_SQ_T1_CALC_OFFSET, bit_selector);
 
-    for i in 0..12 {
-        yield_constr.constraint(
-            bit_selector.unwrap_or(P::ONES) *
-            local_values[start_col + FP4_SQ_T2_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET] *
-            (local_values[start_col + FP4_SQ_T2_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i] -
-            local_values[start_col + FP4_SQ_T1_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET + i])
-        );
-        yield_constr.constraint(
-            bit_selector.unwrap_or(P::ONES) *
-            local_values[start_col + FP4_SQ_T2_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET] *
-            (local_values[start_col + FP4_SQ_T2_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + 12 + i] -
-            local_values[start_col + FP4_SQ_T1_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET + i])
-        );
-    }
-    add_non_residue_multiplication_constraints(local_values, yield_constr, start_col + FP4_SQ_T2_CALC_OFFSET, bit_selector);
+    add_mul_by_nonresidue_constraints(local_values, yield_constr, start_col + FP4_SQ_T2_CALC_OFFSET, [start_col + FP4_SQ_T1_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET, start_col + FP4_SQ_T1_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET], bit_selector);
 
     for i in 0..12 {
         yield_constr.constraint(
@@ -2138,18 +2236,7 @@ pub fn add_fp4_sq_constraints_ext_circuit<
     }
     add_fp2_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FP4_SQ_T1_CALC_OFFSET, bit_selector);
 
-    for i in 0..12 {
-        let tmp = builder.mul_extension(bit_selector_val, local_values[start_col + FP4_SQ_T2_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET]);
-
-        let c = builder.sub_extension(local_values[start_col + FP4_SQ_T2_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i], local_values[start_col + FP4_SQ_T1_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET + i]);
-        let c = builder.mul_extension(tmp, c);
-        yield_constr.constraint(builder, c);
-
-        let c = builder.sub_extension(local_values[start_col + FP4_SQ_T2_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + 12 + i], local_values[start_col + FP4_SQ_T1_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET + i]);
-        let c = builder.mul_extension(tmp, c);
-        yield_constr.constraint(builder, c);
-    }
-    add_non_residue_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP4_SQ_T2_CALC_OFFSET, bit_selector);
+    add_mul_by_nonresidue_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP4_SQ_T2_CALC_OFFSET, [start_col + FP4_SQ_T1_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET, start_col + FP4_SQ_T1_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET], bit_selector);
 
     for i in 0..12 {
         let tmp1 = builder.mul_extension(bit_selector_val, local_values[start_col + FP4_SQ_X_CALC_OFFSET + FP2_ADDITION_0_OFFSET + FP_ADDITION_CHECK_OFFSET]);
@@ -2305,7 +2392,7 @@ pub fn add_fp2_forbenius_map_constraints<F: RichField + Extendable<D>,
         local_values[start_col + FP2_FORBENIUS_MAP_POW_OFFSET])
     );
     let bit = local_values[start_col + FP2_FORBENIUS_MAP_REM_OFFSET];
-    let forbenius_coefficients = Fp2::forbenius_coefficients().iter().map(|fp| fp.0).collect::<Vec<[u32; 12]>>();
+    let forbenius_coefficients = FP2_FORBENIUS_COEFFICIENTS.iter().map(|fp| fp.0).collect::<Vec<[u32; 12]>>();
     let y = (0..12).map(|i|
         (P::ONES - bit) * FE::from_canonical_u32(forbenius_coefficients[0][i]) + bit * FE::from_canonical_u32(forbenius_coefficients[1][i])
     ).collect::<Vec<P>>();
@@ -2369,7 +2456,7 @@ pub fn add_fp2_forbenius_map_constraints_ext_circuit<
 
     let bit = local_values[start_col + FP2_FORBENIUS_MAP_REM_OFFSET];
     let one = builder.constant_extension(F::Extension::ONE);
-    let forbenius_coefficients = Fp2::forbenius_coefficients().iter().map(|fp| fp.0).collect::<Vec<[u32; 12]>>();
+    let forbenius_coefficients = FP2_FORBENIUS_COEFFICIENTS.iter().map(|fp| fp.0).collect::<Vec<[u32; 12]>>();
     let y = (0..12).map(|i| {
         let sub = builder.sub_extension(one, bit);
         let const1 = builder.constant_extension(F::Extension::from_canonical_u32(forbenius_coefficients[0][i]));
@@ -2403,3 +2490,134 @@ pub fn add_fp2_forbenius_map_constraints_ext_circuit<
     add_reduce_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FP2_FORBENIUS_MAP_T0_CALC_OFFSET + FP_MULTIPLICATION_TOTAL_COLUMNS, start_col + FP2_FORBENIUS_MAP_SELECTOR_OFFSET, bit_selector);
     add_range_check_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP2_FORBENIUS_MAP_T0_CALC_OFFSET + FP_MULTIPLICATION_TOTAL_COLUMNS + REDUCTION_TOTAL, bit_selector);
 }
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use num_bigint::BigUint;
+    use plonky2::{field::{extension::{Extendable, FieldExtension}, packed::PackedField, types::{Field, PrimeField64}}, hash::hash_types::RichField, iop::ext_target::ExtensionTarget, plonk::{circuit_builder::CircuitBuilder, config::{GenericConfig, PoseidonGoldilocksConfig}}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer}, evaluation_frame::{StarkEvaluationFrame, StarkFrame}, prover::prove, stark::Stark, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::{fp::fp_neg_res, native::{modulus, Fp, Fp2}};
+
+    use super::{add_neg_fp2_constraints, add_neg_fp2_constraints_ext_circuit, fill_trace_neg_fp2, FP2_NEG_0_OFFSET, FP2_NEG_1_OFFSET, FP2_NEG_TOTAL};
+    #[cfg(feature = "debug-constraints")]
+    use crate::{debug_constraints::debug_check_trace, fp::{fill_range_check_trace, fill_trace_addition_fp_mod, FP_NEG_ADD_MOD_OFFSET, FP_NEG_RANGECHECK_OFFSET, FP_NEG_TOTAL}, native::get_u32_vec_from_literal, utils::{assign_u32_in_series, num_constraints}};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    const NEG_ROWS: usize = 8;
+
+    /// Negates an fp2 element in each row.
+    #[derive(Clone, Copy)]
+    struct NegFp2Stark<F: RichField + Extendable<D>, const D: usize> {
+        _f: std::marker::PhantomData<F>,
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for NegFp2Stark<F, D> {
+        type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, FP2_NEG_TOTAL, 0>
+        where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>;
+
+        type EvaluationFrameTarget = StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, FP2_NEG_TOTAL, 0>;
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: &Self::EvaluationFrame<FE, P, D2>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            add_neg_fp2_constraints(vars.get_local_values(), yield_constr, 0, None);
+        }
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut CircuitBuilder<F, D>,
+            vars: &Self::EvaluationFrameTarget,
+            yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+        ) {
+            add_neg_fp2_constraints_ext_circuit(builder, yield_constr, vars.get_local_values(), 0, None);
+        }
+
+        fn constraint_degree(&self) -> usize {
+            3
+        }
+    }
+
+    fn fp(s: &str) -> Fp {
+        Fp::get_fp_from_biguint(BigUint::from_str(s).unwrap())
+    }
+
+    fn test_inputs() -> [Fp2; NEG_ROWS] {
+        let p_minus_one = Fp::get_fp_from_biguint(modulus() - 1u32);
+        let x = fp("1216495682195235861952885506871698490232894470117269383940381148575524314493849307811227440691167647909822763414941");
+        let y = fp("2153848155426317245700560287567131132765685008362732985860101000686875894603366983854567186180519945327668975076337");
+        [
+            Fp2::zero(),
+            Fp2::one(),
+            Fp2([Fp::zero(), x]),
+            Fp2([x, Fp::zero()]),
+            Fp2([x, y]),
+            Fp2([p_minus_one, Fp::one()]),
+            Fp2([y, p_minus_one]),
+            Fp2([p_minus_one, p_minus_one]),
+        ]
+    }
+
+    /// Proves and verifies the negation trace.
+    fn prove_neg_fp2(trace: Vec<[F; FP2_NEG_TOTAL]>) {
+        let stark = NegFp2Stark::<F, D> { _f: std::marker::PhantomData };
+        let config = StarkConfig::standard_fast_config();
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, NegFp2Stark<F, D>, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &[],
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+    }
+
+    #[test]
+    fn test_neg_fp2() {
+        let inputs = test_inputs();
+        let mut trace = vec![[F::ZERO; FP2_NEG_TOTAL]; NEG_ROWS];
+        for (row, x) in inputs.iter().enumerate() {
+            let res = fill_trace_neg_fp2(&mut trace, &x.get_u32_slice(), row, 0);
+            assert_eq!(res, (-*x).get_u32_slice());
+            for (i, col) in [FP2_NEG_0_OFFSET, FP2_NEG_1_OFFSET].into_iter().enumerate() {
+                let res_trace: Vec<u32> = (0..12).map(|j| trace[row][fp_neg_res(col) + j].to_canonical_u64() as u32).collect();
+                assert_eq!(res_trace, res[i].to_vec());
+            }
+        }
+        prove_neg_fp2(trace);
+    }
+
+    #[cfg(feature = "debug-constraints")]
+    #[test]
+    fn test_neg_fp2_not_reduced() {
+        let inputs = test_inputs();
+        let mut trace = vec![[F::ZERO; FP2_NEG_TOTAL]; NEG_ROWS];
+        for (row, x) in inputs.iter().enumerate() {
+            fill_trace_neg_fp2(&mut trace, &x.get_u32_slice(), row, 0);
+        }
+        // p instead of 0 as the negation of the real part of zero, which satisfies x + (-x) == 0 mod p.
+        let p = get_u32_vec_from_literal(modulus());
+        for col in 0..FP_NEG_TOTAL {
+            trace[0][FP2_NEG_0_OFFSET + col] = F::ZERO;
+        }
+        assign_u32_in_series(&mut trace, 0, fp_neg_res(FP2_NEG_0_OFFSET), &p);
+        fill_range_check_trace(&mut trace, &p, 0, FP2_NEG_0_OFFSET + FP_NEG_RANGECHECK_OFFSET);
+        fill_trace_addition_fp_mod(&mut trace, &[0; 12], &p, 0, FP2_NEG_0_OFFSET + FP_NEG_ADD_MOD_OFFSET);
+        // Only the range check of the negated real part fails, on the bit 30 of its sum, which is its third constraint. The range check constraints, 3 for each of the 12 limbs, are the last ones of the negation of the real part, which emits half of the constraints.
+        let stark = NegFp2Stark::<F, D> { _f: std::marker::PhantomData };
+        assert_eq!(debug_check_trace(&stark, &trace, &[]), Some((0, num_constraints(&stark) / 2 - 3*12 + 2)));
+    }
+}
@@ -0,0 +1,249 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use crate::native::{get_bits_as_array, Fp2};
+
+use crate::fp::*;
+use crate::fp2::*;
+
+// Fp2 frobenius map stark layout offsets
+/*
+    These trace offsets are for proving the fp2 frobenius map (super::native::Fp2::forbenius_map) on its own. It needs 12 rows.
+    [x0, x1]^(p^pow) = [x0, x1 * constant[pow % 2]], i.e. the conjugate for odd powers.
+    FP2_FROBENIUS_MAP_OFFSET -> offset where the fp2 forbenius map operation (super::fp2::fill_trace_fp2_forbenius_map) is done.
+    FP2_FROBENIUS_DIV_BITS_OFFSET -> offset where the 32 bit decomposition of pow/2 is set, least significant bit first. Bounds the divisor, so that the remainder is pow % 2.
+*/
+pub const FP2_FROBENIUS_MAP_OFFSET: usize = 0;
+pub const FP2_FROBENIUS_DIV_BITS_OFFSET: usize = FP2_FROBENIUS_MAP_OFFSET + FP2_FORBENIUS_MAP_TOTAL_COLUMNS;
+pub const FP2_FROBENIUS_TOTAL: usize = FP2_FROBENIUS_DIV_BITS_OFFSET + 32;
+
+pub const TOTAL_COLUMNS: usize = FP2_FROBENIUS_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the input `x`, the power `pow`, then the result `x^(p^pow)`.
+*/
+pub const PIS_INPUT_OFFSET: usize = 0;
+pub const PIS_POW_OFFSET: usize = PIS_INPUT_OFFSET + 24;
+pub const PIS_RESULT_OFFSET: usize = PIS_POW_OFFSET + 1;
+pub const PUBLIC_INPUTS: usize = PIS_RESULT_OFFSET + 24;
+
+/// Column of the reduced imaginary part of the result of the fp2 forbenius map operation starting at `col`.
+fn fp2_frobenius_res_c1(col: usize) -> usize {
+    col + FP2_FORBENIUS_MAP_T0_CALC_OFFSET + FP_MULTIPLICATION_TOTAL_COLUMNS + REDUCED_OFFSET
+}
+
+#[derive(Clone, Copy)]
+pub struct Fp2FrobeniusStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> Fp2FrobeniusStark<F, D> {
+    /// Rows used by one fp2 frobenius map, 12. The stark needs a power of two number of rows of at least this many.
+    pub fn required_rows() -> usize {
+        12
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(x: &Fp2, pow: usize, res: &Fp2) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for (i, limb) in x.get_u32_slice().concat().iter().enumerate() {
+            public_inputs[PIS_INPUT_OFFSET + i] = F::from_canonical_u32(*limb);
+        }
+        public_inputs[PIS_POW_OFFSET] = F::from_canonical_usize(pow);
+        for (i, limb) in res.get_u32_slice().concat().iter().enumerate() {
+            public_inputs[PIS_RESULT_OFFSET + i] = F::from_canonical_u32(*limb);
+        }
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, x: &Fp2, pow: usize) -> Vec<[F; TOTAL_COLUMNS]> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_fp2_forbenius_map(&mut trace, x, pow, 0, 11, FP2_FROBENIUS_MAP_OFFSET);
+        let div_bits = get_bits_as_array(u32::try_from(pow / 2).unwrap());
+        for row in 0..12 {
+            for (i, bit) in div_bits.iter().enumerate() {
+                trace[row][FP2_FROBENIUS_DIV_BITS_OFFSET + i] = F::from_canonical_u32(*bit);
+            }
+        }
+        trace
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Fp2FrobeniusStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let map_col = FP2_FROBENIUS_MAP_OFFSET;
+        yield_constr.constraint_first_row(local_values[map_col + FP2_FORBENIUS_MAP_SELECTOR_OFFSET] - P::ONES);
+        yield_constr.constraint_first_row(
+            local_values[map_col + FP2_FORBENIUS_MAP_T0_CALC_OFFSET + MULTIPLICATION_SELECTOR_OFFSET] - P::ONES
+        );
+        for i in 0..24 {
+            yield_constr.constraint_first_row(local_values[map_col + FP2_FORBENIUS_MAP_INPUT_OFFSET + i] - public_inputs[PIS_INPUT_OFFSET + i]);
+        }
+        yield_constr.constraint_first_row(local_values[map_col + FP2_FORBENIUS_MAP_POW_OFFSET] - public_inputs[PIS_POW_OFFSET]);
+        for i in 0..12 {
+            yield_constr.constraint_first_row(local_values[map_col + FP2_FORBENIUS_MAP_INPUT_OFFSET + i] - public_inputs[PIS_RESULT_OFFSET + i]);
+            yield_constr.constraint_first_row(local_values[fp2_frobenius_res_c1(map_col) + i] - public_inputs[PIS_RESULT_OFFSET + 12 + i]);
+        }
+
+        let rem = local_values[map_col + FP2_FORBENIUS_MAP_REM_OFFSET];
+        yield_constr.constraint_first_row(rem * (P::ONES - rem));
+        let div = (0..32).fold(P::ZEROS, |acc, i| {
+            let bit = local_values[FP2_FROBENIUS_DIV_BITS_OFFSET + i];
+            yield_constr.constraint_first_row(bit * (P::ONES - bit));
+            acc + bit * FE::from_canonical_u64(1 << i)
+        });
+        yield_constr.constraint_first_row(div - local_values[map_col + FP2_FORBENIUS_MAP_DIV_OFFSET]);
+
+        add_fp2_forbenius_map_constraints(local_values, next_values, yield_constr, map_col, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let map_col = FP2_FROBENIUS_MAP_OFFSET;
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[map_col + FP2_FORBENIUS_MAP_SELECTOR_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        let c = builder.sub_extension(local_values[map_col + FP2_FORBENIUS_MAP_T0_CALC_OFFSET + MULTIPLICATION_SELECTOR_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for i in 0..24 {
+            let c = builder.sub_extension(local_values[map_col + FP2_FORBENIUS_MAP_INPUT_OFFSET + i], public_inputs[PIS_INPUT_OFFSET + i]);
+            yield_constr.constraint_first_row(builder, c);
+        }
+        let c = builder.sub_extension(local_values[map_col + FP2_FORBENIUS_MAP_POW_OFFSET], public_inputs[PIS_POW_OFFSET]);
+        yield_constr.constraint_first_row(builder, c);
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[map_col + FP2_FORBENIUS_MAP_INPUT_OFFSET + i], public_inputs[PIS_RESULT_OFFSET + i]);
+            yield_constr.constraint_first_row(builder, c);
+            let c = builder.sub_extension(local_values[fp2_frobenius_res_c1(map_col) + i], public_inputs[PIS_RESULT_OFFSET + 12 + i]);
+            yield_constr.constraint_first_row(builder, c);
+        }
+
+        let rem = local_values[map_col + FP2_FORBENIUS_MAP_REM_OFFSET];
+        let c = builder.sub_extension(one, rem);
+        let c = builder.mul_extension(rem, c);
+        yield_constr.constraint_first_row(builder, c);
+        let zero = builder.constant_extension(F::Extension::ZERO);
+        let div = (0..32).fold(zero, |acc, i| {
+            let bit = local_values[FP2_FROBENIUS_DIV_BITS_OFFSET + i];
+            let c = builder.sub_extension(one, bit);
+            let c = builder.mul_extension(bit, c);
+            yield_constr.constraint_first_row(builder, c);
+            let tmp_const = builder.constant_extension(F::Extension::from_canonical_u64(1 << i));
+            builder.mul_add_extension(bit, tmp_const, acc)
+        });
+        let c = builder.sub_extension(div, local_values[map_col + FP2_FORBENIUS_MAP_DIV_OFFSET]);
+        yield_constr.constraint_first_row(builder, c);
+
+        add_fp2_forbenius_map_constraints_ext_circuit(builder, yield_constr, local_values, next_values, map_col, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{Fp, Fp2};
+
+    use super::{Fp2FrobeniusStark, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Fp2FrobeniusStark<F, D>;
+
+    fn prove_and_verify_frobenius(x: &Fp2, pow: usize) -> Fp2 {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(16);
+        let res = x.forbenius_map(pow);
+        let public_inputs = S::public_inputs(x, pow, &res);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(x, pow);
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        res
+    }
+
+    fn test_element() -> Fp2 {
+        Fp2([
+            Fp::get_fp_from_biguint(BigUint::parse_bytes(b"17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb", 16).unwrap()),
+            Fp::get_fp_from_biguint(BigUint::parse_bytes(b"08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1", 16).unwrap()),
+        ])
+    }
+
+    #[test]
+    fn test_fp2_frobenius_pow_0() {
+        let x = test_element();
+        assert_eq!(prove_and_verify_frobenius(&x, 0), x);
+    }
+
+    #[test]
+    fn test_fp2_frobenius_pow_1() {
+        let x = test_element();
+        let res = prove_and_verify_frobenius(&x, 1);
+        assert_eq!(res, Fp2([x.0[0], Fp::zero() - x.0[1]]));
+        assert_eq!(prove_and_verify_frobenius(&res, 1), x);
+    }
+}
@@ -0,0 +1,346 @@
+use anyhow::Result;
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use crate::native::{Fp, Fp2};
+
+use crate::fp::*;
+use crate::fp2::*;
+use crate::fp_inverse::*;
+use crate::utils::*;
+
+// Fp2 inverse layout offsets
+/*
+    These trace offsets are for proving the inverse of an fp2 element with the conjugate-and-norm formula. It needs 12 rows.
+    [a0, a1]^-1 = [a0, -a1] * (a0^2 + a1^2)^-1
+    FP2_INV_CHECK_OFFSET -> Selector set 1 in all rows of the operation, where the intermediate values are constrained with each other.
+    FP2_INV_INPUT_OFFSET -> offset where the input `a` is set.
+    FP2_INV_INVERSE_OFFSET -> offset where the inverse `a_inv` is set.
+    FP2_INV_NEG_OFFSET -> offset where a1 + (-a1) fp addition modulo p is done, which is constrained to be 0. The y input of this operation is the imaginary part of the conjugate.
+    FP2_INV_NORM_OFFSET -> offset where the fp2 multiplication of `a` with its conjugate is done. The real part of the result is the norm, and the imaginary part is constrained to be 0.
+    FP2_INV_NORM_INV_OFFSET -> offset where the fp inverse of the norm is done. This rejects `a == 0`.
+    FP2_INV_MUL_OFFSET -> offset where the fp2 fp multiplication of the conjugate with the inverse of the norm is done. The result is constrained to be `a_inv`.
+*/
+pub const FP2_INV_CHECK_OFFSET: usize = 0;
+pub const FP2_INV_INPUT_OFFSET: usize = FP2_INV_CHECK_OFFSET + 1;
+pub const FP2_INV_INVERSE_OFFSET: usize = FP2_INV_INPUT_OFFSET + 24;
+pub const FP2_INV_NEG_OFFSET: usize = FP2_INV_INVERSE_OFFSET + 24;
+pub const FP2_INV_NORM_OFFSET: usize = FP2_INV_NEG_OFFSET + FP_ADD_MOD_TOTAL;
+pub const FP2_INV_NORM_INV_OFFSET: usize = FP2_INV_NORM_OFFSET + TOTAL_COLUMNS_FP2_MULTIPLICATION;
+pub const FP2_INV_MUL_OFFSET: usize = FP2_INV_NORM_INV_OFFSET + FP_INV_TOTAL;
+pub const FP2_INV_TOTAL: usize = FP2_INV_MUL_OFFSET + FP2_FP_TOTAL_COLUMNS;
+
+pub const TOTAL_COLUMNS: usize = FP2_INV_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the input `a` followed by its inverse `a_inv`.
+*/
+pub const PIS_INPUT_OFFSET: usize = 0;
+pub const PIS_INVERSE_OFFSET: usize = PIS_INPUT_OFFSET + 24;
+pub const PUBLIC_INPUTS: usize = PIS_INVERSE_OFFSET + 24;
+
+/// Pairs of (column, column) which are constrained to be equal wherever `FP2_INV_CHECK_OFFSET` is set, along with the number of limbs.
+fn fp2_inverse_links(start_col: usize) -> Vec<(usize, usize, usize)> {
+    let neg_col = start_col + FP2_INV_NEG_OFFSET;
+    let norm_col = start_col + FP2_INV_NORM_OFFSET;
+    let norm_inv_col = start_col + FP2_INV_NORM_INV_OFFSET;
+    let mul_col = start_col + FP2_INV_MUL_OFFSET;
+    vec![
+        (fp_add_mod_x(neg_col), start_col + FP2_INV_INPUT_OFFSET + 12, 12),
+        (norm_col + FP2_FP2_X_INPUT_OFFSET, start_col + FP2_INV_INPUT_OFFSET, 24),
+        (norm_col + FP2_FP2_Y_INPUT_OFFSET, start_col + FP2_INV_INPUT_OFFSET, 12),
+        (norm_col + FP2_FP2_Y_INPUT_OFFSET + 12, fp_add_mod_y(neg_col), 12),
+        (norm_inv_col + FP_INV_INPUT_OFFSET, norm_col + Z1_REDUCE_OFFSET + REDUCED_OFFSET, 12),
+        (mul_col + FP2_FP_X_INPUT_OFFSET, start_col + FP2_INV_INPUT_OFFSET, 12),
+        (mul_col + FP2_FP_X_INPUT_OFFSET + 12, fp_add_mod_y(neg_col), 12),
+        (mul_col + FP2_FP_Y_INPUT_OFFSET, norm_inv_col + FP_INV_INVERSE_OFFSET, 12),
+        (mul_col + X0_Y_REDUCE_OFFSET + REDUCED_OFFSET, start_col + FP2_INV_INVERSE_OFFSET, 12),
+        (mul_col + X1_Y_REDUCE_OFFSET + REDUCED_OFFSET, start_col + FP2_INV_INVERSE_OFFSET + 12, 12),
+    ]
+}
+
+/// Columns which are constrained to be zero wherever `FP2_INV_CHECK_OFFSET` is set, 12 limbs each.
+fn fp2_inverse_zeros(start_col: usize) -> [usize; 2] {
+    [
+        fp_add_mod_res(start_col + FP2_INV_NEG_OFFSET),
+        start_col + FP2_INV_NORM_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET,
+    ]
+}
+
+/// Fills the stark trace of fp2 inverse. Input is an fp2 element, returns an error if it is zero. Needs 12 rows. Fills the negation of the imaginary part, the multiplication of the input with its conjugate, the inverse of the norm and the multiplication of the conjugate with the inverse of the norm. Returns the inverse.
+pub fn fill_trace_fp2_inverse<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, a: &Fp2, start_row: usize, end_row: usize, start_col: usize) -> Result<Fp2> {
+    let neg_a1 = if a.0[1] == Fp::zero() {
+        Fp::zero()
+    } else {
+        -a.0[1]
+    };
+    let conj = Fp2([a.0[0], neg_a1]);
+    let norm = a.0[0] * a.0[0] + a.0[1] * a.0[1];
+    let norm_inv = fill_trace_fp_inverse(trace, &norm, start_row, end_row, start_col + FP2_INV_NORM_INV_OFFSET)?;
+    let a_inv = conj * norm_inv;
+    for row in start_row..end_row + 1 {
+        trace[row][start_col + FP2_INV_CHECK_OFFSET] = F::ONE;
+        assign_u32_in_series(trace, row, start_col + FP2_INV_INPUT_OFFSET, &a.get_u32_slice().concat());
+        assign_u32_in_series(trace, row, start_col + FP2_INV_INVERSE_OFFSET, &a_inv.get_u32_slice().concat());
+        fill_trace_addition_fp_mod(trace, &a.0[1].0, &neg_a1.0, row, start_col + FP2_INV_NEG_OFFSET);
+    }
+    generate_trace_fp2_mul(trace, a.get_u32_slice(), conj.get_u32_slice(), start_row, end_row, start_col + FP2_INV_NORM_OFFSET);
+    fill_trace_fp2_fp_mul(trace, &conj.get_u32_slice(), &norm_inv.0, start_row, end_row, start_col + FP2_INV_MUL_OFFSET);
+    assert_eq!(*a * a_inv, Fp2::one());
+    Ok(a_inv)
+}
+
+/// Constraints fp2 inverse.
+/// Constraints the intermediate values of the conjugate-and-norm formula with each other wherever `FP2_INV_CHECK_OFFSET` is set. The sum of the imaginary parts of the input and the conjugate, as well as the imaginary part of the product of the input with its conjugate, are constrained to be 0. Then constraints the fp addition, fp2 multiplication, fp inverse and fp2 fp multiplication operations.
+pub fn add_fp2_inverse_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+    let check = bit_selector_val * local_values[start_col + FP2_INV_CHECK_OFFSET];
+
+    for (x, y, limbs) in fp2_inverse_links(start_col) {
+        for i in 0..limbs {
+            yield_constr.constraint(check * (local_values[x + i] - local_values[y + i]));
+        }
+    }
+    for col in fp2_inverse_zeros(start_col) {
+        for i in 0..12 {
+            yield_constr.constraint(check * local_values[col + i]);
+        }
+    }
+
+    add_addition_fp_mod_constraints(local_values, yield_constr, start_col + FP2_INV_NEG_OFFSET, bit_selector);
+    add_fp2_mul_constraints(local_values, next_values, yield_constr, start_col + FP2_INV_NORM_OFFSET, bit_selector);
+    add_fp_inverse_constraints(local_values, next_values, yield_constr, start_col + FP2_INV_NORM_INV_OFFSET, bit_selector);
+    add_fp2_fp_mul_constraints(local_values, next_values, yield_constr, start_col + FP2_INV_MUL_OFFSET, bit_selector);
+}
+
+pub fn add_fp2_inverse_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let bit_selector_val = bit_selector.unwrap_or(builder.constant_extension(F::Extension::ONE));
+    let check = builder.mul_extension(bit_selector_val, local_values[start_col + FP2_INV_CHECK_OFFSET]);
+
+    for (x, y, limbs) in fp2_inverse_links(start_col) {
+        for i in 0..limbs {
+            let c = builder.sub_extension(local_values[x + i], local_values[y + i]);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+    for col in fp2_inverse_zeros(start_col) {
+        for i in 0..12 {
+            let c = builder.mul_extension(check, local_values[col + i]);
+            yield_constr.constraint(builder, c);
+        }
+    }
+
+    add_addition_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP2_INV_NEG_OFFSET, bit_selector);
+    add_fp2_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FP2_INV_NORM_OFFSET, bit_selector);
+    add_fp_inverse_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FP2_INV_NORM_INV_OFFSET, bit_selector);
+    add_fp2_fp_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FP2_INV_MUL_OFFSET, bit_selector);
+}
+
+#[derive(Clone, Copy)]
+pub struct Fp2InverseStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> Fp2InverseStark<F, D> {
+    /// Rows used by one fp2 inversion, 12. The stark needs a power of two number of rows of at least this many.
+    pub fn required_rows() -> usize {
+        12
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(a: &Fp2, a_inv: &Fp2) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for (i, limb) in a.get_u32_slice().concat().iter().enumerate() {
+            public_inputs[PIS_INPUT_OFFSET + i] = F::from_canonical_u32(*limb);
+        }
+        for (i, limb) in a_inv.get_u32_slice().concat().iter().enumerate() {
+            public_inputs[PIS_INVERSE_OFFSET + i] = F::from_canonical_u32(*limb);
+        }
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, a: &Fp2) -> Result<Vec<[F; TOTAL_COLUMNS]>> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_fp2_inverse(&mut trace, a, 0, 11, 0)?;
+        Ok(trace)
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Fp2InverseStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[FP2_INV_CHECK_OFFSET] - P::ONES);
+        for i in 0..24 {
+            yield_constr.constraint(
+                local_values[FP2_INV_CHECK_OFFSET] *
+                (local_values[FP2_INV_INPUT_OFFSET + i] - public_inputs[PIS_INPUT_OFFSET + i])
+            );
+            yield_constr.constraint(
+                local_values[FP2_INV_CHECK_OFFSET] *
+                (local_values[FP2_INV_INVERSE_OFFSET + i] - public_inputs[PIS_INVERSE_OFFSET + i])
+            );
+        }
+        add_fp2_inverse_constraints(local_values, next_values, yield_constr, 0, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[FP2_INV_CHECK_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for i in 0..24 {
+            let c = builder.sub_extension(local_values[FP2_INV_INPUT_OFFSET + i], public_inputs[PIS_INPUT_OFFSET + i]);
+            let c = builder.mul_extension(local_values[FP2_INV_CHECK_OFFSET], c);
+            yield_constr.constraint(builder, c);
+            let c = builder.sub_extension(local_values[FP2_INV_INVERSE_OFFSET + i], public_inputs[PIS_INVERSE_OFFSET + i]);
+            let c = builder.mul_extension(local_values[FP2_INV_CHECK_OFFSET], c);
+            yield_constr.constraint(builder, c);
+        }
+        add_fp2_inverse_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{modulus, Fp, Fp2};
+
+    use super::{Fp2InverseStark, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Fp2InverseStark<F, D>;
+
+    fn prove_and_verify_inverse(a: &Fp2) -> Fp2 {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(16);
+        let a_inv = a.invert();
+        let public_inputs = S::public_inputs(a, &a_inv);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(a).unwrap();
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        a_inv
+    }
+
+    #[test]
+    fn test_fp2_inverse_random_elements() {
+        let seed = BigUint::parse_bytes(b"5b0f3e9a1c7d2e4f6a8b0c1d2e3f405162738495a6b7c8d9eafb0c1d2e3f4051", 16).unwrap();
+        let mut x = seed.clone();
+        for _ in 0..2 {
+            x = (&x * &seed + 3u32) % modulus();
+            let a0 = Fp::get_fp_from_biguint(x.clone());
+            x = (&x * &seed + 3u32) % modulus();
+            let a1 = Fp::get_fp_from_biguint(x.clone());
+            let a = Fp2([a0, a1]);
+            let a_inv = prove_and_verify_inverse(&a);
+            assert_eq!(a * a_inv, Fp2::one());
+        }
+    }
+
+    #[test]
+    fn test_fp2_inverse_real_element() {
+        let a = Fp2([Fp::get_fp_from_biguint(BigUint::from(2u32)), Fp::zero()]);
+        let a_inv = prove_and_verify_inverse(&a);
+        assert_eq!(a_inv, Fp2([Fp::get_fp_from_biguint((modulus() + 1u32) / 2u32), Fp::zero()]));
+    }
+
+    #[test]
+    fn test_fp2_inverse_of_zero() {
+        let stark = S::new(16);
+        assert!(stark.generate_trace(&Fp2::zero()).is_err());
+    }
+}
@@ -0,0 +1,397 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use crate::native::{sqrt_fp2, Fp2};
+
+use crate::fp::*;
+use crate::fp2::*;
+use crate::fp2_inverse::*;
+use crate::utils::*;
+
+// Fp2 square root layout offsets
+/*
+    These trace offsets are for proving the square root of an fp2 element, or that it has none. It needs 12 rows.
+    The non residue 1 + u of the Fp6 tower is not a square in Fp2, so exactly one of `a` and `(1 + u) * a` is a square for non zero `a`. A non zero root of `(1 + u) * a` is the certificate that `a` is not a square.
+    FP2_SQRT_CHECK_OFFSET -> Selector set 1 in all rows of the operation, where the intermediate values are constrained with each other.
+    FP2_SQRT_INPUT_OFFSET -> offset where the input `a` is set.
+    FP2_SQRT_ROOT_OFFSET -> offset where the witness `root` is set. It is the square root of `a` if `a` is a square, and the square root of `(1 + u) * a` otherwise.
+    FP2_SQRT_IS_SQUARE_OFFSET -> boolean set 1 if `a` is a square.
+    FP2_SQRT_MUL_OFFSET -> offset where root * root fp2 multiplication is done. The result is constrained to be `a` if `a` is a square.
+    FP2_SQRT_NON_RESIDUE_MUL_OFFSET -> offset where the multiplication of `a` by the non residue is done. The square of the root is constrained to be its result if `a` is not a square.
+    FP2_SQRT_ROOT_INV_OFFSET -> offset where the fp2 inverse of the root is done if `a` is not a square, which rejects `a == 0` claimed as a non square. If `a` is a square, the inverse of 1 is filled instead and left unconstrained with the root.
+*/
+pub const FP2_SQRT_CHECK_OFFSET: usize = 0;
+pub const FP2_SQRT_INPUT_OFFSET: usize = FP2_SQRT_CHECK_OFFSET + 1;
+pub const FP2_SQRT_ROOT_OFFSET: usize = FP2_SQRT_INPUT_OFFSET + 24;
+pub const FP2_SQRT_IS_SQUARE_OFFSET: usize = FP2_SQRT_ROOT_OFFSET + 24;
+pub const FP2_SQRT_MUL_OFFSET: usize = FP2_SQRT_IS_SQUARE_OFFSET + 1;
+pub const FP2_SQRT_NON_RESIDUE_MUL_OFFSET: usize = FP2_SQRT_MUL_OFFSET + TOTAL_COLUMNS_FP2_MULTIPLICATION;
+pub const FP2_SQRT_ROOT_INV_OFFSET: usize = FP2_SQRT_NON_RESIDUE_MUL_OFFSET + FP2_NON_RESIDUE_MUL_TOTAL;
+pub const FP2_SQRT_TOTAL: usize = FP2_SQRT_ROOT_INV_OFFSET + FP2_INV_TOTAL;
+
+pub const TOTAL_COLUMNS: usize = FP2_SQRT_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the input `a`, the witness `root` and the boolean which is 1 if `a` is a square.
+*/
+pub const PIS_INPUT_OFFSET: usize = 0;
+pub const PIS_ROOT_OFFSET: usize = PIS_INPUT_OFFSET + 24;
+pub const PIS_IS_SQUARE_OFFSET: usize = PIS_ROOT_OFFSET + 24;
+pub const PUBLIC_INPUTS: usize = PIS_IS_SQUARE_OFFSET + 1;
+
+/// Columns of the real and imaginary parts of the square of the root.
+fn fp2_sqrt_root_sq(start_col: usize) -> [usize; 2] {
+    let mul_col = start_col + FP2_SQRT_MUL_OFFSET;
+    [mul_col + Z1_REDUCE_OFFSET + REDUCED_OFFSET, mul_col + Z2_REDUCE_OFFSET + REDUCED_OFFSET]
+}
+
+/// Pairs of (column, column) which are constrained to be equal wherever `FP2_SQRT_CHECK_OFFSET` is set, along with the number of limbs.
+fn fp2_sqrt_links(start_col: usize) -> Vec<(usize, usize, usize)> {
+    let mul_col = start_col + FP2_SQRT_MUL_OFFSET;
+    vec![
+        (mul_col + FP2_FP2_X_INPUT_OFFSET, start_col + FP2_SQRT_ROOT_OFFSET, 24),
+        (mul_col + FP2_FP2_Y_INPUT_OFFSET, start_col + FP2_SQRT_ROOT_OFFSET, 24),
+        (start_col + FP2_SQRT_NON_RESIDUE_MUL_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET, start_col + FP2_SQRT_INPUT_OFFSET, 24),
+    ]
+}
+
+/// Pairs of (column, column) of 12 limbs which are constrained to be equal if `a` is a square: the square of the root and the input.
+fn fp2_sqrt_square_links(start_col: usize) -> [(usize, usize); 2] {
+    let [sq0, sq1] = fp2_sqrt_root_sq(start_col);
+    [
+        (sq0, start_col + FP2_SQRT_INPUT_OFFSET),
+        (sq1, start_col + FP2_SQRT_INPUT_OFFSET + 12),
+    ]
+}
+
+/// Pairs of (column, column) of 12 limbs which are constrained to be equal if `a` is not a square: the square of the root and the input multiplied by the non residue, and the root and the input of the fp2 inverse.
+fn fp2_sqrt_non_square_links(start_col: usize) -> [(usize, usize); 4] {
+    let [sq0, sq1] = fp2_sqrt_root_sq(start_col);
+    let non_residue_col = start_col + FP2_SQRT_NON_RESIDUE_MUL_OFFSET;
+    let root_inv_col = start_col + FP2_SQRT_ROOT_INV_OFFSET;
+    [
+        (sq0, non_residue_col + FP2_NON_RESIDUE_MUL_Z0_REDUCE_OFFSET + FP_SINGLE_REDUCED_OFFSET),
+        (sq1, non_residue_col + FP2_NON_RESIDUE_MUL_Z1_REDUCE_OFFSET + FP_SINGLE_REDUCED_OFFSET),
+        (root_inv_col + FP2_INV_INPUT_OFFSET, start_col + FP2_SQRT_ROOT_OFFSET),
+        (root_inv_col + FP2_INV_INPUT_OFFSET + 12, start_col + FP2_SQRT_ROOT_OFFSET + 12),
+    ]
+}
+
+/// Returns the witness root of `a` along with whether `a` is a square. The root is the square root of `a` if it exists, and the square root of `(1 + u) * a` otherwise.
+pub fn get_fp2_sqrt_witness(a: &Fp2) -> (Fp2, bool) {
+    match sqrt_fp2(*a) {
+        Some(root) => (root, true),
+        None => (sqrt_fp2(a.mul_by_nonresidue()).unwrap(), false),
+    }
+}
+
+/// Fills the stark trace of fp2 square root. Input is an fp2 element. Needs 12 rows. Fills the square of the witness root, the multiplication of the input by the non residue and the inverse of the root. Returns the square root of the input if it exists.
+pub fn fill_trace_fp2_sqrt<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, a: &Fp2, start_row: usize, end_row: usize, start_col: usize) -> Option<Fp2> {
+    let (root, is_square) = get_fp2_sqrt_witness(a);
+    for row in start_row..end_row + 1 {
+        trace[row][start_col + FP2_SQRT_CHECK_OFFSET] = F::ONE;
+        assign_u32_in_series(trace, row, start_col + FP2_SQRT_INPUT_OFFSET, &a.get_u32_slice().concat());
+        assign_u32_in_series(trace, row, start_col + FP2_SQRT_ROOT_OFFSET, &root.get_u32_slice().concat());
+        trace[row][start_col + FP2_SQRT_IS_SQUARE_OFFSET] = F::from_bool(is_square);
+        fill_trace_non_residue_multiplication(trace, &a.get_u32_slice(), row, start_col + FP2_SQRT_NON_RESIDUE_MUL_OFFSET);
+    }
+    generate_trace_fp2_mul(trace, root.get_u32_slice(), root.get_u32_slice(), start_row, end_row, start_col + FP2_SQRT_MUL_OFFSET);
+    let root_inv_input = if is_square {
+        Fp2::one()
+    } else {
+        root
+    };
+    fill_trace_fp2_inverse(trace, &root_inv_input, start_row, end_row, start_col + FP2_SQRT_ROOT_INV_OFFSET).unwrap();
+    if is_square {
+        Some(root)
+    } else {
+        None
+    }
+}
+
+/// Constraints fp2 square root.
+/// Wherever `FP2_SQRT_CHECK_OFFSET` is set, constraints the square boolean, the root with both inputs of the fp2 multiplication and the input with the input of the non residue multiplication. If the input is a square, the square of the root is constrained to be the input. Otherwise, the square of the root is constrained to be the input multiplied by the non residue, and the root is constrained with the input of the fp2 inverse. Then constraints the fp2 multiplication, non residue multiplication and fp2 inverse operations.
+pub fn add_fp2_sqrt_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+    let check = bit_selector_val * local_values[start_col + FP2_SQRT_CHECK_OFFSET];
+    let is_square = local_values[start_col + FP2_SQRT_IS_SQUARE_OFFSET];
+
+    yield_constr.constraint(check * is_square * (P::ONES - is_square));
+    for (x, y, limbs) in fp2_sqrt_links(start_col) {
+        for i in 0..limbs {
+            yield_constr.constraint(check * (local_values[x + i] - local_values[y + i]));
+        }
+    }
+    for (x, y) in fp2_sqrt_square_links(start_col) {
+        for i in 0..12 {
+            yield_constr.constraint(check * is_square * (local_values[x + i] - local_values[y + i]));
+        }
+    }
+    for (x, y) in fp2_sqrt_non_square_links(start_col) {
+        for i in 0..12 {
+            yield_constr.constraint(check * (P::ONES - is_square) * (local_values[x + i] - local_values[y + i]));
+        }
+    }
+
+    add_fp2_mul_constraints(local_values, next_values, yield_constr, start_col + FP2_SQRT_MUL_OFFSET, bit_selector);
+    add_non_residue_multiplication_constraints(local_values, yield_constr, start_col + FP2_SQRT_NON_RESIDUE_MUL_OFFSET, bit_selector);
+    add_fp2_inverse_constraints(local_values, next_values, yield_constr, start_col + FP2_SQRT_ROOT_INV_OFFSET, bit_selector);
+}
+
+pub fn add_fp2_sqrt_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let one = builder.constant_extension(F::Extension::ONE);
+    let bit_selector_val = bit_selector.unwrap_or(one);
+    let check = builder.mul_extension(bit_selector_val, local_values[start_col + FP2_SQRT_CHECK_OFFSET]);
+    let is_square = local_values[start_col + FP2_SQRT_IS_SQUARE_OFFSET];
+    let not_square = builder.sub_extension(one, is_square);
+    let check_square = builder.mul_extension(check, is_square);
+    let check_not_square = builder.mul_extension(check, not_square);
+
+    let c = builder.mul_extension(check_square, not_square);
+    yield_constr.constraint(builder, c);
+    for (x, y, limbs) in fp2_sqrt_links(start_col) {
+        for i in 0..limbs {
+            let c = builder.sub_extension(local_values[x + i], local_values[y + i]);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+    for (x, y) in fp2_sqrt_square_links(start_col) {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[x + i], local_values[y + i]);
+            let c = builder.mul_extension(check_square, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+    for (x, y) in fp2_sqrt_non_square_links(start_col) {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[x + i], local_values[y + i]);
+            let c = builder.mul_extension(check_not_square, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+
+    add_fp2_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FP2_SQRT_MUL_OFFSET, bit_selector);
+    add_non_residue_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP2_SQRT_NON_RESIDUE_MUL_OFFSET, bit_selector);
+    add_fp2_inverse_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FP2_SQRT_ROOT_INV_OFFSET, bit_selector);
+}
+
+/// Stark proving either `root^2 == a`, or `root^2 == (1 + u) * a` with `root != 0`, which certifies that `a` is not a square.
+#[derive(Clone, Copy)]
+pub struct Fp2SqrtStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> Fp2SqrtStark<F, D> {
+    /// Rows used by one fp2 square root, 12. The stark needs a power of two number of rows of at least this many.
+    pub fn required_rows() -> usize {
+        12
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(a: &Fp2, root: &Fp2, is_square: bool) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for (i, limb) in a.get_u32_slice().concat().iter().enumerate() {
+            public_inputs[PIS_INPUT_OFFSET + i] = F::from_canonical_u32(*limb);
+        }
+        for (i, limb) in root.get_u32_slice().concat().iter().enumerate() {
+            public_inputs[PIS_ROOT_OFFSET + i] = F::from_canonical_u32(*limb);
+        }
+        public_inputs[PIS_IS_SQUARE_OFFSET] = F::from_bool(is_square);
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, a: &Fp2) -> Vec<[F; TOTAL_COLUMNS]> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_fp2_sqrt(&mut trace, a, 0, 11, 0);
+        trace
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Fp2SqrtStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[FP2_SQRT_CHECK_OFFSET] - P::ONES);
+        for i in 0..24 {
+            yield_constr.constraint(
+                local_values[FP2_SQRT_CHECK_OFFSET] *
+                (local_values[FP2_SQRT_INPUT_OFFSET + i] - public_inputs[PIS_INPUT_OFFSET + i])
+            );
+            yield_constr.constraint(
+                local_values[FP2_SQRT_CHECK_OFFSET] *
+                (local_values[FP2_SQRT_ROOT_OFFSET + i] - public_inputs[PIS_ROOT_OFFSET + i])
+            );
+        }
+        yield_constr.constraint(
+            local_values[FP2_SQRT_CHECK_OFFSET] *
+            (local_values[FP2_SQRT_IS_SQUARE_OFFSET] - public_inputs[PIS_IS_SQUARE_OFFSET])
+        );
+        add_fp2_sqrt_constraints(local_values, next_values, yield_constr, 0, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[FP2_SQRT_CHECK_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for i in 0..24 {
+            let c = builder.sub_extension(local_values[FP2_SQRT_INPUT_OFFSET + i], public_inputs[PIS_INPUT_OFFSET + i]);
+            let c = builder.mul_extension(local_values[FP2_SQRT_CHECK_OFFSET], c);
+            yield_constr.constraint(builder, c);
+            let c = builder.sub_extension(local_values[FP2_SQRT_ROOT_OFFSET + i], public_inputs[PIS_ROOT_OFFSET + i]);
+            let c = builder.mul_extension(local_values[FP2_SQRT_CHECK_OFFSET], c);
+            yield_constr.constraint(builder, c);
+        }
+        let c = builder.sub_extension(local_values[FP2_SQRT_IS_SQUARE_OFFSET], public_inputs[PIS_IS_SQUARE_OFFSET]);
+        let c = builder.mul_extension(local_values[FP2_SQRT_CHECK_OFFSET], c);
+        yield_constr.constraint(builder, c);
+        add_fp2_sqrt_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{modulus, Fp, Fp2};
+
+    use super::{get_fp2_sqrt_witness, Fp2SqrtStark, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Fp2SqrtStark<F, D>;
+
+    fn prove_and_verify_sqrt(a: &Fp2) -> Option<Fp2> {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(16);
+        let (root, is_square) = get_fp2_sqrt_witness(a);
+        let public_inputs = S::public_inputs(a, &root, is_square);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(a);
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        if is_square {
+            Some(root)
+        } else {
+            None
+        }
+    }
+
+    fn test_element() -> Fp2 {
+        let seed = BigUint::parse_bytes(b"7d1e5a3c9b0f2468ace13579bdf02468ace13579bdf0246813579bdf02468ace", 16).unwrap();
+        let x0 = Fp::get_fp_from_biguint((&seed * &seed + 7u32) % modulus());
+        let x1 = Fp::get_fp_from_biguint((&seed * &seed * &seed + 11u32) % modulus());
+        Fp2([x0, x1])
+    }
+
+    #[test]
+    fn test_fp2_sqrt_quadratic_residues() {
+        assert_eq!(prove_and_verify_sqrt(&Fp2::zero()), Some(Fp2::zero()));
+        // -1 = u^2
+        let minus_one = Fp2([-Fp::one(), Fp::zero()]);
+        let root = prove_and_verify_sqrt(&minus_one).unwrap();
+        assert_eq!(root * root, minus_one);
+        let x = test_element();
+        let root = prove_and_verify_sqrt(&(x * x)).unwrap();
+        assert_eq!(root * root, x * x);
+    }
+
+    #[test]
+    fn test_fp2_sqrt_non_residues() {
+        let non_residue = Fp2([Fp::one(), Fp::one()]);
+        assert_eq!(prove_and_verify_sqrt(&non_residue), None);
+        let x = test_element();
+        assert_eq!(prove_and_verify_sqrt(&(x * x * non_residue)), None);
+    }
+}
@@ -0,0 +1,256 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use crate::native::Fp2;
+
+use crate::fp::*;
+use crate::fp2::*;
+
+// Fp4 square stark layout offsets
+/*
+    These trace offsets are for proving the fp4 square (super::native::fp4_square) on its own, i.e. the squaring used by the cyclotomic square. It needs 12 rows.
+    (a, b)^2 = (b^2 * non_residue + a^2, (a + b)^2 - a^2 - b^2)
+    FP4_SQUARE_OFFSET -> offset where the fp4 square operation (super::fp2::fill_trace_fp4_sq) is done.
+*/
+pub const FP4_SQUARE_OFFSET: usize = 0;
+pub const FP4_SQUARE_TOTAL: usize = FP4_SQUARE_OFFSET + FP4_SQ_TOTAL_COLUMNS;
+
+pub const TOTAL_COLUMNS: usize = FP4_SQUARE_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the inputs `a` and `b`, then the result `(x, y)` of the fp4 square.
+*/
+pub const PIS_A_OFFSET: usize = 0;
+pub const PIS_B_OFFSET: usize = PIS_A_OFFSET + 24;
+pub const PIS_X_OFFSET: usize = PIS_B_OFFSET + 24;
+pub const PIS_Y_OFFSET: usize = PIS_X_OFFSET + 24;
+pub const PUBLIC_INPUTS: usize = PIS_Y_OFFSET + 24;
+
+/// Columns of the fp2 multiplications of the fp4 square operation starting at `col`, i.e. a*a, b*b and (a+b)*(a+b).
+fn fp4_square_muls(col: usize) -> [usize; 3] {
+    [
+        col + FP4_SQ_T0_CALC_OFFSET,
+        col + FP4_SQ_T1_CALC_OFFSET,
+        col + FP4_SQ_T4_CALC_OFFSET,
+    ]
+}
+
+/// Column of the `j`th reduced limb series (real part for 0, imaginary part for 1) of the result of the fp4 square operation starting at `col`. `calc_offset` is `FP4_SQ_X_CALC_OFFSET + FP2_ADDITION_TOTAL` for x and `FP4_SQ_Y_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL` for y.
+fn fp4_square_res(col: usize, calc_offset: usize, j: usize) -> usize {
+    col + calc_offset + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*j + FP_SINGLE_REDUCED_OFFSET
+}
+
+#[derive(Clone, Copy)]
+pub struct Fp4SquareStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> Fp4SquareStark<F, D> {
+    /// Rows used by one fp4 squaring, 12. The stark needs a power of two number of rows of at least this many.
+    pub fn required_rows() -> usize {
+        12
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(a: &Fp2, b: &Fp2, res: &(Fp2, Fp2)) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for (offset, x) in [(PIS_A_OFFSET, a), (PIS_B_OFFSET, b), (PIS_X_OFFSET, &res.0), (PIS_Y_OFFSET, &res.1)] {
+            for (i, limb) in x.get_u32_slice().concat().iter().enumerate() {
+                public_inputs[offset + i] = F::from_canonical_u32(*limb);
+            }
+        }
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, a: &Fp2, b: &Fp2) -> Vec<[F; TOTAL_COLUMNS]> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_fp4_sq(&mut trace, a, b, 0, 11, FP4_SQUARE_OFFSET);
+        trace
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for Fp4SquareStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let sq_col = FP4_SQUARE_OFFSET;
+        yield_constr.constraint_first_row(local_values[sq_col + FP4_SQ_SELECTOR_OFFSET] - P::ONES);
+        for mul_col in fp4_square_muls(sq_col) {
+            yield_constr.constraint_first_row(local_values[mul_col + FP2_FP2_SELECTOR_OFFSET] - P::ONES);
+            for fp_mul_offset in [X_0_Y_0_MULTIPLICATION_OFFSET, X_1_Y_1_MULTIPLICATION_OFFSET, X_0_Y_1_MULTIPLICATION_OFFSET, X_1_Y_0_MULTIPLICATION_OFFSET] {
+                yield_constr.constraint_first_row(local_values[mul_col + fp_mul_offset + MULTIPLICATION_SELECTOR_OFFSET] - P::ONES);
+            }
+        }
+        for i in 0..24 {
+            yield_constr.constraint_first_row(local_values[sq_col + FP4_SQ_INPUT_X_OFFSET + i] - public_inputs[PIS_A_OFFSET + i]);
+            yield_constr.constraint_first_row(local_values[sq_col + FP4_SQ_INPUT_Y_OFFSET + i] - public_inputs[PIS_B_OFFSET + i]);
+        }
+        for j in 0..2 {
+            for i in 0..12 {
+                yield_constr.constraint_first_row(
+                    local_values[fp4_square_res(sq_col, FP4_SQ_X_CALC_OFFSET + FP2_ADDITION_TOTAL, j) + i] -
+                    public_inputs[PIS_X_OFFSET + j*12 + i]
+                );
+                yield_constr.constraint_first_row(
+                    local_values[fp4_square_res(sq_col, FP4_SQ_Y_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL, j) + i] -
+                    public_inputs[PIS_Y_OFFSET + j*12 + i]
+                );
+            }
+        }
+
+        add_fp4_sq_constraints(local_values, next_values, yield_constr, sq_col, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let sq_col = FP4_SQUARE_OFFSET;
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[sq_col + FP4_SQ_SELECTOR_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for mul_col in fp4_square_muls(sq_col) {
+            let c = builder.sub_extension(local_values[mul_col + FP2_FP2_SELECTOR_OFFSET], one);
+            yield_constr.constraint_first_row(builder, c);
+            for fp_mul_offset in [X_0_Y_0_MULTIPLICATION_OFFSET, X_1_Y_1_MULTIPLICATION_OFFSET, X_0_Y_1_MULTIPLICATION_OFFSET, X_1_Y_0_MULTIPLICATION_OFFSET] {
+                let c = builder.sub_extension(local_values[mul_col + fp_mul_offset + MULTIPLICATION_SELECTOR_OFFSET], one);
+                yield_constr.constraint_first_row(builder, c);
+            }
+        }
+        for i in 0..24 {
+            let c = builder.sub_extension(local_values[sq_col + FP4_SQ_INPUT_X_OFFSET + i], public_inputs[PIS_A_OFFSET + i]);
+            yield_constr.constraint_first_row(builder, c);
+            let c = builder.sub_extension(local_values[sq_col + FP4_SQ_INPUT_Y_OFFSET + i], public_inputs[PIS_B_OFFSET + i]);
+            yield_constr.constraint_first_row(builder, c);
+        }
+        for j in 0..2 {
+            for i in 0..12 {
+                let c = builder.sub_extension(
+                    local_values[fp4_square_res(sq_col, FP4_SQ_X_CALC_OFFSET + FP2_ADDITION_TOTAL, j) + i],
+                    public_inputs[PIS_X_OFFSET + j*12 + i],
+                );
+                yield_constr.constraint_first_row(builder, c);
+                let c = builder.sub_extension(
+                    local_values[fp4_square_res(sq_col, FP4_SQ_Y_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL, j) + i],
+                    public_inputs[PIS_Y_OFFSET + j*12 + i],
+                );
+                yield_constr.constraint_first_row(builder, c);
+            }
+        }
+
+        add_fp4_sq_constraints_ext_circuit(builder, yield_constr, local_values, next_values, sq_col, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{fp4_square, Fp, Fp2};
+
+    use super::{Fp4SquareStark, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = Fp4SquareStark<F, D>;
+
+    fn prove_and_verify_fp4_square(a: &Fp2, b: &Fp2) -> (Fp2, Fp2) {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(16);
+        let res = fp4_square(*a, *b);
+        let public_inputs = S::public_inputs(a, b, &res);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(a, b);
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        res
+    }
+
+    #[test]
+    fn test_fp4_square() {
+        let a = Fp2([
+            Fp::get_fp_from_biguint(BigUint::parse_bytes(b"17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb", 16).unwrap()),
+            Fp::get_fp_from_biguint(BigUint::parse_bytes(b"08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1", 16).unwrap()),
+        ]);
+        let b = a * a;
+        let (x, y) = prove_and_verify_fp4_square(&a, &b);
+        // (a + b*w)^2 = (a^2 + b^2 * non_residue) + 2ab*w
+        assert_eq!(x, a * a + (b * b).mul_by_nonresidue());
+        assert_eq!(y, a * b + a * b);
+    }
+
+    #[cfg(feature = "test-utils")]
+    #[test]
+    fn test_fp4_square_random() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        use crate::test_utils::random_fp2;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..3 {
+            let a = random_fp2(&mut rng);
+            let b = random_fp2(&mut rng);
+            assert_eq!(prove_and_verify_fp4_square(&a, &b), fp4_square(a, b));
+        }
+    }
+}
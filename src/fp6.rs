@@ -2,7 +2,7 @@
 use num_bigint::BigUint;
 use plonky2::{field::{extension::{Extendable, FieldExtension}, packed::PackedField, types::Field}, hash::hash_types::RichField, iop::ext_target::ExtensionTarget, plonk::circuit_builder::CircuitBuilder};
 use starky::constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer};
-use crate::{native::{get_u32_vec_from_literal, modulus, Fp2, Fp6}, utils::*, fp::*, fp2::*};
+use crate::{constants::{FP6_FORBENIUS_COEFFICIENTS_1, FP6_FORBENIUS_COEFFICIENTS_2}, native::{get_u32_vec_from_literal, modulus, negate_fp, Fp2, Fp6}, utils::*, fp::*, fp2::*};
 
 // FP6 multiplication offsets
 /*
@@ -191,7 +191,8 @@ pub fn fill_trace_negate_fp6<F: RichField + Extendable<D>,
     row: usize,
     start_col: usize
 ) {
-    fill_trace_addition_fp6(trace, &x.get_u32_slice(), &(-(*x)).get_u32_slice(), row, start_col);
+    let minus_x = x.0.map(|e| negate_fp(e).0);
+    fill_trace_addition_fp6(trace, &x.get_u32_slice(), &minus_x, row, start_col);
 }
 
 /// Fills trace of [mul_by_nonresidue](super::native::mul_by_nonresidue) function. Input is 12*6 limbs. Needs 1 row.
@@ -426,8 +427,8 @@ pub fn fill_trace_fp6_forbenius_map<F: RichField + Extendable<D>,
     let c0 = Fp2(x.0[0..2].to_vec().try_into().unwrap());
     let c1 = Fp2(x.0[2..4].to_vec().try_into().unwrap());
     let c2 = Fp2(x.0[4..6].to_vec().try_into().unwrap());
-    let forbenius_coefficients_1 = Fp6::forbenius_coefficients_1();
-    let forbenius_coefficients_2 = Fp6::forbenius_coefficients_2();
+    let forbenius_coefficients_1 = *FP6_FORBENIUS_COEFFICIENTS_1;
+    let forbenius_coefficients_2 = *FP6_FORBENIUS_COEFFICIENTS_2;
     let _x = c0.forbenius_map(pow);
     fill_trace_fp2_forbenius_map(trace, &c0, pow, start_row, end_row, start_col + FP6_FORBENIUS_MAP_X_CALC_OFFSET);
     let t0 = c1.forbenius_map(pow);
@@ -842,20 +843,54 @@ pub fn add_non_residue_multiplication_fp6_constraints<F: RichField + Extendable<
 ) where
     FE: FieldExtension<D2, BaseField = F>,
     P: PackedField<Scalar = FE>,
+{
+    add_mul_by_nonresidue_constraints(local_values, yield_constr, start_col + FP6_NON_RESIDUE_MUL_C2, [start_col + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + 48, start_col + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + 60], bit_selector);
+}
+
+pub fn add_non_residue_multiplication_fp6_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+){
+    add_mul_by_nonresidue_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP6_NON_RESIDUE_MUL_C2, [start_col + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + 48, start_col + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + 60], bit_selector);
+}
+
+/// Constraints [mul_by_nonresidue](super::native::mul_by_nonresidue) function along with its input. `input_cols` are the starting columns of the six Fp elements (12 limbs each) of the value multiplied by the non residue, which are constrained to be equal to the input of the gadget at `start_col`.
+pub fn add_mul_by_nonresidue_fp6_constraints<F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+    >(
+    local_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    input_cols: [usize; 6],
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
 {
     let bit_selector_val = bit_selector.unwrap_or(P::ONES);
 
-    for i in 0..24 {
-        yield_constr.constraint(
-            bit_selector_val *
-            local_values[start_col + FP6_NON_RESIDUE_MUL_CHECK_OFFSET] *
-            (local_values[start_col + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + i + 48] - local_values[start_col + FP6_NON_RESIDUE_MUL_C2 + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i])
-        );
+    for (j, input_col) in input_cols.iter().enumerate() {
+        for i in 0..12 {
+            yield_constr.constraint(
+                bit_selector_val *
+                local_values[start_col + FP6_NON_RESIDUE_MUL_CHECK_OFFSET] *
+                (local_values[start_col + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + j*12 + i] - local_values[input_col + i])
+            );
+        }
     }
-    add_non_residue_multiplication_constraints(local_values, yield_constr, start_col + FP6_NON_RESIDUE_MUL_C2, bit_selector);
+    add_non_residue_multiplication_fp6_constraints(local_values, yield_constr, start_col, bit_selector);
 }
 
-pub fn add_non_residue_multiplication_fp6_constraints_ext_circuit<
+pub fn add_mul_by_nonresidue_fp6_constraints_ext_circuit<
     F: RichField + Extendable<D>,
     const D: usize,
 >(
@@ -863,17 +898,20 @@ pub fn add_non_residue_multiplication_fp6_constraints_ext_circuit<
     yield_constr: &mut RecursiveConstraintConsumer<F, D>,
     local_values: &[ExtensionTarget<D>],
     start_col: usize,
+    input_cols: [usize; 6],
     bit_selector: Option<ExtensionTarget<D>>,
 ){
     let bit_selector_val = bit_selector.unwrap_or(builder.constant_extension(F::Extension::ONE));
 
-    for i in 0..24 {
-        let sub_tmp = builder.sub_extension(local_values[start_col + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + i + 48] , local_values[start_col + FP6_NON_RESIDUE_MUL_C2 + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i]);
-        let c = builder.mul_extension(sub_tmp, local_values[start_col + FP6_NON_RESIDUE_MUL_CHECK_OFFSET]);
-        let c = builder.mul_extension(bit_selector_val, c);
-        yield_constr.constraint(builder,c);
+    let tmp = builder.mul_extension(bit_selector_val, local_values[start_col + FP6_NON_RESIDUE_MUL_CHECK_OFFSET]);
+    for (j, input_col) in input_cols.iter().enumerate() {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[start_col + FP6_NON_RESIDUE_MUL_INPUT_OFFSET + j*12 + i], local_values[input_col + i]);
+            let c = builder.mul_extension(tmp, c);
+            yield_constr.constraint(builder, c);
+        }
     }
-    add_non_residue_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP6_NON_RESIDUE_MUL_C2, bit_selector);
+    add_non_residue_multiplication_fp6_constraints_ext_circuit(builder, yield_constr, local_values, start_col, bit_selector);
 }
 
 /// Constraints fp6 multiplication.
@@ -1116,23 +1154,7 @@ pub fn add_fp6_multiplication_constraints<F: RichField + Extendable<D>,
     add_subtraction_with_reduction_constranints(local_values, yield_constr, start_col + FP6_MUL_T7_CALC_OFFSET, bit_selector);
 
     // T8
-    for i in 0..12 {
-        yield_constr.constraint(
-            bit_selector_val *
-            local_values[start_col + FP6_MUL_T8_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET] *
-            (
-                local_values[start_col + FP6_MUL_T8_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i] - local_values[start_col + FP6_MUL_T7_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + FP_SINGLE_REDUCED_OFFSET + i]
-            )
-        );
-        yield_constr.constraint(
-            bit_selector_val *
-            local_values[start_col + FP6_MUL_T8_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET] *
-            (
-                local_values[start_col + FP6_MUL_T8_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i + 12] - local_values[start_col + FP6_MUL_T7_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL + FP_SINGLE_REDUCED_OFFSET + i]
-            )
-        );
-    }
-    add_non_residue_multiplication_constraints(local_values, yield_constr, start_col + FP6_MUL_T8_CALC_OFFSET, bit_selector);
+    add_mul_by_nonresidue_constraints(local_values, yield_constr, start_col + FP6_MUL_T8_CALC_OFFSET, [start_col + FP6_MUL_T7_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + FP_SINGLE_REDUCED_OFFSET, start_col + FP6_MUL_T7_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL + FP_SINGLE_REDUCED_OFFSET], bit_selector);
 
     // X calc offset
     for i in 0..12 {
@@ -1330,23 +1352,7 @@ pub fn add_fp6_multiplication_constraints<F: RichField + Extendable<D>,
     add_subtraction_with_reduction_constranints(local_values, yield_constr, start_col + FP6_MUL_T13_CALC_OFFSET, bit_selector);
 
     // T14
-    for i in 0..12 {
-        yield_constr.constraint(
-            bit_selector_val *
-            local_values[start_col + FP6_MUL_T14_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET] *
-            (
-                local_values[start_col + FP6_MUL_T14_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i] - local_values[start_col + FP6_MUL_T2_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET + i]
-            )
-        );
-        yield_constr.constraint(
-            bit_selector_val *
-            local_values[start_col + FP6_MUL_T14_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET] *
-            (
-                local_values[start_col + FP6_MUL_T14_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i + 12] - local_values[start_col + FP6_MUL_T2_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET + i]
-            )
-        );
-    }
-    add_non_residue_multiplication_constraints(local_values, yield_constr, start_col + FP6_MUL_T14_CALC_OFFSET, bit_selector);
+    add_mul_by_nonresidue_constraints(local_values, yield_constr, start_col + FP6_MUL_T14_CALC_OFFSET, [start_col + FP6_MUL_T2_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET, start_col + FP6_MUL_T2_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET], bit_selector);
 
     // Y calc offset
     for i in 0..12 {
@@ -1768,20 +1774,7 @@ pub fn add_fp6_multiplication_constraints_ext_circuit<
     add_subtraction_with_reduction_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP6_MUL_T7_CALC_OFFSET, bit_selector);
 
     // T8 
-    for i in 0..12 {
-        let mul_tmp = local_values[start_col + FP6_MUL_T8_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET];
-
-        let sub_tmp1 = builder.sub_extension(local_values[start_col + FP6_MUL_T8_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i] , local_values[start_col + FP6_MUL_T7_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + FP_SINGLE_REDUCED_OFFSET + i]);
-        let c1 = builder.mul_extension(sub_tmp1, mul_tmp);
-        let c = builder.mul_extension(bit_selector_val, c1);
-        yield_constr.constraint(builder,c);
-
-        let sub_tmp2 = builder.sub_extension(local_values[start_col + FP6_MUL_T8_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i + 12] , local_values[start_col + FP6_MUL_T7_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL + FP_SINGLE_REDUCED_OFFSET + i]);
-        let c2 = builder.mul_extension(sub_tmp2, mul_tmp);
-        let c = builder.mul_extension(bit_selector_val, c2);
-        yield_constr.constraint(builder,c);
-    }
-    add_non_residue_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP6_MUL_T8_CALC_OFFSET, bit_selector);
+    add_mul_by_nonresidue_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP6_MUL_T8_CALC_OFFSET, [start_col + FP6_MUL_T7_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + FP_SINGLE_REDUCED_OFFSET, start_col + FP6_MUL_T7_CALC_OFFSET + FP2_ADDITION_TOTAL + FP2_SUBTRACTION_TOTAL + FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL + FP_SINGLE_REDUCED_OFFSET], bit_selector);
 
     // X calc offset
     for i in 0..12 {
@@ -1930,20 +1923,7 @@ pub fn add_fp6_multiplication_constraints_ext_circuit<
     add_subtraction_with_reduction_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP6_MUL_T13_CALC_OFFSET, bit_selector);
 
     // T14
-    for i in 0..12 {
-        let mul_tmp = local_values[start_col + FP6_MUL_T14_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET];
-
-        let sub_tmp1 = builder.sub_extension(local_values[start_col + FP6_MUL_T14_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i] , local_values[start_col + FP6_MUL_T2_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET + i]);
-        let c1 = builder.mul_extension(sub_tmp1, mul_tmp);
-        let c = builder.mul_extension(bit_selector_val, c1);
-        yield_constr.constraint(builder,c);
-
-        let sub_tmp2 = builder.sub_extension(local_values[start_col + FP6_MUL_T14_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i + 12] , local_values[start_col + FP6_MUL_T2_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET + i]);
-        let c2 = builder.mul_extension(sub_tmp2, mul_tmp);
-        let c = builder.mul_extension(bit_selector_val, c2);
-        yield_constr.constraint(builder,c);
-    }
-    add_non_residue_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP6_MUL_T14_CALC_OFFSET, bit_selector);
+    add_mul_by_nonresidue_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP6_MUL_T14_CALC_OFFSET, [start_col + FP6_MUL_T2_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET, start_col + FP6_MUL_T2_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET], bit_selector);
 
     // Y calc offset
     for i in 0..12 {
@@ -2167,21 +2147,7 @@ pub fn add_multiply_by_1_constraints<F: RichField + Extendable<D>,
     add_fp2_mul_constraints(local_values, next_values, yield_constr, start_col + MULTIPLY_BY_1_T0_CALC_OFFSET, bit_selector);
 
     // X
-    for i in 0..12 {
-        yield_constr.constraint(
-            bit_selector_val *
-            local_values[start_col + MULTIPLY_BY_1_X_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET] *
-            (local_values[start_col + MULTIPLY_BY_1_X_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i] -
-            local_values[start_col + MULTIPLY_BY_1_T0_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET + i])
-        );
-        yield_constr.constraint(
-            bit_selector_val *
-            local_values[start_col + MULTIPLY_BY_1_X_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET] *
-            (local_values[start_col + MULTIPLY_BY_1_X_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i + 12] -
-            local_values[start_col + MULTIPLY_BY_1_T0_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET + i])
-        );
-    }
-    add_non_residue_multiplication_constraints(local_values, yield_constr, start_col + MULTIPLY_BY_1_X_CALC_OFFSET, bit_selector);
+    add_mul_by_nonresidue_constraints(local_values, yield_constr, start_col + MULTIPLY_BY_1_X_CALC_OFFSET, [start_col + MULTIPLY_BY_1_T0_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET, start_col + MULTIPLY_BY_1_T0_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET], bit_selector);
 
     // Y
     for i in 0..24 {
@@ -2259,20 +2225,7 @@ pub fn add_multiply_by_1_constraints_ext_circuit<
     add_fp2_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + MULTIPLY_BY_1_T0_CALC_OFFSET, bit_selector);
     
     // X
-    for i in 0..12 {
-        let mul_tmp = local_values[start_col + MULTIPLY_BY_1_X_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET];
-
-        let sub_tmp1 = builder.sub_extension(local_values[start_col + MULTIPLY_BY_1_X_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i] , local_values[start_col + MULTIPLY_BY_1_T0_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET + i]);
-        let c1 = builder.mul_extension(sub_tmp1, mul_tmp);
-        let c = builder.mul_extension(bit_selector_val, c1);
-        yield_constr.constraint(builder,c);
-
-        let sub_tmp2 = builder.sub_extension(local_values[start_col + MULTIPLY_BY_1_X_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i + 12] , local_values[start_col + MULTIPLY_BY_1_T0_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET + i]);
-        let c2 = builder.mul_extension(sub_tmp2, mul_tmp);
-        let c = builder.mul_extension(bit_selector_val, c2);
-        yield_constr.constraint(builder,c);
-    }
-    add_non_residue_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, start_col + MULTIPLY_BY_1_X_CALC_OFFSET, bit_selector);
+    add_mul_by_nonresidue_constraints_ext_circuit(builder, yield_constr, local_values, start_col + MULTIPLY_BY_1_X_CALC_OFFSET, [start_col + MULTIPLY_BY_1_T0_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET, start_col + MULTIPLY_BY_1_T0_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET], bit_selector);
 
     // Y
     for i in 0..24{
@@ -2394,21 +2347,7 @@ pub fn add_multiply_by_01_constraints<F: RichField + Extendable<D>,
     add_fp2_mul_constraints(local_values, next_values, yield_constr, start_col + MULTIPLY_BY_01_T2_CALC_OFFSET, bit_selector);
 
     // T3
-    for i in 0..12 {
-        yield_constr.constraint(
-            bit_selector_val *
-            local_values[start_col + MULTIPLY_BY_01_T3_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET] *
-            (local_values[start_col + MULTIPLY_BY_01_T3_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i] -
-            local_values[start_col + MULTIPLY_BY_01_T2_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET + i])
-        );
-        yield_constr.constraint(
-            bit_selector_val *
-            local_values[start_col + MULTIPLY_BY_01_T3_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET] *
-            (local_values[start_col + MULTIPLY_BY_01_T3_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i + 12] -
-            local_values[start_col + MULTIPLY_BY_01_T2_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET + i])
-        );
-    }
-    add_non_residue_multiplication_constraints(local_values, yield_constr, start_col + MULTIPLY_BY_01_T3_CALC_OFFSET, bit_selector);
+    add_mul_by_nonresidue_constraints(local_values, yield_constr, start_col + MULTIPLY_BY_01_T3_CALC_OFFSET, [start_col + MULTIPLY_BY_01_T2_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET, start_col + MULTIPLY_BY_01_T2_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET], bit_selector);
 
     // X
     for i in 0..12 {
@@ -2732,20 +2671,7 @@ pub fn add_multiply_by_01_constraints_ext_circuit<
     add_fp2_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + MULTIPLY_BY_01_T2_CALC_OFFSET, bit_selector);
 
     // T3
-    for i in 0..12 {
-        let mul_tmp = local_values[start_col + MULTIPLY_BY_01_T3_CALC_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET];
-
-        let sub_tmp1 = builder.sub_extension(local_values[start_col + MULTIPLY_BY_01_T3_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i] , local_values[start_col + MULTIPLY_BY_01_T2_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET + i]);
-        let c1 = builder.mul_extension(sub_tmp1, mul_tmp);
-        let c = builder.mul_extension(bit_selector_val, c1);
-        yield_constr.constraint(builder,c);
-
-        let sub_tmp2 = builder.sub_extension(local_values[start_col + MULTIPLY_BY_01_T3_CALC_OFFSET + FP2_NON_RESIDUE_MUL_INPUT_OFFSET + i + 12] , local_values[start_col + MULTIPLY_BY_01_T2_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET + i]);
-        let c2 = builder.mul_extension(sub_tmp2, mul_tmp);
-        let c = builder.mul_extension(bit_selector_val, c2);
-        yield_constr.constraint(builder,c);
-    }
-    add_non_residue_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, start_col + MULTIPLY_BY_01_T3_CALC_OFFSET, bit_selector);
+    add_mul_by_nonresidue_constraints_ext_circuit(builder, yield_constr, local_values, start_col + MULTIPLY_BY_01_T3_CALC_OFFSET, [start_col + MULTIPLY_BY_01_T2_CALC_OFFSET + Z1_REDUCE_OFFSET + REDUCED_OFFSET, start_col + MULTIPLY_BY_01_T2_CALC_OFFSET + Z2_REDUCE_OFFSET + REDUCED_OFFSET], bit_selector);
 
 
     // X
@@ -2985,8 +2911,8 @@ pub fn add_fp6_forbenius_map_constraints<F: RichField + Extendable<D>,
         bit2 * FE::from_canonical_usize(4) -
         local_values[start_col + FP6_FORBENIUS_MAP_REM_OFFSET])
     );
-    let forbenius_coefficients_1 = Fp6::forbenius_coefficients_1().iter().map(|fp2| fp2.get_u32_slice().concat().try_into().unwrap()).collect::<Vec<[u32; 24]>>();
-    let forbenius_coefficients_2 = Fp6::forbenius_coefficients_2().iter().map(|fp2| fp2.get_u32_slice().concat().try_into().unwrap()).collect::<Vec<[u32; 24]>>();
+    let forbenius_coefficients_1 = FP6_FORBENIUS_COEFFICIENTS_1.iter().map(|fp2| fp2.get_u32_slice().concat().try_into().unwrap()).collect::<Vec<[u32; 24]>>();
+    let forbenius_coefficients_2 = FP6_FORBENIUS_COEFFICIENTS_2.iter().map(|fp2| fp2.get_u32_slice().concat().try_into().unwrap()).collect::<Vec<[u32; 24]>>();
     let y1 = (0..24).map(|i|
         (P::ONES - bit0) * (P::ONES - bit1) * FE::from_canonical_u32(forbenius_coefficients_1[0][i]) +
         (bit0) * (P::ONES - bit1) * FE::from_canonical_u32(forbenius_coefficients_1[1][i]) +
@@ -3152,8 +3078,8 @@ pub fn add_fp6_forbenius_map_constraints_ext_circuit<F: RichField + Extendable<D
     let c = builder.mul_extension(tmp, c);
     yield_constr.constraint(builder, c);
 
-    let forbenius_coefficients_1 = Fp6::forbenius_coefficients_1().iter().map(|fp2| fp2.get_u32_slice().concat().try_into().unwrap()).collect::<Vec<[u32; 24]>>();
-    let forbenius_coefficients_2 = Fp6::forbenius_coefficients_2().iter().map(|fp2| fp2.get_u32_slice().concat().try_into().unwrap()).collect::<Vec<[u32; 24]>>();
+    let forbenius_coefficients_1 = FP6_FORBENIUS_COEFFICIENTS_1.iter().map(|fp2| fp2.get_u32_slice().concat().try_into().unwrap()).collect::<Vec<[u32; 24]>>();
+    let forbenius_coefficients_2 = FP6_FORBENIUS_COEFFICIENTS_2.iter().map(|fp2| fp2.get_u32_slice().concat().try_into().unwrap()).collect::<Vec<[u32; 24]>>();
     let y1 = (0..24).map(|i| {
         let const1 = builder.constant_extension(F::Extension::from_canonical_u32(forbenius_coefficients_1[0][i]));
         let const2 = builder.constant_extension(F::Extension::from_canonical_u32(forbenius_coefficients_1[1][i]));
@@ -3276,3 +3202,177 @@ pub fn add_fp6_forbenius_map_constraints_ext_circuit<F: RichField + Extendable<D
     }
     add_fp2_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FP6_FORBENIUS_MAP_Z_CALC_OFFSET, bit_selector);
 }
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{
+        field::{
+            extension::{Extendable, FieldExtension},
+            packed::PackedField,
+            types::Field,
+        },
+        hash::hash_types::RichField,
+        iop::ext_target::ExtensionTarget,
+        plonk::{circuit_builder::CircuitBuilder, config::{GenericConfig, PoseidonGoldilocksConfig}},
+        util::timing::TimingTree,
+    };
+    use starky::{
+        config::StarkConfig,
+        constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+        evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+        prover::prove,
+        stark::Stark,
+        util::trace_rows_to_poly_values,
+        verifier::verify_stark_proof,
+    };
+
+    use crate::native::{mul_by_nonresidue, Fp, Fp2, Fp6};
+
+    use super::*;
+
+    /*
+        Layout of the test stark. The fp2 and fp6 values to multiply by the non residue are assigned in their own columns, and linked to the gadgets through `input_cols`. Needs 1 row.
+        The public inputs are the fp2 result, followed by the fp6 result.
+    */
+    const FP2_INPUT_OFFSET: usize = 0;
+    const FP2_GADGET_OFFSET: usize = FP2_INPUT_OFFSET + 24;
+    const FP6_INPUT_OFFSET: usize = FP2_GADGET_OFFSET + FP2_NON_RESIDUE_MUL_TOTAL;
+    const FP6_GADGET_OFFSET: usize = FP6_INPUT_OFFSET + 24*3;
+    const TOTAL_COLUMNS: usize = FP6_GADGET_OFFSET + FP6_NON_RESIDUE_MUL_TOTAL;
+    const PUBLIC_INPUTS: usize = 24 + 24*3;
+
+    /// Columns of the 12 limb series of the results of the fp2 and fp6 gadgets, in the order of the public inputs.
+    fn res_cols() -> [usize; 8] {
+        let fp2_res = |col: usize| [
+            col + FP2_NON_RESIDUE_MUL_Z0_REDUCE_OFFSET + FP_SINGLE_REDUCED_OFFSET,
+            col + FP2_NON_RESIDUE_MUL_Z1_REDUCE_OFFSET + FP_SINGLE_REDUCED_OFFSET,
+        ];
+        let [x0, x1] = fp2_res(FP2_GADGET_OFFSET);
+        let [y0, y1] = fp2_res(FP6_GADGET_OFFSET + FP6_NON_RESIDUE_MUL_C2);
+        let y_input = FP6_GADGET_OFFSET + FP6_NON_RESIDUE_MUL_INPUT_OFFSET;
+        [x0, x1, y0, y1, y_input, y_input + 12, y_input + 24, y_input + 36]
+    }
+
+    /// Stark containing only the fp2 and fp6 non residue multiplication gadgets.
+    #[derive(Clone, Copy)]
+    struct MulByNonresidueStark<F: RichField + Extendable<D>, const D: usize> {
+        num_rows: usize,
+        _f: std::marker::PhantomData<F>,
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> MulByNonresidueStark<F, D> {
+        fn generate_trace(&self, x: &Fp2, y: &Fp6) -> Vec<[F; TOTAL_COLUMNS]> {
+            let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+            assign_u32_in_series(&mut trace, 0, FP2_INPUT_OFFSET, &x.get_u32_slice().concat());
+            fill_trace_non_residue_multiplication(&mut trace, &x.get_u32_slice(), 0, FP2_GADGET_OFFSET);
+            assign_u32_in_series(&mut trace, 0, FP6_INPUT_OFFSET, &y.get_u32_slice().concat());
+            fill_trace_non_residue_multiplication_fp6(&mut trace, y, 0, FP6_GADGET_OFFSET);
+            trace
+        }
+    }
+
+    impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for MulByNonresidueStark<F, D> {
+        type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, TOTAL_COLUMNS, PUBLIC_INPUTS>
+        where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>;
+
+        fn eval_packed_generic<FE, P, const D2: usize>(
+            &self,
+            vars: &Self::EvaluationFrame<FE, P, D2>,
+            yield_constr: &mut ConstraintConsumer<P>,
+        ) where
+            FE: FieldExtension<D2, BaseField = F>,
+            P: PackedField<Scalar = FE>,
+        {
+            let local_values = vars.get_local_values();
+            let public_inputs = vars.get_public_inputs();
+
+            yield_constr.constraint_first_row(local_values[FP2_GADGET_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET] - P::ONES);
+            yield_constr.constraint_first_row(local_values[FP6_GADGET_OFFSET + FP6_NON_RESIDUE_MUL_CHECK_OFFSET] - P::ONES);
+            for (j, col) in res_cols().iter().enumerate() {
+                for i in 0..12 {
+                    yield_constr.constraint_first_row(local_values[col + i] - public_inputs[j*12 + i]);
+                }
+            }
+
+            add_mul_by_nonresidue_constraints(local_values, yield_constr, FP2_GADGET_OFFSET, [FP2_INPUT_OFFSET, FP2_INPUT_OFFSET + 12], None);
+            add_mul_by_nonresidue_fp6_constraints(local_values, yield_constr, FP6_GADGET_OFFSET, std::array::from_fn(|i| FP6_INPUT_OFFSET + i*12), None);
+        }
+
+        type EvaluationFrameTarget =
+            StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, TOTAL_COLUMNS, PUBLIC_INPUTS>;
+
+        fn eval_ext_circuit(
+            &self,
+            builder: &mut CircuitBuilder<F, D>,
+            vars: &Self::EvaluationFrameTarget,
+            yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+        ) {
+            let local_values = vars.get_local_values();
+            let public_inputs = vars.get_public_inputs();
+
+            let one = builder.constant_extension(F::Extension::ONE);
+            let c = builder.sub_extension(local_values[FP2_GADGET_OFFSET + FP2_NON_RESIDUE_MUL_CHECK_OFFSET], one);
+            yield_constr.constraint_first_row(builder, c);
+            let c = builder.sub_extension(local_values[FP6_GADGET_OFFSET + FP6_NON_RESIDUE_MUL_CHECK_OFFSET], one);
+            yield_constr.constraint_first_row(builder, c);
+            for (j, col) in res_cols().iter().enumerate() {
+                for i in 0..12 {
+                    let c = builder.sub_extension(local_values[col + i], public_inputs[j*12 + i]);
+                    yield_constr.constraint_first_row(builder, c);
+                }
+            }
+
+            add_mul_by_nonresidue_constraints_ext_circuit(builder, yield_constr, local_values, FP2_GADGET_OFFSET, [FP2_INPUT_OFFSET, FP2_INPUT_OFFSET + 12], None);
+            add_mul_by_nonresidue_fp6_constraints_ext_circuit(builder, yield_constr, local_values, FP6_GADGET_OFFSET, std::array::from_fn(|i| FP6_INPUT_OFFSET + i*12), None);
+        }
+
+        fn constraint_degree(&self) -> usize {
+            3
+        }
+    }
+
+    #[test]
+    fn test_mul_by_nonresidue_gadgets() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = MulByNonresidueStark<F, D>;
+
+        let x = Fp2([
+            Fp::get_fp_from_biguint(BigUint::parse_bytes(b"17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb", 16).unwrap()),
+            Fp::get_fp_from_biguint(BigUint::parse_bytes(b"08b3f481e3aaa0f1a09e30ed741d8ae4fcf5e095d5d00af600db18cb2c04b3edd03cc744a2888ae40caa232946c5e7e1", 16).unwrap()),
+        ]);
+        let x_sq = x * x;
+        let x_cube = x_sq * x;
+        let y = Fp6([x.0[0], x.0[1], x_sq.0[0], x_sq.0[1], x_cube.0[0], x_cube.0[1]]);
+
+        let x_res = x.mul_by_nonresidue();
+        let y_res = mul_by_nonresidue(y.0);
+        let public_inputs: Vec<F> = [x_res.get_u32_slice().concat(), y_res.get_u32_slice().concat()].concat()
+            .into_iter()
+            .map(F::from_canonical_u32)
+            .collect();
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S { num_rows: 16, _f: std::marker::PhantomData };
+        let trace = stark.generate_trace(&x, &y);
+        for (j, col) in res_cols().iter().enumerate() {
+            for i in 0..12 {
+                assert_eq!(trace[0][col + i], public_inputs[j*12 + i]);
+            }
+        }
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+    }
+}
@@ -0,0 +1,306 @@
+use anyhow::{bail, Result};
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use crate::native::Fp;
+
+use crate::fp::*;
+use crate::utils::*;
+
+// Fp inverse layout offsets
+/*
+    These trace offsets are for proving the inverse of an fp element (a * a_inv == 1 mod p). It needs 12 rows.
+    FP_INV_CHECK_OFFSET -> Selector set 1 in all rows of the operation, where the input and the inverse are constrained with the multiplication.
+    FP_INV_INPUT_OFFSET -> offset where the input `a` is set.
+    FP_INV_INVERSE_OFFSET -> offset where the inverse `a_inv` is set.
+    FP_INV_MUL_OFFSET -> offset where the fp multiplication modulo p of `a` and `a_inv` is done. The reduced (and range checked) result is constrained to be exactly 1, which also rejects `a == 0`.
+*/
+pub const FP_INV_CHECK_OFFSET: usize = 0;
+pub const FP_INV_INPUT_OFFSET: usize = FP_INV_CHECK_OFFSET + 1;
+pub const FP_INV_INVERSE_OFFSET: usize = FP_INV_INPUT_OFFSET + 12;
+pub const FP_INV_MUL_OFFSET: usize = FP_INV_INVERSE_OFFSET + 12;
+pub const FP_INV_TOTAL: usize = FP_INV_MUL_OFFSET + FP_MUL_MOD_TOTAL;
+
+pub const TOTAL_COLUMNS: usize = FP_INV_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the input `a` followed by its inverse `a_inv`.
+*/
+pub const PIS_INPUT_OFFSET: usize = 0;
+pub const PIS_INVERSE_OFFSET: usize = PIS_INPUT_OFFSET + 12;
+pub const PUBLIC_INPUTS: usize = PIS_INVERSE_OFFSET + 12;
+
+/// Fills the stark trace of fp inverse. Input is an fp element, returns an error if it is zero. Needs 12 rows. Sets the input and its inverse in all rows of the operation and fills the multiplication of the input with its inverse. Returns the inverse.
+pub fn fill_trace_fp_inverse<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, a: &Fp, start_row: usize, end_row: usize, start_col: usize) -> Result<Fp> {
+    if *a == Fp::zero() {
+        bail!("fill_trace_fp_inverse: inverse of zero Fp");
+    }
+    let a_inv = a.invert();
+    for row in start_row..end_row + 1 {
+        trace[row][start_col + FP_INV_CHECK_OFFSET] = F::ONE;
+        assign_u32_in_series(trace, row, start_col + FP_INV_INPUT_OFFSET, &a.0);
+        assign_u32_in_series(trace, row, start_col + FP_INV_INVERSE_OFFSET, &a_inv.0);
+    }
+    let res = fill_trace_fp_mul_mod(trace, &a.0, &a_inv.0, start_row, end_row, start_col + FP_INV_MUL_OFFSET);
+    assert_eq!(res, Fp::one().0);
+    Ok(a_inv)
+}
+
+/// Constraints fp inverse.
+/// Constraints the input and the inverse with the inputs of the multiplication, and the reduced result of the multiplication to be 1, wherever `FP_INV_CHECK_OFFSET` is set. Then constraints the multiplication modulo p.
+pub fn add_fp_inverse_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+    let check = bit_selector_val * local_values[start_col + FP_INV_CHECK_OFFSET];
+    let mul_col = start_col + FP_INV_MUL_OFFSET;
+
+    for i in 0..12 {
+        yield_constr.constraint(
+            check *
+            (local_values[fp_mul_mod_x(mul_col) + i] - local_values[start_col + FP_INV_INPUT_OFFSET + i])
+        );
+        yield_constr.constraint(
+            check *
+            (local_values[fp_mul_mod_y(mul_col) + i] - local_values[start_col + FP_INV_INVERSE_OFFSET + i])
+        );
+        let one = if i == 0 {
+            P::ONES
+        } else {
+            P::ZEROS
+        };
+        yield_constr.constraint(
+            check *
+            (local_values[fp_mul_mod_res(mul_col) + i] - one)
+        );
+    }
+    add_fp_mul_mod_constraints(local_values, next_values, yield_constr, mul_col, bit_selector);
+}
+
+pub fn add_fp_inverse_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let one = builder.constant_extension(F::Extension::ONE);
+    let bit_selector_val = bit_selector.unwrap_or(one);
+    let check = builder.mul_extension(bit_selector_val, local_values[start_col + FP_INV_CHECK_OFFSET]);
+    let mul_col = start_col + FP_INV_MUL_OFFSET;
+
+    for i in 0..12 {
+        let c = builder.sub_extension(local_values[fp_mul_mod_x(mul_col) + i], local_values[start_col + FP_INV_INPUT_OFFSET + i]);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(local_values[fp_mul_mod_y(mul_col) + i], local_values[start_col + FP_INV_INVERSE_OFFSET + i]);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+        let c = if i == 0 {
+            builder.sub_extension(local_values[fp_mul_mod_res(mul_col) + i], one)
+        } else {
+            local_values[fp_mul_mod_res(mul_col) + i]
+        };
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+    }
+    add_fp_mul_mod_constraints_ext_circuit(builder, yield_constr, local_values, next_values, mul_col, bit_selector);
+}
+
+#[derive(Clone, Copy)]
+pub struct FpInverseStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> FpInverseStark<F, D> {
+    /// Rows used by one fp inversion, 12. The stark needs a power of two number of rows of at least this many.
+    pub fn required_rows() -> usize {
+        12
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(a: &Fp, a_inv: &Fp) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for i in 0..12 {
+            public_inputs[PIS_INPUT_OFFSET + i] = F::from_canonical_u32(a.0[i]);
+            public_inputs[PIS_INVERSE_OFFSET + i] = F::from_canonical_u32(a_inv.0[i]);
+        }
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, a: &Fp) -> Result<Vec<[F; TOTAL_COLUMNS]>> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_fp_inverse(&mut trace, a, 0, 11, 0)?;
+        Ok(trace)
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FpInverseStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[FP_INV_CHECK_OFFSET] - P::ONES);
+        for i in 0..12 {
+            yield_constr.constraint(
+                local_values[FP_INV_CHECK_OFFSET] *
+                (local_values[FP_INV_INPUT_OFFSET + i] - public_inputs[PIS_INPUT_OFFSET + i])
+            );
+            yield_constr.constraint(
+                local_values[FP_INV_CHECK_OFFSET] *
+                (local_values[FP_INV_INVERSE_OFFSET + i] - public_inputs[PIS_INVERSE_OFFSET + i])
+            );
+        }
+        add_fp_inverse_constraints(local_values, next_values, yield_constr, 0, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[FP_INV_CHECK_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[FP_INV_INPUT_OFFSET + i], public_inputs[PIS_INPUT_OFFSET + i]);
+            let c = builder.mul_extension(local_values[FP_INV_CHECK_OFFSET], c);
+            yield_constr.constraint(builder, c);
+            let c = builder.sub_extension(local_values[FP_INV_INVERSE_OFFSET + i], public_inputs[PIS_INVERSE_OFFSET + i]);
+            let c = builder.mul_extension(local_values[FP_INV_CHECK_OFFSET], c);
+            yield_constr.constraint(builder, c);
+        }
+        add_fp_inverse_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{modulus, Fp};
+
+    use super::{FpInverseStark, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = FpInverseStark<F, D>;
+
+    fn prove_and_verify_inverse(a: &Fp) -> Fp {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(16);
+        let a_inv = a.invert();
+        let public_inputs = S::public_inputs(a, &a_inv);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(a).unwrap();
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        a_inv
+    }
+
+    #[test]
+    fn test_fp_inverse_small_constants() {
+        let two = Fp::get_fp_from_biguint(BigUint::from(2u32));
+        let half = Fp::get_fp_from_biguint((modulus() + 1u32) / 2u32);
+        assert_eq!(prove_and_verify_inverse(&two), half);
+        assert_eq!(prove_and_verify_inverse(&Fp::one()), Fp::one());
+        let minus_one = -Fp::one();
+        assert_eq!(prove_and_verify_inverse(&minus_one), minus_one);
+    }
+
+    #[test]
+    fn test_fp_inverse_random_elements() {
+        let seed = BigUint::parse_bytes(b"1f9b2a6d3c7e4f8091a2b3c4d5e6f708192a3b4c5d6e7f8091a2b3c4d5e6f70819", 16).unwrap();
+        let mut x = seed.clone();
+        for _ in 0..3 {
+            x = (&x * &seed + 7u32) % modulus();
+            let a = Fp::get_fp_from_biguint(x.clone());
+            let a_inv = prove_and_verify_inverse(&a);
+            assert_eq!(a * a_inv, Fp::one());
+        }
+    }
+
+    #[test]
+    fn test_fp_inverse_of_zero() {
+        let stark = S::new(16);
+        assert!(stark.generate_trace(&Fp::zero()).is_err());
+    }
+}
@@ -0,0 +1,274 @@
+use anyhow::{bail, Result};
+use num_bigint::BigUint;
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use crate::native::{modulus, Fp};
+
+use crate::fp::*;
+
+// Fp reduce layout offsets
+/*
+    These trace offsets are for proving that a wide value of 24 limbs reduces to a canonical fp element, i.e. x == q*p + r with r < p. It needs 12 rows.
+    The quotient q is a witness of 12 limbs, so the input must be less than p * 2^384. This covers the product of any two values of 12 limbs less than p.
+    FP_REDUCE_SELECTOR_OFFSET -> Selector to ensure that the input and the result are same across all rows. Set 1 in all rows except last one.
+    FP_REDUCE_REDUCTION_OFFSET -> offset where the reduction modulo p is done. The input is at REDUCE_X_OFFSET and the result at REDUCED_OFFSET of this operation.
+    FP_REDUCE_RANGECHECK_OFFSET -> range check of the result wrt the field prime, which makes the result canonical. The range check takes its input from the result of the reduction, the 12 columns before it.
+*/
+pub const FP_REDUCE_SELECTOR_OFFSET: usize = 0;
+pub const FP_REDUCE_REDUCTION_OFFSET: usize = FP_REDUCE_SELECTOR_OFFSET + 1;
+pub const FP_REDUCE_RANGECHECK_OFFSET: usize = FP_REDUCE_REDUCTION_OFFSET + REDUCTION_TOTAL;
+pub const FP_REDUCE_TOTAL: usize = FP_REDUCE_RANGECHECK_OFFSET + RANGE_CHECK_TOTAL;
+
+pub const TOTAL_COLUMNS: usize = FP_REDUCE_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the 24 limbs of the wide input followed by the 12 limbs of the reduced result.
+*/
+pub const PIS_INPUT_OFFSET: usize = 0;
+pub const PIS_RES_OFFSET: usize = PIS_INPUT_OFFSET + 24;
+pub const PUBLIC_INPUTS: usize = PIS_RES_OFFSET + 12;
+
+/// Column of the wide input of an fp reduce operation starting at `col`.
+pub fn fp_reduce_x(col: usize) -> usize {
+    col + FP_REDUCE_REDUCTION_OFFSET + REDUCE_X_OFFSET
+}
+
+/// Column of the reduced result of an fp reduce operation starting at `col`.
+pub fn fp_reduce_res(col: usize) -> usize {
+    col + FP_REDUCE_REDUCTION_OFFSET + REDUCED_OFFSET
+}
+
+/// Fills the stark trace of reducing a value of 24 limbs modulo p. Returns an error if the input is not less than p * 2^384, since the quotient is set in 12 limbs. Needs 12 rows. Fills the reduction trace, with the quotient computed by [get_div_rem_modulus_from_biguint_12](super::native::get_div_rem_modulus_from_biguint_12), and the range check of the result. Returns the canonical result.
+pub fn fill_trace_fp_reduce<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, x: &[u32; 24], start_row: usize, end_row: usize, start_col: usize) -> Result<Fp> {
+    if BigUint::new(x.to_vec()) >= modulus() << 384 {
+        bail!("fill_trace_fp_reduce: input is not less than p * 2^384");
+    }
+    for row in start_row..end_row {
+        trace[row][start_col + FP_REDUCE_SELECTOR_OFFSET] = F::ONE;
+    }
+    let res = fill_reduction_trace(trace, x, start_row, end_row, start_col + FP_REDUCE_REDUCTION_OFFSET);
+    for row in start_row..end_row + 1 {
+        fill_range_check_trace(trace, &res, row, start_col + FP_REDUCE_RANGECHECK_OFFSET);
+    }
+    Ok(Fp(res))
+}
+
+/// Constraints the reduction of a value of 24 limbs modulo p.
+/// Constraints the reduction operation, with the selector keeping the input and the result same across the rows, then constraints the range check of the result.
+pub fn add_fp_reduce_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    add_reduce_constraints(local_values, next_values, yield_constr, start_col + FP_REDUCE_REDUCTION_OFFSET, start_col + FP_REDUCE_SELECTOR_OFFSET, bit_selector);
+    add_range_check_constraints(local_values, yield_constr, start_col + FP_REDUCE_RANGECHECK_OFFSET, bit_selector);
+}
+
+pub fn add_fp_reduce_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    add_reduce_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + FP_REDUCE_REDUCTION_OFFSET, start_col + FP_REDUCE_SELECTOR_OFFSET, bit_selector);
+    add_range_check_constraints_ext_circuit(builder, yield_constr, local_values, start_col + FP_REDUCE_RANGECHECK_OFFSET, bit_selector);
+}
+
+/// Stark proving that a value of 24 limbs reduces to a canonical fp element modulo p.
+#[derive(Clone, Copy)]
+pub struct FpReduceStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> FpReduceStark<F, D> {
+    /// Rows used by one reduction, 12. The stark needs a power of two number of rows of at least this many.
+    pub fn required_rows() -> usize {
+        12
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(x: &[u32; 24], res: &Fp) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for i in 0..24 {
+            public_inputs[PIS_INPUT_OFFSET + i] = F::from_canonical_u32(x[i]);
+        }
+        for i in 0..12 {
+            public_inputs[PIS_RES_OFFSET + i] = F::from_canonical_u32(res.0[i]);
+        }
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, x: &[u32; 24]) -> Result<Vec<[F; TOTAL_COLUMNS]>> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_fp_reduce(&mut trace, x, 0, 11, 0)?;
+        Ok(trace)
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FpReduceStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[FP_REDUCE_SELECTOR_OFFSET] - P::ONES);
+        for i in 0..24 {
+            yield_constr.constraint_first_row(local_values[fp_reduce_x(0) + i] - public_inputs[PIS_INPUT_OFFSET + i]);
+        }
+        for i in 0..12 {
+            yield_constr.constraint_first_row(local_values[fp_reduce_res(0) + i] - public_inputs[PIS_RES_OFFSET + i]);
+        }
+        add_fp_reduce_constraints(local_values, next_values, yield_constr, 0, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[FP_REDUCE_SELECTOR_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for i in 0..24 {
+            let c = builder.sub_extension(local_values[fp_reduce_x(0) + i], public_inputs[PIS_INPUT_OFFSET + i]);
+            yield_constr.constraint_first_row(builder, c);
+        }
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[fp_reduce_res(0) + i], public_inputs[PIS_RES_OFFSET + i]);
+            yield_constr.constraint_first_row(builder, c);
+        }
+        add_fp_reduce_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{get_u32_vec_from_literal_24, modulus, Fp};
+
+    use super::{FpReduceStark, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = FpReduceStark<F, D>;
+
+    fn prove_and_verify_reduce(x: &BigUint) -> Fp {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(16);
+        let x_limbs = get_u32_vec_from_literal_24(x.clone());
+        let res = Fp::get_fp_from_biguint(x % modulus());
+        let public_inputs = S::public_inputs(&x_limbs, &res);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(&x_limbs).unwrap();
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        res
+    }
+
+    #[test]
+    fn test_fp_reduce_two_p_minus_one() {
+        let p = modulus();
+        assert_eq!(prove_and_verify_reduce(&(&p * 2u32 - 1u32)), -Fp::one());
+        assert_eq!(prove_and_verify_reduce(&p), Fp::zero());
+        assert_eq!(prove_and_verify_reduce(&BigUint::from(5u32)), Fp::get_fp_from_biguint(BigUint::from(5u32)));
+    }
+
+    #[test]
+    fn test_fp_reduce_random_wide_values() {
+        let bound = modulus() << 384;
+        let seed = BigUint::parse_bytes(b"9d3f6a1c7e2b5f8041a3c5e7092b4d6f8a1c3e5f7092b4d6f8a1c3e5f7092b4d6f8a1c3e5f7092b4d6f8a1c3e5f7092b4d6f8a1c3e5f7092b4d6f8a1c3e5f7092b", 16).unwrap();
+        let mut x = seed.clone();
+        for _ in 0..3 {
+            x = (&x * &seed + 11u32) % &bound;
+            let res = prove_and_verify_reduce(&x);
+            assert_eq!(res, Fp::get_fp_from_biguint(&x % modulus()));
+        }
+        let max = &bound - 1u32;
+        prove_and_verify_reduce(&max);
+    }
+
+    #[test]
+    fn test_fp_reduce_input_too_large() {
+        let stark = S::new(16);
+        let x = get_u32_vec_from_literal_24(modulus() << 384);
+        assert!(stark.generate_trace(&x).is_err());
+    }
+}
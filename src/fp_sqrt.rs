@@ -0,0 +1,371 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use crate::native::{sqrt_fp, Fp};
+
+use crate::fp::*;
+use crate::fp_inverse::*;
+use crate::utils::*;
+
+// Fp square root layout offsets
+/*
+    These trace offsets are for proving the square root of an fp element, or that it has none. It needs 12 rows.
+    Since p = 3 mod 4, -1 is not a quadratic residue, so exactly one of `a` and `-a` is a square for non zero `a`. A non zero root of `-a` is the certificate that `a` is not a square.
+    FP_SQRT_CHECK_OFFSET -> Selector set 1 in all rows of the operation, where the intermediate values are constrained with each other.
+    FP_SQRT_INPUT_OFFSET -> offset where the input `a` is set.
+    FP_SQRT_ROOT_OFFSET -> offset where the witness `root` is set. It is the square root of `a` if `a` is a square, and the square root of `-a` otherwise.
+    FP_SQRT_IS_SQUARE_OFFSET -> boolean set 1 if `a` is a square.
+    FP_SQRT_MUL_OFFSET -> offset where root * root fp multiplication modulo p is done. The result is constrained to be `a` if `a` is a square.
+    FP_SQRT_NEG_OFFSET -> offset where root^2 + a fp addition modulo p is done. The result is constrained to be 0 if `a` is not a square.
+    FP_SQRT_ROOT_INV_OFFSET -> offset where the fp inverse of the root is done if `a` is not a square, which rejects `a == 0` claimed as a non square. If `a` is a square, the inverse of 1 is filled instead and left unconstrained with the root.
+*/
+pub const FP_SQRT_CHECK_OFFSET: usize = 0;
+pub const FP_SQRT_INPUT_OFFSET: usize = FP_SQRT_CHECK_OFFSET + 1;
+pub const FP_SQRT_ROOT_OFFSET: usize = FP_SQRT_INPUT_OFFSET + 12;
+pub const FP_SQRT_IS_SQUARE_OFFSET: usize = FP_SQRT_ROOT_OFFSET + 12;
+pub const FP_SQRT_MUL_OFFSET: usize = FP_SQRT_IS_SQUARE_OFFSET + 1;
+pub const FP_SQRT_NEG_OFFSET: usize = FP_SQRT_MUL_OFFSET + FP_MUL_MOD_TOTAL;
+pub const FP_SQRT_ROOT_INV_OFFSET: usize = FP_SQRT_NEG_OFFSET + FP_ADD_MOD_TOTAL;
+pub const FP_SQRT_TOTAL: usize = FP_SQRT_ROOT_INV_OFFSET + FP_INV_TOTAL;
+
+pub const TOTAL_COLUMNS: usize = FP_SQRT_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the input `a`, the witness `root` and the boolean which is 1 if `a` is a square.
+*/
+pub const PIS_INPUT_OFFSET: usize = 0;
+pub const PIS_ROOT_OFFSET: usize = PIS_INPUT_OFFSET + 12;
+pub const PIS_IS_SQUARE_OFFSET: usize = PIS_ROOT_OFFSET + 12;
+pub const PUBLIC_INPUTS: usize = PIS_IS_SQUARE_OFFSET + 1;
+
+/// Returns the witness root of `a` along with whether `a` is a square. The root is the square root of `a` if it exists, and the square root of `-a` otherwise.
+pub fn get_fp_sqrt_witness(a: &Fp) -> (Fp, bool) {
+    match sqrt_fp(*a) {
+        Some(root) => (root, true),
+        None => (sqrt_fp(-*a).unwrap(), false),
+    }
+}
+
+/// Fills the stark trace of fp square root. Input is an fp element. Needs 12 rows. Fills the square of the witness root, its sum with the input and the inverse of the root. Returns the square root of the input if it exists.
+pub fn fill_trace_fp_sqrt<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, a: &Fp, start_row: usize, end_row: usize, start_col: usize) -> Option<Fp> {
+    let (root, is_square) = get_fp_sqrt_witness(a);
+    let root_sq = fill_trace_fp_mul_mod(trace, &root.0, &root.0, start_row, end_row, start_col + FP_SQRT_MUL_OFFSET);
+    for row in start_row..end_row + 1 {
+        trace[row][start_col + FP_SQRT_CHECK_OFFSET] = F::ONE;
+        assign_u32_in_series(trace, row, start_col + FP_SQRT_INPUT_OFFSET, &a.0);
+        assign_u32_in_series(trace, row, start_col + FP_SQRT_ROOT_OFFSET, &root.0);
+        trace[row][start_col + FP_SQRT_IS_SQUARE_OFFSET] = F::from_bool(is_square);
+        fill_trace_addition_fp_mod(trace, &root_sq, &a.0, row, start_col + FP_SQRT_NEG_OFFSET);
+    }
+    let root_inv_input = if is_square {
+        Fp::one()
+    } else {
+        root
+    };
+    fill_trace_fp_inverse(trace, &root_inv_input, start_row, end_row, start_col + FP_SQRT_ROOT_INV_OFFSET).unwrap();
+    if is_square {
+        Some(root)
+    } else {
+        None
+    }
+}
+
+/// Constraints fp square root.
+/// Wherever `FP_SQRT_CHECK_OFFSET` is set, constraints the square boolean, the root with both inputs of the multiplication and the square with the x input of the addition. If the input is a square, the square of the root is constrained to be the input. Otherwise, the sum of the square with the input is constrained to be 0 and the root is constrained with the input of the fp inverse. Then constraints the fp multiplication, fp addition and fp inverse operations.
+pub fn add_fp_sqrt_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+    let check = bit_selector_val * local_values[start_col + FP_SQRT_CHECK_OFFSET];
+    let is_square = local_values[start_col + FP_SQRT_IS_SQUARE_OFFSET];
+    let mul_col = start_col + FP_SQRT_MUL_OFFSET;
+    let neg_col = start_col + FP_SQRT_NEG_OFFSET;
+    let root_inv_col = start_col + FP_SQRT_ROOT_INV_OFFSET;
+
+    yield_constr.constraint(check * is_square * (P::ONES - is_square));
+    for i in 0..12 {
+        yield_constr.constraint(
+            check *
+            (local_values[fp_mul_mod_x(mul_col) + i] - local_values[start_col + FP_SQRT_ROOT_OFFSET + i])
+        );
+        yield_constr.constraint(
+            check *
+            (local_values[fp_mul_mod_y(mul_col) + i] - local_values[start_col + FP_SQRT_ROOT_OFFSET + i])
+        );
+        yield_constr.constraint(
+            check *
+            (local_values[fp_add_mod_x(neg_col) + i] - local_values[fp_mul_mod_res(mul_col) + i])
+        );
+        yield_constr.constraint(
+            check *
+            (local_values[fp_add_mod_y(neg_col) + i] - local_values[start_col + FP_SQRT_INPUT_OFFSET + i])
+        );
+        yield_constr.constraint(
+            check * is_square *
+            (local_values[fp_mul_mod_res(mul_col) + i] - local_values[start_col + FP_SQRT_INPUT_OFFSET + i])
+        );
+        yield_constr.constraint(
+            check * (P::ONES - is_square) *
+            local_values[fp_add_mod_res(neg_col) + i]
+        );
+        yield_constr.constraint(
+            check * (P::ONES - is_square) *
+            (local_values[root_inv_col + FP_INV_INPUT_OFFSET + i] - local_values[start_col + FP_SQRT_ROOT_OFFSET + i])
+        );
+    }
+
+    add_fp_mul_mod_constraints(local_values, next_values, yield_constr, mul_col, bit_selector);
+    add_addition_fp_mod_constraints(local_values, yield_constr, neg_col, bit_selector);
+    add_fp_inverse_constraints(local_values, next_values, yield_constr, root_inv_col, bit_selector);
+}
+
+pub fn add_fp_sqrt_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let one = builder.constant_extension(F::Extension::ONE);
+    let bit_selector_val = bit_selector.unwrap_or(one);
+    let check = builder.mul_extension(bit_selector_val, local_values[start_col + FP_SQRT_CHECK_OFFSET]);
+    let is_square = local_values[start_col + FP_SQRT_IS_SQUARE_OFFSET];
+    let not_square = builder.sub_extension(one, is_square);
+    let check_square = builder.mul_extension(check, is_square);
+    let check_not_square = builder.mul_extension(check, not_square);
+    let mul_col = start_col + FP_SQRT_MUL_OFFSET;
+    let neg_col = start_col + FP_SQRT_NEG_OFFSET;
+    let root_inv_col = start_col + FP_SQRT_ROOT_INV_OFFSET;
+
+    let c = builder.mul_extension(check_square, not_square);
+    yield_constr.constraint(builder, c);
+    for i in 0..12 {
+        let c = builder.sub_extension(local_values[fp_mul_mod_x(mul_col) + i], local_values[start_col + FP_SQRT_ROOT_OFFSET + i]);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(local_values[fp_mul_mod_y(mul_col) + i], local_values[start_col + FP_SQRT_ROOT_OFFSET + i]);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(local_values[fp_add_mod_x(neg_col) + i], local_values[fp_mul_mod_res(mul_col) + i]);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(local_values[fp_add_mod_y(neg_col) + i], local_values[start_col + FP_SQRT_INPUT_OFFSET + i]);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(local_values[fp_mul_mod_res(mul_col) + i], local_values[start_col + FP_SQRT_INPUT_OFFSET + i]);
+        let c = builder.mul_extension(check_square, c);
+        yield_constr.constraint(builder, c);
+        let c = builder.mul_extension(check_not_square, local_values[fp_add_mod_res(neg_col) + i]);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(local_values[root_inv_col + FP_INV_INPUT_OFFSET + i], local_values[start_col + FP_SQRT_ROOT_OFFSET + i]);
+        let c = builder.mul_extension(check_not_square, c);
+        yield_constr.constraint(builder, c);
+    }
+
+    add_fp_mul_mod_constraints_ext_circuit(builder, yield_constr, local_values, next_values, mul_col, bit_selector);
+    add_addition_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, neg_col, bit_selector);
+    add_fp_inverse_constraints_ext_circuit(builder, yield_constr, local_values, next_values, root_inv_col, bit_selector);
+}
+
+/// Stark proving either `root^2 == a`, or `root^2 == -a` with `root != 0`, which certifies that `a` is not a square.
+#[derive(Clone, Copy)]
+pub struct FpSqrtStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> FpSqrtStark<F, D> {
+    /// Rows used by one fp square root, 12. The stark needs a power of two number of rows of at least this many.
+    pub fn required_rows() -> usize {
+        12
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(a: &Fp, root: &Fp, is_square: bool) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for i in 0..12 {
+            public_inputs[PIS_INPUT_OFFSET + i] = F::from_canonical_u32(a.0[i]);
+            public_inputs[PIS_ROOT_OFFSET + i] = F::from_canonical_u32(root.0[i]);
+        }
+        public_inputs[PIS_IS_SQUARE_OFFSET] = F::from_bool(is_square);
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, a: &Fp) -> Vec<[F; TOTAL_COLUMNS]> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_fp_sqrt(&mut trace, a, 0, 11, 0);
+        trace
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for FpSqrtStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[FP_SQRT_CHECK_OFFSET] - P::ONES);
+        for i in 0..12 {
+            yield_constr.constraint(
+                local_values[FP_SQRT_CHECK_OFFSET] *
+                (local_values[FP_SQRT_INPUT_OFFSET + i] - public_inputs[PIS_INPUT_OFFSET + i])
+            );
+            yield_constr.constraint(
+                local_values[FP_SQRT_CHECK_OFFSET] *
+                (local_values[FP_SQRT_ROOT_OFFSET + i] - public_inputs[PIS_ROOT_OFFSET + i])
+            );
+        }
+        yield_constr.constraint(
+            local_values[FP_SQRT_CHECK_OFFSET] *
+            (local_values[FP_SQRT_IS_SQUARE_OFFSET] - public_inputs[PIS_IS_SQUARE_OFFSET])
+        );
+        add_fp_sqrt_constraints(local_values, next_values, yield_constr, 0, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[FP_SQRT_CHECK_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[FP_SQRT_INPUT_OFFSET + i], public_inputs[PIS_INPUT_OFFSET + i]);
+            let c = builder.mul_extension(local_values[FP_SQRT_CHECK_OFFSET], c);
+            yield_constr.constraint(builder, c);
+            let c = builder.sub_extension(local_values[FP_SQRT_ROOT_OFFSET + i], public_inputs[PIS_ROOT_OFFSET + i]);
+            let c = builder.mul_extension(local_values[FP_SQRT_CHECK_OFFSET], c);
+            yield_constr.constraint(builder, c);
+        }
+        let c = builder.sub_extension(local_values[FP_SQRT_IS_SQUARE_OFFSET], public_inputs[PIS_IS_SQUARE_OFFSET]);
+        let c = builder.mul_extension(local_values[FP_SQRT_CHECK_OFFSET], c);
+        yield_constr.constraint(builder, c);
+        add_fp_sqrt_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{modulus, Fp};
+
+    use super::{get_fp_sqrt_witness, FpSqrtStark, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = FpSqrtStark<F, D>;
+
+    fn prove_and_verify_sqrt(a: &Fp) -> Option<Fp> {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(16);
+        let (root, is_square) = get_fp_sqrt_witness(a);
+        let public_inputs = S::public_inputs(a, &root, is_square);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(a);
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        if is_square {
+            Some(root)
+        } else {
+            None
+        }
+    }
+
+    #[test]
+    fn test_fp_sqrt_quadratic_residues() {
+        let four = Fp::get_fp_from_biguint(BigUint::from(4u32));
+        let two = prove_and_verify_sqrt(&four).unwrap();
+        assert_eq!(two * two, four);
+        assert_eq!(prove_and_verify_sqrt(&Fp::zero()), Some(Fp::zero()));
+        let seed = BigUint::parse_bytes(b"3c5a7e9b1d2f4a6c8e0b2d4f6a8c0e1b3d5f7a9c2e4b6d8f0a1c3e5b7d9f2a4c", 16).unwrap();
+        let x = Fp::get_fp_from_biguint((&seed * &seed + 5u32) % modulus());
+        let root = prove_and_verify_sqrt(&(x * x)).unwrap();
+        assert!(root == x || root == -x);
+    }
+
+    #[test]
+    fn test_fp_sqrt_non_residues() {
+        assert_eq!(prove_and_verify_sqrt(&-Fp::one()), None);
+        // 5 is not a quadratic residue modulo p.
+        let five = Fp::get_fp_from_biguint(BigUint::from(5u32));
+        assert_eq!(prove_and_verify_sqrt(&five), None);
+    }
+}
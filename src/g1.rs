@@ -0,0 +1,966 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use anyhow::{ensure, Result};
+
+use crate::native::{Fp, G1Affine};
+
+use crate::fp::*;
+use crate::utils::*;
+
+// G1 point doubling layout offsets
+/*
+    These trace offsets are for doubling an affine G1 point (super::native::G1Affine::double). It needs 12 rows.
+    The slope `lambda = 3x^2 / 2y` is set as a witness and constrained as `lambda * 2y == 3x^2`. Then x3 = lambda^2 - 2x and y3 = lambda*(x - x3) - y.
+    G1_DOUBLE_SELECTOR_OFFSET -> Selector to ensure that the input is same across all rows. Set 1 in all rows except last one.
+    G1_DOUBLE_CHECK_OFFSET -> Selector set 1 in all rows of the operation. The single row operations are filled in every row, and their inputs are constrained wherever this selector is on.
+    G1_DOUBLE_X_OFFSET, G1_DOUBLE_Y_OFFSET -> offsets where the input point is set.
+    G1_DOUBLE_INFINITY_OFFSET -> Flag set 1 if the input is the point at infinity. The coordinates of the point at infinity are zero.
+    G1_DOUBLE_LAMBDA_OFFSET -> offset where the slope of the tangent line is set.
+    G1_DOUBLE_X_SQ_OFFSET -> x * x
+    G1_DOUBLE_2X_SQ_OFFSET -> x^2 + x^2
+    G1_DOUBLE_3X_SQ_OFFSET -> 2x^2 + x^2
+    G1_DOUBLE_2Y_OFFSET -> y + y
+    G1_DOUBLE_LAMBDA_2Y_OFFSET -> lambda * 2y. Constrained to be equal to 3x^2, unless the result is the point at infinity.
+    G1_DOUBLE_LAMBDA_SQ_OFFSET -> lambda * lambda
+    G1_DOUBLE_2X_OFFSET -> x + x
+    G1_DOUBLE_X3_OFFSET -> lambda^2 - 2x
+    G1_DOUBLE_X_X3_OFFSET -> x - x3
+    G1_DOUBLE_LAMBDA_X_X3_OFFSET -> lambda * (x - x3)
+    G1_DOUBLE_Y3_OFFSET -> lambda*(x - x3) - y
+    G1_DOUBLE_RES_INFINITY_OFFSET -> Flag set 1 if the result is the point at infinity, i.e. the input is the point at infinity or y == 0.
+    G1_DOUBLE_RES_X_OFFSET, G1_DOUBLE_RES_Y_OFFSET -> offsets where the result is set. Both are zero if the result is the point at infinity.
+*/
+pub const G1_DOUBLE_SELECTOR_OFFSET: usize = 0;
+pub const G1_DOUBLE_CHECK_OFFSET: usize = G1_DOUBLE_SELECTOR_OFFSET + 1;
+pub const G1_DOUBLE_X_OFFSET: usize = G1_DOUBLE_CHECK_OFFSET + 1;
+pub const G1_DOUBLE_Y_OFFSET: usize = G1_DOUBLE_X_OFFSET + 12;
+pub const G1_DOUBLE_INFINITY_OFFSET: usize = G1_DOUBLE_Y_OFFSET + 12;
+pub const G1_DOUBLE_LAMBDA_OFFSET: usize = G1_DOUBLE_INFINITY_OFFSET + 1;
+pub const G1_DOUBLE_X_SQ_OFFSET: usize = G1_DOUBLE_LAMBDA_OFFSET + 12;
+pub const G1_DOUBLE_2X_SQ_OFFSET: usize = G1_DOUBLE_X_SQ_OFFSET + FP_MUL_MOD_TOTAL;
+pub const G1_DOUBLE_3X_SQ_OFFSET: usize = G1_DOUBLE_2X_SQ_OFFSET + FP_ADD_MOD_TOTAL;
+pub const G1_DOUBLE_2Y_OFFSET: usize = G1_DOUBLE_3X_SQ_OFFSET + FP_ADD_MOD_TOTAL;
+pub const G1_DOUBLE_LAMBDA_2Y_OFFSET: usize = G1_DOUBLE_2Y_OFFSET + FP_ADD_MOD_TOTAL;
+pub const G1_DOUBLE_LAMBDA_SQ_OFFSET: usize = G1_DOUBLE_LAMBDA_2Y_OFFSET + FP_MUL_MOD_TOTAL;
+pub const G1_DOUBLE_2X_OFFSET: usize = G1_DOUBLE_LAMBDA_SQ_OFFSET + FP_MUL_MOD_TOTAL;
+pub const G1_DOUBLE_X3_OFFSET: usize = G1_DOUBLE_2X_OFFSET + FP_ADD_MOD_TOTAL;
+pub const G1_DOUBLE_X_X3_OFFSET: usize = G1_DOUBLE_X3_OFFSET + FP_SUB_MOD_TOTAL;
+pub const G1_DOUBLE_LAMBDA_X_X3_OFFSET: usize = G1_DOUBLE_X_X3_OFFSET + FP_SUB_MOD_TOTAL;
+pub const G1_DOUBLE_Y3_OFFSET: usize = G1_DOUBLE_LAMBDA_X_X3_OFFSET + FP_MUL_MOD_TOTAL;
+pub const G1_DOUBLE_RES_INFINITY_OFFSET: usize = G1_DOUBLE_Y3_OFFSET + FP_SUB_MOD_TOTAL;
+pub const G1_DOUBLE_RES_X_OFFSET: usize = G1_DOUBLE_RES_INFINITY_OFFSET + 1;
+pub const G1_DOUBLE_RES_Y_OFFSET: usize = G1_DOUBLE_RES_X_OFFSET + 12;
+pub const G1_DOUBLE_TOTAL: usize = G1_DOUBLE_RES_Y_OFFSET + 12;
+
+// G1 point addition layout offsets
+/*
+    These trace offsets are for adding two affine G1 points (super::native::G1Affine::add). It needs 12 rows. Adding a point to itself is not supported, use doubling for that.
+    The slope `lambda = (y2 - y1) / (x2 - x1)` is set as a witness and constrained as `lambda * (x2 - x1) == y2 - y1`. Then x3 = lambda^2 - x1 - x2 and y3 = lambda*(x1 - x3) - y1.
+    G1_ADD_SELECTOR_OFFSET -> Selector to ensure that the input is same across all rows. Set 1 in all rows except last one.
+    G1_ADD_CHECK_OFFSET -> Selector set 1 in all rows of the operation. The single row operations are filled in every row, and their inputs are constrained wherever this selector is on.
+    G1_ADD_X1_OFFSET, G1_ADD_Y1_OFFSET, G1_ADD_INFINITY1_OFFSET -> offsets where the first point and its infinity flag are set.
+    G1_ADD_X2_OFFSET, G1_ADD_Y2_OFFSET, G1_ADD_INFINITY2_OFFSET -> offsets where the second point and its infinity flag are set.
+    G1_ADD_BOTH_FINITE_OFFSET -> Flag set 1 if none of the inputs is the point at infinity, i.e. (1 - infinity1) * (1 - infinity2).
+    G1_ADD_OPPOSITE_OFFSET -> Flag set 1 if both inputs are finite and the second point is the negation of the first one, i.e. x1 == x2 and y1 + y2 == 0.
+        The generic addition is done if both inputs are finite and they are not opposite, in which case x2 - x1 is constrained to have an inverse.
+    G1_ADD_LAMBDA_OFFSET -> offset where the slope of the line is set.
+    G1_ADD_DX_INV_OFFSET -> offset where the inverse of x2 - x1 is set.
+    G1_ADD_DX_OFFSET -> x2 - x1
+    G1_ADD_DY_OFFSET -> y2 - y1
+    G1_ADD_DX_INV_MUL_OFFSET -> (x2 - x1) * dx_inv. Constrained to be one for the generic addition.
+    G1_ADD_LAMBDA_DX_OFFSET -> lambda * (x2 - x1). Constrained to be equal to y2 - y1 for the generic addition.
+    G1_ADD_Y_SUM_OFFSET -> y1 + y2. Constrained to be zero if the inputs are opposite.
+    G1_ADD_LAMBDA_SQ_OFFSET -> lambda * lambda
+    G1_ADD_X1_X2_OFFSET -> x1 + x2
+    G1_ADD_X3_OFFSET -> lambda^2 - (x1 + x2)
+    G1_ADD_X1_X3_OFFSET -> x1 - x3
+    G1_ADD_LAMBDA_X1_X3_OFFSET -> lambda * (x1 - x3)
+    G1_ADD_Y3_OFFSET -> lambda*(x1 - x3) - y1
+    G1_ADD_RES_INFINITY_OFFSET -> Flag set 1 if the result is the point at infinity, i.e. both inputs are the point at infinity or the inputs are opposite.
+    G1_ADD_RES_X_OFFSET, G1_ADD_RES_Y_OFFSET -> offsets where the result is set.
+*/
+pub const G1_ADD_SELECTOR_OFFSET: usize = 0;
+pub const G1_ADD_CHECK_OFFSET: usize = G1_ADD_SELECTOR_OFFSET + 1;
+pub const G1_ADD_X1_OFFSET: usize = G1_ADD_CHECK_OFFSET + 1;
+pub const G1_ADD_Y1_OFFSET: usize = G1_ADD_X1_OFFSET + 12;
+pub const G1_ADD_INFINITY1_OFFSET: usize = G1_ADD_Y1_OFFSET + 12;
+pub const G1_ADD_X2_OFFSET: usize = G1_ADD_INFINITY1_OFFSET + 1;
+pub const G1_ADD_Y2_OFFSET: usize = G1_ADD_X2_OFFSET + 12;
+pub const G1_ADD_INFINITY2_OFFSET: usize = G1_ADD_Y2_OFFSET + 12;
+pub const G1_ADD_BOTH_FINITE_OFFSET: usize = G1_ADD_INFINITY2_OFFSET + 1;
+pub const G1_ADD_OPPOSITE_OFFSET: usize = G1_ADD_BOTH_FINITE_OFFSET + 1;
+pub const G1_ADD_LAMBDA_OFFSET: usize = G1_ADD_OPPOSITE_OFFSET + 1;
+pub const G1_ADD_DX_INV_OFFSET: usize = G1_ADD_LAMBDA_OFFSET + 12;
+pub const G1_ADD_DX_OFFSET: usize = G1_ADD_DX_INV_OFFSET + 12;
+pub const G1_ADD_DY_OFFSET: usize = G1_ADD_DX_OFFSET + FP_SUB_MOD_TOTAL;
+pub const G1_ADD_DX_INV_MUL_OFFSET: usize = G1_ADD_DY_OFFSET + FP_SUB_MOD_TOTAL;
+pub const G1_ADD_LAMBDA_DX_OFFSET: usize = G1_ADD_DX_INV_MUL_OFFSET + FP_MUL_MOD_TOTAL;
+pub const G1_ADD_Y_SUM_OFFSET: usize = G1_ADD_LAMBDA_DX_OFFSET + FP_MUL_MOD_TOTAL;
+pub const G1_ADD_LAMBDA_SQ_OFFSET: usize = G1_ADD_Y_SUM_OFFSET + FP_ADD_MOD_TOTAL;
+pub const G1_ADD_X1_X2_OFFSET: usize = G1_ADD_LAMBDA_SQ_OFFSET + FP_MUL_MOD_TOTAL;
+pub const G1_ADD_X3_OFFSET: usize = G1_ADD_X1_X2_OFFSET + FP_ADD_MOD_TOTAL;
+pub const G1_ADD_X1_X3_OFFSET: usize = G1_ADD_X3_OFFSET + FP_SUB_MOD_TOTAL;
+pub const G1_ADD_LAMBDA_X1_X3_OFFSET: usize = G1_ADD_X1_X3_OFFSET + FP_SUB_MOD_TOTAL;
+pub const G1_ADD_Y3_OFFSET: usize = G1_ADD_LAMBDA_X1_X3_OFFSET + FP_MUL_MOD_TOTAL;
+pub const G1_ADD_RES_INFINITY_OFFSET: usize = G1_ADD_Y3_OFFSET + FP_SUB_MOD_TOTAL;
+pub const G1_ADD_RES_X_OFFSET: usize = G1_ADD_RES_INFINITY_OFFSET + 1;
+pub const G1_ADD_RES_Y_OFFSET: usize = G1_ADD_RES_X_OFFSET + 12;
+pub const G1_ADD_TOTAL: usize = G1_ADD_RES_Y_OFFSET + 12;
+
+pub const TOTAL_COLUMNS: usize = G1_DOUBLE_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the input point (x, y) and its infinity flag, then the result point (x, y) and the infinity flag of the result.
+*/
+pub const PIS_X_OFFSET: usize = 0;
+pub const PIS_Y_OFFSET: usize = PIS_X_OFFSET + 12;
+pub const PIS_INFINITY_OFFSET: usize = PIS_Y_OFFSET + 12;
+pub const PIS_RES_X_OFFSET: usize = PIS_INFINITY_OFFSET + 1;
+pub const PIS_RES_Y_OFFSET: usize = PIS_RES_X_OFFSET + 12;
+pub const PIS_RES_INFINITY_OFFSET: usize = PIS_RES_Y_OFFSET + 12;
+pub const PUBLIC_INPUTS: usize = PIS_RES_INFINITY_OFFSET + 1;
+
+/// Pairs of (operation input, value) columns of the doubling operation, each 12 limbs, which are constrained to be equal wherever G1_DOUBLE_CHECK_OFFSET is set.
+fn g1_double_input_links(start_col: usize) -> Vec<(usize, usize)> {
+    let x = start_col + G1_DOUBLE_X_OFFSET;
+    let y = start_col + G1_DOUBLE_Y_OFFSET;
+    let lambda = start_col + G1_DOUBLE_LAMBDA_OFFSET;
+    let x_sq = fp_mul_mod_res(start_col + G1_DOUBLE_X_SQ_OFFSET);
+    vec![
+        (fp_mul_mod_x(start_col + G1_DOUBLE_X_SQ_OFFSET), x),
+        (fp_mul_mod_y(start_col + G1_DOUBLE_X_SQ_OFFSET), x),
+        (fp_add_mod_x(start_col + G1_DOUBLE_2X_SQ_OFFSET), x_sq),
+        (fp_add_mod_y(start_col + G1_DOUBLE_2X_SQ_OFFSET), x_sq),
+        (fp_add_mod_x(start_col + G1_DOUBLE_3X_SQ_OFFSET), fp_add_mod_res(start_col + G1_DOUBLE_2X_SQ_OFFSET)),
+        (fp_add_mod_y(start_col + G1_DOUBLE_3X_SQ_OFFSET), x_sq),
+        (fp_add_mod_x(start_col + G1_DOUBLE_2Y_OFFSET), y),
+        (fp_add_mod_y(start_col + G1_DOUBLE_2Y_OFFSET), y),
+        (fp_mul_mod_x(start_col + G1_DOUBLE_LAMBDA_2Y_OFFSET), lambda),
+        (fp_mul_mod_y(start_col + G1_DOUBLE_LAMBDA_2Y_OFFSET), fp_add_mod_res(start_col + G1_DOUBLE_2Y_OFFSET)),
+        (fp_mul_mod_x(start_col + G1_DOUBLE_LAMBDA_SQ_OFFSET), lambda),
+        (fp_mul_mod_y(start_col + G1_DOUBLE_LAMBDA_SQ_OFFSET), lambda),
+        (fp_add_mod_x(start_col + G1_DOUBLE_2X_OFFSET), x),
+        (fp_add_mod_y(start_col + G1_DOUBLE_2X_OFFSET), x),
+        (fp_sub_mod_x(start_col + G1_DOUBLE_X3_OFFSET), fp_mul_mod_res(start_col + G1_DOUBLE_LAMBDA_SQ_OFFSET)),
+        (fp_sub_mod_y(start_col + G1_DOUBLE_X3_OFFSET), fp_add_mod_res(start_col + G1_DOUBLE_2X_OFFSET)),
+        (fp_sub_mod_x(start_col + G1_DOUBLE_X_X3_OFFSET), x),
+        (fp_sub_mod_y(start_col + G1_DOUBLE_X_X3_OFFSET), fp_sub_mod_res(start_col + G1_DOUBLE_X3_OFFSET)),
+        (fp_mul_mod_x(start_col + G1_DOUBLE_LAMBDA_X_X3_OFFSET), lambda),
+        (fp_mul_mod_y(start_col + G1_DOUBLE_LAMBDA_X_X3_OFFSET), fp_sub_mod_res(start_col + G1_DOUBLE_X_X3_OFFSET)),
+        (fp_sub_mod_x(start_col + G1_DOUBLE_Y3_OFFSET), fp_mul_mod_res(start_col + G1_DOUBLE_LAMBDA_X_X3_OFFSET)),
+        (fp_sub_mod_y(start_col + G1_DOUBLE_Y3_OFFSET), y),
+    ]
+}
+
+/// Pairs of (operation input, value) columns of the addition operation, each 12 limbs, which are constrained to be equal wherever G1_ADD_CHECK_OFFSET is set.
+fn g1_add_input_links(start_col: usize) -> Vec<(usize, usize)> {
+    let x1 = start_col + G1_ADD_X1_OFFSET;
+    let y1 = start_col + G1_ADD_Y1_OFFSET;
+    let x2 = start_col + G1_ADD_X2_OFFSET;
+    let y2 = start_col + G1_ADD_Y2_OFFSET;
+    let lambda = start_col + G1_ADD_LAMBDA_OFFSET;
+    let dx = fp_sub_mod_res(start_col + G1_ADD_DX_OFFSET);
+    vec![
+        (fp_sub_mod_x(start_col + G1_ADD_DX_OFFSET), x2),
+        (fp_sub_mod_y(start_col + G1_ADD_DX_OFFSET), x1),
+        (fp_sub_mod_x(start_col + G1_ADD_DY_OFFSET), y2),
+        (fp_sub_mod_y(start_col + G1_ADD_DY_OFFSET), y1),
+        (fp_mul_mod_x(start_col + G1_ADD_DX_INV_MUL_OFFSET), dx),
+        (fp_mul_mod_y(start_col + G1_ADD_DX_INV_MUL_OFFSET), start_col + G1_ADD_DX_INV_OFFSET),
+        (fp_mul_mod_x(start_col + G1_ADD_LAMBDA_DX_OFFSET), lambda),
+        (fp_mul_mod_y(start_col + G1_ADD_LAMBDA_DX_OFFSET), dx),
+        (fp_add_mod_x(start_col + G1_ADD_Y_SUM_OFFSET), y1),
+        (fp_add_mod_y(start_col + G1_ADD_Y_SUM_OFFSET), y2),
+        (fp_mul_mod_x(start_col + G1_ADD_LAMBDA_SQ_OFFSET), lambda),
+        (fp_mul_mod_y(start_col + G1_ADD_LAMBDA_SQ_OFFSET), lambda),
+        (fp_add_mod_x(start_col + G1_ADD_X1_X2_OFFSET), x1),
+        (fp_add_mod_y(start_col + G1_ADD_X1_X2_OFFSET), x2),
+        (fp_sub_mod_x(start_col + G1_ADD_X3_OFFSET), fp_mul_mod_res(start_col + G1_ADD_LAMBDA_SQ_OFFSET)),
+        (fp_sub_mod_y(start_col + G1_ADD_X3_OFFSET), fp_add_mod_res(start_col + G1_ADD_X1_X2_OFFSET)),
+        (fp_sub_mod_x(start_col + G1_ADD_X1_X3_OFFSET), x1),
+        (fp_sub_mod_y(start_col + G1_ADD_X1_X3_OFFSET), fp_sub_mod_res(start_col + G1_ADD_X3_OFFSET)),
+        (fp_mul_mod_x(start_col + G1_ADD_LAMBDA_X1_X3_OFFSET), lambda),
+        (fp_mul_mod_y(start_col + G1_ADD_LAMBDA_X1_X3_OFFSET), fp_sub_mod_res(start_col + G1_ADD_X1_X3_OFFSET)),
+        (fp_sub_mod_x(start_col + G1_ADD_Y3_OFFSET), fp_mul_mod_res(start_col + G1_ADD_LAMBDA_X1_X3_OFFSET)),
+        (fp_sub_mod_y(start_col + G1_ADD_Y3_OFFSET), y1),
+    ]
+}
+
+/// Fills the stark trace of G1 point doubling. Input is an affine point (x, y). Needs 12 rows. Returns the doubled point.
+pub fn fill_trace_g1_double<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, p: &G1Affine, start_row: usize, end_row: usize, start_col: usize) -> G1Affine {
+    let res = p.double();
+    let two_y = p.y + p.y;
+    let lambda = if res.infinity {
+        Fp::zero()
+    } else {
+        let x_sq = p.x * p.x;
+        (x_sq + x_sq + x_sq) / two_y
+    };
+    for row in start_row..end_row + 1 {
+        trace[row][start_col + G1_DOUBLE_SELECTOR_OFFSET] = F::ONE;
+        trace[row][start_col + G1_DOUBLE_CHECK_OFFSET] = F::ONE;
+        assign_u32_in_series(trace, row, start_col + G1_DOUBLE_X_OFFSET, &p.x.0);
+        assign_u32_in_series(trace, row, start_col + G1_DOUBLE_Y_OFFSET, &p.y.0);
+        trace[row][start_col + G1_DOUBLE_INFINITY_OFFSET] = F::from_bool(p.infinity);
+        assign_u32_in_series(trace, row, start_col + G1_DOUBLE_LAMBDA_OFFSET, &lambda.0);
+        trace[row][start_col + G1_DOUBLE_RES_INFINITY_OFFSET] = F::from_bool(res.infinity);
+        assign_u32_in_series(trace, row, start_col + G1_DOUBLE_RES_X_OFFSET, &res.x.0);
+        assign_u32_in_series(trace, row, start_col + G1_DOUBLE_RES_Y_OFFSET, &res.y.0);
+    }
+    trace[end_row][start_col + G1_DOUBLE_SELECTOR_OFFSET] = F::ZERO;
+
+    let x_sq = fill_trace_fp_mul_mod(trace, &p.x.0, &p.x.0, start_row, end_row, start_col + G1_DOUBLE_X_SQ_OFFSET);
+    let lambda_2y = fill_trace_fp_mul_mod(trace, &lambda.0, &two_y.0, start_row, end_row, start_col + G1_DOUBLE_LAMBDA_2Y_OFFSET);
+    let lambda_sq = fill_trace_fp_mul_mod(trace, &lambda.0, &lambda.0, start_row, end_row, start_col + G1_DOUBLE_LAMBDA_SQ_OFFSET);
+    let x3 = Fp(lambda_sq) - (p.x + p.x);
+    let x_x3 = p.x - x3;
+    let lambda_x_x3 = fill_trace_fp_mul_mod(trace, &lambda.0, &x_x3.0, start_row, end_row, start_col + G1_DOUBLE_LAMBDA_X_X3_OFFSET);
+    for row in start_row..end_row + 1 {
+        let two_x_sq = fill_trace_addition_fp_mod(trace, &x_sq, &x_sq, row, start_col + G1_DOUBLE_2X_SQ_OFFSET);
+        let three_x_sq = fill_trace_addition_fp_mod(trace, &two_x_sq, &x_sq, row, start_col + G1_DOUBLE_3X_SQ_OFFSET);
+        fill_trace_addition_fp_mod(trace, &p.y.0, &p.y.0, row, start_col + G1_DOUBLE_2Y_OFFSET);
+        let two_x = fill_trace_addition_fp_mod(trace, &p.x.0, &p.x.0, row, start_col + G1_DOUBLE_2X_OFFSET);
+        fill_trace_subtraction_fp_mod(trace, &lambda_sq, &two_x, row, start_col + G1_DOUBLE_X3_OFFSET);
+        fill_trace_subtraction_fp_mod(trace, &p.x.0, &x3.0, row, start_col + G1_DOUBLE_X_X3_OFFSET);
+        fill_trace_subtraction_fp_mod(trace, &lambda_x_x3, &p.y.0, row, start_col + G1_DOUBLE_Y3_OFFSET);
+        if !res.infinity {
+            assert_eq!(lambda_2y, three_x_sq);
+        }
+    }
+    res
+}
+
+/// Constraints G1 point doubling.
+///
+/// Constraints the input, slope and result to be same across the rows wherever the selector is on. Constraints the inputs of every operation with the input point, the slope or the result of a previous operation.
+/// The infinity flags are constrained to be boolean, and the coordinates of the input are constrained to be zero if the input is the point at infinity, in which case the result is also the point at infinity.
+/// Constraints `lambda * 2y == 3x^2` unless the result infinity flag is set. If the result flag is set, y must be zero and the result coordinates must be zero. Otherwise the result is constrained to be (x3, y3).
+pub fn add_g1_double_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+    let selector = bit_selector_val * local_values[start_col + G1_DOUBLE_SELECTOR_OFFSET];
+    let check = bit_selector_val * local_values[start_col + G1_DOUBLE_CHECK_OFFSET];
+    let input_infinity = local_values[start_col + G1_DOUBLE_INFINITY_OFFSET];
+    let infinity = local_values[start_col + G1_DOUBLE_RES_INFINITY_OFFSET];
+
+    for col in [G1_DOUBLE_X_OFFSET, G1_DOUBLE_Y_OFFSET, G1_DOUBLE_LAMBDA_OFFSET, G1_DOUBLE_RES_X_OFFSET, G1_DOUBLE_RES_Y_OFFSET] {
+        for i in 0..12 {
+            yield_constr.constraint_transition(
+                selector *
+                (local_values[start_col + col + i] - next_values[start_col + col + i])
+            );
+        }
+    }
+    for col in [G1_DOUBLE_INFINITY_OFFSET, G1_DOUBLE_RES_INFINITY_OFFSET] {
+        yield_constr.constraint_transition(
+            selector *
+            (local_values[start_col + col] - next_values[start_col + col])
+        );
+    }
+
+    for (input_col, value_col) in g1_double_input_links(start_col) {
+        for i in 0..12 {
+            yield_constr.constraint(
+                check *
+                (local_values[input_col + i] - local_values[value_col + i])
+            );
+        }
+    }
+
+    yield_constr.constraint(check * input_infinity * (P::ONES - input_infinity));
+    yield_constr.constraint(check * infinity * (P::ONES - infinity));
+    yield_constr.constraint(check * input_infinity * (P::ONES - infinity));
+    let lambda_2y = fp_mul_mod_res(start_col + G1_DOUBLE_LAMBDA_2Y_OFFSET);
+    let three_x_sq = fp_add_mod_res(start_col + G1_DOUBLE_3X_SQ_OFFSET);
+    let x3 = fp_sub_mod_res(start_col + G1_DOUBLE_X3_OFFSET);
+    let y3 = fp_sub_mod_res(start_col + G1_DOUBLE_Y3_OFFSET);
+    for i in 0..12 {
+        yield_constr.constraint(
+            check * (P::ONES - infinity) *
+            (local_values[lambda_2y + i] - local_values[three_x_sq + i])
+        );
+        yield_constr.constraint(
+            check * infinity *
+            local_values[start_col + G1_DOUBLE_Y_OFFSET + i]
+        );
+        yield_constr.constraint(
+            check * input_infinity *
+            local_values[start_col + G1_DOUBLE_X_OFFSET + i]
+        );
+        yield_constr.constraint(
+            check *
+            (local_values[start_col + G1_DOUBLE_RES_X_OFFSET + i] - (P::ONES - infinity) * local_values[x3 + i])
+        );
+        yield_constr.constraint(
+            check *
+            (local_values[start_col + G1_DOUBLE_RES_Y_OFFSET + i] - (P::ONES - infinity) * local_values[y3 + i])
+        );
+    }
+
+    for col in [G1_DOUBLE_X_SQ_OFFSET, G1_DOUBLE_LAMBDA_2Y_OFFSET, G1_DOUBLE_LAMBDA_SQ_OFFSET, G1_DOUBLE_LAMBDA_X_X3_OFFSET] {
+        add_fp_mul_mod_constraints(local_values, next_values, yield_constr, start_col + col, bit_selector);
+    }
+    for col in [G1_DOUBLE_2X_SQ_OFFSET, G1_DOUBLE_3X_SQ_OFFSET, G1_DOUBLE_2Y_OFFSET, G1_DOUBLE_2X_OFFSET] {
+        add_addition_fp_mod_constraints(local_values, yield_constr, start_col + col, bit_selector);
+    }
+    for col in [G1_DOUBLE_X3_OFFSET, G1_DOUBLE_X_X3_OFFSET, G1_DOUBLE_Y3_OFFSET] {
+        add_subtraction_fp_mod_constraints(local_values, yield_constr, start_col + col, bit_selector);
+    }
+}
+
+pub fn add_g1_double_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let bit_selector_val = bit_selector.unwrap_or(builder.constant_extension(F::Extension::ONE));
+    let one = builder.constant_extension(F::Extension::ONE);
+    let selector = builder.mul_extension(bit_selector_val, local_values[start_col + G1_DOUBLE_SELECTOR_OFFSET]);
+    let check = builder.mul_extension(bit_selector_val, local_values[start_col + G1_DOUBLE_CHECK_OFFSET]);
+    let input_infinity = local_values[start_col + G1_DOUBLE_INFINITY_OFFSET];
+    let infinity = local_values[start_col + G1_DOUBLE_RES_INFINITY_OFFSET];
+    let not_input_infinity = builder.sub_extension(one, input_infinity);
+    let not_infinity = builder.sub_extension(one, infinity);
+
+    for col in [G1_DOUBLE_X_OFFSET, G1_DOUBLE_Y_OFFSET, G1_DOUBLE_LAMBDA_OFFSET, G1_DOUBLE_RES_X_OFFSET, G1_DOUBLE_RES_Y_OFFSET] {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[start_col + col + i], next_values[start_col + col + i]);
+            let c = builder.mul_extension(selector, c);
+            yield_constr.constraint_transition(builder, c);
+        }
+    }
+    for col in [G1_DOUBLE_INFINITY_OFFSET, G1_DOUBLE_RES_INFINITY_OFFSET] {
+        let c = builder.sub_extension(local_values[start_col + col], next_values[start_col + col]);
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint_transition(builder, c);
+    }
+
+    for (input_col, value_col) in g1_double_input_links(start_col) {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[input_col + i], local_values[value_col + i]);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+
+    let c = builder.mul_extension(input_infinity, not_input_infinity);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(infinity, not_infinity);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(input_infinity, not_infinity);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let lambda_2y = fp_mul_mod_res(start_col + G1_DOUBLE_LAMBDA_2Y_OFFSET);
+    let three_x_sq = fp_add_mod_res(start_col + G1_DOUBLE_3X_SQ_OFFSET);
+    let x3 = fp_sub_mod_res(start_col + G1_DOUBLE_X3_OFFSET);
+    let y3 = fp_sub_mod_res(start_col + G1_DOUBLE_Y3_OFFSET);
+    let check_not_infinity = builder.mul_extension(check, not_infinity);
+    let check_infinity = builder.mul_extension(check, infinity);
+    let check_input_infinity = builder.mul_extension(check, input_infinity);
+    for i in 0..12 {
+        let c = builder.sub_extension(local_values[lambda_2y + i], local_values[three_x_sq + i]);
+        let c = builder.mul_extension(check_not_infinity, c);
+        yield_constr.constraint(builder, c);
+
+        let c = builder.mul_extension(check_infinity, local_values[start_col + G1_DOUBLE_Y_OFFSET + i]);
+        yield_constr.constraint(builder, c);
+
+        let c = builder.mul_extension(check_input_infinity, local_values[start_col + G1_DOUBLE_X_OFFSET + i]);
+        yield_constr.constraint(builder, c);
+
+        let c = builder.mul_extension(not_infinity, local_values[x3 + i]);
+        let c = builder.sub_extension(local_values[start_col + G1_DOUBLE_RES_X_OFFSET + i], c);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+
+        let c = builder.mul_extension(not_infinity, local_values[y3 + i]);
+        let c = builder.sub_extension(local_values[start_col + G1_DOUBLE_RES_Y_OFFSET + i], c);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+    }
+
+    for col in [G1_DOUBLE_X_SQ_OFFSET, G1_DOUBLE_LAMBDA_2Y_OFFSET, G1_DOUBLE_LAMBDA_SQ_OFFSET, G1_DOUBLE_LAMBDA_X_X3_OFFSET] {
+        add_fp_mul_mod_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + col, bit_selector);
+    }
+    for col in [G1_DOUBLE_2X_SQ_OFFSET, G1_DOUBLE_3X_SQ_OFFSET, G1_DOUBLE_2Y_OFFSET, G1_DOUBLE_2X_OFFSET] {
+        add_addition_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + col, bit_selector);
+    }
+    for col in [G1_DOUBLE_X3_OFFSET, G1_DOUBLE_X_X3_OFFSET, G1_DOUBLE_Y3_OFFSET] {
+        add_subtraction_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + col, bit_selector);
+    }
+}
+
+/// Fills the stark trace of G1 point addition. Inputs are affine points (x1, y1) and (x2, y2). Needs 12 rows. Returns the sum of the points, or an error if they are the same finite point, whose sum is filled with [fill_trace_g1_double] instead.
+pub fn fill_trace_g1_add<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, p: &G1Affine, q: &G1Affine, start_row: usize, end_row: usize, start_col: usize) -> Result<G1Affine> {
+    let both_finite = !p.infinity && !q.infinity;
+    let opposite = both_finite && p.x == q.x;
+    ensure!(!opposite || p.y != q.y, "fill_trace_g1_add: addition of a point to itself, use fill_trace_g1_double");
+    let res = *p + *q;
+    let dx = q.x - p.x;
+    let (lambda, dx_inv) = if both_finite && !opposite {
+        let dx_inv = dx.invert();
+        ((q.y - p.y) * dx_inv, dx_inv)
+    } else {
+        (Fp::zero(), Fp::zero())
+    };
+    for row in start_row..end_row + 1 {
+        trace[row][start_col + G1_ADD_SELECTOR_OFFSET] = F::ONE;
+        trace[row][start_col + G1_ADD_CHECK_OFFSET] = F::ONE;
+        assign_u32_in_series(trace, row, start_col + G1_ADD_X1_OFFSET, &p.x.0);
+        assign_u32_in_series(trace, row, start_col + G1_ADD_Y1_OFFSET, &p.y.0);
+        trace[row][start_col + G1_ADD_INFINITY1_OFFSET] = F::from_bool(p.infinity);
+        assign_u32_in_series(trace, row, start_col + G1_ADD_X2_OFFSET, &q.x.0);
+        assign_u32_in_series(trace, row, start_col + G1_ADD_Y2_OFFSET, &q.y.0);
+        trace[row][start_col + G1_ADD_INFINITY2_OFFSET] = F::from_bool(q.infinity);
+        trace[row][start_col + G1_ADD_BOTH_FINITE_OFFSET] = F::from_bool(both_finite);
+        trace[row][start_col + G1_ADD_OPPOSITE_OFFSET] = F::from_bool(opposite);
+        assign_u32_in_series(trace, row, start_col + G1_ADD_LAMBDA_OFFSET, &lambda.0);
+        assign_u32_in_series(trace, row, start_col + G1_ADD_DX_INV_OFFSET, &dx_inv.0);
+        trace[row][start_col + G1_ADD_RES_INFINITY_OFFSET] = F::from_bool(res.infinity);
+        assign_u32_in_series(trace, row, start_col + G1_ADD_RES_X_OFFSET, &res.x.0);
+        assign_u32_in_series(trace, row, start_col + G1_ADD_RES_Y_OFFSET, &res.y.0);
+    }
+    trace[end_row][start_col + G1_ADD_SELECTOR_OFFSET] = F::ZERO;
+
+    fill_trace_fp_mul_mod(trace, &dx.0, &dx_inv.0, start_row, end_row, start_col + G1_ADD_DX_INV_MUL_OFFSET);
+    fill_trace_fp_mul_mod(trace, &lambda.0, &dx.0, start_row, end_row, start_col + G1_ADD_LAMBDA_DX_OFFSET);
+    let lambda_sq = fill_trace_fp_mul_mod(trace, &lambda.0, &lambda.0, start_row, end_row, start_col + G1_ADD_LAMBDA_SQ_OFFSET);
+    let x3 = Fp(lambda_sq) - (p.x + q.x);
+    let x1_x3 = p.x - x3;
+    let lambda_x1_x3 = fill_trace_fp_mul_mod(trace, &lambda.0, &x1_x3.0, start_row, end_row, start_col + G1_ADD_LAMBDA_X1_X3_OFFSET);
+    for row in start_row..end_row + 1 {
+        fill_trace_subtraction_fp_mod(trace, &q.x.0, &p.x.0, row, start_col + G1_ADD_DX_OFFSET);
+        fill_trace_subtraction_fp_mod(trace, &q.y.0, &p.y.0, row, start_col + G1_ADD_DY_OFFSET);
+        fill_trace_addition_fp_mod(trace, &p.y.0, &q.y.0, row, start_col + G1_ADD_Y_SUM_OFFSET);
+        let x1_x2 = fill_trace_addition_fp_mod(trace, &p.x.0, &q.x.0, row, start_col + G1_ADD_X1_X2_OFFSET);
+        fill_trace_subtraction_fp_mod(trace, &lambda_sq, &x1_x2, row, start_col + G1_ADD_X3_OFFSET);
+        fill_trace_subtraction_fp_mod(trace, &p.x.0, &x3.0, row, start_col + G1_ADD_X1_X3_OFFSET);
+        fill_trace_subtraction_fp_mod(trace, &lambda_x1_x3, &p.y.0, row, start_col + G1_ADD_Y3_OFFSET);
+    }
+    Ok(res)
+}
+
+/// Fills the selectors and the inputs of the addition of `p` with itself, and leaves the rest of the addition unfilled. Used where the constraints of the addition are turned off by its bit selector and the sum is taken from [fill_trace_g1_double] instead.
+pub fn fill_trace_g1_add_inputs<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, p: &G1Affine, start_row: usize, end_row: usize, start_col: usize) {
+    for row in start_row..end_row + 1 {
+        trace[row][start_col + G1_ADD_SELECTOR_OFFSET] = F::from_bool(row != end_row);
+        trace[row][start_col + G1_ADD_CHECK_OFFSET] = F::ONE;
+        for (x, y, infinity) in [(G1_ADD_X1_OFFSET, G1_ADD_Y1_OFFSET, G1_ADD_INFINITY1_OFFSET), (G1_ADD_X2_OFFSET, G1_ADD_Y2_OFFSET, G1_ADD_INFINITY2_OFFSET)] {
+            assign_u32_in_series(trace, row, start_col + x, &p.x.0);
+            assign_u32_in_series(trace, row, start_col + y, &p.y.0);
+            trace[row][start_col + infinity] = F::from_bool(p.infinity);
+        }
+    }
+}
+
+/// Constraints G1 point addition.
+///
+/// Constraints the inputs, flags, slope and result to be same across the rows wherever the selector is on. Constraints the inputs of every operation with the input points, the slope or the result of a previous operation.
+/// The infinity and opposite flags are constrained to be boolean, and the coordinates of an input are constrained to be zero if it is the point at infinity. The opposite flag can only be set if both inputs are finite, in which case x1 == x2 and y1 + y2 == 0.
+/// For the generic addition, constraints `(x2 - x1) * dx_inv == 1` and `lambda * (x2 - x1) == y2 - y1`.
+/// Then constraints the result as `P + O = P`, `O + P = P`, `P + (-P) = O`, or (x3, y3) for the generic addition.
+pub fn add_g1_add_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+    let selector = bit_selector_val * local_values[start_col + G1_ADD_SELECTOR_OFFSET];
+    let check = bit_selector_val * local_values[start_col + G1_ADD_CHECK_OFFSET];
+    let infinity1 = local_values[start_col + G1_ADD_INFINITY1_OFFSET];
+    let infinity2 = local_values[start_col + G1_ADD_INFINITY2_OFFSET];
+    let both_finite = local_values[start_col + G1_ADD_BOTH_FINITE_OFFSET];
+    let opposite = local_values[start_col + G1_ADD_OPPOSITE_OFFSET];
+    let generic = both_finite - opposite;
+    let infinity = local_values[start_col + G1_ADD_RES_INFINITY_OFFSET];
+
+    for col in [G1_ADD_X1_OFFSET, G1_ADD_Y1_OFFSET, G1_ADD_X2_OFFSET, G1_ADD_Y2_OFFSET, G1_ADD_LAMBDA_OFFSET, G1_ADD_DX_INV_OFFSET, G1_ADD_RES_X_OFFSET, G1_ADD_RES_Y_OFFSET] {
+        for i in 0..12 {
+            yield_constr.constraint_transition(
+                selector *
+                (local_values[start_col + col + i] - next_values[start_col + col + i])
+            );
+        }
+    }
+    for col in [G1_ADD_INFINITY1_OFFSET, G1_ADD_INFINITY2_OFFSET, G1_ADD_BOTH_FINITE_OFFSET, G1_ADD_OPPOSITE_OFFSET, G1_ADD_RES_INFINITY_OFFSET] {
+        yield_constr.constraint_transition(
+            selector *
+            (local_values[start_col + col] - next_values[start_col + col])
+        );
+    }
+
+    for (input_col, value_col) in g1_add_input_links(start_col) {
+        for i in 0..12 {
+            yield_constr.constraint(
+                check *
+                (local_values[input_col + i] - local_values[value_col + i])
+            );
+        }
+    }
+
+    yield_constr.constraint(check * infinity1 * (P::ONES - infinity1));
+    yield_constr.constraint(check * infinity2 * (P::ONES - infinity2));
+    yield_constr.constraint(check * opposite * (P::ONES - opposite));
+    yield_constr.constraint(check * (both_finite - (P::ONES - infinity1) * (P::ONES - infinity2)));
+    yield_constr.constraint(check * (P::ONES - both_finite) * opposite);
+    yield_constr.constraint(check * (infinity - infinity1 * infinity2 - opposite));
+
+    let dx_inv_mul = fp_mul_mod_res(start_col + G1_ADD_DX_INV_MUL_OFFSET);
+    let lambda_dx = fp_mul_mod_res(start_col + G1_ADD_LAMBDA_DX_OFFSET);
+    let dy = fp_sub_mod_res(start_col + G1_ADD_DY_OFFSET);
+    let y_sum = fp_add_mod_res(start_col + G1_ADD_Y_SUM_OFFSET);
+    let x3 = fp_sub_mod_res(start_col + G1_ADD_X3_OFFSET);
+    let y3 = fp_sub_mod_res(start_col + G1_ADD_Y3_OFFSET);
+    for i in 0..12 {
+        let x1 = local_values[start_col + G1_ADD_X1_OFFSET + i];
+        let y1 = local_values[start_col + G1_ADD_Y1_OFFSET + i];
+        let x2 = local_values[start_col + G1_ADD_X2_OFFSET + i];
+        let y2 = local_values[start_col + G1_ADD_Y2_OFFSET + i];
+        yield_constr.constraint(check * infinity1 * x1);
+        yield_constr.constraint(check * infinity1 * y1);
+        yield_constr.constraint(check * infinity2 * x2);
+        yield_constr.constraint(check * infinity2 * y2);
+        yield_constr.constraint(check * opposite * (x1 - x2));
+        yield_constr.constraint(check * opposite * local_values[y_sum + i]);
+        let one = if i == 0 { P::ONES } else { P::ZEROS };
+        yield_constr.constraint(check * generic * (local_values[dx_inv_mul + i] - one));
+        yield_constr.constraint(check * generic * (local_values[lambda_dx + i] - local_values[dy + i]));
+        yield_constr.constraint(
+            check *
+            (local_values[start_col + G1_ADD_RES_X_OFFSET + i] - infinity1 * x2 - infinity2 * x1 - generic * local_values[x3 + i])
+        );
+        yield_constr.constraint(
+            check *
+            (local_values[start_col + G1_ADD_RES_Y_OFFSET + i] - infinity1 * y2 - infinity2 * y1 - generic * local_values[y3 + i])
+        );
+    }
+
+    for col in [G1_ADD_DX_INV_MUL_OFFSET, G1_ADD_LAMBDA_DX_OFFSET, G1_ADD_LAMBDA_SQ_OFFSET, G1_ADD_LAMBDA_X1_X3_OFFSET] {
+        add_fp_mul_mod_constraints(local_values, next_values, yield_constr, start_col + col, bit_selector);
+    }
+    for col in [G1_ADD_Y_SUM_OFFSET, G1_ADD_X1_X2_OFFSET] {
+        add_addition_fp_mod_constraints(local_values, yield_constr, start_col + col, bit_selector);
+    }
+    for col in [G1_ADD_DX_OFFSET, G1_ADD_DY_OFFSET, G1_ADD_X3_OFFSET, G1_ADD_X1_X3_OFFSET, G1_ADD_Y3_OFFSET] {
+        add_subtraction_fp_mod_constraints(local_values, yield_constr, start_col + col, bit_selector);
+    }
+}
+
+pub fn add_g1_add_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let bit_selector_val = bit_selector.unwrap_or(builder.constant_extension(F::Extension::ONE));
+    let one = builder.constant_extension(F::Extension::ONE);
+    let selector = builder.mul_extension(bit_selector_val, local_values[start_col + G1_ADD_SELECTOR_OFFSET]);
+    let check = builder.mul_extension(bit_selector_val, local_values[start_col + G1_ADD_CHECK_OFFSET]);
+    let infinity1 = local_values[start_col + G1_ADD_INFINITY1_OFFSET];
+    let infinity2 = local_values[start_col + G1_ADD_INFINITY2_OFFSET];
+    let both_finite = local_values[start_col + G1_ADD_BOTH_FINITE_OFFSET];
+    let opposite = local_values[start_col + G1_ADD_OPPOSITE_OFFSET];
+    let generic = builder.sub_extension(both_finite, opposite);
+    let infinity = local_values[start_col + G1_ADD_RES_INFINITY_OFFSET];
+    let not_infinity1 = builder.sub_extension(one, infinity1);
+    let not_infinity2 = builder.sub_extension(one, infinity2);
+    let not_opposite = builder.sub_extension(one, opposite);
+    let not_both_finite = builder.sub_extension(one, both_finite);
+
+    for col in [G1_ADD_X1_OFFSET, G1_ADD_Y1_OFFSET, G1_ADD_X2_OFFSET, G1_ADD_Y2_OFFSET, G1_ADD_LAMBDA_OFFSET, G1_ADD_DX_INV_OFFSET, G1_ADD_RES_X_OFFSET, G1_ADD_RES_Y_OFFSET] {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[start_col + col + i], next_values[start_col + col + i]);
+            let c = builder.mul_extension(selector, c);
+            yield_constr.constraint_transition(builder, c);
+        }
+    }
+    for col in [G1_ADD_INFINITY1_OFFSET, G1_ADD_INFINITY2_OFFSET, G1_ADD_BOTH_FINITE_OFFSET, G1_ADD_OPPOSITE_OFFSET, G1_ADD_RES_INFINITY_OFFSET] {
+        let c = builder.sub_extension(local_values[start_col + col], next_values[start_col + col]);
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint_transition(builder, c);
+    }
+
+    for (input_col, value_col) in g1_add_input_links(start_col) {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[input_col + i], local_values[value_col + i]);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+
+    let c = builder.mul_extension(infinity1, not_infinity1);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(infinity2, not_infinity2);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(opposite, not_opposite);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(not_infinity1, not_infinity2);
+    let c = builder.sub_extension(both_finite, c);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(not_both_finite, opposite);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(infinity1, infinity2);
+    let c = builder.sub_extension(infinity, c);
+    let c = builder.sub_extension(c, opposite);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+
+    let dx_inv_mul = fp_mul_mod_res(start_col + G1_ADD_DX_INV_MUL_OFFSET);
+    let lambda_dx = fp_mul_mod_res(start_col + G1_ADD_LAMBDA_DX_OFFSET);
+    let dy = fp_sub_mod_res(start_col + G1_ADD_DY_OFFSET);
+    let y_sum = fp_add_mod_res(start_col + G1_ADD_Y_SUM_OFFSET);
+    let x3 = fp_sub_mod_res(start_col + G1_ADD_X3_OFFSET);
+    let y3 = fp_sub_mod_res(start_col + G1_ADD_Y3_OFFSET);
+    let check_infinity1 = builder.mul_extension(check, infinity1);
+    let check_infinity2 = builder.mul_extension(check, infinity2);
+    let check_opposite = builder.mul_extension(check, opposite);
+    let check_generic = builder.mul_extension(check, generic);
+    for i in 0..12 {
+        let x1 = local_values[start_col + G1_ADD_X1_OFFSET + i];
+        let y1 = local_values[start_col + G1_ADD_Y1_OFFSET + i];
+        let x2 = local_values[start_col + G1_ADD_X2_OFFSET + i];
+        let y2 = local_values[start_col + G1_ADD_Y2_OFFSET + i];
+        let c = builder.mul_extension(check_infinity1, x1);
+        yield_constr.constraint(builder, c);
+        let c = builder.mul_extension(check_infinity1, y1);
+        yield_constr.constraint(builder, c);
+        let c = builder.mul_extension(check_infinity2, x2);
+        yield_constr.constraint(builder, c);
+        let c = builder.mul_extension(check_infinity2, y2);
+        yield_constr.constraint(builder, c);
+
+        let c = builder.sub_extension(x1, x2);
+        let c = builder.mul_extension(check_opposite, c);
+        yield_constr.constraint(builder, c);
+        let c = builder.mul_extension(check_opposite, local_values[y_sum + i]);
+        yield_constr.constraint(builder, c);
+
+        let c = if i == 0 {
+            builder.sub_extension(local_values[dx_inv_mul + i], one)
+        } else {
+            local_values[dx_inv_mul + i]
+        };
+        let c = builder.mul_extension(check_generic, c);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(local_values[lambda_dx + i], local_values[dy + i]);
+        let c = builder.mul_extension(check_generic, c);
+        yield_constr.constraint(builder, c);
+
+        let t1 = builder.mul_extension(infinity1, x2);
+        let t2 = builder.mul_extension(infinity2, x1);
+        let t3 = builder.mul_extension(generic, local_values[x3 + i]);
+        let c = builder.sub_extension(local_values[start_col + G1_ADD_RES_X_OFFSET + i], t1);
+        let c = builder.sub_extension(c, t2);
+        let c = builder.sub_extension(c, t3);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+
+        let t1 = builder.mul_extension(infinity1, y2);
+        let t2 = builder.mul_extension(infinity2, y1);
+        let t3 = builder.mul_extension(generic, local_values[y3 + i]);
+        let c = builder.sub_extension(local_values[start_col + G1_ADD_RES_Y_OFFSET + i], t1);
+        let c = builder.sub_extension(c, t2);
+        let c = builder.sub_extension(c, t3);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+    }
+
+    for col in [G1_ADD_DX_INV_MUL_OFFSET, G1_ADD_LAMBDA_DX_OFFSET, G1_ADD_LAMBDA_SQ_OFFSET, G1_ADD_LAMBDA_X1_X3_OFFSET] {
+        add_fp_mul_mod_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + col, bit_selector);
+    }
+    for col in [G1_ADD_Y_SUM_OFFSET, G1_ADD_X1_X2_OFFSET] {
+        add_addition_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + col, bit_selector);
+    }
+    for col in [G1_ADD_DX_OFFSET, G1_ADD_DY_OFFSET, G1_ADD_X3_OFFSET, G1_ADD_X1_X3_OFFSET, G1_ADD_Y3_OFFSET] {
+        add_subtraction_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + col, bit_selector);
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct G1DoubleStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> G1DoubleStark<F, D> {
+    /// Rows used by one G1 doubling, 12. The stark needs a power of two number of rows of at least this many.
+    pub fn required_rows() -> usize {
+        12
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(p: &G1Affine, res: &G1Affine) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for i in 0..12 {
+            public_inputs[PIS_X_OFFSET + i] = F::from_canonical_u32(p.x.0[i]);
+            public_inputs[PIS_Y_OFFSET + i] = F::from_canonical_u32(p.y.0[i]);
+            public_inputs[PIS_RES_X_OFFSET + i] = F::from_canonical_u32(res.x.0[i]);
+            public_inputs[PIS_RES_Y_OFFSET + i] = F::from_canonical_u32(res.y.0[i]);
+        }
+        public_inputs[PIS_INFINITY_OFFSET] = F::from_bool(p.infinity);
+        public_inputs[PIS_RES_INFINITY_OFFSET] = F::from_bool(res.infinity);
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, p: &G1Affine) -> Vec<[F; TOTAL_COLUMNS]> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_g1_double(&mut trace, p, 0, 11, 0);
+        trace
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for G1DoubleStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[G1_DOUBLE_CHECK_OFFSET] - P::ONES);
+        for i in 0..12 {
+            yield_constr.constraint(
+                local_values[G1_DOUBLE_CHECK_OFFSET] *
+                (local_values[G1_DOUBLE_X_OFFSET + i] - public_inputs[PIS_X_OFFSET + i])
+            );
+            yield_constr.constraint(
+                local_values[G1_DOUBLE_CHECK_OFFSET] *
+                (local_values[G1_DOUBLE_Y_OFFSET + i] - public_inputs[PIS_Y_OFFSET + i])
+            );
+            yield_constr.constraint(
+                local_values[G1_DOUBLE_CHECK_OFFSET] *
+                (local_values[G1_DOUBLE_RES_X_OFFSET + i] - public_inputs[PIS_RES_X_OFFSET + i])
+            );
+            yield_constr.constraint(
+                local_values[G1_DOUBLE_CHECK_OFFSET] *
+                (local_values[G1_DOUBLE_RES_Y_OFFSET + i] - public_inputs[PIS_RES_Y_OFFSET + i])
+            );
+        }
+        yield_constr.constraint(
+            local_values[G1_DOUBLE_CHECK_OFFSET] *
+            (local_values[G1_DOUBLE_INFINITY_OFFSET] - public_inputs[PIS_INFINITY_OFFSET])
+        );
+        yield_constr.constraint(
+            local_values[G1_DOUBLE_CHECK_OFFSET] *
+            (local_values[G1_DOUBLE_RES_INFINITY_OFFSET] - public_inputs[PIS_RES_INFINITY_OFFSET])
+        );
+        add_g1_double_constraints(local_values, next_values, yield_constr, 0, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[G1_DOUBLE_CHECK_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[G1_DOUBLE_X_OFFSET + i], public_inputs[PIS_X_OFFSET + i]);
+            let c = builder.mul_extension(local_values[G1_DOUBLE_CHECK_OFFSET], c);
+            yield_constr.constraint(builder, c);
+
+            let c = builder.sub_extension(local_values[G1_DOUBLE_Y_OFFSET + i], public_inputs[PIS_Y_OFFSET + i]);
+            let c = builder.mul_extension(local_values[G1_DOUBLE_CHECK_OFFSET], c);
+            yield_constr.constraint(builder, c);
+
+            let c = builder.sub_extension(local_values[G1_DOUBLE_RES_X_OFFSET + i], public_inputs[PIS_RES_X_OFFSET + i]);
+            let c = builder.mul_extension(local_values[G1_DOUBLE_CHECK_OFFSET], c);
+            yield_constr.constraint(builder, c);
+
+            let c = builder.sub_extension(local_values[G1_DOUBLE_RES_Y_OFFSET + i], public_inputs[PIS_RES_Y_OFFSET + i]);
+            let c = builder.mul_extension(local_values[G1_DOUBLE_CHECK_OFFSET], c);
+            yield_constr.constraint(builder, c);
+        }
+        let c = builder.sub_extension(local_values[G1_DOUBLE_INFINITY_OFFSET], public_inputs[PIS_INFINITY_OFFSET]);
+        let c = builder.mul_extension(local_values[G1_DOUBLE_CHECK_OFFSET], c);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(local_values[G1_DOUBLE_RES_INFINITY_OFFSET], public_inputs[PIS_RES_INFINITY_OFFSET]);
+        let c = builder.mul_extension(local_values[G1_DOUBLE_CHECK_OFFSET], c);
+        yield_constr.constraint(builder, c);
+        add_g1_double_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Fq, G1Affine as ArkG1Affine};
+    use ark_ec::{AffineRepr, CurveGroup, Group};
+    use ark_ff::PrimeField;
+    use plonky2::{field::types::{Field, PrimeField64}, plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{Fp, G1Affine};
+
+    use super::{G1DoubleStark, G1_DOUBLE_RES_INFINITY_OFFSET, G1_DOUBLE_RES_X_OFFSET, G1_DOUBLE_RES_Y_OFFSET, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = G1DoubleStark<F, D>;
+
+    fn g1_to_ark(p: &G1Affine) -> ArkG1Affine {
+        if p.infinity {
+            return ArkG1Affine::identity();
+        }
+        let fq = |x: &Fp| Fq::from_be_bytes_mod_order(&x.to_bytes_be());
+        ArkG1Affine::new(fq(&p.x), fq(&p.y))
+    }
+
+    /// Proves and verifies the doubling of `p`, and returns the result read from the output columns of the trace.
+    fn prove_and_verify_double(p: &G1Affine) -> G1Affine {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(16);
+        let trace = stark.generate_trace(p);
+        let limbs = |offset: usize| Fp(std::array::from_fn(|i| trace[0][offset + i].to_canonical_u64() as u32));
+        let res = G1Affine {
+            x: limbs(G1_DOUBLE_RES_X_OFFSET),
+            y: limbs(G1_DOUBLE_RES_Y_OFFSET),
+            infinity: trace[0][G1_DOUBLE_RES_INFINITY_OFFSET] == F::ONE,
+        };
+        let public_inputs = S::public_inputs(p, &res);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        res
+    }
+
+    #[test]
+    fn test_g1_double_generator_repeatedly() {
+        let mut p = G1Affine::generator();
+        let mut expected = ArkG1Affine::generator().into_group();
+        assert_eq!(g1_to_ark(&p), expected.into_affine());
+        for _ in 0..4 {
+            let res = prove_and_verify_double(&p);
+            assert_eq!(res, p.double());
+            assert!(!res.infinity);
+            assert!(res.is_on_curve());
+            expected.double_in_place();
+            assert_eq!(g1_to_ark(&res), expected.into_affine());
+            p = res;
+        }
+    }
+
+    /// (1, 0) is not on the curve, BLS12-381 G1 has no point with y = 0. Only tests the branch of the gadget which sets the result to infinity when y is zero.
+    #[test]
+    fn test_g1_double_y_zero_branch_off_curve() {
+        let p = G1Affine {
+            x: Fp::one(),
+            y: Fp::zero(),
+            infinity: false,
+        };
+        let res = prove_and_verify_double(&p);
+        assert_eq!(res, G1Affine::identity());
+    }
+
+    #[test]
+    fn test_g1_double_identity() {
+        let res = prove_and_verify_double(&G1Affine::identity());
+        assert_eq!(res, G1Affine::identity());
+        assert_eq!(g1_to_ark(&res), ArkG1Affine::identity().into_group().double().into_affine());
+    }
+}
@@ -0,0 +1,247 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use anyhow::Result;
+
+use crate::native::G1Affine;
+
+use crate::g1::*;
+
+pub const TOTAL_COLUMNS: usize = G1_ADD_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the first point (x, y) and its infinity flag, the second point (x, y) and its infinity flag, then the result point (x, y) and the infinity flag of the result.
+*/
+pub const PIS_X1_OFFSET: usize = 0;
+pub const PIS_Y1_OFFSET: usize = PIS_X1_OFFSET + 12;
+pub const PIS_INFINITY1_OFFSET: usize = PIS_Y1_OFFSET + 12;
+pub const PIS_X2_OFFSET: usize = PIS_INFINITY1_OFFSET + 1;
+pub const PIS_Y2_OFFSET: usize = PIS_X2_OFFSET + 12;
+pub const PIS_INFINITY2_OFFSET: usize = PIS_Y2_OFFSET + 12;
+pub const PIS_RES_X_OFFSET: usize = PIS_INFINITY2_OFFSET + 1;
+pub const PIS_RES_Y_OFFSET: usize = PIS_RES_X_OFFSET + 12;
+pub const PIS_RES_INFINITY_OFFSET: usize = PIS_RES_Y_OFFSET + 12;
+pub const PUBLIC_INPUTS: usize = PIS_RES_INFINITY_OFFSET + 1;
+
+/// Pairs of (trace column, public input) which are constrained to be equal wherever G1_ADD_CHECK_OFFSET is set, along with the number of limbs.
+const PIS_LINKS: [(usize, usize, usize); 9] = [
+    (G1_ADD_X1_OFFSET, PIS_X1_OFFSET, 12),
+    (G1_ADD_Y1_OFFSET, PIS_Y1_OFFSET, 12),
+    (G1_ADD_INFINITY1_OFFSET, PIS_INFINITY1_OFFSET, 1),
+    (G1_ADD_X2_OFFSET, PIS_X2_OFFSET, 12),
+    (G1_ADD_Y2_OFFSET, PIS_Y2_OFFSET, 12),
+    (G1_ADD_INFINITY2_OFFSET, PIS_INFINITY2_OFFSET, 1),
+    (G1_ADD_RES_X_OFFSET, PIS_RES_X_OFFSET, 12),
+    (G1_ADD_RES_Y_OFFSET, PIS_RES_Y_OFFSET, 12),
+    (G1_ADD_RES_INFINITY_OFFSET, PIS_RES_INFINITY_OFFSET, 1),
+];
+
+#[derive(Clone, Copy)]
+pub struct G1AddStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> G1AddStark<F, D> {
+    /// Rows used by one G1 addition, 12. The stark needs a power of two number of rows of at least this many.
+    pub fn required_rows() -> usize {
+        12
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(p: &G1Affine, q: &G1Affine, res: &G1Affine) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for i in 0..12 {
+            public_inputs[PIS_X1_OFFSET + i] = F::from_canonical_u32(p.x.0[i]);
+            public_inputs[PIS_Y1_OFFSET + i] = F::from_canonical_u32(p.y.0[i]);
+            public_inputs[PIS_X2_OFFSET + i] = F::from_canonical_u32(q.x.0[i]);
+            public_inputs[PIS_Y2_OFFSET + i] = F::from_canonical_u32(q.y.0[i]);
+            public_inputs[PIS_RES_X_OFFSET + i] = F::from_canonical_u32(res.x.0[i]);
+            public_inputs[PIS_RES_Y_OFFSET + i] = F::from_canonical_u32(res.y.0[i]);
+        }
+        public_inputs[PIS_INFINITY1_OFFSET] = F::from_bool(p.infinity);
+        public_inputs[PIS_INFINITY2_OFFSET] = F::from_bool(q.infinity);
+        public_inputs[PIS_RES_INFINITY_OFFSET] = F::from_bool(res.infinity);
+        public_inputs
+    }
+
+    /// Fills the trace for the addition of `p` and `q`. Returns an error if they are the same finite point, see [fill_trace_g1_add].
+    pub fn generate_trace(&self, p: &G1Affine, q: &G1Affine) -> Result<Vec<[F; TOTAL_COLUMNS]>> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_g1_add(&mut trace, p, q, 0, 11, 0)?;
+        Ok(trace)
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for G1AddStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[G1_ADD_CHECK_OFFSET] - P::ONES);
+        for (col, pis, limbs) in PIS_LINKS {
+            for i in 0..limbs {
+                yield_constr.constraint(
+                    local_values[G1_ADD_CHECK_OFFSET] *
+                    (local_values[col + i] - public_inputs[pis + i])
+                );
+            }
+        }
+        add_g1_add_constraints(local_values, next_values, yield_constr, 0, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[G1_ADD_CHECK_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for (col, pis, limbs) in PIS_LINKS {
+            for i in 0..limbs {
+                let c = builder.sub_extension(local_values[col + i], public_inputs[pis + i]);
+                let c = builder.mul_extension(local_values[G1_ADD_CHECK_OFFSET], c);
+                yield_constr.constraint(builder, c);
+            }
+        }
+        add_g1_add_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Fq, Fr, G1Affine as ArkG1Affine, G1Projective as ArkG1Projective};
+    use ark_ec::{AffineRepr, CurveGroup, Group};
+    use ark_ff::PrimeField;
+    use plonky2::{field::types::{Field, PrimeField64}, plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::g1::{G1_ADD_RES_INFINITY_OFFSET, G1_ADD_RES_X_OFFSET, G1_ADD_RES_Y_OFFSET};
+    use crate::native::{Fp, G1Affine};
+
+    use super::{G1AddStark, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = G1AddStark<F, D>;
+
+    fn g1_to_ark(p: &G1Affine) -> ArkG1Affine {
+        if p.infinity {
+            return ArkG1Affine::identity();
+        }
+        let fq = |x: &Fp| Fq::from_be_bytes_mod_order(&x.to_bytes_be());
+        ArkG1Affine::new(fq(&p.x), fq(&p.y))
+    }
+
+    /// Proves and verifies the addition of `p` and `q`, and returns the result read from the output columns of the trace, checked against arkworks.
+    fn prove_and_verify_add(p: &G1Affine, q: &G1Affine) -> G1Affine {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(16);
+        let trace = stark.generate_trace(p, q).unwrap();
+        let limbs = |offset: usize| Fp(std::array::from_fn(|i| trace[0][offset + i].to_canonical_u64() as u32));
+        let res = G1Affine {
+            x: limbs(G1_ADD_RES_X_OFFSET),
+            y: limbs(G1_ADD_RES_Y_OFFSET),
+            infinity: trace[0][G1_ADD_RES_INFINITY_OFFSET] == F::ONE,
+        };
+        assert_eq!(g1_to_ark(&res), (g1_to_ark(p) + g1_to_ark(q)).into_affine());
+        let public_inputs = S::public_inputs(p, q, &res);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        res
+    }
+
+    #[test]
+    fn test_g1_add_generic() {
+        let g = G1Affine::generator();
+        let res = prove_and_verify_add(&g, &g.double());
+        assert!(res.is_on_curve());
+        assert_eq!(g1_to_ark(&res), (ArkG1Projective::generator() * Fr::from(3u32)).into_affine());
+    }
+
+    #[test]
+    fn test_g1_add_identity_right() {
+        let g = G1Affine::generator();
+        assert_eq!(prove_and_verify_add(&g, &G1Affine::identity()), g);
+    }
+
+    #[test]
+    fn test_g1_add_identity_left() {
+        let g = G1Affine::generator();
+        assert_eq!(prove_and_verify_add(&G1Affine::identity(), &g), g);
+    }
+
+    #[test]
+    fn test_g1_add_opposite() {
+        let g = G1Affine::generator();
+        assert_eq!(prove_and_verify_add(&g, &-g), G1Affine::identity());
+    }
+
+    #[test]
+    fn test_g1_add_identity_both() {
+        assert_eq!(prove_and_verify_add(&G1Affine::identity(), &G1Affine::identity()), G1Affine::identity());
+    }
+
+    #[test]
+    fn test_g1_add_same_point() {
+        let g = G1Affine::generator();
+        assert!(S::new(16).generate_trace(&g, &g).is_err());
+    }
+}
@@ -0,0 +1,346 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use anyhow::Result;
+
+use crate::native::{get_bits_as_array, get_bls_12_381_parameter, G1Affine};
+
+use crate::g1::*;
+use crate::g1_scalar_mul::*;
+
+/// Number of rows needed by the cofactor clearing.
+pub const G1_CLEAR_COFACTOR_ROWS: usize = G1_SCALAR_MUL_ROWS;
+
+// G1 cofactor clearing layout offsets
+/*
+    These trace offsets are for clearing the cofactor of a G1 point (super::native::clear_cofactor_g1), as `P + [|x|]P`. It needs G1_CLEAR_COFACTOR_ROWS rows.
+    G1_CLEAR_COFACTOR_SCALAR_MUL_OFFSET -> offset where the scalar multiplication of the point by |x| is done. The scalar bits are constrained to be the bits of |x|.
+    G1_CLEAR_COFACTOR_ADD_OFFSET -> offset where the addition of [|x|]P with P is done, in the rows of the last step of the scalar multiplication. Its inputs and result are read in the last row of the scalar multiplication.
+*/
+pub const G1_CLEAR_COFACTOR_SCALAR_MUL_OFFSET: usize = 0;
+pub const G1_CLEAR_COFACTOR_ADD_OFFSET: usize = G1_CLEAR_COFACTOR_SCALAR_MUL_OFFSET + G1_SCALAR_MUL_TOTAL;
+pub const G1_CLEAR_COFACTOR_TOTAL: usize = G1_CLEAR_COFACTOR_ADD_OFFSET + G1_ADD_TOTAL;
+
+pub const TOTAL_COLUMNS: usize = G1_CLEAR_COFACTOR_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the raw curve point (x, y) and its infinity flag, then the cleared point (x, y) and its infinity flag.
+*/
+pub const PIS_X_OFFSET: usize = 0;
+pub const PIS_Y_OFFSET: usize = PIS_X_OFFSET + 12;
+pub const PIS_INFINITY_OFFSET: usize = PIS_Y_OFFSET + 12;
+pub const PIS_RES_X_OFFSET: usize = PIS_INFINITY_OFFSET + 1;
+pub const PIS_RES_Y_OFFSET: usize = PIS_RES_X_OFFSET + 12;
+pub const PIS_RES_INFINITY_OFFSET: usize = PIS_RES_Y_OFFSET + 12;
+pub const PUBLIC_INPUTS: usize = PIS_RES_INFINITY_OFFSET + 1;
+
+/// Bits of |x|, least significant bit first, as set in the scalar bits of the scalar multiplication.
+fn bls_parameter_bits() -> Vec<u32> {
+    get_scalar_limbs(&get_bls_12_381_parameter()).iter().map(|limb| get_bits_as_array(*limb)).collect::<Vec<[u32; 32]>>().concat()
+}
+
+/// Pairs of (input column of the addition, value column) which are constrained to be equal in the last row of the scalar multiplication.
+fn g1_clear_cofactor_add_links(start_col: usize) -> Vec<(usize, usize)> {
+    let scalar_mul_col = start_col + G1_CLEAR_COFACTOR_SCALAR_MUL_OFFSET;
+    let add_col = start_col + G1_CLEAR_COFACTOR_ADD_OFFSET;
+    let mut links = vec![];
+    for i in 0..12 {
+        links.push((add_col + G1_ADD_X1_OFFSET + i, scalar_mul_col + G1_SCALAR_MUL_RES_X_OFFSET + i));
+        links.push((add_col + G1_ADD_Y1_OFFSET + i, scalar_mul_col + G1_SCALAR_MUL_RES_Y_OFFSET + i));
+        links.push((add_col + G1_ADD_X2_OFFSET + i, scalar_mul_col + G1_SCALAR_MUL_X_OFFSET + i));
+        links.push((add_col + G1_ADD_Y2_OFFSET + i, scalar_mul_col + G1_SCALAR_MUL_Y_OFFSET + i));
+    }
+    links.push((add_col + G1_ADD_INFINITY1_OFFSET, scalar_mul_col + G1_SCALAR_MUL_RES_INFINITY_OFFSET));
+    links.push((add_col + G1_ADD_INFINITY2_OFFSET, scalar_mul_col + G1_SCALAR_MUL_INFINITY_OFFSET));
+    links
+}
+
+/// Fills the stark trace of G1 cofactor clearing. Input is a point on the G1 curve. Needs G1_CLEAR_COFACTOR_ROWS rows starting from `start_row`. Fills the scalar multiplication of the point by |x|, then the addition of the result with the point in the rows of the last step. Returns `P + [|x|]P`.
+/// Returns an error for points with `[|x|]P == P`, as the addition gadget does not handle doubling.
+pub fn fill_trace_g1_clear_cofactor<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, p: &G1Affine, start_row: usize, start_col: usize) -> Result<G1Affine> {
+    let end_row = start_row + G1_CLEAR_COFACTOR_ROWS - 1;
+    let x_p = fill_trace_g1_scalar_mul(trace, p, &get_bls_12_381_parameter(), start_row, start_col + G1_CLEAR_COFACTOR_SCALAR_MUL_OFFSET);
+    fill_trace_g1_add(trace, &x_p, p, end_row + 1 - G1_SCALAR_MUL_STEP_ROWS, end_row, start_col + G1_CLEAR_COFACTOR_ADD_OFFSET)
+}
+
+/// Constraints G1 cofactor clearing.
+///
+/// Constraints the scalar bits of the scalar multiplication to be the bits of |x| wherever it is on. In the last row of the scalar multiplication, constraints the addition to be on, with `[|x|]P` as first input and the point as second input.
+/// Then constraints the scalar multiplication and the addition operations.
+pub fn add_g1_clear_cofactor_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let scalar_mul_col = start_col + G1_CLEAR_COFACTOR_SCALAR_MUL_OFFSET;
+    let add_col = start_col + G1_CLEAR_COFACTOR_ADD_OFFSET;
+    let check = local_values[scalar_mul_col + G1_SCALAR_MUL_CHECK_OFFSET];
+    let res_row = local_values[scalar_mul_col + G1_SCALAR_MUL_RES_ROW_OFFSET];
+
+    for (i, bit) in bls_parameter_bits().iter().enumerate() {
+        yield_constr.constraint(
+            check *
+            (local_values[scalar_mul_col + G1_SCALAR_MUL_SCALAR_BITS_OFFSET + i] - FE::from_canonical_u32(*bit))
+        );
+    }
+
+    yield_constr.constraint(res_row * (P::ONES - local_values[add_col + G1_ADD_CHECK_OFFSET]));
+    for (input_col, value_col) in g1_clear_cofactor_add_links(start_col) {
+        yield_constr.constraint(
+            res_row *
+            (local_values[input_col] - local_values[value_col])
+        );
+    }
+
+    add_g1_scalar_mul_constraints(local_values, next_values, yield_constr, scalar_mul_col);
+    add_g1_add_constraints(local_values, next_values, yield_constr, add_col, None);
+}
+
+pub fn add_g1_clear_cofactor_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+) {
+    let one = builder.constant_extension(F::Extension::ONE);
+    let scalar_mul_col = start_col + G1_CLEAR_COFACTOR_SCALAR_MUL_OFFSET;
+    let add_col = start_col + G1_CLEAR_COFACTOR_ADD_OFFSET;
+    let check = local_values[scalar_mul_col + G1_SCALAR_MUL_CHECK_OFFSET];
+    let res_row = local_values[scalar_mul_col + G1_SCALAR_MUL_RES_ROW_OFFSET];
+
+    for (i, bit) in bls_parameter_bits().iter().enumerate() {
+        let bit = builder.constant_extension(F::Extension::from_canonical_u32(*bit));
+        let c = builder.sub_extension(local_values[scalar_mul_col + G1_SCALAR_MUL_SCALAR_BITS_OFFSET + i], bit);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+    }
+
+    let c = builder.sub_extension(one, local_values[add_col + G1_ADD_CHECK_OFFSET]);
+    let c = builder.mul_extension(res_row, c);
+    yield_constr.constraint(builder, c);
+    for (input_col, value_col) in g1_clear_cofactor_add_links(start_col) {
+        let c = builder.sub_extension(local_values[input_col], local_values[value_col]);
+        let c = builder.mul_extension(res_row, c);
+        yield_constr.constraint(builder, c);
+    }
+
+    add_g1_scalar_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, scalar_mul_col);
+    add_g1_add_constraints_ext_circuit(builder, yield_constr, local_values, next_values, add_col, None);
+}
+
+/// Stark proving that a G1 point is the cofactor clearing of a raw curve point, e.g. the output of the map to curve.
+#[derive(Clone, Copy)]
+pub struct G1ClearCofactorStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> G1ClearCofactorStark<F, D> {
+    /// Rows used by one cofactor clearing, `G1_CLEAR_COFACTOR_ROWS`.
+    pub fn required_rows() -> usize {
+        G1_CLEAR_COFACTOR_ROWS
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(p: &G1Affine, res: &G1Affine) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for i in 0..12 {
+            public_inputs[PIS_X_OFFSET + i] = F::from_canonical_u32(p.x.0[i]);
+            public_inputs[PIS_Y_OFFSET + i] = F::from_canonical_u32(p.y.0[i]);
+            public_inputs[PIS_RES_X_OFFSET + i] = F::from_canonical_u32(res.x.0[i]);
+            public_inputs[PIS_RES_Y_OFFSET + i] = F::from_canonical_u32(res.y.0[i]);
+        }
+        public_inputs[PIS_INFINITY_OFFSET] = F::from_bool(p.infinity);
+        public_inputs[PIS_RES_INFINITY_OFFSET] = F::from_bool(res.infinity);
+        public_inputs
+    }
+
+    /// Fills the trace for the cofactor clearing of `p`. Returns an error for the same inputs as [fill_trace_g1_clear_cofactor].
+    pub fn generate_trace(&self, p: &G1Affine) -> Result<Vec<[F; TOTAL_COLUMNS]>> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_g1_clear_cofactor(&mut trace, p, 0, 0)?;
+        Ok(trace)
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for G1ClearCofactorStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[G1_SCALAR_MUL_CHECK_OFFSET] - P::ONES);
+        yield_constr.constraint_first_row(local_values[G1_SCALAR_MUL_START_ROW_OFFSET] - P::ONES);
+        for i in 0..12 {
+            yield_constr.constraint_first_row(local_values[G1_SCALAR_MUL_X_OFFSET + i] - public_inputs[PIS_X_OFFSET + i]);
+            yield_constr.constraint_first_row(local_values[G1_SCALAR_MUL_Y_OFFSET + i] - public_inputs[PIS_Y_OFFSET + i]);
+        }
+        yield_constr.constraint_first_row(local_values[G1_SCALAR_MUL_INFINITY_OFFSET] - public_inputs[PIS_INFINITY_OFFSET]);
+
+        let res_row = local_values[G1_SCALAR_MUL_RES_ROW_OFFSET];
+        for i in 0..12 {
+            yield_constr.constraint(
+                res_row *
+                (local_values[G1_CLEAR_COFACTOR_ADD_OFFSET + G1_ADD_RES_X_OFFSET + i] - public_inputs[PIS_RES_X_OFFSET + i])
+            );
+            yield_constr.constraint(
+                res_row *
+                (local_values[G1_CLEAR_COFACTOR_ADD_OFFSET + G1_ADD_RES_Y_OFFSET + i] - public_inputs[PIS_RES_Y_OFFSET + i])
+            );
+        }
+        yield_constr.constraint(
+            res_row *
+            (local_values[G1_CLEAR_COFACTOR_ADD_OFFSET + G1_ADD_RES_INFINITY_OFFSET] - public_inputs[PIS_RES_INFINITY_OFFSET])
+        );
+
+        add_g1_clear_cofactor_constraints(local_values, next_values, yield_constr, 0);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[G1_SCALAR_MUL_CHECK_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        let c = builder.sub_extension(local_values[G1_SCALAR_MUL_START_ROW_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[G1_SCALAR_MUL_X_OFFSET + i], public_inputs[PIS_X_OFFSET + i]);
+            yield_constr.constraint_first_row(builder, c);
+            let c = builder.sub_extension(local_values[G1_SCALAR_MUL_Y_OFFSET + i], public_inputs[PIS_Y_OFFSET + i]);
+            yield_constr.constraint_first_row(builder, c);
+        }
+        let c = builder.sub_extension(local_values[G1_SCALAR_MUL_INFINITY_OFFSET], public_inputs[PIS_INFINITY_OFFSET]);
+        yield_constr.constraint_first_row(builder, c);
+
+        let res_row = local_values[G1_SCALAR_MUL_RES_ROW_OFFSET];
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[G1_CLEAR_COFACTOR_ADD_OFFSET + G1_ADD_RES_X_OFFSET + i], public_inputs[PIS_RES_X_OFFSET + i]);
+            let c = builder.mul_extension(res_row, c);
+            yield_constr.constraint(builder, c);
+            let c = builder.sub_extension(local_values[G1_CLEAR_COFACTOR_ADD_OFFSET + G1_ADD_RES_Y_OFFSET + i], public_inputs[PIS_RES_Y_OFFSET + i]);
+            let c = builder.mul_extension(res_row, c);
+            yield_constr.constraint(builder, c);
+        }
+        let c = builder.sub_extension(local_values[G1_CLEAR_COFACTOR_ADD_OFFSET + G1_ADD_RES_INFINITY_OFFSET], public_inputs[PIS_RES_INFINITY_OFFSET]);
+        let c = builder.mul_extension(res_row, c);
+        yield_constr.constraint(builder, c);
+
+        add_g1_clear_cofactor_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        G1_SCALAR_MUL_CONSTRAINT_DEGREE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{clear_cofactor_g1, iso_map_g1, map_to_curve_g1, Fp, G1Affine};
+
+    use super::{G1ClearCofactorStark, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = G1ClearCofactorStark<F, D>;
+
+    fn fp_from_hex(s: &str) -> Fp {
+        Fp::get_fp_from_biguint(BigUint::parse_bytes(s.as_bytes(), 16).unwrap())
+    }
+
+    fn prove_and_verify_clear_cofactor(p: &G1Affine) -> G1Affine {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(4096);
+        let res = clear_cofactor_g1(*p);
+        let public_inputs = S::public_inputs(p, &res);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(p).unwrap();
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        res
+    }
+
+    #[test]
+    fn test_g1_clear_cofactor() {
+        // Q0 = map_to_curve(u0) of the RFC 9380 BLS12381G1_XMD:SHA-256_SSWU_RO_ test vector for msg "".
+        let q0 = iso_map_g1(map_to_curve_g1(fp_from_hex("0ba14bd907ad64a016293ee7c2d276b8eae71f25a4b941eece7b0d89f17f75cb3ae5438a614fb61d6835ad59f29c564f")));
+        assert!(!q0.is_in_subgroup());
+        let res = prove_and_verify_clear_cofactor(&q0);
+        assert!(res.is_in_subgroup());
+        assert_eq!(res.x, fp_from_hex("1307444eaa3816adc50e791c4012217e8142333bb100bdc957baa23356cbc4afe602565c54e007fff021463785d1a382"));
+        assert_eq!(res.y, fp_from_hex("00fbb64739ce68558950598c43114f37b69e99a910f693d899d6e59a666a7034826f982f613aa1811b1d64dce6423003"));
+    }
+}
@@ -0,0 +1,381 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use crate::native::{glv_lambda, phi_g1, G1Affine, GLV_BETA};
+
+use crate::fp::*;
+use crate::g1::*;
+use crate::g1_scalar_mul::*;
+use crate::utils::*;
+
+// G1 endomorphism layout offsets
+/*
+    These trace offsets are for the GLV endomorphism of G1, phi(x, y) = (beta*x, y) (super::native::phi_g1). It needs 12 rows.
+    The point at infinity has zero coordinates, so it is mapped to itself without a separate case.
+    G1_PHI_SELECTOR_OFFSET -> Selector to ensure that the input is same across all rows. Set 1 in all rows except last one.
+    G1_PHI_CHECK_OFFSET -> Selector set 1 in all rows of the operation. The inputs of the multiplication are constrained wherever this selector is on.
+    G1_PHI_X_OFFSET, G1_PHI_Y_OFFSET -> offsets where the input point is set. The y coordinate of the result is the input y.
+    G1_PHI_BETA_X_OFFSET -> x * beta, with beta constrained to the constant GLV_BETA. Its result is the x coordinate of the result.
+*/
+pub const G1_PHI_SELECTOR_OFFSET: usize = 0;
+pub const G1_PHI_CHECK_OFFSET: usize = G1_PHI_SELECTOR_OFFSET + 1;
+pub const G1_PHI_X_OFFSET: usize = G1_PHI_CHECK_OFFSET + 1;
+pub const G1_PHI_Y_OFFSET: usize = G1_PHI_X_OFFSET + 12;
+pub const G1_PHI_BETA_X_OFFSET: usize = G1_PHI_Y_OFFSET + 12;
+pub const G1_PHI_TOTAL: usize = G1_PHI_BETA_X_OFFSET + FP_MUL_MOD_TOTAL;
+
+// G1EndomorphismStark layout offsets
+/*
+    G1_ENDO_SCALAR_MUL_OFFSET -> offset of the scalar multiplication of the input point by lambda.
+    G1_ENDO_PHI_OFFSET -> offset of the endomorphism of the input point, in the first 12 rows.
+    G1_ENDO_PHI_RES_X_OFFSET -> x coordinate of phi(P), copied from the first row and kept same across all rows, so it can be compared with the result of the scalar multiplication in its last row.
+*/
+pub const G1_ENDO_SCALAR_MUL_OFFSET: usize = 0;
+pub const G1_ENDO_PHI_OFFSET: usize = G1_ENDO_SCALAR_MUL_OFFSET + G1_SCALAR_MUL_TOTAL;
+pub const G1_ENDO_PHI_RES_X_OFFSET: usize = G1_ENDO_PHI_OFFSET + G1_PHI_TOTAL;
+pub const TOTAL_COLUMNS: usize = G1_ENDO_PHI_RES_X_OFFSET + 12;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the point (x, y) and its infinity flag.
+*/
+pub const PIS_X_OFFSET: usize = 0;
+pub const PIS_Y_OFFSET: usize = PIS_X_OFFSET + 12;
+pub const PIS_INFINITY_OFFSET: usize = PIS_Y_OFFSET + 12;
+pub const PUBLIC_INPUTS: usize = PIS_INFINITY_OFFSET + 1;
+
+/// Column of the x coordinate of the result of a G1 endomorphism operation starting at `col`.
+pub fn g1_phi_res_x(col: usize) -> usize {
+    fp_mul_mod_res(col + G1_PHI_BETA_X_OFFSET)
+}
+
+/// Fills the stark trace of the G1 endomorphism `phi(x, y) = (beta*x, y)`. Input is an affine point. Needs 12 rows. Returns phi(P).
+pub fn fill_trace_g1_phi<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, p: &G1Affine, start_row: usize, end_row: usize, start_col: usize) -> G1Affine {
+    for row in start_row..end_row + 1 {
+        trace[row][start_col + G1_PHI_SELECTOR_OFFSET] = F::ONE;
+        trace[row][start_col + G1_PHI_CHECK_OFFSET] = F::ONE;
+        assign_u32_in_series(trace, row, start_col + G1_PHI_X_OFFSET, &p.x.0);
+        assign_u32_in_series(trace, row, start_col + G1_PHI_Y_OFFSET, &p.y.0);
+    }
+    trace[end_row][start_col + G1_PHI_SELECTOR_OFFSET] = F::ZERO;
+    let beta_x = fill_trace_fp_mul_mod(trace, &p.x.0, &GLV_BETA.0, start_row, end_row, start_col + G1_PHI_BETA_X_OFFSET);
+    let res = phi_g1(p);
+    assert_eq!(beta_x, res.x.0);
+    res
+}
+
+/// Constraints the G1 endomorphism.
+///
+/// Constraints the input to be same across the rows wherever the selector is on. Wherever the check selector is on, constraints the x input of the multiplication with the x coordinate of the point and its y input with the constant beta.
+pub fn add_g1_phi_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+    let selector = bit_selector_val * local_values[start_col + G1_PHI_SELECTOR_OFFSET];
+    let check = bit_selector_val * local_values[start_col + G1_PHI_CHECK_OFFSET];
+
+    for col in [G1_PHI_X_OFFSET, G1_PHI_Y_OFFSET] {
+        for i in 0..12 {
+            yield_constr.constraint_transition(
+                selector *
+                (local_values[start_col + col + i] - next_values[start_col + col + i])
+            );
+        }
+    }
+
+    let x = fp_mul_mod_x(start_col + G1_PHI_BETA_X_OFFSET);
+    let beta = fp_mul_mod_y(start_col + G1_PHI_BETA_X_OFFSET);
+    for i in 0..12 {
+        yield_constr.constraint(
+            check *
+            (local_values[x + i] - local_values[start_col + G1_PHI_X_OFFSET + i])
+        );
+        yield_constr.constraint(
+            check *
+            (local_values[beta + i] - FE::from_canonical_u32(GLV_BETA.0[i]))
+        );
+    }
+
+    add_fp_mul_mod_constraints(local_values, next_values, yield_constr, start_col + G1_PHI_BETA_X_OFFSET, bit_selector);
+}
+
+pub fn add_g1_phi_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let bit_selector_val = bit_selector.unwrap_or(builder.constant_extension(F::Extension::ONE));
+    let selector = builder.mul_extension(bit_selector_val, local_values[start_col + G1_PHI_SELECTOR_OFFSET]);
+    let check = builder.mul_extension(bit_selector_val, local_values[start_col + G1_PHI_CHECK_OFFSET]);
+
+    for col in [G1_PHI_X_OFFSET, G1_PHI_Y_OFFSET] {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[start_col + col + i], next_values[start_col + col + i]);
+            let c = builder.mul_extension(selector, c);
+            yield_constr.constraint_transition(builder, c);
+        }
+    }
+
+    let x = fp_mul_mod_x(start_col + G1_PHI_BETA_X_OFFSET);
+    let beta = fp_mul_mod_y(start_col + G1_PHI_BETA_X_OFFSET);
+    for i in 0..12 {
+        let c = builder.sub_extension(local_values[x + i], local_values[start_col + G1_PHI_X_OFFSET + i]);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+
+        let beta_limb = builder.constant_extension(F::Extension::from_canonical_u32(GLV_BETA.0[i]));
+        let c = builder.sub_extension(local_values[beta + i], beta_limb);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+    }
+
+    add_fp_mul_mod_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + G1_PHI_BETA_X_OFFSET, bit_selector);
+}
+
+/// Stark proving `phi(P) == [lambda]P` for a G1 point, i.e. that the GLV endomorphism acts as the multiplication by lambda, which holds for the points of the prime order subgroup.
+/// `[lambda]P` is computed with the scalar multiplication gadget and phi(P) with the endomorphism gadget. Both results share the y coordinate and the infinity flag of the input.
+#[derive(Clone, Copy)]
+pub struct G1EndomorphismStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> G1EndomorphismStark<F, D> {
+    /// Rows used by the endomorphism check, `G1_SCALAR_MUL_ROWS` of the multiplication by lambda, with phi in its first 12 rows.
+    pub fn required_rows() -> usize {
+        G1_SCALAR_MUL_ROWS
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(p: &G1Affine) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for i in 0..12 {
+            public_inputs[PIS_X_OFFSET + i] = F::from_canonical_u32(p.x.0[i]);
+            public_inputs[PIS_Y_OFFSET + i] = F::from_canonical_u32(p.y.0[i]);
+        }
+        public_inputs[PIS_INFINITY_OFFSET] = F::from_bool(p.infinity);
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, p: &G1Affine) -> Vec<[F; TOTAL_COLUMNS]> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        let lambda_p = fill_trace_g1_scalar_mul(&mut trace, p, &glv_lambda(), 0, G1_ENDO_SCALAR_MUL_OFFSET);
+        let phi_p = fill_trace_g1_phi(&mut trace, p, 0, 11, G1_ENDO_PHI_OFFSET);
+        assert_eq!(lambda_p, phi_p);
+        for row in trace.iter_mut() {
+            for i in 0..12 {
+                row[G1_ENDO_PHI_RES_X_OFFSET + i] = F::from_canonical_u32(phi_p.x.0[i]);
+            }
+        }
+        trace
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for G1EndomorphismStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let scalar_mul = G1_ENDO_SCALAR_MUL_OFFSET;
+        yield_constr.constraint_first_row(local_values[scalar_mul + G1_SCALAR_MUL_CHECK_OFFSET] - P::ONES);
+        yield_constr.constraint_first_row(local_values[scalar_mul + G1_SCALAR_MUL_START_ROW_OFFSET] - P::ONES);
+        yield_constr.constraint_first_row(local_values[G1_ENDO_PHI_OFFSET + G1_PHI_CHECK_OFFSET] - P::ONES);
+        for i in 0..12 {
+            yield_constr.constraint_first_row(local_values[scalar_mul + G1_SCALAR_MUL_X_OFFSET + i] - public_inputs[PIS_X_OFFSET + i]);
+            yield_constr.constraint_first_row(local_values[scalar_mul + G1_SCALAR_MUL_Y_OFFSET + i] - public_inputs[PIS_Y_OFFSET + i]);
+            yield_constr.constraint_first_row(local_values[G1_ENDO_PHI_OFFSET + G1_PHI_X_OFFSET + i] - public_inputs[PIS_X_OFFSET + i]);
+            yield_constr.constraint_first_row(local_values[G1_ENDO_PHI_OFFSET + G1_PHI_Y_OFFSET + i] - public_inputs[PIS_Y_OFFSET + i]);
+            yield_constr.constraint_first_row(local_values[G1_ENDO_PHI_RES_X_OFFSET + i] - local_values[g1_phi_res_x(G1_ENDO_PHI_OFFSET) + i]);
+            yield_constr.constraint_transition(local_values[G1_ENDO_PHI_RES_X_OFFSET + i] - next_values[G1_ENDO_PHI_RES_X_OFFSET + i]);
+        }
+        yield_constr.constraint_first_row(local_values[scalar_mul + G1_SCALAR_MUL_INFINITY_OFFSET] - public_inputs[PIS_INFINITY_OFFSET]);
+        let lambda_limbs = get_scalar_limbs(&glv_lambda());
+        for i in 0..8 {
+            let limb = (0..32).fold(P::ZEROS, |acc, j|
+                acc + local_values[scalar_mul + G1_SCALAR_MUL_SCALAR_BITS_OFFSET + i*32 + j] * FE::from_canonical_u64(1 << j)
+            );
+            yield_constr.constraint_first_row(limb - FE::from_canonical_u32(lambda_limbs[i]));
+        }
+
+        let res_row = local_values[scalar_mul + G1_SCALAR_MUL_RES_ROW_OFFSET];
+        for i in 0..12 {
+            yield_constr.constraint(
+                res_row *
+                (local_values[scalar_mul + G1_SCALAR_MUL_RES_X_OFFSET + i] - local_values[G1_ENDO_PHI_RES_X_OFFSET + i])
+            );
+            yield_constr.constraint(
+                res_row *
+                (local_values[scalar_mul + G1_SCALAR_MUL_RES_Y_OFFSET + i] - public_inputs[PIS_Y_OFFSET + i])
+            );
+        }
+        yield_constr.constraint(
+            res_row *
+            (local_values[scalar_mul + G1_SCALAR_MUL_RES_INFINITY_OFFSET] - public_inputs[PIS_INFINITY_OFFSET])
+        );
+
+        add_g1_scalar_mul_constraints(local_values, next_values, yield_constr, scalar_mul);
+        add_g1_phi_constraints(local_values, next_values, yield_constr, G1_ENDO_PHI_OFFSET, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let scalar_mul = G1_ENDO_SCALAR_MUL_OFFSET;
+        let one = builder.constant_extension(F::Extension::ONE);
+        for col in [scalar_mul + G1_SCALAR_MUL_CHECK_OFFSET, scalar_mul + G1_SCALAR_MUL_START_ROW_OFFSET, G1_ENDO_PHI_OFFSET + G1_PHI_CHECK_OFFSET] {
+            let c = builder.sub_extension(local_values[col], one);
+            yield_constr.constraint_first_row(builder, c);
+        }
+        for i in 0..12 {
+            for (col, pi) in [
+                (scalar_mul + G1_SCALAR_MUL_X_OFFSET, PIS_X_OFFSET),
+                (scalar_mul + G1_SCALAR_MUL_Y_OFFSET, PIS_Y_OFFSET),
+                (G1_ENDO_PHI_OFFSET + G1_PHI_X_OFFSET, PIS_X_OFFSET),
+                (G1_ENDO_PHI_OFFSET + G1_PHI_Y_OFFSET, PIS_Y_OFFSET),
+            ] {
+                let c = builder.sub_extension(local_values[col + i], public_inputs[pi + i]);
+                yield_constr.constraint_first_row(builder, c);
+            }
+            let c = builder.sub_extension(local_values[G1_ENDO_PHI_RES_X_OFFSET + i], local_values[g1_phi_res_x(G1_ENDO_PHI_OFFSET) + i]);
+            yield_constr.constraint_first_row(builder, c);
+            let c = builder.sub_extension(local_values[G1_ENDO_PHI_RES_X_OFFSET + i], next_values[G1_ENDO_PHI_RES_X_OFFSET + i]);
+            yield_constr.constraint_transition(builder, c);
+        }
+        let c = builder.sub_extension(local_values[scalar_mul + G1_SCALAR_MUL_INFINITY_OFFSET], public_inputs[PIS_INFINITY_OFFSET]);
+        yield_constr.constraint_first_row(builder, c);
+        let lambda_limbs = get_scalar_limbs(&glv_lambda());
+        for i in 0..8 {
+            let zero = builder.constant_extension(F::Extension::ZERO);
+            let limb = (0..32).fold(zero, |acc, j| {
+                let tmp_const = builder.constant_extension(F::Extension::from_canonical_u64(1 << j));
+                let c = builder.mul_extension(local_values[scalar_mul + G1_SCALAR_MUL_SCALAR_BITS_OFFSET + i*32 + j], tmp_const);
+                builder.add_extension(acc, c)
+            });
+            let lambda_limb = builder.constant_extension(F::Extension::from_canonical_u32(lambda_limbs[i]));
+            let c = builder.sub_extension(limb, lambda_limb);
+            yield_constr.constraint_first_row(builder, c);
+        }
+
+        let res_row = local_values[scalar_mul + G1_SCALAR_MUL_RES_ROW_OFFSET];
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[scalar_mul + G1_SCALAR_MUL_RES_X_OFFSET + i], local_values[G1_ENDO_PHI_RES_X_OFFSET + i]);
+            let c = builder.mul_extension(res_row, c);
+            yield_constr.constraint(builder, c);
+            let c = builder.sub_extension(local_values[scalar_mul + G1_SCALAR_MUL_RES_Y_OFFSET + i], public_inputs[PIS_Y_OFFSET + i]);
+            let c = builder.mul_extension(res_row, c);
+            yield_constr.constraint(builder, c);
+        }
+        let c = builder.sub_extension(local_values[scalar_mul + G1_SCALAR_MUL_RES_INFINITY_OFFSET], public_inputs[PIS_INFINITY_OFFSET]);
+        let c = builder.mul_extension(res_row, c);
+        yield_constr.constraint(builder, c);
+
+        add_g1_scalar_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, scalar_mul);
+        add_g1_phi_constraints_ext_circuit(builder, yield_constr, local_values, next_values, G1_ENDO_PHI_OFFSET, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        G1_SCALAR_MUL_CONSTRAINT_DEGREE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::G1Affine;
+
+    use super::{G1EndomorphismStark, PUBLIC_INPUTS};
+
+    #[test]
+    fn test_g1_endomorphism() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = G1EndomorphismStark<F, D>;
+
+        let g = G1Affine::generator();
+        for p in [g, g.double() + g] {
+            let config = StarkConfig::standard_fast_config();
+            let stark = S::new(4096);
+            let public_inputs = S::public_inputs(&p);
+            assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+            let trace = stark.generate_trace(&p);
+            let trace_poly_values = trace_rows_to_poly_values(trace);
+            let proof = prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace_poly_values,
+                &public_inputs,
+                &mut TimingTree::default(),
+            ).unwrap();
+            verify_stark_proof(stark, proof, &config).unwrap();
+        }
+    }
+}
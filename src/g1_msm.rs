@@ -0,0 +1,347 @@
+use num_bigint::BigUint;
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use anyhow::Result;
+
+use crate::native::{msm_g1, G1Affine};
+
+use crate::g1::*;
+use crate::g1_scalar_mul::*;
+
+/// Number of (point, scalar) pairs of the multi-scalar multiplication.
+pub const G1_MSM_POINTS: usize = 2;
+
+// G1MsmStark layout offsets
+/*
+    G1_MSM_SCALAR_MUL_OFFSET -> offset of the G1_MSM_POINTS scalar multiplications, one after the other. They all start in the first row, so their results are set in the same row, G1_SCALAR_MUL_ROWS - 1.
+    G1_MSM_SUM_OFFSET -> offset of the G1_MSM_POINTS - 1 additions summing the results of the scalar multiplications, in the last 12 rows of the scalar multiplications. The k-th addition adds the (k+1)-th scalar multiplication to the result of the previous addition, or to the first scalar multiplication for k == 0.
+*/
+pub const G1_MSM_SCALAR_MUL_OFFSET: usize = 0;
+pub const G1_MSM_SUM_OFFSET: usize = G1_MSM_SCALAR_MUL_OFFSET + G1_MSM_POINTS * G1_SCALAR_MUL_TOTAL;
+pub const TOTAL_COLUMNS: usize = G1_MSM_SUM_OFFSET + (G1_MSM_POINTS - 1) * G1_ADD_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the x coordinates, y coordinates and infinity flags of the G1_MSM_POINTS points, then their scalars as 8 u32 limbs each, then the result point (x, y) and its infinity flag.
+*/
+pub const PIS_X_OFFSET: usize = 0;
+pub const PIS_Y_OFFSET: usize = PIS_X_OFFSET + 12 * G1_MSM_POINTS;
+pub const PIS_INFINITY_OFFSET: usize = PIS_Y_OFFSET + 12 * G1_MSM_POINTS;
+pub const PIS_SCALAR_OFFSET: usize = PIS_INFINITY_OFFSET + G1_MSM_POINTS;
+pub const PIS_RES_X_OFFSET: usize = PIS_SCALAR_OFFSET + 8 * G1_MSM_POINTS;
+pub const PIS_RES_Y_OFFSET: usize = PIS_RES_X_OFFSET + 12;
+pub const PIS_RES_INFINITY_OFFSET: usize = PIS_RES_Y_OFFSET + 12;
+pub const PUBLIC_INPUTS: usize = PIS_RES_INFINITY_OFFSET + 1;
+
+/// Column of the i-th scalar multiplication.
+fn g1_msm_scalar_mul_col(i: usize) -> usize {
+    G1_MSM_SCALAR_MUL_OFFSET + i * G1_SCALAR_MUL_TOTAL
+}
+
+/// Column of the k-th addition of the sum.
+fn g1_msm_sum_col(k: usize) -> usize {
+    G1_MSM_SUM_OFFSET + k * G1_ADD_TOTAL
+}
+
+/// Columns (x, infinity) of the result of the i-th scalar multiplication. The y coordinate follows x.
+fn g1_msm_product_cols(i: usize) -> (usize, usize) {
+    let scalar_mul = g1_msm_scalar_mul_col(i);
+    (scalar_mul + G1_SCALAR_MUL_RES_X_OFFSET, scalar_mul + G1_SCALAR_MUL_RES_INFINITY_OFFSET)
+}
+
+/// Columns (x, infinity) of the result of the k-th addition of the sum. The y coordinate follows x.
+fn g1_msm_sum_res_cols(k: usize) -> (usize, usize) {
+    let add_col = g1_msm_sum_col(k);
+    (add_col + G1_ADD_RES_X_OFFSET, add_col + G1_ADD_RES_INFINITY_OFFSET)
+}
+
+/// Columns (x, infinity) of the result of the multi-scalar multiplication. The y coordinate follows x.
+fn g1_msm_res_cols() -> (usize, usize) {
+    if G1_MSM_POINTS == 1 {
+        g1_msm_product_cols(0)
+    } else {
+        g1_msm_sum_res_cols(G1_MSM_POINTS - 2)
+    }
+}
+
+/// Column pairs (input, value, limbs) linking the inputs of the additions of the sum, constrained in the result row. The coordinates of an input are linked with 24 limbs, since x and y are next to each other both in the inputs of an addition and in the results.
+fn g1_msm_sum_links() -> Vec<(usize, usize, usize)> {
+    let mut links = vec![];
+    for k in 0..G1_MSM_POINTS - 1 {
+        let add_col = g1_msm_sum_col(k);
+        let (acc_x, acc_infinity) = if k == 0 {
+            g1_msm_product_cols(0)
+        } else {
+            g1_msm_sum_res_cols(k - 1)
+        };
+        let (p_x, p_infinity) = g1_msm_product_cols(k + 1);
+        links.extend([
+            (add_col + G1_ADD_X1_OFFSET, acc_x, 24),
+            (add_col + G1_ADD_INFINITY1_OFFSET, acc_infinity, 1),
+            (add_col + G1_ADD_X2_OFFSET, p_x, 24),
+            (add_col + G1_ADD_INFINITY2_OFFSET, p_infinity, 1),
+        ]);
+    }
+    links
+}
+
+/// Stark proving the multi-scalar multiplication `sum([k_i]P_i)` of G1_MSM_POINTS points (super::native::msm_g1).
+/// Each `[k_i]P_i` is computed with the scalar multiplication gadget, and the results are summed with the addition gadget in the row where they are set. The scalars are at most 255 bits, and, as for the addition gadget, a partial sum must not be the same finite point as the next term.
+#[derive(Clone, Copy)]
+pub struct G1MsmStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> G1MsmStark<F, D> {
+    /// Rows used by the msm, `G1_SCALAR_MUL_ROWS`, since the scalar multiplications are side by side and the sums are in the rows of their last step.
+    pub fn required_rows() -> usize {
+        G1_SCALAR_MUL_ROWS
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(points: &[G1Affine; G1_MSM_POINTS], scalars: &[BigUint; G1_MSM_POINTS], res: &G1Affine) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for (i, (p, k)) in points.iter().zip(scalars).enumerate() {
+            for j in 0..12 {
+                public_inputs[PIS_X_OFFSET + i*12 + j] = F::from_canonical_u32(p.x.0[j]);
+                public_inputs[PIS_Y_OFFSET + i*12 + j] = F::from_canonical_u32(p.y.0[j]);
+            }
+            public_inputs[PIS_INFINITY_OFFSET + i] = F::from_bool(p.infinity);
+            for (j, limb) in get_scalar_limbs(k).iter().enumerate() {
+                public_inputs[PIS_SCALAR_OFFSET + i*8 + j] = F::from_canonical_u32(*limb);
+            }
+        }
+        for i in 0..12 {
+            public_inputs[PIS_RES_X_OFFSET + i] = F::from_canonical_u32(res.x.0[i]);
+            public_inputs[PIS_RES_Y_OFFSET + i] = F::from_canonical_u32(res.y.0[i]);
+        }
+        public_inputs[PIS_RES_INFINITY_OFFSET] = F::from_bool(res.infinity);
+        public_inputs
+    }
+
+    /// Fills the trace for the sum of `[k_i]P_i`. Returns an error if a product is equal to the sum of the previous ones, which the addition gadget does not handle (see [fill_trace_g1_add]).
+    pub fn generate_trace(&self, points: &[G1Affine; G1_MSM_POINTS], scalars: &[BigUint; G1_MSM_POINTS]) -> Result<Vec<[F; TOTAL_COLUMNS]>> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        let products = (0..G1_MSM_POINTS).map(|i|
+            fill_trace_g1_scalar_mul(&mut trace, &points[i], &scalars[i], 0, g1_msm_scalar_mul_col(i))
+        ).collect::<Vec<G1Affine>>();
+        let res_row = G1_SCALAR_MUL_ROWS - 1;
+        let mut acc = products[0];
+        for k in 0..G1_MSM_POINTS - 1 {
+            acc = fill_trace_g1_add(&mut trace, &acc, &products[k + 1], res_row - 11, res_row, g1_msm_sum_col(k))?;
+        }
+        assert_eq!(acc, msm_g1(points, scalars));
+        Ok(trace)
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for G1MsmStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        for p in 0..G1_MSM_POINTS {
+            let scalar_mul = g1_msm_scalar_mul_col(p);
+            yield_constr.constraint_first_row(local_values[scalar_mul + G1_SCALAR_MUL_CHECK_OFFSET] - P::ONES);
+            yield_constr.constraint_first_row(local_values[scalar_mul + G1_SCALAR_MUL_START_ROW_OFFSET] - P::ONES);
+            for i in 0..12 {
+                yield_constr.constraint_first_row(local_values[scalar_mul + G1_SCALAR_MUL_X_OFFSET + i] - public_inputs[PIS_X_OFFSET + p*12 + i]);
+                yield_constr.constraint_first_row(local_values[scalar_mul + G1_SCALAR_MUL_Y_OFFSET + i] - public_inputs[PIS_Y_OFFSET + p*12 + i]);
+            }
+            yield_constr.constraint_first_row(local_values[scalar_mul + G1_SCALAR_MUL_INFINITY_OFFSET] - public_inputs[PIS_INFINITY_OFFSET + p]);
+            for i in 0..8 {
+                let limb = (0..32).fold(P::ZEROS, |acc, j|
+                    acc + local_values[scalar_mul + G1_SCALAR_MUL_SCALAR_BITS_OFFSET + i*32 + j] * FE::from_canonical_u64(1 << j)
+                );
+                yield_constr.constraint_first_row(limb - public_inputs[PIS_SCALAR_OFFSET + p*8 + i]);
+            }
+            add_g1_scalar_mul_constraints(local_values, next_values, yield_constr, scalar_mul);
+        }
+
+        // All the scalar multiplications end in the same row
+        let res_row = local_values[g1_msm_scalar_mul_col(0) + G1_SCALAR_MUL_RES_ROW_OFFSET];
+        for k in 0..G1_MSM_POINTS - 1 {
+            let add_col = g1_msm_sum_col(k);
+            yield_constr.constraint(res_row * (P::ONES - local_values[add_col + G1_ADD_CHECK_OFFSET]));
+            yield_constr.constraint(res_row * local_values[add_col + G1_ADD_SELECTOR_OFFSET]);
+        }
+        for (input, value, limbs) in g1_msm_sum_links() {
+            for i in 0..limbs {
+                yield_constr.constraint(res_row * (local_values[input + i] - local_values[value + i]));
+            }
+        }
+        let (res_x, res_infinity) = g1_msm_res_cols();
+        for i in 0..12 {
+            yield_constr.constraint(res_row * (local_values[res_x + i] - public_inputs[PIS_RES_X_OFFSET + i]));
+            yield_constr.constraint(res_row * (local_values[res_x + 12 + i] - public_inputs[PIS_RES_Y_OFFSET + i]));
+        }
+        yield_constr.constraint(res_row * (local_values[res_infinity] - public_inputs[PIS_RES_INFINITY_OFFSET]));
+
+        for k in 0..G1_MSM_POINTS - 1 {
+            add_g1_add_constraints(local_values, next_values, yield_constr, g1_msm_sum_col(k), None);
+        }
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        for p in 0..G1_MSM_POINTS {
+            let scalar_mul = g1_msm_scalar_mul_col(p);
+            for col in [G1_SCALAR_MUL_CHECK_OFFSET, G1_SCALAR_MUL_START_ROW_OFFSET] {
+                let c = builder.sub_extension(local_values[scalar_mul + col], one);
+                yield_constr.constraint_first_row(builder, c);
+            }
+            for i in 0..12 {
+                let c = builder.sub_extension(local_values[scalar_mul + G1_SCALAR_MUL_X_OFFSET + i], public_inputs[PIS_X_OFFSET + p*12 + i]);
+                yield_constr.constraint_first_row(builder, c);
+                let c = builder.sub_extension(local_values[scalar_mul + G1_SCALAR_MUL_Y_OFFSET + i], public_inputs[PIS_Y_OFFSET + p*12 + i]);
+                yield_constr.constraint_first_row(builder, c);
+            }
+            let c = builder.sub_extension(local_values[scalar_mul + G1_SCALAR_MUL_INFINITY_OFFSET], public_inputs[PIS_INFINITY_OFFSET + p]);
+            yield_constr.constraint_first_row(builder, c);
+            for i in 0..8 {
+                let zero = builder.constant_extension(F::Extension::ZERO);
+                let limb = (0..32).fold(zero, |acc, j| {
+                    let tmp_const = builder.constant_extension(F::Extension::from_canonical_u64(1 << j));
+                    let c = builder.mul_extension(local_values[scalar_mul + G1_SCALAR_MUL_SCALAR_BITS_OFFSET + i*32 + j], tmp_const);
+                    builder.add_extension(acc, c)
+                });
+                let c = builder.sub_extension(limb, public_inputs[PIS_SCALAR_OFFSET + p*8 + i]);
+                yield_constr.constraint_first_row(builder, c);
+            }
+            add_g1_scalar_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, scalar_mul);
+        }
+
+        let res_row = local_values[g1_msm_scalar_mul_col(0) + G1_SCALAR_MUL_RES_ROW_OFFSET];
+        for k in 0..G1_MSM_POINTS - 1 {
+            let add_col = g1_msm_sum_col(k);
+            let c = builder.sub_extension(one, local_values[add_col + G1_ADD_CHECK_OFFSET]);
+            let c = builder.mul_extension(res_row, c);
+            yield_constr.constraint(builder, c);
+            let c = builder.mul_extension(res_row, local_values[add_col + G1_ADD_SELECTOR_OFFSET]);
+            yield_constr.constraint(builder, c);
+        }
+        for (input, value, limbs) in g1_msm_sum_links() {
+            for i in 0..limbs {
+                let c = builder.sub_extension(local_values[input + i], local_values[value + i]);
+                let c = builder.mul_extension(res_row, c);
+                yield_constr.constraint(builder, c);
+            }
+        }
+        let (res_x, res_infinity) = g1_msm_res_cols();
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[res_x + i], public_inputs[PIS_RES_X_OFFSET + i]);
+            let c = builder.mul_extension(res_row, c);
+            yield_constr.constraint(builder, c);
+            let c = builder.sub_extension(local_values[res_x + 12 + i], public_inputs[PIS_RES_Y_OFFSET + i]);
+            let c = builder.mul_extension(res_row, c);
+            yield_constr.constraint(builder, c);
+        }
+        let c = builder.sub_extension(local_values[res_infinity], public_inputs[PIS_RES_INFINITY_OFFSET]);
+        let c = builder.mul_extension(res_row, c);
+        yield_constr.constraint(builder, c);
+
+        for k in 0..G1_MSM_POINTS - 1 {
+            add_g1_add_constraints_ext_circuit(builder, yield_constr, local_values, next_values, g1_msm_sum_col(k), None);
+        }
+    }
+
+    fn constraint_degree(&self) -> usize {
+        G1_SCALAR_MUL_CONSTRAINT_DEGREE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{msm_g1, G1Affine};
+
+    use super::{G1MsmStark, PUBLIC_INPUTS};
+
+    #[test]
+    fn test_g1_msm() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = G1MsmStark<F, D>;
+
+        let g = G1Affine::generator();
+        let points = [g, g.double() + g];
+        let k = BigUint::from_str("40535487838306347262513418034127355155416283154289396519919342425880917706245").unwrap();
+        for scalars in [[k.clone(), BigUint::from(5u32)], [BigUint::from(0u32), k.clone()]] {
+            let res = msm_g1(&points, &scalars);
+            assert_eq!(res, points[0].mul_scalar(&scalars[0]) + points[1].mul_scalar(&scalars[1]));
+
+            let config = StarkConfig::standard_fast_config();
+            let stark = S::new(4096);
+            let public_inputs = S::public_inputs(&points, &scalars, &res);
+            assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+            let trace = stark.generate_trace(&points, &scalars).unwrap();
+            let trace_poly_values = trace_rows_to_poly_values(trace);
+            let proof = prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace_poly_values,
+                &public_inputs,
+                &mut TimingTree::default(),
+            ).unwrap();
+            verify_stark_proof(stark, proof, &config).unwrap();
+        }
+
+        // Equal products, whose sum is a doubling.
+        let stark = S::new(4096);
+        assert!(stark.generate_trace(&[g, g], &[k.clone(), k]).is_err());
+    }
+}
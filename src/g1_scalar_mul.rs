@@ -0,0 +1,729 @@
+use num_bigint::BigUint;
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use crate::native::{G1Affine, ScalarBits};
+
+use crate::g1::*;
+use crate::utils::*;
+
+/// Number of bits of the scalar, one double-and-add step is done for each bit.
+pub const G1_SCALAR_MUL_BITS: usize = 255;
+/// Number of rows needed by one double-and-add step.
+pub const G1_SCALAR_MUL_STEP_ROWS: usize = 12;
+/// Number of rows needed by the scalar multiplication.
+pub const G1_SCALAR_MUL_ROWS: usize = G1_SCALAR_MUL_BITS * G1_SCALAR_MUL_STEP_ROWS;
+
+// G1 scalar multiplication layout offsets
+/*
+    These trace offsets are for multiplying a G1 point by a scalar (super::native::G1Affine::mul_scalar). It needs G1_SCALAR_MUL_ROWS rows.
+    The scalar is processed from the most significant bit. Each bit is processed in a step of 12 rows, where the accumulator is doubled and then added with the point if the bit is 1, or with the point at infinity otherwise. The addition does not support adding a point to itself, which happens for points of small order, so the doubled accumulator is also doubled and its double is the result of the step when it is equal to the point.
+    G1_SCALAR_MUL_SELECTOR_OFFSET -> Selector to ensure that the input is same across all rows. Set 1 in all rows except last one.
+    G1_SCALAR_MUL_CHECK_OFFSET -> Selector set 1 in all rows of the operation.
+    G1_SCALAR_MUL_START_ROW_OFFSET -> Selector set 1 in the first row of the operation, where the accumulator is constrained to be the point at infinity.
+    G1_SCALAR_MUL_RES_ROW_OFFSET -> Selector set 1 in the last row of the operation, where the result of the last step is set.
+    G1_SCALAR_MUL_X_OFFSET, G1_SCALAR_MUL_Y_OFFSET, G1_SCALAR_MUL_INFINITY_OFFSET -> offsets where the input point and its infinity flag are set.
+    G1_SCALAR_MUL_SCALAR_BITS_OFFSET -> offset where the bit decomposition of the scalar is set, least significant bit first. 256 bits, the last of which is constrained to be zero.
+    G1_SCALAR_MUL_STEP_SELECTORS -> G1_SCALAR_MUL_BITS selectors, the i-th one set 1 in the rows of the i-th step. Step i processes bit (G1_SCALAR_MUL_BITS - 1 - i) of the scalar.
+    G1_SCALAR_MUL_BIT_OFFSET -> the bit processed in the current step.
+    G1_SCALAR_MUL_DOUBLE_OFFSET -> offset where the doubling of the accumulator is done.
+    G1_SCALAR_MUL_ADD_OFFSET -> offset where the addition of the doubled accumulator with the point (or the point at infinity) is done.
+    G1_SCALAR_MUL_EQUAL_OFFSET -> Flag set 1 in the rows of a step whose addition inputs are the same finite point. The addition is then turned off and only its inputs are filled.
+    G1_SCALAR_MUL_ADD_DOUBLE_OFFSET -> offset where the doubled accumulator, the first input of the addition, is doubled.
+    G1_SCALAR_MUL_RES_X_OFFSET, G1_SCALAR_MUL_RES_Y_OFFSET, G1_SCALAR_MUL_RES_INFINITY_OFFSET -> offsets where the result of the step is set, from G1_SCALAR_MUL_ADD_DOUBLE_OFFSET if the equal flag is set and from G1_SCALAR_MUL_ADD_OFFSET otherwise.
+*/
+pub const G1_SCALAR_MUL_SELECTOR_OFFSET: usize = 0;
+pub const G1_SCALAR_MUL_CHECK_OFFSET: usize = G1_SCALAR_MUL_SELECTOR_OFFSET + 1;
+pub const G1_SCALAR_MUL_START_ROW_OFFSET: usize = G1_SCALAR_MUL_CHECK_OFFSET + 1;
+pub const G1_SCALAR_MUL_RES_ROW_OFFSET: usize = G1_SCALAR_MUL_START_ROW_OFFSET + 1;
+pub const G1_SCALAR_MUL_X_OFFSET: usize = G1_SCALAR_MUL_RES_ROW_OFFSET + 1;
+pub const G1_SCALAR_MUL_Y_OFFSET: usize = G1_SCALAR_MUL_X_OFFSET + 12;
+pub const G1_SCALAR_MUL_INFINITY_OFFSET: usize = G1_SCALAR_MUL_Y_OFFSET + 12;
+pub const G1_SCALAR_MUL_SCALAR_BITS_OFFSET: usize = G1_SCALAR_MUL_INFINITY_OFFSET + 1;
+pub const G1_SCALAR_MUL_STEP_SELECTORS: usize = G1_SCALAR_MUL_SCALAR_BITS_OFFSET + 256;
+pub const G1_SCALAR_MUL_BIT_OFFSET: usize = G1_SCALAR_MUL_STEP_SELECTORS + G1_SCALAR_MUL_BITS;
+pub const G1_SCALAR_MUL_DOUBLE_OFFSET: usize = G1_SCALAR_MUL_BIT_OFFSET + 1;
+pub const G1_SCALAR_MUL_ADD_OFFSET: usize = G1_SCALAR_MUL_DOUBLE_OFFSET + G1_DOUBLE_TOTAL;
+pub const G1_SCALAR_MUL_EQUAL_OFFSET: usize = G1_SCALAR_MUL_ADD_OFFSET + G1_ADD_TOTAL;
+pub const G1_SCALAR_MUL_ADD_DOUBLE_OFFSET: usize = G1_SCALAR_MUL_EQUAL_OFFSET + 1;
+pub const G1_SCALAR_MUL_RES_X_OFFSET: usize = G1_SCALAR_MUL_ADD_DOUBLE_OFFSET + G1_DOUBLE_TOTAL;
+pub const G1_SCALAR_MUL_RES_Y_OFFSET: usize = G1_SCALAR_MUL_RES_X_OFFSET + 12;
+pub const G1_SCALAR_MUL_RES_INFINITY_OFFSET: usize = G1_SCALAR_MUL_RES_Y_OFFSET + 12;
+pub const G1_SCALAR_MUL_TOTAL: usize = G1_SCALAR_MUL_RES_INFINITY_OFFSET + 1;
+
+/// Degree of the constraints of [add_g1_scalar_mul_constraints] and of the starks built on it. The addition, of degree 3, is gated by the negated equal flag.
+pub const G1_SCALAR_MUL_CONSTRAINT_DEGREE: usize = 4;
+
+/// Pairs of (step result column, addition result column, doubling result column), along with the number of limbs.
+const STEP_RES_LINKS: [(usize, usize, usize, usize); 3] = [
+    (G1_SCALAR_MUL_RES_X_OFFSET, G1_ADD_RES_X_OFFSET, G1_DOUBLE_RES_X_OFFSET, 12),
+    (G1_SCALAR_MUL_RES_Y_OFFSET, G1_ADD_RES_Y_OFFSET, G1_DOUBLE_RES_Y_OFFSET, 12),
+    (G1_SCALAR_MUL_RES_INFINITY_OFFSET, G1_ADD_RES_INFINITY_OFFSET, G1_DOUBLE_RES_INFINITY_OFFSET, 1),
+];
+
+/// Pairs of (doubling input column, addition input column), along with the number of limbs. The doubling input is the first input of the addition.
+const ADD_DOUBLE_INPUT_LINKS: [(usize, usize, usize); 3] = [
+    (G1_DOUBLE_X_OFFSET, G1_ADD_X1_OFFSET, 12),
+    (G1_DOUBLE_Y_OFFSET, G1_ADD_Y1_OFFSET, 12),
+    (G1_DOUBLE_INFINITY_OFFSET, G1_ADD_INFINITY1_OFFSET, 1),
+];
+
+pub const TOTAL_COLUMNS: usize = G1_SCALAR_MUL_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the input point (x, y) and its infinity flag, the scalar as 8 u32 limbs, then the result point (x, y) and the infinity flag of the result.
+*/
+pub const PIS_X_OFFSET: usize = 0;
+pub const PIS_Y_OFFSET: usize = PIS_X_OFFSET + 12;
+pub const PIS_INFINITY_OFFSET: usize = PIS_Y_OFFSET + 12;
+pub const PIS_SCALAR_OFFSET: usize = PIS_INFINITY_OFFSET + 1;
+pub const PIS_RES_X_OFFSET: usize = PIS_SCALAR_OFFSET + 8;
+pub const PIS_RES_Y_OFFSET: usize = PIS_RES_X_OFFSET + 12;
+pub const PIS_RES_INFINITY_OFFSET: usize = PIS_RES_Y_OFFSET + 12;
+pub const PUBLIC_INPUTS: usize = PIS_RES_INFINITY_OFFSET + 1;
+
+/// Splits a scalar of at most 255 bits into 8 u32 limbs, least significant limb first.
+pub fn get_scalar_limbs(k: &BigUint) -> [u32; 8] {
+    assert!(k.bits() <= G1_SCALAR_MUL_BITS as u64);
+    let mut limbs = [0u32; 8];
+    for (i, d) in k.to_u32_digits().iter().enumerate() {
+        limbs[i] = *d;
+    }
+    limbs
+}
+
+/// Fills the stark trace of G1 scalar multiplication. Inputs are an affine point and a scalar of at most 255 bits. Needs G1_SCALAR_MUL_ROWS rows starting from `start_row`. For each bit of the scalar, fills the doubling of the accumulator and the addition of the doubled accumulator with the point if the bit is 1, or with the point at infinity otherwise, alongside the doubling of the doubled accumulator which is used instead when it is equal to the point. Returns `[k]P`.
+pub fn fill_trace_g1_scalar_mul<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, p: &G1Affine, k: &BigUint, start_row: usize, start_col: usize) -> G1Affine {
+    let end_row = start_row + G1_SCALAR_MUL_ROWS - 1;
+    assert!(k.bits() <= G1_SCALAR_MUL_BITS as u64);
+    let bits = ScalarBits::new(k);
+    for row in start_row..end_row + 1 {
+        trace[row][start_col + G1_SCALAR_MUL_SELECTOR_OFFSET] = F::ONE;
+        trace[row][start_col + G1_SCALAR_MUL_CHECK_OFFSET] = F::ONE;
+        assign_u32_in_series(trace, row, start_col + G1_SCALAR_MUL_X_OFFSET, &p.x.0);
+        assign_u32_in_series(trace, row, start_col + G1_SCALAR_MUL_Y_OFFSET, &p.y.0);
+        trace[row][start_col + G1_SCALAR_MUL_INFINITY_OFFSET] = F::from_bool(p.infinity);
+        assign_u32_in_series(trace, row, start_col + G1_SCALAR_MUL_SCALAR_BITS_OFFSET, bits.as_array());
+    }
+    trace[end_row][start_col + G1_SCALAR_MUL_SELECTOR_OFFSET] = F::ZERO;
+    trace[start_row][start_col + G1_SCALAR_MUL_START_ROW_OFFSET] = F::ONE;
+    trace[end_row][start_col + G1_SCALAR_MUL_RES_ROW_OFFSET] = F::ONE;
+
+    let mut acc = G1Affine::identity();
+    for step in 0..G1_SCALAR_MUL_BITS {
+        let step_start = start_row + step * G1_SCALAR_MUL_STEP_ROWS;
+        let step_end = step_start + G1_SCALAR_MUL_STEP_ROWS - 1;
+        let bit = bits.as_array()[G1_SCALAR_MUL_BITS - 1 - step];
+        for row in step_start..step_end + 1 {
+            trace[row][start_col + G1_SCALAR_MUL_STEP_SELECTORS + step] = F::ONE;
+            trace[row][start_col + G1_SCALAR_MUL_BIT_OFFSET] = F::from_canonical_u32(bit);
+        }
+        let doubled = fill_trace_g1_double(trace, &acc, step_start, step_end, start_col + G1_SCALAR_MUL_DOUBLE_OFFSET);
+        let q = if bit == 1 {
+            *p
+        } else {
+            G1Affine::identity()
+        };
+        let doubled_twice = fill_trace_g1_double(trace, &doubled, step_start, step_end, start_col + G1_SCALAR_MUL_ADD_DOUBLE_OFFSET);
+        let equal = !q.infinity && doubled == q;
+        acc = if equal {
+            fill_trace_g1_add_inputs(trace, &q, step_start, step_end, start_col + G1_SCALAR_MUL_ADD_OFFSET);
+            doubled_twice
+        } else {
+            fill_trace_g1_add(trace, &doubled, &q, step_start, step_end, start_col + G1_SCALAR_MUL_ADD_OFFSET)
+                .expect("fill_trace_g1_scalar_mul: addition of a point to itself outside an equal step")
+        };
+        for row in step_start..step_end + 1 {
+            trace[row][start_col + G1_SCALAR_MUL_EQUAL_OFFSET] = F::from_bool(equal);
+            assign_u32_in_series(trace, row, start_col + G1_SCALAR_MUL_RES_X_OFFSET, &acc.x.0);
+            assign_u32_in_series(trace, row, start_col + G1_SCALAR_MUL_RES_Y_OFFSET, &acc.y.0);
+            trace[row][start_col + G1_SCALAR_MUL_RES_INFINITY_OFFSET] = F::from_bool(acc.infinity);
+        }
+    }
+    assert_eq!(acc, p.mul_scalar(k));
+    acc
+}
+
+/// Constraints G1 scalar multiplication.
+///
+/// Constraints the input point and the scalar bits to be same across the rows wherever the selector is on, and the scalar bits to be boolean with the 256th bit zero.
+/// When `G1_SCALAR_MUL_START_ROW` is set, constraints the accumulator (input of the doubling) to be the point at infinity and the first step selector to be on. The step selectors shift by one at the end of each step, where the result of the step is constrained to be the input of the doubling of the next row.
+/// `G1_SCALAR_MUL_RES_ROW` is constrained to be set only on the last row of the last step, which is the only row of the operation where the selector is off.
+/// Constraints the bit of the current step with the step selectors and the scalar bits. Constraints the doubled accumulator to be the first input of the addition, and the second input of the addition to be the point if the bit is 1, or the point at infinity otherwise.
+/// Constraints the second doubling to double the first input of the addition. Constraints the equal flag to be boolean, same across the rows of a step, and set only if both inputs of the addition are the same finite point. The addition is gated by the negated flag, and the result of the step, which is the input of the next doubling, is the result of the second doubling if the flag is set, of the addition otherwise.
+/// The constraints have degree at most [G1_SCALAR_MUL_CONSTRAINT_DEGREE].
+pub fn add_g1_scalar_mul_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let selector = local_values[start_col + G1_SCALAR_MUL_SELECTOR_OFFSET];
+    let check = local_values[start_col + G1_SCALAR_MUL_CHECK_OFFSET];
+    let start_row = local_values[start_col + G1_SCALAR_MUL_START_ROW_OFFSET];
+    let double_col = start_col + G1_SCALAR_MUL_DOUBLE_OFFSET;
+    let add_col = start_col + G1_SCALAR_MUL_ADD_OFFSET;
+    // 1 on the last row of every step, except the last step
+    let step_end = selector - local_values[double_col + G1_DOUBLE_SELECTOR_OFFSET];
+    let bit = local_values[start_col + G1_SCALAR_MUL_BIT_OFFSET];
+    let add_double_col = start_col + G1_SCALAR_MUL_ADD_DOUBLE_OFFSET;
+    let equal = local_values[start_col + G1_SCALAR_MUL_EQUAL_OFFSET];
+
+    for i in 0..25 {
+        yield_constr.constraint_transition(
+            selector *
+            (local_values[start_col + G1_SCALAR_MUL_X_OFFSET + i] - next_values[start_col + G1_SCALAR_MUL_X_OFFSET + i])
+        );
+    }
+    for i in 0..256 {
+        let b = local_values[start_col + G1_SCALAR_MUL_SCALAR_BITS_OFFSET + i];
+        yield_constr.constraint_transition(
+            selector *
+            (b - next_values[start_col + G1_SCALAR_MUL_SCALAR_BITS_OFFSET + i])
+        );
+        yield_constr.constraint(check * b * (P::ONES - b));
+    }
+    yield_constr.constraint(check * local_values[start_col + G1_SCALAR_MUL_SCALAR_BITS_OFFSET + 255]);
+
+    yield_constr.constraint(check * (P::ONES - local_values[double_col + G1_DOUBLE_CHECK_OFFSET]));
+    yield_constr.constraint(check * (P::ONES - local_values[add_col + G1_ADD_CHECK_OFFSET]));
+    yield_constr.constraint(check * (P::ONES - local_values[add_double_col + G1_DOUBLE_CHECK_OFFSET]));
+
+    for i in 0..12 {
+        yield_constr.constraint(start_row * local_values[double_col + G1_DOUBLE_X_OFFSET + i]);
+        yield_constr.constraint(start_row * local_values[double_col + G1_DOUBLE_Y_OFFSET + i]);
+    }
+    yield_constr.constraint(start_row * (P::ONES - local_values[double_col + G1_DOUBLE_INFINITY_OFFSET]));
+    for i in 0..G1_SCALAR_MUL_BITS {
+        let val = if i == 0 {
+            P::ONES
+        } else {
+            P::ZEROS
+        };
+        yield_constr.constraint(start_row * (local_values[start_col + G1_SCALAR_MUL_STEP_SELECTORS + i] - val));
+    }
+
+    // The result row must be the last row of the last step
+    let res_row = local_values[start_col + G1_SCALAR_MUL_RES_ROW_OFFSET];
+    yield_constr.constraint(
+        res_row *
+        (P::ONES - local_values[start_col + G1_SCALAR_MUL_STEP_SELECTORS + G1_SCALAR_MUL_BITS - 1])
+    );
+    yield_constr.constraint(res_row * local_values[add_col + G1_ADD_SELECTOR_OFFSET]);
+    yield_constr.constraint(
+        (check - selector) * (P::ONES - res_row)
+    );
+
+    for i in 0..G1_SCALAR_MUL_BITS {
+        let prev = if i == 0 {
+            P::ZEROS
+        } else {
+            local_values[start_col + G1_SCALAR_MUL_STEP_SELECTORS + i - 1]
+        };
+        let curr = local_values[start_col + G1_SCALAR_MUL_STEP_SELECTORS + i];
+        yield_constr.constraint_transition(
+            selector *
+            (next_values[start_col + G1_SCALAR_MUL_STEP_SELECTORS + i] - curr * (P::ONES - step_end) - prev * step_end)
+        );
+    }
+    for (res, input) in [(G1_SCALAR_MUL_RES_X_OFFSET, G1_DOUBLE_X_OFFSET), (G1_SCALAR_MUL_RES_Y_OFFSET, G1_DOUBLE_Y_OFFSET)] {
+        for i in 0..12 {
+            yield_constr.constraint_transition(
+                step_end *
+                (next_values[double_col + input + i] - local_values[start_col + res + i])
+            );
+        }
+    }
+    yield_constr.constraint_transition(
+        step_end *
+        (next_values[double_col + G1_DOUBLE_INFINITY_OFFSET] - local_values[start_col + G1_SCALAR_MUL_RES_INFINITY_OFFSET])
+    );
+
+    let step_bit = (0..G1_SCALAR_MUL_BITS).fold(P::ZEROS, |acc, i|
+        acc + local_values[start_col + G1_SCALAR_MUL_STEP_SELECTORS + i] * local_values[start_col + G1_SCALAR_MUL_SCALAR_BITS_OFFSET + G1_SCALAR_MUL_BITS - 1 - i]
+    );
+    yield_constr.constraint(check * (bit - step_bit));
+
+    for (input, res) in [(G1_ADD_X1_OFFSET, G1_DOUBLE_RES_X_OFFSET), (G1_ADD_Y1_OFFSET, G1_DOUBLE_RES_Y_OFFSET)] {
+        for i in 0..12 {
+            yield_constr.constraint(
+                check *
+                (local_values[add_col + input + i] - local_values[double_col + res + i])
+            );
+        }
+    }
+    yield_constr.constraint(
+        check *
+        (local_values[add_col + G1_ADD_INFINITY1_OFFSET] - local_values[double_col + G1_DOUBLE_RES_INFINITY_OFFSET])
+    );
+    for (input, point) in [(G1_ADD_X2_OFFSET, G1_SCALAR_MUL_X_OFFSET), (G1_ADD_Y2_OFFSET, G1_SCALAR_MUL_Y_OFFSET)] {
+        for i in 0..12 {
+            yield_constr.constraint(
+                check *
+                (local_values[add_col + input + i] - bit * local_values[start_col + point + i])
+            );
+        }
+    }
+    yield_constr.constraint(
+        check *
+        (local_values[add_col + G1_ADD_INFINITY2_OFFSET] - (P::ONES - bit) - bit * local_values[start_col + G1_SCALAR_MUL_INFINITY_OFFSET])
+    );
+
+    yield_constr.constraint(
+        check *
+        (local_values[add_double_col + G1_DOUBLE_SELECTOR_OFFSET] - local_values[double_col + G1_DOUBLE_SELECTOR_OFFSET])
+    );
+    for (double_input, add_input, limbs) in ADD_DOUBLE_INPUT_LINKS {
+        for i in 0..limbs {
+            yield_constr.constraint(
+                check *
+                (local_values[add_double_col + double_input + i] - local_values[add_col + add_input + i])
+            );
+        }
+    }
+    yield_constr.constraint(check * equal * (P::ONES - equal));
+    yield_constr.constraint_transition(
+        local_values[add_col + G1_ADD_SELECTOR_OFFSET] *
+        (next_values[start_col + G1_SCALAR_MUL_EQUAL_OFFSET] - equal)
+    );
+    for col in [G1_ADD_INFINITY1_OFFSET, G1_ADD_INFINITY2_OFFSET] {
+        yield_constr.constraint(check * equal * local_values[add_col + col]);
+    }
+    for i in 0..12 {
+        for (col1, col2) in [(G1_ADD_X1_OFFSET, G1_ADD_X2_OFFSET), (G1_ADD_Y1_OFFSET, G1_ADD_Y2_OFFSET)] {
+            yield_constr.constraint(
+                check * equal *
+                (local_values[add_col + col1 + i] - local_values[add_col + col2 + i])
+            );
+        }
+    }
+    for (res_col, add_res_col, double_res_col, limbs) in STEP_RES_LINKS {
+        for i in 0..limbs {
+            yield_constr.constraint(
+                check *
+                (local_values[start_col + res_col + i]
+                    - equal * local_values[add_double_col + double_res_col + i]
+                    - (P::ONES - equal) * local_values[add_col + add_res_col + i])
+            );
+        }
+    }
+
+    add_g1_double_constraints(local_values, next_values, yield_constr, double_col, None);
+    add_g1_double_constraints(local_values, next_values, yield_constr, add_double_col, None);
+    add_g1_add_constraints(local_values, next_values, yield_constr, add_col, Some(P::ONES - equal));
+}
+
+pub fn add_g1_scalar_mul_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+) {
+    let one = builder.constant_extension(F::Extension::ONE);
+    let selector = local_values[start_col + G1_SCALAR_MUL_SELECTOR_OFFSET];
+    let check = local_values[start_col + G1_SCALAR_MUL_CHECK_OFFSET];
+    let start_row = local_values[start_col + G1_SCALAR_MUL_START_ROW_OFFSET];
+    let double_col = start_col + G1_SCALAR_MUL_DOUBLE_OFFSET;
+    let add_col = start_col + G1_SCALAR_MUL_ADD_OFFSET;
+    let step_end = builder.sub_extension(selector, local_values[double_col + G1_DOUBLE_SELECTOR_OFFSET]);
+    let not_step_end = builder.sub_extension(one, step_end);
+    let bit = local_values[start_col + G1_SCALAR_MUL_BIT_OFFSET];
+    let not_bit = builder.sub_extension(one, bit);
+    let add_double_col = start_col + G1_SCALAR_MUL_ADD_DOUBLE_OFFSET;
+    let equal = local_values[start_col + G1_SCALAR_MUL_EQUAL_OFFSET];
+    let not_equal = builder.sub_extension(one, equal);
+
+    for i in 0..25 {
+        let c = builder.sub_extension(local_values[start_col + G1_SCALAR_MUL_X_OFFSET + i], next_values[start_col + G1_SCALAR_MUL_X_OFFSET + i]);
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint_transition(builder, c);
+    }
+    for i in 0..256 {
+        let b = local_values[start_col + G1_SCALAR_MUL_SCALAR_BITS_OFFSET + i];
+        let c = builder.sub_extension(b, next_values[start_col + G1_SCALAR_MUL_SCALAR_BITS_OFFSET + i]);
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint_transition(builder, c);
+
+        let not_b = builder.sub_extension(one, b);
+        let c = builder.mul_extension(b, not_b);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+    }
+    let c = builder.mul_extension(check, local_values[start_col + G1_SCALAR_MUL_SCALAR_BITS_OFFSET + 255]);
+    yield_constr.constraint(builder, c);
+
+    let c = builder.sub_extension(one, local_values[double_col + G1_DOUBLE_CHECK_OFFSET]);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.sub_extension(one, local_values[add_col + G1_ADD_CHECK_OFFSET]);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.sub_extension(one, local_values[add_double_col + G1_DOUBLE_CHECK_OFFSET]);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+
+    for i in 0..12 {
+        let c = builder.mul_extension(start_row, local_values[double_col + G1_DOUBLE_X_OFFSET + i]);
+        yield_constr.constraint(builder, c);
+        let c = builder.mul_extension(start_row, local_values[double_col + G1_DOUBLE_Y_OFFSET + i]);
+        yield_constr.constraint(builder, c);
+    }
+    let c = builder.sub_extension(one, local_values[double_col + G1_DOUBLE_INFINITY_OFFSET]);
+    let c = builder.mul_extension(start_row, c);
+    yield_constr.constraint(builder, c);
+    for i in 0..G1_SCALAR_MUL_BITS {
+        let c = if i == 0 {
+            builder.sub_extension(local_values[start_col + G1_SCALAR_MUL_STEP_SELECTORS + i], one)
+        } else {
+            local_values[start_col + G1_SCALAR_MUL_STEP_SELECTORS + i]
+        };
+        let c = builder.mul_extension(start_row, c);
+        yield_constr.constraint(builder, c);
+    }
+
+    let res_row = local_values[start_col + G1_SCALAR_MUL_RES_ROW_OFFSET];
+    let c = builder.sub_extension(one, local_values[start_col + G1_SCALAR_MUL_STEP_SELECTORS + G1_SCALAR_MUL_BITS - 1]);
+    let c = builder.mul_extension(res_row, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(res_row, local_values[add_col + G1_ADD_SELECTOR_OFFSET]);
+    yield_constr.constraint(builder, c);
+    let not_res_row = builder.sub_extension(one, res_row);
+    let c = builder.sub_extension(check, selector);
+    let c = builder.mul_extension(c, not_res_row);
+    yield_constr.constraint(builder, c);
+
+    for i in 0..G1_SCALAR_MUL_BITS {
+        let curr = local_values[start_col + G1_SCALAR_MUL_STEP_SELECTORS + i];
+        let c = builder.mul_extension(curr, not_step_end);
+        let c = builder.sub_extension(next_values[start_col + G1_SCALAR_MUL_STEP_SELECTORS + i], c);
+        let c = if i == 0 {
+            c
+        } else {
+            let prev = builder.mul_extension(local_values[start_col + G1_SCALAR_MUL_STEP_SELECTORS + i - 1], step_end);
+            builder.sub_extension(c, prev)
+        };
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint_transition(builder, c);
+    }
+    for (res, input) in [(G1_SCALAR_MUL_RES_X_OFFSET, G1_DOUBLE_X_OFFSET), (G1_SCALAR_MUL_RES_Y_OFFSET, G1_DOUBLE_Y_OFFSET)] {
+        for i in 0..12 {
+            let c = builder.sub_extension(next_values[double_col + input + i], local_values[start_col + res + i]);
+            let c = builder.mul_extension(step_end, c);
+            yield_constr.constraint_transition(builder, c);
+        }
+    }
+    let c = builder.sub_extension(next_values[double_col + G1_DOUBLE_INFINITY_OFFSET], local_values[start_col + G1_SCALAR_MUL_RES_INFINITY_OFFSET]);
+    let c = builder.mul_extension(step_end, c);
+    yield_constr.constraint_transition(builder, c);
+
+    let zero = builder.constant_extension(F::Extension::ZERO);
+    let step_bit = (0..G1_SCALAR_MUL_BITS).fold(zero, |acc, i| {
+        let c = builder.mul_extension(local_values[start_col + G1_SCALAR_MUL_STEP_SELECTORS + i], local_values[start_col + G1_SCALAR_MUL_SCALAR_BITS_OFFSET + G1_SCALAR_MUL_BITS - 1 - i]);
+        builder.add_extension(acc, c)
+    });
+    let c = builder.sub_extension(bit, step_bit);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+
+    for (input, res) in [(G1_ADD_X1_OFFSET, G1_DOUBLE_RES_X_OFFSET), (G1_ADD_Y1_OFFSET, G1_DOUBLE_RES_Y_OFFSET)] {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[add_col + input + i], local_values[double_col + res + i]);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+    let c = builder.sub_extension(local_values[add_col + G1_ADD_INFINITY1_OFFSET], local_values[double_col + G1_DOUBLE_RES_INFINITY_OFFSET]);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    for (input, point) in [(G1_ADD_X2_OFFSET, G1_SCALAR_MUL_X_OFFSET), (G1_ADD_Y2_OFFSET, G1_SCALAR_MUL_Y_OFFSET)] {
+        for i in 0..12 {
+            let c = builder.mul_extension(bit, local_values[start_col + point + i]);
+            let c = builder.sub_extension(local_values[add_col + input + i], c);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+    let c = builder.mul_extension(bit, local_values[start_col + G1_SCALAR_MUL_INFINITY_OFFSET]);
+    let c = builder.add_extension(not_bit, c);
+    let c = builder.sub_extension(local_values[add_col + G1_ADD_INFINITY2_OFFSET], c);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+
+    let c = builder.sub_extension(local_values[add_double_col + G1_DOUBLE_SELECTOR_OFFSET], local_values[double_col + G1_DOUBLE_SELECTOR_OFFSET]);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    for (double_input, add_input, limbs) in ADD_DOUBLE_INPUT_LINKS {
+        for i in 0..limbs {
+            let c = builder.sub_extension(local_values[add_double_col + double_input + i], local_values[add_col + add_input + i]);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+    let check_equal = builder.mul_extension(check, equal);
+    let c = builder.mul_extension(check_equal, not_equal);
+    yield_constr.constraint(builder, c);
+    let c = builder.sub_extension(next_values[start_col + G1_SCALAR_MUL_EQUAL_OFFSET], equal);
+    let c = builder.mul_extension(local_values[add_col + G1_ADD_SELECTOR_OFFSET], c);
+    yield_constr.constraint_transition(builder, c);
+    for col in [G1_ADD_INFINITY1_OFFSET, G1_ADD_INFINITY2_OFFSET] {
+        let c = builder.mul_extension(check_equal, local_values[add_col + col]);
+        yield_constr.constraint(builder, c);
+    }
+    for i in 0..12 {
+        for (col1, col2) in [(G1_ADD_X1_OFFSET, G1_ADD_X2_OFFSET), (G1_ADD_Y1_OFFSET, G1_ADD_Y2_OFFSET)] {
+            let c = builder.sub_extension(local_values[add_col + col1 + i], local_values[add_col + col2 + i]);
+            let c = builder.mul_extension(check_equal, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+    for (res_col, add_res_col, double_res_col, limbs) in STEP_RES_LINKS {
+        for i in 0..limbs {
+            let diff = builder.sub_extension(local_values[add_double_col + double_res_col + i], local_values[add_col + add_res_col + i]);
+            let sum = builder.mul_add_extension(equal, diff, local_values[add_col + add_res_col + i]);
+            let c = builder.sub_extension(local_values[start_col + res_col + i], sum);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+
+    add_g1_double_constraints_ext_circuit(builder, yield_constr, local_values, next_values, double_col, None);
+    add_g1_double_constraints_ext_circuit(builder, yield_constr, local_values, next_values, add_double_col, None);
+    add_g1_add_constraints_ext_circuit(builder, yield_constr, local_values, next_values, add_col, Some(not_equal));
+}
+
+#[derive(Clone, Copy)]
+pub struct G1ScalarMulStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> G1ScalarMulStark<F, D> {
+    /// Rows used by one scalar multiplication, `G1_SCALAR_MUL_ROWS`.
+    pub fn required_rows() -> usize {
+        G1_SCALAR_MUL_ROWS
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(p: &G1Affine, k: &BigUint, res: &G1Affine) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for i in 0..12 {
+            public_inputs[PIS_X_OFFSET + i] = F::from_canonical_u32(p.x.0[i]);
+            public_inputs[PIS_Y_OFFSET + i] = F::from_canonical_u32(p.y.0[i]);
+            public_inputs[PIS_RES_X_OFFSET + i] = F::from_canonical_u32(res.x.0[i]);
+            public_inputs[PIS_RES_Y_OFFSET + i] = F::from_canonical_u32(res.y.0[i]);
+        }
+        for (i, limb) in get_scalar_limbs(k).iter().enumerate() {
+            public_inputs[PIS_SCALAR_OFFSET + i] = F::from_canonical_u32(*limb);
+        }
+        public_inputs[PIS_INFINITY_OFFSET] = F::from_bool(p.infinity);
+        public_inputs[PIS_RES_INFINITY_OFFSET] = F::from_bool(res.infinity);
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, p: &G1Affine, k: &BigUint) -> Vec<[F; TOTAL_COLUMNS]> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_g1_scalar_mul(&mut trace, p, k, 0, 0);
+        trace
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for G1ScalarMulStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[G1_SCALAR_MUL_CHECK_OFFSET] - P::ONES);
+        yield_constr.constraint_first_row(local_values[G1_SCALAR_MUL_START_ROW_OFFSET] - P::ONES);
+        for i in 0..12 {
+            yield_constr.constraint_first_row(local_values[G1_SCALAR_MUL_X_OFFSET + i] - public_inputs[PIS_X_OFFSET + i]);
+            yield_constr.constraint_first_row(local_values[G1_SCALAR_MUL_Y_OFFSET + i] - public_inputs[PIS_Y_OFFSET + i]);
+        }
+        yield_constr.constraint_first_row(local_values[G1_SCALAR_MUL_INFINITY_OFFSET] - public_inputs[PIS_INFINITY_OFFSET]);
+        for i in 0..8 {
+            let limb = (0..32).fold(P::ZEROS, |acc, j|
+                acc + local_values[G1_SCALAR_MUL_SCALAR_BITS_OFFSET + i*32 + j] * FE::from_canonical_u64(1 << j)
+            );
+            yield_constr.constraint_first_row(limb - public_inputs[PIS_SCALAR_OFFSET + i]);
+        }
+
+        let res_row = local_values[G1_SCALAR_MUL_RES_ROW_OFFSET];
+        for i in 0..12 {
+            yield_constr.constraint(
+                res_row *
+                (local_values[G1_SCALAR_MUL_RES_X_OFFSET + i] - public_inputs[PIS_RES_X_OFFSET + i])
+            );
+            yield_constr.constraint(
+                res_row *
+                (local_values[G1_SCALAR_MUL_RES_Y_OFFSET + i] - public_inputs[PIS_RES_Y_OFFSET + i])
+            );
+        }
+        yield_constr.constraint(
+            res_row *
+            (local_values[G1_SCALAR_MUL_RES_INFINITY_OFFSET] - public_inputs[PIS_RES_INFINITY_OFFSET])
+        );
+
+        add_g1_scalar_mul_constraints(local_values, next_values, yield_constr, 0);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[G1_SCALAR_MUL_CHECK_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        let c = builder.sub_extension(local_values[G1_SCALAR_MUL_START_ROW_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[G1_SCALAR_MUL_X_OFFSET + i], public_inputs[PIS_X_OFFSET + i]);
+            yield_constr.constraint_first_row(builder, c);
+            let c = builder.sub_extension(local_values[G1_SCALAR_MUL_Y_OFFSET + i], public_inputs[PIS_Y_OFFSET + i]);
+            yield_constr.constraint_first_row(builder, c);
+        }
+        let c = builder.sub_extension(local_values[G1_SCALAR_MUL_INFINITY_OFFSET], public_inputs[PIS_INFINITY_OFFSET]);
+        yield_constr.constraint_first_row(builder, c);
+        for i in 0..8 {
+            let zero = builder.constant_extension(F::Extension::ZERO);
+            let limb = (0..32).fold(zero, |acc, j| {
+                let tmp_const = builder.constant_extension(F::Extension::from_canonical_u64(1 << j));
+                let c = builder.mul_extension(local_values[G1_SCALAR_MUL_SCALAR_BITS_OFFSET + i*32 + j], tmp_const);
+                builder.add_extension(acc, c)
+            });
+            let c = builder.sub_extension(limb, public_inputs[PIS_SCALAR_OFFSET + i]);
+            yield_constr.constraint_first_row(builder, c);
+        }
+
+        let res_row = local_values[G1_SCALAR_MUL_RES_ROW_OFFSET];
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[G1_SCALAR_MUL_RES_X_OFFSET + i], public_inputs[PIS_RES_X_OFFSET + i]);
+            let c = builder.mul_extension(res_row, c);
+            yield_constr.constraint(builder, c);
+            let c = builder.sub_extension(local_values[G1_SCALAR_MUL_RES_Y_OFFSET + i], public_inputs[PIS_RES_Y_OFFSET + i]);
+            let c = builder.mul_extension(res_row, c);
+            yield_constr.constraint(builder, c);
+        }
+        let c = builder.sub_extension(local_values[G1_SCALAR_MUL_RES_INFINITY_OFFSET], public_inputs[PIS_RES_INFINITY_OFFSET]);
+        let c = builder.mul_extension(res_row, c);
+        yield_constr.constraint(builder, c);
+
+        add_g1_scalar_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        G1_SCALAR_MUL_CONSTRAINT_DEGREE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{group_order, G1Affine};
+
+    use super::{G1ScalarMulStark, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = G1ScalarMulStark<F, D>;
+
+    fn prove_and_verify_scalar_mul(p: &G1Affine, k: &BigUint) -> G1Affine {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(4096);
+        let res = p.mul_scalar(k);
+        let public_inputs = S::public_inputs(p, k, &res);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(p, k);
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        res
+    }
+
+    #[test]
+    fn test_g1_scalar_mul_small_scalars() {
+        let g = G1Affine::generator();
+        let mut expected = G1Affine::identity();
+        for k in 0u32..6 {
+            assert_eq!(g.mul_scalar(&BigUint::from(k)), expected);
+            expected = expected + g;
+        }
+        let res = prove_and_verify_scalar_mul(&g, &BigUint::from(5u32));
+        assert_eq!(res, g.double().double() + g);
+    }
+
+    #[test]
+    fn test_g1_scalar_mul_group_order_minus_one() {
+        let g = G1Affine::generator();
+        let res = prove_and_verify_scalar_mul(&g, &(group_order() - 1u32));
+        assert_eq!(res, -g);
+    }
+}
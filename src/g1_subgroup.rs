@@ -0,0 +1,307 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use anyhow::{ensure, Result};
+
+use crate::native::{group_order, G1Affine};
+
+use crate::g1_scalar_mul::*;
+use crate::on_curve::*;
+
+// G1 subgroup check layout offsets
+/*
+    These trace offsets are for checking if a G1 point lies in the prime order subgroup (super::native::G1Affine::is_in_subgroup). It needs G1_SCALAR_MUL_ROWS rows.
+    The multiplication of the point by the group order is done from the first column, with the scalar bits constrained to be the bits of the group order.
+    G1_SUBGROUP_ON_CURVE_OFFSET -> offset where the curve membership check of the point is done, in the first 12 rows. The point is constrained to be on the curve.
+*/
+pub const G1_SUBGROUP_ON_CURVE_OFFSET: usize = G1_SCALAR_MUL_TOTAL;
+pub const G1_SUBGROUP_TOTAL: usize = G1_SUBGROUP_ON_CURVE_OFFSET + G1_ON_CURVE_TOTAL;
+
+pub const TOTAL_COLUMNS: usize = G1_SUBGROUP_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the candidate point (x, y) and its infinity flag, then the membership result, which is 1 if the point lies in the prime order subgroup and 0 otherwise.
+*/
+pub const PIS_X_OFFSET: usize = 0;
+pub const PIS_Y_OFFSET: usize = PIS_X_OFFSET + 12;
+pub const PIS_INFINITY_OFFSET: usize = PIS_Y_OFFSET + 12;
+pub const PIS_IS_MEMBER_OFFSET: usize = PIS_INFINITY_OFFSET + 1;
+pub const PUBLIC_INPUTS: usize = PIS_IS_MEMBER_OFFSET + 1;
+
+/// Pairs of (curve membership check column, public input), along with the number of limbs.
+const ON_CURVE_PIS_LINKS: [(usize, usize, usize); 3] = [
+    (G1_ON_CURVE_X_OFFSET, PIS_X_OFFSET, 12),
+    (G1_ON_CURVE_Y_OFFSET, PIS_Y_OFFSET, 12),
+    (G1_ON_CURVE_INFINITY_OFFSET, PIS_INFINITY_OFFSET, 1),
+];
+
+/// Stark proving whether a G1 point lies in the prime order subgroup, by computing `[r]P` with the scalar multiplication gadget and checking whether it is the point at infinity.
+/// The point is constrained to be on the curve with the curve membership check gadget.
+#[derive(Clone, Copy)]
+pub struct G1SubgroupCheckStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> G1SubgroupCheckStark<F, D> {
+    /// Rows used by the subgroup check, the `G1_SCALAR_MUL_ROWS` of the multiplication by the group order.
+    pub fn required_rows() -> usize {
+        G1_SCALAR_MUL_ROWS
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    /// Lays out the public inputs for this stark, with the membership result computed natively. Returns an error if the point is not on the curve.
+    pub fn public_inputs(p: &G1Affine) -> Result<Vec<F>> {
+        ensure!(p.is_on_curve(), "the point is not on the G1 curve");
+        let is_member = p.is_in_subgroup();
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for i in 0..12 {
+            public_inputs[PIS_X_OFFSET + i] = F::from_canonical_u32(p.x.0[i]);
+            public_inputs[PIS_Y_OFFSET + i] = F::from_canonical_u32(p.y.0[i]);
+        }
+        public_inputs[PIS_INFINITY_OFFSET] = F::from_bool(p.infinity);
+        public_inputs[PIS_IS_MEMBER_OFFSET] = F::from_bool(is_member);
+        Ok(public_inputs)
+    }
+
+    /// Fills the trace for `[r]P` and the curve membership check of `p`. Returns an error if the point is not on the curve.
+    pub fn generate_trace(&self, p: &G1Affine) -> Result<Vec<[F; TOTAL_COLUMNS]>> {
+        ensure!(p.is_on_curve(), "the point is not on the G1 curve");
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_g1_scalar_mul(&mut trace, p, &group_order(), 0, 0);
+        fill_trace_g1_on_curve(&mut trace, p, 0, 11, G1_SUBGROUP_ON_CURVE_OFFSET);
+        Ok(trace)
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for G1SubgroupCheckStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[G1_SCALAR_MUL_CHECK_OFFSET] - P::ONES);
+        yield_constr.constraint_first_row(local_values[G1_SCALAR_MUL_START_ROW_OFFSET] - P::ONES);
+        for i in 0..12 {
+            yield_constr.constraint_first_row(local_values[G1_SCALAR_MUL_X_OFFSET + i] - public_inputs[PIS_X_OFFSET + i]);
+            yield_constr.constraint_first_row(local_values[G1_SCALAR_MUL_Y_OFFSET + i] - public_inputs[PIS_Y_OFFSET + i]);
+        }
+        yield_constr.constraint_first_row(local_values[G1_SCALAR_MUL_INFINITY_OFFSET] - public_inputs[PIS_INFINITY_OFFSET]);
+        yield_constr.constraint_first_row(local_values[G1_SUBGROUP_ON_CURVE_OFFSET + G1_ON_CURVE_CHECK_OFFSET] - P::ONES);
+        yield_constr.constraint_first_row(local_values[G1_SUBGROUP_ON_CURVE_OFFSET + G1_ON_CURVE_RES_OFFSET] - P::ONES);
+        for (col, pis, limbs) in ON_CURVE_PIS_LINKS {
+            for i in 0..limbs {
+                yield_constr.constraint_first_row(local_values[G1_SUBGROUP_ON_CURVE_OFFSET + col + i] - public_inputs[pis + i]);
+            }
+        }
+        let r_limbs = get_scalar_limbs(&group_order());
+        for i in 0..8 {
+            let limb = (0..32).fold(P::ZEROS, |acc, j|
+                acc + local_values[G1_SCALAR_MUL_SCALAR_BITS_OFFSET + i*32 + j] * FE::from_canonical_u64(1 << j)
+            );
+            yield_constr.constraint_first_row(limb - FE::from_canonical_u32(r_limbs[i]));
+        }
+
+        yield_constr.constraint(
+            local_values[G1_SCALAR_MUL_RES_ROW_OFFSET] *
+            (local_values[G1_SCALAR_MUL_RES_INFINITY_OFFSET] - public_inputs[PIS_IS_MEMBER_OFFSET])
+        );
+
+        add_g1_scalar_mul_constraints(local_values, next_values, yield_constr, 0);
+        add_g1_on_curve_constraints(local_values, next_values, yield_constr, G1_SUBGROUP_ON_CURVE_OFFSET, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[G1_SCALAR_MUL_CHECK_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        let c = builder.sub_extension(local_values[G1_SCALAR_MUL_START_ROW_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[G1_SCALAR_MUL_X_OFFSET + i], public_inputs[PIS_X_OFFSET + i]);
+            yield_constr.constraint_first_row(builder, c);
+            let c = builder.sub_extension(local_values[G1_SCALAR_MUL_Y_OFFSET + i], public_inputs[PIS_Y_OFFSET + i]);
+            yield_constr.constraint_first_row(builder, c);
+        }
+        let c = builder.sub_extension(local_values[G1_SCALAR_MUL_INFINITY_OFFSET], public_inputs[PIS_INFINITY_OFFSET]);
+        yield_constr.constraint_first_row(builder, c);
+        for col in [G1_ON_CURVE_CHECK_OFFSET, G1_ON_CURVE_RES_OFFSET] {
+            let c = builder.sub_extension(local_values[G1_SUBGROUP_ON_CURVE_OFFSET + col], one);
+            yield_constr.constraint_first_row(builder, c);
+        }
+        for (col, pis, limbs) in ON_CURVE_PIS_LINKS {
+            for i in 0..limbs {
+                let c = builder.sub_extension(local_values[G1_SUBGROUP_ON_CURVE_OFFSET + col + i], public_inputs[pis + i]);
+                yield_constr.constraint_first_row(builder, c);
+            }
+        }
+        let r_limbs = get_scalar_limbs(&group_order());
+        for i in 0..8 {
+            let zero = builder.constant_extension(F::Extension::ZERO);
+            let limb = (0..32).fold(zero, |acc, j| {
+                let tmp_const = builder.constant_extension(F::Extension::from_canonical_u64(1 << j));
+                let c = builder.mul_extension(local_values[G1_SCALAR_MUL_SCALAR_BITS_OFFSET + i*32 + j], tmp_const);
+                builder.add_extension(acc, c)
+            });
+            let r_limb = builder.constant_extension(F::Extension::from_canonical_u32(r_limbs[i]));
+            let c = builder.sub_extension(limb, r_limb);
+            yield_constr.constraint_first_row(builder, c);
+        }
+
+        let c = builder.sub_extension(local_values[G1_SCALAR_MUL_RES_INFINITY_OFFSET], public_inputs[PIS_IS_MEMBER_OFFSET]);
+        let c = builder.mul_extension(local_values[G1_SCALAR_MUL_RES_ROW_OFFSET], c);
+        yield_constr.constraint(builder, c);
+
+        add_g1_scalar_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0);
+        add_g1_on_curve_constraints_ext_circuit(builder, yield_constr, local_values, next_values, G1_SUBGROUP_ON_CURVE_OFFSET, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        G1_SCALAR_MUL_CONSTRAINT_DEGREE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{field::types::Field, plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::g1_scalar_mul::G1_SCALAR_MUL_EQUAL_OFFSET;
+    use crate::native::{modulus, Fp, G1Affine};
+
+    use super::{G1SubgroupCheckStark, PIS_IS_MEMBER_OFFSET, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = G1SubgroupCheckStark<F, D>;
+
+    fn prove_and_verify_subgroup_check(p: &G1Affine) -> bool {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(4096);
+        let public_inputs = S::public_inputs(p).unwrap();
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let is_member = public_inputs[PIS_IS_MEMBER_OFFSET] == F::ONE;
+        assert_eq!(is_member, p.is_in_subgroup());
+        let trace = stark.generate_trace(p).unwrap();
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        is_member
+    }
+
+    /// Returns a point on the curve with the smallest x > 0, which is outside the prime order subgroup with overwhelming probability.
+    fn curve_point_outside_subgroup() -> G1Affine {
+        let p = modulus();
+        let mut x = BigUint::from(1u32);
+        loop {
+            let rhs = (x.pow(3) + 4u32) % &p;
+            let y = rhs.modpow(&((&p + 1u32) >> 2), &p);
+            if (&y * &y) % &p == rhs {
+                let point = G1Affine {
+                    x: Fp::get_fp_from_biguint(x),
+                    y: Fp::get_fp_from_biguint(y),
+                    infinity: false,
+                };
+                assert!(point.is_on_curve());
+                return point;
+            }
+            x += 1u32;
+        }
+    }
+
+    #[test]
+    fn test_g1_subgroup_check_member() {
+        let g = G1Affine::generator();
+        assert!(prove_and_verify_subgroup_check(&g));
+        assert!(prove_and_verify_subgroup_check(&g.double()));
+    }
+
+    #[test]
+    fn test_g1_subgroup_check_non_member() {
+        let p = curve_point_outside_subgroup();
+        assert!(!p.is_in_subgroup());
+        assert!(!prove_and_verify_subgroup_check(&p));
+    }
+
+    #[test]
+    fn test_g1_subgroup_check_small_order() {
+        // (0, 2) is on the curve and has order 3, so the double-and-add of [r]P adds the point to itself
+        let p = G1Affine {
+            x: Fp::zero(),
+            y: Fp::get_fp_from_biguint(BigUint::from(2u32)),
+            infinity: false,
+        };
+        assert!(p.is_on_curve());
+        assert!(p.mul_scalar(&BigUint::from(3u32)).infinity);
+        let trace = S::new(4096).generate_trace(&p).unwrap();
+        assert!(trace.iter().any(|row| row[G1_SCALAR_MUL_EQUAL_OFFSET] == F::ONE));
+        assert!(!prove_and_verify_subgroup_check(&p));
+    }
+
+    #[test]
+    fn test_g1_subgroup_check_not_on_curve() {
+        let g = G1Affine::generator();
+        let p = G1Affine {
+            x: g.x,
+            y: g.y + g.y,
+            infinity: false,
+        };
+        assert!(!p.is_on_curve());
+        assert!(S::public_inputs(&p).is_err());
+        assert!(S::new(4096).generate_trace(&p).is_err());
+    }
+}
@@ -0,0 +1,753 @@
+use anyhow::{ensure, Result};
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use crate::native::{Fp2, G2Affine};
+
+use crate::fp::*;
+use crate::fp2::*;
+use crate::fp2_inverse::*;
+use crate::utils::*;
+
+// G2 point addition layout offsets
+/*
+    These trace offsets are for adding two affine G2 points (super::native::G2Affine::add), whose coordinates are fp2 elements. It needs 12 rows. Adding a point to itself is supported, in which case the point is doubled.
+    The slope is `lambda = num * den^-1`, with `num = y2 - y1` and `den = x2 - x1` for the generic addition, and `num = 3x1^2` and `den = 2y1` for doubling. If no slope is needed, `num = 0` and `den = 1`. The inverse of `den` is done with the fp2 inverse gadget, which rejects `den == 0`.
+    Then x3 = lambda^2 - x1 - x2 and y3 = lambda*(x1 - x3) - y1. The fp2 additions and subtractions are done as two fp modular operations, one for each component.
+    G2_ADD_SELECTOR_OFFSET -> Selector to ensure that the input is same across all rows. Set 1 in all rows except last one.
+    G2_ADD_CHECK_OFFSET -> Selector set 1 in all rows of the operation. The single row operations are filled in every row, and their inputs are constrained wherever this selector is on.
+    G2_ADD_X1_OFFSET, G2_ADD_Y1_OFFSET, G2_ADD_INFINITY1_OFFSET -> offsets where the first point and its infinity flag are set.
+    G2_ADD_X2_OFFSET, G2_ADD_Y2_OFFSET, G2_ADD_INFINITY2_OFFSET -> offsets where the second point and its infinity flag are set.
+    G2_ADD_BOTH_FINITE_OFFSET -> Flag set 1 if none of the inputs is the point at infinity, i.e. (1 - infinity1) * (1 - infinity2).
+    G2_ADD_OPPOSITE_OFFSET -> Flag set 1 if both inputs are finite and the second point is the negation of the first one, i.e. x1 == x2 and y1 + y2 == 0.
+    G2_ADD_DOUBLING_OFFSET -> Flag set 1 if both inputs are finite and the same point, which is not its own negation.
+        The generic addition is done if both inputs are finite, and they are neither opposite nor the same point.
+    G2_ADD_LAMBDA_OFFSET -> offset where the slope of the line is set.
+    G2_ADD_DX_OFFSET -> x2 - x1
+    G2_ADD_DY_OFFSET -> y2 - y1
+    G2_ADD_Y_SUM_OFFSET -> y1 + y2. Constrained to be zero if the inputs are opposite.
+    G2_ADD_X1_SQ_OFFSET -> x1 * x1
+    G2_ADD_2X1_SQ_OFFSET -> x1^2 + x1^2
+    G2_ADD_3X1_SQ_OFFSET -> 2x1^2 + x1^2
+    G2_ADD_2Y1_OFFSET -> y1 + y1
+    G2_ADD_DEN_INV_OFFSET -> fp2 inverse of `den`. The input of this operation is constrained to be `den`.
+    G2_ADD_LAMBDA_MUL_OFFSET -> num * den^-1. The x input of this operation is constrained to be `num`, and the result is constrained to be lambda.
+    G2_ADD_LAMBDA_SQ_OFFSET -> lambda * lambda
+    G2_ADD_X1_X2_OFFSET -> x1 + x2
+    G2_ADD_X3_OFFSET -> lambda^2 - (x1 + x2)
+    G2_ADD_X1_X3_OFFSET -> x1 - x3
+    G2_ADD_LAMBDA_X1_X3_OFFSET -> lambda * (x1 - x3)
+    G2_ADD_Y3_OFFSET -> lambda*(x1 - x3) - y1
+    G2_ADD_RES_INFINITY_OFFSET -> Flag set 1 if the result is the point at infinity, i.e. both inputs are the point at infinity or the inputs are opposite.
+    G2_ADD_RES_X_OFFSET, G2_ADD_RES_Y_OFFSET -> offsets where the result is set.
+*/
+pub const G2_ADD_SELECTOR_OFFSET: usize = 0;
+pub const G2_ADD_CHECK_OFFSET: usize = G2_ADD_SELECTOR_OFFSET + 1;
+pub const G2_ADD_X1_OFFSET: usize = G2_ADD_CHECK_OFFSET + 1;
+pub const G2_ADD_Y1_OFFSET: usize = G2_ADD_X1_OFFSET + 24;
+pub const G2_ADD_INFINITY1_OFFSET: usize = G2_ADD_Y1_OFFSET + 24;
+pub const G2_ADD_X2_OFFSET: usize = G2_ADD_INFINITY1_OFFSET + 1;
+pub const G2_ADD_Y2_OFFSET: usize = G2_ADD_X2_OFFSET + 24;
+pub const G2_ADD_INFINITY2_OFFSET: usize = G2_ADD_Y2_OFFSET + 24;
+pub const G2_ADD_BOTH_FINITE_OFFSET: usize = G2_ADD_INFINITY2_OFFSET + 1;
+pub const G2_ADD_OPPOSITE_OFFSET: usize = G2_ADD_BOTH_FINITE_OFFSET + 1;
+pub const G2_ADD_DOUBLING_OFFSET: usize = G2_ADD_OPPOSITE_OFFSET + 1;
+pub const G2_ADD_LAMBDA_OFFSET: usize = G2_ADD_DOUBLING_OFFSET + 1;
+pub const G2_ADD_DX_OFFSET: usize = G2_ADD_LAMBDA_OFFSET + 24;
+pub const G2_ADD_DY_OFFSET: usize = G2_ADD_DX_OFFSET + FP_SUB_MOD_TOTAL * 2;
+pub const G2_ADD_Y_SUM_OFFSET: usize = G2_ADD_DY_OFFSET + FP_SUB_MOD_TOTAL * 2;
+pub const G2_ADD_X1_SQ_OFFSET: usize = G2_ADD_Y_SUM_OFFSET + FP_ADD_MOD_TOTAL * 2;
+pub const G2_ADD_2X1_SQ_OFFSET: usize = G2_ADD_X1_SQ_OFFSET + TOTAL_COLUMNS_FP2_MULTIPLICATION;
+pub const G2_ADD_3X1_SQ_OFFSET: usize = G2_ADD_2X1_SQ_OFFSET + FP_ADD_MOD_TOTAL * 2;
+pub const G2_ADD_2Y1_OFFSET: usize = G2_ADD_3X1_SQ_OFFSET + FP_ADD_MOD_TOTAL * 2;
+pub const G2_ADD_DEN_INV_OFFSET: usize = G2_ADD_2Y1_OFFSET + FP_ADD_MOD_TOTAL * 2;
+pub const G2_ADD_LAMBDA_MUL_OFFSET: usize = G2_ADD_DEN_INV_OFFSET + FP2_INV_TOTAL;
+pub const G2_ADD_LAMBDA_SQ_OFFSET: usize = G2_ADD_LAMBDA_MUL_OFFSET + TOTAL_COLUMNS_FP2_MULTIPLICATION;
+pub const G2_ADD_X1_X2_OFFSET: usize = G2_ADD_LAMBDA_SQ_OFFSET + TOTAL_COLUMNS_FP2_MULTIPLICATION;
+pub const G2_ADD_X3_OFFSET: usize = G2_ADD_X1_X2_OFFSET + FP_ADD_MOD_TOTAL * 2;
+pub const G2_ADD_X1_X3_OFFSET: usize = G2_ADD_X3_OFFSET + FP_SUB_MOD_TOTAL * 2;
+pub const G2_ADD_LAMBDA_X1_X3_OFFSET: usize = G2_ADD_X1_X3_OFFSET + FP_SUB_MOD_TOTAL * 2;
+pub const G2_ADD_Y3_OFFSET: usize = G2_ADD_LAMBDA_X1_X3_OFFSET + TOTAL_COLUMNS_FP2_MULTIPLICATION;
+pub const G2_ADD_RES_INFINITY_OFFSET: usize = G2_ADD_Y3_OFFSET + FP_SUB_MOD_TOTAL * 2;
+pub const G2_ADD_RES_X_OFFSET: usize = G2_ADD_RES_INFINITY_OFFSET + 1;
+pub const G2_ADD_RES_Y_OFFSET: usize = G2_ADD_RES_X_OFFSET + 24;
+pub const G2_ADD_TOTAL: usize = G2_ADD_RES_Y_OFFSET + 24;
+
+pub const TOTAL_COLUMNS: usize = G2_ADD_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the first point (x, y) and its infinity flag, the second point (x, y) and its infinity flag, then the result point (x, y) and the infinity flag of the result. Every coordinate is 12*2 limbs.
+*/
+pub const PIS_X1_OFFSET: usize = 0;
+pub const PIS_Y1_OFFSET: usize = PIS_X1_OFFSET + 24;
+pub const PIS_INFINITY1_OFFSET: usize = PIS_Y1_OFFSET + 24;
+pub const PIS_X2_OFFSET: usize = PIS_INFINITY1_OFFSET + 1;
+pub const PIS_Y2_OFFSET: usize = PIS_X2_OFFSET + 24;
+pub const PIS_INFINITY2_OFFSET: usize = PIS_Y2_OFFSET + 24;
+pub const PIS_RES_X_OFFSET: usize = PIS_INFINITY2_OFFSET + 1;
+pub const PIS_RES_Y_OFFSET: usize = PIS_RES_X_OFFSET + 24;
+pub const PIS_RES_INFINITY_OFFSET: usize = PIS_RES_Y_OFFSET + 24;
+pub const PUBLIC_INPUTS: usize = PIS_RES_INFINITY_OFFSET + 1;
+
+/// Pairs of (trace column, public input) which are constrained to be equal wherever G2_ADD_CHECK_OFFSET is set, along with the number of limbs.
+const PIS_LINKS: [(usize, usize, usize); 9] = [
+    (G2_ADD_X1_OFFSET, PIS_X1_OFFSET, 24),
+    (G2_ADD_Y1_OFFSET, PIS_Y1_OFFSET, 24),
+    (G2_ADD_INFINITY1_OFFSET, PIS_INFINITY1_OFFSET, 1),
+    (G2_ADD_X2_OFFSET, PIS_X2_OFFSET, 24),
+    (G2_ADD_Y2_OFFSET, PIS_Y2_OFFSET, 24),
+    (G2_ADD_INFINITY2_OFFSET, PIS_INFINITY2_OFFSET, 1),
+    (G2_ADD_RES_X_OFFSET, PIS_RES_X_OFFSET, 24),
+    (G2_ADD_RES_Y_OFFSET, PIS_RES_Y_OFFSET, 24),
+    (G2_ADD_RES_INFINITY_OFFSET, PIS_RES_INFINITY_OFFSET, 1),
+];
+
+/// Columns of the two components of an fp2 element set in 24 consecutive columns.
+pub(crate) fn fp2_cols(col: usize) -> [usize; 2] {
+    [col, col + 12]
+}
+
+/// Columns of the two components of an fp2 addition or subtraction done as two fp modular operations of `width` columns each. `f` picks the column within one fp operation.
+pub(crate) fn fp2_mod_cols(col: usize, width: usize, f: fn(usize) -> usize) -> [usize; 2] {
+    [f(col), f(col + width)]
+}
+
+/// Columns of the two components of the result of an fp2 multiplication.
+pub(crate) fn fp2_mul_res(col: usize) -> [usize; 2] {
+    [col + Z1_REDUCE_OFFSET + REDUCED_OFFSET, col + Z2_REDUCE_OFFSET + REDUCED_OFFSET]
+}
+
+/// Pairs of (operation input, value) columns of the addition operation, each 12 limbs, which are constrained to be equal wherever G2_ADD_CHECK_OFFSET is set.
+fn g2_add_input_links(start_col: usize) -> Vec<(usize, usize)> {
+    let x1 = fp2_cols(start_col + G2_ADD_X1_OFFSET);
+    let y1 = fp2_cols(start_col + G2_ADD_Y1_OFFSET);
+    let x2 = fp2_cols(start_col + G2_ADD_X2_OFFSET);
+    let y2 = fp2_cols(start_col + G2_ADD_Y2_OFFSET);
+    let lambda = fp2_cols(start_col + G2_ADD_LAMBDA_OFFSET);
+    let add_x = |col: usize| fp2_mod_cols(start_col + col, FP_ADD_MOD_TOTAL, fp_add_mod_x);
+    let add_y = |col: usize| fp2_mod_cols(start_col + col, FP_ADD_MOD_TOTAL, fp_add_mod_y);
+    let add_res = |col: usize| fp2_mod_cols(start_col + col, FP_ADD_MOD_TOTAL, fp_add_mod_res);
+    let sub_x = |col: usize| fp2_mod_cols(start_col + col, FP_SUB_MOD_TOTAL, fp_sub_mod_x);
+    let sub_y = |col: usize| fp2_mod_cols(start_col + col, FP_SUB_MOD_TOTAL, fp_sub_mod_y);
+    let sub_res = |col: usize| fp2_mod_cols(start_col + col, FP_SUB_MOD_TOTAL, fp_sub_mod_res);
+    let mul_x = |col: usize| fp2_cols(start_col + col + FP2_FP2_X_INPUT_OFFSET);
+    let mul_y = |col: usize| fp2_cols(start_col + col + FP2_FP2_Y_INPUT_OFFSET);
+    let mul_res = |col: usize| fp2_mul_res(start_col + col);
+    let x1_sq = mul_res(G2_ADD_X1_SQ_OFFSET);
+    vec![
+        (sub_x(G2_ADD_DX_OFFSET), x2),
+        (sub_y(G2_ADD_DX_OFFSET), x1),
+        (sub_x(G2_ADD_DY_OFFSET), y2),
+        (sub_y(G2_ADD_DY_OFFSET), y1),
+        (add_x(G2_ADD_Y_SUM_OFFSET), y1),
+        (add_y(G2_ADD_Y_SUM_OFFSET), y2),
+        (mul_x(G2_ADD_X1_SQ_OFFSET), x1),
+        (mul_y(G2_ADD_X1_SQ_OFFSET), x1),
+        (add_x(G2_ADD_2X1_SQ_OFFSET), x1_sq),
+        (add_y(G2_ADD_2X1_SQ_OFFSET), x1_sq),
+        (add_x(G2_ADD_3X1_SQ_OFFSET), add_res(G2_ADD_2X1_SQ_OFFSET)),
+        (add_y(G2_ADD_3X1_SQ_OFFSET), x1_sq),
+        (add_x(G2_ADD_2Y1_OFFSET), y1),
+        (add_y(G2_ADD_2Y1_OFFSET), y1),
+        (mul_y(G2_ADD_LAMBDA_MUL_OFFSET), fp2_cols(start_col + G2_ADD_DEN_INV_OFFSET + FP2_INV_INVERSE_OFFSET)),
+        (mul_res(G2_ADD_LAMBDA_MUL_OFFSET), lambda),
+        (mul_x(G2_ADD_LAMBDA_SQ_OFFSET), lambda),
+        (mul_y(G2_ADD_LAMBDA_SQ_OFFSET), lambda),
+        (add_x(G2_ADD_X1_X2_OFFSET), x1),
+        (add_y(G2_ADD_X1_X2_OFFSET), x2),
+        (sub_x(G2_ADD_X3_OFFSET), mul_res(G2_ADD_LAMBDA_SQ_OFFSET)),
+        (sub_y(G2_ADD_X3_OFFSET), add_res(G2_ADD_X1_X2_OFFSET)),
+        (sub_x(G2_ADD_X1_X3_OFFSET), x1),
+        (sub_y(G2_ADD_X1_X3_OFFSET), sub_res(G2_ADD_X3_OFFSET)),
+        (mul_x(G2_ADD_LAMBDA_X1_X3_OFFSET), lambda),
+        (mul_y(G2_ADD_LAMBDA_X1_X3_OFFSET), sub_res(G2_ADD_X1_X3_OFFSET)),
+        (sub_x(G2_ADD_Y3_OFFSET), mul_res(G2_ADD_LAMBDA_X1_X3_OFFSET)),
+        (sub_y(G2_ADD_Y3_OFFSET), y1),
+    ].into_iter().flat_map(|(a, b)| [(a[0], b[0]), (a[1], b[1])]).collect()
+}
+
+/// Fills the stark trace of G2 point addition. Inputs are affine points (x1, y1) and (x2, y2). Needs 12 rows. Returns the sum of the points.
+/// Returns an error if both points are finite with the same x coordinate, but are neither the same point nor opposite, which can only happen for points that are not on the curve.
+pub fn fill_trace_g2_add<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, p: &G2Affine, q: &G2Affine, start_row: usize, end_row: usize, start_col: usize) -> Result<G2Affine> {
+    let both_finite = !p.infinity && !q.infinity;
+    let opposite = both_finite && p.x == q.x && p.y + q.y == Fp2::zero();
+    let doubling = both_finite && p.x == q.x && p.y == q.y && !opposite;
+    let generic = both_finite && p.x != q.x;
+    ensure!(!both_finite || generic || opposite || doubling, "points with the same x coordinate are neither equal nor opposite");
+    let res = *p + *q;
+    let x1_sq = p.x * p.x;
+    let (num, den) = if generic {
+        (q.y - p.y, q.x - p.x)
+    } else if doubling {
+        (x1_sq + x1_sq + x1_sq, p.y + p.y)
+    } else {
+        (Fp2::zero(), Fp2::one())
+    };
+    let den_inv = fill_trace_fp2_inverse(trace, &den, start_row, end_row, start_col + G2_ADD_DEN_INV_OFFSET)?;
+    let lambda = num * den_inv;
+    for row in start_row..end_row + 1 {
+        trace[row][start_col + G2_ADD_SELECTOR_OFFSET] = F::ONE;
+        trace[row][start_col + G2_ADD_CHECK_OFFSET] = F::ONE;
+        assign_u32_in_series(trace, row, start_col + G2_ADD_X1_OFFSET, &p.x.get_u32_slice().concat());
+        assign_u32_in_series(trace, row, start_col + G2_ADD_Y1_OFFSET, &p.y.get_u32_slice().concat());
+        trace[row][start_col + G2_ADD_INFINITY1_OFFSET] = F::from_bool(p.infinity);
+        assign_u32_in_series(trace, row, start_col + G2_ADD_X2_OFFSET, &q.x.get_u32_slice().concat());
+        assign_u32_in_series(trace, row, start_col + G2_ADD_Y2_OFFSET, &q.y.get_u32_slice().concat());
+        trace[row][start_col + G2_ADD_INFINITY2_OFFSET] = F::from_bool(q.infinity);
+        trace[row][start_col + G2_ADD_BOTH_FINITE_OFFSET] = F::from_bool(both_finite);
+        trace[row][start_col + G2_ADD_OPPOSITE_OFFSET] = F::from_bool(opposite);
+        trace[row][start_col + G2_ADD_DOUBLING_OFFSET] = F::from_bool(doubling);
+        assign_u32_in_series(trace, row, start_col + G2_ADD_LAMBDA_OFFSET, &lambda.get_u32_slice().concat());
+        trace[row][start_col + G2_ADD_RES_INFINITY_OFFSET] = F::from_bool(res.infinity);
+        assign_u32_in_series(trace, row, start_col + G2_ADD_RES_X_OFFSET, &res.x.get_u32_slice().concat());
+        assign_u32_in_series(trace, row, start_col + G2_ADD_RES_Y_OFFSET, &res.y.get_u32_slice().concat());
+    }
+    trace[end_row][start_col + G2_ADD_SELECTOR_OFFSET] = F::ZERO;
+
+    let x1_x2 = p.x + q.x;
+    let x3 = lambda * lambda - x1_x2;
+    let x1_x3 = p.x - x3;
+    generate_trace_fp2_mul(trace, p.x.get_u32_slice(), p.x.get_u32_slice(), start_row, end_row, start_col + G2_ADD_X1_SQ_OFFSET);
+    generate_trace_fp2_mul(trace, num.get_u32_slice(), den_inv.get_u32_slice(), start_row, end_row, start_col + G2_ADD_LAMBDA_MUL_OFFSET);
+    generate_trace_fp2_mul(trace, lambda.get_u32_slice(), lambda.get_u32_slice(), start_row, end_row, start_col + G2_ADD_LAMBDA_SQ_OFFSET);
+    generate_trace_fp2_mul(trace, lambda.get_u32_slice(), x1_x3.get_u32_slice(), start_row, end_row, start_col + G2_ADD_LAMBDA_X1_X3_OFFSET);
+    let lambda_sq = lambda * lambda;
+    let two_x1_sq = x1_sq + x1_sq;
+    let lambda_x1_x3 = lambda * x1_x3;
+    for row in start_row..end_row + 1 {
+        for j in 0..2 {
+            let add_col = |col: usize| start_col + col + j * FP_ADD_MOD_TOTAL;
+            let sub_col = |col: usize| start_col + col + j * FP_SUB_MOD_TOTAL;
+            fill_trace_subtraction_fp_mod(trace, &q.x.0[j].0, &p.x.0[j].0, row, sub_col(G2_ADD_DX_OFFSET));
+            fill_trace_subtraction_fp_mod(trace, &q.y.0[j].0, &p.y.0[j].0, row, sub_col(G2_ADD_DY_OFFSET));
+            fill_trace_addition_fp_mod(trace, &p.y.0[j].0, &q.y.0[j].0, row, add_col(G2_ADD_Y_SUM_OFFSET));
+            fill_trace_addition_fp_mod(trace, &x1_sq.0[j].0, &x1_sq.0[j].0, row, add_col(G2_ADD_2X1_SQ_OFFSET));
+            fill_trace_addition_fp_mod(trace, &two_x1_sq.0[j].0, &x1_sq.0[j].0, row, add_col(G2_ADD_3X1_SQ_OFFSET));
+            fill_trace_addition_fp_mod(trace, &p.y.0[j].0, &p.y.0[j].0, row, add_col(G2_ADD_2Y1_OFFSET));
+            fill_trace_addition_fp_mod(trace, &p.x.0[j].0, &q.x.0[j].0, row, add_col(G2_ADD_X1_X2_OFFSET));
+            fill_trace_subtraction_fp_mod(trace, &lambda_sq.0[j].0, &x1_x2.0[j].0, row, sub_col(G2_ADD_X3_OFFSET));
+            fill_trace_subtraction_fp_mod(trace, &p.x.0[j].0, &x3.0[j].0, row, sub_col(G2_ADD_X1_X3_OFFSET));
+            fill_trace_subtraction_fp_mod(trace, &lambda_x1_x3.0[j].0, &p.y.0[j].0, row, sub_col(G2_ADD_Y3_OFFSET));
+        }
+    }
+    Ok(res)
+}
+
+/// Constraints G2 point addition.
+///
+/// Constraints the inputs, flags, slope and result to be same across the rows wherever the selector is on. Constraints the inputs of every operation with the input points, the slope or the result of a previous operation.
+/// The infinity, opposite and doubling flags are constrained to be boolean, and the coordinates of an input are constrained to be zero if it is the point at infinity. The opposite and doubling flags can only be set if both inputs are finite, and not together. If opposite, x1 == x2 and y1 + y2 == 0. If doubling, x1 == x2 and y1 == y2.
+/// Constraints the input of the fp2 inverse to be `den` and the x input of the slope multiplication to be `num`, which are selected with the flags.
+/// Then constraints the result as `P + O = P`, `O + P = P`, `P + (-P) = O`, or (x3, y3) for the generic addition and doubling.
+pub fn add_g2_add_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+    let selector = bit_selector_val * local_values[start_col + G2_ADD_SELECTOR_OFFSET];
+    let check = bit_selector_val * local_values[start_col + G2_ADD_CHECK_OFFSET];
+    let infinity1 = local_values[start_col + G2_ADD_INFINITY1_OFFSET];
+    let infinity2 = local_values[start_col + G2_ADD_INFINITY2_OFFSET];
+    let both_finite = local_values[start_col + G2_ADD_BOTH_FINITE_OFFSET];
+    let opposite = local_values[start_col + G2_ADD_OPPOSITE_OFFSET];
+    let doubling = local_values[start_col + G2_ADD_DOUBLING_OFFSET];
+    let slope = both_finite - opposite;
+    let generic = slope - doubling;
+    let infinity = local_values[start_col + G2_ADD_RES_INFINITY_OFFSET];
+
+    for col in [G2_ADD_X1_OFFSET, G2_ADD_Y1_OFFSET, G2_ADD_X2_OFFSET, G2_ADD_Y2_OFFSET, G2_ADD_LAMBDA_OFFSET, G2_ADD_RES_X_OFFSET, G2_ADD_RES_Y_OFFSET] {
+        for i in 0..24 {
+            yield_constr.constraint_transition(
+                selector *
+                (local_values[start_col + col + i] - next_values[start_col + col + i])
+            );
+        }
+    }
+    for col in [G2_ADD_INFINITY1_OFFSET, G2_ADD_INFINITY2_OFFSET, G2_ADD_BOTH_FINITE_OFFSET, G2_ADD_OPPOSITE_OFFSET, G2_ADD_DOUBLING_OFFSET, G2_ADD_RES_INFINITY_OFFSET] {
+        yield_constr.constraint_transition(
+            selector *
+            (local_values[start_col + col] - next_values[start_col + col])
+        );
+    }
+
+    for (input_col, value_col) in g2_add_input_links(start_col) {
+        for i in 0..12 {
+            yield_constr.constraint(
+                check *
+                (local_values[input_col + i] - local_values[value_col + i])
+            );
+        }
+    }
+
+    yield_constr.constraint(check * infinity1 * (P::ONES - infinity1));
+    yield_constr.constraint(check * infinity2 * (P::ONES - infinity2));
+    yield_constr.constraint(check * opposite * (P::ONES - opposite));
+    yield_constr.constraint(check * doubling * (P::ONES - doubling));
+    yield_constr.constraint(check * (both_finite - (P::ONES - infinity1) * (P::ONES - infinity2)));
+    yield_constr.constraint(check * (P::ONES - both_finite) * opposite);
+    yield_constr.constraint(check * (P::ONES - both_finite) * doubling);
+    yield_constr.constraint(check * opposite * doubling);
+    yield_constr.constraint(check * (infinity - infinity1 * infinity2 - opposite));
+
+    let dx = fp2_mod_cols(start_col + G2_ADD_DX_OFFSET, FP_SUB_MOD_TOTAL, fp_sub_mod_res);
+    let dy = fp2_mod_cols(start_col + G2_ADD_DY_OFFSET, FP_SUB_MOD_TOTAL, fp_sub_mod_res);
+    let y_sum = fp2_mod_cols(start_col + G2_ADD_Y_SUM_OFFSET, FP_ADD_MOD_TOTAL, fp_add_mod_res);
+    let three_x1_sq = fp2_mod_cols(start_col + G2_ADD_3X1_SQ_OFFSET, FP_ADD_MOD_TOTAL, fp_add_mod_res);
+    let two_y1 = fp2_mod_cols(start_col + G2_ADD_2Y1_OFFSET, FP_ADD_MOD_TOTAL, fp_add_mod_res);
+    let x3 = fp2_mod_cols(start_col + G2_ADD_X3_OFFSET, FP_SUB_MOD_TOTAL, fp_sub_mod_res);
+    let y3 = fp2_mod_cols(start_col + G2_ADD_Y3_OFFSET, FP_SUB_MOD_TOTAL, fp_sub_mod_res);
+    let den = fp2_cols(start_col + G2_ADD_DEN_INV_OFFSET + FP2_INV_INPUT_OFFSET);
+    let num = fp2_cols(start_col + G2_ADD_LAMBDA_MUL_OFFSET + FP2_FP2_X_INPUT_OFFSET);
+    for j in 0..2 {
+        for i in 0..12 {
+            let x1 = local_values[start_col + G2_ADD_X1_OFFSET + j * 12 + i];
+            let y1 = local_values[start_col + G2_ADD_Y1_OFFSET + j * 12 + i];
+            let x2 = local_values[start_col + G2_ADD_X2_OFFSET + j * 12 + i];
+            let y2 = local_values[start_col + G2_ADD_Y2_OFFSET + j * 12 + i];
+            yield_constr.constraint(check * infinity1 * x1);
+            yield_constr.constraint(check * infinity1 * y1);
+            yield_constr.constraint(check * infinity2 * x2);
+            yield_constr.constraint(check * infinity2 * y2);
+            yield_constr.constraint(check * opposite * (x1 - x2));
+            yield_constr.constraint(check * opposite * local_values[y_sum[j] + i]);
+            yield_constr.constraint(check * doubling * (x1 - x2));
+            yield_constr.constraint(check * doubling * (y1 - y2));
+            let one = if j == 0 && i == 0 { P::ONES } else { P::ZEROS };
+            yield_constr.constraint(
+                check *
+                (local_values[den[j] + i] - generic * local_values[dx[j] + i] - doubling * local_values[two_y1[j] + i] - (P::ONES - slope) * one)
+            );
+            yield_constr.constraint(
+                check *
+                (local_values[num[j] + i] - generic * local_values[dy[j] + i] - doubling * local_values[three_x1_sq[j] + i])
+            );
+            yield_constr.constraint(
+                check *
+                (local_values[start_col + G2_ADD_RES_X_OFFSET + j * 12 + i] - infinity1 * x2 - infinity2 * x1 - slope * local_values[x3[j] + i])
+            );
+            yield_constr.constraint(
+                check *
+                (local_values[start_col + G2_ADD_RES_Y_OFFSET + j * 12 + i] - infinity1 * y2 - infinity2 * y1 - slope * local_values[y3[j] + i])
+            );
+        }
+    }
+
+    for col in [G2_ADD_X1_SQ_OFFSET, G2_ADD_LAMBDA_MUL_OFFSET, G2_ADD_LAMBDA_SQ_OFFSET, G2_ADD_LAMBDA_X1_X3_OFFSET] {
+        add_fp2_mul_constraints(local_values, next_values, yield_constr, start_col + col, bit_selector);
+    }
+    for col in [G2_ADD_Y_SUM_OFFSET, G2_ADD_2X1_SQ_OFFSET, G2_ADD_3X1_SQ_OFFSET, G2_ADD_2Y1_OFFSET, G2_ADD_X1_X2_OFFSET] {
+        for j in 0..2 {
+            add_addition_fp_mod_constraints(local_values, yield_constr, start_col + col + j * FP_ADD_MOD_TOTAL, bit_selector);
+        }
+    }
+    for col in [G2_ADD_DX_OFFSET, G2_ADD_DY_OFFSET, G2_ADD_X3_OFFSET, G2_ADD_X1_X3_OFFSET, G2_ADD_Y3_OFFSET] {
+        for j in 0..2 {
+            add_subtraction_fp_mod_constraints(local_values, yield_constr, start_col + col + j * FP_SUB_MOD_TOTAL, bit_selector);
+        }
+    }
+    add_fp2_inverse_constraints(local_values, next_values, yield_constr, start_col + G2_ADD_DEN_INV_OFFSET, bit_selector);
+}
+
+pub fn add_g2_add_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let bit_selector_val = bit_selector.unwrap_or(builder.constant_extension(F::Extension::ONE));
+    let one = builder.constant_extension(F::Extension::ONE);
+    let selector = builder.mul_extension(bit_selector_val, local_values[start_col + G2_ADD_SELECTOR_OFFSET]);
+    let check = builder.mul_extension(bit_selector_val, local_values[start_col + G2_ADD_CHECK_OFFSET]);
+    let infinity1 = local_values[start_col + G2_ADD_INFINITY1_OFFSET];
+    let infinity2 = local_values[start_col + G2_ADD_INFINITY2_OFFSET];
+    let both_finite = local_values[start_col + G2_ADD_BOTH_FINITE_OFFSET];
+    let opposite = local_values[start_col + G2_ADD_OPPOSITE_OFFSET];
+    let doubling = local_values[start_col + G2_ADD_DOUBLING_OFFSET];
+    let slope = builder.sub_extension(both_finite, opposite);
+    let generic = builder.sub_extension(slope, doubling);
+    let infinity = local_values[start_col + G2_ADD_RES_INFINITY_OFFSET];
+    let not_infinity1 = builder.sub_extension(one, infinity1);
+    let not_infinity2 = builder.sub_extension(one, infinity2);
+    let not_opposite = builder.sub_extension(one, opposite);
+    let not_doubling = builder.sub_extension(one, doubling);
+    let not_both_finite = builder.sub_extension(one, both_finite);
+    let not_slope = builder.sub_extension(one, slope);
+
+    for col in [G2_ADD_X1_OFFSET, G2_ADD_Y1_OFFSET, G2_ADD_X2_OFFSET, G2_ADD_Y2_OFFSET, G2_ADD_LAMBDA_OFFSET, G2_ADD_RES_X_OFFSET, G2_ADD_RES_Y_OFFSET] {
+        for i in 0..24 {
+            let c = builder.sub_extension(local_values[start_col + col + i], next_values[start_col + col + i]);
+            let c = builder.mul_extension(selector, c);
+            yield_constr.constraint_transition(builder, c);
+        }
+    }
+    for col in [G2_ADD_INFINITY1_OFFSET, G2_ADD_INFINITY2_OFFSET, G2_ADD_BOTH_FINITE_OFFSET, G2_ADD_OPPOSITE_OFFSET, G2_ADD_DOUBLING_OFFSET, G2_ADD_RES_INFINITY_OFFSET] {
+        let c = builder.sub_extension(local_values[start_col + col], next_values[start_col + col]);
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint_transition(builder, c);
+    }
+
+    for (input_col, value_col) in g2_add_input_links(start_col) {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[input_col + i], local_values[value_col + i]);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+
+    for (a, b) in [
+        (infinity1, not_infinity1),
+        (infinity2, not_infinity2),
+        (opposite, not_opposite),
+        (doubling, not_doubling),
+        (not_both_finite, opposite),
+        (not_both_finite, doubling),
+        (opposite, doubling),
+    ] {
+        let c = builder.mul_extension(a, b);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+    }
+    let c = builder.mul_extension(not_infinity1, not_infinity2);
+    let c = builder.sub_extension(both_finite, c);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(infinity1, infinity2);
+    let c = builder.sub_extension(infinity, c);
+    let c = builder.sub_extension(c, opposite);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+
+    let dx = fp2_mod_cols(start_col + G2_ADD_DX_OFFSET, FP_SUB_MOD_TOTAL, fp_sub_mod_res);
+    let dy = fp2_mod_cols(start_col + G2_ADD_DY_OFFSET, FP_SUB_MOD_TOTAL, fp_sub_mod_res);
+    let y_sum = fp2_mod_cols(start_col + G2_ADD_Y_SUM_OFFSET, FP_ADD_MOD_TOTAL, fp_add_mod_res);
+    let three_x1_sq = fp2_mod_cols(start_col + G2_ADD_3X1_SQ_OFFSET, FP_ADD_MOD_TOTAL, fp_add_mod_res);
+    let two_y1 = fp2_mod_cols(start_col + G2_ADD_2Y1_OFFSET, FP_ADD_MOD_TOTAL, fp_add_mod_res);
+    let x3 = fp2_mod_cols(start_col + G2_ADD_X3_OFFSET, FP_SUB_MOD_TOTAL, fp_sub_mod_res);
+    let y3 = fp2_mod_cols(start_col + G2_ADD_Y3_OFFSET, FP_SUB_MOD_TOTAL, fp_sub_mod_res);
+    let den = fp2_cols(start_col + G2_ADD_DEN_INV_OFFSET + FP2_INV_INPUT_OFFSET);
+    let num = fp2_cols(start_col + G2_ADD_LAMBDA_MUL_OFFSET + FP2_FP2_X_INPUT_OFFSET);
+    let check_infinity1 = builder.mul_extension(check, infinity1);
+    let check_infinity2 = builder.mul_extension(check, infinity2);
+    let check_opposite = builder.mul_extension(check, opposite);
+    let check_doubling = builder.mul_extension(check, doubling);
+    for j in 0..2 {
+        for i in 0..12 {
+            let x1 = local_values[start_col + G2_ADD_X1_OFFSET + j * 12 + i];
+            let y1 = local_values[start_col + G2_ADD_Y1_OFFSET + j * 12 + i];
+            let x2 = local_values[start_col + G2_ADD_X2_OFFSET + j * 12 + i];
+            let y2 = local_values[start_col + G2_ADD_Y2_OFFSET + j * 12 + i];
+            for (flag, value) in [(check_infinity1, x1), (check_infinity1, y1), (check_infinity2, x2), (check_infinity2, y2), (check_opposite, local_values[y_sum[j] + i])] {
+                let c = builder.mul_extension(flag, value);
+                yield_constr.constraint(builder, c);
+            }
+            for (flag, a, b) in [(check_opposite, x1, x2), (check_doubling, x1, x2), (check_doubling, y1, y2)] {
+                let c = builder.sub_extension(a, b);
+                let c = builder.mul_extension(flag, c);
+                yield_constr.constraint(builder, c);
+            }
+
+            let t1 = builder.mul_extension(generic, local_values[dx[j] + i]);
+            let t2 = builder.mul_extension(doubling, local_values[two_y1[j] + i]);
+            let c = builder.sub_extension(local_values[den[j] + i], t1);
+            let c = builder.sub_extension(c, t2);
+            let c = if j == 0 && i == 0 {
+                builder.sub_extension(c, not_slope)
+            } else {
+                c
+            };
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+
+            let t1 = builder.mul_extension(generic, local_values[dy[j] + i]);
+            let t2 = builder.mul_extension(doubling, local_values[three_x1_sq[j] + i]);
+            let c = builder.sub_extension(local_values[num[j] + i], t1);
+            let c = builder.sub_extension(c, t2);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+
+            let t1 = builder.mul_extension(infinity1, x2);
+            let t2 = builder.mul_extension(infinity2, x1);
+            let t3 = builder.mul_extension(slope, local_values[x3[j] + i]);
+            let c = builder.sub_extension(local_values[start_col + G2_ADD_RES_X_OFFSET + j * 12 + i], t1);
+            let c = builder.sub_extension(c, t2);
+            let c = builder.sub_extension(c, t3);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+
+            let t1 = builder.mul_extension(infinity1, y2);
+            let t2 = builder.mul_extension(infinity2, y1);
+            let t3 = builder.mul_extension(slope, local_values[y3[j] + i]);
+            let c = builder.sub_extension(local_values[start_col + G2_ADD_RES_Y_OFFSET + j * 12 + i], t1);
+            let c = builder.sub_extension(c, t2);
+            let c = builder.sub_extension(c, t3);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+
+    for col in [G2_ADD_X1_SQ_OFFSET, G2_ADD_LAMBDA_MUL_OFFSET, G2_ADD_LAMBDA_SQ_OFFSET, G2_ADD_LAMBDA_X1_X3_OFFSET] {
+        add_fp2_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + col, bit_selector);
+    }
+    for col in [G2_ADD_Y_SUM_OFFSET, G2_ADD_2X1_SQ_OFFSET, G2_ADD_3X1_SQ_OFFSET, G2_ADD_2Y1_OFFSET, G2_ADD_X1_X2_OFFSET] {
+        for j in 0..2 {
+            add_addition_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + col + j * FP_ADD_MOD_TOTAL, bit_selector);
+        }
+    }
+    for col in [G2_ADD_DX_OFFSET, G2_ADD_DY_OFFSET, G2_ADD_X3_OFFSET, G2_ADD_X1_X3_OFFSET, G2_ADD_Y3_OFFSET] {
+        for j in 0..2 {
+            add_subtraction_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + col + j * FP_SUB_MOD_TOTAL, bit_selector);
+        }
+    }
+    add_fp2_inverse_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + G2_ADD_DEN_INV_OFFSET, bit_selector);
+}
+
+#[derive(Clone, Copy)]
+pub struct G2AddStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> G2AddStark<F, D> {
+    /// Rows used by one G2 addition, 12. The stark needs a power of two number of rows of at least this many.
+    pub fn required_rows() -> usize {
+        12
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(p: &G2Affine, q: &G2Affine, res: &G2Affine) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for (offset, x) in [
+            (PIS_X1_OFFSET, p.x),
+            (PIS_Y1_OFFSET, p.y),
+            (PIS_X2_OFFSET, q.x),
+            (PIS_Y2_OFFSET, q.y),
+            (PIS_RES_X_OFFSET, res.x),
+            (PIS_RES_Y_OFFSET, res.y),
+        ] {
+            for (i, limb) in x.get_u32_slice().concat().iter().enumerate() {
+                public_inputs[offset + i] = F::from_canonical_u32(*limb);
+            }
+        }
+        public_inputs[PIS_INFINITY1_OFFSET] = F::from_bool(p.infinity);
+        public_inputs[PIS_INFINITY2_OFFSET] = F::from_bool(q.infinity);
+        public_inputs[PIS_RES_INFINITY_OFFSET] = F::from_bool(res.infinity);
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, p: &G2Affine, q: &G2Affine) -> Result<Vec<[F; TOTAL_COLUMNS]>> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_g2_add(&mut trace, p, q, 0, 11, 0)?;
+        Ok(trace)
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for G2AddStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[G2_ADD_CHECK_OFFSET] - P::ONES);
+        for (col, pis, limbs) in PIS_LINKS {
+            for i in 0..limbs {
+                yield_constr.constraint(
+                    local_values[G2_ADD_CHECK_OFFSET] *
+                    (local_values[col + i] - public_inputs[pis + i])
+                );
+            }
+        }
+        add_g2_add_constraints(local_values, next_values, yield_constr, 0, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[G2_ADD_CHECK_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for (col, pis, limbs) in PIS_LINKS {
+            for i in 0..limbs {
+                let c = builder.sub_extension(local_values[col + i], public_inputs[pis + i]);
+                let c = builder.mul_extension(local_values[G2_ADD_CHECK_OFFSET], c);
+                yield_constr.constraint(builder, c);
+            }
+        }
+        add_g2_add_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Fq, Fq2, G2Affine as ArkG2Affine};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::PrimeField;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{Fp, Fp2, G2Affine};
+
+    use super::{G2AddStark, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = G2AddStark<F, D>;
+
+    fn fp_to_ark(x: &Fp) -> Fq {
+        Fq::from_be_bytes_mod_order(&x.to_bytes_be())
+    }
+
+    fn g2_to_ark(p: &G2Affine) -> ArkG2Affine {
+        if p.infinity {
+            return ArkG2Affine::identity();
+        }
+        let fq2 = |x: &Fp2| Fq2::new(fp_to_ark(&x.0[0]), fp_to_ark(&x.0[1]));
+        ArkG2Affine::new(fq2(&p.x), fq2(&p.y))
+    }
+
+    fn prove_and_verify_add(p: &G2Affine, q: &G2Affine) -> G2Affine {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(16);
+        let res = *p + *q;
+        assert_eq!(g2_to_ark(&res), (g2_to_ark(p).into_group() + g2_to_ark(q)).into_affine());
+        let public_inputs = S::public_inputs(p, q, &res);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(p, q).unwrap();
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        res
+    }
+
+    #[test]
+    fn test_g2_generator_matches_reference() {
+        let g = G2Affine::generator();
+        assert!(g.is_on_curve());
+        assert_eq!(g2_to_ark(&g), ArkG2Affine::generator());
+    }
+
+    #[test]
+    fn test_g2_add_generic() {
+        let g = G2Affine::generator();
+        let res = prove_and_verify_add(&g, &g.double());
+        assert!(res.is_on_curve());
+        assert_eq!(res, g.double() + g);
+    }
+
+    #[test]
+    fn test_g2_add_doubling() {
+        let g = G2Affine::generator();
+        let res = prove_and_verify_add(&g, &g);
+        assert!(res.is_on_curve());
+        assert_eq!(res, g.double());
+    }
+
+    #[test]
+    fn test_g2_add_identity_right() {
+        let g = G2Affine::generator();
+        assert_eq!(prove_and_verify_add(&g, &G2Affine::identity()), g);
+    }
+
+    #[test]
+    fn test_g2_add_identity_left() {
+        let g = G2Affine::generator();
+        assert_eq!(prove_and_verify_add(&G2Affine::identity(), &g), g);
+    }
+
+    #[test]
+    fn test_g2_add_opposite() {
+        let g = G2Affine::generator();
+        assert_eq!(prove_and_verify_add(&g, &-g), G2Affine::identity());
+    }
+
+    #[test]
+    fn test_g2_add_identity_both() {
+        assert_eq!(prove_and_verify_add(&G2Affine::identity(), &G2Affine::identity()), G2Affine::identity());
+    }
+
+    #[test]
+    fn test_g2_add_same_x_not_on_curve() {
+        let g = G2Affine::generator();
+        let q = G2Affine {
+            x: g.x,
+            y: g.y + Fp2::one(),
+            infinity: false,
+        };
+        let stark = S::new(16);
+        assert!(stark.generate_trace(&g, &q).is_err());
+    }
+}
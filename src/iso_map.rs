@@ -0,0 +1,629 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use crate::native::{Fp, G1Affine, ISO_G1_X_DEN, ISO_G1_X_NUM, ISO_G1_Y_DEN, ISO_G1_Y_NUM};
+
+use crate::fp::*;
+use crate::fp_inverse::*;
+use crate::utils::*;
+
+/// Number of Horner steps to evaluate the isogeny polynomials, which are padded to degree 15.
+pub const ISO_MAP_STEPS: usize = 15;
+/// Number of rows needed by the isogeny map gadget.
+pub const ISO_MAP_ROWS: usize = 12 * ISO_MAP_STEPS;
+
+// Horner step layout offsets
+/*
+    These trace offsets are for one step `acc * x + c` of the Horner evaluation of a polynomial. A step takes 12 rows.
+    ISO_MAP_HORNER_ACC_OFFSET -> offset where the accumulator is set.
+    ISO_MAP_HORNER_MUL_OFFSET -> acc * x
+    ISO_MAP_HORNER_ADD_OFFSET -> acc * x + c, where c is the coefficient of the current step. The result is the accumulator of the next step.
+*/
+pub const ISO_MAP_HORNER_ACC_OFFSET: usize = 0;
+pub const ISO_MAP_HORNER_MUL_OFFSET: usize = ISO_MAP_HORNER_ACC_OFFSET + 12;
+pub const ISO_MAP_HORNER_ADD_OFFSET: usize = ISO_MAP_HORNER_MUL_OFFSET + FP_MUL_MOD_TOTAL;
+pub const ISO_MAP_HORNER_TOTAL: usize = ISO_MAP_HORNER_ADD_OFFSET + FP_ADD_MOD_TOTAL;
+
+// 11-isogeny map layout offsets
+/*
+    These trace offsets are for the 11-isogeny map (super::native::iso_map_g1) of a finite point on the isogenous curve E' to the G1 curve. It needs ISO_MAP_ROWS rows.
+    The four polynomials are evaluated in parallel with Horner's method, one step every 12 rows, starting with the coefficient of degree 15 as accumulator. The divisions are done in the rows of the last step.
+    ISO_MAP_SELECTOR_OFFSET -> Selector to ensure that the input is same across all rows. Set 1 in all rows except last one. Where it is set and ISO_MAP_STEP_SELECTOR_OFFSET is not, the next row starts the next step.
+    ISO_MAP_CHECK_OFFSET -> Selector set 1 in all rows of the operation.
+    ISO_MAP_STEP_SELECTOR_OFFSET -> Selector to ensure that the step is same across the rows of a step. Set 1 in all rows of a step except the last one.
+    ISO_MAP_STEP_OFFSET -> offset which stores which Horner step the trace is currently on. Total ISO_MAP_STEPS selectors, one for each step. The first row of the operation must be constrained to be on the first step.
+    ISO_MAP_X_OFFSET, ISO_MAP_Y_OFFSET -> offsets where the input point is set.
+    ISO_MAP_X_NUM_OFFSET, ISO_MAP_X_DEN_OFFSET, ISO_MAP_Y_NUM_OFFSET, ISO_MAP_Y_DEN_OFFSET -> offsets of the Horner steps of the x numerator, x denominator, y numerator and y denominator.
+    ISO_MAP_DEN_PROD_OFFSET -> x_den * y_den
+    ISO_MAP_INFINITY_OFFSET -> boolean set 1 in the rows of the last step if x_den * y_den is zero, i.e. the result is the point at infinity.
+    ISO_MAP_DEN_INV_OFFSET -> offset where the fp inverse of x_den * y_den is done. If the result is the point at infinity, the inverse of 1 is filled instead and left unconstrained with the product.
+    ISO_MAP_X_NUM_Y_DEN_OFFSET -> x_num * y_den
+    ISO_MAP_RES_X_MUL_OFFSET -> x_num * y_den / (x_den * y_den) = x_num / x_den
+    ISO_MAP_Y_Y_NUM_OFFSET -> y * y_num
+    ISO_MAP_Y_Y_NUM_X_DEN_OFFSET -> y * y_num * x_den
+    ISO_MAP_RES_Y_MUL_OFFSET -> y * y_num * x_den / (x_den * y_den) = y * y_num / y_den
+    ISO_MAP_RES_X_OFFSET, ISO_MAP_RES_Y_OFFSET -> offsets where the resulting point is set, in the rows of the last step. Both are zero for the point at infinity.
+*/
+pub const ISO_MAP_SELECTOR_OFFSET: usize = 0;
+pub const ISO_MAP_CHECK_OFFSET: usize = ISO_MAP_SELECTOR_OFFSET + 1;
+pub const ISO_MAP_STEP_SELECTOR_OFFSET: usize = ISO_MAP_CHECK_OFFSET + 1;
+pub const ISO_MAP_STEP_OFFSET: usize = ISO_MAP_STEP_SELECTOR_OFFSET + 1;
+pub const ISO_MAP_X_OFFSET: usize = ISO_MAP_STEP_OFFSET + ISO_MAP_STEPS;
+pub const ISO_MAP_Y_OFFSET: usize = ISO_MAP_X_OFFSET + 12;
+pub const ISO_MAP_X_NUM_OFFSET: usize = ISO_MAP_Y_OFFSET + 12;
+pub const ISO_MAP_X_DEN_OFFSET: usize = ISO_MAP_X_NUM_OFFSET + ISO_MAP_HORNER_TOTAL;
+pub const ISO_MAP_Y_NUM_OFFSET: usize = ISO_MAP_X_DEN_OFFSET + ISO_MAP_HORNER_TOTAL;
+pub const ISO_MAP_Y_DEN_OFFSET: usize = ISO_MAP_Y_NUM_OFFSET + ISO_MAP_HORNER_TOTAL;
+pub const ISO_MAP_DEN_PROD_OFFSET: usize = ISO_MAP_Y_DEN_OFFSET + ISO_MAP_HORNER_TOTAL;
+pub const ISO_MAP_INFINITY_OFFSET: usize = ISO_MAP_DEN_PROD_OFFSET + FP_MUL_MOD_TOTAL;
+pub const ISO_MAP_DEN_INV_OFFSET: usize = ISO_MAP_INFINITY_OFFSET + 1;
+pub const ISO_MAP_X_NUM_Y_DEN_OFFSET: usize = ISO_MAP_DEN_INV_OFFSET + FP_INV_TOTAL;
+pub const ISO_MAP_RES_X_MUL_OFFSET: usize = ISO_MAP_X_NUM_Y_DEN_OFFSET + FP_MUL_MOD_TOTAL;
+pub const ISO_MAP_Y_Y_NUM_OFFSET: usize = ISO_MAP_RES_X_MUL_OFFSET + FP_MUL_MOD_TOTAL;
+pub const ISO_MAP_Y_Y_NUM_X_DEN_OFFSET: usize = ISO_MAP_Y_Y_NUM_OFFSET + FP_MUL_MOD_TOTAL;
+pub const ISO_MAP_RES_Y_MUL_OFFSET: usize = ISO_MAP_Y_Y_NUM_X_DEN_OFFSET + FP_MUL_MOD_TOTAL;
+pub const ISO_MAP_RES_X_OFFSET: usize = ISO_MAP_RES_Y_MUL_OFFSET + FP_MUL_MOD_TOTAL;
+pub const ISO_MAP_RES_Y_OFFSET: usize = ISO_MAP_RES_X_OFFSET + 12;
+pub const ISO_MAP_TOTAL: usize = ISO_MAP_RES_Y_OFFSET + 12;
+
+pub const TOTAL_COLUMNS: usize = ISO_MAP_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the input point (x, y) on E', then the resulting point (x, y) on the G1 curve and its infinity flag.
+*/
+pub const PIS_X_OFFSET: usize = 0;
+pub const PIS_Y_OFFSET: usize = PIS_X_OFFSET + 12;
+pub const PIS_RES_X_OFFSET: usize = PIS_Y_OFFSET + 12;
+pub const PIS_RES_Y_OFFSET: usize = PIS_RES_X_OFFSET + 12;
+pub const PIS_RES_INFINITY_OFFSET: usize = PIS_RES_Y_OFFSET + 12;
+pub const PUBLIC_INPUTS: usize = PIS_RES_INFINITY_OFFSET + 1;
+
+/// The Horner evaluations of the isogeny map along with the coefficients of their polynomial.
+const ISO_MAP_POLYS: [(usize, &[Fp]); 4] = [
+    (ISO_MAP_X_NUM_OFFSET, &ISO_G1_X_NUM),
+    (ISO_MAP_X_DEN_OFFSET, &ISO_G1_X_DEN),
+    (ISO_MAP_Y_NUM_OFFSET, &ISO_G1_Y_NUM),
+    (ISO_MAP_Y_DEN_OFFSET, &ISO_G1_Y_DEN),
+];
+
+/// Returns the coefficient of degree `k` of the polynomial, zero above its degree.
+fn iso_map_coeff(coeffs: &[Fp], k: usize) -> Fp {
+    coeffs.get(k).copied().unwrap_or(Fp::zero())
+}
+
+/// Column of the value of the polynomial evaluated at `start_col`, in the rows of the last step.
+fn iso_map_poly_res(start_col: usize) -> usize {
+    fp_add_mod_res(start_col + ISO_MAP_HORNER_ADD_OFFSET)
+}
+
+/// Pairs of (input column, value column) of the divisions which are constrained to be equal in the rows of the last step.
+fn iso_map_division_links(start_col: usize) -> Vec<(usize, usize)> {
+    let x_num = iso_map_poly_res(start_col + ISO_MAP_X_NUM_OFFSET);
+    let x_den = iso_map_poly_res(start_col + ISO_MAP_X_DEN_OFFSET);
+    let y_num = iso_map_poly_res(start_col + ISO_MAP_Y_NUM_OFFSET);
+    let y_den = iso_map_poly_res(start_col + ISO_MAP_Y_DEN_OFFSET);
+    let den_inv = start_col + ISO_MAP_DEN_INV_OFFSET + FP_INV_INVERSE_OFFSET;
+    vec![
+        (fp_mul_mod_x(start_col + ISO_MAP_DEN_PROD_OFFSET), x_den),
+        (fp_mul_mod_y(start_col + ISO_MAP_DEN_PROD_OFFSET), y_den),
+        (fp_mul_mod_x(start_col + ISO_MAP_X_NUM_Y_DEN_OFFSET), x_num),
+        (fp_mul_mod_y(start_col + ISO_MAP_X_NUM_Y_DEN_OFFSET), y_den),
+        (fp_mul_mod_x(start_col + ISO_MAP_RES_X_MUL_OFFSET), fp_mul_mod_res(start_col + ISO_MAP_X_NUM_Y_DEN_OFFSET)),
+        (fp_mul_mod_y(start_col + ISO_MAP_RES_X_MUL_OFFSET), den_inv),
+        (fp_mul_mod_x(start_col + ISO_MAP_Y_Y_NUM_OFFSET), start_col + ISO_MAP_Y_OFFSET),
+        (fp_mul_mod_y(start_col + ISO_MAP_Y_Y_NUM_OFFSET), y_num),
+        (fp_mul_mod_x(start_col + ISO_MAP_Y_Y_NUM_X_DEN_OFFSET), fp_mul_mod_res(start_col + ISO_MAP_Y_Y_NUM_OFFSET)),
+        (fp_mul_mod_y(start_col + ISO_MAP_Y_Y_NUM_X_DEN_OFFSET), x_den),
+        (fp_mul_mod_x(start_col + ISO_MAP_RES_Y_MUL_OFFSET), fp_mul_mod_res(start_col + ISO_MAP_Y_Y_NUM_X_DEN_OFFSET)),
+        (fp_mul_mod_y(start_col + ISO_MAP_RES_Y_MUL_OFFSET), den_inv),
+    ]
+}
+
+/// Fills the stark trace of the 11-isogeny map. Input is a finite point on E'. Needs ISO_MAP_ROWS rows. Fills the Horner steps of the four polynomials with the step selectors, then the divisions in the rows of the last step. Returns the point on the G1 curve.
+pub fn fill_trace_iso_map<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, p: &G1Affine, start_row: usize, end_row: usize, start_col: usize) -> G1Affine {
+    assert!(!p.infinity);
+    assert_eq!(end_row + 1 - start_row, ISO_MAP_ROWS);
+    for row in start_row..end_row + 1 {
+        trace[row][start_col + ISO_MAP_SELECTOR_OFFSET] = F::ONE;
+        trace[row][start_col + ISO_MAP_CHECK_OFFSET] = F::ONE;
+        assign_u32_in_series(trace, row, start_col + ISO_MAP_X_OFFSET, &p.x.0);
+        assign_u32_in_series(trace, row, start_col + ISO_MAP_Y_OFFSET, &p.y.0);
+    }
+    trace[end_row][start_col + ISO_MAP_SELECTOR_OFFSET] = F::ZERO;
+
+    let mut values = [Fp::zero(); 4];
+    for (j, (col, coeffs)) in ISO_MAP_POLYS.iter().enumerate() {
+        let mut acc = iso_map_coeff(coeffs, ISO_MAP_STEPS);
+        for step in 0..ISO_MAP_STEPS {
+            let s_row = start_row + step*12;
+            let e_row = s_row + 11;
+            let acc_x = fill_trace_fp_mul_mod(trace, &acc.0, &p.x.0, s_row, e_row, start_col + col + ISO_MAP_HORNER_MUL_OFFSET);
+            let coeff = iso_map_coeff(coeffs, ISO_MAP_STEPS - 1 - step);
+            for row in s_row..e_row + 1 {
+                assign_u32_in_series(trace, row, start_col + col + ISO_MAP_HORNER_ACC_OFFSET, &acc.0);
+                fill_trace_addition_fp_mod(trace, &acc_x, &coeff.0, row, start_col + col + ISO_MAP_HORNER_ADD_OFFSET);
+                trace[row][start_col + ISO_MAP_STEP_OFFSET + step] = F::ONE;
+                if row != e_row {
+                    trace[row][start_col + ISO_MAP_STEP_SELECTOR_OFFSET] = F::ONE;
+                }
+            }
+            acc = Fp(acc_x) + coeff;
+        }
+        values[j] = acc;
+    }
+    let [x_num, x_den, y_num, y_den] = values;
+
+    let s_row = end_row - 11;
+    let den_prod = Fp(fill_trace_fp_mul_mod(trace, &x_den.0, &y_den.0, s_row, end_row, start_col + ISO_MAP_DEN_PROD_OFFSET));
+    let infinity = den_prod == Fp::zero();
+    let den_inv = fill_trace_fp_inverse(trace, if infinity { &Fp::one() } else { &den_prod }, s_row, end_row, start_col + ISO_MAP_DEN_INV_OFFSET).unwrap();
+    let x_num_y_den = fill_trace_fp_mul_mod(trace, &x_num.0, &y_den.0, s_row, end_row, start_col + ISO_MAP_X_NUM_Y_DEN_OFFSET);
+    let res_x = fill_trace_fp_mul_mod(trace, &x_num_y_den, &den_inv.0, s_row, end_row, start_col + ISO_MAP_RES_X_MUL_OFFSET);
+    let y_y_num = fill_trace_fp_mul_mod(trace, &p.y.0, &y_num.0, s_row, end_row, start_col + ISO_MAP_Y_Y_NUM_OFFSET);
+    let y_y_num_x_den = fill_trace_fp_mul_mod(trace, &y_y_num, &x_den.0, s_row, end_row, start_col + ISO_MAP_Y_Y_NUM_X_DEN_OFFSET);
+    let res_y = fill_trace_fp_mul_mod(trace, &y_y_num_x_den, &den_inv.0, s_row, end_row, start_col + ISO_MAP_RES_Y_MUL_OFFSET);
+    let res = if infinity {
+        G1Affine::identity()
+    } else {
+        G1Affine {
+            x: Fp(res_x),
+            y: Fp(res_y),
+            infinity: false,
+        }
+    };
+    for row in s_row..end_row + 1 {
+        trace[row][start_col + ISO_MAP_INFINITY_OFFSET] = F::from_bool(infinity);
+        assign_u32_in_series(trace, row, start_col + ISO_MAP_RES_X_OFFSET, &res.x.0);
+        assign_u32_in_series(trace, row, start_col + ISO_MAP_RES_Y_OFFSET, &res.y.0);
+    }
+    res
+}
+
+/// Constraints the 11-isogeny map.
+///
+/// Constraints the input to be same across the rows wherever the selector is on. The step selectors are boolean and sum to `ISO_MAP_CHECK_OFFSET`, stay the same within a step and are followed by the next step. All constraints are kept within degree 3, so the step end is derived as `ISO_MAP_SELECTOR_OFFSET - ISO_MAP_STEP_SELECTOR_OFFSET`.
+/// Wherever `ISO_MAP_CHECK_OFFSET` is set, constraints the Horner multiplication of the accumulator with x and the addition of the coefficient of the current step. The accumulator is the coefficient of degree 15 in the first step and the result of the previous step in the next ones.
+/// In the rows of the last step, constraints the inputs of the divisions. The infinity flag is boolean, only set in the last step and only if `x_den * y_den` is zero, otherwise the product is constrained with the input of the fp inverse. The result is `(x_num / x_den, y * y_num / y_den)`, or zero for the point at infinity.
+/// Then constraints the fp multiplication, addition and inverse operations.
+pub fn add_iso_map_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+    let selector = bit_selector_val * local_values[start_col + ISO_MAP_SELECTOR_OFFSET];
+    let check = bit_selector_val * local_values[start_col + ISO_MAP_CHECK_OFFSET];
+    let step_selector = local_values[start_col + ISO_MAP_STEP_SELECTOR_OFFSET];
+    let step_end = bit_selector_val * (local_values[start_col + ISO_MAP_SELECTOR_OFFSET] - step_selector);
+
+    for col in [ISO_MAP_X_OFFSET, ISO_MAP_Y_OFFSET] {
+        for i in 0..12 {
+            yield_constr.constraint_transition(
+                selector *
+                (local_values[start_col + col + i] - next_values[start_col + col + i])
+            );
+        }
+    }
+
+    let mut step_sum = P::ZEROS;
+    for step in 0..ISO_MAP_STEPS {
+        let s = local_values[start_col + ISO_MAP_STEP_OFFSET + step];
+        step_sum = step_sum + s;
+        yield_constr.constraint(bit_selector_val * s * (P::ONES - s));
+        yield_constr.constraint_transition(
+            bit_selector_val * step_selector *
+            (next_values[start_col + ISO_MAP_STEP_OFFSET + step] - s)
+        );
+        if step < ISO_MAP_STEPS - 1 {
+            yield_constr.constraint_transition(
+                step_end *
+                (next_values[start_col + ISO_MAP_STEP_OFFSET + step + 1] - s)
+            );
+        }
+    }
+    yield_constr.constraint(bit_selector_val * (step_sum - local_values[start_col + ISO_MAP_CHECK_OFFSET]));
+
+    let first_step = bit_selector_val * local_values[start_col + ISO_MAP_STEP_OFFSET];
+    for (col, coeffs) in ISO_MAP_POLYS {
+        let acc = start_col + col + ISO_MAP_HORNER_ACC_OFFSET;
+        let mul = start_col + col + ISO_MAP_HORNER_MUL_OFFSET;
+        let add = start_col + col + ISO_MAP_HORNER_ADD_OFFSET;
+        for i in 0..12 {
+            let coeff = (0..ISO_MAP_STEPS).fold(P::ZEROS, |sum, step| {
+                sum + local_values[start_col + ISO_MAP_STEP_OFFSET + step] *
+                    FE::from_canonical_u32(iso_map_coeff(coeffs, ISO_MAP_STEPS - 1 - step).0[i])
+            });
+            yield_constr.constraint(check * (local_values[fp_mul_mod_x(mul) + i] - local_values[acc + i]));
+            yield_constr.constraint(check * (local_values[fp_mul_mod_y(mul) + i] - local_values[start_col + ISO_MAP_X_OFFSET + i]));
+            yield_constr.constraint(check * (local_values[fp_add_mod_x(add) + i] - local_values[fp_mul_mod_res(mul) + i]));
+            yield_constr.constraint(check * (local_values[fp_add_mod_y(add) + i] - coeff));
+            yield_constr.constraint(
+                first_step *
+                (local_values[acc + i] - FE::from_canonical_u32(iso_map_coeff(coeffs, ISO_MAP_STEPS).0[i]))
+            );
+            yield_constr.constraint_transition(
+                step_end *
+                (next_values[acc + i] - local_values[fp_add_mod_res(add) + i])
+            );
+        }
+    }
+
+    let last_step = bit_selector_val * local_values[start_col + ISO_MAP_STEP_OFFSET + ISO_MAP_STEPS - 1];
+    let infinity = bit_selector_val * local_values[start_col + ISO_MAP_INFINITY_OFFSET];
+    let finite = last_step - infinity;
+    for (input_col, value_col) in iso_map_division_links(start_col) {
+        for i in 0..12 {
+            yield_constr.constraint(
+                last_step *
+                (local_values[input_col + i] - local_values[value_col + i])
+            );
+        }
+    }
+    yield_constr.constraint(infinity * (P::ONES - local_values[start_col + ISO_MAP_INFINITY_OFFSET]));
+    yield_constr.constraint(infinity * (P::ONES - local_values[start_col + ISO_MAP_STEP_OFFSET + ISO_MAP_STEPS - 1]));
+    let den_prod = fp_mul_mod_res(start_col + ISO_MAP_DEN_PROD_OFFSET);
+    let res_x = fp_mul_mod_res(start_col + ISO_MAP_RES_X_MUL_OFFSET);
+    let res_y = fp_mul_mod_res(start_col + ISO_MAP_RES_Y_MUL_OFFSET);
+    for i in 0..12 {
+        yield_constr.constraint(infinity * local_values[den_prod + i]);
+        yield_constr.constraint(
+            finite *
+            (local_values[start_col + ISO_MAP_DEN_INV_OFFSET + FP_INV_INPUT_OFFSET + i] - local_values[den_prod + i])
+        );
+        for (res_col, value_col) in [(ISO_MAP_RES_X_OFFSET, res_x), (ISO_MAP_RES_Y_OFFSET, res_y)] {
+            yield_constr.constraint(
+                finite *
+                (local_values[start_col + res_col + i] - local_values[value_col + i])
+            );
+            yield_constr.constraint(infinity * local_values[start_col + res_col + i]);
+        }
+    }
+
+    for (col, _) in ISO_MAP_POLYS {
+        add_fp_mul_mod_constraints(local_values, next_values, yield_constr, start_col + col + ISO_MAP_HORNER_MUL_OFFSET, bit_selector);
+        add_addition_fp_mod_constraints(local_values, yield_constr, start_col + col + ISO_MAP_HORNER_ADD_OFFSET, bit_selector);
+    }
+    for col in [ISO_MAP_DEN_PROD_OFFSET, ISO_MAP_X_NUM_Y_DEN_OFFSET, ISO_MAP_RES_X_MUL_OFFSET, ISO_MAP_Y_Y_NUM_OFFSET, ISO_MAP_Y_Y_NUM_X_DEN_OFFSET, ISO_MAP_RES_Y_MUL_OFFSET] {
+        add_fp_mul_mod_constraints(local_values, next_values, yield_constr, start_col + col, bit_selector);
+    }
+    add_fp_inverse_constraints(local_values, next_values, yield_constr, start_col + ISO_MAP_DEN_INV_OFFSET, bit_selector);
+}
+
+pub fn add_iso_map_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let one = builder.constant_extension(F::Extension::ONE);
+    let bit_selector_val = bit_selector.unwrap_or(one);
+    let selector = builder.mul_extension(bit_selector_val, local_values[start_col + ISO_MAP_SELECTOR_OFFSET]);
+    let check = builder.mul_extension(bit_selector_val, local_values[start_col + ISO_MAP_CHECK_OFFSET]);
+    let step_selector = local_values[start_col + ISO_MAP_STEP_SELECTOR_OFFSET];
+    let step_end = builder.sub_extension(local_values[start_col + ISO_MAP_SELECTOR_OFFSET], step_selector);
+    let step_end = builder.mul_extension(bit_selector_val, step_end);
+
+    for col in [ISO_MAP_X_OFFSET, ISO_MAP_Y_OFFSET] {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[start_col + col + i], next_values[start_col + col + i]);
+            let c = builder.mul_extension(selector, c);
+            yield_constr.constraint_transition(builder, c);
+        }
+    }
+
+    let mut step_sum = builder.constant_extension(F::Extension::ZERO);
+    for step in 0..ISO_MAP_STEPS {
+        let s = local_values[start_col + ISO_MAP_STEP_OFFSET + step];
+        step_sum = builder.add_extension(step_sum, s);
+        let c = builder.sub_extension(one, s);
+        let c = builder.mul_many_extension([bit_selector_val, s, c]);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(next_values[start_col + ISO_MAP_STEP_OFFSET + step], s);
+        let c = builder.mul_many_extension([bit_selector_val, step_selector, c]);
+        yield_constr.constraint_transition(builder, c);
+        if step < ISO_MAP_STEPS - 1 {
+            let c = builder.sub_extension(next_values[start_col + ISO_MAP_STEP_OFFSET + step + 1], s);
+            let c = builder.mul_extension(step_end, c);
+            yield_constr.constraint_transition(builder, c);
+        }
+    }
+    let c = builder.sub_extension(step_sum, local_values[start_col + ISO_MAP_CHECK_OFFSET]);
+    let c = builder.mul_extension(bit_selector_val, c);
+    yield_constr.constraint(builder, c);
+
+    let first_step = builder.mul_extension(bit_selector_val, local_values[start_col + ISO_MAP_STEP_OFFSET]);
+    for (col, coeffs) in ISO_MAP_POLYS {
+        let acc = start_col + col + ISO_MAP_HORNER_ACC_OFFSET;
+        let mul = start_col + col + ISO_MAP_HORNER_MUL_OFFSET;
+        let add = start_col + col + ISO_MAP_HORNER_ADD_OFFSET;
+        for i in 0..12 {
+            let mut coeff = builder.constant_extension(F::Extension::ZERO);
+            for step in 0..ISO_MAP_STEPS {
+                let value = builder.constant_extension(F::Extension::from_canonical_u32(iso_map_coeff(coeffs, ISO_MAP_STEPS - 1 - step).0[i]));
+                coeff = builder.mul_add_extension(local_values[start_col + ISO_MAP_STEP_OFFSET + step], value, coeff);
+            }
+            let c = builder.sub_extension(local_values[fp_mul_mod_x(mul) + i], local_values[acc + i]);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+            let c = builder.sub_extension(local_values[fp_mul_mod_y(mul) + i], local_values[start_col + ISO_MAP_X_OFFSET + i]);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+            let c = builder.sub_extension(local_values[fp_add_mod_x(add) + i], local_values[fp_mul_mod_res(mul) + i]);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+            let c = builder.sub_extension(local_values[fp_add_mod_y(add) + i], coeff);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+            let leading = builder.constant_extension(F::Extension::from_canonical_u32(iso_map_coeff(coeffs, ISO_MAP_STEPS).0[i]));
+            let c = builder.sub_extension(local_values[acc + i], leading);
+            let c = builder.mul_extension(first_step, c);
+            yield_constr.constraint(builder, c);
+            let c = builder.sub_extension(next_values[acc + i], local_values[fp_add_mod_res(add) + i]);
+            let c = builder.mul_extension(step_end, c);
+            yield_constr.constraint_transition(builder, c);
+        }
+    }
+
+    let last_step = builder.mul_extension(bit_selector_val, local_values[start_col + ISO_MAP_STEP_OFFSET + ISO_MAP_STEPS - 1]);
+    let infinity = builder.mul_extension(bit_selector_val, local_values[start_col + ISO_MAP_INFINITY_OFFSET]);
+    let finite = builder.sub_extension(last_step, infinity);
+    for (input_col, value_col) in iso_map_division_links(start_col) {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[input_col + i], local_values[value_col + i]);
+            let c = builder.mul_extension(last_step, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+    let c = builder.sub_extension(one, local_values[start_col + ISO_MAP_INFINITY_OFFSET]);
+    let c = builder.mul_extension(infinity, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.sub_extension(one, local_values[start_col + ISO_MAP_STEP_OFFSET + ISO_MAP_STEPS - 1]);
+    let c = builder.mul_extension(infinity, c);
+    yield_constr.constraint(builder, c);
+    let den_prod = fp_mul_mod_res(start_col + ISO_MAP_DEN_PROD_OFFSET);
+    let res_x = fp_mul_mod_res(start_col + ISO_MAP_RES_X_MUL_OFFSET);
+    let res_y = fp_mul_mod_res(start_col + ISO_MAP_RES_Y_MUL_OFFSET);
+    for i in 0..12 {
+        let c = builder.mul_extension(infinity, local_values[den_prod + i]);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(local_values[start_col + ISO_MAP_DEN_INV_OFFSET + FP_INV_INPUT_OFFSET + i], local_values[den_prod + i]);
+        let c = builder.mul_extension(finite, c);
+        yield_constr.constraint(builder, c);
+        for (res_col, value_col) in [(ISO_MAP_RES_X_OFFSET, res_x), (ISO_MAP_RES_Y_OFFSET, res_y)] {
+            let c = builder.sub_extension(local_values[start_col + res_col + i], local_values[value_col + i]);
+            let c = builder.mul_extension(finite, c);
+            yield_constr.constraint(builder, c);
+            let c = builder.mul_extension(infinity, local_values[start_col + res_col + i]);
+            yield_constr.constraint(builder, c);
+        }
+    }
+
+    for (col, _) in ISO_MAP_POLYS {
+        add_fp_mul_mod_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + col + ISO_MAP_HORNER_MUL_OFFSET, bit_selector);
+        add_addition_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + col + ISO_MAP_HORNER_ADD_OFFSET, bit_selector);
+    }
+    for col in [ISO_MAP_DEN_PROD_OFFSET, ISO_MAP_X_NUM_Y_DEN_OFFSET, ISO_MAP_RES_X_MUL_OFFSET, ISO_MAP_Y_Y_NUM_OFFSET, ISO_MAP_Y_Y_NUM_X_DEN_OFFSET, ISO_MAP_RES_Y_MUL_OFFSET] {
+        add_fp_mul_mod_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + col, bit_selector);
+    }
+    add_fp_inverse_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + ISO_MAP_DEN_INV_OFFSET, bit_selector);
+}
+
+/// Stark proving that a point on the G1 curve is the 11-isogeny map of a finite point on E'.
+#[derive(Clone, Copy)]
+pub struct IsoMapStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+/// Pairs of (trace column, public input) which are constrained to be equal in the rows of the last step, along with the number of limbs.
+const PIS_LINKS: [(usize, usize, usize); 5] = [
+    (ISO_MAP_X_OFFSET, PIS_X_OFFSET, 12),
+    (ISO_MAP_Y_OFFSET, PIS_Y_OFFSET, 12),
+    (ISO_MAP_RES_X_OFFSET, PIS_RES_X_OFFSET, 12),
+    (ISO_MAP_RES_Y_OFFSET, PIS_RES_Y_OFFSET, 12),
+    (ISO_MAP_INFINITY_OFFSET, PIS_RES_INFINITY_OFFSET, 1),
+];
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> IsoMapStark<F, D> {
+    /// Rows used by one isogeny map, `ISO_MAP_ROWS`.
+    pub fn required_rows() -> usize {
+        ISO_MAP_ROWS
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(p: &G1Affine, res: &G1Affine) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for i in 0..12 {
+            public_inputs[PIS_X_OFFSET + i] = F::from_canonical_u32(p.x.0[i]);
+            public_inputs[PIS_Y_OFFSET + i] = F::from_canonical_u32(p.y.0[i]);
+            public_inputs[PIS_RES_X_OFFSET + i] = F::from_canonical_u32(res.x.0[i]);
+            public_inputs[PIS_RES_Y_OFFSET + i] = F::from_canonical_u32(res.y.0[i]);
+        }
+        public_inputs[PIS_RES_INFINITY_OFFSET] = F::from_bool(res.infinity);
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, p: &G1Affine) -> Vec<[F; TOTAL_COLUMNS]> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_iso_map(&mut trace, p, 0, ISO_MAP_ROWS - 1, 0);
+        trace
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for IsoMapStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[ISO_MAP_STEP_OFFSET] - P::ONES);
+        let last_step = local_values[ISO_MAP_STEP_OFFSET + ISO_MAP_STEPS - 1];
+        for (col, pis, limbs) in PIS_LINKS {
+            for i in 0..limbs {
+                yield_constr.constraint(
+                    last_step *
+                    (local_values[col + i] - public_inputs[pis + i])
+                );
+            }
+        }
+        add_iso_map_constraints(local_values, next_values, yield_constr, 0, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[ISO_MAP_STEP_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        let last_step = local_values[ISO_MAP_STEP_OFFSET + ISO_MAP_STEPS - 1];
+        for (col, pis, limbs) in PIS_LINKS {
+            for i in 0..limbs {
+                let c = builder.sub_extension(local_values[col + i], public_inputs[pis + i]);
+                let c = builder.mul_extension(last_step, c);
+                yield_constr.constraint(builder, c);
+            }
+        }
+        add_iso_map_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{iso_map_g1, map_to_curve_g1, Fp, G1Affine};
+
+    use super::{IsoMapStark, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = IsoMapStark<F, D>;
+
+    fn fp_from_hex(s: &str) -> Fp {
+        Fp::get_fp_from_biguint(BigUint::parse_bytes(s.as_bytes(), 16).unwrap())
+    }
+
+    fn prove_and_verify_iso_map(p: &G1Affine) -> G1Affine {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(256);
+        let res = iso_map_g1(*p);
+        let public_inputs = S::public_inputs(p, &res);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(p);
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        res
+    }
+
+    #[test]
+    fn test_iso_map_rfc_vectors() {
+        // u[0] and Q0 of the RFC 9380 BLS12381G1_XMD:SHA-256_SSWU_RO_ test vectors for msg "" and msg "abc".
+        let vectors = [
+            (
+                "0ba14bd907ad64a016293ee7c2d276b8eae71f25a4b941eece7b0d89f17f75cb3ae5438a614fb61d6835ad59f29c564f",
+                "11a3cce7e1d90975990066b2f2643b9540fa40d6137780df4e753a8054d07580db3b7f1f03396333d4a359d1fe3766fe",
+                "0eeaf6d794e479e270da10fdaf768db4c96b650a74518fc67b04b03927754bac66f3ac720404f339ecdcc028afa091b7",
+            ),
+            (
+                "0d921c33f2bad966478a03ca35d05719bdf92d347557ea166e5bba579eea9b83e9afa5c088573c2281410369fbd32951",
+                "125435adce8e1cbd1c803e7123f45392dc6e326d292499c2c45c5865985fd74fe8f042ecdeeec5ecac80680d04317d80",
+                "0e8828948c989126595ee30e4f7c931cbd6f4570735624fd25aef2fa41d3f79cfb4b4ee7b7e55a8ce013af2a5ba20bf2",
+            ),
+        ];
+        for (u, x, y) in vectors {
+            let res = prove_and_verify_iso_map(&map_to_curve_g1(fp_from_hex(u)));
+            assert_eq!(res.x, fp_from_hex(x));
+            assert_eq!(res.y, fp_from_hex(y));
+        }
+    }
+
+    #[test]
+    fn test_iso_map_kernel() {
+        // A point of E' in the kernel of the isogeny, which maps to the point at infinity.
+        let p = G1Affine {
+            x: Fp::get_fp_from_biguint(BigUint::from_str("2512099095366387796245759085729510986367032014959769672734622752070562589059815523018960565849753051338812932816014").unwrap()),
+            y: Fp::get_fp_from_biguint(BigUint::from_str("3869440428665828672126986942449931708292407950503965278303393917520883965908367251300173570528123700077316614991402").unwrap()),
+            infinity: false,
+        };
+        assert_eq!(prove_and_verify_iso_map(&p), G1Affine::identity());
+    }
+}
@@ -0,0 +1,84 @@
+pub mod native;
+pub mod constants;
+#[cfg(feature = "prover")]
+pub mod config;
+pub mod big_arithmetic;
+#[cfg(feature = "prover")]
+pub mod fp;
+#[cfg(feature = "prover")]
+pub mod fp2;
+#[cfg(feature = "prover")]
+pub mod fp6;
+#[cfg(feature = "prover")]
+pub mod fp12;
+#[cfg(feature = "prover")]
+pub mod utils;
+#[cfg(feature = "prover")]
+pub mod calc_pairing_precomp;
+#[cfg(feature = "prover")]
+pub mod miller_loop;
+#[cfg(feature = "prover")]
+pub mod final_exponentiate;
+#[cfg(feature = "prover")]
+pub mod fp12_mul;
+#[cfg(feature = "prover")]
+pub mod pairing;
+#[cfg(feature = "prover")]
+pub mod multi_miller_loop;
+#[cfg(feature = "prover")]
+pub mod g1;
+#[cfg(feature = "prover")]
+pub mod g1_add;
+#[cfg(feature = "prover")]
+pub mod g1_scalar_mul;
+#[cfg(feature = "prover")]
+pub mod g1_subgroup;
+#[cfg(feature = "prover")]
+pub mod g1_glv;
+#[cfg(feature = "prover")]
+pub mod g1_msm;
+#[cfg(feature = "prover")]
+pub mod fp_inverse;
+#[cfg(feature = "prover")]
+pub mod fp_reduce;
+#[cfg(feature = "prover")]
+pub mod fp2_inverse;
+#[cfg(feature = "prover")]
+pub mod fp2_sqrt;
+#[cfg(feature = "prover")]
+pub mod fp12_exp;
+#[cfg(feature = "prover")]
+pub mod fp_sqrt;
+#[cfg(feature = "prover")]
+pub mod serialization;
+pub mod vectors;
+#[cfg(feature = "serde")]
+mod native_serde;
+#[cfg(feature = "prover")]
+pub mod aggregate_verify;
+#[cfg(feature = "prover")]
+pub mod bls_verify;
+#[cfg(feature = "prover")]
+pub mod map_to_curve;
+#[cfg(feature = "prover")]
+pub mod iso_map;
+#[cfg(feature = "prover")]
+pub mod g1_clear_cofactor;
+#[cfg(feature = "prover")]
+pub mod fp2_frobenius;
+#[cfg(feature = "prover")]
+pub mod fp4_square;
+#[cfg(feature = "prover")]
+pub mod g2;
+#[cfg(feature = "prover")]
+pub mod on_curve;
+#[cfg(feature = "debug-constraints")]
+pub mod debug_constraints;
+#[cfg(feature = "debug-dump")]
+pub mod debug_dump;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
+#[cfg(test)]
+mod native_reference_tests;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
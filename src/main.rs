@@ -1,7 +1,7 @@
 use plonky2::{plonk::config::{PoseidonGoldilocksConfig, GenericConfig}, util::timing::TimingTree};
 use starky::{config::StarkConfig, prover::prove, verifier::verify_stark_proof};
-use crate::{native::{Fp2, Fp, Fp12}, calc_pairing_precomp::PairingPrecompStark, miller_loop::MillerLoopStark, final_exponentiate::FinalExponentiateStark, fp12_mul::FP12MulStark};
-use starky::util::trace_rows_to_poly_values;
+use starky_bls12_381::{native::{Fp2, Fp, Fp12, G2Affine, G2Projective}, calc_pairing_precomp::PairingPrecompStark, miller_loop::MillerLoopStark, final_exponentiate::FinalExponentiateStark, fp12_mul::FP12MulStark};
+use starky_bls12_381::utils::trace_rows_to_poly_values_streaming;
 use std::time::Instant;
 
 use plonky2::plonk::circuit_data::{CircuitConfig, CommonCircuitData, VerifierOnlyCircuitData};
@@ -15,17 +15,16 @@ use plonky2::plonk::prover::prove as plonky2_prove;
 use log::Level;
 use anyhow::Result;
 
-pub mod native;
-pub mod big_arithmetic;
-pub mod fp;
-pub mod fp2;
-pub mod fp6;
-pub mod fp12;
-pub mod utils;
-pub mod calc_pairing_precomp;
-pub mod miller_loop;
-pub mod final_exponentiate;
-pub mod fp12_mul;
+use starky_bls12_381::{native, calc_pairing_precomp, miller_loop, final_exponentiate, fp12_mul};
+#[cfg(feature = "debug-constraints")]
+use starky_bls12_381::debug_constraints;
+
+/// Default config of the prove functions below, `StarkConfig::standard_fast_config()` with a blowup factor of `2^rate_bits`. A stark with constraints of degree `d` needs `rate_bits >= log2_ceil(d - 1)`, i.e. 1 for the miller loop and fp12 multiplication starks, and 2 for the pairing precompute and final exponentiate starks. Any other config meeting this bound can be passed instead, e.g. a higher blowup with fewer FRI queries for smaller proofs.
+pub fn default_stark_config(rate_bits: usize) -> StarkConfig {
+    let mut config = StarkConfig::standard_fast_config();
+    config.fri_config.rate_bits = rate_bits;
+    config
+}
 
 fn calc_pairing_precomp<
     F: RichField + Extendable<D>,
@@ -35,9 +34,8 @@ fn calc_pairing_precomp<
     x: Fp2,
     y: Fp2,
     z: Fp2,
+    config: StarkConfig,
 ) -> (PairingPrecompStark<F, D>, starky::proof::StarkProofWithPublicInputs<F, C, D>, StarkConfig) {
-    let mut config = StarkConfig::standard_fast_config();
-    config.fri_config.rate_bits = 2;
     let stark = PairingPrecompStark::<F, D>::new(1024);
     let trace = stark.generate_trace(x.get_u32_slice(), y.get_u32_slice(), z.get_u32_slice());
     let ell_coeffs = native::calc_pairing_precomp(x, y, z);
@@ -61,7 +59,9 @@ fn calc_pairing_precomp<
         }
     }
     assert_eq!(public_inputs.len(), calc_pairing_precomp::PUBLIC_INPUTS);
-    let trace_poly_values = trace_rows_to_poly_values(trace);
+    #[cfg(feature = "debug-constraints")]
+    debug_constraints::assert_trace_satisfies(&stark, &trace, &public_inputs);
+    let trace_poly_values = trace_rows_to_poly_values_streaming(trace);
     let t = Instant::now();
     let proof = prove::<F, C, PairingPrecompStark<F, D>, D>(
         stark,
@@ -79,36 +79,16 @@ fn miller_loop_main<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F=F>,
     const D: usize
->(x: Fp, y: Fp, q_x: Fp2, q_y: Fp2, q_z: Fp2) -> (MillerLoopStark<F, D>, starky::proof::StarkProofWithPublicInputs<F, C, D>, StarkConfig) {
-    let config = StarkConfig::standard_fast_config();
+>(x: Fp, y: Fp, q: &G2Affine, config: StarkConfig) -> (MillerLoopStark<F, D>, starky::proof::StarkProofWithPublicInputs<F, C, D>, StarkConfig) {
     let stark = MillerLoopStark::<F, D>::new(1024);
-    let ell_coeffs = native::calc_pairing_precomp(q_x, q_y, q_z);
-    let res = native::miller_loop(x, y, q_x, q_y, q_z);
-    let mut public_inputs = Vec::<F>::new();
-    for e in x.0.iter() {
-        public_inputs.push(F::from_canonical_u32(*e));
-    }
-    for e in y.0.iter() {
-        public_inputs.push(F::from_canonical_u32(*e));
-    }
-    for coeff in ell_coeffs.iter() {
-        for f2 in coeff.iter() {
-            for f in f2.0.iter() {
-                for e in f.0.iter() {
-                    public_inputs.push(F::from_canonical_u32(*e));
-                }
-            }
-        }
-    }
-    for f in res.0.iter() {
-        for e in f.0.iter() {
-            public_inputs.push(F::from_canonical_u32(*e));
-        }
-    }
+    let res = native::miller_loop(x, y, q.x, q.y, Fp2::one());
+    let public_inputs = MillerLoopStark::<F, D>::public_inputs(&x, &y, q, &res).unwrap();
     assert_eq!(public_inputs.len(), miller_loop::PUBLIC_INPUTS);
     let s = Instant::now();
-    let trace = stark.generate_trace(x, y, ell_coeffs);
-    let trace_poly_values = trace_rows_to_poly_values(trace);
+    let trace = stark.generate_trace(x, y, q).unwrap();
+    #[cfg(feature = "debug-constraints")]
+    debug_constraints::assert_trace_satisfies(&stark, &trace, &public_inputs);
+    let trace_poly_values = trace_rows_to_poly_values_streaming(trace);
     let proof = prove::<F, C, MillerLoopStark<F, D>, D>(
         stark,
         &config,
@@ -125,8 +105,7 @@ fn fp12_mul_main<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F=F>,
     const D: usize
->(x: Fp12, y: Fp12) -> (FP12MulStark<F, D>, starky::proof::StarkProofWithPublicInputs<F, C, D>, StarkConfig) {
-    let config = StarkConfig::standard_fast_config();
+>(x: Fp12, y: Fp12, config: StarkConfig) -> (FP12MulStark<F, D>, starky::proof::StarkProofWithPublicInputs<F, C, D>, StarkConfig) {
     let stark = FP12MulStark::<F, D>::new(16);
     let s = Instant::now();
     let mut public_inputs = Vec::<F>::new();
@@ -141,7 +120,9 @@ fn fp12_mul_main<
     }
     assert_eq!(public_inputs.len(), fp12_mul::PUBLIC_INPUTS);
     let trace = stark.generate_trace(x, y);
-    let trace_poly_values = trace_rows_to_poly_values(trace);
+    #[cfg(feature = "debug-constraints")]
+    debug_constraints::assert_trace_satisfies(&stark, &trace, &public_inputs);
+    let trace_poly_values = trace_rows_to_poly_values_streaming(trace);
     let proof = prove::<F, C, FP12MulStark<F, D>, D>(
         stark,
         &config,
@@ -154,25 +135,22 @@ fn fp12_mul_main<
     (stark, proof, config)
 }
 
+/// Number of rows of the final exponentiate stark proved by [final_exponentiate_main], the smallest power of two fitting `final_exponentiate::TOTAL_ROW`.
+const FINAL_EXP_NUM_ROWS: usize = 8192;
+
 fn final_exponentiate_main<
     F: RichField + Extendable<D>,
     C: GenericConfig<D, F=F>,
     const D: usize
->(x: Fp12) -> (FinalExponentiateStark<F, D>, starky::proof::StarkProofWithPublicInputs<F, C, D>, StarkConfig) {
-    let mut config = StarkConfig::standard_fast_config();
-    config.fri_config.rate_bits = 2;
-    let stark = FinalExponentiateStark::<F, D>::new(8192);
+>(x: Fp12, config: StarkConfig) -> (FinalExponentiateStark<F, D>, starky::proof::StarkProofWithPublicInputs<F, C, D>, StarkConfig) {
+    let stark = FinalExponentiateStark::<F, D>::new(FINAL_EXP_NUM_ROWS).unwrap();
     let s = Instant::now();
-    let mut public_inputs = Vec::<F>::new();
-    for e in x.get_u32_slice().concat().iter() {
-        public_inputs.push(F::from_canonical_u32(*e));
-    }
-    for e in x.final_exponentiate().get_u32_slice().concat().iter() {
-        public_inputs.push(F::from_canonical_u32(*e));
-    }
+    let public_inputs = FinalExponentiateStark::<F, D>::public_inputs(&x, &x.final_exponentiate());
     assert_eq!(public_inputs.len(), final_exponentiate::PUBLIC_INPUTS);
-    let trace = stark.generate_trace(x);
-    let trace_poly_values = trace_rows_to_poly_values(trace);
+    let trace = stark.generate_trace(x).unwrap();
+    #[cfg(feature = "debug-constraints")]
+    debug_constraints::assert_trace_satisfies(&stark, &trace, &public_inputs);
+    let trace_poly_values = trace_rows_to_poly_values_streaming(trace);
     let proof = prove::<F, C, FinalExponentiateStark<F, D>, D>(
         stark,
         &config,
@@ -201,12 +179,15 @@ fn aggregate_proof() {
     let q_y1 = Fp2([Fp([3944640261, 440162500, 3767697757, 767512216, 3185360355, 1355179671, 2310853452, 2890628660, 2539693039, 3306767406, 473197245, 198293246]), Fp([920955909, 775806582, 2117093864, 286632291, 2248224021, 4208799968, 2272086148, 4009382258, 291945614, 2017047933, 1541154483, 220533456])]);
     let q_z1 = Fp2([Fp([2780158026, 2572579871, 3558563268, 1947800745, 1784566622, 912901049, 1766882808, 1286945791, 2204464567, 728083964, 3377958885, 227852528]), Fp([1492897660, 2845803056, 3990009560, 3332584519, 1144621723, 1049137482, 2386536189, 2220905202, 28647458, 3875714686, 701911767, 391244403])]);
 
+    // The miller loop stark takes Q in affine coordinates, so the pairing precompute is done on the affine point too, for their ell_coeffs to match.
+    let q1 = G2Projective { x: q_x1, y: q_y1, z: q_z1 }.to_affine();
+
     println!("calc_pairing_precomp stark 1");
     let (
         stark_pp1,
         proof_pp1,
         config_pp1
-    ) = calc_pairing_precomp::<F, C, D>(q_x1, q_y1, q_z1);
+    ) = calc_pairing_precomp::<F, C, D>(q1.x, q1.y, Fp2::one(), default_stark_config(2));
     let recursive_pp1 = recursive_proof::<F, C, PpStark, C, D>(stark_pp1, proof_pp1.clone(), &config_pp1, true);
 
     println!("miller_loop stark 1");
@@ -214,7 +195,7 @@ fn aggregate_proof() {
         stark_ml1,
         proof_ml1,
         config_ml1,
-    ) = miller_loop_main::<F, C, D>(px1, py1, q_x1, q_y1, q_z1);
+    ) = miller_loop_main::<F, C, D>(px1, py1, &q1, default_stark_config(1));
     let recursive_ml1 = recursive_proof::<F, C, MlStark, C, D>(stark_ml1, proof_ml1.clone(), &config_ml1, true);
 
     let px2 = Fp([3676489403, 4214943754, 4185529071, 1817569343, 387689560, 2706258495, 2541009157, 3278408783, 1336519695, 647324556, 832034708, 401724327]);
@@ -223,12 +204,14 @@ fn aggregate_proof() {
     let q_y2 = Fp2([Fp([3291452691, 1526698400, 123085972, 4217256013, 2390597986, 3622429380, 1791215328, 2878530825, 3131550138, 3116253669, 3504636512, 151829271]), Fp([4123265126, 2752013218, 1556720399, 386948539, 3643514185, 2039427681, 3467442232, 2876818448, 3322584909, 2011252300, 838048598, 284195453])]);
     let q_z2 = Fp2([Fp([1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]), Fp([0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0])]);
 
+    let q2 = G2Projective { x: q_x2, y: q_y2, z: q_z2 }.to_affine();
+
     println!("calc_pairing_precomp stark 2");
     let (
         stark_pp2,
         proof_pp2,
         config_pp2
-    ) = calc_pairing_precomp::<F, C, D>(q_x2, q_y2, q_z2);
+    ) = calc_pairing_precomp::<F, C, D>(q2.x, q2.y, Fp2::one(), default_stark_config(2));
     let recursive_pp2 = recursive_proof::<F, C, PpStark, C, D>(stark_pp2, proof_pp2.clone(), &config_pp2, true);
 
     println!("miller_loop stark 2");
@@ -236,17 +219,17 @@ fn aggregate_proof() {
         stark_ml2,
         proof_ml2,
         config_ml2,
-    ) = miller_loop_main::<F, C, D>(px2, py2, q_x2, q_y2, q_z2);
+    ) = miller_loop_main::<F, C, D>(px2, py2, &q2, default_stark_config(1));
     let recursive_ml2 = recursive_proof::<F, C, MlStark, C, D>(stark_ml2, proof_ml2.clone(), &config_ml2, true);
     
-    let ml1_res = native::miller_loop(px1, py1, q_x1, q_y1, q_z1);
-    let ml2_res = native::miller_loop(px2, py2, q_x2, q_y2, q_z2);
+    let ml1_res = native::miller_loop(px1, py1, q1.x, q1.y, Fp2::one());
+    let ml2_res = native::miller_loop(px2, py2, q2.x, q2.y, Fp2::one());
     println!("fp12_mul stark");
     let (
         stark_fp12_mul,
         proof_fp12_mul,
         config_fp12_mul,
-    ) = fp12_mul_main::<F, C, D>(ml1_res, ml2_res);
+    ) = fp12_mul_main::<F, C, D>(ml1_res, ml2_res, default_stark_config(1));
     let recursive_fp12_mul = recursive_proof::<F, C, Fp12MulStark, C, D>(stark_fp12_mul, proof_fp12_mul.clone(), &config_fp12_mul, true);
 
     let final_exp_input = ml1_res * ml2_res;
@@ -255,7 +238,7 @@ fn aggregate_proof() {
         stark_final_exp,
         proof_final_exp,
         config_final_exp
-    ) = final_exponentiate_main::<F, C, D>(final_exp_input);
+    ) = final_exponentiate_main::<F, C, D>(final_exp_input, default_stark_config(2));
     let recursive_final_exp = recursive_proof::<F, C, FeStark, C, D>(stark_final_exp, proof_final_exp, &config_final_exp, true);
 
     aggregate_recursive_proof::<F, C, C, D>(
@@ -425,3 +408,49 @@ fn main() {
     }).unwrap().join().unwrap();
     return;
 }
+
+#[cfg(test)]
+mod tests {
+    use plonky2::plonk::config::{GenericConfig, PoseidonGoldilocksConfig};
+    use starky::verifier::verify_stark_proof;
+
+    use starky_bls12_381::{final_exponentiate::TOTAL_ROW, native::Fp12};
+
+    use super::{default_stark_config, final_exponentiate_main, FINAL_EXP_NUM_ROWS};
+
+    #[test]
+    fn test_final_exp_num_rows_is_minimal() {
+        assert_eq!(FINAL_EXP_NUM_ROWS, TOTAL_ROW.next_power_of_two());
+    }
+
+    #[test]
+    fn test_final_exponentiate_with_custom_configs() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+
+        let x = Fp12::from_str(["2181142506194812233868097821779361009807326315828153071050324314717744521676711650071190927260282422014627435089208",
+            "3266212670671256779826008414922395966600400122723332695666308996296105595418386213353825620535446475769829785237189",
+            "3280330655787598118299804758957910379684134784964426565939861302675766948066521588562898980898245868682162153155911",
+            "333668007718210311816046938245689395232794221928183840372182128979685996722059498232053963662509478803385469716056",
+            "1650925102445293819378017648160637800280351377141029658990698964033732511884552459036333864590686008335846481856882",
+            "3925133212240632255860280854235945320282874550806663137653784505923891479863770370026712801361887427462376126696706",
+            "2444089052091192833501409081021321360112867893942837175254954622703299880931587618210267154453853513743076365662283",
+            "3142914221549818039420055870398197863502329018278548609868118001898418737390067291084903575823960349378631910285921",
+            "1952057563719092278028425573632201081234877258097927010867141683896274170520489868686437644804596724295624637397077",
+            "254131389529427774765960554324483250584297364987873642087841623909520980093766889928789173976296059957431962608694",
+            "1385128161651935856764061834929068245137081648283968377947672499160305921464670953157912428887005620142387465559867",
+            "101302147352745188522496764263445345397483945567997375025250825330209385517139484882425580831299520200841767383756"]);
+
+        // Higher blowup with fewer queries, for a smaller proof at the same conjectured security
+        let mut small_proof_config = default_stark_config(3);
+        small_proof_config.fri_config.num_query_rounds = 28;
+
+        for config in [default_stark_config(2), small_proof_config] {
+            let num_query_rounds = config.fri_config.num_query_rounds;
+            let (stark, proof, config) = final_exponentiate_main::<F, C, D>(x, config);
+            assert_eq!(proof.proof.opening_proof.query_round_proofs.len(), num_query_rounds);
+            verify_stark_proof(stark, proof, &config).unwrap();
+        }
+    }
+}
@@ -0,0 +1,623 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use crate::native::{
+    get_bits_as_array, Fp, G1Affine, SSWU_G1_A, SSWU_G1_B, SSWU_G1_B_OVER_ZA, SSWU_G1_MINUS_B_OVER_A, SSWU_G1_Z,
+};
+
+use crate::fp::*;
+use crate::fp_inverse::*;
+use crate::fp_sqrt::*;
+use crate::utils::*;
+
+// Evaluation of g(x) = x^3 + A'x + B' layout offsets
+/*
+    These trace offsets are for evaluating the curve equation of the isogenous curve E' at x. It needs 12 rows.
+    SSWU_GX_X_SQ_OFFSET -> x * x
+    SSWU_GX_X_CU_OFFSET -> x^2 * x
+    SSWU_GX_AX_OFFSET -> A' * x
+    SSWU_GX_SUM_OFFSET -> x^3 + A'x
+    SSWU_GX_RES_OFFSET -> x^3 + A'x + B'
+*/
+pub const SSWU_GX_X_SQ_OFFSET: usize = 0;
+pub const SSWU_GX_X_CU_OFFSET: usize = SSWU_GX_X_SQ_OFFSET + FP_MUL_MOD_TOTAL;
+pub const SSWU_GX_AX_OFFSET: usize = SSWU_GX_X_CU_OFFSET + FP_MUL_MOD_TOTAL;
+pub const SSWU_GX_SUM_OFFSET: usize = SSWU_GX_AX_OFFSET + FP_MUL_MOD_TOTAL;
+pub const SSWU_GX_RES_OFFSET: usize = SSWU_GX_SUM_OFFSET + FP_ADD_MOD_TOTAL;
+pub const SSWU_GX_TOTAL: usize = SSWU_GX_RES_OFFSET + FP_ADD_MOD_TOTAL;
+
+// Simplified SWU map layout offsets
+/*
+    These trace offsets are for the simplified SWU map (super::native::map_to_curve_g1) of an fp element u to a point on the isogenous curve E'. It needs 12 rows.
+    SSWU_CHECK_OFFSET -> Selector set 1 in all rows of the operation, where the intermediate values are constrained with each other.
+    SSWU_INPUT_OFFSET -> offset where u is set.
+    SSWU_U_SQ_OFFSET -> u * u
+    SSWU_Z_U_SQ_OFFSET -> Z * u^2
+    SSWU_Z_U_SQ_SQ_OFFSET -> (Z * u^2)^2
+    SSWU_TV1_DEN_OFFSET -> Z^2 * u^4 + Z * u^2
+    SSWU_EXCEPTIONAL_OFFSET -> boolean set 1 if Z^2 * u^4 + Z * u^2 is zero.
+    SSWU_TV1_DEN_INV_OFFSET -> offset where the fp inverse of Z^2 * u^4 + Z * u^2 is done. In the exceptional case, the inverse of 1 is filled instead and left unconstrained with the denominator.
+    SSWU_ONE_TV1_OFFSET -> 1 + tv1, where tv1 is the inverse of the denominator.
+    SSWU_X1_MUL_OFFSET -> -B'/A' * (1 + tv1)
+    SSWU_X1_OFFSET -> offset where x1 is set. It is B'/(Z * A') in the exceptional case and -B'/A' * (1 + tv1) otherwise.
+    SSWU_GX1_OFFSET -> evaluation of g(x1).
+    SSWU_X2_OFFSET -> x2 = Z * u^2 * x1
+    SSWU_GX2_OFFSET -> evaluation of g(x2).
+    SSWU_SQRT1_OFFSET -> square root of g(x1), or the certificate that g(x1) is not a square.
+    SSWU_SQRT2_OFFSET -> square root of g(x2), or the certificate that g(x2) is not a square. g(x2) is constrained to be a square if g(x1) is not.
+    SSWU_Y0_OFFSET -> offset where the root of g(x) is set, g(x1) if it is a square and g(x2) otherwise.
+    SSWU_U_BITS_OFFSET -> bit decomposition of the first limb of u. The first bit is sgn0(u).
+    SSWU_Y0_BITS_OFFSET -> bit decomposition of the first limb of the root. The first bit is sgn0 of the root.
+    SSWU_FLIP_OFFSET -> boolean set 1 if sgn0(u) != sgn0 of the root, i.e. the root is negated.
+    SSWU_NEG_Y0_OFFSET -> 0 - root
+    SSWU_X_OFFSET, SSWU_Y_OFFSET -> offsets where the resulting point (x, y) on E' is set.
+*/
+pub const SSWU_CHECK_OFFSET: usize = 0;
+pub const SSWU_INPUT_OFFSET: usize = SSWU_CHECK_OFFSET + 1;
+pub const SSWU_U_SQ_OFFSET: usize = SSWU_INPUT_OFFSET + 12;
+pub const SSWU_Z_U_SQ_OFFSET: usize = SSWU_U_SQ_OFFSET + FP_MUL_MOD_TOTAL;
+pub const SSWU_Z_U_SQ_SQ_OFFSET: usize = SSWU_Z_U_SQ_OFFSET + FP_MUL_MOD_TOTAL;
+pub const SSWU_TV1_DEN_OFFSET: usize = SSWU_Z_U_SQ_SQ_OFFSET + FP_MUL_MOD_TOTAL;
+pub const SSWU_EXCEPTIONAL_OFFSET: usize = SSWU_TV1_DEN_OFFSET + FP_ADD_MOD_TOTAL;
+pub const SSWU_TV1_DEN_INV_OFFSET: usize = SSWU_EXCEPTIONAL_OFFSET + 1;
+pub const SSWU_ONE_TV1_OFFSET: usize = SSWU_TV1_DEN_INV_OFFSET + FP_INV_TOTAL;
+pub const SSWU_X1_MUL_OFFSET: usize = SSWU_ONE_TV1_OFFSET + FP_ADD_MOD_TOTAL;
+pub const SSWU_X1_OFFSET: usize = SSWU_X1_MUL_OFFSET + FP_MUL_MOD_TOTAL;
+pub const SSWU_GX1_OFFSET: usize = SSWU_X1_OFFSET + 12;
+pub const SSWU_X2_OFFSET: usize = SSWU_GX1_OFFSET + SSWU_GX_TOTAL;
+pub const SSWU_GX2_OFFSET: usize = SSWU_X2_OFFSET + FP_MUL_MOD_TOTAL;
+pub const SSWU_SQRT1_OFFSET: usize = SSWU_GX2_OFFSET + SSWU_GX_TOTAL;
+pub const SSWU_SQRT2_OFFSET: usize = SSWU_SQRT1_OFFSET + FP_SQRT_TOTAL;
+pub const SSWU_Y0_OFFSET: usize = SSWU_SQRT2_OFFSET + FP_SQRT_TOTAL;
+pub const SSWU_U_BITS_OFFSET: usize = SSWU_Y0_OFFSET + 12;
+pub const SSWU_Y0_BITS_OFFSET: usize = SSWU_U_BITS_OFFSET + 32;
+pub const SSWU_FLIP_OFFSET: usize = SSWU_Y0_BITS_OFFSET + 32;
+pub const SSWU_NEG_Y0_OFFSET: usize = SSWU_FLIP_OFFSET + 1;
+pub const SSWU_X_OFFSET: usize = SSWU_NEG_Y0_OFFSET + FP_SUB_MOD_TOTAL;
+pub const SSWU_Y_OFFSET: usize = SSWU_X_OFFSET + 12;
+pub const SSWU_TOTAL: usize = SSWU_Y_OFFSET + 12;
+
+pub const TOTAL_COLUMNS: usize = SSWU_TOTAL;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the input u, then the resulting point (x, y) on the isogenous curve E'.
+*/
+pub const PIS_INPUT_OFFSET: usize = 0;
+pub const PIS_X_OFFSET: usize = PIS_INPUT_OFFSET + 12;
+pub const PIS_Y_OFFSET: usize = PIS_X_OFFSET + 12;
+pub const PUBLIC_INPUTS: usize = PIS_Y_OFFSET + 12;
+
+/// Pairs of (input column, value column) of the g(x) evaluation starting at `start_col`, for x set at `x_col`.
+fn sswu_gx_input_links(start_col: usize, x_col: usize) -> Vec<(usize, usize)> {
+    vec![
+        (fp_mul_mod_x(start_col + SSWU_GX_X_SQ_OFFSET), x_col),
+        (fp_mul_mod_y(start_col + SSWU_GX_X_SQ_OFFSET), x_col),
+        (fp_mul_mod_x(start_col + SSWU_GX_X_CU_OFFSET), fp_mul_mod_res(start_col + SSWU_GX_X_SQ_OFFSET)),
+        (fp_mul_mod_y(start_col + SSWU_GX_X_CU_OFFSET), x_col),
+        (fp_mul_mod_y(start_col + SSWU_GX_AX_OFFSET), x_col),
+        (fp_add_mod_x(start_col + SSWU_GX_SUM_OFFSET), fp_mul_mod_res(start_col + SSWU_GX_X_CU_OFFSET)),
+        (fp_add_mod_y(start_col + SSWU_GX_SUM_OFFSET), fp_mul_mod_res(start_col + SSWU_GX_AX_OFFSET)),
+        (fp_add_mod_x(start_col + SSWU_GX_RES_OFFSET), fp_add_mod_res(start_col + SSWU_GX_SUM_OFFSET)),
+    ]
+}
+
+/// Pairs of (input column, value column) which are constrained to be equal wherever `SSWU_CHECK_OFFSET` is set.
+fn sswu_input_links(start_col: usize) -> Vec<(usize, usize)> {
+    let u = start_col + SSWU_INPUT_OFFSET;
+    let z_u_sq = fp_mul_mod_res(start_col + SSWU_Z_U_SQ_OFFSET);
+    let x1 = start_col + SSWU_X1_OFFSET;
+    let mut links = vec![
+        (fp_mul_mod_x(start_col + SSWU_U_SQ_OFFSET), u),
+        (fp_mul_mod_y(start_col + SSWU_U_SQ_OFFSET), u),
+        (fp_mul_mod_y(start_col + SSWU_Z_U_SQ_OFFSET), fp_mul_mod_res(start_col + SSWU_U_SQ_OFFSET)),
+        (fp_mul_mod_x(start_col + SSWU_Z_U_SQ_SQ_OFFSET), z_u_sq),
+        (fp_mul_mod_y(start_col + SSWU_Z_U_SQ_SQ_OFFSET), z_u_sq),
+        (fp_add_mod_x(start_col + SSWU_TV1_DEN_OFFSET), fp_mul_mod_res(start_col + SSWU_Z_U_SQ_SQ_OFFSET)),
+        (fp_add_mod_y(start_col + SSWU_TV1_DEN_OFFSET), z_u_sq),
+        (fp_add_mod_y(start_col + SSWU_ONE_TV1_OFFSET), start_col + SSWU_TV1_DEN_INV_OFFSET + FP_INV_INVERSE_OFFSET),
+        (fp_mul_mod_y(start_col + SSWU_X1_MUL_OFFSET), fp_add_mod_res(start_col + SSWU_ONE_TV1_OFFSET)),
+        (fp_mul_mod_x(start_col + SSWU_X2_OFFSET), z_u_sq),
+        (fp_mul_mod_y(start_col + SSWU_X2_OFFSET), x1),
+        (start_col + SSWU_SQRT1_OFFSET + FP_SQRT_INPUT_OFFSET, fp_add_mod_res(start_col + SSWU_GX1_OFFSET + SSWU_GX_RES_OFFSET)),
+        (start_col + SSWU_SQRT2_OFFSET + FP_SQRT_INPUT_OFFSET, fp_add_mod_res(start_col + SSWU_GX2_OFFSET + SSWU_GX_RES_OFFSET)),
+        (fp_sub_mod_y(start_col + SSWU_NEG_Y0_OFFSET), start_col + SSWU_Y0_OFFSET),
+    ];
+    links.extend(sswu_gx_input_links(start_col + SSWU_GX1_OFFSET, x1));
+    links.extend(sswu_gx_input_links(start_col + SSWU_GX2_OFFSET, fp_mul_mod_res(start_col + SSWU_X2_OFFSET)));
+    links
+}
+
+/// Pairs of (input column, constant) which are constrained to be equal wherever `SSWU_CHECK_OFFSET` is set.
+fn sswu_constant_links(start_col: usize) -> Vec<(usize, Fp)> {
+    let mut links = vec![
+        (fp_mul_mod_x(start_col + SSWU_Z_U_SQ_OFFSET), SSWU_G1_Z),
+        (fp_add_mod_x(start_col + SSWU_ONE_TV1_OFFSET), Fp::one()),
+        (fp_mul_mod_x(start_col + SSWU_X1_MUL_OFFSET), SSWU_G1_MINUS_B_OVER_A),
+        (fp_sub_mod_x(start_col + SSWU_NEG_Y0_OFFSET), Fp::zero()),
+    ];
+    for gx in [SSWU_GX1_OFFSET, SSWU_GX2_OFFSET] {
+        links.push((fp_mul_mod_x(start_col + gx + SSWU_GX_AX_OFFSET), SSWU_G1_A));
+        links.push((fp_add_mod_y(start_col + gx + SSWU_GX_RES_OFFSET), SSWU_G1_B));
+    }
+    links
+}
+
+/// Fills the trace of g(x) = x^3 + A'x + B'. Needs 12 rows. Returns g(x).
+fn fill_trace_sswu_gx<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, x: &Fp, start_row: usize, end_row: usize, start_col: usize) -> Fp {
+    let x_sq = fill_trace_fp_mul_mod(trace, &x.0, &x.0, start_row, end_row, start_col + SSWU_GX_X_SQ_OFFSET);
+    let x_cu = fill_trace_fp_mul_mod(trace, &x_sq, &x.0, start_row, end_row, start_col + SSWU_GX_X_CU_OFFSET);
+    let ax = fill_trace_fp_mul_mod(trace, &SSWU_G1_A.0, &x.0, start_row, end_row, start_col + SSWU_GX_AX_OFFSET);
+    let mut gx = [0; 12];
+    for row in start_row..end_row + 1 {
+        let sum = fill_trace_addition_fp_mod(trace, &x_cu, &ax, row, start_col + SSWU_GX_SUM_OFFSET);
+        gx = fill_trace_addition_fp_mod(trace, &sum, &SSWU_G1_B.0, row, start_col + SSWU_GX_RES_OFFSET);
+    }
+    Fp(gx)
+}
+
+/// Fills the stark trace of the simplified SWU map. Input is an fp element u. Needs 12 rows. Returns the resulting point on the isogenous curve E'.
+pub fn fill_trace_sswu<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, u: &Fp, start_row: usize, end_row: usize, start_col: usize) -> G1Affine {
+    let u_sq = fill_trace_fp_mul_mod(trace, &u.0, &u.0, start_row, end_row, start_col + SSWU_U_SQ_OFFSET);
+    let z_u_sq = fill_trace_fp_mul_mod(trace, &SSWU_G1_Z.0, &u_sq, start_row, end_row, start_col + SSWU_Z_U_SQ_OFFSET);
+    let z_u_sq_sq = fill_trace_fp_mul_mod(trace, &z_u_sq, &z_u_sq, start_row, end_row, start_col + SSWU_Z_U_SQ_SQ_OFFSET);
+    let den = Fp(z_u_sq) * Fp(z_u_sq) + Fp(z_u_sq);
+    let exceptional = den == Fp::zero();
+    let den_inv = fill_trace_fp_inverse(trace, if exceptional { &Fp::one() } else { &den }, start_row, end_row, start_col + SSWU_TV1_DEN_INV_OFFSET).unwrap();
+    let x1_mul = fill_trace_fp_mul_mod(trace, &SSWU_G1_MINUS_B_OVER_A.0, &(Fp::one() + den_inv).0, start_row, end_row, start_col + SSWU_X1_MUL_OFFSET);
+    let x1 = if exceptional {
+        SSWU_G1_B_OVER_ZA
+    } else {
+        Fp(x1_mul)
+    };
+    fill_trace_sswu_gx(trace, &x1, start_row, end_row, start_col + SSWU_GX1_OFFSET);
+    let x2 = Fp(fill_trace_fp_mul_mod(trace, &z_u_sq, &x1.0, start_row, end_row, start_col + SSWU_X2_OFFSET));
+    fill_trace_sswu_gx(trace, &x2, start_row, end_row, start_col + SSWU_GX2_OFFSET);
+    let gx1 = x1 * x1 * x1 + SSWU_G1_A * x1 + SSWU_G1_B;
+    let gx2 = x2 * x2 * x2 + SSWU_G1_A * x2 + SSWU_G1_B;
+    let root1 = fill_trace_fp_sqrt(trace, &gx1, start_row, end_row, start_col + SSWU_SQRT1_OFFSET);
+    let root2 = fill_trace_fp_sqrt(trace, &gx2, start_row, end_row, start_col + SSWU_SQRT2_OFFSET);
+    let (x, y0) = match root1 {
+        Some(root) => (x1, root),
+        None => (x2, root2.unwrap()),
+    };
+    let flip = (u.0[0] & 1) != (y0.0[0] & 1);
+    let y = if flip {
+        Fp::zero() - y0
+    } else {
+        y0
+    };
+    for row in start_row..end_row + 1 {
+        trace[row][start_col + SSWU_CHECK_OFFSET] = F::ONE;
+        assign_u32_in_series(trace, row, start_col + SSWU_INPUT_OFFSET, &u.0);
+        trace[row][start_col + SSWU_EXCEPTIONAL_OFFSET] = F::from_bool(exceptional);
+        fill_trace_addition_fp_mod(trace, &z_u_sq_sq, &z_u_sq, row, start_col + SSWU_TV1_DEN_OFFSET);
+        fill_trace_addition_fp_mod(trace, &Fp::one().0, &den_inv.0, row, start_col + SSWU_ONE_TV1_OFFSET);
+        assign_u32_in_series(trace, row, start_col + SSWU_X1_OFFSET, &x1.0);
+        assign_u32_in_series(trace, row, start_col + SSWU_Y0_OFFSET, &y0.0);
+        assign_u32_in_series(trace, row, start_col + SSWU_U_BITS_OFFSET, &get_bits_as_array(u.0[0]));
+        assign_u32_in_series(trace, row, start_col + SSWU_Y0_BITS_OFFSET, &get_bits_as_array(y0.0[0]));
+        trace[row][start_col + SSWU_FLIP_OFFSET] = F::from_bool(flip);
+        fill_trace_subtraction_fp_mod(trace, &Fp::zero().0, &y0.0, row, start_col + SSWU_NEG_Y0_OFFSET);
+        assign_u32_in_series(trace, row, start_col + SSWU_X_OFFSET, &x.0);
+        assign_u32_in_series(trace, row, start_col + SSWU_Y_OFFSET, &y.0);
+    }
+    G1Affine {
+        x,
+        y,
+        infinity: false,
+    }
+}
+
+/// Constraints the simplified SWU map.
+///
+/// Wherever `SSWU_CHECK_OFFSET` is set, constraints the inputs of every operation with u, the constants or the result of a previous operation.
+/// The exceptional flag is boolean and can only be set if `Z^2 * u^4 + Z * u^2` is zero, otherwise the denominator is constrained with the input of the fp inverse. x1 is constrained to be `B'/(Z * A')` in the exceptional case and `-B'/A' * (1 + tv1)` otherwise.
+/// If g(x1) is a square, x is constrained to be x1 and the root to be the square root of g(x1). Otherwise g(x2) is constrained to be a square, x to be x2 and the root to be the square root of g(x2).
+/// The first limbs of u and the root are constrained with their bit decompositions, and y is constrained to be the root, or its negation if the first bits differ.
+/// Then constraints the fp multiplication, addition, subtraction, inverse and square root operations.
+pub fn add_sswu_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+    let check = bit_selector_val * local_values[start_col + SSWU_CHECK_OFFSET];
+    let exceptional = local_values[start_col + SSWU_EXCEPTIONAL_OFFSET];
+    let is_square1 = local_values[start_col + SSWU_SQRT1_OFFSET + FP_SQRT_IS_SQUARE_OFFSET];
+    let is_square2 = local_values[start_col + SSWU_SQRT2_OFFSET + FP_SQRT_IS_SQUARE_OFFSET];
+    let flip = local_values[start_col + SSWU_FLIP_OFFSET];
+
+    for (input_col, value_col) in sswu_input_links(start_col) {
+        for i in 0..12 {
+            yield_constr.constraint(
+                check *
+                (local_values[input_col + i] - local_values[value_col + i])
+            );
+        }
+    }
+    for (input_col, value) in sswu_constant_links(start_col) {
+        for i in 0..12 {
+            yield_constr.constraint(
+                check *
+                (local_values[input_col + i] - FE::from_canonical_u32(value.0[i]))
+            );
+        }
+    }
+
+    yield_constr.constraint(check * exceptional * (P::ONES - exceptional));
+    yield_constr.constraint(check * (P::ONES - is_square1) * (P::ONES - is_square2));
+    let den = fp_add_mod_res(start_col + SSWU_TV1_DEN_OFFSET);
+    let x1_mul = fp_mul_mod_res(start_col + SSWU_X1_MUL_OFFSET);
+    let x2 = fp_mul_mod_res(start_col + SSWU_X2_OFFSET);
+    let neg_y0 = fp_sub_mod_res(start_col + SSWU_NEG_Y0_OFFSET);
+    for i in 0..12 {
+        let x1 = local_values[start_col + SSWU_X1_OFFSET + i];
+        let x = local_values[start_col + SSWU_X_OFFSET + i];
+        let y0 = local_values[start_col + SSWU_Y0_OFFSET + i];
+        yield_constr.constraint(check * exceptional * local_values[den + i]);
+        yield_constr.constraint(
+            check * (P::ONES - exceptional) *
+            (local_values[start_col + SSWU_TV1_DEN_INV_OFFSET + FP_INV_INPUT_OFFSET + i] - local_values[den + i])
+        );
+        yield_constr.constraint(check * (P::ONES - exceptional) * (x1 - local_values[x1_mul + i]));
+        yield_constr.constraint(check * exceptional * (x1 - FE::from_canonical_u32(SSWU_G1_B_OVER_ZA.0[i])));
+        yield_constr.constraint(check * is_square1 * (x - x1));
+        yield_constr.constraint(check * (P::ONES - is_square1) * (x - local_values[x2 + i]));
+        yield_constr.constraint(check * is_square1 * (y0 - local_values[start_col + SSWU_SQRT1_OFFSET + FP_SQRT_ROOT_OFFSET + i]));
+        yield_constr.constraint(check * (P::ONES - is_square1) * (y0 - local_values[start_col + SSWU_SQRT2_OFFSET + FP_SQRT_ROOT_OFFSET + i]));
+        yield_constr.constraint(
+            check *
+            (local_values[start_col + SSWU_Y_OFFSET + i] - y0 - flip * (local_values[neg_y0 + i] - y0))
+        );
+    }
+
+    for (bits_col, limb_col) in [(SSWU_U_BITS_OFFSET, SSWU_INPUT_OFFSET), (SSWU_Y0_BITS_OFFSET, SSWU_Y0_OFFSET)] {
+        let mut limb = P::ZEROS;
+        for i in 0..32 {
+            let bit = local_values[start_col + bits_col + i];
+            yield_constr.constraint(check * bit * (P::ONES - bit));
+            limb = limb + bit * FE::from_canonical_u64(1 << i);
+        }
+        yield_constr.constraint(check * (limb - local_values[start_col + limb_col]));
+    }
+    let sign_u = local_values[start_col + SSWU_U_BITS_OFFSET];
+    let sign_y0 = local_values[start_col + SSWU_Y0_BITS_OFFSET];
+    yield_constr.constraint(
+        check *
+        (flip - sign_u - sign_y0 + sign_u * sign_y0 * FE::TWO)
+    );
+
+    for col in [SSWU_U_SQ_OFFSET, SSWU_Z_U_SQ_OFFSET, SSWU_Z_U_SQ_SQ_OFFSET, SSWU_X1_MUL_OFFSET, SSWU_X2_OFFSET] {
+        add_fp_mul_mod_constraints(local_values, next_values, yield_constr, start_col + col, bit_selector);
+    }
+    for gx in [SSWU_GX1_OFFSET, SSWU_GX2_OFFSET] {
+        for col in [SSWU_GX_X_SQ_OFFSET, SSWU_GX_X_CU_OFFSET, SSWU_GX_AX_OFFSET] {
+            add_fp_mul_mod_constraints(local_values, next_values, yield_constr, start_col + gx + col, bit_selector);
+        }
+        for col in [SSWU_GX_SUM_OFFSET, SSWU_GX_RES_OFFSET] {
+            add_addition_fp_mod_constraints(local_values, yield_constr, start_col + gx + col, bit_selector);
+        }
+    }
+    for col in [SSWU_TV1_DEN_OFFSET, SSWU_ONE_TV1_OFFSET] {
+        add_addition_fp_mod_constraints(local_values, yield_constr, start_col + col, bit_selector);
+    }
+    add_subtraction_fp_mod_constraints(local_values, yield_constr, start_col + SSWU_NEG_Y0_OFFSET, bit_selector);
+    add_fp_inverse_constraints(local_values, next_values, yield_constr, start_col + SSWU_TV1_DEN_INV_OFFSET, bit_selector);
+    for col in [SSWU_SQRT1_OFFSET, SSWU_SQRT2_OFFSET] {
+        add_fp_sqrt_constraints(local_values, next_values, yield_constr, start_col + col, bit_selector);
+    }
+}
+
+pub fn add_sswu_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let one = builder.constant_extension(F::Extension::ONE);
+    let bit_selector_val = bit_selector.unwrap_or(one);
+    let check = builder.mul_extension(bit_selector_val, local_values[start_col + SSWU_CHECK_OFFSET]);
+    let exceptional = local_values[start_col + SSWU_EXCEPTIONAL_OFFSET];
+    let is_square1 = local_values[start_col + SSWU_SQRT1_OFFSET + FP_SQRT_IS_SQUARE_OFFSET];
+    let is_square2 = local_values[start_col + SSWU_SQRT2_OFFSET + FP_SQRT_IS_SQUARE_OFFSET];
+    let flip = local_values[start_col + SSWU_FLIP_OFFSET];
+    let not_exceptional = builder.sub_extension(one, exceptional);
+    let not_square1 = builder.sub_extension(one, is_square1);
+    let not_square2 = builder.sub_extension(one, is_square2);
+
+    for (input_col, value_col) in sswu_input_links(start_col) {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[input_col + i], local_values[value_col + i]);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+    for (input_col, value) in sswu_constant_links(start_col) {
+        for i in 0..12 {
+            let value = builder.constant_extension(F::Extension::from_canonical_u32(value.0[i]));
+            let c = builder.sub_extension(local_values[input_col + i], value);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+
+    let check_exceptional = builder.mul_extension(check, exceptional);
+    let check_not_exceptional = builder.mul_extension(check, not_exceptional);
+    let check_square1 = builder.mul_extension(check, is_square1);
+    let check_not_square1 = builder.mul_extension(check, not_square1);
+    let c = builder.mul_extension(check_exceptional, not_exceptional);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(check_not_square1, not_square2);
+    yield_constr.constraint(builder, c);
+    let den = fp_add_mod_res(start_col + SSWU_TV1_DEN_OFFSET);
+    let x1_mul = fp_mul_mod_res(start_col + SSWU_X1_MUL_OFFSET);
+    let x2 = fp_mul_mod_res(start_col + SSWU_X2_OFFSET);
+    let neg_y0 = fp_sub_mod_res(start_col + SSWU_NEG_Y0_OFFSET);
+    for i in 0..12 {
+        let x1 = local_values[start_col + SSWU_X1_OFFSET + i];
+        let x = local_values[start_col + SSWU_X_OFFSET + i];
+        let y0 = local_values[start_col + SSWU_Y0_OFFSET + i];
+        let c = builder.mul_extension(check_exceptional, local_values[den + i]);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(local_values[start_col + SSWU_TV1_DEN_INV_OFFSET + FP_INV_INPUT_OFFSET + i], local_values[den + i]);
+        let c = builder.mul_extension(check_not_exceptional, c);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(x1, local_values[x1_mul + i]);
+        let c = builder.mul_extension(check_not_exceptional, c);
+        yield_constr.constraint(builder, c);
+        let b_over_za = builder.constant_extension(F::Extension::from_canonical_u32(SSWU_G1_B_OVER_ZA.0[i]));
+        let c = builder.sub_extension(x1, b_over_za);
+        let c = builder.mul_extension(check_exceptional, c);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(x, x1);
+        let c = builder.mul_extension(check_square1, c);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(x, local_values[x2 + i]);
+        let c = builder.mul_extension(check_not_square1, c);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(y0, local_values[start_col + SSWU_SQRT1_OFFSET + FP_SQRT_ROOT_OFFSET + i]);
+        let c = builder.mul_extension(check_square1, c);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(y0, local_values[start_col + SSWU_SQRT2_OFFSET + FP_SQRT_ROOT_OFFSET + i]);
+        let c = builder.mul_extension(check_not_square1, c);
+        yield_constr.constraint(builder, c);
+        let c = builder.sub_extension(local_values[neg_y0 + i], y0);
+        let c = builder.mul_extension(flip, c);
+        let c = builder.add_extension(y0, c);
+        let c = builder.sub_extension(local_values[start_col + SSWU_Y_OFFSET + i], c);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+    }
+
+    for (bits_col, limb_col) in [(SSWU_U_BITS_OFFSET, SSWU_INPUT_OFFSET), (SSWU_Y0_BITS_OFFSET, SSWU_Y0_OFFSET)] {
+        let mut limb = builder.constant_extension(F::Extension::ZERO);
+        for i in 0..32 {
+            let bit = local_values[start_col + bits_col + i];
+            let c = builder.sub_extension(one, bit);
+            let c = builder.mul_many_extension([check, bit, c]);
+            yield_constr.constraint(builder, c);
+            let pow = builder.constant_extension(F::Extension::from_canonical_u64(1 << i));
+            limb = builder.mul_add_extension(bit, pow, limb);
+        }
+        let c = builder.sub_extension(limb, local_values[start_col + limb_col]);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+    }
+    let sign_u = local_values[start_col + SSWU_U_BITS_OFFSET];
+    let sign_y0 = local_values[start_col + SSWU_Y0_BITS_OFFSET];
+    let two = builder.constant_extension(F::Extension::TWO);
+    let c = builder.mul_many_extension([sign_u, sign_y0, two]);
+    let c = builder.add_extension(flip, c);
+    let c = builder.sub_extension(c, sign_u);
+    let c = builder.sub_extension(c, sign_y0);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+
+    for col in [SSWU_U_SQ_OFFSET, SSWU_Z_U_SQ_OFFSET, SSWU_Z_U_SQ_SQ_OFFSET, SSWU_X1_MUL_OFFSET, SSWU_X2_OFFSET] {
+        add_fp_mul_mod_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + col, bit_selector);
+    }
+    for gx in [SSWU_GX1_OFFSET, SSWU_GX2_OFFSET] {
+        for col in [SSWU_GX_X_SQ_OFFSET, SSWU_GX_X_CU_OFFSET, SSWU_GX_AX_OFFSET] {
+            add_fp_mul_mod_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + gx + col, bit_selector);
+        }
+        for col in [SSWU_GX_SUM_OFFSET, SSWU_GX_RES_OFFSET] {
+            add_addition_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + gx + col, bit_selector);
+        }
+    }
+    for col in [SSWU_TV1_DEN_OFFSET, SSWU_ONE_TV1_OFFSET] {
+        add_addition_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + col, bit_selector);
+    }
+    add_subtraction_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + SSWU_NEG_Y0_OFFSET, bit_selector);
+    add_fp_inverse_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + SSWU_TV1_DEN_INV_OFFSET, bit_selector);
+    for col in [SSWU_SQRT1_OFFSET, SSWU_SQRT2_OFFSET] {
+        add_fp_sqrt_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + col, bit_selector);
+    }
+}
+
+/// Stark proving that (x, y) is the simplified SWU map of u on the isogenous curve E'.
+#[derive(Clone, Copy)]
+pub struct MapToCurveStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> MapToCurveStark<F, D> {
+    /// Rows used by one SSWU map, 12. The stark needs a power of two number of rows of at least this many.
+    pub fn required_rows() -> usize {
+        12
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(u: &Fp, res: &G1Affine) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for i in 0..12 {
+            public_inputs[PIS_INPUT_OFFSET + i] = F::from_canonical_u32(u.0[i]);
+            public_inputs[PIS_X_OFFSET + i] = F::from_canonical_u32(res.x.0[i]);
+            public_inputs[PIS_Y_OFFSET + i] = F::from_canonical_u32(res.y.0[i]);
+        }
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, u: &Fp) -> Vec<[F; TOTAL_COLUMNS]> {
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        fill_trace_sswu(&mut trace, u, 0, 11, 0);
+        trace
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for MapToCurveStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[SSWU_CHECK_OFFSET] - P::ONES);
+        for (col, pis) in [(SSWU_INPUT_OFFSET, PIS_INPUT_OFFSET), (SSWU_X_OFFSET, PIS_X_OFFSET), (SSWU_Y_OFFSET, PIS_Y_OFFSET)] {
+            for i in 0..12 {
+                yield_constr.constraint(
+                    local_values[SSWU_CHECK_OFFSET] *
+                    (local_values[col + i] - public_inputs[pis + i])
+                );
+            }
+        }
+        add_sswu_constraints(local_values, next_values, yield_constr, 0, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[SSWU_CHECK_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for (col, pis) in [(SSWU_INPUT_OFFSET, PIS_INPUT_OFFSET), (SSWU_X_OFFSET, PIS_X_OFFSET), (SSWU_Y_OFFSET, PIS_Y_OFFSET)] {
+            for i in 0..12 {
+                let c = builder.sub_extension(local_values[col + i], public_inputs[pis + i]);
+                let c = builder.mul_extension(local_values[SSWU_CHECK_OFFSET], c);
+                yield_constr.constraint(builder, c);
+            }
+        }
+        add_sswu_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{map_to_curve_g1, Fp, G1Affine};
+
+    use super::{MapToCurveStark, PUBLIC_INPUTS};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = MapToCurveStark<F, D>;
+
+    fn prove_and_verify_map_to_curve(u: &Fp) -> G1Affine {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(16);
+        let res = map_to_curve_g1(*u);
+        let public_inputs = S::public_inputs(u, &res);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(u);
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        res
+    }
+
+    #[test]
+    fn test_map_to_curve_rfc_vectors() {
+        // u[0] and u[1] of the RFC 9380 BLS12381G1_XMD:SHA-256_SSWU_RO_ test vector for msg "".
+        for u in [
+            "0ba14bd907ad64a016293ee7c2d276b8eae71f25a4b941eece7b0d89f17f75cb3ae5438a614fb61d6835ad59f29c564f",
+            "019b9bd7979f12657976de2884c7cce192b82c177c80e0ec604436a7f538d231552f0d96d9f7babe5fa3b19b3ff25ac9",
+        ] {
+            let u = Fp::get_fp_from_biguint(BigUint::parse_bytes(u.as_bytes(), 16).unwrap());
+            prove_and_verify_map_to_curve(&u);
+        }
+    }
+
+    #[test]
+    fn test_map_to_curve_exceptional() {
+        prove_and_verify_map_to_curve(&Fp::zero());
+    }
+}
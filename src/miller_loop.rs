@@ -16,8 +16,10 @@ use starky::{
     stark::Stark,
 };
 
+use anyhow::Result;
+
 use crate::native::{
-    Fp, Fp2, get_bls_12_381_parameter, Fp6, Fp12,
+    Fp, Fp2, get_bls_12_381_parameter, Fp6, Fp12, G2Affine,
 };
 
 use crate::fp::*;
@@ -25,6 +27,12 @@ use crate::fp2::*;
 use crate::fp6::*;
 use crate::fp12::*;
 use crate::utils::*;
+use crate::pairing::pairing_precomp;
+
+/// Number of `ell_coeffs` of the miller loop, one per doubling or addition step.
+pub const MILLER_LOOP_ELL_COEFFS: usize = 68;
+/// Rows of the miller loop trace, 12 per step.
+pub const MILLER_LOOP_ROWS: usize = 12 * MILLER_LOOP_ELL_COEFFS;
 
 // Miller loop offsets
 /*
@@ -52,7 +60,7 @@ pub const BIT1_SELECTOR_OFFSET: usize = FIRST_ROW_SELECTOR_OFFSET + 1;
 pub const PX_OFFSET: usize = BIT1_SELECTOR_OFFSET + 1;
 pub const PY_OFFSET: usize = PX_OFFSET + 12;
 pub const ELL_COEFFS_INDEX_OFFEST: usize = PY_OFFSET + 12;
-pub const ELL_COEFFS_OFFSET: usize = ELL_COEFFS_INDEX_OFFEST + 68;
+pub const ELL_COEFFS_OFFSET: usize = ELL_COEFFS_INDEX_OFFEST + MILLER_LOOP_ELL_COEFFS;
 pub const F12_OFFSET: usize = ELL_COEFFS_OFFSET + 24*3;
 pub const O1_CALC_OFFSET: usize = F12_OFFSET + 24*3*2;
 pub const O4_CALC_OFFSET: usize = O1_CALC_OFFSET + FP2_FP_TOTAL_COLUMNS;
@@ -73,7 +81,7 @@ pub const COLUMNS: usize = TOTAL_COLUMNS;
 pub const PIS_PX_OFFSET: usize = 0;
 pub const PIS_PY_OFFSET: usize = PIS_PX_OFFSET + 12;
 pub const PIS_ELL_COEFFS_OFFSET: usize = PIS_PY_OFFSET + 12;
-pub const PIS_RES_OFFSET: usize = PIS_ELL_COEFFS_OFFSET + 68*24*3;
+pub const PIS_RES_OFFSET: usize = PIS_ELL_COEFFS_OFFSET + MILLER_LOOP_ELL_COEFFS*24*3;
 pub const PUBLIC_INPUTS: usize = PIS_RES_OFFSET + 24*3*2;
 
 // A (Fp) * B (Fp) => C (Fp)
@@ -83,11 +91,11 @@ pub struct MillerLoopStark<F: RichField + Extendable<D>, const D: usize> {
     _f: std::marker::PhantomData<F>,
 }
 
-/// Fills the trace of [miller_loop](super::native::miller_loop) function. Inputs are two 12 limbs and `ell_coeffs` array computed from `calc_pairing_precomp`. The values of Px and Py are filled across all rows in the trace. `FIRST_BIT_SELECTOR_OFFSET` is set 1 for the first loop computation. Sets the `ELL_COEFFS_INDEX` for the corresponding index. Sets the corresponding `ell_coeff` for the current row of computation. Fills the F12 trace, starting with 1 and then updates after each loop of computation. Fills trace for O1 and O4 calculations. Sets `FIRST_ROW_SELECTOR` to 1 for starting row of the operation. Fills the trace for multiplyBy014 caluclations and Fp12 multiplication calculations. Then fills the trace with miller_loop result, followed by conjugate computation for miller loop result.
+/// Fills the trace of [miller_loop](super::native::miller_loop) function. Inputs are two 12 limbs and `ell_coeffs` array computed from `calc_pairing_precomp`. The values of Px and Py are filled across all rows in the trace. `FIRST_BIT_SELECTOR_OFFSET` is set 1 for the first loop computation. Sets the `ELL_COEFFS_INDEX` for the corresponding index. Sets the corresponding `ell_coeff` for the current row of computation. Fills the F12 trace, starting with 1 and then updates after each loop of computation. Fills trace for O1 and O4 calculations. Sets `FIRST_ROW_SELECTOR` to 1 for starting row of the operation. Fills the trace for multiplyBy014 caluclations and Fp12 multiplication calculations. Then fills the trace with miller_loop result, followed by conjugate computation for miller loop result. Returns the miller_loop result.
 pub fn fill_trace_miller_loop<F: RichField + Extendable<D>,
     const D: usize,
     const C: usize,
->(trace: &mut Vec<[F; C]>, x: &Fp, y: &Fp, ell_coeffs: &[[Fp2; 3]], start_row: usize, end_row: usize, start_col: usize) {
+>(trace: &mut Vec<[F; C]>, x: &Fp, y: &Fp, ell_coeffs: &[[Fp2; 3]], start_row: usize, end_row: usize, start_col: usize) -> Fp12 {
     for row in start_row..end_row+1 {
         assign_u32_in_series(trace, row, start_col + PX_OFFSET, &x.0);
         assign_u32_in_series(trace, row, start_col + PY_OFFSET, &y.0);
@@ -143,10 +151,16 @@ pub fn fill_trace_miller_loop<F: RichField + Extendable<D>,
         fill_trace_negate_fp6(trace, &Fp6(f12.0[6..].try_into().unwrap()), row, start_col + RES_CONJUGATE_OFFSET);
     }
     // assert_eq!(i, 0);
+    f12
 }
 
 // Implement trace generator
 impl<F: RichField + Extendable<D>, const D: usize> MillerLoopStark<F, D> {
+    /// Rows used by the miller loop, `MILLER_LOOP_ROWS`. The stark needs a power of two number of rows of at least this many.
+    pub fn required_rows() -> usize {
+        MILLER_LOOP_ROWS
+    }
+
     pub fn new(num_rows: usize) -> Self {
         Self {
             num_rows,
@@ -154,10 +168,31 @@ impl<F: RichField + Extendable<D>, const D: usize> MillerLoopStark<F, D> {
         }
     }
 
-    pub fn generate_trace(&self, x: Fp, y: Fp, ell_coeffs: Vec<[Fp2; 3]>) -> Vec<[F; TOTAL_COLUMNS]> {
+    /// Lays out the public inputs for this stark. Px and Py are placed at `PIS_PX_OFFSET` and `PIS_PY_OFFSET`, the `ell_coeffs` of `q` computed by `calc_pairing_precomp` at `PIS_ELL_COEFFS_OFFSET` and the miller loop result at `PIS_RES_OFFSET`. Returns an error if `q` is the point at infinity or is not on the G2 curve.
+    pub fn public_inputs(x: &Fp, y: &Fp, q: &G2Affine, res: &Fp12) -> Result<Vec<F>> {
+        let ell_coeffs = pairing_precomp(q)?;
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for i in 0..12 {
+            public_inputs[PIS_PX_OFFSET + i] = F::from_canonical_u32(x.0[i]);
+            public_inputs[PIS_PY_OFFSET + i] = F::from_canonical_u32(y.0[i]);
+        }
+        for (j, coeff) in ell_coeffs.iter().enumerate() {
+            for (k, e) in coeff.iter().flat_map(|f2| f2.get_u32_slice().concat()).enumerate() {
+                public_inputs[PIS_ELL_COEFFS_OFFSET + j*24*3 + k] = F::from_canonical_u32(e);
+            }
+        }
+        for (i, e) in res.get_u32_slice().concat().iter().enumerate() {
+            public_inputs[PIS_RES_OFFSET + i] = F::from_canonical_u32(*e);
+        }
+        Ok(public_inputs)
+    }
+
+    /// Fills the miller loop trace of (Px, Py) and the `ell_coeffs` of `q`. Returns an error for the same `q` as [MillerLoopStark::public_inputs].
+    pub fn generate_trace(&self, x: Fp, y: Fp, q: &G2Affine) -> Result<Vec<[F; TOTAL_COLUMNS]>> {
+        let ell_coeffs = pairing_precomp(q)?;
         let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
         fill_trace_miller_loop(&mut trace, &x, &y, &ell_coeffs, 0, self.num_rows-1, 0);
-        trace
+        Ok(trace)
         // let start_col = 0;
         // for row in 0..self.num_rows-1 {
         //     let local_values = self.trace[row];
@@ -714,3 +749,64 @@ impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for MillerLoopSta
         3
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ark_bls12_381::{Bls12_381, G1Affine as ArkG1Affine, G2Affine as ArkG2Affine};
+    use ark_ec::{pairing::Pairing, AffineRepr};
+    use plonky2::{field::types::PrimeField64, plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{Fp, Fp12, G2Affine};
+    use crate::native_reference_tests::{fp12_from_ark, g1_from_ark, g2_from_ark};
+
+    use super::{MillerLoopStark, MILLER_LOOP_RES_OFFSET, PUBLIC_INPUTS};
+
+    #[test]
+    fn test_miller_loop_prove_verify() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = MillerLoopStark<F, D>;
+
+        // e(G1, G2) computed by arkworks, which the miller loop result of the trace gives after the final exponentiation.
+        let (g1, g2) = (ArkG1Affine::generator(), ArkG2Affine::generator());
+        let expected = fp12_from_ark(&Bls12_381::pairing(g1, g2).0);
+        let (p, q) = (g1_from_ark(&g1), g2_from_ark(&g2));
+
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(1024);
+        let trace = stark.generate_trace(p.x, p.y, &q).unwrap();
+        let res = Fp12(core::array::from_fn(|i| {
+            Fp(core::array::from_fn(|j| trace[0][MILLER_LOOP_RES_OFFSET + i*12 + j].to_canonical_u64() as u32))
+        }));
+        assert_eq!(res.final_exponentiate(), expected);
+        let public_inputs = S::public_inputs(&p.x, &p.y, &q, &res).unwrap();
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+    }
+
+    #[test]
+    fn test_miller_loop_invalid_g2_point() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = MillerLoopStark<F, D>;
+
+        let p = g1_from_ark(&ArkG1Affine::generator());
+        let q = g2_from_ark(&ArkG2Affine::generator());
+        let off_curve = G2Affine { x: q.x, y: q.y + q.y, infinity: false };
+        for q in [G2Affine::identity(), off_curve] {
+            assert!(S::public_inputs(&p.x, &p.y, &q, &Fp12::one()).is_err());
+            assert!(S::new(1024).generate_trace(p.x, p.y, &q).is_err());
+        }
+    }
+}
@@ -0,0 +1,399 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use crate::native::{Fp, Fp2, Fp12};
+
+use crate::fp::*;
+use crate::fp6::*;
+use crate::fp12::*;
+use crate::final_exponentiate::*;
+use crate::miller_loop::*;
+
+/// Maximum number of pairs whose pairings can be multiplied by the stark.
+pub const MULTI_MILLER_LOOP_MAX_PAIRS: usize = 4;
+
+/*
+    These offsets are for the product of the pairings of up to MULTI_MILLER_LOOP_MAX_PAIRS pairs, i.e. final_exponentiate(miller_loop(P1, Q1) * ... * miller_loop(Pn, Qn)). A single final exponentiate is done for the product of the miller loops. This stark needs 8192 rows.
+    MML_FINAL_EXP_OFFSET -> offset where the final exponentiate trace is filled.
+    MML_MILLER_LOOP_OFFSET -> offset where the miller loop traces are filled, the k-th one at MML_MILLER_LOOP_OFFSET + k*MILLER_LOOP_TOTAL. The miller loops of the unused pairs are left empty.
+    MML_FP12_MUL_OFFSET -> offset where the miller loop results are multiplied. Each multiplication takes 12 rows, the k-th multiplication is filled in rows 12*k..12*k+11 and multiplies the product of the previous multiplication (the first miller loop result for k = 0) with the k+1-th miller loop result, or with one if the k+1-th pair is unused. The last product is the input of final exponentiate.
+    MML_MUL_INDEX_OFFSET -> offset which stores which multiplication the trace is currently on. Total MULTI_MILLER_LOOP_MAX_PAIRS-1 selectors, one for each multiplication.
+*/
+pub const MML_FINAL_EXP_OFFSET: usize = 0;
+pub const MML_MILLER_LOOP_OFFSET: usize = MML_FINAL_EXP_OFFSET + FINAL_EXP_TOTAL_COLUMNS;
+pub const MML_FP12_MUL_OFFSET: usize = MML_MILLER_LOOP_OFFSET + MILLER_LOOP_TOTAL*MULTI_MILLER_LOOP_MAX_PAIRS;
+pub const MML_MUL_INDEX_OFFSET: usize = MML_FP12_MUL_OFFSET + FP12_MUL_TOTAL_COLUMNS;
+pub const MML_TOTAL_COLUMNS: usize = MML_MUL_INDEX_OFFSET + MULTI_MILLER_LOOP_MAX_PAIRS - 1;
+
+pub const TOTAL_COLUMNS: usize = MML_TOTAL_COLUMNS;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the pairs, each as the G1 point (Px, Py) followed by the `ell_coeffs` of the G2 point resulted from calc_pairing_precomp, then the product of the pairings. The unused pair slots are set to zero.
+*/
+pub const PIS_PAIRS_OFFSET: usize = 0;
+pub const PIS_PAIR_SIZE: usize = 12*2 + 68*24*3;
+pub const PIS_RES_OFFSET: usize = PIS_PAIRS_OFFSET + PIS_PAIR_SIZE*MULTI_MILLER_LOOP_MAX_PAIRS;
+pub const PUBLIC_INPUTS: usize = PIS_RES_OFFSET + 24*3*2;
+
+/// Returns the column of the i-th limb of the fp12 multiplication result.
+fn fp12_mul_res(start_col: usize, i: usize) -> usize {
+    let (k, j, l) = (i / (24*3), (i % (24*3)) / 12, i % 12);
+    let x_y = if k == 0 {
+        FP12_MUL_X_CALC_OFFSET + FP6_ADDITION_TOTAL
+    } else {
+        FP12_MUL_Y_CALC_OFFSET + FP6_ADDITION_TOTAL + FP6_SUBTRACTION_TOTAL
+    };
+    start_col + x_y + (FP_SINGLE_REDUCE_TOTAL + RANGE_CHECK_TOTAL)*j + FP_SINGLE_REDUCED_OFFSET + l
+}
+
+/// Returns the offset of the k-th miller loop trace.
+fn miller_loop_offset(k: usize) -> usize {
+    MML_MILLER_LOOP_OFFSET + k*MILLER_LOOP_TOTAL
+}
+
+#[derive(Clone, Copy)]
+pub struct MultiMillerLoopStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    num_pairs: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> MultiMillerLoopStark<F, D> {
+    /// Rows used by the product of pairings: the miller loops and their products, side by side with final exponentiate from the first row. Does not depend on the number of pairs.
+    pub fn required_rows() -> usize {
+        MILLER_LOOP_ROWS.max(12 * (MULTI_MILLER_LOOP_MAX_PAIRS - 1)).max(crate::final_exponentiate::TOTAL_ROW)
+    }
+
+    /// Creates the stark for the product of the pairings of `num_pairs` pairs. Panics if `num_pairs` is zero or more than `MULTI_MILLER_LOOP_MAX_PAIRS`.
+    pub fn new(num_rows: usize, num_pairs: usize) -> Self {
+        assert!(num_pairs > 0 && num_pairs <= MULTI_MILLER_LOOP_MAX_PAIRS);
+        Self {
+            num_rows,
+            num_pairs,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    /// Lays out the public inputs for this stark. Each pair is given as (Px, Py, `ell_coeffs`), with the `ell_coeffs` computed by `calc_pairing_precomp`. The pairs are padded with zeros up to `MULTI_MILLER_LOOP_MAX_PAIRS`, followed by the product of the pairings at `PIS_RES_OFFSET`.
+    pub fn public_inputs(pairs: &[(Fp, Fp, Vec<[Fp2; 3]>)], res: &Fp12) -> Vec<F> {
+        assert!(!pairs.is_empty() && pairs.len() <= MULTI_MILLER_LOOP_MAX_PAIRS);
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for (n, (x, y, ell_coeffs)) in pairs.iter().enumerate() {
+            assert_eq!(ell_coeffs.len(), 68);
+            let offset = PIS_PAIRS_OFFSET + n*PIS_PAIR_SIZE;
+            for i in 0..12 {
+                public_inputs[offset + i] = F::from_canonical_u32(x.0[i]);
+                public_inputs[offset + 12 + i] = F::from_canonical_u32(y.0[i]);
+            }
+            for (j, coeff) in ell_coeffs.iter().enumerate() {
+                for (k, e) in coeff.iter().flat_map(|f2| f2.get_u32_slice().concat()).enumerate() {
+                    public_inputs[offset + 24 + j*24*3 + k] = F::from_canonical_u32(e);
+                }
+            }
+        }
+        for (i, e) in res.get_u32_slice().concat().iter().enumerate() {
+            public_inputs[PIS_RES_OFFSET + i] = F::from_canonical_u32(*e);
+        }
+        public_inputs
+    }
+
+    /// Fills the trace for the product of the pairings. First fills the miller loop trace of each pair, then multiplies the miller loop results with one fp12 multiplication per 12 rows, and fills the final exponentiate trace with the product as input.
+    pub fn generate_trace(&self, pairs: &[(Fp, Fp, Vec<[Fp2; 3]>)]) -> Vec<[F; TOTAL_COLUMNS]> {
+        assert_eq!(pairs.len(), self.num_pairs);
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        let f12s = pairs.iter().enumerate().map(|(k, (x, y, ell_coeffs))|
+            fill_trace_miller_loop(&mut trace, x, y, ell_coeffs, 0, self.num_rows-1, miller_loop_offset(k))
+        ).collect::<Vec<Fp12>>();
+        let mut product = f12s[0];
+        for k in 0..MULTI_MILLER_LOOP_MAX_PAIRS-1 {
+            let y = f12s.get(k+1).copied().unwrap_or(Fp12::one());
+            fill_trace_fp12_multiplication(&mut trace, &product, &y, k*12, k*12 + 11, MML_FP12_MUL_OFFSET);
+            product = product * y;
+            for row in k*12..k*12 + 12 {
+                trace[row][MML_MUL_INDEX_OFFSET + k] = F::ONE;
+            }
+        }
+        fill_trace_final_exponentiate(&mut trace, &product, MML_FINAL_EXP_OFFSET).unwrap();
+        trace
+    }
+}
+
+/*
+    The constraints of this stark are as follows:
+    * Constraints the multiplication index selectors to be boolean, the first one to be set in the first row, each one to stay the same within its multiplication and to be followed by the next one. The fp12 multiplication selector is constrained to be set at the start of each multiplication.
+    * Constraints the inputs of the first multiplication to be the first two miller loop results, and the first input of each following multiplication to be the result of the previous one. The second input of each multiplication is the next miller loop result, or one if that pair is unused.
+    * Constraints the result of the last multiplication to be the final exponentiate input.
+    * Constraints Px, Py and the `ell_coeffs` of the miller loops of the used pairs with the public inputs, and the miller loop results to be same across all rows.
+    * Constraints the final exponentiate output (T31) to be the product in the public inputs, in the last row.
+    * Constraints the miller loop, fp12 multiplication and final exponentiate computations.
+*/
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for MultiMillerLoopStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        // Miller loops
+        for k in 0..self.num_pairs {
+            let offset = miller_loop_offset(k);
+            let pis_offset = PIS_PAIRS_OFFSET + k*PIS_PAIR_SIZE;
+            for i in 0..12 {
+                yield_constr.constraint(local_values[offset + PX_OFFSET + i] - public_inputs[pis_offset + i]);
+                yield_constr.constraint(local_values[offset + PY_OFFSET + i] - public_inputs[pis_offset + 12 + i]);
+            }
+            for i in 0..68 {
+                for j in 0..24*3 {
+                    yield_constr.constraint(
+                        local_values[offset + ELL_COEFFS_INDEX_OFFEST + i] *
+                        (local_values[offset + ELL_COEFFS_OFFSET + j] - public_inputs[pis_offset + 24 + i*24*3 + j])
+                    );
+                }
+            }
+            for i in 0..24*3*2 {
+                yield_constr.constraint_transition(
+                    local_values[offset + MILLER_LOOP_RES_OFFSET + i] - next_values[offset + MILLER_LOOP_RES_OFFSET + i]
+                );
+            }
+            add_miller_loop_constraints(local_values, next_values, yield_constr, offset, None);
+        }
+
+        // Product of the miller loop results
+        let last = MULTI_MILLER_LOOP_MAX_PAIRS - 2;
+        let mul_selector = local_values[MML_FP12_MUL_OFFSET + FP12_MUL_SELECTOR_OFFSET];
+        for k in 0..MULTI_MILLER_LOOP_MAX_PAIRS-1 {
+            let index = local_values[MML_MUL_INDEX_OFFSET + k];
+            yield_constr.constraint(index * (P::ONES - index));
+            yield_constr.constraint_transition(mul_selector * (next_values[MML_MUL_INDEX_OFFSET + k] - index));
+            for i in 0..24*3*2 {
+                if k == 0 {
+                    yield_constr.constraint(
+                        index *
+                        (local_values[MML_FP12_MUL_OFFSET + FP12_MUL_X_INPUT_OFFSET + i] - local_values[miller_loop_offset(0) + MILLER_LOOP_RES_OFFSET + i])
+                    );
+                }
+                let y = if k + 1 < self.num_pairs {
+                    local_values[miller_loop_offset(k+1) + MILLER_LOOP_RES_OFFSET + i]
+                } else if i == 0 {
+                    P::ONES
+                } else {
+                    P::ZEROS
+                };
+                yield_constr.constraint(index * (local_values[MML_FP12_MUL_OFFSET + FP12_MUL_Y_INPUT_OFFSET + i] - y));
+            }
+            if k < last {
+                let next_index = next_values[MML_MUL_INDEX_OFFSET + k + 1];
+                yield_constr.constraint_transition(index * (P::ONES - mul_selector) * (next_index - P::ONES));
+                yield_constr.constraint_transition(
+                    index * next_index * (next_values[MML_FP12_MUL_OFFSET + FP12_MUL_SELECTOR_OFFSET] - P::ONES)
+                );
+                for i in 0..24*3*2 {
+                    yield_constr.constraint_transition(
+                        index * next_index *
+                        (next_values[MML_FP12_MUL_OFFSET + FP12_MUL_X_INPUT_OFFSET + i] - local_values[fp12_mul_res(MML_FP12_MUL_OFFSET, i)])
+                    );
+                }
+            }
+        }
+        yield_constr.constraint_first_row(local_values[MML_MUL_INDEX_OFFSET] - P::ONES);
+        yield_constr.constraint_first_row(mul_selector - P::ONES);
+        let last_index = local_values[MML_MUL_INDEX_OFFSET + last];
+        for i in 0..24*3*2 {
+            yield_constr.constraint(
+                last_index *
+                (local_values[fp12_mul_res(MML_FP12_MUL_OFFSET, i)] - local_values[MML_FINAL_EXP_OFFSET + FINAL_EXP_INPUT_OFFSET + i])
+            );
+        }
+
+        // Final exponentiate
+        for i in 0..24*3*2 {
+            yield_constr.constraint_last_row(
+                local_values[MML_FINAL_EXP_OFFSET + FINAL_EXP_T31_OFFSET + i] - public_inputs[PIS_RES_OFFSET + i]
+            );
+        }
+
+        add_fp12_multiplication_constraints(local_values, next_values, yield_constr, MML_FP12_MUL_OFFSET, None);
+        add_final_exponentiate_constraints(local_values, next_values, yield_constr, self.num_rows, MML_FINAL_EXP_OFFSET);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let zero = builder.constant_extension(F::Extension::ZERO);
+
+        // Miller loops
+        for k in 0..self.num_pairs {
+            let offset = miller_loop_offset(k);
+            let pis_offset = PIS_PAIRS_OFFSET + k*PIS_PAIR_SIZE;
+            for i in 0..12 {
+                let c = builder.sub_extension(local_values[offset + PX_OFFSET + i], public_inputs[pis_offset + i]);
+                yield_constr.constraint(builder, c);
+                let c = builder.sub_extension(local_values[offset + PY_OFFSET + i], public_inputs[pis_offset + 12 + i]);
+                yield_constr.constraint(builder, c);
+            }
+            for i in 0..68 {
+                for j in 0..24*3 {
+                    let c = builder.sub_extension(local_values[offset + ELL_COEFFS_OFFSET + j], public_inputs[pis_offset + 24 + i*24*3 + j]);
+                    let c = builder.mul_extension(local_values[offset + ELL_COEFFS_INDEX_OFFEST + i], c);
+                    yield_constr.constraint(builder, c);
+                }
+            }
+            for i in 0..24*3*2 {
+                let c = builder.sub_extension(local_values[offset + MILLER_LOOP_RES_OFFSET + i], next_values[offset + MILLER_LOOP_RES_OFFSET + i]);
+                yield_constr.constraint_transition(builder, c);
+            }
+            add_miller_loop_constraints_ext_circuit(builder, yield_constr, local_values, next_values, offset, None);
+        }
+
+        // Product of the miller loop results
+        let last = MULTI_MILLER_LOOP_MAX_PAIRS - 2;
+        let mul_selector = local_values[MML_FP12_MUL_OFFSET + FP12_MUL_SELECTOR_OFFSET];
+        for k in 0..MULTI_MILLER_LOOP_MAX_PAIRS-1 {
+            let index = local_values[MML_MUL_INDEX_OFFSET + k];
+            let c = builder.sub_extension(one, index);
+            let c = builder.mul_extension(index, c);
+            yield_constr.constraint(builder, c);
+            let c = builder.sub_extension(next_values[MML_MUL_INDEX_OFFSET + k], index);
+            let c = builder.mul_extension(mul_selector, c);
+            yield_constr.constraint_transition(builder, c);
+            for i in 0..24*3*2 {
+                if k == 0 {
+                    let c = builder.sub_extension(local_values[MML_FP12_MUL_OFFSET + FP12_MUL_X_INPUT_OFFSET + i], local_values[miller_loop_offset(0) + MILLER_LOOP_RES_OFFSET + i]);
+                    let c = builder.mul_extension(index, c);
+                    yield_constr.constraint(builder, c);
+                }
+                let y = if k + 1 < self.num_pairs {
+                    local_values[miller_loop_offset(k+1) + MILLER_LOOP_RES_OFFSET + i]
+                } else if i == 0 {
+                    one
+                } else {
+                    zero
+                };
+                let c = builder.sub_extension(local_values[MML_FP12_MUL_OFFSET + FP12_MUL_Y_INPUT_OFFSET + i], y);
+                let c = builder.mul_extension(index, c);
+                yield_constr.constraint(builder, c);
+            }
+            if k < last {
+                let next_index = next_values[MML_MUL_INDEX_OFFSET + k + 1];
+                let block_end = builder.sub_extension(one, mul_selector);
+                let c = builder.sub_extension(next_index, one);
+                let c = builder.mul_many_extension([index, block_end, c]);
+                yield_constr.constraint_transition(builder, c);
+                let c = builder.sub_extension(next_values[MML_FP12_MUL_OFFSET + FP12_MUL_SELECTOR_OFFSET], one);
+                let c = builder.mul_many_extension([index, next_index, c]);
+                yield_constr.constraint_transition(builder, c);
+                for i in 0..24*3*2 {
+                    let c = builder.sub_extension(next_values[MML_FP12_MUL_OFFSET + FP12_MUL_X_INPUT_OFFSET + i], local_values[fp12_mul_res(MML_FP12_MUL_OFFSET, i)]);
+                    let c = builder.mul_many_extension([index, next_index, c]);
+                    yield_constr.constraint_transition(builder, c);
+                }
+            }
+        }
+        let c = builder.sub_extension(local_values[MML_MUL_INDEX_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        let c = builder.sub_extension(mul_selector, one);
+        yield_constr.constraint_first_row(builder, c);
+        let last_index = local_values[MML_MUL_INDEX_OFFSET + last];
+        for i in 0..24*3*2 {
+            let c = builder.sub_extension(local_values[fp12_mul_res(MML_FP12_MUL_OFFSET, i)], local_values[MML_FINAL_EXP_OFFSET + FINAL_EXP_INPUT_OFFSET + i]);
+            let c = builder.mul_extension(last_index, c);
+            yield_constr.constraint(builder, c);
+        }
+
+        // Final exponentiate
+        for i in 0..24*3*2 {
+            let c = builder.sub_extension(local_values[MML_FINAL_EXP_OFFSET + FINAL_EXP_T31_OFFSET + i], public_inputs[PIS_RES_OFFSET + i]);
+            yield_constr.constraint_last_row(builder, c);
+        }
+
+        add_fp12_multiplication_constraints_ext_circuit(builder, yield_constr, local_values, next_values, MML_FP12_MUL_OFFSET, None);
+        add_final_exponentiate_constraints_ext_circuit(builder, yield_constr, local_values, next_values, self.num_rows, MML_FINAL_EXP_OFFSET);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        FINAL_EXP_CONSTRAINT_DEGREE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{self, Fp2, Fp12, G1Affine, G2Affine};
+
+    use super::{MultiMillerLoopStark, PUBLIC_INPUTS};
+
+    #[test]
+    fn test_multi_miller_loop_two_pairs() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = MultiMillerLoopStark<F, D>;
+
+        // e(G1, G2) * e(-G1, G2) == 1
+        let g1 = G1Affine::generator();
+        let neg_g1 = -g1;
+        let g2 = G2Affine::generator();
+        let ell_coeffs = native::calc_pairing_precomp(g2.x, g2.y, Fp2::one());
+        let mu = native::miller_loop(g1.x, g1.y, g2.x, g2.y, Fp2::one()) * native::miller_loop(neg_g1.x, neg_g1.y, g2.x, g2.y, Fp2::one());
+        let res = mu.final_exponentiate();
+        assert_eq!(res, Fp12::one());
+
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let stark = S::new(8192, 2);
+        let pairs = vec![(g1.x, g1.y, ell_coeffs.clone()), (neg_g1.x, neg_g1.y, ell_coeffs)];
+        let public_inputs = S::public_inputs(&pairs, &res);
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        let trace = stark.generate_trace(&pairs);
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+    }
+}
@@ -2,12 +2,17 @@
 
 use std::ops::{Add, Sub, Neg, Mul, Div};
 
-use std::{str::FromStr, vec};
+use std::{fmt, str::FromStr, vec};
+
+use anyhow::{anyhow, ensure, Result};
 
 
 use num_bigint::{BigUint, BigInt, Sign, ToBigInt};
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
 
 use crate::big_arithmetic::{big_add, big_less_than, self};
+use crate::constants::{FP12_FORBENIUS_COEFFICIENTS, FP2_FORBENIUS_COEFFICIENTS, FP6_FORBENIUS_COEFFICIENTS_1, FP6_FORBENIUS_COEFFICIENTS_2, G1_GENERATOR, G2_GENERATOR, GROUP_ORDER_R};
 
 pub fn modulus() -> BigUint {
     BigUint::from_str("4002409555221667393417789825735904156556882819939007885332058136124031650490837864442687629129015664037894272559787").unwrap()
@@ -17,10 +22,43 @@ pub fn modulus_digits() -> Vec<u32> {
     modulus().to_u32_digits()
 }
 
+/// Order `r` of the prime order subgroups G1 and G2.
+pub fn group_order() -> BigUint {
+    GROUP_ORDER_R.clone()
+}
+
+/// The bls12-381 parameter `x` (negated, as `x` is negative), which is the exponent of [Fp12::cyclotocmic_exponent].
+pub const BLS_12_381_PARAMETER: u64 = 0xd201000000010000;
+
 pub fn get_bls_12_381_parameter() -> BigUint {
-    BigUint::from_str("15132376222941642752").unwrap()
+    BigUint::from(BLS_12_381_PARAMETER)
+}
+
+/// Errors of the fallible native functions, which trace generators can return instead of panicking.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum NativeError {
+    /// The value needs `actual` u32 limbs, but at most `expected` are available.
+    WrongLimbCount { expected: usize, actual: usize },
+    /// The value is not less than the bound of the operation.
+    ValueTooLarge,
+    /// The value has no inverse modulo the modulus.
+    NonInvertible,
+}
+
+impl fmt::Display for NativeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NativeError::WrongLimbCount { expected, actual } => write!(f, "value needs {} u32 limbs, expected at most {}", actual, expected),
+            NativeError::ValueTooLarge => write!(f, "value is too large"),
+            NativeError::NonInvertible => write!(f, "value is not invertible"),
+        }
+    }
 }
 
+impl std::error::Error for NativeError {}
+
+pub type NativeResult<T> = std::result::Result<T, NativeError>;
+
 pub fn get_negate(y: &[u32; 12]) -> [u32; 12] {
     let y_bu = BigUint::new(y.to_vec());
     let neg = modulus() - y_bu;
@@ -52,22 +90,31 @@ pub fn get_u32_carries(x: &[u32; 12], y: &[u32; 12]) -> [u32; 12] {
 }
 
 
+// The widest limb step of a long multiplication by a u32, `x[i] * y + carry` with all three `2^32 - 1`, fits in the u64 accumulator, so the carry out of a step, its high half, is at most `2^32 - 2`.
+const _: () = assert!(u32::MAX as u128 * u32::MAX as u128 + u32::MAX as u128 <= u64::MAX as u128);
+
+/// One limb step of a long multiplication by a u32, accumulated in a u64. Returns the low half as the result limb and the high half as the carry.
+fn mul_limb_u32(x: u32, y: u32, carry: u32) -> (u32, u32) {
+    let acc = x as u64 * y as u64 + carry as u64;
+    let (limb, carry) = (acc as u32, (acc >> 32) as u32);
+    debug_assert!(carry < u32::MAX, "mul_limb_u32: carry {} out of range", carry);
+    (limb, carry)
+}
+
+/// Long multiplication of 12 limbs by a u32, least significant limb first. Returns the 13 limbs of the product, which always fits, and the carry of each limb step.
 pub fn multiply_by_slice(x: &[u32; 12], y: u32) -> ([u32; 13],[u32; 12]) {
     let mut res: [u32; 13] = [0u32; 13];
     let mut carries: [u32; 12] = [0u32; 12];
     let mut prev_carry = 0;
     for i in 0..12 {
-        let temp = (x[i] as u64 * y as u64) + prev_carry as u64;
-        let temp_res = temp as u32;
-        let new_carry = (temp>>32) as u32;
-        prev_carry = new_carry;
-        res[i] = temp_res;
-        carries[i] = prev_carry;
+        (res[i], carries[i]) = mul_limb_u32(x[i], y, prev_carry);
+        prev_carry = carries[i];
     }
     res[12] = prev_carry;
     (res, carries)
 }
 
+/// Long addition of 24 limbs, least significant limb first. Returns the sum modulo 2^768 and the carry of each limb, the carry out of the addition being the last one.
 pub fn add_u32_slices(x: &[u32; 24], y: &[u32; 24]) -> ([u32; 24], [u32; 24]) {
     let mut prev_carry = 0u32;
     let mut res = [0u32; 24];
@@ -83,6 +130,7 @@ pub fn add_u32_slices(x: &[u32; 24], y: &[u32; 24]) -> ([u32; 24], [u32; 24]) {
     (res, carries)
 }
 
+/// Long addition of 12 limbs, least significant limb first. Returns the sum modulo 2^384 and the carry of each limb, the last one being the carry out of the addition, i.e. the 13th limb of the sum.
 pub fn add_u32_slices_12(x: &[u32; 12], y: &[u32; 12]) -> ([u32; 12], [u32; 12]) {
     let mut prev_carry = 0u32;
     let mut res = [0u32; 12];
@@ -99,12 +147,13 @@ pub fn add_u32_slices_12(x: &[u32; 12], y: &[u32; 12]) -> ([u32; 12], [u32; 12])
 }
 
 // assume x > y
-pub fn sub_u32_slices(x: &[u32; 24], y: &[u32; 24]) -> ([u32; 24], [u32; 24]) {
+/// Long subtraction of 24 limbs, least significant limb first. Returns the difference modulo 2^768, the borrow of each limb, and whether the subtraction borrowed out, i.e. `x < y`.
+pub fn sub_u32_slices(x: &[u32; 24], y: &[u32; 24]) -> ([u32; 24], [u32; 24], bool) {
     let mut prev_borrow = 0u32;
     let mut res = [0u32; 24];
     let mut borrows = [0u32; 24];
     for i in 0..24 {
-        if x[i] >= y[i] + prev_borrow {
+        if x[i] as u64 >= y[i] as u64 + prev_borrow as u64 {
             res[i] = x[i]-y[i]-prev_borrow;
             borrows[i] = 0;
             prev_borrow = 0;
@@ -114,7 +163,7 @@ pub fn sub_u32_slices(x: &[u32; 24], y: &[u32; 24]) -> ([u32; 24], [u32; 24]) {
             prev_borrow = 1;
         }
     }
-    (res, borrows)
+    (res, borrows, prev_borrow == 1)
 }
 
 // assume x > y
@@ -123,7 +172,7 @@ pub fn sub_u32_slices_12(x: &[u32; 12], y: &[u32; 12]) -> ([u32; 12], [u32; 12])
     let mut res = [0u32; 12];
     let mut borrows = [0u32; 12];
     for i in 0..12 {
-        if x[i] >= y[i] + prev_borrow {
+        if x[i] as u64 >= y[i] as u64 + prev_borrow as u64 {
             res[i] = x[i]-y[i]-prev_borrow;
             borrows[i] = 0;
             prev_borrow = 0;
@@ -137,17 +186,16 @@ pub fn sub_u32_slices_12(x: &[u32; 12], y: &[u32; 12]) -> ([u32; 12], [u32; 12])
     (res, borrows)
 }
 
+/// [multiply_by_slice] truncated to 12 limbs, with the carry of each limb step. Panics if the product does not fit in 12 limbs, i.e. if the last carry is not zero.
 pub fn mul_u32_slice_u32(x: &[u32; 12], y: u32) -> ([u32; 12], [u32; 12]) {
     let mut prev_carry = 0u32;
     let mut res = [0u32; 12];
     let mut carries = [0u32; 12];
     for i in 0..12 {
-        let tmp = x[i] as u64 * y as u64 + prev_carry as u64;
-        res[i] = tmp as u32;
-        carries[i] = (tmp >> 32) as u32;
+        (res[i], carries[i]) = mul_limb_u32(x[i], y, prev_carry);
         prev_carry = carries[i];
     }
-    assert_eq!(prev_carry, 0);
+    assert_eq!(prev_carry, 0, "mul_u32_slice_u32: product does not fit in 12 limbs");
     (res, carries)
 }
 
@@ -162,6 +210,56 @@ pub fn get_bits_as_array(number: u32) -> [u32; 32] {
     result
 }
 
+/// The 256 bits of a scalar, least significant first, decomposed limb by limb with [get_bits_as_array]. Used by the native scalar multiplications, so that the bits of a secret scalar are held in one place; with the `zeroize` feature they are wiped when the buffer is dropped.
+pub struct ScalarBits([u32; 256]);
+
+impl ScalarBits {
+    pub fn new(k: &BigUint) -> Self {
+        assert!(k.bits() <= 256);
+        let digits = k.to_u32_digits();
+        let mut bits = [0u32; 256];
+        for (i, d) in digits.iter().enumerate() {
+            bits[i * 32..(i + 1) * 32].copy_from_slice(&get_bits_as_array(*d));
+        }
+        #[cfg(feature = "zeroize")]
+        {
+            let mut digits = digits;
+            digits.zeroize();
+        }
+        ScalarBits(bits)
+    }
+
+    pub fn bit(&self, i: usize) -> bool {
+        self.0[i] == 1
+    }
+
+    /// The bits as 0 or 1 limbs, e.g. to be assigned in a trace.
+    pub fn as_array(&self) -> &[u32; 256] {
+        &self.0
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Zeroize for ScalarBits {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for ScalarBits {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+/// Overwrites the digits of `k` with zeros. num-bigint has no `Zeroize` implementation, but assigning a value of the same number of digits writes over the digit buffer of `k` in place.
+#[cfg(feature = "zeroize")]
+fn zeroize_biguint(k: &mut BigUint) {
+    let len = k.iter_u32_digits().count();
+    k.assign_from_slice(&vec![0u32; len]);
+}
+
 pub fn add_u32_slices_1(x: &[u32; 24], y: &[u32; 25]) -> ([u32; 25], [u32; 24]) {
     let mut x_padded = [0u32; 25];
     x_padded[0..24].copy_from_slice(x);
@@ -218,6 +316,15 @@ pub fn mod_inverse(a: BigUint, m: BigUint) -> BigUint {
     // x % m
 }
 
+/// [mod_inverse], which returns [NativeError::NonInvertible] if `a` has no inverse modulo `m`.
+pub fn try_mod_inverse(a: BigUint, m: BigUint) -> NativeResult<BigUint> {
+    let inv = mod_inverse(a.clone(), m.clone());
+    if (a * inv.clone()) % m != BigUint::from(1u32) {
+        return Err(NativeError::NonInvertible);
+    }
+    Ok(inv)
+}
+
 pub fn fp4_square(a: Fp2, b: Fp2) -> (Fp2, Fp2) {
     let a2 = a * a;
     let b2 = b * b;
@@ -227,12 +334,18 @@ pub fn fp4_square(a: Fp2, b: Fp2) -> (Fp2, Fp2) {
     )
 }
 
-pub fn get_u32_vec_from_literal(x: BigUint) -> [u32; 12] {
+/// Returns the `N` little endian u32 limbs of `x`, or [NativeError::WrongLimbCount] if `x` does not fit in `N` limbs.
+pub fn try_get_u32_limbs<const N: usize>(x: &BigUint) -> NativeResult<[u32; N]> {
     let mut x_u32_vec: Vec<u32> = x.to_u32_digits();
-    while x_u32_vec.len() != 12 {
-        x_u32_vec.push(0 as u32);
+    if x_u32_vec.len() > N {
+        return Err(NativeError::WrongLimbCount { expected: N, actual: x_u32_vec.len() });
     }
-    x_u32_vec.try_into().unwrap()
+    x_u32_vec.resize(N, 0);
+    Ok(x_u32_vec.try_into().unwrap())
+}
+
+pub fn get_u32_vec_from_literal(x: BigUint) -> [u32; 12] {
+    try_get_u32_limbs(&x).unwrap()
 }
 
 pub fn get_selector_bits_from_u32(x: u32) -> [u32; 12] {
@@ -247,17 +360,15 @@ pub fn get_selector_bits_from_u32(x: u32) -> [u32; 12] {
 }
 
 pub fn get_u32_vec_from_literal_24(x: BigUint) -> [u32; 24] {
-    let mut x_u32_vec: Vec<u32> = x.to_u32_digits();
-    while x_u32_vec.len() != 24 {
-        x_u32_vec.push(0 as u32);
-    }
-    x_u32_vec.try_into().unwrap()
+    try_get_u32_limbs(&x).unwrap()
 }
 
-pub fn get_div_rem_modulus_from_biguint_12(x: BigUint) -> ([u32; 12], [u32; 12]) {
+/// Returns the quotient and remainder of `x` divided by the modulus, in 12 limbs each. Returns [NativeError::ValueTooLarge] if the quotient does not fit in 12 limbs, i.e. `x` is not less than p * 2^384.
+pub fn get_div_rem_modulus_from_biguint_12(x: BigUint) -> NativeResult<([u32; 12], [u32; 12])> {
     let rem = x.clone()%modulus();
     let div = x/modulus();
-    (get_u32_vec_from_literal(div), get_u32_vec_from_literal(rem))
+    let div = try_get_u32_limbs(&div).map_err(|_| NativeError::ValueTooLarge)?;
+    Ok((div, get_u32_vec_from_literal(rem)))
 }
 
 pub fn calc_qs(x: Fp2, y: Fp2, z: Fp2) -> (Fp2, Fp2, Fp2) {
@@ -345,7 +456,19 @@ pub fn calc_precomp_stuff_loop1(rx: Fp2, ry: Fp2, rz: Fp2, qx: Fp2, qy: Fp2) ->
     ]
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+/// u32 limbs of a field element, least significant limb first, as one vector of 12 limbs per fp component. The traces set the components one after the other, in the order of `get_u32_slice().concat()`.
+pub trait LimbRepr {
+    fn get_u32_slice(&self) -> Vec<Vec<u32>>;
+    /// Number of limbs of the element, i.e. the number of trace columns it takes.
+    fn num_limbs() -> usize;
+
+    /// The limbs of all the components, flattened.
+    fn limbs(&self) -> Vec<u32> {
+        LimbRepr::get_u32_slice(self).concat()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
 pub struct Fp(pub(crate) [u32; 12]);
 
 impl Fp {
@@ -363,6 +486,28 @@ impl Fp {
         Fp(get_u32_vec_from_literal(x))
     }
 
+    pub fn get_u32_slice(&self) -> [u32; 12] {
+        self.0
+    }
+
+    /// Big-endian hex of the element reduced modulo p, prefixed with `0x`.
+    pub fn to_hex(&self) -> String {
+        format!("{:#x}", BigUint::new(self.get_u32_slice().to_vec()) % modulus())
+    }
+
+    /// Parses the big-endian hex written by [to_hex](Self::to_hex). The `0x` prefix and the leading zeros are optional. Errors if `s` is not hex or the value is not canonical.
+    pub fn from_hex(s: &str) -> Result<Fp> {
+        let digits = s.strip_prefix("0x").unwrap_or(s);
+        let x = BigUint::parse_bytes(digits.as_bytes(), 16).ok_or_else(|| anyhow!("{:?} is not a hex fp element", s))?;
+        Fp::from_canonical_biguint(x)
+    }
+
+    /// Strict construction, which errors if `x` is not canonical, i.e. `x >= p`. See `From<BigUint>` for the reducing construction.
+    pub fn from_canonical_biguint(x: BigUint) -> Result<Fp> {
+        ensure!(x < modulus(), "{:#x} is not a canonical fp element", x);
+        Ok(Fp::get_fp_from_biguint(x))
+    }
+
     pub fn get_bitlen(&self) -> u64 {
         BigUint::new(self.0.try_into().unwrap()).bits()
     }
@@ -378,9 +523,33 @@ impl Fp {
         Fp::get_fp_from_biguint(inv)
     }
 
+    /// [invert](Self::invert), which returns [NativeError::NonInvertible] for zero.
+    pub fn try_invert(&self) -> NativeResult<Self> {
+        let inv = try_mod_inverse(self.to_biguint(), modulus())?;
+        Ok(Fp::get_fp_from_biguint(inv))
+    }
+
     pub fn to_biguint(&self) -> BigUint {
         BigUint::new(self.0.to_vec())
     }
+
+    /// Parses a canonical 48-byte big-endian encoding. Returns `None` if the value is not less than the modulus.
+    pub fn from_bytes_be(bytes: &[u8; 48]) -> Option<Fp> {
+        let x = BigUint::from_bytes_be(bytes);
+        if x >= modulus() {
+            return None;
+        }
+        Some(Fp::get_fp_from_biguint(x))
+    }
+
+    /// Returns the 48-byte big-endian encoding.
+    pub fn to_bytes_be(&self) -> [u8; 48] {
+        let mut res = [0u8; 48];
+        for (i, limb) in self.0.iter().enumerate() {
+            res[44 - i*4..48 - i*4].copy_from_slice(&limb.to_be_bytes());
+        }
+        res
+    }
 }
 
 impl Div for Fp {
@@ -416,9 +585,12 @@ impl Mul for Fp {
 impl Neg for Fp {
     type Output = Self;
 
+    /// Returns `p - x` reduced modulo p, so that the negation of zero is zero. See [negate_fp] for the non reduced negation used by the negation gadgets.
     fn neg(self) -> Self::Output {
-        let x: BigUint = BigUint::new(self.0.try_into().unwrap());
-        Fp(get_u32_vec_from_literal(modulus()-x))
+        if self == Fp::zero() {
+            return self;
+        }
+        negate_fp(self)
     }
 }
 
@@ -479,6 +651,7 @@ pub fn mul_fp_without_reduction(x: Fp, y: Fp) -> [u32; 24] {
     get_u32_vec_from_literal_24(z)
 }
 
+/// Returns `p - x` without reducing, i.e. `p` for zero. The negation gadgets (see [fill_trace_negate_fp](super::fp::fill_trace_negate_fp)) constrain `x + (p - x) == p`.
 pub fn negate_fp(x: Fp) -> Fp {
     let x: BigUint = BigUint::new(x.0.try_into().unwrap());
     Fp(get_u32_vec_from_literal(modulus()-x))
@@ -493,6 +666,34 @@ pub fn sub_fp(x: Fp, y: Fp) -> Fp {
     Fp(get_u32_vec_from_literal(z))
 }
 
+/// Square root of an fp element as `x^((p+1)/4)`, which is valid since p = 3 mod 4. Returns `None` if `x` is not a quadratic residue.
+pub fn sqrt_fp(x: Fp) -> Option<Fp> {
+    let p = modulus();
+    let x_b = x.to_biguint();
+    let root = x_b.modpow(&((&p + 1u32) >> 2), &p);
+    if (&root * &root) % &p != x_b {
+        return None;
+    }
+    Some(Fp::get_fp_from_biguint(root))
+}
+
+/// Square root of an fp2 element with algorithm 9 of "Square root computation over even extension fields" (Adj, Rodríguez-Henríquez), which is valid since p = 3 mod 4. Returns `None` if `a` is not a quadratic residue.
+/// With `a1 = a^((p-3)/4)` and `alpha = a1^2 * a = a^((p-1)/2)`, `a` is a square iff the norm `alpha^p * alpha` (taken with the Frobenius) is not -1. The candidate `a1 * a` is then corrected by multiplying with `u` if `alpha == -1`, and with `(1 + alpha)^((p-1)/2)` otherwise.
+pub fn sqrt_fp2(a: Fp2) -> Option<Fp2> {
+    let p = modulus();
+    let a1 = a.pow(&((&p - 3u32) >> 2));
+    let alpha = a1 * a1 * a;
+    let minus_one = Fp2([-Fp::one(), Fp::zero()]);
+    if alpha.forbenius_map(1) * alpha == minus_one {
+        return None;
+    }
+    let x0 = a1 * a;
+    if alpha == minus_one {
+        return Some(Fp2([Fp::zero(), Fp::one()]) * x0);
+    }
+    Some((Fp2::one() + alpha).pow(&((&p - 1u32) >> 1)) * x0)
+}
+
 pub fn sum_of_products(a: Vec<Fp>, b: Vec<Fp>) -> Fp{
     let acc = a.iter().zip(b.iter()).fold(Fp([0; 12]),|acc, (a_i, b_i)| {
         add_fp(mul_fp(a_i.clone(), b_i.clone()), acc)
@@ -500,10 +701,100 @@ pub fn sum_of_products(a: Vec<Fp>, b: Vec<Fp>) -> Fp{
     acc
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+/// The modulus p as 12 u32 limbs, least significant limb first.
+pub const MODULUS_LIMBS: [u32; 12] = [4294945451, 3120496639, 2975072255, 514588670, 4138792484, 1731252896, 4085584575, 1685539716, 1129032919, 1260103606, 964683418, 436277738];
+/// `-p^-1 mod 2^32`, used by the Montgomery reduction to clear one limb at a time.
+pub const MONT_INV: u32 = 4294770685;
+/// `R^2 mod p` with `R = 2^384`, multiplying by it converts to Montgomery form.
+pub const MONT_R2: [u32; 12] = [473175878, 4108263220, 164693233, 175564454, 1284880085, 2380613484, 2476573632, 1743489193, 3038352685, 2591637125, 2462770090, 295210981];
+
+/// Fp element in Montgomery form, i.e. `x * R mod p` with `R = 2^384`. Multiplication reduces the 24 limb product with [mont_reduce], which only needs limb multiplications by constants, instead of the division by the modulus done by [mul_fp].
+/// Only used natively, the starks keep the canonical representation.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FpMont(pub(crate) [u32; 12]);
+
+impl Fp {
+    /// Converts to Montgomery form, `x * R mod p`.
+    pub fn to_mont(self) -> FpMont {
+        FpMont(mont_reduce(&mul_u32_slices_12(&self.0, &MONT_R2)))
+    }
+
+    /// Converts back from Montgomery form, `x * R^-1 mod p`.
+    pub fn from_mont(x: FpMont) -> Fp {
+        let mut t = [0u32; 24];
+        t[..12].copy_from_slice(&x.0);
+        Fp(mont_reduce(&t))
+    }
+}
+
+impl Mul for FpMont {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        FpMont(mont_reduce(&mul_u32_slices_12(&self.0, &rhs.0)))
+    }
+}
+
+/// Schoolbook product of two 12 limb numbers, without reduction.
+pub fn mul_u32_slices_12(x: &[u32; 12], y: &[u32; 12]) -> [u32; 24] {
+    let mut res = [0u32; 24];
+    for i in 0..12 {
+        let mut carry = 0u64;
+        for j in 0..12 {
+            let s = res[i + j] as u64 + x[j] as u64 * y[i] as u64 + carry;
+            res[i + j] = s as u32;
+            carry = s >> 32;
+        }
+        res[i + 12] = carry as u32;
+    }
+    res
+}
+
+/// Montgomery reduction, returns `t * R^-1 mod p` for `t < p * R`. Each step adds the multiple of p which clears the lowest remaining limb, so the working memory is the 24 limbs of `t` and a carry limb.
+pub fn mont_reduce(t: &[u32; 24]) -> [u32; 12] {
+    let mut t_ext = [0u32; 25];
+    t_ext[..24].copy_from_slice(t);
+    for i in 0..12 {
+        let m = t_ext[i].wrapping_mul(MONT_INV) as u64;
+        let mut carry = 0u64;
+        for j in 0..12 {
+            let s = t_ext[i + j] as u64 + m * MODULUS_LIMBS[j] as u64 + carry;
+            t_ext[i + j] = s as u32;
+            carry = s >> 32;
+        }
+        for limb in t_ext[i + 12..].iter_mut() {
+            if carry == 0 {
+                break;
+            }
+            let s = *limb as u64 + carry;
+            *limb = s as u32;
+            carry = s >> 32;
+        }
+    }
+    // The result is below 2p < 2^384, so the top limb is zero.
+    assert_eq!(t_ext[24], 0);
+    let res: [u32; 12] = t_ext[12..24].try_into().unwrap();
+    if big_less_than(&res, &MODULUS_LIMBS) {
+        res
+    } else {
+        sub_u32_slices_12(&res, &MODULUS_LIMBS).0
+    }
+}
+
+/// Multiplication through the Montgomery form, same result as [mul_fp].
+pub fn mul_fp_mont(x: Fp, y: Fp) -> Fp {
+    Fp::from_mont(x.to_mont() * y.to_mont())
+}
+
+#[derive(Clone, Copy, PartialEq)]
 pub struct Fp2(pub(crate) [Fp; 2]);
 
 impl Fp2 {
+    /// The element `c0 + c1 * u` from `[c0, c1]`.
+    pub fn from_coeffs(coeffs: [Fp; 2]) -> Fp2 {
+        Fp2(coeffs)
+    }
+
     pub fn zero() -> Fp2 {
         Fp2([Fp::zero(), Fp::zero()])
     }
@@ -539,6 +830,18 @@ impl Fp2 {
         ])
     }
 
+    /// Exponentiation by an arbitrary exponent with square-and-multiply, processing the exponent from the most significant bit.
+    pub fn pow(&self, exp: &BigUint) -> Fp2 {
+        let mut z = Fp2::one();
+        for i in (0..exp.bits()).rev() {
+            z = z * z;
+            if exp.bit(i) {
+                z = z * *self;
+            }
+        }
+        z
+    }
+
     pub fn to_biguint(&self) -> [BigUint; 2] {
         [
             BigUint::new(self.0[0].0.to_vec()),
@@ -551,6 +854,11 @@ impl Fp2 {
             self.0[0].0,self.0[1].0
         ]
     }
+
+    /// Big-endian hex of each fp component reduced modulo p, prefixed with `0x`.
+    pub fn to_hex(&self) -> Vec<String> {
+        self.get_u32_slice().iter().map(|limbs| Fp(*limbs).to_hex()).collect()
+    }
 }
 
 impl Add for Fp2 {
@@ -629,10 +937,15 @@ pub fn mul_fp2(x: Fp2, y: Fp2) -> Fp2 {
 
 // }
 
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy)]
 pub struct Fp6(pub(crate) [Fp;6]);
 
 impl Fp6 {
+    /// The element `c0 + c1 * v + c2 * v^2` from `[c0, c1, c2]`.
+    pub fn from_coeffs(coeffs: [Fp2; 3]) -> Fp6 {
+        Fp6([coeffs[0].0, coeffs[1].0, coeffs[2].0].concat().try_into().unwrap())
+    }
+
     pub fn invert(&self) -> Self {
         let c0c1c2 = self;
         let c0 = Fp2(c0c1c2.0[0..2].to_vec().try_into().unwrap());
@@ -653,6 +966,11 @@ impl Fp6 {
         self.0.iter().map(|f| f.0).collect::<Vec<[u32; 12]>>().try_into().unwrap()
     }
 
+    /// Big-endian hex of each fp component reduced modulo p, prefixed with `0x`.
+    pub fn to_hex(&self) -> Vec<String> {
+        self.get_u32_slice().iter().map(|limbs| Fp(*limbs).to_hex()).collect()
+    }
+
     pub fn print(&self) {
         // println!("--- Printing Fp6 ---");
         // for i in 0..self.0.len() {
@@ -833,7 +1151,7 @@ impl Fp6 {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy)]
 pub struct Fp12(pub(crate) [Fp; 12]);
 
 impl Fp12 {
@@ -843,6 +1161,21 @@ impl Fp12 {
         Fp12(x)
     }
 
+    /// The element `c0 + c1 * w` from the fp2 coefficients `[c0.c0, c0.c1, c0.c2, c1.c0, c1.c1, c1.c2]`, in the order of `get_u32_slice`.
+    pub fn from_coeffs(coeffs: [Fp2; 6]) -> Fp12 {
+        Fp12(coeffs.iter().flat_map(|c| c.0).collect::<Vec<Fp>>().try_into().unwrap())
+    }
+
+    /// The fp6 coefficient `c0` of `c0 + c1 * w`.
+    pub fn c0(&self) -> Fp6 {
+        Fp6(self.0[0..6].try_into().unwrap())
+    }
+
+    /// The fp6 coefficient `c1` of `c0 + c1 * w`.
+    pub fn c1(&self) -> Fp6 {
+        Fp6(self.0[6..12].try_into().unwrap())
+    }
+
     pub fn invert(&self) -> Self {
         let c0 = Fp6(self.0[0..6].try_into().unwrap());
         let c1 = Fp6(self.0[6..12].try_into().unwrap());
@@ -853,6 +1186,14 @@ impl Fp12 {
         ].concat().try_into().unwrap())
     }
 
+    /// [invert](Self::invert), which returns [NativeError::NonInvertible] for zero.
+    pub fn try_invert(&self) -> NativeResult<Self> {
+        if *self == Fp12([Fp::zero(); 12]) {
+            return Err(NativeError::NonInvertible);
+        }
+        Ok(self.invert())
+    }
+
     pub fn print(&self) {
         // println!("--- Printing Fp12 ---");
         // for i in 0..self.0.len() {
@@ -874,6 +1215,144 @@ impl Fp12 {
     pub fn get_u32_slice(&self) -> [[u32; 12]; 12] {
         self.0.iter().map(|f| f.0).collect::<Vec<[u32; 12]>>().try_into().unwrap()
     }
+
+    /// Compares both elements after reducing each fp component modulo p, so that non-reduced representations of the same value are equal.
+    /// All limbs are compared without short-circuiting on the first difference.
+    pub fn eq_reduced(&self, other: &Fp12) -> bool {
+        let p = modulus();
+        let diff = self.0.iter().zip(other.0.iter()).fold(0u32, |acc, (x, y)| {
+            let x = get_u32_vec_from_literal(x.to_biguint() % &p);
+            let y = get_u32_vec_from_literal(y.to_biguint() % &p);
+            x.iter().zip(y.iter()).fold(acc, |acc, (a, b)| acc | (a ^ b))
+        });
+        diff == 0
+    }
+
+    /// Big-endian hex of each fp component reduced modulo p, prefixed with `0x`.
+    pub fn to_hex(&self) -> Vec<String> {
+        self.get_u32_slice().iter().map(|limbs| Fp(*limbs).to_hex()).collect()
+    }
+
+    /// Parses the 12 fp components written by [to_hex](Self::to_hex), see [Fp::from_hex]. Errors if there are not 12 components or any of them does not parse.
+    pub fn from_hex<S: AsRef<str>>(words: &[S]) -> Result<Fp12> {
+        ensure!(words.len() == 12, "expected 12 hex fp components, got {}", words.len());
+        let fps = words.iter().map(|w| Fp::from_hex(w.as_ref())).collect::<Result<Vec<Fp>>>()?;
+        Ok(Fp12(fps.try_into().unwrap()))
+    }
+
+    /// Parses the 12 fp components, in the order of [get_u32_slice](Self::get_u32_slice), each a 48-byte big-endian encoding (see [Fp::from_bytes_be]). Returns `None` if any component is not less than the modulus.
+    pub fn from_bytes_be(bytes: &[u8; 576]) -> Option<Fp12> {
+        let mut res = [Fp::zero(); 12];
+        for (i, chunk) in bytes.chunks_exact(48).enumerate() {
+            res[i] = Fp::from_bytes_be(chunk.try_into().unwrap())?;
+        }
+        Some(Fp12(res))
+    }
+
+    /// Returns the 576-byte encoding parsed by [from_bytes_be](Self::from_bytes_be).
+    pub fn to_bytes_be(&self) -> [u8; 576] {
+        let mut res = [0u8; 576];
+        for (i, x) in self.0.iter().enumerate() {
+            res[i*48..(i + 1)*48].copy_from_slice(&x.to_bytes_be());
+        }
+        res
+    }
+}
+
+/// Reduces the value modulo p.
+impl From<BigUint> for Fp {
+    fn from(x: BigUint) -> Self {
+        Fp::get_fp_from_biguint(x % modulus())
+    }
+}
+
+/// Errors if the value is not canonical. Implemented on a reference, as `From<BigUint>` already provides the infallible `TryFrom<BigUint>`.
+impl TryFrom<&BigUint> for Fp {
+    type Error = anyhow::Error;
+
+    fn try_from(x: &BigUint) -> Result<Self> {
+        Fp::from_canonical_biguint(x.clone())
+    }
+}
+
+/// Parses the encoding of [Fp12::from_bytes_be]. Errors if there are not 576 bytes or any component is not canonical.
+impl TryFrom<&[u8]> for Fp12 {
+    type Error = anyhow::Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self> {
+        let bytes: &[u8; 576] = bytes.try_into().map_err(|_| anyhow!("expected 576 bytes for an fp12, got {}", bytes.len()))?;
+        Fp12::from_bytes_be(bytes).ok_or_else(|| anyhow!("fp12 component is not less than the modulus"))
+    }
+}
+
+impl fmt::Debug for Fp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fp({})", self.to_hex())
+    }
+}
+
+impl fmt::Debug for Fp2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fp2([{}])", self.to_hex().join(", "))
+    }
+}
+
+impl fmt::Debug for Fp6 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fp6([{}])", self.to_hex().join(", "))
+    }
+}
+
+impl LimbRepr for Fp {
+    fn get_u32_slice(&self) -> Vec<Vec<u32>> {
+        vec![self.0.to_vec()]
+    }
+
+    fn num_limbs() -> usize {
+        12
+    }
+}
+
+impl LimbRepr for Fp2 {
+    fn get_u32_slice(&self) -> Vec<Vec<u32>> {
+        self.0.iter().map(|f| f.0.to_vec()).collect()
+    }
+
+    fn num_limbs() -> usize {
+        24
+    }
+}
+
+impl LimbRepr for Fp6 {
+    fn get_u32_slice(&self) -> Vec<Vec<u32>> {
+        self.0.iter().map(|f| f.0.to_vec()).collect()
+    }
+
+    fn num_limbs() -> usize {
+        72
+    }
+}
+
+impl LimbRepr for Fp12 {
+    fn get_u32_slice(&self) -> Vec<Vec<u32>> {
+        self.0.iter().map(|f| f.0.to_vec()).collect()
+    }
+
+    fn num_limbs() -> usize {
+        144
+    }
+}
+
+impl fmt::Debug for Fp12 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Fp12([{}])", self.to_hex().join(", "))
+    }
+}
+
+impl PartialEq for Fp12 {
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_reduced(other)
+    }
 }
 
 impl Add for Fp12 {
@@ -944,6 +1423,7 @@ pub fn mul_fp_12(x: Fp12, y: Fp12) -> Fp12 {
 
 impl Fp2 {
 
+    /// Builds the frobenius coefficients of fp2, read them from the cached [FP2_FORBENIUS_COEFFICIENTS] instead.
     pub fn forbenius_coefficients() -> [Fp; 2] {
         [
             Fp::get_fp_from_biguint(BigUint::from_str("1").unwrap()),
@@ -951,7 +1431,7 @@ impl Fp2 {
         ]
     }
     pub fn forbenius_map(&self, pow: usize) -> Self {
-        let constants = Fp2::forbenius_coefficients();
+        let constants = *FP2_FORBENIUS_COEFFICIENTS;
         Fp2([
             self.0[0],
             self.0[1]*constants[pow%2]
@@ -961,6 +1441,7 @@ impl Fp2 {
 
 impl Fp6 {
 
+    /// Builds the frobenius coefficients of the `c1` component, read them from the cached [FP6_FORBENIUS_COEFFICIENTS_1] instead.
     pub fn forbenius_coefficients_1() -> [Fp2; 6] {
         [
             Fp2([
@@ -980,16 +1461,17 @@ impl Fp6 {
                 Fp::get_fp_from_biguint(BigUint::from_str("1").unwrap()),
             ]),
             Fp2([
-                Fp::get_fp_from_biguint(BigUint::from_str("793479390729215512621379701633421447060886740281060493010456487427281649075476305620758731620350").unwrap()),
+                Fp::get_fp_from_biguint(BigUint::from_str("4002409555221667392624310435006688643935503118305586438271171395842971157480381377015405980053539358417135540939436").unwrap()),
                 Fp::get_fp_from_biguint(BigUint::from_str("0").unwrap()),
             ]),
             Fp2([
                 Fp::get_fp_from_biguint(BigUint::from_str("0").unwrap()),
-                Fp::get_fp_from_biguint(BigUint::from_str("4002409555221667392624310435006688643935503118305586438271171395842971157480381377015405980053539358417135540939436").unwrap()),
+                Fp::get_fp_from_biguint(BigUint::from_str("793479390729215512621379701633421447060886740281060493010456487427281649075476305620758731620350").unwrap()),
             ]),
         ]
     }
 
+    /// Builds the frobenius coefficients of the `c2` component, read them from the cached [FP6_FORBENIUS_COEFFICIENTS_2] instead.
     pub fn forbenius_coefficients_2() -> [Fp2; 6] {
         [
             Fp2([
@@ -1013,16 +1495,16 @@ impl Fp6 {
                 Fp::get_fp_from_biguint(BigUint::from_str("0").unwrap()),
             ]),
             Fp2([
-                Fp::get_fp_from_biguint(BigUint::from_str("0").unwrap()),
                 Fp::get_fp_from_biguint(BigUint::from_str("793479390729215512621379701633421447060886740281060493010456487427281649075476305620758731620351").unwrap()),
+                Fp::get_fp_from_biguint(BigUint::from_str("0").unwrap()),
             ]),
         ]
     }
     pub fn forbenius_map(&self, pow: usize) -> Self {
         // println!("--- fp6 forbenius map ---");
-        let fp6_frobenius_coefficients_1 = Fp6::forbenius_coefficients_1();
+        let fp6_frobenius_coefficients_1 = *FP6_FORBENIUS_COEFFICIENTS_1;
 
-        let fp6_frobenius_coefficients_2 = Fp6::forbenius_coefficients_2();
+        let fp6_frobenius_coefficients_2 = *FP6_FORBENIUS_COEFFICIENTS_2;
         self.print();
         let c0 = Fp2(self.0[0..2].to_vec().try_into().unwrap());
         // println!("c0 {:?}", c0.to_biguint());
@@ -1040,6 +1522,7 @@ impl Fp6 {
 }
 
 impl Fp12 {
+    /// Builds the frobenius coefficients of fp12, read them from the cached [FP12_FORBENIUS_COEFFICIENTS] instead.
     pub fn forbenius_coefficients() -> [Fp2; 12] {
         [
             Fp2([
@@ -1095,7 +1578,7 @@ impl Fp12 {
 
     pub fn forbenius_map(&self, pow: usize) -> Self {
         // println!(" ---- forbenius - map -----");
-        let fp12_forbenius_coefficients = Fp12::forbenius_coefficients();
+        let fp12_forbenius_coefficients = *FP12_FORBENIUS_COEFFICIENTS;
         let r0 = Fp6(self.0[0..6].to_vec().try_into().unwrap()).forbenius_map(pow);
         r0.print();
         let c0c1c2 = Fp6(self.0[6..12].to_vec().try_into().unwrap()).forbenius_map(pow);
@@ -1114,6 +1597,11 @@ impl Fp12 {
         ].concat().try_into().unwrap())
 
     }
+
+    /// The forbenius map of `self` multiplied by `y`, which the final exponentiation computes as one operation, see `fill_trace_fp12_forbenius_mul`.
+    pub fn forbenius_mul(&self, pow: usize, y: &Fp12) -> Self {
+        self.forbenius_map(pow) * *y
+    }
 }
 
 impl Fp12 {
@@ -1135,12 +1623,57 @@ impl Fp12 {
         ].concat().try_into().unwrap())
     }
 
+    /// Multiplies by the sparse element with c0 = (o0, 0, 0) and c1 = (o3, o4, 0).
+    pub fn multiply_by_034(&self, o0: Fp2, o3: Fp2, o4: Fp2) -> Self {
+        let c0 = Fp6(self.0[0..6].to_vec().try_into().unwrap());
+        let c1 = Fp6(self.0[6..12].to_vec().try_into().unwrap());
+        let t0 = Fp6((0..3).flat_map(|i| (Fp2([c0.0[i*2], c0.0[i*2+1]])*o0).0).collect::<Vec<Fp>>().try_into().unwrap());
+        let t1 = c1.multiply_by_01(o3, o4);
+        let t2 = mul_by_nonresidue(t1.0);
+        let x = t2+t0;
+
+        let t3 = c0+c1;
+        let t4 = o0+o3;
+        let t5 = t3.multiply_by_01(t4, o4);
+        let t6 = t5-t0;
+        let y = t6-t1;
+        Fp12([
+            x.0,y.0
+        ].concat().try_into().unwrap())
+    }
+
     pub fn conjugate(&self) -> Self {
-        let mut x = self.0.clone();
+        let mut x = *self;
+        x.conjugate_assign();
+        x
+    }
+
+    /// [conjugate](Self::conjugate) in place, negating the second Fp6 component without copying the element.
+    pub fn conjugate_assign(&mut self) {
         for i in 6..12 {
-            x[i] = -x[i];
+            self.0[i] = -self.0[i];
         }
-        Fp12(x)
+    }
+
+    /// Inverse of an element of the cyclotomic subgroup, which is its conjugate since `x^(p^6 + 1) = 1` there. The final exponentiation relies on this after its easy part (see [Fp12::final_exponentiate_easy_part]). Not the inverse for other elements.
+    pub fn cyclotomic_inverse(&self) -> Self {
+        self.conjugate()
+    }
+
+    /// Easy part of the final exponentiation, `x^((p^6 - 1)(p^2 + 1))`, which maps a non-zero `x` into the cyclotomic subgroup. Same as T3 in [Fp12::final_exponentiate].
+    pub fn final_exponentiate_easy_part(&self) -> Self {
+        let t_0 = self.forbenius_map(6);
+        let t_1 = t_0 / *self;
+        let t_2 = t_1.forbenius_map(2);
+        let t_3 = t_2 * t_1;
+        debug_assert!(t_3.is_in_cyclotomic_subgroup(), "final_exponentiate_easy_part: result is not in the cyclotomic subgroup");
+        t_3
+    }
+
+    /// Whether the element is in the cyclotomic subgroup, of order `p^4 - p^2 + 1`, where the hard part of the final exponentiation and [Fp12::cyclotomic_square] work.
+    /// Checks that the element is unitary, `x * conjugate(x) == 1` (i.e. `x^(p^6 + 1) == 1`, as `x^(p^6)` is the conjugate), then that `x^(p^4) * x == x^(p^2)`.
+    pub fn is_in_cyclotomic_subgroup(&self) -> bool {
+        *self * self.conjugate() == Fp12::one() && self.forbenius_map(4) * *self == self.forbenius_map(2)
     }
 
     pub fn cyclotomic_square(&self) -> Self {
@@ -1193,21 +1726,41 @@ impl Fp12 {
     }
 
     pub fn cyclotocmic_exponent(&self) -> Fp12 {
+        self.cyclotomic_exp_by(BLS_12_381_PARAMETER)
+    }
+
+    /// Exponentiation by `exp` with cyclotomic squarings, so `self` must be in the cyclotomic subgroup for the result to be `self^exp`.
+    pub fn cyclotomic_exp_by(&self, exp: u64) -> Fp12 {
         let mut z = Fp12::one();
-        for i in (0..get_bls_12_381_parameter().bits()).rev() {
+        for i in (0..64 - exp.leading_zeros()).rev() {
             z = z.cyclotomic_square();
-            if get_bls_12_381_parameter().bit(i) {
+            if (exp >> i) & 1 == 1 {
                 z = z * self.clone();
             }
         }
         z
     }
 
-    pub fn final_exponentiate(&self) -> Self{
+    /// Exponentiation by an arbitrary exponent with square-and-multiply, processing the exponent from the most significant bit.
+    pub fn pow(&self, exp: &BigUint) -> Fp12 {
+        let mut z = Fp12::one();
+        for i in (0..exp.bits()).rev() {
+            z = z * z;
+            if exp.bit(i) {
+                z = z * *self;
+            }
+        }
+        z
+    }
+
+    /// The values T0..T31 of [final_exponentiate](Self::final_exponentiate), in order, so that T31 is the result. These are the values that the final exponentiation trace sets, see `fill_trace_final_exponentiate`.
+    pub fn final_exponentiate_steps(&self) -> [Fp12; 32] {
         let t_0 = self.forbenius_map(6);
         let t_1 = t_0 / self.clone();
         let t_2 = t_1.forbenius_map(2);
         let t_3 = t_2 * t_1;
+        // A wrong easy part would only show up as a wrong output of the hard part.
+        debug_assert!(t_3.is_in_cyclotomic_subgroup(), "final_exponentiate_steps: T3 is not in the cyclotomic subgroup");
         let t_4 = t_3.cyclotocmic_exponent();
         let t_5 = t_4.conjugate();
         let t_6 = t_3.cyclotomic_square();
@@ -1236,8 +1789,43 @@ impl Fp12 {
         let t_29 = t_20 * t_22;
         let t_30 = t_29 * t_25;
         let t_31 = t_30 * t_28;
-        t_31
+        [
+            t_0, t_1, t_2, t_3, t_4, t_5, t_6, t_7, t_8, t_9, t_10, t_11, t_12, t_13, t_14, t_15,
+            t_16, t_17, t_18, t_19, t_20, t_21, t_22, t_23, t_24, t_25, t_26, t_27, t_28, t_29, t_30, t_31,
+        ]
     }
+
+    pub fn final_exponentiate(&self) -> Self {
+        self.final_exponentiate_steps()[31]
+    }
+}
+
+/// Final exponentiation of `x` with the native Fp12 arithmetic only, running the same T0..T31 sequence as the final exponentiation trace without filling it. Useful to compute the expected output of a proof.
+/// Unlike [final_exponentiate_steps](Fp12::final_exponentiate_steps) the Ti's are not kept, so the conjugates are taken in place on the Ti's which are not used again.
+pub fn final_exponentiate_native(x: Fp12) -> Fp12 {
+    let t_1 = x.forbenius_map(6) / x;
+    let t_3 = t_1.forbenius_mul(2, &t_1);
+    let mut t_5 = t_3.cyclotocmic_exponent();
+    t_5.conjugate_assign();
+    let mut t_7 = t_3.cyclotomic_square();
+    t_7.conjugate_assign();
+    let mut t_8 = t_7 * t_5;
+    let mut t_10 = t_8.cyclotocmic_exponent();
+    t_10.conjugate_assign();
+    let mut t_12 = t_10.cyclotocmic_exponent();
+    t_12.conjugate_assign();
+    let mut t_14 = t_12.cyclotocmic_exponent();
+    t_14.conjugate_assign();
+    let t_16 = t_14 * t_5.cyclotomic_square();
+    let mut t_18 = t_16.cyclotocmic_exponent();
+    t_18.conjugate_assign();
+    let t_20 = (t_5 * t_12).forbenius_map(2);
+    let t_29 = (t_10 * t_3).forbenius_mul(3, &t_20);
+    let t_25 = (t_16 * t_3.conjugate()).forbenius_map(1);
+    // T26
+    t_8.conjugate_assign();
+    let t_28 = t_18 * t_8 * t_3;
+    t_29 * t_25 * t_28
 }
 
 
@@ -1359,7 +1947,8 @@ pub fn miller_loop(g1_x: Fp, g1_y: Fp, g2_x: Fp2, g2_y: Fp2, g2_z: Fp2) -> Fp12
         }
         j+=1;
     }
-    f12.conjugate()
+    f12.conjugate_assign();
+    f12
 }
 
 pub fn pairing(p_x: Fp, p_y: Fp, q_x: Fp2, q_y: Fp2, q_z: Fp2) -> Fp12 {
@@ -1421,32 +2010,926 @@ pub fn verify_bls_signatures() -> bool {
     mu_finaexp == Fp12::one()
 }
 
-#[cfg(test)]   
-mod tests {
-    use std::str::FromStr;
+/// Affine point on the BLS12-381 G1 curve `y^2 = x^3 + 4`. The point at infinity is represented with `infinity` set and zero coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct G1Affine {
+    pub x: Fp,
+    pub y: Fp,
+    pub infinity: bool,
+}
 
-    use num_bigint::BigUint;
+impl G1Affine {
+    pub fn generator() -> Self {
+        G1_GENERATOR
+    }
 
-    use crate::native::sub_u32_slices_12;
+    pub fn identity() -> Self {
+        G1Affine {
+            x: Fp::zero(),
+            y: Fp::zero(),
+            infinity: true,
+        }
+    }
 
-    use super::{verify_bls_signatures, Fp12, modulus, get_u32_vec_from_literal};
+    pub fn is_on_curve(&self) -> bool {
+        if self.infinity {
+            return true;
+        }
+        let b = Fp::get_fp_from_biguint(BigUint::from(4u32));
+        self.y * self.y == self.x * self.x * self.x + b
+    }
 
-    #[test]
-    pub fn test_bls_signature_verification() {
-        assert!(verify_bls_signatures());
+    /// Doubles the point using the tangent line slope `lambda = 3x^2 / 2y`. Returns the point at infinity if `y == 0`.
+    pub fn double(&self) -> Self {
+        if self.infinity || self.y == Fp::zero() {
+            return G1Affine::identity();
+        }
+        let x_sq = self.x * self.x;
+        let lambda = (x_sq + x_sq + x_sq) / (self.y + self.y);
+        let x3 = lambda * lambda - (self.x + self.x);
+        let y3 = lambda * (self.x - x3) - self.y;
+        G1Affine {
+            x: x3,
+            y: y3,
+            infinity: false,
+        }
     }
 
-    #[test]
-    pub fn test_final_exponentiate() {
-        let aa = ["2181142506194812233868097821779361009807326315828153071050324314717744521676711650071190927260282422014627435089208",
-            "3266212670671256779826008414922395966600400122723332695666308996296105595418386213353825620535446475769829785237189",
-            "3280330655787598118299804758957910379684134784964426565939861302675766948066521588562898980898245868682162153155911",
-            "333668007718210311816046938245689395232794221928183840372182128979685996722059498232053963662509478803385469716056",
-            "1650925102445293819378017648160637800280351377141029658990698964033732511884552459036333864590686008335846481856882",
-            "3925133212240632255860280854235945320282874550806663137653784505923891479863770370026712801361887427462376126696706",
-            "2444089052091192833501409081021321360112867893942837175254954622703299880931587618210267154453853513743076365662283",
-            "3142914221549818039420055870398197863502329018278548609868118001898418737390067291084903575823960349378631910285921",
-            "1952057563719092278028425573632201081234877258097927010867141683896274170520489868686437644804596724295624637397077",
+    /// Multiplies the point by the scalar `k` using double-and-add, starting from the most significant of the 255 bits of `k`.
+    pub fn mul_scalar(&self, k: &BigUint) -> Self {
+        assert!(k.bits() <= 255);
+        let bits = ScalarBits::new(k);
+        let mut res = G1Affine::identity();
+        for i in (0..255).rev() {
+            res = res.double();
+            if bits.bit(i) {
+                res = res + *self;
+            }
+        }
+        res
+    }
+
+    /// Scalar multiplication with the GLV endomorphism. The scalar is split as `k = k1 + k2*lambda mod r` (see [glv_decompose]), and `[k1]P + [k2]phi(P)` is computed with a joint double-and-add over the 128 bit halves, i.e. half the doublings of [G1Affine::mul_scalar]. Only valid for points in the prime order subgroup, where `phi(P) == [lambda]P`.
+    pub fn mul_scalar_glv(&self, k: &BigUint) -> Self {
+        let (k1, k2) = glv_decompose(k);
+        let num_bits = k1.bits().max(k2.bits()) as usize;
+        let bits = (ScalarBits::new(&k1), ScalarBits::new(&k2));
+        #[cfg(feature = "zeroize")]
+        {
+            let (mut k1, mut k2) = (k1, k2);
+            zeroize_biguint(&mut k1);
+            zeroize_biguint(&mut k2);
+        }
+        let (k1, k2) = bits;
+        let phi_p = phi_g1(self);
+        let sum = *self + phi_p;
+        let mut res = G1Affine::identity();
+        for i in (0..num_bits).rev() {
+            res = res.double();
+            res = match (k1.bit(i), k2.bit(i)) {
+                (true, true) => res + sum,
+                (true, false) => res + *self,
+                (false, true) => res + phi_p,
+                (false, false) => res,
+            };
+        }
+        res
+    }
+
+    /// Checks whether the point lies in the prime order subgroup, i.e. `[r]P == O`.
+    pub fn is_in_subgroup(&self) -> bool {
+        self.mul_scalar(&group_order()).infinity
+    }
+
+    /// Decodes a point from the 96-byte uncompressed Zcash/Ethereum encoding, `x` followed by `y` in big-endian.
+    /// The three most significant bits are the compression, infinity and sign flags, of which only the infinity flag may be set. Returns `None` if the flags or coordinates are not canonical, or if the point is not on the curve or not in the G1 subgroup.
+    pub fn from_uncompressed(bytes: &[u8; 96]) -> Option<Self> {
+        let compression_flag = bytes[0] & 0x80 != 0;
+        let infinity_flag = bytes[0] & 0x40 != 0;
+        let sign_flag = bytes[0] & 0x20 != 0;
+        if compression_flag || sign_flag {
+            return None;
+        }
+        let mut x_bytes: [u8; 48] = bytes[..48].try_into().unwrap();
+        x_bytes[0] &= 0x1f;
+        let y_bytes: [u8; 48] = bytes[48..].try_into().unwrap();
+        if infinity_flag {
+            if x_bytes.iter().chain(y_bytes.iter()).any(|b| *b != 0) {
+                return None;
+            }
+            return Some(G1Affine::identity());
+        }
+        let p = G1Affine {
+            x: Fp::from_bytes_be(&x_bytes)?,
+            y: Fp::from_bytes_be(&y_bytes)?,
+            infinity: false,
+        };
+        if !p.is_on_curve() || !p.is_in_subgroup() {
+            return None;
+        }
+        Some(p)
+    }
+
+    /// Encodes the point in the 96-byte uncompressed encoding read by [G1Affine::from_uncompressed].
+    pub fn to_uncompressed(&self) -> [u8; 96] {
+        let mut res = [0u8; 96];
+        if self.infinity {
+            res[0] = 0x40;
+            return res;
+        }
+        res[..48].copy_from_slice(&self.x.to_bytes_be());
+        res[48..].copy_from_slice(&self.y.to_bytes_be());
+        res
+    }
+
+    /// Encodes the point in the 48-byte compressed encoding read by [decompress_g1], with the sign flag given by [fp_sign_bit].
+    pub fn to_compressed(&self) -> [u8; 48] {
+        let mut res = [0u8; 48];
+        if self.infinity {
+            res[0] = 0xc0;
+            return res;
+        }
+        res.copy_from_slice(&self.x.to_bytes_be());
+        res[0] |= 0x80;
+        if fp_sign_bit(&self.y) {
+            res[0] |= 0x20;
+        }
+        res
+    }
+}
+
+impl Neg for G1Affine {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        if self.infinity || self.y == Fp::zero() {
+            return self;
+        }
+        G1Affine {
+            x: self.x,
+            y: -self.y,
+            infinity: false,
+        }
+    }
+}
+
+impl Add for G1Affine {
+    type Output = Self;
+
+    /// Adds the points using the slope `lambda = (y2 - y1) / (x2 - x1)`. Follows `P + O = P`, `O + P = P` and `P + (-P) = O`, and falls back to doubling if both points are the same.
+    fn add(self, rhs: Self) -> Self::Output {
+        if self.infinity {
+            return rhs;
+        }
+        if rhs.infinity {
+            return self;
+        }
+        if self.x == rhs.x {
+            if self.y == rhs.y {
+                return self.double();
+            }
+            return G1Affine::identity();
+        }
+        let lambda = (rhs.y - self.y) / (rhs.x - self.x);
+        let x3 = lambda * lambda - (self.x + rhs.x);
+        let y3 = lambda * (self.x - x3) - self.y;
+        G1Affine {
+            x: x3,
+            y: y3,
+            infinity: false,
+        }
+    }
+}
+
+/// Point on the BLS12-381 G1 curve in homogeneous projective coordinates, representing the affine point (x/z, y/z). The point at infinity is (0, 1, 0).
+/// The group law uses the complete formulas of Renes, Costello and Batina (eprint 2015/1060, algorithms 7 to 9 for `a = 0`), which have no exceptional cases and need no inversion, so it serves as the reference for the affine group law and its stark gadgets.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct G1Projective {
+    pub x: Fp,
+    pub y: Fp,
+    pub z: Fp,
+}
+
+impl G1Projective {
+    /// `3b` of the curve equation, used by the complete formulas.
+    fn b3() -> Fp {
+        Fp::get_fp_from_biguint(BigUint::from(12u32))
+    }
+
+    pub fn identity() -> Self {
+        G1Projective {
+            x: Fp::zero(),
+            y: Fp::one(),
+            z: Fp::zero(),
+        }
+    }
+
+    pub fn from_affine(p: &G1Affine) -> Self {
+        if p.infinity {
+            return G1Projective::identity();
+        }
+        G1Projective {
+            x: p.x,
+            y: p.y,
+            z: Fp::one(),
+        }
+    }
+
+    pub fn to_affine(&self) -> G1Affine {
+        if self.z == Fp::zero() {
+            return G1Affine::identity();
+        }
+        let z_inv = self.z.invert();
+        G1Affine {
+            x: self.x * z_inv,
+            y: self.y * z_inv,
+            infinity: false,
+        }
+    }
+
+    /// Doubles the point (algorithm 9).
+    pub fn double(&self) -> Self {
+        let t0 = self.y * self.y;
+        let z3 = t0 + t0;
+        let z3 = z3 + z3;
+        let z3 = z3 + z3;
+        let t1 = self.y * self.z;
+        let t2 = Self::b3() * (self.z * self.z);
+        let x3 = t2 * z3;
+        let y3 = t0 + t2;
+        let z3 = t1 * z3;
+        let t2 = t2 + t2 + t2;
+        let t0 = t0 - t2;
+        let y3 = x3 + t0 * y3;
+        let x3 = t0 * (self.x * self.y);
+        G1Projective {
+            x: x3 + x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    /// Adds the affine point `q` (algorithm 8). The formula does not handle `q` at infinity, which returns the point unchanged.
+    pub fn add_mixed(&self, q: &G1Affine) -> Self {
+        if q.infinity {
+            return *self;
+        }
+        let t0 = self.x * q.x;
+        let t1 = self.y * q.y;
+        let t3 = (q.x + q.y) * (self.x + self.y) - (t0 + t1);
+        let t4 = q.y * self.z + self.y;
+        let y3 = q.x * self.z + self.x;
+        let t0 = t0 + t0 + t0;
+        let t2 = Self::b3() * self.z;
+        let z3 = t1 + t2;
+        let t1 = t1 - t2;
+        let y3 = Self::b3() * y3;
+        let x3 = t3 * t1 - t4 * y3;
+        let y3 = t1 * z3 + y3 * t0;
+        let z3 = z3 * t4 + t0 * t3;
+        G1Projective {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+}
+
+impl Add for G1Projective {
+    type Output = Self;
+
+    /// Adds the points (algorithm 7), including the point at infinity and equal points.
+    fn add(self, rhs: Self) -> Self::Output {
+        let t0 = self.x * rhs.x;
+        let t1 = self.y * rhs.y;
+        let t2 = self.z * rhs.z;
+        let t3 = (self.x + self.y) * (rhs.x + rhs.y) - (t0 + t1);
+        let t4 = (self.y + self.z) * (rhs.y + rhs.z) - (t1 + t2);
+        let y3 = (self.x + self.z) * (rhs.x + rhs.z) - (t0 + t2);
+        let t0 = t0 + t0 + t0;
+        let t2 = Self::b3() * t2;
+        let z3 = t1 + t2;
+        let t1 = t1 - t2;
+        let y3 = Self::b3() * y3;
+        let x3 = t3 * t1 - t4 * y3;
+        let y3 = t1 * z3 + y3 * t0;
+        let z3 = z3 * t4 + t0 * t3;
+        G1Projective {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+}
+
+/// Cube root of unity `beta` in Fp, such that the endomorphism `phi(x, y) = (beta*x, y)` acts as the multiplication by [glv_lambda] on G1.
+pub const GLV_BETA: Fp = Fp([43692, 2348613632, 1330249725, 1083451371, 263741339, 2306681189, 2306185940, 2853012861, 1674894981, 3959570566, 964683417, 436277738]);
+
+/// Eigenvalue `lambda = z^2 - 1` of the endomorphism on G1, where z is the BLS parameter. It is a cube root of unity modulo r, with `r = lambda^2 + lambda + 1`.
+pub fn glv_lambda() -> BigUint {
+    BigUint::from_slice(&[4294967295, 0, 107522, 2890245121])
+}
+
+/// The GLV endomorphism of G1, `phi(x, y) = (beta*x, y)`. For points in the prime order subgroup `phi(P) == [lambda]P`.
+pub fn phi_g1(p: &G1Affine) -> G1Affine {
+    if p.infinity {
+        return *p;
+    }
+    G1Affine {
+        x: GLV_BETA * p.x,
+        y: p.y,
+        infinity: false,
+    }
+}
+
+/// Splits a scalar as `k = k1 + k2*lambda mod r`, with `k1 < lambda` and `k2 <= lambda + 1`, both at most 128 bits. Since `r = lambda^2 + lambda + 1`, this is the division of `k mod r` by lambda.
+pub fn glv_decompose(k: &BigUint) -> (BigUint, BigUint) {
+    let lambda = glv_lambda();
+    let k = k % group_order();
+    let halves = (&k % &lambda, &k / &lambda);
+    #[cfg(feature = "zeroize")]
+    {
+        let mut k = k;
+        zeroize_biguint(&mut k);
+    }
+    halves
+}
+
+/// Multi-scalar multiplication `sum([k_i]P_i)` with Pippenger's bucket method.
+/// The scalars are cut into windows of `c` bits, from the most significant one. For every window, each point is added to the bucket of its digit, and the buckets are summed as `sum(d * B_d)` with a running sum, so that a window costs about `n + 2^(c+1)` additions instead of one scalar multiplication per point.
+pub fn msm_g1(points: &[G1Affine], scalars: &[BigUint]) -> G1Affine {
+    assert_eq!(points.len(), scalars.len());
+    let c = if points.len() < 32 {
+        3
+    } else {
+        (points.len() as f64).ln().ceil() as usize + 2
+    };
+    let num_bits = scalars.iter().map(|k| k.bits()).max().unwrap_or(0) as usize;
+    let mut res = G1Affine::identity();
+    for window in (0..num_bits.div_ceil(c)).rev() {
+        for _ in 0..c {
+            res = res.double();
+        }
+        let mut buckets = vec![G1Affine::identity(); (1 << c) - 1];
+        for (p, k) in points.iter().zip(scalars) {
+            let digit = (0..c).filter(|j| k.bit((window * c + j) as u64)).fold(0, |acc, j| acc + (1 << j));
+            if digit != 0 {
+                buckets[digit - 1] = buckets[digit - 1] + *p;
+            }
+        }
+        let mut running = G1Affine::identity();
+        let mut window_sum = G1Affine::identity();
+        for bucket in buckets.into_iter().rev() {
+            running = running + bucket;
+            window_sum = window_sum + running;
+        }
+        res = res + window_sum;
+    }
+    res
+}
+
+/// Affine point on the BLS12-381 G2 curve `y^2 = x^3 + 4(1 + u)` over Fp2. The point at infinity is represented with `infinity` set and zero coordinates.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct G2Affine {
+    pub x: Fp2,
+    pub y: Fp2,
+    pub infinity: bool,
+}
+
+impl G2Affine {
+    pub fn generator() -> Self {
+        G2_GENERATOR
+    }
+
+    pub fn identity() -> Self {
+        G2Affine {
+            x: Fp2::zero(),
+            y: Fp2::zero(),
+            infinity: true,
+        }
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        if self.infinity {
+            return true;
+        }
+        let four = Fp::get_fp_from_biguint(BigUint::from(4u32));
+        let b = Fp2([four, four]);
+        self.y * self.y == self.x * self.x * self.x + b
+    }
+
+    /// Doubles the point using the tangent line slope `lambda = 3x^2 / 2y`. Returns the point at infinity if `y == 0`.
+    pub fn double(&self) -> Self {
+        if self.infinity || self.y == Fp2::zero() {
+            return G2Affine::identity();
+        }
+        let x_sq = self.x * self.x;
+        let lambda = (x_sq + x_sq + x_sq) * (self.y + self.y).invert();
+        let x3 = lambda * lambda - (self.x + self.x);
+        let y3 = lambda * (self.x - x3) - self.y;
+        G2Affine {
+            x: x3,
+            y: y3,
+            infinity: false,
+        }
+    }
+
+    /// Multiplies the point by the scalar `k` using double-and-add, as [G1Affine::mul_scalar].
+    pub fn mul_scalar(&self, k: &BigUint) -> Self {
+        assert!(k.bits() <= 255);
+        let bits = ScalarBits::new(k);
+        let mut res = G2Affine::identity();
+        for i in (0..255).rev() {
+            res = res.double();
+            if bits.bit(i) {
+                res = res + *self;
+            }
+        }
+        res
+    }
+
+    /// Checks whether the point lies in the prime order subgroup, i.e. `[r]Q == O`.
+    pub fn is_in_subgroup(&self) -> bool {
+        self.mul_scalar(&group_order()).infinity
+    }
+
+    /// Encodes the point in the 96-byte compressed encoding read by [decompress_g2], with the sign flag given by [fp2_sign_bit].
+    pub fn to_compressed(&self) -> [u8; 96] {
+        let mut res = [0u8; 96];
+        if self.infinity {
+            res[0] = 0xc0;
+            return res;
+        }
+        res[..48].copy_from_slice(&self.x.0[1].to_bytes_be());
+        res[48..].copy_from_slice(&self.x.0[0].to_bytes_be());
+        res[0] |= 0x80;
+        if fp2_sign_bit(&self.y) {
+            res[0] |= 0x20;
+        }
+        res
+    }
+}
+
+impl Neg for G2Affine {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        if self.infinity {
+            return self;
+        }
+        // Negates each fp coordinate separately, so that a zero coordinate stays reduced
+        let neg = |x: Fp| if x == Fp::zero() { x } else { -x };
+        G2Affine {
+            x: self.x,
+            y: Fp2([neg(self.y.0[0]), neg(self.y.0[1])]),
+            infinity: false,
+        }
+    }
+}
+
+impl Add for G2Affine {
+    type Output = Self;
+
+    /// Adds the points using the slope `lambda = (y2 - y1) / (x2 - x1)`. Follows `P + O = P`, `O + P = P` and `P + (-P) = O`, and falls back to doubling if both points are the same.
+    fn add(self, rhs: Self) -> Self::Output {
+        if self.infinity {
+            return rhs;
+        }
+        if rhs.infinity {
+            return self;
+        }
+        if self.x == rhs.x {
+            if self.y == rhs.y {
+                return self.double();
+            }
+            return G2Affine::identity();
+        }
+        let lambda = (rhs.y - self.y) * (rhs.x - self.x).invert();
+        let x3 = lambda * lambda - (self.x + rhs.x);
+        let y3 = lambda * (self.x - x3) - self.y;
+        G2Affine {
+            x: x3,
+            y: y3,
+            infinity: false,
+        }
+    }
+}
+
+/// Point on the BLS12-381 G2 curve in homogeneous projective coordinates, representing the affine point (x/z, y/z). The point at infinity has `z == 0`. Used as the accumulator of the Miller loop.
+/// The group law uses the same complete formulas as [G1Projective], with `3b = 12(1 + u)`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct G2Projective {
+    pub x: Fp2,
+    pub y: Fp2,
+    pub z: Fp2,
+}
+
+impl G2Projective {
+    /// `3b` of the curve equation, used by the complete formulas.
+    fn b3() -> Fp2 {
+        let twelve = Fp::get_fp_from_biguint(BigUint::from(12u32));
+        Fp2([twelve, twelve])
+    }
+
+    pub fn identity() -> Self {
+        G2Projective {
+            x: Fp2::zero(),
+            y: Fp2::one(),
+            z: Fp2::zero(),
+        }
+    }
+
+    pub fn from_affine(p: &G2Affine) -> Self {
+        if p.infinity {
+            return G2Projective::identity();
+        }
+        G2Projective {
+            x: p.x,
+            y: p.y,
+            z: Fp2::one(),
+        }
+    }
+
+    pub fn to_affine(&self) -> G2Affine {
+        if self.z == Fp2::zero() {
+            return G2Affine::identity();
+        }
+        let z_inv = self.z.invert();
+        G2Affine {
+            x: self.x * z_inv,
+            y: self.y * z_inv,
+            infinity: false,
+        }
+    }
+
+    /// Doubles the point, as [G1Projective::double].
+    pub fn double(&self) -> Self {
+        let t0 = self.y * self.y;
+        let z3 = t0 + t0;
+        let z3 = z3 + z3;
+        let z3 = z3 + z3;
+        let t1 = self.y * self.z;
+        let t2 = Self::b3() * (self.z * self.z);
+        let x3 = t2 * z3;
+        let y3 = t0 + t2;
+        let z3 = t1 * z3;
+        let t2 = t2 + t2 + t2;
+        let t0 = t0 - t2;
+        let y3 = x3 + t0 * y3;
+        let x3 = t0 * (self.x * self.y);
+        G2Projective {
+            x: x3 + x3,
+            y: y3,
+            z: z3,
+        }
+    }
+
+    /// Adds the affine point `q`, as [G1Projective::add_mixed].
+    pub fn add_mixed(&self, q: &G2Affine) -> Self {
+        if q.infinity {
+            return *self;
+        }
+        let t0 = self.x * q.x;
+        let t1 = self.y * q.y;
+        let t3 = (q.x + q.y) * (self.x + self.y) - (t0 + t1);
+        let t4 = q.y * self.z + self.y;
+        let y3 = q.x * self.z + self.x;
+        let t0 = t0 + t0 + t0;
+        let t2 = Self::b3() * self.z;
+        let z3 = t1 + t2;
+        let t1 = t1 - t2;
+        let y3 = Self::b3() * y3;
+        let x3 = t3 * t1 - t4 * y3;
+        let y3 = t1 * z3 + y3 * t0;
+        let z3 = z3 * t4 + t0 * t3;
+        G2Projective {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+}
+
+impl Add for G2Projective {
+    type Output = Self;
+
+    /// Adds the points, as the addition of [G1Projective].
+    fn add(self, rhs: Self) -> Self::Output {
+        let t0 = self.x * rhs.x;
+        let t1 = self.y * rhs.y;
+        let t2 = self.z * rhs.z;
+        let t3 = (self.x + self.y) * (rhs.x + rhs.y) - (t0 + t1);
+        let t4 = (self.y + self.z) * (rhs.y + rhs.z) - (t1 + t2);
+        let y3 = (self.x + self.z) * (rhs.x + rhs.z) - (t0 + t2);
+        let t0 = t0 + t0 + t0;
+        let t2 = Self::b3() * t2;
+        let z3 = t1 + t2;
+        let t1 = t1 - t2;
+        let y3 = Self::b3() * y3;
+        let x3 = t3 * t1 - t4 * y3;
+        let y3 = t1 * z3 + y3 * t0;
+        let z3 = z3 * t4 + t0 * t3;
+        G2Projective {
+            x: x3,
+            y: y3,
+            z: z3,
+        }
+    }
+}
+
+/// Doubling step of the Miller loop. Returns `2t` and the coefficients of the tangent line at `t`, same as the doubling coefficients of `calc_pairing_precomp`.
+pub fn line_double_coeffs(t: &G2Projective) -> (G2Projective, [Fp2; 3]) {
+    let two = Fp::get_fp_from_biguint(BigUint::from(2u32));
+    let three = Fp::get_fp_from_biguint(BigUint::from(3u32));
+    let two_inv = Fp::get_fp_from_biguint(mod_inverse(BigUint::from(2u32), modulus()));
+    let t0 = t.y * t.y;
+    let t1 = t.z * t.z;
+    let t2 = (t1 * three).multiply_by_b();
+    let t3 = t2 * three;
+    let t4 = t.y * t.z * two;
+    let coeffs = [t2 - t0, t.x * t.x * three, -t4];
+    let h = (t0 + t3) * two_inv;
+    let res = G2Projective {
+        x: (t0 - t3) * (t.x * t.y) * two_inv,
+        y: h * h - t2 * t2 * three,
+        z: t0 * t4,
+    };
+    (res, coeffs)
+}
+
+/// Addition step of the Miller loop. Returns `t + q` and the coefficients of the line through `t` and `q`, same as the addition coefficients of `calc_pairing_precomp`.
+pub fn line_add_coeffs(t: &G2Projective, q: &G2Affine) -> (G2Projective, [Fp2; 3]) {
+    let two = Fp::get_fp_from_biguint(BigUint::from(2u32));
+    let theta = t.y - q.y * t.z;
+    let lambda = t.x - q.x * t.z;
+    let coeffs = [theta * q.x - lambda * q.y, -theta, lambda];
+    let lambda_sq = lambda * lambda;
+    let lambda_cu = lambda_sq * lambda;
+    let g = lambda_sq * t.x;
+    let h = lambda_cu - g * two + theta * theta * t.z;
+    let res = G2Projective {
+        x: lambda * h,
+        y: (g - h) * theta - lambda_cu * t.y,
+        z: t.z * lambda_cu,
+    };
+    (res, coeffs)
+}
+
+/// Evaluates the line with coefficients `ell` at `p`. The result is a sparse fp12 element, whose only non zero fp2 coefficients are at positions 0, 1 and 4, i.e. `ell[0]`, `ell[1] * p.x` and `ell[2] * p.y`. Multiplying the Miller loop accumulator by it is the same as `Fp12::multiply_by_014`.
+pub fn line_evaluation(ell: &[Fp2; 3], p: &G1Affine) -> Fp12 {
+    let zero = Fp2::zero();
+    Fp12([ell[0], ell[1] * p.x, zero, zero, ell[2] * p.y, zero].iter().flat_map(|x| x.0).collect::<Vec<Fp>>().try_into().unwrap())
+}
+
+/// Doubling step of the Miller loop evaluated at `p`. Returns `2t` and the value of the tangent line at `t`, evaluated at `p`.
+pub fn line_double(t: &G2Projective, p: &G1Affine) -> (G2Projective, Fp12) {
+    let (res, coeffs) = line_double_coeffs(t);
+    (res, line_evaluation(&coeffs, p))
+}
+
+/// Addition step of the Miller loop evaluated at `p`. Returns `t + q` and the value of the line through `t` and `q`, evaluated at `p`.
+pub fn line_add(t: &G2Projective, q: &G2Affine, p: &G1Affine) -> (G2Projective, Fp12) {
+    let (res, coeffs) = line_add_coeffs(t, q);
+    (res, line_evaluation(&coeffs, p))
+}
+
+/// Sign flag of `y` in the compressed point encodings: whether `y` is the lexicographically largest of `y` and `-y`, i.e. `y > (p-1)/2` once reduced (RFC 9380, appendix C). Zero is not the largest.
+pub fn fp_sign_bit(y: &Fp) -> bool {
+    y.to_biguint() % modulus() > (modulus() - 1u32) >> 1
+}
+
+/// Sign flag of `y` in the compressed G2 encoding, which compares `y.c1`, or `y.c0` if `y.c1` is zero, see [fp_sign_bit].
+pub fn fp2_sign_bit(y: &Fp2) -> bool {
+    if y.0[1].to_biguint() % modulus() == BigUint::from(0u32) {
+        fp_sign_bit(&y.0[0])
+    } else {
+        fp_sign_bit(&y.0[1])
+    }
+}
+
+/// Decompresses a G1 point from the 48-byte Zcash/Ethereum encoding.
+/// The three most significant bits are the compression, infinity and sign flags, the sign flag being set when `y` is the lexicographically largest of the two roots.
+/// Returns `None` if the flags are malformed, `x` is not canonical or `x` is not the abscissa of a point on the curve. Subgroup membership is not checked.
+pub fn decompress_g1(bytes: &[u8; 48]) -> Option<G1Affine> {
+    let compression_flag = bytes[0] & 0x80 != 0;
+    let infinity_flag = bytes[0] & 0x40 != 0;
+    let sign_flag = bytes[0] & 0x20 != 0;
+    if !compression_flag {
+        return None;
+    }
+    let mut x_bytes = *bytes;
+    x_bytes[0] &= 0x1f;
+    if infinity_flag {
+        if sign_flag || x_bytes.iter().any(|b| *b != 0) {
+            return None;
+        }
+        return Some(G1Affine::identity());
+    }
+    let x = Fp::from_bytes_be(&x_bytes)?;
+    let y = sqrt_fp(x * x * x + Fp::get_fp_from_biguint(BigUint::from(4u32)))?;
+    Some(G1Affine {
+        x,
+        y: if fp_sign_bit(&y) == sign_flag { y } else { -y },
+        infinity: false,
+    })
+}
+
+/// Decompresses a G2 point from the 96-byte Zcash/Ethereum encoding, the big-endian `x.c1` followed by `x.c0`, with the flags of [decompress_g1] in the three most significant bits.
+/// The sign flag is set when `y` is the lexicographically largest of the two roots, which compares `y.c1`, or `y.c0` if `y.c1` is zero.
+/// Returns `None` if the flags are malformed, `x` is not canonical or `x` is not the abscissa of a point on the twist `y^2 = x^3 + 4(1 + u)`. Subgroup membership is not checked.
+pub fn decompress_g2(bytes: &[u8; 96]) -> Option<G2Affine> {
+    let compression_flag = bytes[0] & 0x80 != 0;
+    let infinity_flag = bytes[0] & 0x40 != 0;
+    let sign_flag = bytes[0] & 0x20 != 0;
+    if !compression_flag {
+        return None;
+    }
+    let mut x_bytes = *bytes;
+    x_bytes[0] &= 0x1f;
+    if infinity_flag {
+        if sign_flag || x_bytes.iter().any(|b| *b != 0) {
+            return None;
+        }
+        return Some(G2Affine::identity());
+    }
+    let x = Fp2([
+        Fp::from_bytes_be(&x_bytes[48..].try_into().unwrap())?,
+        Fp::from_bytes_be(&x_bytes[..48].try_into().unwrap())?,
+    ]);
+    let four = Fp::get_fp_from_biguint(BigUint::from(4u32));
+    let y = sqrt_fp2(x * x * x + Fp2([four, four]))?;
+    Some(G2Affine {
+        x,
+        // Subtracting from zero keeps the components reduced, a zero component stays zero.
+        y: if fp2_sign_bit(&y) == sign_flag { y } else { Fp2::zero() - y },
+        infinity: false,
+    })
+}
+
+/// Coefficient A' of the curve E': y^2 = x^3 + A'x + B', which is 11-isogenous to the BLS12-381 G1 curve (RFC 9380, section 8.8.1).
+pub const SSWU_G1_A: Fp = Fp([760761373, 1559504904, 2699098495, 2559799181, 4023943596, 3639121946, 2213439144, 2968164435, 3379382658, 1030306306, 2746804547, 1328792]);
+/// Coefficient B' of the isogenous curve E'.
+pub const SSWU_G1_B: Fp = Fp([2383883232, 3519826153, 829221541, 1512251738, 3472056053, 2696528207, 4065280112, 538362352, 1967058491, 25891560, 292061232, 316838029]);
+/// The non square Z = 11 of the simplified SWU map for G1.
+pub const SSWU_G1_Z: Fp = Fp([11, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+/// -B' / A', which is multiplied with `1 + tv1` to get x1 in the simplified SWU map.
+pub const SSWU_G1_MINUS_B_OVER_A: Fp = Fp([1582079100, 701919335, 1518606478, 1371405561, 2854718375, 502503251, 1541143234, 2838015391, 1175222518, 1726948464, 3629527513, 127079759]);
+/// B' / (Z * A'), which is x1 in the simplified SWU map when `Z^2 * u^4 + Z * u^2 == 0`.
+pub const SSWU_G1_B_OVER_ZA: Fp = Fp([4151126036, 4157899114, 3196988495, 4154088796, 3531414189, 822959329, 1659875589, 1748748812, 1495179584, 3883056250, 371630893, 305740195]);
+
+/// Sign of an fp element as `sgn0` of RFC 9380, i.e. its parity.
+pub fn sgn0_fp(x: Fp) -> bool {
+    x.0[0] & 1 == 1
+}
+
+/// Simplified SWU map (RFC 9380, section 6.6.2) of a field element to a point on the isogenous curve E'. The point is not on the G1 curve, it has to be mapped through the 11-isogeny for that.
+/// x1 is `-B'/A' * (1 + 1/(Z^2 * u^4 + Z * u^2))`, or `B'/(Z * A')` if the denominator is zero, and x2 is `Z * u^2 * x1`. x is x1 if `g(x1) = x1^3 + A'x1 + B'` is a square, and x2 otherwise. The sign of y is the sign of u.
+pub fn map_to_curve_g1(u: Fp) -> G1Affine {
+    let g = |x: Fp| x * x * x + SSWU_G1_A * x + SSWU_G1_B;
+    let z_u2 = SSWU_G1_Z * u * u;
+    let tv1 = z_u2 * z_u2 + z_u2;
+    let x1 = if tv1 == Fp::zero() {
+        SSWU_G1_B_OVER_ZA
+    } else {
+        SSWU_G1_MINUS_B_OVER_A * (Fp::one() + tv1.invert())
+    };
+    let (x, y) = match sqrt_fp(g(x1)) {
+        Some(y) => (x1, y),
+        None => {
+            let x2 = z_u2 * x1;
+            (x2, sqrt_fp(g(x2)).unwrap())
+        }
+    };
+    G1Affine {
+        x,
+        y: if sgn0_fp(u) == sgn0_fp(y) { y } else { Fp::zero() - y },
+        infinity: false,
+    }
+}
+
+/// Coefficients of the x numerator of the 11-isogeny map from E' to the G1 curve, lowest degree first (RFC 9380, appendix E.2).
+pub const ISO_G1_X_NUM: [Fp; 12] = [
+    Fp([1933986231, 2930513506, 4075171182, 1443939029, 3451183816, 4066540374, 2731999327, 1798323848, 3650622853, 3087601693, 511914816, 295722795]),
+    Fp([3016459195, 3895783153, 4078486358, 1211691686, 1892804715, 4117095228, 795509783, 2081941339, 558344220, 92846770, 3913526063, 388583123]),
+    Fp([2896092336, 3759644573, 705262239, 2508996157, 3966296668, 1752753409, 693732325, 3456312435, 3507132955, 487606469, 3111549164, 223608925]),
+    Fp([496724065, 3316877412, 3809505683, 1396105820, 4055052419, 4055052937, 1787674405, 3623216229, 132469778, 1309018579, 1875668407, 393799446]),
+    Fp([2894632409, 1360350441, 807303108, 2556045423, 2553261080, 141486949, 307755839, 2581305690, 3826355796, 1715647667, 832173123, 244937322]),
+    Fp([1868900739, 3440624070, 3404152089, 2693205293, 2548469648, 2664672144, 3646060955, 3678187901, 4154935989, 30482459, 225645567, 372294437]),
+    Fp([2704928132, 3721916965, 3238342346, 2376225064, 4190661418, 2416501272, 1326419169, 2970977830, 2330350271, 1781999299, 1071926569, 225367637]),
+    Fp([3824208014, 2630571544, 3371464111, 968345848, 4042421555, 2793588270, 660295089, 896394667, 3507584595, 3899930756, 28040162, 397942391]),
+    Fp([2224546583, 1836161130, 2528595605, 1336055282, 684052958, 2730858185, 3885978996, 3292231020, 1673435405, 2073080629, 4188508100, 135085297]),
+    Fp([3191409822, 2133069927, 3691194233, 2736327470, 4105902975, 1734546618, 973888665, 3710831488, 1561562914, 772552529, 466593732, 379264910]),
+    Fp([369490811, 3395804991, 2351455724, 4137383711, 61950054, 3574264672, 3866656662, 4202548445, 3528874765, 1926818825, 2043545570, 271719840]),
+    Fp([2342703657, 2848504366, 3058514207, 3259702144, 3259788136, 599834395, 583546812, 836214142, 2179853885, 3172898339, 2384858736, 115379236]),
+];
+/// Coefficients of the x denominator of the 11-isogeny map, lowest degree first. The polynomial is monic.
+pub const ISO_G1_X_DEN: [Fp; 11] = [
+    Fp([1087511324, 2570910202, 3191094776, 3042498177, 2143857762, 2627045473, 2740668602, 30797643, 1272183407, 414377519, 2365528494, 147492180]),
+    Fp([3358276607, 3855099758, 1823483625, 333097092, 2353577815, 19317336, 217088012, 1883367626, 2565158454, 1219784978, 3948256323, 307632733]),
+    Fp([1555578393, 4240587194, 2090139356, 3601059990, 2971324076, 4238757376, 1517391057, 1871266159, 3220347167, 2167924265, 1470308958, 187261694]),
+    Fp([2378575064, 319678611, 1055716804, 1232524580, 3264049104, 1422698667, 380724561, 1527260223, 2084629332, 3359289327, 2777342718, 54678913]),
+    Fp([892510750, 1402812763, 523597853, 2614270888, 867370714, 2372617448, 2703254203, 4292855641, 1139253373, 175054175, 36246696, 329834850]),
+    Fp([2674066597, 3224238498, 3290599185, 1946210891, 616077359, 172211439, 3466374477, 962016745, 84330217, 1429802879, 3840305081, 242439672]),
+    Fp([1860717114, 3961877577, 2400051308, 3933451091, 1655627420, 1311639344, 2941493641, 1460623257, 3763955489, 255724643, 4050204185, 124963500]),
+    Fp([3184286302, 301455771, 2635195091, 262519112, 4186057816, 429318605, 466249896, 1853842370, 1959723417, 817100208, 2640619698, 346532906]),
+    Fp([2040649281, 1198976058, 2881110896, 58877586, 2134476853, 1947209126, 1902196331, 3766455581, 2949096396, 1586629437, 2913292162, 168881398]),
+    Fp([1760353546, 761496785, 1266181376, 2467778123, 2029198357, 1994347321, 2532823095, 4135761134, 3013961344, 1198353982, 3119065812, 157270330]),
+    Fp([1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+];
+/// Coefficients of the y numerator of the 11-isogeny map, lowest degree first.
+pub const ISO_G1_Y_NUM: [Fp; 16] = [
+    Fp([2533866291, 3197650289, 2604377026, 3440147182, 2506441683, 726839148, 1218996562, 296555406, 1233181946, 631054089, 463621856, 151885768]),
+    Fp([776062614, 3768051546, 2519705791, 3603261201, 3949440308, 261120835, 625948733, 304343404, 1996179075, 3574609623, 82728977, 323589793]),
+    Fp([3341672950, 3101680192, 682624291, 3530379798, 2436894119, 3276062283, 1487084770, 626189014, 1782270356, 4104357064, 2861985382, 13400171]),
+    Fp([2967860427, 2788514935, 2663783916, 3728759482, 52763897, 165979116, 250312988, 3559565241, 3631507287, 2023257370, 3902282785, 33055606]),
+    Fp([4075355867, 1102502636, 160448680, 786954460, 557128412, 2038644690, 3821949112, 425015035, 2719295940, 1554993122, 4024499987, 147588093]),
+    Fp([909354480, 2578594481, 3378151538, 541025062, 987059278, 1984977981, 2947397565, 1957155028, 3102779040, 571597085, 1080249194, 375406538]),
+    Fp([1609160434, 3378641029, 1175404894, 1202223810, 1298353975, 1378959676, 736199883, 3881547978, 662268732, 2999547856, 3484163004, 78318491]),
+    Fp([410057513, 2707311779, 4276087093, 1123435856, 3261891622, 4244868518, 3820869663, 386532799, 749127284, 3734578877, 859486319, 159893717]),
+    Fp([2077992327, 1614220887, 3347449763, 1775653377, 522242750, 512454250, 1738392172, 2402991382, 508404155, 3901529634, 3180750923, 167526424]),
+    Fp([3396541488, 732932843, 3712924271, 2604626005, 3521434683, 110648958, 2438657790, 3291090087, 2913028643, 574275047, 294043061, 236698234]),
+    Fp([3490824498, 3893588250, 3460283132, 834615900, 1669981712, 3508026945, 1823426879, 3442461643, 490994579, 234687375, 2474430910, 426851911]),
+    Fp([3490139534, 3456616612, 457496070, 1239010804, 2434753586, 778829439, 2867905094, 1379228088, 4275236300, 3105407299, 2402744030, 414476944]),
+    Fp([194453240, 223085808, 2442269170, 113791425, 129262399, 3223785704, 874950798, 2057810218, 83181380, 3512011104, 270242713, 186133676]),
+    Fp([3684172083, 1121571829, 472066660, 3335491757, 1010853276, 333865773, 223090048, 1971010312, 2078479836, 1929117870, 2904476315, 38118292]),
+    Fp([1416962635, 2255167089, 1702473655, 1805714587, 963280100, 3651866461, 3622331752, 1583824257, 1220846588, 44128759, 1581567761, 96545124]),
+    Fp([1774761476, 78927550, 4274003063, 3060073086, 2511274418, 1471010042, 2224446009, 3407355279, 1121922741, 1319439163, 2567898062, 367443534]),
+];
+/// Coefficients of the y denominator of the 11-isogeny map, lowest degree first. The polynomial is monic.
+pub const ISO_G1_Y_DEN: [Fp; 16] = [
+    Fp([2956354497, 21467731, 4127989869, 133392526, 1541877370, 4005765931, 2023230438, 1612344805, 4207995625, 1377308992, 983341234, 370224204]),
+    Fp([776577085, 854986796, 1666358837, 2024023559, 4099137567, 2762015574, 834983324, 2244497399, 1136871608, 437042540, 595664926, 425908060]),
+    Fp([1035954162, 505755829, 922316681, 2792717526, 664589036, 204842461, 1370390321, 2026145109, 1854845271, 1873456765, 1715526183, 93188912]),
+    Fp([1147098134, 2832362904, 3263338413, 1920165106, 3878072129, 306029705, 3720139534, 3507865051, 2314056913, 4060947391, 2039370645, 381145736]),
+    Fp([296026141, 3661173795, 265481709, 2718635484, 3386820886, 1412356623, 258587568, 3335625887, 4163014108, 2953627922, 1415529444, 199286905]),
+    Fp([2868492204, 46548863, 3752447202, 1240698297, 1863277156, 3320633467, 1431361665, 3070317254, 2893558146, 2679269098, 1904663341, 148497705]),
+    Fp([308967260, 1025119129, 2777358265, 3589970945, 1074397265, 1574135228, 2366437615, 1592589818, 2900984395, 3283781428, 2325338927, 375392192]),
+    Fp([360787673, 1626227034, 134340324, 3105945008, 104165682, 4273164242, 4196356055, 3852744542, 3148283758, 62709242, 3191777258, 379842312]),
+    Fp([1431526730, 1797471645, 3146926211, 1389355767, 1274256200, 2881844492, 3333380384, 4193979620, 3520548036, 438035791, 862740771, 409389293]),
+    Fp([3096526677, 879686795, 3576279902, 3342360227, 2124928251, 1393056046, 3778906043, 840365923, 3645539472, 3934389655, 2802478620, 377116109]),
+    Fp([3129961128, 16299164, 1333986820, 1906688671, 3862341064, 240720694, 3143738921, 2624442425, 450007325, 1223692448, 4003726781, 80933465]),
+    Fp([3797291154, 2525279529, 1870576618, 385443992, 3467088181, 2349832840, 4023272146, 2140471178, 1220873287, 4119145502, 1209860927, 181189479]),
+    Fp([1462977996, 513388856, 3820554945, 2466252643, 2685246342, 2103242489, 2329870886, 1412646617, 3026130070, 3277926724, 1282834403, 181844305]),
+    Fp([653068023, 3522878483, 2266287232, 1107630013, 2034386958, 3413413362, 3878234049, 3210107796, 1405974258, 1653333261, 3945680699, 40240128]),
+    Fp([493046671, 1142245178, 4046755712, 1554183536, 896313888, 844037334, 3799234643, 1908674405, 225974721, 1797530974, 383631422, 235905496]),
+    Fp([1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]),
+];
+
+/// Evaluates the polynomial with the given coefficients, lowest degree first, at x using Horner's method.
+pub fn eval_poly_fp(coeffs: &[Fp], x: Fp) -> Fp {
+    coeffs.iter().rev().fold(Fp::zero(), |acc, c| acc * x + *c)
+}
+
+/// 11-isogeny map (RFC 9380, appendix E.2) of a point on the isogenous curve E' to the G1 curve, as `(x_num(x) / x_den(x), y * y_num(x) / y_den(x))`.
+/// Returns the point at infinity if the input is the point at infinity or one of the denominators is zero, i.e. the input is in the kernel of the isogeny.
+pub fn iso_map_g1(p: G1Affine) -> G1Affine {
+    if p.infinity {
+        return G1Affine::identity();
+    }
+    let x_den = eval_poly_fp(&ISO_G1_X_DEN, p.x);
+    let y_den = eval_poly_fp(&ISO_G1_Y_DEN, p.x);
+    if x_den == Fp::zero() || y_den == Fp::zero() {
+        return G1Affine::identity();
+    }
+    G1Affine {
+        x: eval_poly_fp(&ISO_G1_X_NUM, p.x) / x_den,
+        y: p.y * eval_poly_fp(&ISO_G1_Y_NUM, p.x) / y_den,
+        infinity: false,
+    }
+}
+
+/// Clears the cofactor of a point on the G1 curve (RFC 9380, section 7) by multiplying it with `h_eff = 1 - x = 0xd201000000010001`, where x is the (negative) BLS12-381 parameter.
+/// Since x is negative, this is computed as `P + [|x|]P`, which needs a 64 bit scalar multiplication instead of one by the full cofactor.
+pub fn clear_cofactor_g1(p: G1Affine) -> G1Affine {
+    p + p.mul_scalar(&get_bls_12_381_parameter())
+}
+
+#[cfg(test)]   
+mod tests {
+    use std::str::FromStr;
+
+    use num_bigint::BigUint;
+
+    use crate::native::{add_u32_slices, add_u32_slices_12, multiply_by_slice, mul_u32_slice_u32, sub_u32_slices, sub_u32_slices_12};
+
+    use super::{verify_bls_signatures, mul_by_nonresidue, fp_sign_bit, fp2_sign_bit, decompress_g1, decompress_g2, phi_g1, glv_decompose, glv_lambda, msm_g1, group_order, negate_fp, mul_fp, mul_fp_mont, mul_u32_slices_12, mont_reduce, mod_inverse, try_mod_inverse, try_get_u32_limbs, get_div_rem_modulus_from_biguint_12, NativeError, MODULUS_LIMBS, sqrt_fp, sqrt_fp2, map_to_curve_g1, iso_map_g1, clear_cofactor_g1, sgn0_fp, line_double, line_add, miller_loop, mul_fp_12, get_bls_12_381_parameter, final_exponentiate_native, Fp, Fp2, Fp6, Fp12, LimbRepr, ScalarBits, G1Affine, G2Affine, G2Projective, SSWU_G1_A, SSWU_G1_B, modulus, get_u32_vec_from_literal};
+
+    #[test]
+    pub fn test_bls_signature_verification() {
+        assert!(verify_bls_signatures());
+    }
+
+    #[test]
+    pub fn test_final_exponentiate() {
+        let aa = ["2181142506194812233868097821779361009807326315828153071050324314717744521676711650071190927260282422014627435089208",
+            "3266212670671256779826008414922395966600400122723332695666308996296105595418386213353825620535446475769829785237189",
+            "3280330655787598118299804758957910379684134784964426565939861302675766948066521588562898980898245868682162153155911",
+            "333668007718210311816046938245689395232794221928183840372182128979685996722059498232053963662509478803385469716056",
+            "1650925102445293819378017648160637800280351377141029658990698964033732511884552459036333864590686008335846481856882",
+            "3925133212240632255860280854235945320282874550806663137653784505923891479863770370026712801361887427462376126696706",
+            "2444089052091192833501409081021321360112867893942837175254954622703299880931587618210267154453853513743076365662283",
+            "3142914221549818039420055870398197863502329018278548609868118001898418737390067291084903575823960349378631910285921",
+            "1952057563719092278028425573632201081234877258097927010867141683896274170520489868686437644804596724295624637397077",
             "254131389529427774765960554324483250584297364987873642087841623909520980093766889928789173976296059957431962608694",
             "1385128161651935856764061834929068245137081648283968377947672499160305921464670953157912428887005620142387465559867",
             "101302147352745188522496764263445345397483945567997375025250825330209385517139484882425580831299520200841767383756"];
@@ -1458,6 +2941,32 @@ mod tests {
         assert_eq!(mu_finaexp, Fp12::one())
     }
 
+    #[test]
+    fn test_native_error_wrong_limb_count() {
+        let x = (BigUint::from(1u32) << 384) - BigUint::from(1u32);
+        assert_eq!(try_get_u32_limbs::<12>(&x), Ok([u32::MAX; 12]));
+        assert_eq!(try_get_u32_limbs::<12>(&(x + BigUint::from(1u32))), Err(NativeError::WrongLimbCount { expected: 12, actual: 13 }));
+    }
+
+    #[test]
+    fn test_native_error_value_too_large() {
+        let bound = modulus() << 384u32;
+        let (div, rem) = get_div_rem_modulus_from_biguint_12(bound.clone() - BigUint::from(1u32)).unwrap();
+        assert_eq!(div, [u32::MAX; 12]);
+        assert_eq!(rem, get_u32_vec_from_literal(modulus() - BigUint::from(1u32)));
+        assert_eq!(get_div_rem_modulus_from_biguint_12(bound), Err(NativeError::ValueTooLarge));
+    }
+
+    #[test]
+    fn test_native_error_non_invertible() {
+        assert_eq!(Fp::zero().try_invert(), Err(NativeError::NonInvertible));
+        assert_eq!(try_mod_inverse(modulus(), modulus()), Err(NativeError::NonInvertible));
+        assert_eq!(Fp12([Fp::zero(); 12]).try_invert(), Err(NativeError::NonInvertible));
+        let x = Fp::get_fp_from_biguint(BigUint::from(7u32));
+        assert_eq!(x.try_invert(), Ok(x.invert()));
+        assert_eq!(x * x.try_invert().unwrap(), Fp::one());
+    }
+
     #[test]
     fn test_subu32() {
         let x: BigUint = BigUint::from_str("1").unwrap() << 381;
@@ -1467,4 +2976,813 @@ mod tests {
         let (res, _carries) = sub_u32_slices_12(&x_u32, &y_u32);
         assert_eq!( x-y, BigUint::new(res.to_vec()));
     }
+
+    #[test]
+    fn test_fp_bytes_be_round_trip() {
+        let x = Fp::get_fp_from_biguint(modulus() - 1u32);
+        let bytes = x.to_bytes_be();
+        assert_eq!(BigUint::from_bytes_be(&bytes), modulus() - 1u32);
+        assert_eq!(Fp::from_bytes_be(&bytes), Some(x));
+        assert_eq!(Fp::from_bytes_be(&[0u8; 48]), Some(Fp::zero()));
+        let mut one = [0u8; 48];
+        one[47] = 1;
+        assert_eq!(Fp::from_bytes_be(&one), Some(Fp::one()));
+        assert_eq!(Fp::one().to_bytes_be(), one);
+    }
+
+    #[test]
+    fn test_fp_from_bytes_be_non_canonical() {
+        let mut bytes = [0u8; 48];
+        let p_bytes = modulus().to_bytes_be();
+        bytes[48 - p_bytes.len()..].copy_from_slice(&p_bytes);
+        assert_eq!(Fp::from_bytes_be(&bytes), None);
+        assert_eq!(Fp::from_bytes_be(&[0xffu8; 48]), None);
+    }
+
+    fn hex_to_bytes_48(x: &str) -> [u8; 48] {
+        let mut res = [0u8; 48];
+        for i in 0..48 {
+            res[i] = u8::from_str_radix(&x[i*2..i*2 + 2], 16).unwrap();
+        }
+        res
+    }
+
+    #[test]
+    fn test_decompress_g1() {
+        let g = G1Affine::generator();
+        let g_compressed = hex_to_bytes_48("97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb");
+        assert_eq!(decompress_g1(&g_compressed), Some(g));
+        let neg_g_compressed = hex_to_bytes_48("b7f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb");
+        assert_eq!(decompress_g1(&neg_g_compressed), Some(-g));
+        let mut infinity = [0u8; 48];
+        infinity[0] = 0xc0;
+        assert_eq!(decompress_g1(&infinity), Some(G1Affine::identity()));
+
+        let g2 = g.double();
+        assert_eq!(decompress_g1(&g2.to_compressed()), Some(g2));
+        for p in [g, -g, G1Affine::identity()] {
+            assert_eq!(decompress_g1(&p.to_compressed()), Some(p));
+        }
+        assert_eq!(g.to_compressed(), g_compressed);
+        assert_eq!((-g).to_compressed(), neg_g_compressed);
+    }
+
+    #[test]
+    fn test_fp_sign_bit() {
+        let half: BigUint = (modulus() - 1u32) >> 1;
+        let fp = |x: BigUint| Fp::get_fp_from_biguint(x);
+        assert!(!fp_sign_bit(&Fp::zero()));
+        assert!(!fp_sign_bit(&fp(half.clone() - 1u32)));
+        assert!(!fp_sign_bit(&fp(half.clone())));
+        assert!(fp_sign_bit(&fp(half.clone() + 1u32)));
+        assert!(fp_sign_bit(&fp(modulus() - 1u32)));
+        // y and -y have opposite signs around (p-1)/2.
+        assert_eq!(-fp(half.clone()), fp(half.clone() + 1u32));
+        // Limbs holding p + 1 are compared once reduced, as 1.
+        assert!(!fp_sign_bit(&Fp((modulus() + 1u32).to_u32_digits().try_into().unwrap())));
+
+        // The c1 component decides, unless it is zero.
+        assert!(!fp2_sign_bit(&Fp2([fp(modulus() - 1u32), fp(half.clone())])));
+        assert!(fp2_sign_bit(&Fp2([Fp::zero(), fp(half.clone() + 1u32)])));
+        assert!(fp2_sign_bit(&Fp2([fp(half.clone() + 1u32), Fp::zero()])));
+        assert!(!fp2_sign_bit(&Fp2([fp(half.clone()), Fp::zero()])));
+        assert!(!fp2_sign_bit(&Fp2::zero()));
+    }
+
+    #[test]
+    fn test_g1_uncompressed() {
+        let g = G1Affine::generator();
+        for p in [g, -g, g.double(), G1Affine::identity()] {
+            assert_eq!(G1Affine::from_uncompressed(&p.to_uncompressed()), Some(p));
+        }
+        let g_uncompressed = g.to_uncompressed();
+        let mut g_compressed = hex_to_bytes_48("97f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb");
+        g_compressed[0] &= 0x1f;
+        assert_eq!(g_uncompressed[..48], g_compressed);
+        let mut infinity = [0u8; 96];
+        infinity[0] = 0x40;
+        assert_eq!(G1Affine::identity().to_uncompressed(), infinity);
+    }
+
+    #[test]
+    fn test_g1_uncompressed_invalid() {
+        let g = G1Affine::generator();
+        // Compression or sign flag set.
+        for flag in [0x80, 0x20] {
+            let mut bytes = g.to_uncompressed();
+            bytes[0] |= flag;
+            assert_eq!(G1Affine::from_uncompressed(&bytes), None);
+        }
+        // Infinity flag with a non zero y.
+        let mut infinity = G1Affine::identity().to_uncompressed();
+        infinity[95] = 1;
+        assert_eq!(G1Affine::from_uncompressed(&infinity), None);
+        // y + 1 is not on the curve.
+        let off_curve = G1Affine { x: g.x, y: g.y + Fp::one(), infinity: false };
+        assert!(!off_curve.is_on_curve());
+        assert_eq!(G1Affine::from_uncompressed(&off_curve.to_uncompressed()), None);
+        // y = p is not canonical.
+        let mut bytes = g.to_uncompressed();
+        let mut y_p = Fp::get_fp_from_biguint(modulus() - 1u32).to_bytes_be();
+        y_p[47] += 1;
+        bytes[48..].copy_from_slice(&y_p);
+        assert_eq!(G1Affine::from_uncompressed(&bytes), None);
+        // (0, 2) is on the curve and has order 3, so it is not in the G1 subgroup.
+        let not_in_subgroup = G1Affine { x: Fp::zero(), y: Fp::get_fp_from_biguint(BigUint::from(2u32)), infinity: false };
+        assert!(not_in_subgroup.is_on_curve());
+        assert_eq!(G1Affine::from_uncompressed(&not_in_subgroup.to_uncompressed()), None);
+    }
+
+    #[test]
+    fn test_decompress_g1_invalid() {
+        // Missing compression flag.
+        let g_uncompressed_flag = hex_to_bytes_48("17f1d3a73197d7942695638c4fa9ac0fc3688c4f9774b905a14e3a3f171bac586c55e83ff97a1aeffb3af00adb22c6bb");
+        assert_eq!(decompress_g1(&g_uncompressed_flag), None);
+        // Infinity flag with a non zero x.
+        let mut infinity = [0u8; 48];
+        infinity[0] = 0xc0;
+        infinity[47] = 1;
+        assert_eq!(decompress_g1(&infinity), None);
+        // x = 1 is not the abscissa of a point on the curve, since 5 is not a square.
+        let mut x_one = [0u8; 48];
+        x_one[0] = 0x80;
+        x_one[47] = 1;
+        assert_eq!(decompress_g1(&x_one), None);
+        // x = p is not canonical.
+        let mut x_p = Fp::get_fp_from_biguint(modulus() - 1u32).to_bytes_be();
+        x_p[47] += 1;
+        x_p[0] |= 0x80;
+        assert_eq!(decompress_g1(&x_p), None);
+    }
+
+    fn hex_to_bytes_96(x: &str) -> [u8; 96] {
+        hex::decode(x).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_decompress_g2() {
+        let g = G2Affine::generator();
+        let g_compressed = hex_to_bytes_96("93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8");
+        assert_eq!(decompress_g2(&g_compressed), Some(g));
+        let mut neg_g_compressed = g_compressed;
+        neg_g_compressed[0] |= 0x20;
+        assert_eq!(decompress_g2(&neg_g_compressed), Some(-g));
+        let mut infinity = [0u8; 96];
+        infinity[0] = 0xc0;
+        assert_eq!(decompress_g2(&infinity), Some(G2Affine::identity()));
+
+        // The signature of verify_bls_signatures.
+        let fp = |s: &str| Fp::get_fp_from_biguint(BigUint::from_str(s).unwrap());
+        let signature = G2Affine {
+            x: Fp2([
+                fp("2623971017592927791661443929103810896934774536775525535423614243457684905034147949323467412106133456094022067726851"),
+                fp("2791552278788393998835490815906332650385266234676766868498515429583366873304026057923442494886948609285829286788356"),
+            ]),
+            y: Fp2([
+                fp("1392880899106984160179818268515214962705329372907929072981217458923190202387659009520579695608141992620405977748755"),
+                fp("2607207514294746608778464853061537277878553458184247374568293197687045701239874275081091959210122811260239467513958"),
+            ]),
+            infinity: false,
+        };
+        let signature_compressed = hex_to_bytes_96("b22317bfdb10ba592724c27d0cdc51378e5cd94a12cd7e85c895d2a68e8589e8d3c5b3c80f4fe905ef67aa7827617d04110c5c5248f2bb36df97a58c541961ed0f2fcd0760e9de5ae1598f27638dd3ddaebeea08bf313832a57cfdb7f2baaa03");
+        assert_eq!(decompress_g2(&signature_compressed), Some(signature));
+        assert_eq!(signature.to_compressed(), signature_compressed);
+        assert_eq!(g.to_compressed(), g_compressed);
+        assert_eq!((-g).to_compressed(), neg_g_compressed);
+        assert_eq!(G2Affine::identity().to_compressed(), infinity);
+        assert!(signature.is_on_curve());
+    }
+
+    #[test]
+    fn test_decompress_g2_invalid() {
+        // Missing compression flag.
+        let mut g_compressed = hex_to_bytes_96("93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8");
+        g_compressed[0] &= 0x7f;
+        assert_eq!(decompress_g2(&g_compressed), None);
+        // Infinity flag with the sign flag or a non zero x.
+        let mut infinity = [0u8; 96];
+        infinity[0] = 0xe0;
+        assert_eq!(decompress_g2(&infinity), None);
+        infinity[0] = 0xc0;
+        infinity[95] = 1;
+        assert_eq!(decompress_g2(&infinity), None);
+        // x = 1 is not the abscissa of a point on the twist, since 5 + 4u is not a square.
+        let mut x_one = [0u8; 96];
+        x_one[0] = 0x80;
+        x_one[95] = 1;
+        assert_eq!(decompress_g2(&x_one), None);
+        // x.c1 = p is not canonical.
+        let mut x_p = [0u8; 96];
+        let mut p_bytes = Fp::get_fp_from_biguint(modulus() - 1u32).to_bytes_be();
+        p_bytes[47] += 1;
+        x_p[..48].copy_from_slice(&p_bytes);
+        x_p[0] |= 0x80;
+        assert_eq!(decompress_g2(&x_p), None);
+    }
+
+    #[test]
+    fn test_sqrt_fp() {
+        assert_eq!(sqrt_fp(Fp::zero()), Some(Fp::zero()));
+        let four = Fp::get_fp_from_biguint(BigUint::from(4u32));
+        let two = sqrt_fp(four).unwrap();
+        assert_eq!(two * two, four);
+        let x = Fp::get_fp_from_biguint(BigUint::from_str("1339506544944476473020471379941921221584933875938349620426543736416511423956333506472724655353366534992391756441569").unwrap());
+        let root = sqrt_fp(x * x).unwrap();
+        assert!(root == x || root == -x);
+        // -1 is not a quadratic residue since p = 3 mod 4.
+        assert_eq!(sqrt_fp(-Fp::one()), None);
+        assert_eq!(sqrt_fp(-(x * x)), None);
+    }
+
+    #[test]
+    fn test_sqrt_fp2() {
+        assert_eq!(sqrt_fp2(Fp2::zero()), Some(Fp2::zero()));
+        // -1 = u^2 is a square in Fp2, which takes the alpha == -1 branch.
+        let minus_one = Fp2([-Fp::one(), Fp::zero()]);
+        let root = sqrt_fp2(minus_one).unwrap();
+        assert_eq!(root * root, minus_one);
+        let g = G2Affine::generator();
+        for x in [g.x, g.y, g.x * g.y] {
+            let root = sqrt_fp2(x * x).unwrap();
+            assert!(root == x || root == Fp2::zero() - x);
+            // 1 + u is not a square, which makes the Fp6 tower.
+            assert_eq!(sqrt_fp2((x * x).mul_by_nonresidue()), None);
+        }
+        assert_eq!(sqrt_fp2(Fp2([Fp::one(), Fp::one()])), None);
+    }
+
+    fn fp_from_hex(s: &str) -> Fp {
+        Fp::get_fp_from_biguint(BigUint::parse_bytes(s.as_bytes(), 16).unwrap())
+    }
+
+    fn fp_from_str(s: &str) -> Fp {
+        Fp::get_fp_from_biguint(BigUint::from_str(s).unwrap())
+    }
+
+    /// Checks that the point is on the isogenous curve E': y^2 = x^3 + A'x + B'.
+    fn is_on_iso_curve(p: &G1Affine) -> bool {
+        p.y * p.y == p.x * p.x * p.x + SSWU_G1_A * p.x + SSWU_G1_B
+    }
+
+    #[test]
+    fn test_map_to_curve_g1() {
+        // u[0] and u[1] of the RFC 9380 BLS12381G1_XMD:SHA-256_SSWU_RO_ test vector for msg "" (appendix J.9.1). The RFC lists the points after the isogeny map, these are the SSWU outputs on E' which map to Q0 and Q1.
+        let vectors = [
+            (
+                "0ba14bd907ad64a016293ee7c2d276b8eae71f25a4b941eece7b0d89f17f75cb3ae5438a614fb61d6835ad59f29c564f",
+                "55754955408227568617581116371788714283404758263795455834132351126402323365162325552060479666088253437660847251596",
+                "2173074412286099169259181501768454018237592991155860708400028583529555379677602082117060416760548122489512570088969",
+            ),
+            (
+                "019b9bd7979f12657976de2884c7cce192b82c177c80e0ec604436a7f538d231552f0d96d9f7babe5fa3b19b3ff25ac9",
+                "3224051941405541723935048736768833730772974575404974299454707331723123813925460120809358256251868038466541413465281",
+                "1344716870190249038921121590452934139748336792432496991355805963859926998870982877556363595833212417386858497568489",
+            ),
+        ];
+        for (u, x, y) in vectors {
+            let u = fp_from_hex(u);
+            let p = map_to_curve_g1(u);
+            assert!(is_on_iso_curve(&p));
+            assert_eq!(sgn0_fp(p.y), sgn0_fp(u));
+            assert_eq!(p.x, fp_from_str(x));
+            assert_eq!(p.y, fp_from_str(y));
+        }
+    }
+
+    #[test]
+    fn test_map_to_curve_g1_exceptional() {
+        // u = 0 makes Z^2 * u^4 + Z * u^2 zero, so x1 = B'/(Z * A').
+        let p = map_to_curve_g1(Fp::zero());
+        assert!(is_on_iso_curve(&p));
+        assert!(!sgn0_fp(p.y));
+    }
+
+    #[test]
+    fn test_iso_map_g1_rfc_vectors() {
+        // (u, Q) pairs of the RFC 9380 BLS12381G1_XMD:SHA-256_SSWU_RO_ test vectors for msg "" and msg "abc" (appendix J.9.1), where Q = map_to_curve(u).
+        let vectors = [
+            (
+                "0ba14bd907ad64a016293ee7c2d276b8eae71f25a4b941eece7b0d89f17f75cb3ae5438a614fb61d6835ad59f29c564f",
+                "11a3cce7e1d90975990066b2f2643b9540fa40d6137780df4e753a8054d07580db3b7f1f03396333d4a359d1fe3766fe",
+                "0eeaf6d794e479e270da10fdaf768db4c96b650a74518fc67b04b03927754bac66f3ac720404f339ecdcc028afa091b7",
+            ),
+            (
+                "019b9bd7979f12657976de2884c7cce192b82c177c80e0ec604436a7f538d231552f0d96d9f7babe5fa3b19b3ff25ac9",
+                "160003aaf1632b13396dbad518effa00fff532f604de1a7fc2082ff4cb0afa2d63b2c32da1bef2bf6c5ca62dc6b72f9c",
+                "0d8bb2d14e20cf9f6036152ed386d79189415b6d015a20133acb4e019139b94e9c146aaad5817f866c95d609a361735e",
+            ),
+            (
+                "0d921c33f2bad966478a03ca35d05719bdf92d347557ea166e5bba579eea9b83e9afa5c088573c2281410369fbd32951",
+                "125435adce8e1cbd1c803e7123f45392dc6e326d292499c2c45c5865985fd74fe8f042ecdeeec5ecac80680d04317d80",
+                "0e8828948c989126595ee30e4f7c931cbd6f4570735624fd25aef2fa41d3f79cfb4b4ee7b7e55a8ce013af2a5ba20bf2",
+            ),
+            (
+                "003574a00b109ada2f26a37a91f9d1e740dffd8d69ec0c35e1e9f4652c7dba61123e9dd2e76c655d956e2b3462611139",
+                "11def93719829ecda3b46aa8c31fc3ac9c34b428982b898369608e4f042babee6c77ab9218aad5c87ba785481eff8ae4",
+                "0007c9cef122ccf2efd233d6eb9bfc680aa276652b0661f4f820a653cec1db7ff69899f8e52b8e92b025a12c822a6ce6",
+            ),
+        ];
+        for (u, x, y) in vectors {
+            let q = iso_map_g1(map_to_curve_g1(fp_from_hex(u)));
+            assert!(q.is_on_curve());
+            assert_eq!(q.x, fp_from_hex(x));
+            assert_eq!(q.y, fp_from_hex(y));
+        }
+    }
+
+    #[test]
+    fn test_iso_map_g1_kernel() {
+        // A point of E' whose x is a root of the x denominator, hence in the kernel of the isogeny.
+        let p = G1Affine {
+            x: fp_from_str("2512099095366387796245759085729510986367032014959769672734622752070562589059815523018960565849753051338812932816014"),
+            y: fp_from_str("3869440428665828672126986942449931708292407950503965278303393917520883965908367251300173570528123700077316614991402"),
+            infinity: false,
+        };
+        assert!(is_on_iso_curve(&p));
+        assert_eq!(iso_map_g1(p), G1Affine::identity());
+        assert_eq!(iso_map_g1(G1Affine::identity()), G1Affine::identity());
+    }
+
+    #[test]
+    fn test_clear_cofactor_g1() {
+        // (u0, u1, P) of the RFC 9380 BLS12381G1_XMD:SHA-256_SSWU_RO_ test vectors for msg "" and msg "abc", where P = clear_cofactor(Q0 + Q1).
+        let vectors = [
+            (
+                "0ba14bd907ad64a016293ee7c2d276b8eae71f25a4b941eece7b0d89f17f75cb3ae5438a614fb61d6835ad59f29c564f",
+                "019b9bd7979f12657976de2884c7cce192b82c177c80e0ec604436a7f538d231552f0d96d9f7babe5fa3b19b3ff25ac9",
+                "052926add2207b76ca4fa57a8734416c8dc95e24501772c814278700eed6d1e4e8cf62d9c09db0fac349612b759e79a1",
+                "08ba738453bfed09cb546dbb0783dbb3a5f1f566ed67bb6be0e8c67e2e81a4cc68ee29813bb7994998f3eae0c9c6a265",
+            ),
+            (
+                "0d921c33f2bad966478a03ca35d05719bdf92d347557ea166e5bba579eea9b83e9afa5c088573c2281410369fbd32951",
+                "003574a00b109ada2f26a37a91f9d1e740dffd8d69ec0c35e1e9f4652c7dba61123e9dd2e76c655d956e2b3462611139",
+                "03567bc5ef9c690c2ab2ecdf6a96ef1c139cc0b2f284dca0a9a7943388a49a3aee664ba5379a7655d3c68900be2f6903",
+                "0b9c15f3fe6e5cf4211f346271d7b01c8f3b28be689c8429c85b67af215533311f0b8dfaaa154fa6b88176c229f2885d",
+            ),
+        ];
+        for (u0, u1, x, y) in vectors {
+            let q0 = iso_map_g1(map_to_curve_g1(fp_from_hex(u0)));
+            let q1 = iso_map_g1(map_to_curve_g1(fp_from_hex(u1)));
+            assert!(!q0.is_in_subgroup());
+            let p = clear_cofactor_g1(q0 + q1);
+            assert!(p.is_in_subgroup());
+            assert_eq!(p.x, fp_from_hex(x));
+            assert_eq!(p.y, fp_from_hex(y));
+        }
+        assert_eq!(clear_cofactor_g1(G1Affine::identity()), G1Affine::identity());
+    }
+
+    #[test]
+    fn test_hex_formatting() {
+        assert_eq!(Fp::one().to_hex(), "0x1");
+        assert_eq!(format!("{:?}", Fp::one()), "Fp(0x1)");
+        // Limbs of p, which is reduced to zero
+        assert_eq!(Fp(get_u32_vec_from_literal(modulus())).to_hex(), "0x0");
+        assert_eq!(Fp2::one().to_hex(), ["0x1", "0x0"]);
+
+        let one_hex = Fp12::one().to_hex();
+        assert_eq!(one_hex.len(), 12);
+        assert_eq!(one_hex[0], "0x1");
+        assert!(one_hex[1..].iter().all(|h| h == "0x0"));
+        assert_eq!(format!("{:?}", Fp12::one()), format!("Fp12([0x1{}])", ", 0x0".repeat(11)));
+    }
+
+    #[test]
+    fn test_fp_from_biguint() {
+        assert_eq!(Fp::from(modulus() + 1u32), Fp::one());
+        assert_eq!(Fp::from(modulus()), Fp::zero());
+        let x = BigUint::from(5u32);
+        assert_eq!(Fp::from(x.clone()), Fp::try_from(&x).unwrap());
+        assert_eq!(Fp::from_canonical_biguint(modulus() - 1u32).unwrap(), Fp::zero() - Fp::one());
+
+        assert!(Fp::from_canonical_biguint(modulus()).is_err());
+        assert!(Fp::from_canonical_biguint(modulus() + 1u32).is_err());
+        assert!(Fp::try_from(&(modulus() + 1u32)).is_err());
+    }
+
+    #[test]
+    fn test_neg() {
+        assert_eq!(-Fp::zero(), Fp::zero());
+        assert_eq!(-Fp2::zero(), Fp2::zero());
+        assert_eq!(-Fp::one(), Fp::from(modulus() - 1u32));
+        assert_eq!(negate_fp(Fp::zero()).to_biguint(), modulus());
+
+        let x = Fp::from(BigUint::from_str("1216495682195235861952885506871698490232894470117269383940381148575524314493849307811227440691167647909822763414941").unwrap());
+        let y = Fp::from(modulus() - 1u32);
+        for x in [Fp::zero(), Fp::one(), x, y] {
+            assert_eq!(-(-x), x);
+            assert_eq!(x + -x, Fp::zero());
+        }
+        for x in [Fp2::zero(), Fp2::one(), Fp2([x, Fp::zero()]), Fp2([y, x])] {
+            assert_eq!(-(-x), x);
+            assert_eq!(x + -x, Fp2::zero());
+        }
+    }
+
+    #[test]
+    fn test_mont_mul_matches_schoolbook() {
+        assert_eq!(MODULUS_LIMBS, get_u32_vec_from_literal(modulus()));
+        let x = Fp::from(BigUint::from_str("1216495682195235861952885506871698490232894470117269383940381148575524314493849307811227440691167647909822763414941").unwrap());
+        let y = Fp::from(BigUint::from_str("2153848155426317245700560287567131132765685008362732985860101000686875894603366983854567186180519945327668975076337").unwrap());
+        let p_minus_1 = Fp::from(modulus() - 1u32);
+        let values = [Fp::zero(), Fp::one(), x, y, p_minus_1];
+        for a in values {
+            assert_eq!(Fp::from_mont(a.to_mont()), a);
+            for b in values {
+                assert_eq!(mul_fp_mont(a, b), mul_fp(a, b));
+                assert_eq!(Fp::from_mont(a.to_mont() * b.to_mont()), a * b);
+            }
+        }
+        assert_eq!(Fp::one().to_mont().0, get_u32_vec_from_literal((BigUint::from(1u32) << 384) % modulus()));
+        // Largest input of the reduction, (p - 1)^2.
+        let t = mul_u32_slices_12(&p_minus_1.0, &p_minus_1.0);
+        let r_inv = mod_inverse((BigUint::from(1u32) << 384) % modulus(), modulus());
+        assert_eq!(Fp(mont_reduce(&t)), Fp::from(p_minus_1.to_biguint() * p_minus_1.to_biguint() * r_inv));
+    }
+
+    #[test]
+    fn test_glv_scalar_mul() {
+        let lambda = glv_lambda();
+        assert_eq!(&lambda * &lambda + &lambda + 1u32, group_order());
+        let g = G1Affine::generator();
+        assert_eq!(phi_g1(&g), g.mul_scalar(&lambda));
+        assert_eq!(phi_g1(&G1Affine::identity()), G1Affine::identity());
+        let p = g.double() + g;
+        let k = BigUint::from_str("40535487838306347262513418034127355155416283154289396519919342425880917706245").unwrap();
+        for k in [BigUint::from(0u32), BigUint::from(1u32), BigUint::from(5u32), lambda.clone(), &lambda + 1u32, k, group_order() - 1u32] {
+            let (k1, k2) = glv_decompose(&k);
+            assert!(k1.bits() <= 128 && k2.bits() <= 128);
+            assert_eq!((&k1 + &k2 * &lambda) % group_order(), &k % group_order());
+            assert_eq!(g.mul_scalar_glv(&k), g.mul_scalar(&k));
+            assert_eq!(p.mul_scalar_glv(&k), p.mul_scalar(&k));
+        }
+    }
+
+    #[test]
+    fn test_g2_subgroup() {
+        let g2 = G2Affine::generator();
+        assert!(g2.is_in_subgroup());
+        assert_eq!(g2.mul_scalar(&BigUint::from(3u32)), g2.double() + g2);
+        assert_eq!(g2.mul_scalar(&(group_order() - 1u32)), -g2);
+        // A point of the twist with the smallest x = (i, 0), which is outside the G2 subgroup with overwhelming probability.
+        let b = Fp2([Fp::get_fp_from_biguint(BigUint::from(4u32)); 2]);
+        let p = (1u32..).find_map(|i| {
+            let x = Fp2([Fp::get_fp_from_biguint(BigUint::from(i)), Fp::zero()]);
+            sqrt_fp2(x * x * x + b).map(|y| G2Affine { x, y, infinity: false })
+        }).unwrap();
+        assert!(p.is_on_curve());
+        assert!(!p.is_in_subgroup());
+    }
+
+    #[test]
+    fn test_is_in_cyclotomic_subgroup() {
+        let fp = |i: u32| Fp::get_fp_from_biguint(BigUint::from(i) * 0x9e3779b9u32 + 7u32);
+        for k in 0..4 {
+            let x = Fp12((0..12).map(|i| fp(12 * k + i)).collect::<Vec<Fp>>().try_into().unwrap());
+            assert!(!x.is_in_cyclotomic_subgroup());
+            let f = x.final_exponentiate_easy_part();
+            assert!(f.is_in_cyclotomic_subgroup());
+            assert!(f.cyclotomic_square().is_in_cyclotomic_subgroup());
+            assert_eq!(f.cyclotomic_square(), f * f);
+            // Unitary, but not in the cyclotomic subgroup: only the p^6 - 1 power of the easy part.
+            let unitary = x.forbenius_map(6) / x;
+            assert_eq!(unitary * unitary.conjugate(), Fp12::one());
+            assert!(!unitary.is_in_cyclotomic_subgroup());
+        }
+        assert!(Fp12::one().is_in_cyclotomic_subgroup());
+        assert!(!Fp12([Fp::zero(); 12]).is_in_cyclotomic_subgroup());
+    }
+
+    #[test]
+    fn test_fp12_from_coeffs() {
+        let fp = |i: u32| Fp::get_fp_from_biguint(BigUint::from(i) * 0x9e3779b9u32 + 1u32);
+        let coeffs = (0..6).map(|i| Fp2::from_coeffs([fp(2 * i), fp(2 * i + 1)])).collect::<Vec<Fp2>>();
+        let x = Fp12::from_coeffs(coeffs.clone().try_into().unwrap());
+        let limbs = x.get_u32_slice();
+        for (i, limb) in limbs.iter().enumerate() {
+            assert_eq!(*limb, fp(i as u32).0);
+        }
+        let c0 = Fp6::from_coeffs([coeffs[0], coeffs[1], coeffs[2]]);
+        let c1 = Fp6::from_coeffs([coeffs[3], coeffs[4], coeffs[5]]);
+        assert_eq!(x.c0().get_u32_slice(), c0.get_u32_slice());
+        assert_eq!(x.c1().get_u32_slice(), c1.get_u32_slice());
+        assert_eq!([x.c0().get_u32_slice(), x.c1().get_u32_slice()].concat(), limbs.to_vec());
+        assert_eq!(Fp12::from_coeffs([Fp2::one(), Fp2::zero(), Fp2::zero(), Fp2::zero(), Fp2::zero(), Fp2::zero()]), Fp12::one());
+    }
+
+    #[test]
+    fn test_fp12_from_hex() {
+        let x = Fp12::from_coeffs((0..6).map(|i| Fp2::from_coeffs([Fp::from(modulus() - 1u32 - i as u32), Fp::from(BigUint::from(i as u32))])).collect::<Vec<Fp2>>().try_into().unwrap());
+        assert_eq!(Fp12::from_hex(&x.to_hex()).unwrap(), x);
+        assert_eq!(Fp::from_hex("1").unwrap(), Fp::one());
+        assert_eq!(Fp::from_hex(&format!("{:#098x}", modulus() - 1u32)).unwrap(), Fp::from(modulus() - 1u32));
+        assert!(Fp::from_hex(&format!("{:#x}", modulus())).is_err());
+        assert!(Fp::from_hex("0xg").is_err());
+        assert!(Fp::from_hex("").is_err());
+        assert!(Fp12::from_hex(&x.to_hex()[..11]).is_err());
+    }
+
+    #[test]
+    fn test_fp12_bytes_be_round_trip() {
+        let x = Fp12::from_coeffs((0..6).map(|i| Fp2::from_coeffs([Fp::from(modulus() - 1u32 - i as u32), Fp::from(BigUint::from(i as u32))])).collect::<Vec<Fp2>>().try_into().unwrap());
+        let bytes = x.to_bytes_be();
+        for (i, limbs) in x.get_u32_slice().iter().enumerate() {
+            assert_eq!(bytes[i*48..(i + 1)*48], Fp(*limbs).to_bytes_be());
+        }
+        assert_eq!(Fp12::from_bytes_be(&bytes), Some(x));
+        assert_eq!(Fp12::try_from(&bytes[..]).unwrap(), x);
+        assert_eq!(Fp12::one().to_bytes_be()[47], 1);
+        assert!(Fp12::try_from(&bytes[..575]).is_err());
+
+        // The modulus in the last component.
+        let mut non_canonical = bytes;
+        let p_bytes = modulus().to_bytes_be();
+        non_canonical[576 - p_bytes.len()..].copy_from_slice(&p_bytes);
+        assert_eq!(Fp12::from_bytes_be(&non_canonical), None);
+        assert!(Fp12::try_from(&non_canonical[..]).is_err());
+    }
+
+    #[test]
+    fn test_multiply_by_slice_widest_limbs() {
+        let check_carries = |x: &[u32; 12], y: u32, res: &[u32], carries: &[u32; 12]| {
+            let mut prev_carry = 0u64;
+            for i in 0..12 {
+                assert_eq!(x[i] as u128 * y as u128 + prev_carry as u128, res[i] as u128 + ((carries[i] as u128) << 32));
+                prev_carry = carries[i] as u64;
+            }
+        };
+        let p = get_u32_vec_from_literal(modulus());
+        let ones = [u32::MAX; 12];
+        for x in [p, ones] {
+            let (res, carries) = multiply_by_slice(&x, u32::MAX);
+            assert_eq!(BigUint::new(res.to_vec()), BigUint::new(x.to_vec()) * u32::MAX);
+            check_carries(&x, u32::MAX, &res, &carries);
+            assert_eq!(res[12], carries[11]);
+        }
+        // Every limb step at its widest, with the product still fitting in 12 limbs.
+        let mut x = ones;
+        x[11] = 0;
+        let (res, carries) = mul_u32_slice_u32(&x, u32::MAX);
+        assert_eq!(BigUint::new(res.to_vec()), BigUint::new(x.to_vec()) * u32::MAX);
+        check_carries(&x, u32::MAX, &res, &carries);
+        assert!(carries[..11].iter().all(|c| *c == u32::MAX - 1));
+        assert!(std::panic::catch_unwind(|| mul_u32_slice_u32(&p, u32::MAX)).is_err());
+    }
+
+    #[test]
+    fn test_karatsuba_matches_schoolbook_multiplication() {
+        let fp = |i: u32| Fp::from(BigUint::from(i + 7).pow(97) + 3u32);
+        let fp2 = |i: u32| Fp2([fp(2 * i), fp(2 * i + 1)]);
+        let (x, y) = (Fp12::from_coeffs(core::array::from_fn(|i| fp2(i as u32))), Fp12::from_coeffs(core::array::from_fn(|i| fp2(i as u32 + 6))));
+
+        // Karatsuba fp2, (x0 + x1)*(y0 + y1) - x0*y0 - x1*y1 for the imaginary part.
+        let (a, b) = (fp2(0), fp2(1));
+        let imaginary = (a.0[0] + a.0[1]) * (b.0[0] + b.0[1]) - a.0[0] * b.0[0] - a.0[1] * b.0[1];
+        assert_eq!(a * b, Fp2([a.0[0] * b.0[0] - a.0[1] * b.0[1], imaginary]));
+
+        // Schoolbook fp6, with v^3 the fp2 non residue.
+        let fp6_schoolbook = |x: Fp6, y: Fp6| {
+            let (a, b) = ([0, 1, 2].map(|i| Fp2([x.0[2*i], x.0[2*i+1]])), [0, 1, 2].map(|i| Fp2([y.0[2*i], y.0[2*i+1]])));
+            Fp6::from_coeffs([
+                a[0]*b[0] + (a[1]*b[2] + a[2]*b[1]).mul_by_nonresidue(),
+                a[0]*b[1] + a[1]*b[0] + (a[2]*b[2]).mul_by_nonresidue(),
+                a[0]*b[2] + a[1]*b[1] + a[2]*b[0],
+            ])
+        };
+        assert_eq!((x.c0() * y.c1()).get_u32_slice(), fp6_schoolbook(x.c0(), y.c1()).get_u32_slice());
+
+        // Schoolbook fp12, with w^2 = v.
+        let c0 = fp6_schoolbook(x.c0(), y.c0()) + mul_by_nonresidue(fp6_schoolbook(x.c1(), y.c1()).0);
+        let c1 = fp6_schoolbook(x.c0(), y.c1()) + fp6_schoolbook(x.c1(), y.c0());
+        assert_eq!(x * y, Fp12([c0.0, c1.0].concat().try_into().unwrap()));
+    }
+
+    #[test]
+    fn test_msm_g1() {
+        let g = G1Affine::generator();
+        let points = (0..40).scan(g, |p, _| {
+            *p = *p + g.double();
+            Some(*p)
+        }).collect::<Vec<G1Affine>>();
+        let k = BigUint::from_str("40535487838306347262513418034127355155416283154289396519919342425880917706245").unwrap();
+        let scalars = (0..40u32).map(|i| (&k * (i + 1) + i) % group_order()).collect::<Vec<BigUint>>();
+        // Small and large batches use different window sizes.
+        for n in [0, 1, 2, 5, 40] {
+            let expected = points[..n].iter().zip(&scalars[..n]).fold(G1Affine::identity(), |acc, (p, k)| acc + p.mul_scalar(k));
+            assert_eq!(msm_g1(&points[..n], &scalars[..n]), expected);
+        }
+        // Repeated points and zero scalars.
+        let scalars = [BigUint::from(3u32), BigUint::from(0u32), group_order() - 3u32];
+        assert_eq!(msm_g1(&[g, g.double(), g], &scalars), G1Affine::identity());
+    }
+
+    #[test]
+    fn test_fp12_eq_reduced() {
+        // One with its first component represented as p + 1
+        let mut x = Fp12::one();
+        x.0[0] = Fp(get_u32_vec_from_literal(modulus() + 1u32));
+        assert_ne!(x.get_u32_slice(), Fp12::one().get_u32_slice());
+        assert!(x.eq_reduced(&Fp12::one()));
+        assert_eq!(x, Fp12::one());
+
+        let mut y = Fp12::one();
+        y.0[11] = Fp::one();
+        assert!(!y.eq_reduced(&Fp12::one()));
+        assert_ne!(x, y);
+    }
+
+    #[test]
+    fn test_cyclotomic_inverse() {
+        for i in 0..4u32 {
+            let x = Fp12((0..12u32).map(|j| Fp::get_fp_from_biguint(BigUint::from(i*12 + j + 1)))
+                .collect::<Vec<Fp>>().try_into().unwrap());
+            // The conjugate is not the inverse outside the cyclotomic subgroup
+            assert_ne!(x * x.cyclotomic_inverse(), Fp12::one());
+
+            let easy = x.final_exponentiate_easy_part();
+            assert_eq!(easy * easy.cyclotomic_inverse(), Fp12::one());
+            assert_eq!(easy.cyclotomic_inverse(), easy.invert());
+        }
+    }
+
+    #[test]
+    fn test_conjugate_assign() {
+        for i in 0..4u32 {
+            let x = Fp12((0..12u32).map(|j| Fp::get_fp_from_biguint(BigUint::from(i*12 + j + 1)))
+                .collect::<Vec<Fp>>().try_into().unwrap());
+            let mut y = x;
+            y.conjugate_assign();
+            assert_eq!(y.get_u32_slice(), x.conjugate().get_u32_slice());
+            assert_eq!(final_exponentiate_native(x), x.final_exponentiate());
+        }
+    }
+
+    #[test]
+    fn test_add_u32_slices_carry_out() {
+        let x = [u32::MAX; 24];
+        let mut y = [0u32; 24];
+        y[0] = 1;
+        let (res, carries) = add_u32_slices(&x, &y);
+        assert_eq!(res, [0u32; 24]);
+        assert_eq!(carries, [1u32; 24]);
+
+        let (res, carries) = add_u32_slices(&y, &y);
+        assert_eq!(res[0], 2);
+        assert_eq!(carries[23], 0);
+    }
+
+    #[test]
+    fn test_add_u32_slices_12_carries() {
+        // Checks every limb of the long addition and the carry out against the sum as a BigUint.
+        let check = |x: [u32; 12], y: [u32; 12]| {
+            let (res, carries) = add_u32_slices_12(&x, &y);
+            for i in 0..12 {
+                let carry_in = if i == 0 { 0 } else { carries[i - 1] as u64 };
+                assert!(carries[i] <= 1);
+                assert_eq!(x[i] as u64 + y[i] as u64 + carry_in, res[i] as u64 + ((carries[i] as u64) << 32));
+            }
+            let sum = BigUint::new(x.to_vec()) + BigUint::new(y.to_vec());
+            assert_eq!(BigUint::new(res.to_vec()) + (BigUint::from(carries[11]) << 384), sum);
+            (res, carries)
+        };
+
+        let mut one = [0u32; 12];
+        one[0] = 1;
+        let (res, carries) = check([u32::MAX; 12], one);
+        assert_eq!(res, [0u32; 12]);
+        assert_eq!(carries, [1u32; 12]);
+
+        let (res, carries) = check([u32::MAX; 12], [u32::MAX; 12]);
+        assert_eq!(res[0], u32::MAX - 1);
+        assert_eq!(res[1..], [u32::MAX; 11]);
+        assert_eq!(carries, [1u32; 12]);
+
+        let (res, carries) = check([0xAAAAAAAA; 12], [0x55555555; 12]);
+        assert_eq!(res, [u32::MAX; 12]);
+        assert_eq!(carries, [0u32; 12]);
+        let (res, carries) = check(res, one);
+        assert_eq!(res, [0u32; 12]);
+        assert_eq!(carries, [1u32; 12]);
+
+        let alternating: [u32; 12] = core::array::from_fn(|i| if i % 2 == 0 { u32::MAX } else { 0 });
+        let (res, carries) = check(alternating, one);
+        let expected_carries: [u32; 12] = core::array::from_fn(|i| if i == 0 { 1 } else { 0 });
+        assert_eq!(carries, expected_carries);
+        assert_eq!(res[1], 1);
+        check(alternating, alternating);
+
+        // (p - 1) + 1 == p, without a carry out
+        let mut p_minus_one = MODULUS_LIMBS;
+        p_minus_one[0] -= 1;
+        let (res, carries) = check(p_minus_one, one);
+        assert_eq!(res, MODULUS_LIMBS);
+        assert_eq!(carries[11], 0);
+    }
+
+    #[test]
+    fn test_sub_u32_slices_borrow_out() {
+        let mut x = [0u32; 24];
+        let mut y = [0u32; 24];
+        y[0] = 1;
+        let (res, borrows, borrow_out) = sub_u32_slices(&x, &y);
+        assert_eq!(res, [u32::MAX; 24]);
+        assert_eq!(borrows, [1u32; 24]);
+        assert!(borrow_out);
+
+        // A borrow into a limb of y equal to u32::MAX
+        y[1] = u32::MAX;
+        x[2] = 1;
+        let (res, borrows, borrow_out) = sub_u32_slices(&x, &y);
+        let expected = BigUint::new(x.to_vec()) - BigUint::new(y.to_vec());
+        assert_eq!(BigUint::new(res.to_vec()), expected);
+        assert_eq!(borrows[1], 1);
+        assert_eq!(borrows[2], 0);
+        assert!(!borrow_out);
+
+        let (res, _, borrow_out) = sub_u32_slices(&x, &x);
+        assert_eq!(res, [0u32; 24]);
+        assert!(!borrow_out);
+    }
+
+    #[test]
+    fn test_line_functions_fold_to_miller_loop() {
+        let p = G1Affine::generator();
+        let q = G2Affine::generator();
+        let mut t = G2Projective::from_affine(&q);
+        let mut f = Fp12::one();
+        for i in (0..get_bls_12_381_parameter().bits() - 1).rev() {
+            let (t_double, line) = line_double(&t, &p);
+            t = t_double;
+            f = mul_fp_12(f, line);
+            if get_bls_12_381_parameter().bit(i) {
+                let (t_add, line) = line_add(&t, &q, &p);
+                t = t_add;
+                f = mul_fp_12(f, line);
+            }
+            if i != 0 {
+                f = mul_fp_12(f, f);
+            }
+        }
+        assert_eq!(f.conjugate(), miller_loop(p.x, p.y, q.x, q.y, Fp2::one()));
+
+        // The accumulator ends at [|x|]q, with x the bls12-381 parameter.
+        let mut expected = G2Affine::identity();
+        for i in (0..get_bls_12_381_parameter().bits()).rev() {
+            expected = expected.double();
+            if get_bls_12_381_parameter().bit(i) {
+                expected = expected + q;
+            }
+        }
+        assert_eq!(t.to_affine(), expected);
+    }
+
+    #[test]
+    fn test_limb_repr_lengths() {
+        let x = Fp12(core::array::from_fn(|i| Fp(core::array::from_fn(|j| (i*12 + j) as u32))));
+        let fp2 = Fp2([x.0[0], x.0[1]]);
+        let fp6 = Fp6(x.0[..6].try_into().unwrap());
+
+        assert_eq!((x.0[0].limbs().len(), Fp::num_limbs()), (12, 12));
+        assert_eq!((fp2.limbs().len(), Fp2::num_limbs()), (24, 24));
+        assert_eq!((fp6.limbs().len(), Fp6::num_limbs()), (72, 72));
+        assert_eq!((x.limbs().len(), Fp12::num_limbs()), (144, 144));
+
+        // Same order as the flattened slices of each type.
+        assert_eq!(x.0[0].limbs(), x.0[0].get_u32_slice().to_vec());
+        assert_eq!(fp2.limbs(), fp2.get_u32_slice().concat());
+        assert_eq!(fp6.limbs(), fp6.get_u32_slice().concat());
+        assert_eq!(x.limbs(), x.get_u32_slice().concat());
+        assert_eq!(LimbRepr::get_u32_slice(&x).len(), 12);
+    }
+
+    #[test]
+    fn test_scalar_bits() {
+        let k = group_order() - 1u32;
+        let bits = ScalarBits::new(&k);
+        assert!((0..256).all(|i| bits.bit(i) == k.bit(i as u64)));
+        assert!((0..256).all(|i| !ScalarBits::new(&BigUint::from(0u32)).bit(i)));
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_scalar_bits_zeroized_on_drop() {
+        use zeroize::Zeroize;
+
+        let k = group_order() - 1u32;
+        let mut bits = ScalarBits::new(&k);
+        assert!(bits.as_array().contains(&1));
+        // The destructor wipes the buffer with `zeroize`.
+        bits.zeroize();
+        assert!(bits.as_array().iter().all(|b| *b == 0));
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn test_zeroize_biguint() {
+        let mut k = group_order() - 1u32;
+        super::zeroize_biguint(&mut k);
+        assert_eq!(k, BigUint::from(0u32));
+        let (k1, k2) = glv_decompose(&(group_order() - 1u32));
+        assert_eq!((k1 + k2 * glv_lambda()) % group_order(), group_order() - 1u32);
+    }
 }
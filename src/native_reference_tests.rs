@@ -0,0 +1,298 @@
+//! Property tests of the native Fp12 arithmetic, sparse multiplications, final exponentiation and its intermediates, Miller loop lines and projective group law against the `ark-bls12-381` implementation.
+//! Elements are converted between both representations through their 48-byte big-endian encodings.
+
+use ark_bls12_381::{Bls12_381, Config, Fq, Fq12, Fq2, Fq6, Fr, G1Affine as ArkG1Affine, G1Projective as ArkG1Projective, G2Affine as ArkG2Affine, G2Projective as ArkG2Projective};
+use ark_ec::{bls12::G2Prepared, pairing::{MillerLoopOutput, Pairing}, AffineRepr, CurveGroup, Group};
+use ark_ff::{BigInteger, CyclotomicMultSubgroup, Field, PrimeField, Zero};
+use num_bigint::BigUint;
+use proptest::prelude::*;
+
+#[cfg(feature = "prover")]
+use crate::final_exponentiate::compute_intermediates;
+use crate::native::{final_exponentiate_native, get_bls_12_381_parameter, line_add, line_double, modulus, Fp, Fp12, Fp2, G1Affine, G1Projective, G2Affine, G2Projective};
+
+fn fp_to_ark(x: &Fp) -> Fq {
+    Fq::from_be_bytes_mod_order(&x.to_bytes_be())
+}
+
+fn fp2_to_ark(x: &Fp2) -> Fq2 {
+    Fq2::new(fp_to_ark(&x.0[0]), fp_to_ark(&x.0[1]))
+}
+
+fn fp_from_ark(x: &Fq) -> Fp {
+    Fp::from_bytes_be(&x.into_bigint().to_bytes_be().try_into().unwrap()).unwrap()
+}
+
+fn fp2_from_ark(x: &Fq2) -> Fp2 {
+    Fp2([fp_from_ark(&x.c0), fp_from_ark(&x.c1)])
+}
+
+pub(crate) fn g1_from_ark(p: &ArkG1Affine) -> G1Affine {
+    match p.xy() {
+        Some((x, y)) => G1Affine { x: fp_from_ark(x), y: fp_from_ark(y), infinity: false },
+        None => G1Affine::identity(),
+    }
+}
+
+pub(crate) fn g2_from_ark(p: &ArkG2Affine) -> G2Affine {
+    match p.xy() {
+        Some((x, y)) => G2Affine { x: fp2_from_ark(x), y: fp2_from_ark(y), infinity: false },
+        None => G2Affine::identity(),
+    }
+}
+
+fn fp12_to_ark(x: &Fp12) -> Fq12 {
+    let c = x.0.iter().map(fp_to_ark).collect::<Vec<Fq>>();
+    let fq6 = |c: &[Fq]| Fq6::new(Fq2::new(c[0], c[1]), Fq2::new(c[2], c[3]), Fq2::new(c[4], c[5]));
+    Fq12::new(fq6(&c[0..6]), fq6(&c[6..12]))
+}
+
+pub(crate) fn fp12_from_ark(x: &Fq12) -> Fp12 {
+    let c = [x.c0, x.c1].iter().flat_map(|c| [c.c0, c.c1, c.c2]).flat_map(|c| [c.c0, c.c1]).collect::<Vec<Fq>>();
+    Fp12(c.iter().map(fp_from_ark).collect::<Vec<Fp>>().try_into().unwrap())
+}
+
+/// Uniform fp elements, along with the boundary values near zero and near the modulus.
+fn fp_strategy() -> impl Strategy<Value = Fp> {
+    prop_oneof![
+        any::<[u8; 48]>().prop_map(|bytes| Fp::get_fp_from_biguint(BigUint::from_bytes_be(&bytes) % modulus())),
+        (0u32..16).prop_map(|k| Fp::get_fp_from_biguint(modulus() - 1u32 - k)),
+        (0u32..16).prop_map(|k| Fp::get_fp_from_biguint(BigUint::from(k))),
+    ]
+}
+
+fn fp2_strategy() -> impl Strategy<Value = Fp2> {
+    prop::array::uniform2(fp_strategy()).prop_map(Fp2)
+}
+
+fn fp12_strategy() -> impl Strategy<Value = Fp12> {
+    prop::array::uniform12(fp_strategy()).prop_map(Fp12)
+}
+
+/// The Fp12 element that is zero everywhere except for the given fp2 coefficients.
+fn sparse_fp12(coeffs: [(usize, Fp2); 3]) -> Fp12 {
+    let mut x = [Fp::zero(); 12];
+    for (i, c) in coeffs {
+        x[i*2..i*2+2].copy_from_slice(&c.0);
+    }
+    Fp12(x)
+}
+
+/// Maps a non-zero element to the cyclotomic subgroup, with `f^((p^6 - 1)(p^2 + 1))`.
+fn cyclotomic_strategy() -> impl Strategy<Value = Fp12> {
+    fp12_strategy()
+        .prop_filter_map("zero has no inverse", |x| fp12_to_ark(&x).inverse().map(|inv| (x, inv)))
+        .prop_map(|(x, inv)| {
+            let mut t = fp12_to_ark(&x);
+            t.conjugate_in_place();
+            t *= inv;
+            let mut t_p2 = t;
+            t_p2.frobenius_map_in_place(2);
+            fp12_from_ark(&(t_p2 * t))
+        })
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    #[test]
+    fn test_fp12_conversion_round_trip(x in fp12_strategy()) {
+        prop_assert_eq!(fp12_from_ark(&fp12_to_ark(&x)).get_u32_slice(), x.get_u32_slice());
+    }
+
+    #[test]
+    fn test_fp12_mul_matches_reference(x in fp12_strategy(), y in fp12_strategy()) {
+        prop_assert_eq!(x * y, fp12_from_ark(&(fp12_to_ark(&x) * fp12_to_ark(&y))));
+    }
+
+    #[test]
+    fn test_fp12_forbenius_map_matches_reference(x in fp12_strategy(), pow in 0usize..12) {
+        let mut expected = fp12_to_ark(&x);
+        expected.frobenius_map_in_place(pow);
+        prop_assert_eq!(x.forbenius_map(pow), fp12_from_ark(&expected));
+    }
+
+    #[test]
+    fn test_fp12_cyclotomic_square_matches_reference(x in cyclotomic_strategy()) {
+        let expected = fp12_to_ark(&x).cyclotomic_square();
+        prop_assert_eq!(x.cyclotomic_square(), fp12_from_ark(&expected));
+        prop_assert_eq!(x.cyclotomic_square(), x * x);
+    }
+
+    #[test]
+    fn test_fp12_cyclotomic_exponent_matches_reference(x in cyclotomic_strategy()) {
+        let exp = get_bls_12_381_parameter().to_u64_digits();
+        let expected = fp12_to_ark(&x).cyclotomic_exp(&exp);
+        prop_assert_eq!(x.cyclotocmic_exponent(), fp12_from_ark(&expected));
+        prop_assert_eq!(x.cyclotocmic_exponent(), fp12_from_ark(&fp12_to_ark(&x).pow(&exp)));
+    }
+
+    #[test]
+    fn test_final_exponentiate_native_matches_reference(x in fp12_strategy()) {
+        // The reference returns None for zero, which has no final exponentiation.
+        let expected = Bls12_381::final_exponentiation(MillerLoopOutput(fp12_to_ark(&x)));
+        prop_assume!(expected.is_some());
+        prop_assert_eq!(final_exponentiate_native(x), fp12_from_ark(&expected.unwrap().0));
+    }
+
+    #[test]
+    fn test_fp12_multiply_by_014_matches_reference(x in fp12_strategy(), o0 in fp2_strategy(), o1 in fp2_strategy(), o4 in fp2_strategy()) {
+        let mut expected = fp12_to_ark(&x);
+        expected.mul_by_014(&fp2_to_ark(&o0), &fp2_to_ark(&o1), &fp2_to_ark(&o4));
+        prop_assert_eq!(x.multiply_by_014(o0, o1, o4), fp12_from_ark(&expected));
+        prop_assert_eq!(x.multiply_by_014(o0, o1, o4), x * sparse_fp12([(0, o0), (1, o1), (4, o4)]));
+    }
+
+    #[test]
+    fn test_fp12_multiply_by_034_matches_reference(x in fp12_strategy(), o0 in fp2_strategy(), o3 in fp2_strategy(), o4 in fp2_strategy()) {
+        let mut expected = fp12_to_ark(&x);
+        expected.mul_by_034(&fp2_to_ark(&o0), &fp2_to_ark(&o3), &fp2_to_ark(&o4));
+        prop_assert_eq!(x.multiply_by_034(o0, o3, o4), fp12_from_ark(&expected));
+        prop_assert_eq!(x.multiply_by_034(o0, o3, o4), x * sparse_fp12([(0, o0), (3, o3), (4, o4)]));
+    }
+}
+
+#[cfg(feature = "prover")]
+/// T0..T31 of the final exponentiation (see [compute_intermediates](crate::final_exponentiate::compute_intermediates)) with the reference arithmetic. The cyclotomic exponent is `x^|u|` for the curve parameter `u`, which is negative, so that the conjugates after it give `x^u`.
+fn ark_final_exponentiate_steps(x: &Fq12) -> [Fq12; 32] {
+    let exp = get_bls_12_381_parameter().to_u64_digits();
+    let forbenius_map = |x: &Fq12, pow: usize| {
+        let mut res = *x;
+        res.frobenius_map_in_place(pow);
+        res
+    };
+    let conjugate = |x: &Fq12| {
+        let mut res = *x;
+        res.conjugate_in_place();
+        res
+    };
+    let mut t = [Fq12::zero(); 32];
+    t[0] = forbenius_map(x, 6);
+    t[1] = t[0] * x.inverse().unwrap();
+    t[2] = forbenius_map(&t[1], 2);
+    t[3] = t[2] * t[1];
+    t[4] = t[3].cyclotomic_exp(&exp);
+    t[5] = conjugate(&t[4]);
+    t[6] = t[3].cyclotomic_square();
+    t[7] = conjugate(&t[6]);
+    t[8] = t[7] * t[5];
+    t[9] = t[8].cyclotomic_exp(&exp);
+    t[10] = conjugate(&t[9]);
+    t[11] = t[10].cyclotomic_exp(&exp);
+    t[12] = conjugate(&t[11]);
+    t[13] = t[12].cyclotomic_exp(&exp);
+    t[14] = conjugate(&t[13]);
+    t[15] = t[5].cyclotomic_square();
+    t[16] = t[14] * t[15];
+    t[17] = t[16].cyclotomic_exp(&exp);
+    t[18] = conjugate(&t[17]);
+    t[19] = t[5] * t[12];
+    t[20] = forbenius_map(&t[19], 2);
+    t[21] = t[10] * t[3];
+    t[22] = forbenius_map(&t[21], 3);
+    t[23] = conjugate(&t[3]);
+    t[24] = t[16] * t[23];
+    t[25] = forbenius_map(&t[24], 1);
+    t[26] = conjugate(&t[8]);
+    t[27] = t[18] * t[26];
+    t[28] = t[27] * t[3];
+    t[29] = t[20] * t[22];
+    t[30] = t[29] * t[25];
+    t[31] = t[30] * t[28];
+    t
+}
+
+#[cfg(feature = "prover")]
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(8))]
+
+    #[test]
+    fn test_final_exp_intermediates_match_reference(x in fp12_strategy()) {
+        let expected = Bls12_381::final_exponentiation(MillerLoopOutput(fp12_to_ark(&x)));
+        prop_assume!(expected.is_some());
+        let reference = ark_final_exponentiate_steps(&fp12_to_ark(&x));
+        prop_assert_eq!(reference[31], expected.unwrap().0);
+        let intermediates = compute_intermediates(x);
+        for i in 0..32 {
+            prop_assert_eq!(intermediates[i], fp12_from_ark(&reference[i]), "T{} differs from the reference", i);
+        }
+    }
+}
+
+/// Steps of a random walk on G1 and G2: whether the accumulator is doubled first, the scalar of the multiple of the generator added to it, and whether it is added as an affine point (`add_mixed`) or a projective one. The zero scalar adds the point at infinity.
+fn group_law_steps_strategy() -> impl Strategy<Value = Vec<(bool, u64, bool)>> {
+    prop::collection::vec((any::<bool>(), prop_oneof![Just(0u64), any::<u64>()], any::<bool>()), 1..8)
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn test_g1_projective_matches_reference(steps in group_law_steps_strategy()) {
+        let mut acc = G1Projective::identity();
+        let mut expected = ArkG1Projective::zero();
+        for (double, k, mixed) in steps {
+            if double {
+                acc = acc.double();
+                expected.double_in_place();
+            }
+            let p = (ArkG1Projective::generator() * Fr::from(k)).into_affine();
+            let q = g1_from_ark(&p);
+            acc = if mixed { acc.add_mixed(&q) } else { acc + G1Projective::from_affine(&q) };
+            expected += p;
+            prop_assert_eq!(acc.to_affine(), g1_from_ark(&expected.into_affine()));
+        }
+        // Adding the negation gives the point at infinity, and doubling it stays there.
+        let neg = -acc.to_affine();
+        prop_assert!(acc.add_mixed(&neg).to_affine().infinity);
+        prop_assert!((acc + G1Projective::from_affine(&neg)).double().to_affine().infinity);
+    }
+
+    #[test]
+    fn test_g2_projective_matches_reference(steps in group_law_steps_strategy()) {
+        let mut acc = G2Projective::identity();
+        let mut expected = ArkG2Projective::zero();
+        for (double, k, mixed) in steps {
+            if double {
+                acc = acc.double();
+                expected.double_in_place();
+            }
+            let p = (ArkG2Projective::generator() * Fr::from(k)).into_affine();
+            let q = g2_from_ark(&p);
+            acc = if mixed { acc.add_mixed(&q) } else { acc + G2Projective::from_affine(&q) };
+            expected += p;
+            prop_assert_eq!(acc.to_affine(), g2_from_ark(&expected.into_affine()));
+        }
+        let neg = -acc.to_affine();
+        prop_assert!(acc.add_mixed(&neg).to_affine().infinity);
+        prop_assert!((acc + G2Projective::from_affine(&neg)).double().to_affine().infinity);
+    }
+}
+
+/// Line value of the reference Miller loop, i.e. the sparse element `Fq12::mul_by_014` multiplies by.
+fn ark_line_evaluation(ell: &(Fq2, Fq2, Fq2), p: &G1Affine) -> Fq12 {
+    let (px, py) = (fp_to_ark(&p.x), fp_to_ark(&p.y));
+    let c1 = Fq2::new(ell.1.c0 * px, ell.1.c1 * px);
+    let c2 = Fq2::new(ell.2.c0 * py, ell.2.c1 * py);
+    Fq12::new(Fq6::new(ell.0, c1, Fq2::zero()), Fq6::new(Fq2::zero(), c2, Fq2::zero()))
+}
+
+#[test]
+fn test_line_functions_match_reference() {
+    let p = G1Affine::generator();
+    let q = G2Affine::generator();
+    let prepared = G2Prepared::<Config>::from(ArkG2Affine::new(fp2_to_ark(&q.x), fp2_to_ark(&q.y)));
+    let mut ell_coeffs = prepared.ell_coeffs.iter();
+    let mut t = G2Projective::from_affine(&q);
+    for i in (0..get_bls_12_381_parameter().bits() - 1).rev() {
+        let (t_double, line) = line_double(&t, &p);
+        assert_eq!(fp12_to_ark(&line), ark_line_evaluation(ell_coeffs.next().unwrap(), &p));
+        t = t_double;
+        if get_bls_12_381_parameter().bit(i) {
+            let (t_add, line) = line_add(&t, &q, &p);
+            assert_eq!(fp12_to_ark(&line), ark_line_evaluation(ell_coeffs.next().unwrap(), &p));
+            t = t_add;
+        }
+    }
+    assert!(ell_coeffs.next().is_none());
+}
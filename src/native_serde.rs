@@ -0,0 +1,224 @@
+//! `serde` support for the native field types [Fp], [Fp2], [Fp6] and [Fp12], behind the `serde` feature.
+//! Human-readable formats (e.g. JSON) get the canonical big-endian hex of every fp component, `0x` prefixed and 96 digits long, as a string for [Fp] and as a tuple of strings for the extensions. Binary formats (e.g. bincode) get the 48-byte big-endian encodings of the components, concatenated into one byte string.
+//! Deserialization rejects values which are not less than the modulus, and hex strings or byte strings of the wrong length.
+
+use std::fmt;
+
+use num_bigint::BigUint;
+use serde::{
+    de::{self, SeqAccess, Visitor},
+    ser::SerializeTuple,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+use crate::native::{Fp, Fp12, Fp2, Fp6};
+
+const FP_BYTES: usize = 48;
+
+/// Canonical encoding of `x`, which is reduced first since the limbs of an fp element may hold a value above the modulus.
+fn fp_to_bytes(x: &Fp) -> [u8; FP_BYTES] {
+    Fp::from(x.to_biguint()).to_bytes_be()
+}
+
+fn fp_from_bytes<E: de::Error>(bytes: &[u8]) -> Result<Fp, E> {
+    let bytes: [u8; FP_BYTES] = bytes.try_into().map_err(|_| E::invalid_length(bytes.len(), &"48 bytes"))?;
+    Fp::from_bytes_be(&bytes).ok_or_else(|| E::custom(format!("{:#x} is not a canonical fp element", BigUint::from_bytes_be(&bytes))))
+}
+
+fn fp_to_hex(x: &Fp) -> String {
+    format!("0x{}", hex::encode(fp_to_bytes(x)))
+}
+
+fn fp_from_hex<E: de::Error>(s: &str) -> Result<Fp, E> {
+    let digits = s.strip_prefix("0x").ok_or_else(|| E::invalid_value(de::Unexpected::Str(s), &"a 0x prefixed hex string"))?;
+    if digits.len() != 2 * FP_BYTES {
+        return Err(E::invalid_value(de::Unexpected::Str(s), &"96 hex digits"));
+    }
+    fp_from_bytes(&hex::decode(digits).map_err(E::custom)?)
+}
+
+fn serialize_fps<S: Serializer>(fps: &[Fp], serializer: S) -> Result<S::Ok, S::Error> {
+    if !serializer.is_human_readable() {
+        return serializer.serialize_bytes(&fps.iter().flat_map(fp_to_bytes).collect::<Vec<u8>>());
+    }
+    if let [fp] = fps {
+        return serializer.serialize_str(&fp_to_hex(fp));
+    }
+    let mut tuple = serializer.serialize_tuple(fps.len())?;
+    for fp in fps {
+        tuple.serialize_element(&fp_to_hex(fp))?;
+    }
+    tuple.end()
+}
+
+/// Visits the `N` fp components written by [serialize_fps].
+struct FpsVisitor<const N: usize>;
+
+impl<'de, const N: usize> Visitor<'de> for FpsVisitor<N> {
+    type Value = [Fp; N];
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        if N == 1 {
+            write!(formatter, "a hex string or {} bytes", FP_BYTES)
+        } else {
+            write!(formatter, "{} hex strings or {} bytes", N, N * FP_BYTES)
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<[Fp; N], E> {
+        if N != 1 {
+            return Err(E::invalid_type(de::Unexpected::Str(v), &self));
+        }
+        Ok([fp_from_hex(v)?; N])
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<[Fp; N], A::Error> {
+        let mut res = [Fp::zero(); N];
+        for (i, fp) in res.iter_mut().enumerate() {
+            let s: String = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?;
+            *fp = fp_from_hex(&s)?;
+        }
+        Ok(res)
+    }
+
+    fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<[Fp; N], E> {
+        if v.len() != N * FP_BYTES {
+            return Err(E::invalid_length(v.len(), &self));
+        }
+        let mut res = [Fp::zero(); N];
+        for (fp, bytes) in res.iter_mut().zip(v.chunks(FP_BYTES)) {
+            *fp = fp_from_bytes(bytes)?;
+        }
+        Ok(res)
+    }
+}
+
+fn deserialize_fps<'de, D: Deserializer<'de>, const N: usize>(deserializer: D) -> Result<[Fp; N], D::Error> {
+    if !deserializer.is_human_readable() {
+        deserializer.deserialize_bytes(FpsVisitor::<N>)
+    } else if N == 1 {
+        deserializer.deserialize_str(FpsVisitor::<N>)
+    } else {
+        deserializer.deserialize_tuple(N, FpsVisitor::<N>)
+    }
+}
+
+impl Serialize for Fp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_fps(&[*self], serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_fps::<D, 1>(deserializer).map(|[x]| x)
+    }
+}
+
+impl Serialize for Fp2 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_fps(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fp2 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_fps(deserializer).map(Fp2)
+    }
+}
+
+impl Serialize for Fp6 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_fps(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fp6 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_fps(deserializer).map(Fp6)
+    }
+}
+
+impl Serialize for Fp12 {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_fps(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fp12 {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_fps(deserializer).map(Fp12)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use proptest::prelude::*;
+
+    use crate::native::{modulus, Fp, Fp12, Fp2, Fp6};
+
+    fn fp_strategy() -> impl Strategy<Value = Fp> {
+        any::<[u8; 48]>().prop_map(|bytes| Fp::get_fp_from_biguint(BigUint::from_bytes_be(&bytes) % modulus()))
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn test_fp_serde_round_trip(x in fp_strategy()) {
+            let json = serde_json::to_string(&x).unwrap();
+            prop_assert_eq!(json.len(), 2 + 2 + 96);
+            prop_assert_eq!(serde_json::from_str::<Fp>(&json).unwrap(), x);
+            let bytes = bincode::serialize(&x).unwrap();
+            prop_assert_eq!(bincode::deserialize::<Fp>(&bytes).unwrap(), x);
+        }
+
+        #[test]
+        fn test_fp2_fp6_serde_round_trip(x in prop::array::uniform2(fp_strategy()), y in prop::array::uniform6(fp_strategy())) {
+            let x = Fp2(x);
+            prop_assert_eq!(serde_json::from_str::<Fp2>(&serde_json::to_string(&x).unwrap()).unwrap(), x);
+            prop_assert_eq!(bincode::deserialize::<Fp2>(&bincode::serialize(&x).unwrap()).unwrap(), x);
+            let y = Fp6(y);
+            prop_assert_eq!(serde_json::from_str::<Fp6>(&serde_json::to_string(&y).unwrap()).unwrap().get_u32_slice(), y.get_u32_slice());
+            prop_assert_eq!(bincode::deserialize::<Fp6>(&bincode::serialize(&y).unwrap()).unwrap().get_u32_slice(), y.get_u32_slice());
+        }
+
+        #[test]
+        fn test_fp12_serde_round_trip(x in prop::array::uniform12(fp_strategy())) {
+            let x = Fp12(x);
+            let json = serde_json::to_string(&x).unwrap();
+            prop_assert_eq!(serde_json::from_str::<Vec<String>>(&json).unwrap(), x.0.iter().map(|c| format!("0x{}", hex::encode(c.to_bytes_be()))).collect::<Vec<String>>());
+            prop_assert_eq!(serde_json::from_str::<Fp12>(&json).unwrap(), x);
+            let bytes = bincode::serialize(&x).unwrap();
+            prop_assert_eq!(bincode::deserialize::<Fp12>(&bytes).unwrap(), x);
+        }
+    }
+
+    #[test]
+    fn test_fp_serde_is_canonical() {
+        // Limbs holding the modulus serialize as zero.
+        let p = Fp(modulus().to_u32_digits().try_into().unwrap());
+        assert_eq!(serde_json::to_string(&p).unwrap(), serde_json::to_string(&Fp::zero()).unwrap());
+        let p_minus_one = Fp::get_fp_from_biguint(modulus() - 1u32);
+        assert_eq!(serde_json::to_string(&p_minus_one).unwrap(), format!("\"{:#098x}\"", modulus() - 1u32));
+    }
+
+    #[test]
+    fn test_fp_serde_rejects_non_canonical() {
+        let p_hex = format!("\"{:#098x}\"", modulus());
+        assert!(serde_json::from_str::<Fp>(&p_hex).is_err());
+        assert!(serde_json::from_str::<Fp2>(&format!("[{}, {}]", serde_json::to_string(&Fp::one()).unwrap(), p_hex)).is_err());
+        // Not zero padded to 96 digits.
+        assert!(serde_json::from_str::<Fp>("\"0x1\"").is_err());
+        assert!(serde_json::from_str::<Fp>(&format!("\"{}\"", "f".repeat(96))).is_err());
+
+        let mut bytes = bincode::serialize(&Fp::zero()).unwrap();
+        let len = bytes.len();
+        bytes[len - 48..].copy_from_slice(&modulus().to_bytes_be());
+        assert!(bincode::deserialize::<Fp>(&bytes).is_err());
+        // bincode writes a byte string like a Vec<u8>, as its length followed by the bytes.
+        let short = bincode::serialize(&vec![0u8; 47]).unwrap();
+        assert!(bincode::deserialize::<Fp>(&short).is_err());
+    }
+}
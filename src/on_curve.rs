@@ -0,0 +1,891 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::circuit_builder::CircuitBuilder,
+};
+use starky::{
+    constraint_consumer::{ConstraintConsumer, RecursiveConstraintConsumer},
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    stark::Stark,
+};
+
+use crate::native::{Fp, Fp2, G1Affine, G2Affine};
+
+use crate::fp::*;
+use crate::fp2::*;
+use crate::g2::{fp2_cols, fp2_mod_cols, fp2_mul_res};
+use crate::utils::*;
+
+/// Limbs of 4, the `b` coefficient of the G1 curve `y^2 = x^3 + 4`. Both components of the `b` coefficient of the G2 curve `y^2 = x^3 + 4(1 + u)` are also 4.
+const FOUR: [u32; 12] = [4, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+
+// G1 curve membership layout offsets
+/*
+    These trace offsets are for checking if an affine G1 point is on the curve (super::native::G1Affine::is_on_curve). It needs 12 rows.
+    The result is a boolean flag. The curve equation is checked with `diff = y^2 - (x^3 + 4)`. If the flag is set for a finite point, `diff` is constrained to be zero. If the flag is not set, `diff` is constrained to have an inverse, which is set as a witness.
+    G1_ON_CURVE_SELECTOR_OFFSET -> Selector to ensure that the input is same across all rows. Set 1 in all rows except last one.
+    G1_ON_CURVE_CHECK_OFFSET -> Selector set 1 in all rows of the operation. The single row operations are filled in every row, and their inputs are constrained wherever this selector is on.
+    G1_ON_CURVE_X_OFFSET, G1_ON_CURVE_Y_OFFSET -> offsets where the input point is set.
+    G1_ON_CURVE_INFINITY_OFFSET -> Flag set 1 if the input is the point at infinity. The coordinates of the point at infinity are zero, and it is on the curve.
+    G1_ON_CURVE_RES_OFFSET -> Flag set 1 if the input is on the curve.
+    G1_ON_CURVE_FINITE_ON_CURVE_OFFSET -> Flag set 1 if the input is finite and on the curve, i.e. res * (1 - infinity).
+    G1_ON_CURVE_DIFF_INV_OFFSET -> offset where the inverse of `diff` is set. Zero if the input is on the curve.
+    G1_ON_CURVE_Y_SQ_OFFSET -> y * y
+    G1_ON_CURVE_X_SQ_OFFSET -> x * x
+    G1_ON_CURVE_X_CUBE_OFFSET -> x^2 * x
+    G1_ON_CURVE_RHS_OFFSET -> x^3 + 4
+    G1_ON_CURVE_DIFF_OFFSET -> y^2 - (x^3 + 4)
+    G1_ON_CURVE_DIFF_INV_MUL_OFFSET -> diff * diff_inv. Constrained to be one if the result flag is not set.
+*/
+pub const G1_ON_CURVE_SELECTOR_OFFSET: usize = 0;
+pub const G1_ON_CURVE_CHECK_OFFSET: usize = G1_ON_CURVE_SELECTOR_OFFSET + 1;
+pub const G1_ON_CURVE_X_OFFSET: usize = G1_ON_CURVE_CHECK_OFFSET + 1;
+pub const G1_ON_CURVE_Y_OFFSET: usize = G1_ON_CURVE_X_OFFSET + 12;
+pub const G1_ON_CURVE_INFINITY_OFFSET: usize = G1_ON_CURVE_Y_OFFSET + 12;
+pub const G1_ON_CURVE_RES_OFFSET: usize = G1_ON_CURVE_INFINITY_OFFSET + 1;
+pub const G1_ON_CURVE_FINITE_ON_CURVE_OFFSET: usize = G1_ON_CURVE_RES_OFFSET + 1;
+pub const G1_ON_CURVE_DIFF_INV_OFFSET: usize = G1_ON_CURVE_FINITE_ON_CURVE_OFFSET + 1;
+pub const G1_ON_CURVE_Y_SQ_OFFSET: usize = G1_ON_CURVE_DIFF_INV_OFFSET + 12;
+pub const G1_ON_CURVE_X_SQ_OFFSET: usize = G1_ON_CURVE_Y_SQ_OFFSET + FP_MUL_MOD_TOTAL;
+pub const G1_ON_CURVE_X_CUBE_OFFSET: usize = G1_ON_CURVE_X_SQ_OFFSET + FP_MUL_MOD_TOTAL;
+pub const G1_ON_CURVE_RHS_OFFSET: usize = G1_ON_CURVE_X_CUBE_OFFSET + FP_MUL_MOD_TOTAL;
+pub const G1_ON_CURVE_DIFF_OFFSET: usize = G1_ON_CURVE_RHS_OFFSET + FP_ADD_MOD_TOTAL;
+pub const G1_ON_CURVE_DIFF_INV_MUL_OFFSET: usize = G1_ON_CURVE_DIFF_OFFSET + FP_SUB_MOD_TOTAL;
+pub const G1_ON_CURVE_TOTAL: usize = G1_ON_CURVE_DIFF_INV_MUL_OFFSET + FP_MUL_MOD_TOTAL;
+
+// G2 curve membership layout offsets
+/*
+    These trace offsets are for checking if an affine G2 point is on the twisted curve (super::native::G2Affine::is_on_curve). It needs 12 rows.
+    Same as the G1 layout, with fp2 coordinates and `diff = y^2 - (x^3 + 4(1 + u))`. The fp2 additions and subtractions are done as two fp modular operations, one for each component.
+    G2_ON_CURVE_SELECTOR_OFFSET -> Selector to ensure that the input is same across all rows. Set 1 in all rows except last one.
+    G2_ON_CURVE_CHECK_OFFSET -> Selector set 1 in all rows of the operation.
+    G2_ON_CURVE_X_OFFSET, G2_ON_CURVE_Y_OFFSET -> offsets where the input point is set, 12*2 limbs each.
+    G2_ON_CURVE_INFINITY_OFFSET -> Flag set 1 if the input is the point at infinity.
+    G2_ON_CURVE_RES_OFFSET -> Flag set 1 if the input is on the curve.
+    G2_ON_CURVE_FINITE_ON_CURVE_OFFSET -> Flag set 1 if the input is finite and on the curve, i.e. res * (1 - infinity).
+    G2_ON_CURVE_DIFF_INV_OFFSET -> offset where the fp2 inverse of `diff` is set. Zero if the input is on the curve.
+    G2_ON_CURVE_Y_SQ_OFFSET -> y * y
+    G2_ON_CURVE_X_SQ_OFFSET -> x * x
+    G2_ON_CURVE_X_CUBE_OFFSET -> x^2 * x
+    G2_ON_CURVE_RHS_OFFSET -> x^3 + 4(1 + u)
+    G2_ON_CURVE_DIFF_OFFSET -> y^2 - (x^3 + 4(1 + u))
+    G2_ON_CURVE_DIFF_INV_MUL_OFFSET -> diff * diff_inv. Constrained to be one if the result flag is not set.
+*/
+pub const G2_ON_CURVE_SELECTOR_OFFSET: usize = 0;
+pub const G2_ON_CURVE_CHECK_OFFSET: usize = G2_ON_CURVE_SELECTOR_OFFSET + 1;
+pub const G2_ON_CURVE_X_OFFSET: usize = G2_ON_CURVE_CHECK_OFFSET + 1;
+pub const G2_ON_CURVE_Y_OFFSET: usize = G2_ON_CURVE_X_OFFSET + 24;
+pub const G2_ON_CURVE_INFINITY_OFFSET: usize = G2_ON_CURVE_Y_OFFSET + 24;
+pub const G2_ON_CURVE_RES_OFFSET: usize = G2_ON_CURVE_INFINITY_OFFSET + 1;
+pub const G2_ON_CURVE_FINITE_ON_CURVE_OFFSET: usize = G2_ON_CURVE_RES_OFFSET + 1;
+pub const G2_ON_CURVE_DIFF_INV_OFFSET: usize = G2_ON_CURVE_FINITE_ON_CURVE_OFFSET + 1;
+pub const G2_ON_CURVE_Y_SQ_OFFSET: usize = G2_ON_CURVE_DIFF_INV_OFFSET + 24;
+pub const G2_ON_CURVE_X_SQ_OFFSET: usize = G2_ON_CURVE_Y_SQ_OFFSET + TOTAL_COLUMNS_FP2_MULTIPLICATION;
+pub const G2_ON_CURVE_X_CUBE_OFFSET: usize = G2_ON_CURVE_X_SQ_OFFSET + TOTAL_COLUMNS_FP2_MULTIPLICATION;
+pub const G2_ON_CURVE_RHS_OFFSET: usize = G2_ON_CURVE_X_CUBE_OFFSET + TOTAL_COLUMNS_FP2_MULTIPLICATION;
+pub const G2_ON_CURVE_DIFF_OFFSET: usize = G2_ON_CURVE_RHS_OFFSET + FP_ADD_MOD_TOTAL * 2;
+pub const G2_ON_CURVE_DIFF_INV_MUL_OFFSET: usize = G2_ON_CURVE_DIFF_OFFSET + FP_SUB_MOD_TOTAL * 2;
+pub const G2_ON_CURVE_TOTAL: usize = G2_ON_CURVE_DIFF_INV_MUL_OFFSET + TOTAL_COLUMNS_FP2_MULTIPLICATION;
+
+/*
+    The public inputs for the G1 stark are the point (x, y) and its infinity flag, then the result flag.
+*/
+pub const G1_PIS_X_OFFSET: usize = 0;
+pub const G1_PIS_Y_OFFSET: usize = G1_PIS_X_OFFSET + 12;
+pub const G1_PIS_INFINITY_OFFSET: usize = G1_PIS_Y_OFFSET + 12;
+pub const G1_PIS_RES_OFFSET: usize = G1_PIS_INFINITY_OFFSET + 1;
+pub const G1_PUBLIC_INPUTS: usize = G1_PIS_RES_OFFSET + 1;
+
+/*
+    The public inputs for the G2 stark are the point (x, y) and its infinity flag, then the result flag. Every coordinate is 12*2 limbs.
+*/
+pub const G2_PIS_X_OFFSET: usize = 0;
+pub const G2_PIS_Y_OFFSET: usize = G2_PIS_X_OFFSET + 24;
+pub const G2_PIS_INFINITY_OFFSET: usize = G2_PIS_Y_OFFSET + 24;
+pub const G2_PIS_RES_OFFSET: usize = G2_PIS_INFINITY_OFFSET + 1;
+pub const G2_PUBLIC_INPUTS: usize = G2_PIS_RES_OFFSET + 1;
+
+/// Pairs of (trace column, public input) of the G1 stark which are constrained to be equal wherever G1_ON_CURVE_CHECK_OFFSET is set, along with the number of limbs.
+const G1_PIS_LINKS: [(usize, usize, usize); 4] = [
+    (G1_ON_CURVE_X_OFFSET, G1_PIS_X_OFFSET, 12),
+    (G1_ON_CURVE_Y_OFFSET, G1_PIS_Y_OFFSET, 12),
+    (G1_ON_CURVE_INFINITY_OFFSET, G1_PIS_INFINITY_OFFSET, 1),
+    (G1_ON_CURVE_RES_OFFSET, G1_PIS_RES_OFFSET, 1),
+];
+
+/// Pairs of (trace column, public input) of the G2 stark which are constrained to be equal wherever G2_ON_CURVE_CHECK_OFFSET is set, along with the number of limbs.
+const G2_PIS_LINKS: [(usize, usize, usize); 4] = [
+    (G2_ON_CURVE_X_OFFSET, G2_PIS_X_OFFSET, 24),
+    (G2_ON_CURVE_Y_OFFSET, G2_PIS_Y_OFFSET, 24),
+    (G2_ON_CURVE_INFINITY_OFFSET, G2_PIS_INFINITY_OFFSET, 1),
+    (G2_ON_CURVE_RES_OFFSET, G2_PIS_RES_OFFSET, 1),
+];
+
+/// Pairs of (operation input, value) columns of the G1 curve membership check, each 12 limbs, which are constrained to be equal wherever G1_ON_CURVE_CHECK_OFFSET is set.
+fn g1_on_curve_input_links(start_col: usize) -> Vec<(usize, usize)> {
+    let x = start_col + G1_ON_CURVE_X_OFFSET;
+    let y = start_col + G1_ON_CURVE_Y_OFFSET;
+    vec![
+        (fp_mul_mod_x(start_col + G1_ON_CURVE_Y_SQ_OFFSET), y),
+        (fp_mul_mod_y(start_col + G1_ON_CURVE_Y_SQ_OFFSET), y),
+        (fp_mul_mod_x(start_col + G1_ON_CURVE_X_SQ_OFFSET), x),
+        (fp_mul_mod_y(start_col + G1_ON_CURVE_X_SQ_OFFSET), x),
+        (fp_mul_mod_x(start_col + G1_ON_CURVE_X_CUBE_OFFSET), fp_mul_mod_res(start_col + G1_ON_CURVE_X_SQ_OFFSET)),
+        (fp_mul_mod_y(start_col + G1_ON_CURVE_X_CUBE_OFFSET), x),
+        (fp_add_mod_x(start_col + G1_ON_CURVE_RHS_OFFSET), fp_mul_mod_res(start_col + G1_ON_CURVE_X_CUBE_OFFSET)),
+        (fp_sub_mod_x(start_col + G1_ON_CURVE_DIFF_OFFSET), fp_mul_mod_res(start_col + G1_ON_CURVE_Y_SQ_OFFSET)),
+        (fp_sub_mod_y(start_col + G1_ON_CURVE_DIFF_OFFSET), fp_add_mod_res(start_col + G1_ON_CURVE_RHS_OFFSET)),
+        (fp_mul_mod_x(start_col + G1_ON_CURVE_DIFF_INV_MUL_OFFSET), fp_sub_mod_res(start_col + G1_ON_CURVE_DIFF_OFFSET)),
+        (fp_mul_mod_y(start_col + G1_ON_CURVE_DIFF_INV_MUL_OFFSET), start_col + G1_ON_CURVE_DIFF_INV_OFFSET),
+    ]
+}
+
+/// Pairs of (operation input, value) columns of the G2 curve membership check, each 12 limbs, which are constrained to be equal wherever G2_ON_CURVE_CHECK_OFFSET is set.
+fn g2_on_curve_input_links(start_col: usize) -> Vec<(usize, usize)> {
+    let x = fp2_cols(start_col + G2_ON_CURVE_X_OFFSET);
+    let y = fp2_cols(start_col + G2_ON_CURVE_Y_OFFSET);
+    let mul_x = |col: usize| fp2_cols(start_col + col + FP2_FP2_X_INPUT_OFFSET);
+    let mul_y = |col: usize| fp2_cols(start_col + col + FP2_FP2_Y_INPUT_OFFSET);
+    let mul_res = |col: usize| fp2_mul_res(start_col + col);
+    vec![
+        (mul_x(G2_ON_CURVE_Y_SQ_OFFSET), y),
+        (mul_y(G2_ON_CURVE_Y_SQ_OFFSET), y),
+        (mul_x(G2_ON_CURVE_X_SQ_OFFSET), x),
+        (mul_y(G2_ON_CURVE_X_SQ_OFFSET), x),
+        (mul_x(G2_ON_CURVE_X_CUBE_OFFSET), mul_res(G2_ON_CURVE_X_SQ_OFFSET)),
+        (mul_y(G2_ON_CURVE_X_CUBE_OFFSET), x),
+        (fp2_mod_cols(start_col + G2_ON_CURVE_RHS_OFFSET, FP_ADD_MOD_TOTAL, fp_add_mod_x), mul_res(G2_ON_CURVE_X_CUBE_OFFSET)),
+        (fp2_mod_cols(start_col + G2_ON_CURVE_DIFF_OFFSET, FP_SUB_MOD_TOTAL, fp_sub_mod_x), mul_res(G2_ON_CURVE_Y_SQ_OFFSET)),
+        (fp2_mod_cols(start_col + G2_ON_CURVE_DIFF_OFFSET, FP_SUB_MOD_TOTAL, fp_sub_mod_y), fp2_mod_cols(start_col + G2_ON_CURVE_RHS_OFFSET, FP_ADD_MOD_TOTAL, fp_add_mod_res)),
+        (mul_x(G2_ON_CURVE_DIFF_INV_MUL_OFFSET), fp2_mod_cols(start_col + G2_ON_CURVE_DIFF_OFFSET, FP_SUB_MOD_TOTAL, fp_sub_mod_res)),
+        (mul_y(G2_ON_CURVE_DIFF_INV_MUL_OFFSET), fp2_cols(start_col + G2_ON_CURVE_DIFF_INV_OFFSET)),
+    ].into_iter().flat_map(|(a, b)| [(a[0], b[0]), (a[1], b[1])]).collect()
+}
+
+/// Fills the stark trace of the G1 curve membership check. Input is an affine point (x, y). Needs 12 rows. Returns whether the point is on the curve.
+pub fn fill_trace_g1_on_curve<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, p: &G1Affine, start_row: usize, end_row: usize, start_col: usize) -> bool {
+    let on_curve = p.is_on_curve();
+    let y_sq = p.y * p.y;
+    let x_sq = p.x * p.x;
+    let x_cube = x_sq * p.x;
+    let rhs = x_cube + Fp(FOUR);
+    let diff = y_sq - rhs;
+    let diff_inv = if on_curve { Fp::zero() } else { diff.invert() };
+    for row in start_row..end_row + 1 {
+        trace[row][start_col + G1_ON_CURVE_SELECTOR_OFFSET] = F::ONE;
+        trace[row][start_col + G1_ON_CURVE_CHECK_OFFSET] = F::ONE;
+        assign_u32_in_series(trace, row, start_col + G1_ON_CURVE_X_OFFSET, &p.x.0);
+        assign_u32_in_series(trace, row, start_col + G1_ON_CURVE_Y_OFFSET, &p.y.0);
+        trace[row][start_col + G1_ON_CURVE_INFINITY_OFFSET] = F::from_bool(p.infinity);
+        trace[row][start_col + G1_ON_CURVE_RES_OFFSET] = F::from_bool(on_curve);
+        trace[row][start_col + G1_ON_CURVE_FINITE_ON_CURVE_OFFSET] = F::from_bool(on_curve && !p.infinity);
+        assign_u32_in_series(trace, row, start_col + G1_ON_CURVE_DIFF_INV_OFFSET, &diff_inv.0);
+    }
+    trace[end_row][start_col + G1_ON_CURVE_SELECTOR_OFFSET] = F::ZERO;
+
+    fill_trace_fp_mul_mod(trace, &p.y.0, &p.y.0, start_row, end_row, start_col + G1_ON_CURVE_Y_SQ_OFFSET);
+    fill_trace_fp_mul_mod(trace, &p.x.0, &p.x.0, start_row, end_row, start_col + G1_ON_CURVE_X_SQ_OFFSET);
+    fill_trace_fp_mul_mod(trace, &x_sq.0, &p.x.0, start_row, end_row, start_col + G1_ON_CURVE_X_CUBE_OFFSET);
+    fill_trace_fp_mul_mod(trace, &diff.0, &diff_inv.0, start_row, end_row, start_col + G1_ON_CURVE_DIFF_INV_MUL_OFFSET);
+    for row in start_row..end_row + 1 {
+        fill_trace_addition_fp_mod(trace, &x_cube.0, &FOUR, row, start_col + G1_ON_CURVE_RHS_OFFSET);
+        fill_trace_subtraction_fp_mod(trace, &y_sq.0, &rhs.0, row, start_col + G1_ON_CURVE_DIFF_OFFSET);
+    }
+    on_curve
+}
+
+/// Constraints the G1 curve membership check.
+///
+/// Constraints the input, flags and inverse witness to be same across the rows wherever the selector is on. Constraints the inputs of every operation with the input point, the constant 4 or the result of a previous operation.
+/// The infinity and result flags are constrained to be boolean, and the coordinates of the input are constrained to be zero if it is the point at infinity, in which case the result flag must be set.
+/// If the input is finite and the result flag is set, `diff` must be zero. If the result flag is not set, `diff * diff_inv` must be one.
+pub fn add_g1_on_curve_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+    let selector = bit_selector_val * local_values[start_col + G1_ON_CURVE_SELECTOR_OFFSET];
+    let check = bit_selector_val * local_values[start_col + G1_ON_CURVE_CHECK_OFFSET];
+    let infinity = local_values[start_col + G1_ON_CURVE_INFINITY_OFFSET];
+    let res = local_values[start_col + G1_ON_CURVE_RES_OFFSET];
+    let finite_on_curve = local_values[start_col + G1_ON_CURVE_FINITE_ON_CURVE_OFFSET];
+
+    for col in [G1_ON_CURVE_X_OFFSET, G1_ON_CURVE_Y_OFFSET, G1_ON_CURVE_DIFF_INV_OFFSET] {
+        for i in 0..12 {
+            yield_constr.constraint_transition(
+                selector *
+                (local_values[start_col + col + i] - next_values[start_col + col + i])
+            );
+        }
+    }
+    for col in [G1_ON_CURVE_INFINITY_OFFSET, G1_ON_CURVE_RES_OFFSET, G1_ON_CURVE_FINITE_ON_CURVE_OFFSET] {
+        yield_constr.constraint_transition(
+            selector *
+            (local_values[start_col + col] - next_values[start_col + col])
+        );
+    }
+
+    for (input_col, value_col) in g1_on_curve_input_links(start_col) {
+        for i in 0..12 {
+            yield_constr.constraint(
+                check *
+                (local_values[input_col + i] - local_values[value_col + i])
+            );
+        }
+    }
+
+    yield_constr.constraint(check * infinity * (P::ONES - infinity));
+    yield_constr.constraint(check * res * (P::ONES - res));
+    yield_constr.constraint(check * infinity * (P::ONES - res));
+    yield_constr.constraint(check * (finite_on_curve - res * (P::ONES - infinity)));
+
+    let four = fp_add_mod_y(start_col + G1_ON_CURVE_RHS_OFFSET);
+    let diff = fp_sub_mod_res(start_col + G1_ON_CURVE_DIFF_OFFSET);
+    let diff_inv_mul = fp_mul_mod_res(start_col + G1_ON_CURVE_DIFF_INV_MUL_OFFSET);
+    for i in 0..12 {
+        yield_constr.constraint(
+            check *
+            (local_values[four + i] - FE::from_canonical_u32(FOUR[i]))
+        );
+        yield_constr.constraint(check * infinity * local_values[start_col + G1_ON_CURVE_X_OFFSET + i]);
+        yield_constr.constraint(check * infinity * local_values[start_col + G1_ON_CURVE_Y_OFFSET + i]);
+        yield_constr.constraint(check * finite_on_curve * local_values[diff + i]);
+        let one = if i == 0 { P::ONES } else { P::ZEROS };
+        yield_constr.constraint(check * (P::ONES - res) * (local_values[diff_inv_mul + i] - one));
+    }
+
+    for col in [G1_ON_CURVE_Y_SQ_OFFSET, G1_ON_CURVE_X_SQ_OFFSET, G1_ON_CURVE_X_CUBE_OFFSET, G1_ON_CURVE_DIFF_INV_MUL_OFFSET] {
+        add_fp_mul_mod_constraints(local_values, next_values, yield_constr, start_col + col, bit_selector);
+    }
+    add_addition_fp_mod_constraints(local_values, yield_constr, start_col + G1_ON_CURVE_RHS_OFFSET, bit_selector);
+    add_subtraction_fp_mod_constraints(local_values, yield_constr, start_col + G1_ON_CURVE_DIFF_OFFSET, bit_selector);
+}
+
+pub fn add_g1_on_curve_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let bit_selector_val = bit_selector.unwrap_or(builder.constant_extension(F::Extension::ONE));
+    let one = builder.constant_extension(F::Extension::ONE);
+    let selector = builder.mul_extension(bit_selector_val, local_values[start_col + G1_ON_CURVE_SELECTOR_OFFSET]);
+    let check = builder.mul_extension(bit_selector_val, local_values[start_col + G1_ON_CURVE_CHECK_OFFSET]);
+    let infinity = local_values[start_col + G1_ON_CURVE_INFINITY_OFFSET];
+    let res = local_values[start_col + G1_ON_CURVE_RES_OFFSET];
+    let finite_on_curve = local_values[start_col + G1_ON_CURVE_FINITE_ON_CURVE_OFFSET];
+    let not_infinity = builder.sub_extension(one, infinity);
+    let not_res = builder.sub_extension(one, res);
+
+    for col in [G1_ON_CURVE_X_OFFSET, G1_ON_CURVE_Y_OFFSET, G1_ON_CURVE_DIFF_INV_OFFSET] {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[start_col + col + i], next_values[start_col + col + i]);
+            let c = builder.mul_extension(selector, c);
+            yield_constr.constraint_transition(builder, c);
+        }
+    }
+    for col in [G1_ON_CURVE_INFINITY_OFFSET, G1_ON_CURVE_RES_OFFSET, G1_ON_CURVE_FINITE_ON_CURVE_OFFSET] {
+        let c = builder.sub_extension(local_values[start_col + col], next_values[start_col + col]);
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint_transition(builder, c);
+    }
+
+    for (input_col, value_col) in g1_on_curve_input_links(start_col) {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[input_col + i], local_values[value_col + i]);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+
+    let c = builder.mul_extension(infinity, not_infinity);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(res, not_res);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(infinity, not_res);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(res, not_infinity);
+    let c = builder.sub_extension(finite_on_curve, c);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+
+    let four = fp_add_mod_y(start_col + G1_ON_CURVE_RHS_OFFSET);
+    let diff = fp_sub_mod_res(start_col + G1_ON_CURVE_DIFF_OFFSET);
+    let diff_inv_mul = fp_mul_mod_res(start_col + G1_ON_CURVE_DIFF_INV_MUL_OFFSET);
+    let check_infinity = builder.mul_extension(check, infinity);
+    let check_finite_on_curve = builder.mul_extension(check, finite_on_curve);
+    let check_not_res = builder.mul_extension(check, not_res);
+    for i in 0..12 {
+        let limb = builder.constant_extension(F::Extension::from_canonical_u32(FOUR[i]));
+        let c = builder.sub_extension(local_values[four + i], limb);
+        let c = builder.mul_extension(check, c);
+        yield_constr.constraint(builder, c);
+        let c = builder.mul_extension(check_infinity, local_values[start_col + G1_ON_CURVE_X_OFFSET + i]);
+        yield_constr.constraint(builder, c);
+        let c = builder.mul_extension(check_infinity, local_values[start_col + G1_ON_CURVE_Y_OFFSET + i]);
+        yield_constr.constraint(builder, c);
+        let c = builder.mul_extension(check_finite_on_curve, local_values[diff + i]);
+        yield_constr.constraint(builder, c);
+        let c = if i == 0 {
+            builder.sub_extension(local_values[diff_inv_mul + i], one)
+        } else {
+            local_values[diff_inv_mul + i]
+        };
+        let c = builder.mul_extension(check_not_res, c);
+        yield_constr.constraint(builder, c);
+    }
+
+    for col in [G1_ON_CURVE_Y_SQ_OFFSET, G1_ON_CURVE_X_SQ_OFFSET, G1_ON_CURVE_X_CUBE_OFFSET, G1_ON_CURVE_DIFF_INV_MUL_OFFSET] {
+        add_fp_mul_mod_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + col, bit_selector);
+    }
+    add_addition_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + G1_ON_CURVE_RHS_OFFSET, bit_selector);
+    add_subtraction_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + G1_ON_CURVE_DIFF_OFFSET, bit_selector);
+}
+
+/// Fills the stark trace of the G2 curve membership check. Input is an affine point (x, y). Needs 12 rows. Returns whether the point is on the curve.
+pub fn fill_trace_g2_on_curve<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+>(trace: &mut Vec<[F; C]>, p: &G2Affine, start_row: usize, end_row: usize, start_col: usize) -> bool {
+    let on_curve = p.is_on_curve();
+    let y_sq = p.y * p.y;
+    let x_sq = p.x * p.x;
+    let x_cube = x_sq * p.x;
+    let rhs = x_cube + Fp2([Fp(FOUR), Fp(FOUR)]);
+    let diff = y_sq - rhs;
+    let diff_inv = if on_curve { Fp2::zero() } else { diff.invert() };
+    for row in start_row..end_row + 1 {
+        trace[row][start_col + G2_ON_CURVE_SELECTOR_OFFSET] = F::ONE;
+        trace[row][start_col + G2_ON_CURVE_CHECK_OFFSET] = F::ONE;
+        assign_u32_in_series(trace, row, start_col + G2_ON_CURVE_X_OFFSET, &p.x.get_u32_slice().concat());
+        assign_u32_in_series(trace, row, start_col + G2_ON_CURVE_Y_OFFSET, &p.y.get_u32_slice().concat());
+        trace[row][start_col + G2_ON_CURVE_INFINITY_OFFSET] = F::from_bool(p.infinity);
+        trace[row][start_col + G2_ON_CURVE_RES_OFFSET] = F::from_bool(on_curve);
+        trace[row][start_col + G2_ON_CURVE_FINITE_ON_CURVE_OFFSET] = F::from_bool(on_curve && !p.infinity);
+        assign_u32_in_series(trace, row, start_col + G2_ON_CURVE_DIFF_INV_OFFSET, &diff_inv.get_u32_slice().concat());
+    }
+    trace[end_row][start_col + G2_ON_CURVE_SELECTOR_OFFSET] = F::ZERO;
+
+    generate_trace_fp2_mul(trace, p.y.get_u32_slice(), p.y.get_u32_slice(), start_row, end_row, start_col + G2_ON_CURVE_Y_SQ_OFFSET);
+    generate_trace_fp2_mul(trace, p.x.get_u32_slice(), p.x.get_u32_slice(), start_row, end_row, start_col + G2_ON_CURVE_X_SQ_OFFSET);
+    generate_trace_fp2_mul(trace, x_sq.get_u32_slice(), p.x.get_u32_slice(), start_row, end_row, start_col + G2_ON_CURVE_X_CUBE_OFFSET);
+    generate_trace_fp2_mul(trace, diff.get_u32_slice(), diff_inv.get_u32_slice(), start_row, end_row, start_col + G2_ON_CURVE_DIFF_INV_MUL_OFFSET);
+    for row in start_row..end_row + 1 {
+        for j in 0..2 {
+            fill_trace_addition_fp_mod(trace, &x_cube.0[j].0, &FOUR, row, start_col + G2_ON_CURVE_RHS_OFFSET + j * FP_ADD_MOD_TOTAL);
+            fill_trace_subtraction_fp_mod(trace, &y_sq.0[j].0, &rhs.0[j].0, row, start_col + G2_ON_CURVE_DIFF_OFFSET + j * FP_SUB_MOD_TOTAL);
+        }
+    }
+    on_curve
+}
+
+/// Constraints the G2 curve membership check.
+///
+/// Same as [add_g1_on_curve_constraints], with fp2 coordinates and both components of the constant `b` set to 4.
+pub fn add_g2_on_curve_constraints<
+    F: RichField + Extendable<D>,
+    const D: usize,
+    FE,
+    P,
+    const D2: usize,
+>(
+    local_values: &[P],
+    next_values: &[P],
+    yield_constr: &mut ConstraintConsumer<P>,
+    start_col: usize,
+    bit_selector: Option<P>,
+) where
+    FE: FieldExtension<D2, BaseField = F>,
+    P: PackedField<Scalar = FE>,
+{
+    let bit_selector_val = bit_selector.unwrap_or(P::ONES);
+    let selector = bit_selector_val * local_values[start_col + G2_ON_CURVE_SELECTOR_OFFSET];
+    let check = bit_selector_val * local_values[start_col + G2_ON_CURVE_CHECK_OFFSET];
+    let infinity = local_values[start_col + G2_ON_CURVE_INFINITY_OFFSET];
+    let res = local_values[start_col + G2_ON_CURVE_RES_OFFSET];
+    let finite_on_curve = local_values[start_col + G2_ON_CURVE_FINITE_ON_CURVE_OFFSET];
+
+    for col in [G2_ON_CURVE_X_OFFSET, G2_ON_CURVE_Y_OFFSET, G2_ON_CURVE_DIFF_INV_OFFSET] {
+        for i in 0..24 {
+            yield_constr.constraint_transition(
+                selector *
+                (local_values[start_col + col + i] - next_values[start_col + col + i])
+            );
+        }
+    }
+    for col in [G2_ON_CURVE_INFINITY_OFFSET, G2_ON_CURVE_RES_OFFSET, G2_ON_CURVE_FINITE_ON_CURVE_OFFSET] {
+        yield_constr.constraint_transition(
+            selector *
+            (local_values[start_col + col] - next_values[start_col + col])
+        );
+    }
+
+    for (input_col, value_col) in g2_on_curve_input_links(start_col) {
+        for i in 0..12 {
+            yield_constr.constraint(
+                check *
+                (local_values[input_col + i] - local_values[value_col + i])
+            );
+        }
+    }
+
+    yield_constr.constraint(check * infinity * (P::ONES - infinity));
+    yield_constr.constraint(check * res * (P::ONES - res));
+    yield_constr.constraint(check * infinity * (P::ONES - res));
+    yield_constr.constraint(check * (finite_on_curve - res * (P::ONES - infinity)));
+
+    let four = fp2_mod_cols(start_col + G2_ON_CURVE_RHS_OFFSET, FP_ADD_MOD_TOTAL, fp_add_mod_y);
+    let diff = fp2_mod_cols(start_col + G2_ON_CURVE_DIFF_OFFSET, FP_SUB_MOD_TOTAL, fp_sub_mod_res);
+    let diff_inv_mul = fp2_mul_res(start_col + G2_ON_CURVE_DIFF_INV_MUL_OFFSET);
+    for j in 0..2 {
+        for i in 0..12 {
+            yield_constr.constraint(
+                check *
+                (local_values[four[j] + i] - FE::from_canonical_u32(FOUR[i]))
+            );
+            yield_constr.constraint(check * infinity * local_values[start_col + G2_ON_CURVE_X_OFFSET + j * 12 + i]);
+            yield_constr.constraint(check * infinity * local_values[start_col + G2_ON_CURVE_Y_OFFSET + j * 12 + i]);
+            yield_constr.constraint(check * finite_on_curve * local_values[diff[j] + i]);
+            let one = if j == 0 && i == 0 { P::ONES } else { P::ZEROS };
+            yield_constr.constraint(check * (P::ONES - res) * (local_values[diff_inv_mul[j] + i] - one));
+        }
+    }
+
+    for col in [G2_ON_CURVE_Y_SQ_OFFSET, G2_ON_CURVE_X_SQ_OFFSET, G2_ON_CURVE_X_CUBE_OFFSET, G2_ON_CURVE_DIFF_INV_MUL_OFFSET] {
+        add_fp2_mul_constraints(local_values, next_values, yield_constr, start_col + col, bit_selector);
+    }
+    for j in 0..2 {
+        add_addition_fp_mod_constraints(local_values, yield_constr, start_col + G2_ON_CURVE_RHS_OFFSET + j * FP_ADD_MOD_TOTAL, bit_selector);
+        add_subtraction_fp_mod_constraints(local_values, yield_constr, start_col + G2_ON_CURVE_DIFF_OFFSET + j * FP_SUB_MOD_TOTAL, bit_selector);
+    }
+}
+
+pub fn add_g2_on_curve_constraints_ext_circuit<
+    F: RichField + Extendable<D>,
+    const D: usize,
+>(
+    builder: &mut CircuitBuilder<F, D>,
+    yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    local_values: &[ExtensionTarget<D>],
+    next_values: &[ExtensionTarget<D>],
+    start_col: usize,
+    bit_selector: Option<ExtensionTarget<D>>,
+) {
+    let bit_selector_val = bit_selector.unwrap_or(builder.constant_extension(F::Extension::ONE));
+    let one = builder.constant_extension(F::Extension::ONE);
+    let selector = builder.mul_extension(bit_selector_val, local_values[start_col + G2_ON_CURVE_SELECTOR_OFFSET]);
+    let check = builder.mul_extension(bit_selector_val, local_values[start_col + G2_ON_CURVE_CHECK_OFFSET]);
+    let infinity = local_values[start_col + G2_ON_CURVE_INFINITY_OFFSET];
+    let res = local_values[start_col + G2_ON_CURVE_RES_OFFSET];
+    let finite_on_curve = local_values[start_col + G2_ON_CURVE_FINITE_ON_CURVE_OFFSET];
+    let not_infinity = builder.sub_extension(one, infinity);
+    let not_res = builder.sub_extension(one, res);
+
+    for col in [G2_ON_CURVE_X_OFFSET, G2_ON_CURVE_Y_OFFSET, G2_ON_CURVE_DIFF_INV_OFFSET] {
+        for i in 0..24 {
+            let c = builder.sub_extension(local_values[start_col + col + i], next_values[start_col + col + i]);
+            let c = builder.mul_extension(selector, c);
+            yield_constr.constraint_transition(builder, c);
+        }
+    }
+    for col in [G2_ON_CURVE_INFINITY_OFFSET, G2_ON_CURVE_RES_OFFSET, G2_ON_CURVE_FINITE_ON_CURVE_OFFSET] {
+        let c = builder.sub_extension(local_values[start_col + col], next_values[start_col + col]);
+        let c = builder.mul_extension(selector, c);
+        yield_constr.constraint_transition(builder, c);
+    }
+
+    for (input_col, value_col) in g2_on_curve_input_links(start_col) {
+        for i in 0..12 {
+            let c = builder.sub_extension(local_values[input_col + i], local_values[value_col + i]);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+
+    let c = builder.mul_extension(infinity, not_infinity);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(res, not_res);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(infinity, not_res);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+    let c = builder.mul_extension(res, not_infinity);
+    let c = builder.sub_extension(finite_on_curve, c);
+    let c = builder.mul_extension(check, c);
+    yield_constr.constraint(builder, c);
+
+    let four = fp2_mod_cols(start_col + G2_ON_CURVE_RHS_OFFSET, FP_ADD_MOD_TOTAL, fp_add_mod_y);
+    let diff = fp2_mod_cols(start_col + G2_ON_CURVE_DIFF_OFFSET, FP_SUB_MOD_TOTAL, fp_sub_mod_res);
+    let diff_inv_mul = fp2_mul_res(start_col + G2_ON_CURVE_DIFF_INV_MUL_OFFSET);
+    let check_infinity = builder.mul_extension(check, infinity);
+    let check_finite_on_curve = builder.mul_extension(check, finite_on_curve);
+    let check_not_res = builder.mul_extension(check, not_res);
+    for j in 0..2 {
+        for i in 0..12 {
+            let limb = builder.constant_extension(F::Extension::from_canonical_u32(FOUR[i]));
+            let c = builder.sub_extension(local_values[four[j] + i], limb);
+            let c = builder.mul_extension(check, c);
+            yield_constr.constraint(builder, c);
+            let c = builder.mul_extension(check_infinity, local_values[start_col + G2_ON_CURVE_X_OFFSET + j * 12 + i]);
+            yield_constr.constraint(builder, c);
+            let c = builder.mul_extension(check_infinity, local_values[start_col + G2_ON_CURVE_Y_OFFSET + j * 12 + i]);
+            yield_constr.constraint(builder, c);
+            let c = builder.mul_extension(check_finite_on_curve, local_values[diff[j] + i]);
+            yield_constr.constraint(builder, c);
+            let c = if j == 0 && i == 0 {
+                builder.sub_extension(local_values[diff_inv_mul[j] + i], one)
+            } else {
+                local_values[diff_inv_mul[j] + i]
+            };
+            let c = builder.mul_extension(check_not_res, c);
+            yield_constr.constraint(builder, c);
+        }
+    }
+
+    for col in [G2_ON_CURVE_Y_SQ_OFFSET, G2_ON_CURVE_X_SQ_OFFSET, G2_ON_CURVE_X_CUBE_OFFSET, G2_ON_CURVE_DIFF_INV_MUL_OFFSET] {
+        add_fp2_mul_constraints_ext_circuit(builder, yield_constr, local_values, next_values, start_col + col, bit_selector);
+    }
+    for j in 0..2 {
+        add_addition_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + G2_ON_CURVE_RHS_OFFSET + j * FP_ADD_MOD_TOTAL, bit_selector);
+        add_subtraction_fp_mod_constraints_ext_circuit(builder, yield_constr, local_values, start_col + G2_ON_CURVE_DIFF_OFFSET + j * FP_SUB_MOD_TOTAL, bit_selector);
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct G1OnCurveStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> G1OnCurveStark<F, D> {
+    /// Rows used by one G1 curve equation check, 12. The stark needs a power of two number of rows of at least this many.
+    pub fn required_rows() -> usize {
+        12
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(p: &G1Affine, on_curve: bool) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; G1_PUBLIC_INPUTS];
+        for i in 0..12 {
+            public_inputs[G1_PIS_X_OFFSET + i] = F::from_canonical_u32(p.x.0[i]);
+            public_inputs[G1_PIS_Y_OFFSET + i] = F::from_canonical_u32(p.y.0[i]);
+        }
+        public_inputs[G1_PIS_INFINITY_OFFSET] = F::from_bool(p.infinity);
+        public_inputs[G1_PIS_RES_OFFSET] = F::from_bool(on_curve);
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, p: &G1Affine) -> Vec<[F; G1_ON_CURVE_TOTAL]> {
+        let mut trace = vec![[F::ZERO; G1_ON_CURVE_TOTAL]; self.num_rows];
+        fill_trace_g1_on_curve(&mut trace, p, 0, 11, 0);
+        trace
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for G1OnCurveStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, G1_ON_CURVE_TOTAL, G1_PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[G1_ON_CURVE_CHECK_OFFSET] - P::ONES);
+        for (col, pis, limbs) in G1_PIS_LINKS {
+            for i in 0..limbs {
+                yield_constr.constraint(
+                    local_values[G1_ON_CURVE_CHECK_OFFSET] *
+                    (local_values[col + i] - public_inputs[pis + i])
+                );
+            }
+        }
+        add_g1_on_curve_constraints(local_values, next_values, yield_constr, 0, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, G1_ON_CURVE_TOTAL, G1_PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[G1_ON_CURVE_CHECK_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for (col, pis, limbs) in G1_PIS_LINKS {
+            for i in 0..limbs {
+                let c = builder.sub_extension(local_values[col + i], public_inputs[pis + i]);
+                let c = builder.mul_extension(local_values[G1_ON_CURVE_CHECK_OFFSET], c);
+                yield_constr.constraint(builder, c);
+            }
+        }
+        add_g1_on_curve_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct G2OnCurveStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> G2OnCurveStark<F, D> {
+    /// Rows used by one G2 curve equation check, 12. The stark needs a power of two number of rows of at least this many.
+    pub fn required_rows() -> usize {
+        12
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    pub fn public_inputs(p: &G2Affine, on_curve: bool) -> Vec<F> {
+        let mut public_inputs = vec![F::ZERO; G2_PUBLIC_INPUTS];
+        for (i, limb) in p.x.get_u32_slice().concat().into_iter().enumerate() {
+            public_inputs[G2_PIS_X_OFFSET + i] = F::from_canonical_u32(limb);
+        }
+        for (i, limb) in p.y.get_u32_slice().concat().into_iter().enumerate() {
+            public_inputs[G2_PIS_Y_OFFSET + i] = F::from_canonical_u32(limb);
+        }
+        public_inputs[G2_PIS_INFINITY_OFFSET] = F::from_bool(p.infinity);
+        public_inputs[G2_PIS_RES_OFFSET] = F::from_bool(on_curve);
+        public_inputs
+    }
+
+    pub fn generate_trace(&self, p: &G2Affine) -> Vec<[F; G2_ON_CURVE_TOTAL]> {
+        let mut trace = vec![[F::ZERO; G2_ON_CURVE_TOTAL]; self.num_rows];
+        fill_trace_g2_on_curve(&mut trace, p, 0, 11, 0);
+        trace
+    }
+}
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for G2OnCurveStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, G2_ON_CURVE_TOTAL, G2_PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        yield_constr.constraint_first_row(local_values[G2_ON_CURVE_CHECK_OFFSET] - P::ONES);
+        for (col, pis, limbs) in G2_PIS_LINKS {
+            for i in 0..limbs {
+                yield_constr.constraint(
+                    local_values[G2_ON_CURVE_CHECK_OFFSET] *
+                    (local_values[col + i] - public_inputs[pis + i])
+                );
+            }
+        }
+        add_g2_on_curve_constraints(local_values, next_values, yield_constr, 0, None);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, G2_ON_CURVE_TOTAL, G2_PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        let one = builder.constant_extension(F::Extension::ONE);
+        let c = builder.sub_extension(local_values[G2_ON_CURVE_CHECK_OFFSET], one);
+        yield_constr.constraint_first_row(builder, c);
+        for (col, pis, limbs) in G2_PIS_LINKS {
+            for i in 0..limbs {
+                let c = builder.sub_extension(local_values[col + i], public_inputs[pis + i]);
+                let c = builder.mul_extension(local_values[G2_ON_CURVE_CHECK_OFFSET], c);
+                yield_constr.constraint(builder, c);
+            }
+        }
+        add_g2_on_curve_constraints_ext_circuit(builder, yield_constr, local_values, next_values, 0, None);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        3
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{Fp, Fp2, G1Affine, G2Affine};
+
+    use super::{G1OnCurveStark, G2OnCurveStark};
+    #[cfg(feature = "debug-constraints")]
+    use crate::debug_constraints::debug_check_trace;
+    #[cfg(feature = "debug-constraints")]
+    use super::G1_PIS_RES_OFFSET;
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    fn prove_and_verify_g1(p: &G1Affine) -> bool {
+        type S = G1OnCurveStark<F, D>;
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(16);
+        let on_curve = p.is_on_curve();
+        let public_inputs = S::public_inputs(p, on_curve);
+        let trace_poly_values = trace_rows_to_poly_values(stark.generate_trace(p));
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        on_curve
+    }
+
+    fn prove_and_verify_g2(p: &G2Affine) -> bool {
+        type S = G2OnCurveStark<F, D>;
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(16);
+        let on_curve = p.is_on_curve();
+        let public_inputs = S::public_inputs(p, on_curve);
+        let trace_poly_values = trace_rows_to_poly_values(stark.generate_trace(p));
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof, &config).unwrap();
+        on_curve
+    }
+
+    #[test]
+    fn test_g1_on_curve() {
+        let g = G1Affine::generator();
+        assert!(prove_and_verify_g1(&g));
+        assert!(prove_and_verify_g1(&g.double()));
+        assert!(prove_and_verify_g1(&G1Affine::identity()));
+    }
+
+    #[test]
+    fn test_g1_off_curve() {
+        let g = G1Affine::generator();
+        let p = G1Affine {
+            x: g.x,
+            y: g.y + Fp::one(),
+            infinity: false,
+        };
+        assert!(!prove_and_verify_g1(&p));
+    }
+
+    #[cfg(feature = "debug-constraints")]
+    #[test]
+    fn test_g1_off_curve_claimed_on_curve() {
+        type S = G1OnCurveStark<F, D>;
+        let stark = S::new(16);
+        let g = G1Affine::generator();
+        let p = G1Affine {
+            x: g.x,
+            y: g.y + Fp::one(),
+            infinity: false,
+        };
+        let public_inputs = S::public_inputs(&p, true);
+        // The result flag of the trace is not set, which fails its link to the public inputs in the first row, after the first row constraint on the check selector and the links of the coordinates and the infinity flag.
+        assert_eq!(debug_check_trace(&stark, &stark.generate_trace(&p), &public_inputs), Some((0, 1 + G1_PIS_RES_OFFSET)));
+    }
+
+    #[test]
+    fn test_g2_on_curve() {
+        let g = G2Affine::generator();
+        assert!(prove_and_verify_g2(&g));
+        assert!(prove_and_verify_g2(&G2Affine::identity()));
+    }
+
+    #[test]
+    fn test_g2_off_curve() {
+        let g = G2Affine::generator();
+        let p = G2Affine {
+            x: g.x,
+            y: g.y + Fp2::one(),
+            infinity: false,
+        };
+        assert!(!prove_and_verify_g2(&p));
+    }
+}
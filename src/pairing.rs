@@ -0,0 +1,263 @@
+use plonky2::{
+    field::{
+        extension::{Extendable, FieldExtension},
+        packed::PackedField,
+        types::Field,
+    },
+    hash::hash_types::RichField,
+    iop::ext_target::ExtensionTarget,
+    plonk::config::GenericConfig,
+};
+use starky::{
+    config::StarkConfig,
+    constraint_consumer::ConstraintConsumer,
+    evaluation_frame::{StarkEvaluationFrame, StarkFrame},
+    proof::StarkProofWithPublicInputs,
+    stark::Stark,
+    verifier::verify_stark_proof,
+};
+
+use anyhow::{ensure, Result};
+
+use crate::native::{calc_pairing_precomp, Fp, Fp2, Fp12, G2Affine};
+
+use crate::final_exponentiate::*;
+use crate::miller_loop::*;
+
+/*
+    These offsets are for the pairing computation, i.e. miller_loop (super::native::miller_loop) followed by final_exponentiate (super::native::Fp12::final_exponentiate). This stark needs 8192 rows.
+    PAIRING_FINAL_EXP_OFFSET -> offset where the final exponentiate trace is filled.
+    PAIRING_MILLER_LOOP_OFFSET -> offset where the miller loop trace is filled. The result of the miller loop is constrained to be the input of final exponentiate in every row.
+*/
+pub const PAIRING_FINAL_EXP_OFFSET: usize = 0;
+pub const PAIRING_MILLER_LOOP_OFFSET: usize = PAIRING_FINAL_EXP_OFFSET + FINAL_EXP_TOTAL_COLUMNS;
+pub const PAIRING_TOTAL_COLUMNS: usize = PAIRING_MILLER_LOOP_OFFSET + MILLER_LOOP_TOTAL;
+
+pub const TOTAL_COLUMNS: usize = PAIRING_TOTAL_COLUMNS;
+pub const COLUMNS: usize = TOTAL_COLUMNS;
+
+/*
+    The public inputs for this stark are the G1 point (Px, Py), the `ell_coeffs` of the G2 point Q resulted from calc_pairing_precomp, then the result of the pairing.
+    Q is not in the trace, the miller loop only reads its `ell_coeffs`, and nothing in the constraints ties them to a G2 point. They are computed from Q by `PairingStark::public_inputs`, so a proof shows e(P, Q) to a verifier who lays out the public inputs from Q itself, and only the miller loop of some line coefficients to one who takes the `ell_coeffs` from the prover.
+    Proofs of this stark must be checked with `PairingStark::verify`, which rebuilds the public inputs from P, Q and the result and rejects a proof carrying any other public inputs.
+*/
+pub const PIS_PX_OFFSET: usize = 0;
+pub const PIS_PY_OFFSET: usize = PIS_PX_OFFSET + 12;
+pub const PIS_ELL_COEFFS_OFFSET: usize = PIS_PY_OFFSET + 12;
+pub const PIS_RES_OFFSET: usize = PIS_ELL_COEFFS_OFFSET + 68*24*3;
+pub const PUBLIC_INPUTS: usize = PIS_RES_OFFSET + 24*3*2;
+
+#[derive(Clone, Copy)]
+pub struct PairingStark<F: RichField + Extendable<D>, const D: usize> {
+    num_rows: usize,
+    _f: std::marker::PhantomData<F>,
+}
+
+// Implement trace generator
+impl<F: RichField + Extendable<D>, const D: usize> PairingStark<F, D> {
+    /// Rows used by the pairing, the most of the miller loop and of final exponentiate, which are side by side from the first row.
+    pub fn required_rows() -> usize {
+        MILLER_LOOP_ROWS.max(crate::final_exponentiate::TOTAL_ROW)
+    }
+
+    pub fn new(num_rows: usize) -> Self {
+        Self {
+            num_rows,
+            _f: std::marker::PhantomData,
+        }
+    }
+
+    /// Lays out the public inputs for this stark. Px and Py are placed at `PIS_PX_OFFSET` and `PIS_PY_OFFSET`, the `ell_coeffs` of `q` computed by `calc_pairing_precomp` at `PIS_ELL_COEFFS_OFFSET` and the pairing result at `PIS_RES_OFFSET`. Returns an error if `q` is the point at infinity or is not on the G2 curve.
+    pub fn public_inputs(x: &Fp, y: &Fp, q: &G2Affine, res: &Fp12) -> Result<Vec<F>> {
+        let ell_coeffs = pairing_precomp(q)?;
+        let mut public_inputs = vec![F::ZERO; PUBLIC_INPUTS];
+        for i in 0..12 {
+            public_inputs[PIS_PX_OFFSET + i] = F::from_canonical_u32(x.0[i]);
+            public_inputs[PIS_PY_OFFSET + i] = F::from_canonical_u32(y.0[i]);
+        }
+        for (j, coeff) in ell_coeffs.iter().enumerate() {
+            for (k, e) in coeff.iter().flat_map(|f2| f2.get_u32_slice().concat()).enumerate() {
+                public_inputs[PIS_ELL_COEFFS_OFFSET + j*24*3 + k] = F::from_canonical_u32(e);
+            }
+        }
+        for (i, e) in res.get_u32_slice().concat().iter().enumerate() {
+            public_inputs[PIS_RES_OFFSET + i] = F::from_canonical_u32(*e);
+        }
+        Ok(public_inputs)
+    }
+
+    /// Fills the trace for the pairing of (x, y) with `q`. First fills the miller loop trace at `PAIRING_MILLER_LOOP_OFFSET` with the `ell_coeffs` of `q`, then fills the final exponentiate trace with the miller loop result as input. Returns an error for the same `q` as [PairingStark::public_inputs].
+    pub fn generate_trace(&self, x: Fp, y: Fp, q: &G2Affine) -> Result<Vec<[F; TOTAL_COLUMNS]>> {
+        let ell_coeffs = pairing_precomp(q)?;
+        let mut trace = vec![[F::ZERO; TOTAL_COLUMNS]; self.num_rows];
+        let f12 = fill_trace_miller_loop(&mut trace, &x, &y, &ell_coeffs, 0, self.num_rows-1, PAIRING_MILLER_LOOP_OFFSET);
+        fill_trace_final_exponentiate(&mut trace, &f12, PAIRING_FINAL_EXP_OFFSET)?;
+        Ok(trace)
+    }
+
+    /// Verifies a proof that the pairing of (x, y) with `q` is `res`. The public inputs are rebuilt with [PairingStark::public_inputs], so the `ell_coeffs` are computed from `q` by the verifier, and the proof is rejected if its public inputs differ from them before the stark proof is checked.
+    /// Returns an error if `q` is not a finite point on the G2 curve, if the public inputs of the proof do not match, or if the proof does not verify.
+    pub fn verify<C: GenericConfig<D, F = F>>(&self, config: &StarkConfig, proof: StarkProofWithPublicInputs<F, C, D>, x: &Fp, y: &Fp, q: &G2Affine, res: &Fp12) -> Result<()> {
+        let public_inputs = Self::public_inputs(x, y, q, res)?;
+        ensure!(proof.public_inputs == public_inputs, "PairingStark::verify: the public inputs of the proof are not the ones of the given P, Q and result");
+        verify_stark_proof(*self, proof, config)
+    }
+}
+
+/// Returns the 68 `ell_coeffs` of `q` computed by `calc_pairing_precomp`, which the miller loop trace reads in place of the G2 point. Returns an error if `q` is the point at infinity or is not on the G2 curve.
+pub fn pairing_precomp(q: &G2Affine) -> Result<Vec<[Fp2; 3]>> {
+    ensure!(!q.infinity && q.is_on_curve(), "the G2 point is not a finite point on the curve");
+    Ok(calc_pairing_precomp(q.x, q.y, Fp2::one()))
+}
+
+/*
+    The constraints of this stark are as follows:
+    * Constraints Px, Py and `ell_coeffs` of the miller loop trace with the public inputs.
+    * Constraints the miller loop result to be equal to the final exponentiate input, limb by limb, in every row.
+    * Constraints the final exponentiate output (T31) with the public inputs in the last row.
+    * Constraints the miller loop and final exponentiate computations.
+*/
+
+// Implement constraint generator
+impl<F: RichField + Extendable<D>, const D: usize> Stark<F, D> for PairingStark<F, D> {
+    type EvaluationFrame<FE, P, const D2: usize> = StarkFrame<P, P::Scalar, COLUMNS, PUBLIC_INPUTS>
+    where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>;
+
+    fn eval_packed_generic<FE, P, const D2: usize>(
+        &self,
+        vars: &Self::EvaluationFrame<FE, P, D2>,
+        yield_constr: &mut ConstraintConsumer<P>,
+    ) where
+        FE: FieldExtension<D2, BaseField = F>,
+        P: PackedField<Scalar = FE>,
+    {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        // ----
+        for i in 0..12 {
+            yield_constr.constraint(
+                local_values[PAIRING_MILLER_LOOP_OFFSET + PX_OFFSET + i] - public_inputs[PIS_PX_OFFSET + i]
+            );
+            yield_constr.constraint(
+                local_values[PAIRING_MILLER_LOOP_OFFSET + PY_OFFSET + i] - public_inputs[PIS_PY_OFFSET + i]
+            );
+        }
+        for i in 0..68 {
+            for j in 0..24*3 {
+                yield_constr.constraint(
+                    local_values[PAIRING_MILLER_LOOP_OFFSET + ELL_COEFFS_INDEX_OFFEST + i] *
+                    (local_values[PAIRING_MILLER_LOOP_OFFSET + ELL_COEFFS_OFFSET + j] - public_inputs[PIS_ELL_COEFFS_OFFSET + i*24*3 + j])
+                );
+            }
+        }
+        for i in 0..24*3*2 {
+            yield_constr.constraint(
+                local_values[PAIRING_MILLER_LOOP_OFFSET + MILLER_LOOP_RES_OFFSET + i] -
+                local_values[PAIRING_FINAL_EXP_OFFSET + FINAL_EXP_INPUT_OFFSET + i]
+            );
+            yield_constr.constraint_last_row(
+                local_values[PAIRING_FINAL_EXP_OFFSET + FINAL_EXP_T31_OFFSET + i] - public_inputs[PIS_RES_OFFSET + i]
+            );
+        }
+
+        add_miller_loop_constraints(local_values, next_values, yield_constr, PAIRING_MILLER_LOOP_OFFSET, None);
+        add_final_exponentiate_constraints(local_values, next_values, yield_constr, self.num_rows, PAIRING_FINAL_EXP_OFFSET);
+    }
+
+    type EvaluationFrameTarget =
+        StarkFrame<ExtensionTarget<D>, ExtensionTarget<D>, COLUMNS, PUBLIC_INPUTS>;
+
+    fn eval_ext_circuit(
+        &self,
+        builder: &mut plonky2::plonk::circuit_builder::CircuitBuilder<F, D>,
+        vars: &Self::EvaluationFrameTarget,
+        yield_constr: &mut starky::constraint_consumer::RecursiveConstraintConsumer<F, D>,
+    ) {
+        let local_values = vars.get_local_values();
+        let next_values = vars.get_next_values();
+        let public_inputs = vars.get_public_inputs();
+
+        // ---
+        for i in 0..12 {
+            let c1 = builder.sub_extension(local_values[PAIRING_MILLER_LOOP_OFFSET + PX_OFFSET + i], public_inputs[PIS_PX_OFFSET + i]);
+            yield_constr.constraint(builder, c1);
+
+            let c2 = builder.sub_extension(local_values[PAIRING_MILLER_LOOP_OFFSET + PY_OFFSET + i], public_inputs[PIS_PY_OFFSET + i]);
+            yield_constr.constraint(builder, c2);
+        }
+        for i in 0..68 {
+            for j in 0..24*3 {
+                let sub_tmp = builder.sub_extension(local_values[PAIRING_MILLER_LOOP_OFFSET + ELL_COEFFS_OFFSET + j], public_inputs[PIS_ELL_COEFFS_OFFSET + i*24*3 + j]);
+                let c = builder.mul_extension(local_values[PAIRING_MILLER_LOOP_OFFSET + ELL_COEFFS_INDEX_OFFEST + i], sub_tmp);
+                yield_constr.constraint(builder, c);
+            }
+        }
+        for i in 0..24*3*2 {
+            let c = builder.sub_extension(local_values[PAIRING_MILLER_LOOP_OFFSET + MILLER_LOOP_RES_OFFSET + i], local_values[PAIRING_FINAL_EXP_OFFSET + FINAL_EXP_INPUT_OFFSET + i]);
+            yield_constr.constraint(builder, c);
+
+            let c = builder.sub_extension(local_values[PAIRING_FINAL_EXP_OFFSET + FINAL_EXP_T31_OFFSET + i], public_inputs[PIS_RES_OFFSET + i]);
+            yield_constr.constraint_last_row(builder, c);
+        }
+
+        add_miller_loop_constraints_ext_circuit(builder, yield_constr, local_values, next_values, PAIRING_MILLER_LOOP_OFFSET, None);
+        add_final_exponentiate_constraints_ext_circuit(builder, yield_constr, local_values, next_values, self.num_rows, PAIRING_FINAL_EXP_OFFSET);
+    }
+
+    fn constraint_degree(&self) -> usize {
+        FINAL_EXP_CONSTRAINT_DEGREE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::native::{self, Fp, Fp2, Fp12, G2Affine};
+
+    use super::{PairingStark, PUBLIC_INPUTS};
+
+    #[test]
+    fn test_pairing_generators() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = PairingStark<F, D>;
+
+        let g1_x = Fp([3676489403, 4214943754, 4185529071, 1817569343, 387689560, 2706258495, 2541009157, 3278408783, 1336519695, 647324556, 832034708, 401724327]);
+        let g1_y = Fp([1187375073, 212476713, 2726857444, 3493644100, 738505709, 14358731, 3587181302, 4243972245, 1948093156, 2694721773, 3819610353, 146011265]);
+        let g2_x = Fp2([Fp([3240213944, 3565180616, 2818948079, 195822374, 2061750647, 3025210212, 4198513410, 3336862420, 767889489, 638059815, 4035906193, 38445746]), Fp([1560554366, 3853286661, 328490327, 860680466, 3699331145, 3050987963, 2569057818, 1500238032, 2284277605, 2108478368, 1383178080, 333458272])]);
+        let g2_y = Fp2([Fp([146286593, 3784529030, 1001169545, 2453326284, 1365299500, 1833081449, 2361250727, 2919078826, 3660461338, 2362035654, 1920822801, 216388903]), Fp([4032788926, 2863204191, 1558977953, 1060572455, 1462671787, 645173931, 2242339759, 3409848446, 734170009, 850186928, 782709964, 101106848])]);
+        let g2_z = Fp2::one();
+
+        let mut config = StarkConfig::standard_fast_config();
+        config.fri_config.rate_bits = 2;
+        let stark = S::new(8192);
+        let g2 = G2Affine { x: g2_x, y: g2_y, infinity: false };
+        assert_eq!(g2, G2Affine::generator());
+        let res = native::miller_loop(g1_x, g1_y, g2_x, g2_y, g2_z).final_exponentiate();
+        assert_ne!(res, Fp12::one());
+        let public_inputs = S::public_inputs(&g1_x, &g1_y, &g2, &res).unwrap();
+        assert_eq!(public_inputs.len(), PUBLIC_INPUTS);
+        assert!(S::public_inputs(&g1_x, &g1_y, &G2Affine::identity(), &res).is_err());
+        let trace = stark.generate_trace(g1_x, g1_y, &g2).unwrap();
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+        verify_stark_proof(stark, proof.clone(), &config).unwrap();
+        stark.verify(&config, proof.clone(), &g1_x, &g1_y, &g2, &res).unwrap();
+        assert!(stark.verify(&config, proof.clone(), &g1_x, &g1_y, &-g2, &res).is_err());
+        assert!(stark.verify(&config, proof.clone(), &g1_x, &g1_y, &g2, &(res * res)).is_err());
+        assert!(stark.verify(&config, proof, &g1_x, &g1_y, &G2Affine::identity(), &res).is_err());
+    }
+}
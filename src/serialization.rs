@@ -0,0 +1,118 @@
+use anyhow::{bail, Result};
+use plonky2::{
+    field::extension::Extendable,
+    fri::proof::FriProof,
+    hash::{hash_types::RichField, merkle_tree::MerkleCap},
+    plonk::config::{GenericConfig, Hasher},
+};
+use serde::{Deserialize, Serialize};
+use starky::proof::{StarkOpeningSet, StarkProof, StarkProofWithPublicInputs};
+
+/// Version of the byte format produced by [serialize_proof]. Bumped whenever the layout of [SerializedStarkProof] changes.
+pub const PROOF_FORMAT_VERSION: u32 = 1;
+
+/// Serde mirror of [StarkProofWithPublicInputs], since starky does not derive serde for its proof types. The fields follow the starky proof, with the opening set flattened, and the format version first.
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "")]
+struct SerializedStarkProof<F: RichField + Extendable<D>, H: Hasher<F>, const D: usize> {
+    version: u32,
+    trace_cap: MerkleCap<F, H>,
+    permutation_zs_cap: Option<MerkleCap<F, H>>,
+    quotient_polys_cap: MerkleCap<F, H>,
+    local_values: Vec<F::Extension>,
+    next_values: Vec<F::Extension>,
+    permutation_zs: Option<Vec<F::Extension>>,
+    permutation_zs_next: Option<Vec<F::Extension>>,
+    quotient_polys: Vec<F::Extension>,
+    opening_proof: FriProof<F, H, D>,
+    public_inputs: Vec<F>,
+}
+
+/// Serializes a stark proof along with its public inputs to bytes, which can be stored and later read back with [deserialize_proof].
+pub fn serialize_proof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    proof: &StarkProofWithPublicInputs<F, C, D>,
+) -> Result<Vec<u8>> {
+    let p = &proof.proof;
+    let serialized = SerializedStarkProof::<F, C::Hasher, D> {
+        version: PROOF_FORMAT_VERSION,
+        trace_cap: p.trace_cap.clone(),
+        permutation_zs_cap: p.permutation_zs_cap.clone(),
+        quotient_polys_cap: p.quotient_polys_cap.clone(),
+        local_values: p.openings.local_values.clone(),
+        next_values: p.openings.next_values.clone(),
+        permutation_zs: p.openings.permutation_zs.clone(),
+        permutation_zs_next: p.openings.permutation_zs_next.clone(),
+        quotient_polys: p.openings.quotient_polys.clone(),
+        opening_proof: p.opening_proof.clone(),
+        public_inputs: proof.public_inputs.clone(),
+    };
+    Ok(serde_json::to_vec(&serialized)?)
+}
+
+/// Reconstructs a stark proof along with its public inputs from bytes produced by [serialize_proof]. Returns an error if the bytes are malformed or were produced with a different format version.
+pub fn deserialize_proof<F: RichField + Extendable<D>, C: GenericConfig<D, F = F>, const D: usize>(
+    bytes: &[u8],
+) -> Result<StarkProofWithPublicInputs<F, C, D>> {
+    let s: SerializedStarkProof<F, C::Hasher, D> = serde_json::from_slice(bytes)?;
+    if s.version != PROOF_FORMAT_VERSION {
+        bail!("deserialize_proof: unsupported proof format version {}, expected {}", s.version, PROOF_FORMAT_VERSION);
+    }
+    Ok(StarkProofWithPublicInputs {
+        proof: StarkProof {
+            trace_cap: s.trace_cap,
+            permutation_zs_cap: s.permutation_zs_cap,
+            quotient_polys_cap: s.quotient_polys_cap,
+            openings: StarkOpeningSet {
+                local_values: s.local_values,
+                next_values: s.next_values,
+                permutation_zs: s.permutation_zs,
+                permutation_zs_next: s.permutation_zs_next,
+                quotient_polys: s.quotient_polys,
+            },
+            opening_proof: s.opening_proof,
+        },
+        public_inputs: s.public_inputs,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use num_bigint::BigUint;
+    use plonky2::{plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::fp_inverse::FpInverseStark;
+    use crate::native::{modulus, Fp};
+
+    use super::{deserialize_proof, serialize_proof};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+    type S = FpInverseStark<F, D>;
+
+    #[test]
+    fn test_proof_serialization_round_trip() {
+        let config = StarkConfig::standard_fast_config();
+        let stark = S::new(16);
+        let a = Fp::get_fp_from_biguint(modulus() - BigUint::from(5u32));
+        let public_inputs = S::public_inputs(&a, &a.invert());
+        let trace = stark.generate_trace(&a).unwrap();
+        let trace_poly_values = trace_rows_to_poly_values(trace);
+        let proof = prove::<F, C, S, D>(
+            stark,
+            &config,
+            trace_poly_values,
+            &public_inputs,
+            &mut TimingTree::default(),
+        ).unwrap();
+
+        let bytes = serialize_proof(&proof).unwrap();
+        let deserialized = deserialize_proof::<F, C, D>(&bytes).unwrap();
+        assert_eq!(deserialized.public_inputs, public_inputs);
+        assert_eq!(serialize_proof(&deserialized).unwrap(), bytes);
+        verify_stark_proof(stark, deserialized, &config).unwrap();
+
+        assert!(deserialize_proof::<F, C, D>(&bytes[..bytes.len() / 2]).is_err());
+    }
+}
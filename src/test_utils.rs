@@ -0,0 +1,71 @@
+//! Random field elements for property tests and fuzzing. Only available with the `test-utils` feature.
+
+use num_bigint::RandBigInt;
+use rand::Rng;
+
+use crate::native::{modulus, Fp, Fp12, Fp2, Fp6};
+
+/// Uniformly random fp element below `modulus()`.
+pub fn random_fp<R: Rng + ?Sized>(rng: &mut R) -> Fp {
+    Fp::get_fp_from_biguint(rng.gen_biguint_below(&modulus()))
+}
+
+pub fn random_fp2<R: Rng + ?Sized>(rng: &mut R) -> Fp2 {
+    Fp2([random_fp(rng), random_fp(rng)])
+}
+
+pub fn random_fp6<R: Rng + ?Sized>(rng: &mut R) -> Fp6 {
+    Fp6([(); 6].map(|_| random_fp(rng)))
+}
+
+pub fn random_fp12<R: Rng + ?Sized>(rng: &mut R) -> Fp12 {
+    Fp12([(); 12].map(|_| random_fp(rng)))
+}
+
+/// Random element of the cyclotomic subgroup, i.e. `f^((p^6 - 1)(p^2 + 1))` for a random non-zero `f`, which is the first part of the final exponentiation.
+pub fn random_fp12_in_cyclotomic_subgroup<R: Rng + ?Sized>(rng: &mut R) -> Fp12 {
+    let f = loop {
+        let f = random_fp12(rng);
+        if f.get_u32_slice().iter().any(|limbs| limbs.iter().any(|limb| *limb != 0)) {
+            break f;
+        }
+    };
+    f.final_exponentiate_easy_part()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use crate::native::{Fp, Fp12};
+
+    use super::{random_fp12, random_fp12_in_cyclotomic_subgroup};
+
+    #[test]
+    fn test_random_fp12_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for x in [random_fp12(&mut rng), random_fp12_in_cyclotomic_subgroup(&mut rng)] {
+            let limbs = x.get_u32_slice();
+            assert_eq!(Fp12(limbs.map(Fp)).get_u32_slice(), limbs);
+        }
+    }
+
+    #[test]
+    fn test_random_fp12_in_cyclotomic_subgroup() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let x = random_fp12_in_cyclotomic_subgroup(&mut rng);
+        // The conjugate is the inverse in the cyclotomic subgroup
+        assert_eq!(x * x.cyclotomic_inverse(), Fp12::one());
+    }
+
+    #[test]
+    fn test_easy_part_is_in_cyclotomic_subgroup() {
+        let mut rng = StdRng::seed_from_u64(2);
+        for _ in 0..8 {
+            let x = random_fp12(&mut rng);
+            assert!(!x.is_in_cyclotomic_subgroup());
+            assert!(x.final_exponentiate_easy_part().is_in_cyclotomic_subgroup());
+            assert!(random_fp12_in_cyclotomic_subgroup(&mut rng).is_in_cyclotomic_subgroup());
+        }
+    }
+}
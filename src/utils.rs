@@ -1,4 +1,26 @@
-use plonky2::{field::extension::Extendable, hash::hash_types::RichField};
+use plonky2::{field::{extension::Extendable, polynomial::PolynomialValues, types::Field}, hash::hash_types::RichField};
+use starky::{constraint_consumer::ConstraintConsumer, evaluation_frame::StarkEvaluationFrame, stark::Stark};
+
+use crate::native::{Fp, Fp12, LimbRepr};
+
+/// Number of chunks in which the rows are moved out of the trace by [trace_rows_to_poly_values_streaming].
+pub const TRANSPOSE_CHUNKS: usize = 16;
+
+/// Layout of the public inputs of a stark. `encode` lays out the values in the order the stark constrains them, and `decode` reads them back, e.g. from the public inputs of a proof.
+pub trait StarkPublicInputs<F: RichField>: Sized {
+    fn encode(&self) -> Vec<F>;
+    fn decode(pis: &[F]) -> Self;
+}
+
+/// Reads back the Fp12 whose 144 limbs start at `offset` in the public inputs `pis`, in the order of [LimbRepr::limbs]. Inverse of setting the limbs with `F::from_canonical_u32`, e.g. to read the proven output of a stark.
+/// Panics if `pis` has less than `offset + 144` elements or if a limb is not a canonical u32.
+pub fn fp12_from_public_inputs<F: RichField>(pis: &[F], offset: usize) -> Fp12 {
+    assert!(pis.len() >= offset + 144, "fp12_from_public_inputs: {} public inputs, expected at least {}", pis.len(), offset + 144);
+    Fp12(core::array::from_fn(|i| Fp(core::array::from_fn(|j| {
+        let limb = pis[offset + i*12 + j].to_canonical_u64();
+        u32::try_from(limb).unwrap_or_else(|_| panic!("fp12_from_public_inputs: public input {} is {}, not a u32", offset + i*12 + j, limb))
+    }))))
+}
 
 pub fn assign_u32_12<F: RichField + Extendable<D>,
     const D: usize,
@@ -18,6 +40,15 @@ pub fn assign_u32_in_series<F: RichField + Extendable<D>,
     }
 }
 
+/// Sets the limbs of `val`, in the order of [LimbRepr::limbs], from `start_col` in `row`.
+pub fn assign_limbs<F: RichField + Extendable<D>,
+    const D: usize,
+    const C: usize,
+    T: LimbRepr,
+>(trace: &mut Vec<[F; C]>, row: usize, start_col: usize, val: &T) {
+    assign_u32_in_series(trace, row, start_col, &val.limbs());
+}
+
 pub fn assign_cols_from_prev<F: RichField + Extendable<D>,
     const D: usize,
     const C: usize,
@@ -27,3 +58,141 @@ pub fn assign_cols_from_prev<F: RichField + Extendable<D>,
         trace[row][start_col + i] = trace[row - 1][start_col + i];
     }
 }
+
+/// Transposes the row-major trace into column polynomials, like `starky::util::trace_rows_to_poly_values`, without holding both copies of the trace at once.
+/// The rows are moved out in [TRANSPOSE_CHUNKS] chunks, and the memory of each chunk is released before the next one is pushed into the columns. The columns are only touched as they are filled, so the peak memory stays close to one copy of the trace plus a chunk.
+pub fn trace_rows_to_poly_values_streaming<F: Field, const C: usize>(mut trace: Vec<[F; C]>) -> Vec<PolynomialValues<F>> {
+    let num_rows = trace.len();
+    let chunk = (num_rows / TRANSPOSE_CHUNKS).max(1);
+    let mut columns = (0..C).map(|_| Vec::with_capacity(num_rows)).collect::<Vec<Vec<F>>>();
+    // Reversed so that the first rows are drained from the end of the vector
+    trace.reverse();
+    while !trace.is_empty() {
+        let start = trace.len().saturating_sub(chunk);
+        for row in trace.drain(start..).rev() {
+            for (column, value) in columns.iter_mut().zip(row) {
+                column.push(value);
+            }
+        }
+        trace.shrink_to_fit();
+    }
+    columns.into_iter().map(PolynomialValues::new).collect()
+}
+
+/// Number of constraints emitted by `eval_packed_generic` of `stark`, counting the constraints of all types (transition, first row, last row). Along with [Stark::constraint_degree], it sizes the recursive verifier circuit of the stark before building it.
+/// The constraint consumer only exposes the constraints combined with powers of alpha, so the constraints are evaluated twice over a zero frame, once after a constraint 1. The difference is alpha^n for n constraints, and alpha is taken of order 2^TWO_ADICITY so that n is found by counting its powers.
+pub fn num_constraints<F: RichField + Extendable<D>, S: Stark<F, D>, const D: usize>(stark: &S) -> usize {
+    let alpha = F::primitive_root_of_unity(F::TWO_ADICITY);
+    let values = vec![F::ZERO; <S::EvaluationFrame<F, F, 1> as StarkEvaluationFrame<F, F>>::COLUMNS];
+    let public_inputs = vec![F::ZERO; <S::EvaluationFrame<F, F, 1> as StarkEvaluationFrame<F, F>>::PUBLIC_INPUTS];
+    let frame = <S::EvaluationFrame<F, F, 1> as StarkEvaluationFrame<F, F>>::from_values(&values, &values, &public_inputs);
+    let eval = |sentinel: bool| {
+        let mut yield_constr = ConstraintConsumer::new(vec![alpha], F::ONE, F::ONE, F::ONE);
+        if sentinel {
+            yield_constr.constraint(F::ONE);
+        }
+        stark.eval_packed_generic::<F, F, 1>(&frame, &mut yield_constr);
+        yield_constr.accumulators()[0]
+    };
+    let alpha_n = eval(true) - eval(false);
+    let mut power = F::ONE;
+    let mut n = 0;
+    while power != alpha_n {
+        power *= alpha;
+        n += 1;
+    }
+    n
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, process::Command};
+
+    use plonky2::{field::types::Field, plonk::config::{GenericConfig, PoseidonGoldilocksConfig}};
+    use starky::util::trace_rows_to_poly_values;
+
+    use crate::final_exponentiate::{FinalExponentiatePublicInputs, FINAL_EXP_MAX_ROWS, PIS_INPUT_OFFSET, PIS_OUTPUT_OFFSET, TOTAL_COLUMNS};
+    use crate::native::{Fp, Fp12};
+
+    use super::{fp12_from_public_inputs, trace_rows_to_poly_values_streaming, StarkPublicInputs};
+
+    const D: usize = 2;
+    type C = PoseidonGoldilocksConfig;
+    type F = <C as GenericConfig<D>>::F;
+
+    #[test]
+    fn test_trace_rows_to_poly_values_streaming() {
+        for num_rows in [1, 15, 16, 100] {
+            let trace = (0..num_rows).map(|row| {
+                [0, 1, 2].map(|col| F::from_canonical_usize(row * 3 + col))
+            }).collect::<Vec<[F; 3]>>();
+            let expected = trace_rows_to_poly_values(trace.clone());
+            let res = trace_rows_to_poly_values_streaming(trace);
+            assert_eq!(res.len(), expected.len());
+            for (col, expected_col) in res.iter().zip(expected.iter()) {
+                assert_eq!(col.values, expected_col.values);
+            }
+        }
+    }
+
+    #[test]
+    fn test_fp12_from_public_inputs() {
+        let input = Fp12(core::array::from_fn(|i| Fp::get_fp_from_biguint((i as u64 * 0x9e3779b9 + 1).into())));
+        let pis = FinalExponentiatePublicInputs { input, output: input.final_exponentiate() };
+        let encoded: Vec<F> = pis.encode();
+        assert_eq!(fp12_from_public_inputs(&encoded, PIS_INPUT_OFFSET), pis.input);
+        assert_eq!(fp12_from_public_inputs(&encoded, PIS_OUTPUT_OFFSET), pis.output);
+        assert_eq!(FinalExponentiatePublicInputs::decode(&encoded), pis);
+
+        // Limbs are u32, a field element of 2^32 is rejected.
+        let mut encoded = encoded;
+        encoded[PIS_OUTPUT_OFFSET + 5] = F::from_canonical_u64(1 << 32);
+        assert!(std::panic::catch_unwind(|| fp12_from_public_inputs(&encoded, PIS_OUTPUT_OFFSET)).is_err());
+        assert!(std::panic::catch_unwind(|| fp12_from_public_inputs(&encoded[..PIS_OUTPUT_OFFSET + 143], PIS_OUTPUT_OFFSET)).is_err());
+    }
+
+    fn peak_rss_kb() -> u64 {
+        let status = fs::read_to_string("/proc/self/status").unwrap();
+        let line = status.lines().find(|line| line.starts_with("VmHWM:")).unwrap();
+        line.split_whitespace().nth(1).unwrap().parse().unwrap()
+    }
+
+    /// Transposes a trace of the final exponentiation dimensions with the transpose selected by `TRANSPOSE_MODE`, then prints the peak RSS of the process. Run by [bench_transpose_peak_rss] in a separate process.
+    #[test]
+    #[ignore]
+    fn transpose_peak_rss_child() {
+        let mode = match env::var("TRANSPOSE_MODE") {
+            Ok(mode) => mode,
+            Err(_) => return,
+        };
+        let trace = vec![[F::ONE; TOTAL_COLUMNS]; FINAL_EXP_MAX_ROWS];
+        let columns = match mode.as_str() {
+            "rows" => trace_rows_to_poly_values(trace),
+            "streaming" => trace_rows_to_poly_values_streaming(trace),
+            _ => panic!("unknown transpose mode {}", mode),
+        };
+        assert_eq!(columns.len(), TOTAL_COLUMNS);
+        println!("peak_rss_kb={}", peak_rss_kb());
+    }
+
+    /// Compares the peak RSS of both transposes on the final exponentiation trace (TOTAL_COLUMNS x FINAL_EXP_MAX_ROWS).
+    /// Run with `cargo test --release bench_transpose_peak_rss -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_transpose_peak_rss() {
+        let peak_rss = ["rows", "streaming"].map(|mode| {
+            let output = Command::new(env::current_exe().unwrap())
+                .args(["utils::tests::transpose_peak_rss_child", "--exact", "--ignored", "--nocapture"])
+                .env("TRANSPOSE_MODE", mode)
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+            let stdout = String::from_utf8(output.stdout).unwrap();
+            let line = stdout.lines().find(|line| line.starts_with("peak_rss_kb=")).unwrap();
+            let kb = line["peak_rss_kb=".len()..].parse::<u64>().unwrap();
+            println!("{} transpose: peak RSS {} MB", mode, kb / 1024);
+            kb
+        });
+        assert!(peak_rss[1] < peak_rss[0]);
+    }
+}
@@ -0,0 +1,128 @@
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::native::{decompress_g1, decompress_g2, G1Affine, G2Affine};
+
+/// `deserialization_G1` vector, a G1 point which is valid if it is a well formed compressed point in the prime order subgroup.
+pub const DESERIALIZATION_G1_CORRECT_POINT: &str = include_str!("../test_vectors/deserialization_G1/deserialization_succeeds_correct_point.json");
+/// `deserialization_G1` vector, a point on the curve which is not in the prime order subgroup.
+pub const DESERIALIZATION_G1_NOT_IN_G1: &str = include_str!("../test_vectors/deserialization_G1/deserialization_fails_not_in_G1.json");
+
+/// Test vector in the format of the BLS vectors of `consensus-spec-tests` and `ethereum/bls12-381-tests`, an `input` object of hex encoded fields and the expected `output`.
+#[derive(Deserialize)]
+struct RawTestVector {
+    input: Value,
+    output: Value,
+}
+
+/// Test vector converted to the native types of the crate. The kind of the vector is inferred from the fields of its input.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EthTestVector {
+    /// `deserialization_G1` vector. `point` is the decompressed point, `None` if the encoding is invalid. `expected` is whether the encoding is a valid public key, i.e. a point in the prime order subgroup.
+    DeserializationG1 {
+        pubkey: [u8; 48],
+        point: Option<G1Affine>,
+        expected: bool,
+    },
+    /// `verify` vector. `signature_point` is the decompressed signature, `None` if its encoding is invalid. It is boxed to keep the variants of the enum close in size.
+    Verify {
+        pubkey: [u8; 48],
+        point: Option<G1Affine>,
+        message: Vec<u8>,
+        signature: [u8; 96],
+        signature_point: Option<Box<G2Affine>>,
+        expected: bool,
+    },
+}
+
+/// Decodes a hex string, with or without the `0x` prefix.
+fn decode_hex(value: &Value, name: &str) -> Result<Vec<u8>> {
+    let s = value.as_str().ok_or_else(|| anyhow!("{} is not a string", name))?;
+    Ok(hex::decode(s.strip_prefix("0x").unwrap_or(s))?)
+}
+
+/// Decodes a hex string of exactly `N` bytes.
+fn decode_hex_array<const N: usize>(value: &Value, name: &str) -> Result<[u8; N]> {
+    let bytes = decode_hex(value, name)?;
+    match bytes.try_into() {
+        Ok(bytes) => Ok(bytes),
+        Err(bytes) => bail!("{} has {} bytes, expected {}", name, bytes.len(), N),
+    }
+}
+
+/// Parses a test vector from the JSON format of `consensus-spec-tests`, with hex encoded points and the expected result, into [EthTestVector]. Supports the `deserialization_G1` and `verify` vectors.
+/// Only the JSON encoding is supported, the YAML files of `consensus-spec-tests` have to be converted first.
+pub fn from_eth_test_vector(json: &str) -> Result<EthTestVector> {
+    let raw: RawTestVector = serde_json::from_str(json)?;
+    let input = raw.input.as_object().ok_or_else(|| anyhow!("input is not an object"))?;
+    let field = |name: &str| input.get(name).ok_or_else(|| anyhow!("missing input field {}", name));
+    let pubkey = decode_hex_array::<48>(field("pubkey")?, "pubkey")?;
+    let point = decompress_g1(&pubkey);
+    // Invalid inputs of `verify` vectors have a null output instead of false.
+    let expected = raw.output.as_bool().unwrap_or(false);
+    match (input.get("message"), input.get("signature")) {
+        (None, None) => Ok(EthTestVector::DeserializationG1 { pubkey, point, expected }),
+        (Some(message), Some(signature)) => {
+            let signature = decode_hex_array::<96>(signature, "signature")?;
+            Ok(EthTestVector::Verify {
+                pubkey,
+                point,
+                message: decode_hex(message, "message")?,
+                signature,
+                signature_point: decompress_g2(&signature).map(Box::new),
+                expected,
+            })
+        }
+        _ => bail!("unsupported test vector input"),
+    }
+}
+
+#[cfg(all(test, feature = "prover"))]
+mod tests {
+    use plonky2::{field::types::Field, plonk::config::{GenericConfig, PoseidonGoldilocksConfig}, util::timing::TimingTree};
+    use starky::{config::StarkConfig, prover::prove, util::trace_rows_to_poly_values, verifier::verify_stark_proof};
+
+    use crate::g1_subgroup::{G1SubgroupCheckStark, PIS_IS_MEMBER_OFFSET};
+
+    use super::{from_eth_test_vector, EthTestVector, DESERIALIZATION_G1_CORRECT_POINT, DESERIALIZATION_G1_NOT_IN_G1};
+
+    #[test]
+    fn test_from_eth_test_vector_invalid() {
+        assert!(from_eth_test_vector("{}").is_err());
+        assert!(from_eth_test_vector(r#"{"input": {"pubkey": "0x00"}, "output": false}"#).is_err());
+        assert!(from_eth_test_vector(r#"{"input": {"pubkey": "zz"}, "output": false}"#).is_err());
+    }
+
+    #[test]
+    fn test_deserialization_g1_vectors() {
+        const D: usize = 2;
+        type C = PoseidonGoldilocksConfig;
+        type F = <C as GenericConfig<D>>::F;
+        type S = G1SubgroupCheckStark<F, D>;
+
+        for vector in [DESERIALIZATION_G1_CORRECT_POINT, DESERIALIZATION_G1_NOT_IN_G1] {
+            let (point, expected) = match from_eth_test_vector(vector).unwrap() {
+                EthTestVector::DeserializationG1 { point, expected, .. } => (point.unwrap(), expected),
+                v => panic!("unexpected test vector {:?}", v),
+            };
+            assert!(point.is_on_curve());
+            assert_eq!(point.is_in_subgroup(), expected);
+
+            let config = StarkConfig::standard_fast_config();
+            let stark = S::new(4096);
+            let public_inputs = S::public_inputs(&point).unwrap();
+            assert_eq!(public_inputs[PIS_IS_MEMBER_OFFSET], F::from_bool(expected));
+            let trace = stark.generate_trace(&point).unwrap();
+            let trace_poly_values = trace_rows_to_poly_values(trace);
+            let proof = prove::<F, C, S, D>(
+                stark,
+                &config,
+                trace_poly_values,
+                &public_inputs,
+                &mut TimingTree::default(),
+            ).unwrap();
+            verify_stark_proof(stark, proof, &config).unwrap();
+        }
+    }
+}
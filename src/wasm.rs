@@ -0,0 +1,27 @@
+//! Native arithmetic entry points for `wasm32` targets, e.g. to run pre-flight checks in the browser before requesting a proof.
+//! The module is only compiled for `target_arch = "wasm32"`, so building the crate with `cargo build --lib --target wasm32-unknown-unknown --no-default-features` checks that the native arithmetic compiles without the prover.
+
+use crate::native::{miller_loop, Fp12, Fp2, G1Affine, G2Affine};
+
+/// [forbenius_map](super::native::Fp12::forbenius_map) of `x` with power `pow`.
+pub fn fp12_forbenius_map(x: &Fp12, pow: usize) -> Fp12 {
+    x.forbenius_map(pow)
+}
+
+/// [cyclotomic_square](super::native::Fp12::cyclotomic_square) of `x`, which must be in the cyclotomic subgroup.
+pub fn fp12_cyclotomic_square(x: &Fp12) -> Fp12 {
+    x.cyclotomic_square()
+}
+
+/// [final_exponentiate](super::native::Fp12::final_exponentiate) of `x`.
+pub fn fp12_final_exponentiate(x: &Fp12) -> Fp12 {
+    x.final_exponentiate()
+}
+
+/// Returns whether the product of the pairings of `pairs` is one, which is the check of BLS signature verification. Points at infinity contribute a factor of one.
+pub fn pairing_product_is_one(pairs: &[(G1Affine, G2Affine)]) -> bool {
+    let looped = pairs.iter()
+        .filter(|(p, q)| !p.infinity && !q.infinity)
+        .fold(Fp12::one(), |acc, (p, q)| acc * miller_loop(p.x, p.y, q.x, q.y, Fp2::one()));
+    looped.final_exponentiate() == Fp12::one()
+}
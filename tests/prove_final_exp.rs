@@ -0,0 +1,68 @@
+use std::process::Command;
+
+use starky::config::StarkConfig;
+use starky_bls12_381::{
+    config::{DefaultConfig, DefaultField, EXTENSION_DEGREE},
+    final_exponentiate::{verify_final_exp, FinalExponentiateStark},
+    native::{modulus, Fp12},
+    serialization::deserialize_proof,
+};
+
+const D: usize = EXTENSION_DEGREE;
+type C = DefaultConfig;
+type F = DefaultField;
+type S = FinalExponentiateStark<F, D>;
+
+fn test_input() -> Fp12 {
+    Fp12::from_str(["2181142506194812233868097821779361009807326315828153071050324314717744521676711650071190927260282422014627435089208",
+        "3266212670671256779826008414922395966600400122723332695666308996296105595418386213353825620535446475769829785237189",
+        "3280330655787598118299804758957910379684134784964426565939861302675766948066521588562898980898245868682162153155911",
+        "333668007718210311816046938245689395232794221928183840372182128979685996722059498232053963662509478803385469716056",
+        "1650925102445293819378017648160637800280351377141029658990698964033732511884552459036333864590686008335846481856882",
+        "3925133212240632255860280854235945320282874550806663137653784505923891479863770370026712801361887427462376126696706",
+        "2444089052091192833501409081021321360112867893942837175254954622703299880931587618210267154453853513743076365662283",
+        "3142914221549818039420055870398197863502329018278548609868118001898418737390067291084903575823960349378631910285921",
+        "1952057563719092278028425573632201081234877258097927010867141683896274170520489868686437644804596724295624637397077",
+        "254131389529427774765960554324483250584297364987873642087841623909520980093766889928789173976296059957431962608694",
+        "1385128161651935856764061834929068245137081648283968377947672499160305921464670953157912428887005620142387465559867",
+        "101302147352745188522496764263445345397483945567997375025250825330209385517139484882425580831299520200841767383756"])
+}
+
+fn prove_final_exp() -> Command {
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_prove_final_exp"));
+    cmd.env("RUST_MIN_STACK", "16777216");
+    cmd
+}
+
+#[test]
+fn test_prove_final_exp_from_file() {
+    let x = test_input();
+    let dir = std::env::temp_dir().join(format!("prove_final_exp_{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let input_path = dir.join("input.txt");
+    let proof_path = dir.join("proof.json");
+    std::fs::write(&input_path, x.to_hex().join("\n")).unwrap();
+
+    let status = prove_final_exp().arg("--out").arg(&proof_path).arg("--file").arg(&input_path).status().unwrap();
+    assert!(status.success());
+
+    let mut config = StarkConfig::standard_fast_config();
+    config.fri_config.rate_bits = 2;
+    let proof = deserialize_proof::<F, C, D>(&std::fs::read(&proof_path).unwrap()).unwrap();
+    assert_eq!(proof.public_inputs, S::public_inputs(&x, &x.final_exponentiate()));
+    verify_final_exp::<F, C, D>(proof.proof, &proof.public_inputs, &config).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn test_prove_final_exp_invalid_input() {
+    let mut words = test_input().to_hex();
+    // Too few words.
+    assert!(!prove_final_exp().args(&words[..11]).status().unwrap().success());
+    // Not hex.
+    words[3] = "0xg".to_string();
+    assert!(!prove_final_exp().args(&words).status().unwrap().success());
+    // Not canonical.
+    words[3] = format!("{:#x}", modulus());
+    assert!(!prove_final_exp().args(&words).status().unwrap().success());
+}